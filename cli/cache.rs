@@ -25,6 +25,9 @@ pub struct EmitMetadata {
 }
 
 pub enum CacheType {
+  /// The hash of the graph below a root module when it last type checked
+  /// without diagnostics.
+  CheckHash,
   Declaration,
   Emit,
   SourceMap,
@@ -183,6 +186,7 @@ impl Cacher for FetchCacher {
     specifier: &ModuleSpecifier,
   ) -> Option<String> {
     let extension = match cache_type {
+      CacheType::CheckHash => "checkhash",
       CacheType::Declaration => "d.ts",
       CacheType::Emit => "js",
       CacheType::SourceMap => "js.map",
@@ -208,6 +212,7 @@ impl Cacher for FetchCacher {
     value: String,
   ) -> Result<(), AnyError> {
     let extension = match cache_type {
+      CacheType::CheckHash => "checkhash",
       CacheType::Declaration => "d.ts",
       CacheType::Emit => "js",
       CacheType::SourceMap => "js.map",
@@ -259,6 +264,7 @@ pub struct MemoryCacher {
   maps: HashMap<ModuleSpecifier, String>,
   build_infos: HashMap<ModuleSpecifier, String>,
   versions: HashMap<ModuleSpecifier, String>,
+  check_hashes: HashMap<ModuleSpecifier, String>,
 }
 
 impl MemoryCacher {
@@ -270,6 +276,7 @@ impl MemoryCacher {
       maps: HashMap::default(),
       build_infos: HashMap::default(),
       versions: HashMap::default(),
+      check_hashes: HashMap::default(),
     }
   }
 }
@@ -307,6 +314,7 @@ impl Cacher for MemoryCacher {
     specifier: &ModuleSpecifier,
   ) -> Option<String> {
     match cache_type {
+      CacheType::CheckHash => self.check_hashes.get(specifier).cloned(),
       CacheType::Declaration => self.declarations.get(specifier).cloned(),
       CacheType::Emit => self.emits.get(specifier).cloned(),
       CacheType::SourceMap => self.maps.get(specifier).cloned(),
@@ -324,6 +332,9 @@ impl Cacher for MemoryCacher {
     value: String,
   ) -> Result<(), AnyError> {
     match cache_type {
+      CacheType::CheckHash => {
+        self.check_hashes.insert(specifier.clone(), value)
+      }
       CacheType::Declaration => {
         self.declarations.insert(specifier.clone(), value)
       }
@@ -1,6 +1,7 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
 use crate::fs_util::canonicalize_path;
+use crate::fs_util::normalize_path;
 use crate::fs_util::specifier_parent;
 use crate::fs_util::specifier_to_file_path;
 
@@ -16,9 +17,11 @@ use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::serde_json::Value;
 use deno_core::ModuleSpecifier;
+use deno_runtime::permissions::GLOB_PREFIX;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::ffi::OsString;
 use std::fmt;
 use std::path::Path;
 use std::path::PathBuf;
@@ -460,6 +463,7 @@ impl FilesConfig {
 struct SerializedLintConfig {
   pub rules: LintRulesConfig,
   pub files: SerializedFilesConfig,
+  pub plugins: Vec<String>,
 }
 
 impl SerializedLintConfig {
@@ -467,9 +471,15 @@ impl SerializedLintConfig {
     self,
     config_file_specifier: &ModuleSpecifier,
   ) -> Result<LintConfig, AnyError> {
+    let config_dir = specifier_parent(config_file_specifier);
     Ok(LintConfig {
       rules: self.rules,
       files: self.files.into_resolved(config_file_specifier)?,
+      plugins: self
+        .plugins
+        .into_iter()
+        .map(|p| config_dir.join(&p))
+        .collect::<Result<Vec<ModuleSpecifier>, _>>()?,
     })
   }
 }
@@ -478,6 +488,8 @@ impl SerializedLintConfig {
 pub struct LintConfig {
   pub rules: LintRulesConfig,
   pub files: FilesConfig,
+  /// Modules providing additional, user-defined lint rules.
+  pub plugins: Vec<ModuleSpecifier>,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -495,7 +507,42 @@ pub struct FmtOptionsConfig {
   pub line_width: Option<u32>,
   pub indent_width: Option<u8>,
   pub single_quote: Option<bool>,
+  pub semi_colons: Option<bool>,
   pub prose_wrap: Option<ProseWrap>,
+  pub css: Option<FmtLanguageOptionsConfig>,
+  pub yaml: Option<FmtLanguageOptionsConfig>,
+  pub sql: Option<FmtLanguageOptionsConfig>,
+}
+
+impl FmtOptionsConfig {
+  /// Resolves the options of a language formatted by its own plugin, where
+  /// the language specific options take precedence over the shared ones.
+  pub fn for_language(
+    &self,
+    overrides: Option<&FmtLanguageOptionsConfig>,
+  ) -> FmtLanguageOptionsConfig {
+    let overrides = overrides.cloned().unwrap_or_default();
+    FmtLanguageOptionsConfig {
+      use_tabs: overrides.use_tabs.or(self.use_tabs),
+      line_width: overrides.line_width.or(self.line_width),
+      indent_width: overrides.indent_width.or(self.indent_width),
+      single_quote: overrides.single_quote.or(self.single_quote),
+      uppercase_keywords: overrides.uppercase_keywords,
+    }
+  }
+}
+
+/// Formatting options for CSS, YAML or SQL files, configured under
+/// `fmt.options.css`, `fmt.options.yaml` and `fmt.options.sql`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct FmtLanguageOptionsConfig {
+  pub use_tabs: Option<bool>,
+  pub line_width: Option<u32>,
+  pub indent_width: Option<u8>,
+  pub single_quote: Option<bool>,
+  /// Only used for SQL.
+  pub uppercase_keywords: Option<bool>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -523,14 +570,280 @@ pub struct FmtConfig {
   pub files: FilesConfig,
 }
 
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SerializedTestConfig {
+  pub setup: Option<String>,
+  pub quarantine: Vec<String>,
+  pub docs: Vec<String>,
+}
+
+impl SerializedTestConfig {
+  pub fn into_resolved(
+    self,
+    config_file_specifier: &ModuleSpecifier,
+  ) -> Result<TestConfig, AnyError> {
+    let config_dir = specifier_parent(config_file_specifier);
+    Ok(TestConfig {
+      setup: self.setup.map(|p| config_dir.join(&p)).transpose()?,
+      quarantine: self.quarantine,
+      docs: self
+        .docs
+        .into_iter()
+        .map(|p| config_dir.join(&p))
+        .collect::<Result<Vec<ModuleSpecifier>, _>>()?,
+    })
+  }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TestConfig {
+  /// Module whose default export runs once before all test modules.
+  pub setup: Option<ModuleSpecifier>,
+  /// Names of known-flaky tests, verbatim or as `/regex/`, whose failures
+  /// don't fail the run.
+  pub quarantine: Vec<String>,
+  /// Documents whose code examples are always tested as documentation.
+  pub docs: Vec<ModuleSpecifier>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+struct SerializedWatchConfig {
+  pub exclude: Vec<String>,
+  pub debounce: Option<u64>,
+  pub poll_interval: Option<u64>,
+  pub clear_screen: Option<bool>,
+}
+
+impl SerializedWatchConfig {
+  pub fn into_resolved(
+    self,
+    config_file_specifier: &ModuleSpecifier,
+  ) -> Result<WatchConfig, AnyError> {
+    // globs can't be joined to the specifier as they'd be percent-encoded,
+    // so they're resolved against the directory of a local config file and
+    // normalized like the specifiers of `files.exclude`
+    let maybe_config_dir = specifier_to_file_path(config_file_specifier)
+      .ok()
+      .and_then(|path| path.parent().map(|dir| dir.to_path_buf()));
+    let exclude = self
+      .exclude
+      .into_iter()
+      .map(|glob| match &maybe_config_dir {
+        Some(config_dir) => normalize_path(config_dir.join(glob))
+          .to_string_lossy()
+          .to_string(),
+        None => glob,
+      })
+      .collect();
+    Ok(WatchConfig {
+      exclude,
+      debounce: self.debounce,
+      poll_interval: self.poll_interval,
+      clear_screen: self.clear_screen,
+    })
+  }
+}
+
+/// The `"watch"` configuration shared by the subcommands supporting
+/// `--watch`, which the flags take precedence over.
+#[derive(Clone, Debug, Default)]
+pub struct WatchConfig {
+  /// Absolute globs of the files whose changes are ignored.
+  pub exclude: Vec<String>,
+  /// Milliseconds to wait for further changes before restarting.
+  pub debounce: Option<u64>,
+  /// Milliseconds between polls of the watched files.
+  pub poll_interval: Option<u64>,
+  pub clear_screen: Option<bool>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum SerializedPermissionGrant<T> {
+  All(bool),
+  List(Vec<T>),
+}
+
+/// Converts a grant to the allowlist of the flags, where an empty list grants
+/// everything.
+fn resolve_grant<T, U>(
+  grant: Option<SerializedPermissionGrant<T>>,
+  resolve: impl FnMut(T) -> Result<U, AnyError>,
+) -> Result<Option<Vec<U>>, AnyError> {
+  Ok(match grant {
+    Some(SerializedPermissionGrant::All(true)) => Some(vec![]),
+    Some(SerializedPermissionGrant::List(list)) if !list.is_empty() => {
+      Some(list.into_iter().map(resolve).collect::<Result<_, _>>()?)
+    }
+    _ => None,
+  })
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum SerializedRunEntry {
+  Name(String),
+  WithArgs {
+    name: String,
+    args: Vec<Vec<String>>,
+  },
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SerializedPermissionsConfig {
+  pub read: Option<SerializedPermissionGrant<String>>,
+  pub write: Option<SerializedPermissionGrant<String>>,
+  pub net: Option<SerializedPermissionGrant<String>>,
+  pub env: Option<SerializedPermissionGrant<String>>,
+  pub run: Option<SerializedPermissionGrant<SerializedRunEntry>>,
+  pub ffi: Option<SerializedPermissionGrant<String>>,
+  pub hrtime: bool,
+}
+
+impl SerializedPermissionsConfig {
+  /// Resolves relative paths against the directory of the file.
+  pub fn into_resolved(
+    self,
+    config_dir: &Path,
+  ) -> Result<PermissionsConfig, AnyError> {
+    let resolve_path =
+      |path: String| -> Result<PathBuf, AnyError> { Ok(config_dir.join(path)) };
+    // a read or write entry that is only a glob pattern keeps its prefix
+    let resolve_path_or_glob = |path: String| -> Result<PathBuf, AnyError> {
+      Ok(match path.strip_prefix(GLOB_PREFIX) {
+        Some(pattern) => {
+          let mut path = OsString::from(GLOB_PREFIX);
+          path.push(config_dir.join(pattern));
+          PathBuf::from(path)
+        }
+        None => config_dir.join(path),
+      })
+    };
+    let resolve_cmd = |cmd: String| {
+      let is_path = cmd.contains('/') || (cfg!(windows) && cmd.contains('\\'));
+      if is_path {
+        config_dir.join(cmd).to_string_lossy().to_string()
+      } else {
+        cmd
+      }
+    };
+
+    let mut allow_run_args = HashMap::new();
+    let allow_run = resolve_grant(self.run, |entry| {
+      Ok(match entry {
+        SerializedRunEntry::Name(name) => resolve_cmd(name),
+        SerializedRunEntry::WithArgs { name, args } => {
+          let cmd = resolve_cmd(name);
+          allow_run_args.insert(cmd.clone(), args);
+          cmd
+        }
+      })
+    })?;
+    let allow_net = match resolve_grant(self.net, Ok)? {
+      Some(hosts) => Some(crate::flags_allow_net::parse(hosts)?),
+      None => None,
+    };
+    let allow_env = resolve_grant(self.env, |env| {
+      Ok(if cfg!(windows) {
+        env.to_uppercase()
+      } else {
+        env
+      })
+    })?;
+    Ok(PermissionsConfig {
+      allow_read: resolve_grant(self.read, resolve_path_or_glob)?,
+      allow_write: resolve_grant(self.write, resolve_path_or_glob)?,
+      allow_net,
+      allow_env,
+      allow_run,
+      allow_run_args,
+      allow_ffi: resolve_grant(self.ffi, resolve_path)?,
+      allow_hrtime: self.hrtime,
+    })
+  }
+}
+
+/// The permissions granted by the `"permissions"` configuration or a
+/// `--permission-set` file in addition to the ones of the flags. The
+/// allowlists follow the flags, where an empty list grants everything.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PermissionsConfig {
+  pub allow_read: Option<Vec<PathBuf>>,
+  pub allow_write: Option<Vec<PathBuf>>,
+  pub allow_net: Option<Vec<String>>,
+  pub allow_env: Option<Vec<String>>,
+  pub allow_run: Option<Vec<String>>,
+  /// The argument prefixes the binaries may be spawned with.
+  pub allow_run_args: HashMap<String, Vec<Vec<String>>>,
+  pub allow_ffi: Option<Vec<PathBuf>>,
+  pub allow_hrtime: bool,
+}
+
+/// Reads a permission set file, as passed to `--permission-set`.
+pub fn read_permission_set(path: &str) -> Result<PermissionsConfig, AnyError> {
+  let path = std::env::current_dir()?.join(path);
+  let text = std::fs::read_to_string(&path).with_context(|| {
+    format!("Error reading permission set {}", path.display())
+  })?;
+  let value = jsonc_parser::parse_to_serde_value(&text)
+    .map_err(|err| anyhow!("Unable to parse {}: {}", path.display(), err))?
+    .unwrap_or_else(|| json!({}));
+  let permissions_config: SerializedPermissionsConfig =
+    serde_json::from_value(value).with_context(|| {
+      format!("Failed to parse permission set {}", path.display())
+    })?;
+  permissions_config.into_resolved(path.parent().unwrap())
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum SerializedTaskDefinition {
+  Script(String),
+  #[serde(rename_all = "camelCase")]
+  Full {
+    cmd: String,
+    #[serde(default)]
+    depends_on: Vec<String>,
+  },
+}
+
+impl From<SerializedTaskDefinition> for TaskDefinition {
+  fn from(value: SerializedTaskDefinition) -> Self {
+    match value {
+      SerializedTaskDefinition::Script(cmd) => TaskDefinition {
+        cmd,
+        depends_on: Vec::new(),
+      },
+      SerializedTaskDefinition::Full { cmd, depends_on } => {
+        TaskDefinition { cmd, depends_on }
+      }
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TaskDefinition {
+  /// The script that is executed by the task shell.
+  pub cmd: String,
+  /// Names of the tasks that have to complete before this one runs.
+  pub depends_on: Vec<String>,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConfigFileJson {
   pub compiler_options: Option<Value>,
   pub import_map: Option<String>,
+  pub files: Option<Value>,
   pub lint: Option<Value>,
   pub fmt: Option<Value>,
+  pub test: Option<Value>,
   pub tasks: Option<Value>,
+  pub watch: Option<Value>,
+  pub permissions: Option<Value>,
 }
 
 #[derive(Clone, Debug)]
@@ -654,14 +967,15 @@ impl ConfigFile {
   /// task in a detail field.
   pub fn to_lsp_tasks(&self) -> Option<Value> {
     let value = self.json.tasks.clone()?;
-    let tasks: BTreeMap<String, String> = serde_json::from_value(value).ok()?;
+    let tasks: BTreeMap<String, SerializedTaskDefinition> =
+      serde_json::from_value(value).ok()?;
     Some(
       tasks
         .into_iter()
         .map(|(key, value)| {
           json!({
             "name": key,
-            "detail": value,
+            "detail": TaskDefinition::from(value).cmd,
           })
         })
         .collect(),
@@ -670,12 +984,17 @@ impl ConfigFile {
 
   pub fn to_tasks_config(
     &self,
-  ) -> Result<Option<BTreeMap<String, String>>, AnyError> {
+  ) -> Result<Option<BTreeMap<String, TaskDefinition>>, AnyError> {
     if let Some(config) = self.json.tasks.clone() {
-      let tasks_config: BTreeMap<String, String> =
+      let tasks_config: BTreeMap<String, SerializedTaskDefinition> =
         serde_json::from_value(config)
           .context("Failed to parse \"tasks\" configuration")?;
-      Ok(Some(tasks_config))
+      Ok(Some(
+        tasks_config
+          .into_iter()
+          .map(|(name, task)| (name, task.into()))
+          .collect(),
+      ))
     } else {
       Ok(None)
     }
@@ -737,6 +1056,54 @@ impl ConfigFile {
       Ok(None)
     }
   }
+
+  /// Return the project wide `"files"` configuration, which `deno check`
+  /// uses to discover the modules of the project.
+  pub fn to_files_config(&self) -> Result<Option<FilesConfig>, AnyError> {
+    if let Some(config) = self.json.files.clone() {
+      let files_config: SerializedFilesConfig = serde_json::from_value(config)
+        .context("Failed to parse \"files\" configuration")?;
+      Ok(Some(files_config.into_resolved(&self.specifier)?))
+    } else {
+      Ok(None)
+    }
+  }
+
+  pub fn to_test_config(&self) -> Result<Option<TestConfig>, AnyError> {
+    if let Some(config) = self.json.test.clone() {
+      let test_config: SerializedTestConfig = serde_json::from_value(config)
+        .context("Failed to parse \"test\" configuration")?;
+      Ok(Some(test_config.into_resolved(&self.specifier)?))
+    } else {
+      Ok(None)
+    }
+  }
+
+  pub fn to_permissions_config(
+    &self,
+  ) -> Result<Option<PermissionsConfig>, AnyError> {
+    if let Some(config) = self.json.permissions.clone() {
+      let permissions_config: SerializedPermissionsConfig =
+        serde_json::from_value(config)
+          .context("Failed to parse \"permissions\" configuration")?;
+      let config_path = specifier_to_file_path(&self.specifier)?;
+      Ok(Some(
+        permissions_config.into_resolved(config_path.parent().unwrap())?,
+      ))
+    } else {
+      Ok(None)
+    }
+  }
+
+  pub fn to_watch_config(&self) -> Result<Option<WatchConfig>, AnyError> {
+    if let Some(config) = self.json.watch.clone() {
+      let watch_config: SerializedWatchConfig = serde_json::from_value(config)
+        .context("Failed to parse \"watch\" configuration")?;
+      Ok(Some(watch_config.into_resolved(&self.specifier)?))
+    } else {
+      Ok(None)
+    }
+  }
 }
 
 #[cfg(test)]
@@ -803,7 +1170,8 @@ mod tests {
         "rules": {
           "tags": ["recommended"],
           "include": ["ban-untagged-todo"]
-        }
+        },
+        "plugins": ["./lint/plugin.ts"]
       },
       "fmt": {
         "files": {
@@ -815,12 +1183,30 @@ mod tests {
           "lineWidth": 80,
           "indentWidth": 4,
           "singleQuote": true,
-          "proseWrap": "preserve"
+          "semiColons": false,
+          "proseWrap": "preserve",
+          "sql": {
+            "indentWidth": 2,
+            "uppercaseKeywords": true
+          }
         }
       },
+      "files": {
+        "include": ["src/", "mod.ts"],
+        "exclude": ["src/testdata/"]
+      },
+      "test": {
+        "setup": "./tests/setup.ts",
+        "quarantine": ["flaky test", "/^network: /"],
+        "docs": ["README.md"]
+      },
       "tasks": {
         "build": "deno run --allow-read --allow-write build.ts",
-        "server": "deno run --allow-net --allow-read server.ts"
+        "server": "deno run --allow-net --allow-read server.ts",
+        "release": {
+          "cmd": "deno compile main.ts",
+          "dependsOn": ["build"]
+        }
       }
     }"#;
     let config_dir = ModuleSpecifier::parse("file:///deno/").unwrap();
@@ -861,6 +1247,10 @@ mod tests {
       Some(vec!["recommended".to_string()])
     );
     assert!(lint_config.rules.exclude.is_none());
+    assert_eq!(
+      lint_config.plugins,
+      vec![config_dir.join("lint/plugin.ts").unwrap()]
+    );
 
     let fmt_config = config_file
       .to_fmt_config()
@@ -878,16 +1268,66 @@ mod tests {
     assert_eq!(fmt_config.options.line_width, Some(80));
     assert_eq!(fmt_config.options.indent_width, Some(4));
     assert_eq!(fmt_config.options.single_quote, Some(true));
+    assert_eq!(fmt_config.options.semi_colons, Some(false));
+    let sql_options = fmt_config
+      .options
+      .for_language(fmt_config.options.sql.as_ref());
+    assert_eq!(sql_options.use_tabs, Some(true));
+    assert_eq!(sql_options.indent_width, Some(2));
+    assert_eq!(sql_options.uppercase_keywords, Some(true));
+    let css_options = fmt_config
+      .options
+      .for_language(fmt_config.options.css.as_ref());
+    assert_eq!(css_options.indent_width, Some(4));
+    assert_eq!(css_options.uppercase_keywords, None);
+
+    let test_config = config_file
+      .to_test_config()
+      .expect("error parsing test object")
+      .expect("test object should be defined");
+    assert_eq!(
+      test_config.setup,
+      Some(config_dir.join("tests/setup.ts").unwrap())
+    );
+    assert_eq!(
+      test_config.quarantine,
+      vec!["flaky test".to_string(), "/^network: /".to_string()]
+    );
+    assert_eq!(
+      test_config.docs,
+      vec![config_dir.join("README.md").unwrap()]
+    );
+
+    let files_config = config_file.to_files_config().unwrap().unwrap();
+    assert_eq!(
+      files_config.include,
+      vec![
+        config_dir.join("src/").unwrap(),
+        config_dir.join("mod.ts").unwrap()
+      ]
+    );
+    assert_eq!(
+      files_config.exclude,
+      vec![config_dir.join("src/testdata/").unwrap()]
+    );
 
     let tasks_config = config_file.to_tasks_config().unwrap().unwrap();
     assert_eq!(
-      tasks_config["build"],
+      tasks_config["build"].cmd,
       "deno run --allow-read --allow-write build.ts",
     );
+    assert!(tasks_config["build"].depends_on.is_empty());
     assert_eq!(
-      tasks_config["server"],
+      tasks_config["server"].cmd,
       "deno run --allow-net --allow-read server.ts"
     );
+    assert_eq!(
+      tasks_config["release"],
+      TaskDefinition {
+        cmd: "deno compile main.ts".to_string(),
+        depends_on: vec!["build".to_string()],
+      }
+    );
   }
 
   #[test]
@@ -1085,6 +1525,94 @@ mod tests {
     assert_eq!(actual, None);
   }
 
+  #[cfg(not(windows))]
+  #[test]
+  fn test_parse_watch_config() {
+    let config_text = r#"{
+      "watch": {
+        "exclude": ["./dist/", "**/*.gen.ts", "../shared/./*.ts"],
+        "debounce": 500,
+        "pollInterval": 1000,
+        "clearScreen": false
+      }
+    }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let watch_config = config_file.to_watch_config().unwrap().unwrap();
+    assert_eq!(
+      watch_config.exclude,
+      vec!["/deno/dist", "/deno/**/*.gen.ts", "/shared/*.ts"]
+    );
+    assert_eq!(watch_config.debounce, Some(500));
+    assert_eq!(watch_config.poll_interval, Some(1000));
+    assert_eq!(watch_config.clear_screen, Some(false));
+
+    let config_text = r#"{ "watch": { "delay": 500 } }"#;
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    assert!(config_file.to_watch_config().is_err());
+  }
+
+  #[test]
+  fn test_parse_config_permissions() {
+    let config_text = r#"{
+      "permissions": {
+        "read": ["./data", "/etc/hosts"],
+        "write": false,
+        "net": true,
+        "run": ["deno", { "name": "git", "args": [["status"], ["log"]] }],
+        "hrtime": true
+      }
+    }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let permissions_config =
+      config_file.to_permissions_config().unwrap().unwrap();
+    assert_eq!(
+      permissions_config,
+      PermissionsConfig {
+        allow_read: Some(vec![
+          PathBuf::from("/deno/data"),
+          PathBuf::from("/etc/hosts")
+        ]),
+        allow_net: Some(vec![]),
+        allow_run: Some(vec!["deno".to_string(), "git".to_string()]),
+        allow_run_args: HashMap::from([(
+          "git".to_string(),
+          vec![vec!["status".to_string()], vec!["log".to_string()]]
+        )]),
+        allow_hrtime: true,
+        ..Default::default()
+      }
+    );
+
+    let config_text = r#"{
+      "permissions": {
+        "read": ["glob:./data/*.csv", "./logs/*.log"],
+        "write": ["glob:/tmp/out-?.txt"]
+      }
+    }"#;
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let permissions_config =
+      config_file.to_permissions_config().unwrap().unwrap();
+    assert_eq!(
+      permissions_config.allow_read,
+      Some(vec![
+        PathBuf::from("glob:/deno/data/*.csv"),
+        PathBuf::from("/deno/logs/*.log")
+      ])
+    );
+    assert_eq!(
+      permissions_config.allow_write,
+      Some(vec![PathBuf::from("glob:/tmp/out-?.txt")])
+    );
+
+    let config_text = r#"{ "permissions": { "sys": true } }"#;
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    assert!(config_file.to_permissions_config().is_err());
+  }
+
   #[test]
   fn resolve_import_map_no_config() {
     let actual = resolve_import_map_specifier(None, None);
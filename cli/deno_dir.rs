@@ -1,6 +1,8 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
+use crate::checksum;
 use crate::disk_cache::DiskCache;
+use std::path::Path;
 use std::path::PathBuf;
 
 /// `DenoDir` serves as coordinator for multiple `DiskCache`s containing them
@@ -45,16 +47,23 @@ impl DenoDir {
     Ok(deno_dir)
   }
 
-  /// Path for the incremental cache used for formatting.
-  pub fn fmt_incremental_cache_db_file_path(&self) -> PathBuf {
+  /// Path for the incremental cache shared by formatting and linting.
+  pub fn incremental_cache_db_file_path(&self) -> PathBuf {
     // bump this version name to invalidate the entire cache
-    self.root.join("fmt_incremental_cache_v1")
+    self.root.join("incremental_cache_v2")
   }
 
-  /// Path for the incremental cache used for linting.
-  pub fn lint_incremental_cache_db_file_path(&self) -> PathBuf {
+  /// Directory holding serialized module maps of `deno run` invocations.
+  pub fn module_map_cache_dir(&self) -> PathBuf {
     // bump this version name to invalidate the entire cache
-    self.root.join("lint_incremental_cache_v1")
+    self.root.join("module_map_v1")
+  }
+
+  /// Path of the file holding the permissions remembered for a project,
+  /// keyed by the canonical path of the project directory.
+  pub fn project_permissions_file_path(&self, project_dir: &Path) -> PathBuf {
+    let key = checksum::gen(&[project_dir.to_string_lossy().as_bytes()]);
+    self.root.join("permissions").join(format!("{}.json", key))
   }
 }
 
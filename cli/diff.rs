@@ -2,6 +2,8 @@
 
 use crate::colors;
 use dissimilar::{diff as difference, Chunk};
+use serde::Serialize;
+use std::collections::HashMap;
 
 /// Print diff of the same file_path, before and after formatting.
 ///
@@ -139,6 +141,203 @@ impl DiffBuilder {
   }
 }
 
+/// A hunk of a line based diff, as found in unified diffs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffHunk {
+  /// 1-based line of the original text the hunk starts at.
+  pub old_start: usize,
+  pub old_lines: usize,
+  /// 1-based line of the edited text the hunk starts at.
+  pub new_start: usize,
+  pub new_lines: usize,
+  /// The lines of the hunk prefixed with ` `, `-` or `+`, without their line
+  /// endings.
+  pub lines: Vec<String>,
+}
+
+impl DiffHunk {
+  fn header(&self) -> String {
+    // hunks that are empty on one side refer to the line before them
+    let start = |start: usize, lines: usize| {
+      if lines == 0 {
+        start - 1
+      } else {
+        start
+      }
+    };
+    format!(
+      "@@ -{},{} +{},{} @@",
+      start(self.old_start, self.old_lines),
+      self.old_lines,
+      start(self.new_start, self.new_lines),
+      self.new_lines
+    )
+  }
+}
+
+enum LineChange<'a> {
+  Equal(&'a str),
+  Delete(&'a str),
+  Insert(&'a str),
+}
+
+/// Diffs the lines of both texts, including their line endings.
+fn line_changes<'a>(
+  orig_text: &'a str,
+  edit_text: &'a str,
+) -> Vec<LineChange<'a>> {
+  let orig_lines = orig_text.split_inclusive('\n').collect::<Vec<_>>();
+  let edit_lines = edit_text.split_inclusive('\n').collect::<Vec<_>>();
+
+  // Diff the lines by diffing strings with one character per distinct line.
+  let mut line_chars = HashMap::new();
+  let mut encode = |lines: &[&'a str]| {
+    lines
+      .iter()
+      .map(|line| {
+        let next = line_chars.len() as u32;
+        *line_chars.entry(*line).or_insert_with(|| {
+          char::from_u32(0x10000 + next).unwrap_or(char::REPLACEMENT_CHARACTER)
+        })
+      })
+      .collect::<String>()
+  };
+  let orig_chars = encode(&orig_lines);
+  let edit_chars = encode(&edit_lines);
+
+  let mut orig_lines = orig_lines.into_iter();
+  let mut edit_lines = edit_lines.into_iter();
+  let mut changes = Vec::new();
+  for chunk in difference(&orig_chars, &edit_chars) {
+    match chunk {
+      Chunk::Equal(s) => {
+        for _ in s.chars() {
+          edit_lines.next();
+          changes.push(LineChange::Equal(orig_lines.next().unwrap()));
+        }
+      }
+      Chunk::Delete(s) => {
+        for _ in s.chars() {
+          changes.push(LineChange::Delete(orig_lines.next().unwrap()));
+        }
+      }
+      Chunk::Insert(s) => {
+        for _ in s.chars() {
+          changes.push(LineChange::Insert(edit_lines.next().unwrap()));
+        }
+      }
+    }
+  }
+  changes
+}
+
+/// Computes the hunks of a line based diff of both texts, with the given
+/// number of unchanged lines of context around the changed lines.
+pub fn diff_hunks(
+  orig_text: &str,
+  edit_text: &str,
+  context: usize,
+) -> Vec<DiffHunk> {
+  let changes = line_changes(orig_text, edit_text);
+  // the 1-based lines of both texts at which each change starts
+  let mut positions = Vec::with_capacity(changes.len());
+  let (mut old_line, mut new_line) = (1, 1);
+  for change in &changes {
+    positions.push((old_line, new_line));
+    match change {
+      LineChange::Equal(_) => {
+        old_line += 1;
+        new_line += 1;
+      }
+      LineChange::Delete(_) => old_line += 1,
+      LineChange::Insert(_) => new_line += 1,
+    }
+  }
+  let changed_indexes = changes
+    .iter()
+    .enumerate()
+    .filter(|(_, change)| !matches!(change, LineChange::Equal(_)))
+    .map(|(i, _)| i)
+    .collect::<Vec<_>>();
+
+  let mut hunks = Vec::new();
+  let mut i = 0;
+  while i < changed_indexes.len() {
+    let start = changed_indexes[i].saturating_sub(context);
+    let mut end = changed_indexes[i] + 1;
+    // merge changes separated by no more than the context of both
+    while i + 1 < changed_indexes.len()
+      && changed_indexes[i + 1] <= end + 2 * context
+    {
+      i += 1;
+      end = changed_indexes[i] + 1;
+    }
+    let end = (end + context).min(changes.len());
+    i += 1;
+
+    let (old_start, new_start) = positions[start];
+    let mut hunk = DiffHunk {
+      old_start,
+      old_lines: 0,
+      new_start,
+      new_lines: 0,
+      lines: Vec::new(),
+    };
+    for change in &changes[start..end] {
+      let (prefix, line) = match change {
+        LineChange::Equal(line) => {
+          hunk.old_lines += 1;
+          hunk.new_lines += 1;
+          (' ', line)
+        }
+        LineChange::Delete(line) => {
+          hunk.old_lines += 1;
+          ('-', line)
+        }
+        LineChange::Insert(line) => {
+          hunk.new_lines += 1;
+          ('+', line)
+        }
+      };
+      match line.strip_suffix('\n') {
+        Some(line) => hunk.lines.push(format!("{}{}", prefix, line)),
+        None => {
+          hunk.lines.push(format!("{}{}", prefix, line));
+          hunk.lines.push("\\ No newline at end of file".to_string());
+        }
+      }
+    }
+    hunks.push(hunk);
+  }
+  hunks
+}
+
+/// Creates a uncolored diff in the unified format understood by `patch` and
+/// `git apply`, or an empty string when both texts are the same.
+pub fn unified_diff(
+  orig_label: &str,
+  edit_label: &str,
+  orig_text: &str,
+  edit_text: &str,
+) -> String {
+  let hunks = diff_hunks(orig_text, edit_text, 3);
+  if hunks.is_empty() {
+    return String::new();
+  }
+
+  let mut output = format!("--- {}\n+++ {}\n", orig_label, edit_label);
+  for hunk in hunks {
+    output.push_str(&hunk.header());
+    output.push('\n');
+    for line in hunk.lines {
+      output.push_str(&line);
+      output.push('\n');
+    }
+  }
+  output
+}
+
 fn fmt_add() -> String {
   colors::green_bold("+").to_string()
 }
@@ -213,6 +412,73 @@ mod tests {
     run_test("test\n", "test\r\n", " | Text differed by line endings.\n");
   }
 
+  #[test]
+  fn test_unified_diff() {
+    let orig = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+    let edit = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+    assert_eq!(
+      unified_diff("a/mod.ts", "b/mod.ts", orig, edit),
+      concat!(
+        "--- a/mod.ts\n",
+        "+++ b/mod.ts\n",
+        "@@ -1,5 +1,5 @@\n",
+        " a\n",
+        "-b\n",
+        "+B\n",
+        " c\n",
+        " d\n",
+        " e\n",
+        "@@ -8,3 +8,4 @@\n",
+        " h\n",
+        " i\n",
+        " j\n",
+        "+k\n",
+      ),
+    );
+    assert_eq!(unified_diff("a/mod.ts", "b/mod.ts", orig, orig), "");
+  }
+
+  #[test]
+  fn test_unified_diff_eof_newline_missing() {
+    assert_eq!(
+      unified_diff("a/mod.ts", "b/mod.ts", "test\nline", "test\nline\n"),
+      concat!(
+        "--- a/mod.ts\n",
+        "+++ b/mod.ts\n",
+        "@@ -1,2 +1,2 @@\n",
+        " test\n",
+        "-line\n",
+        "\\ No newline at end of file\n",
+        "+line\n",
+      ),
+    );
+  }
+
+  #[test]
+  fn test_diff_hunks_insertion_into_empty_text() {
+    assert_eq!(
+      diff_hunks("", "a\n", 3),
+      vec![DiffHunk {
+        old_start: 1,
+        old_lines: 0,
+        new_start: 1,
+        new_lines: 1,
+        lines: vec!["+a".to_string()],
+      }],
+    );
+    assert_eq!(
+      DiffHunk {
+        old_start: 1,
+        old_lines: 0,
+        new_start: 1,
+        new_lines: 1,
+        lines: vec![],
+      }
+      .header(),
+      "@@ -0,0 +1,1 @@"
+    );
+  }
+
   fn run_test(diff_text1: &str, diff_text2: &str, expected_output: &str) {
     assert_eq!(
       test_util::strip_ansi_codes(&diff(diff_text1, diff_text2,)),
@@ -263,6 +263,18 @@ declare namespace Deno {
      */
     parent?: TestContext;
 
+    /** Assert that `actual` matches the snapshot stored for it in the
+     * `__snapshots__` directory next to the test module. Run `deno test
+     * --update-snapshots` to create or update snapshots.
+     *
+     * ```ts
+     * Deno.test("snapshot", (t) => {
+     *   t.assertSnapshot({ hello: "world" });
+     * });
+     * ```
+     */
+    assertSnapshot(actual: unknown): void;
+
     /** Run a sub step of the parent test or step. Returns a promise
      * that resolves to a boolean signifying if the step completed successfully.
      * The returned promise never rejects unless the arguments are invalid.
@@ -321,6 +333,20 @@ declare namespace Deno {
     /** Ensure the test case does not prematurely cause the process to exit,
      * for example via a call to `Deno.exit`. Defaults to true. */
     sanitizeExit?: boolean;
+    /** Fail the test if it hasn't finished after this many milliseconds. The
+     * test function is not cancelled and keeps running in the background;
+     * the sanitizers still run when a test times out, and report what it
+     * left pending. Defaults to the value of `deno test --timeout`, or no
+     * timeout. */
+    timeout?: number;
+    /** Re-run the test up to this many times if it fails. Only the result
+     * and steps of the last attempt are reported, along with the number of
+     * retries; a test that only passes after being retried is reported as
+     * flaky. Defaults to the value of `deno test --retries`, or 0. */
+    retries?: number;
+    /** Labels used to select tests with `deno test --filter-tag` and
+     * `deno test --skip-tag`. */
+    tags?: string[];
 
     /** Specifies the permissions that should be used to run the test.
      * Set this to "inherit" to keep the calling thread's permissions.
@@ -2340,6 +2366,7 @@ declare namespace Deno {
   export type Signal =
     | "SIGABRT"
     | "SIGALRM"
+    | "SIGBREAK"
     | "SIGBUS"
     | "SIGCHLD"
     | "SIGCONT"
@@ -2380,7 +2407,8 @@ declare namespace Deno {
    * });
    * ```
    *
-   * NOTE: This functionality is not yet implemented on Windows.
+   * NOTE: On Windows only "SIGINT" (ctrl-c) and "SIGBREAK" (ctrl-break) are
+   * supported.
    */
   export function addSignalListener(signal: Signal, handler: () => void): void;
 
@@ -2395,7 +2423,8 @@ declare namespace Deno {
    * Deno.removeSignalListener("SIGTERM", listener);
    * ```
    *
-   * NOTE: This functionality is not yet implemented on Windows.
+   * NOTE: On Windows only "SIGINT" (ctrl-c) and "SIGBREAK" (ctrl-break) are
+   * supported.
    */
   export function removeSignalListener(
     signal: Signal,
@@ -2561,16 +2590,23 @@ declare namespace Deno {
 
   export interface NetPermissionDescriptor {
     name: "net";
-    /** Optional host string of the form `"<hostname>[:<port>]"`. Examples:
+    /** Optional host string of the form `"<hostname>[:<port>]"`. The
+     * hostname may also be a wildcard covering all subdomains of a domain, or
+     * an IP network in CIDR notation. Examples:
      *
      *      "github.com"
      *      "deno.land:8080"
+     *      "*.example.com"
+     *      "10.0.0.0/8"
      */
     host?: string;
   }
 
   export interface EnvPermissionDescriptor {
     name: "env";
+    /** Optional name of an environment variable. A trailing `*` covers all
+     * the variables with that prefix, e.g. `"AWS_*"`, which is granted when
+     * `--allow-env` grants a prefix covering it. */
     variable?: string;
   }
 
@@ -2641,6 +2677,9 @@ declare namespace Deno {
     query(desc: PermissionDescriptor): Promise<PermissionStatus>;
 
     /** Revokes a permission, and resolves to the state of the permission.
+     * The permission is revoked in the workers created by this worker, and
+     * the workers they created in turn, as well, before they next check or
+     * query a permission.
      *
      * ```ts
      * import { assert } from "https://deno.land/std/testing/asserts.ts";
@@ -2652,6 +2691,11 @@ declare namespace Deno {
     revoke(desc: PermissionDescriptor): Promise<PermissionStatus>;
 
     /** Requests the permission, and resolves to the state of the permission.
+     * In a worker, requesting a permission that its parent or another
+     * ancestor was denied throws a `Deno.errors.PermissionDenied` error. The
+     * user is prompted for a permission they would prompt for, and the
+     * permissions they are granted after the worker was created can be
+     * requested as well.
      *
      * ```ts
      * const status = await Deno.permissions.request({ name: "env" });
@@ -2934,7 +2978,11 @@ declare namespace Deno {
   /** Send a signal to process under given `pid`.
    *
    * If `pid` is negative, the signal will be sent to the process group
-   * identified by `pid`.
+   * identified by `pid`, such as a subprocess spawned with the unstable
+   * `newProcessGroup` option. On Windows, only `"SIGKILL"` and `"SIGTERM"`
+   * are supported for a process, while a group can only be sent `"SIGBREAK"`,
+   * as a ctrl-break event. That event only reaches the processes of the group
+   * attached to the console of this process, so not detached ones.
    *
    * ```ts
    * const p = Deno.run({
@@ -208,6 +208,38 @@ declare namespace Deno {
     rows: number;
   };
 
+  /** **UNSTABLE**: New API, yet to be vetted.
+   *
+   * Watches the size of the console, which defaults to the one of stdout.
+   * The returned async iterator yields the new size whenever the console is
+   * resized, independently of the program's `SIGWINCH` listeners, until it
+   * is closed.
+   *
+   * Reading from `Deno.stdin` while it's a terminal doesn't block a thread
+   * until input arrives, so a full-screen app can wait for key presses in
+   * raw mode and for resizes at the same time.
+   *
+   * ```ts
+   * const watcher = Deno.watchConsoleSize();
+   * for await (const { columns, rows } of watcher) {
+   *   console.log(`resized to ${columns}x${rows}`);
+   * }
+   * ```
+   */
+  export function watchConsoleSize(rid?: number): ConsoleSizeWatcher;
+
+  /** **UNSTABLE**: New API, yet to be vetted. */
+  export interface ConsoleSizeWatcher
+    extends AsyncIterable<{ columns: number; rows: number }> {
+    /** The resource id of the watcher. */
+    readonly rid: number;
+    /** Stops watching the console size. */
+    close(): void;
+    [Symbol.asyncIterator](): AsyncIterableIterator<
+      { columns: number; rows: number }
+    >;
+  }
+
   /** **Unstable**  There are questions around which permission this needs. And
    * maybe should be renamed (loadAverage?)
    *
@@ -1385,6 +1417,23 @@ declare namespace Deno {
     uid?: number;
     /** Similar to `uid`, but sets the group ID of the child process. */
     gid?: number;
+    /**
+     * Runs the process independently of this one: it isn't killed when the
+     * `Child` is garbage collected or when this process exits, and it runs in
+     * a new session on Unix, without a controlling terminal, and without a
+     * console on Windows. Combine it with `child.unref()` so that this
+     * process can exit while the child still runs, and with `"null"` stdio
+     * so that the child doesn't depend on this process' streams.
+     * Also implies `newProcessGroup`.
+     */
+    detached?: boolean;
+    /**
+     * Runs the process in a new process group, whose id is the pid of the
+     * process, so that the whole group can be signalled with
+     * `Deno.kill(-child.pid, signo)`, which is limited to `"SIGBREAK"` on
+     * Windows.
+     */
+    newProcessGroup?: boolean;
 
     /** Defaults to "null". */
     stdin?: "piped" | "inherit" | "null";
@@ -1392,6 +1441,19 @@ declare namespace Deno {
     stdout?: "piped" | "inherit" | "null";
     /** Defaults to "piped". */
     stderr?: "piped" | "inherit" | "null";
+
+    /**
+     * Runs the process in a new pseudo-terminal, so that it behaves as if
+     * run interactively, for example by printing colors and prompts. The
+     * terminal has the given size, which defaults to 80 columns and 24 rows.
+     *
+     * The output of the process is then read from `stdout` and its input is
+     * written to `stdin`, `stderr` being `null` as it is part of the output.
+     * The `stdin`, `stdout` and `stderr` options are ignored.
+     *
+     * Only supported by `Deno.spawnChild()`, and not supported on Windows.
+     */
+    pty?: boolean | { columns?: number; rows?: number };
   }
 
   /**
@@ -1422,11 +1484,14 @@ declare namespace Deno {
   ): Child<T>;
 
   export class Child<T extends SpawnOptions> {
-    readonly stdin: T["stdin"] extends "piped" ? WritableStream<Uint8Array>
+    readonly stdin: T["pty"] extends true | object ? WritableStream<Uint8Array>
+      : T["stdin"] extends "piped" ? WritableStream<Uint8Array>
       : null;
-    readonly stdout: T["stdout"] extends "inherit" | "null" ? null
+    readonly stdout: T["pty"] extends true | object ? ReadableStream<Uint8Array>
+      : T["stdout"] extends "inherit" | "null" ? null
       : ReadableStream<Uint8Array>;
-    readonly stderr: T["stderr"] extends "inherit" | "null" ? null
+    readonly stderr: T["pty"] extends true | object ? null
+      : T["stderr"] extends "inherit" | "null" ? null
       : ReadableStream<Uint8Array>;
 
     readonly pid: number;
@@ -1437,6 +1502,22 @@ declare namespace Deno {
     output(): Promise<SpawnOutput<T>>;
     /** Kills the process with given Signal. */
     kill(signo: Signal): void;
+    /**
+     * Makes the child keep the event loop alive until it exits, which is
+     * the default.
+     */
+    ref(): void;
+    /**
+     * Makes the event loop not wait for the child to exit, so that this
+     * process can exit while the child is still running. Its piped streams
+     * still keep the event loop alive while they are read.
+     */
+    unref(): void;
+    /**
+     * Resizes the pseudo-terminal of a child spawned with the `pty` option,
+     * which notifies the process with a `SIGWINCH` signal.
+     */
+    resizePty(size: { columns: number; rows: number }): void;
   }
 
   /**
@@ -1518,8 +1599,21 @@ declare interface WorkerOptions {
    * of a worker can't be extended beyond its parent's permissions reach.
    * - "inherit" will take the permissions of the thread the worker is created in
    * - "none" will use the default behavior and have no permission
-   * - You can provide a list of routes relative to the file the worker
-   *   is created in to limit the access of the worker (read/write permissions only)
+   * - An object narrows each permission separately, either to "inherit",
+   *   `true`, `false` or a list of descriptors, e.g. hosts for `net` or
+   *   paths for `read` and `write`. Every descriptor has to be covered by
+   *   the parent's permissions, e.g. `"api.example.com:443"` by
+   *   `"*.example.com"` or `"./data/public"` by `"./data"`, otherwise the
+   *   constructor throws a `Deno.errors.PermissionDenied` error naming it.
+   *
+   * Set deno.limits to bound the resources the worker may use, so that
+   * untrusted code can't take down the host process. A worker exceeding a
+   * limit is terminated and an `error` event is dispatched on it, which can
+   * be handled with `event.preventDefault()`.
+   * - `memoryMb` is the maximum size of the worker's heap in megabytes
+   * - `cpuMs` is the maximum CPU time in milliseconds the worker's thread
+   *   may use, which doesn't include the time it waits for timers, I/O or
+   *   messages
    *
    * Example:
    *
@@ -1531,7 +1625,8 @@ declare interface WorkerOptions {
    *     deno: {
    *       namespace: true,
    *       permissions: {
-   *         read: true,
+   *         read: ["./data"],
+   *         net: ["api.example.com:443"],
    *       },
    *     },
    *   }
@@ -1545,6 +1640,10 @@ declare interface WorkerOptions {
     namespace?: boolean;
     /** Set to `"none"` to disable all the permissions in the worker. */
     permissions?: Deno.PermissionOptions;
+    limits?: {
+      memoryMb?: number;
+      cpuMs?: number;
+    };
   };
 }
 
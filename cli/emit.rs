@@ -323,6 +323,36 @@ fn get_version(source_bytes: &[u8], config_bytes: &[u8]) -> String {
   ])
 }
 
+/// Hashes the modules of a graph segment, along with the config and check mode
+/// they are checked with, to determine if a previous successful type check of
+/// the segment is still valid.
+fn get_graph_hash(
+  graph_data: &GraphData,
+  config_bytes: &[u8],
+  type_check_mode: &flags::TypeCheckMode,
+) -> String {
+  let mut modules: Vec<_> = graph_data
+    .entries()
+    .into_iter()
+    .filter_map(|(specifier, module_entry)| match module_entry {
+      ModuleEntry::Module { code, .. } => Some((specifier, code)),
+      _ => None,
+    })
+    .collect();
+  modules.sort_by(|(a, _), (b, _)| a.cmp(b));
+  let type_check_mode = format!("{:?}", type_check_mode);
+  let mut data: Vec<&[u8]> = vec![
+    version::deno().as_bytes(),
+    config_bytes,
+    type_check_mode.as_bytes(),
+  ];
+  for (specifier, code) in &modules {
+    data.push(specifier.as_str().as_bytes());
+    data.push(code.as_bytes());
+  }
+  crate::checksum::gen(&data)
+}
+
 /// Determine if a given module kind and media type is emittable or not.
 pub fn is_emittable(
   kind: &ModuleKind,
@@ -402,6 +432,48 @@ pub fn check_and_maybe_emit(
   ) {
     return Ok(Default::default());
   }
+  // Roots whose graph segment checked without diagnostics before, and hasn't
+  // changed since, are not checked again as long as the emits of the segment
+  // are still valid.
+  let config_bytes = options.ts_config.as_bytes();
+  let roots_with_hash: Vec<_> = {
+    let graph_data = graph_data.read();
+    roots
+      .iter()
+      .filter_map(|root| {
+        let root_graph_data = graph_data
+          .graph_segment(std::slice::from_ref(root))
+          .unwrap();
+        let hash = get_graph_hash(
+          &root_graph_data,
+          &config_bytes,
+          &options.type_check_mode,
+        );
+        let is_unchanged = cache.get(CacheType::CheckHash, &root.0).as_ref()
+          == Some(&hash)
+          && valid_emit(
+            &root_graph_data,
+            cache,
+            &options.ts_config,
+            options.reload,
+            &options.reload_exclusions,
+          );
+        if is_unchanged {
+          None
+        } else {
+          Some((root.clone(), hash))
+        }
+      })
+      .collect()
+  };
+  if roots_with_hash.is_empty() {
+    return Ok(Default::default());
+  }
+  let roots: Vec<_> = roots_with_hash
+    .iter()
+    .map(|(root, _)| root.clone())
+    .collect();
+  let roots = roots.as_slice();
   let root_names = get_tsc_roots(roots, &segment_graph_data, check_js);
   if options.log_checks {
     for (root, _) in roots {
@@ -427,7 +499,6 @@ pub fn check_and_maybe_emit(
     options.ts_config.as_bytes(),
     version::deno().as_bytes().to_owned(),
   ];
-  let config_bytes = options.ts_config.as_bytes();
 
   let response = tsc::exec(tsc::Request {
     config: options.ts_config,
@@ -520,12 +591,19 @@ pub fn check_and_maybe_emit(
     }
   }
 
+  if diagnostics.is_empty() {
+    for ((root, _), hash) in roots_with_hash {
+      cache.set(CacheType::CheckHash, &root, hash)?;
+    }
+  }
+
   Ok(CheckEmitResult {
     diagnostics,
     stats: response.stats,
   })
 }
 
+#[derive(Clone, Copy)]
 pub enum BundleType {
   /// Return the emitted contents of the program as a single "flattened" ES
   /// module.
@@ -549,6 +627,23 @@ pub struct BundleOptions {
   pub bundle_type: BundleType,
   pub ts_config: TsConfig,
   pub emit_ignore_directives: bool,
+  /// Imports which are left unbundled, either as written in the source or as
+  /// the URL they resolve to. Values ending with `/` match every URL they
+  /// prefix.
+  pub external: Vec<String>,
+}
+
+/// An output file of bundling multiple entry points.
+pub struct BundleChunk {
+  pub file_name: String,
+  /// The entry point bundled into this file, or `None` for a chunk of modules
+  /// shared by several entry points.
+  pub entry_point: Option<ModuleSpecifier>,
+  pub modules: Vec<ModuleSpecifier>,
+  /// The file names of the chunks this file imports.
+  pub imports: Vec<String>,
+  pub code: String,
+  pub maybe_map: Option<String>,
 }
 
 /// A module loader for swc which does the appropriate retrieval and transpiling
@@ -703,10 +798,275 @@ impl swc::bundler::Resolve for BundleResolver<'_> {
 pub fn bundle(
   graph: &ModuleGraph,
   options: BundleOptions,
+) -> Result<(String, Option<String>), AnyError> {
+  let modules = graph
+    .modules()
+    .into_iter()
+    .map(|module| module.specifier.clone())
+    .collect::<Vec<_>>();
+  let imports =
+    external_imports(graph, &modules, &options.external, &HashMap::new())?;
+  let emit_options: deno_ast::EmitOptions = options.ts_config.into();
+  bundle_module(
+    graph,
+    &graph.roots[0].0,
+    options.bundle_type,
+    &emit_options,
+    options.emit_ignore_directives,
+    &imports,
+  )
+}
+
+/// Bundles each root of the module graph as an entry point. Modules which are
+/// reachable from several entry points are bundled once into a shared chunk,
+/// which the bundles of the entry points import.
+pub fn bundle_chunks(
+  graph: &ModuleGraph,
+  options: BundleOptions,
+) -> Result<Vec<BundleChunk>, AnyError> {
+  let mut entries = Vec::new();
+  for (root, _) in &graph.roots {
+    if let Some(module) = graph.get(root) {
+      if !entries.contains(&module.specifier) {
+        entries.push(module.specifier.clone());
+      }
+    }
+  }
+  let roots = split_chunks(graph, &entries, &options.external);
+  let mut file_names = HashSet::new();
+  let chunk_files = roots
+    .iter()
+    .enumerate()
+    .map(|(i, root)| {
+      let prefix = if i < entries.len() { "" } else { "chunk-" };
+      (
+        root.clone(),
+        unique_chunk_file_name(prefix, root, &mut file_names),
+      )
+    })
+    .collect::<HashMap<_, _>>();
+  let root_set = roots.iter().cloned().collect::<HashSet<_>>();
+  let emit_options: deno_ast::EmitOptions = options.ts_config.into();
+
+  let mut chunks = Vec::new();
+  for (i, root) in roots.iter().enumerate() {
+    let modules = chunk_modules(graph, root, &root_set, &options.external);
+    let mut other_files = chunk_files.clone();
+    other_files.remove(root);
+    let imports =
+      external_imports(graph, &modules, &options.external, &other_files)?;
+    let (code, maybe_map) = bundle_module(
+      graph,
+      root,
+      options.bundle_type,
+      &emit_options,
+      options.emit_ignore_directives,
+      &imports,
+    )?;
+    let mut chunk_imports = imports
+      .into_values()
+      .flatten()
+      .map(|target| target.trim_start_matches("./").to_string())
+      .collect::<Vec<_>>();
+    chunk_imports.sort();
+    chunk_imports.dedup();
+    chunks.push(BundleChunk {
+      file_name: chunk_files[root].clone(),
+      entry_point: (i < entries.len()).then(|| root.clone()),
+      modules,
+      imports: chunk_imports,
+      code,
+      maybe_map,
+    });
+  }
+  Ok(chunks)
+}
+
+/// Returns the roots of the separately bundled parts of the graph: the entry
+/// points, followed by the modules which are statically imported by more than
+/// one of the parts.
+fn split_chunks(
+  graph: &ModuleGraph,
+  entries: &[ModuleSpecifier],
+  external: &[String],
+) -> Vec<ModuleSpecifier> {
+  let mut roots = entries.to_vec();
+  loop {
+    let root_set = roots.iter().cloned().collect::<HashSet<_>>();
+    let mut counts = HashMap::<ModuleSpecifier, usize>::new();
+    let mut order = Vec::new();
+    for root in &roots {
+      for specifier in chunk_modules(graph, root, &root_set, external)
+        .into_iter()
+        .skip(1)
+      {
+        let count = counts.entry(specifier.clone()).or_insert(0);
+        if *count == 0 {
+          order.push(specifier);
+        }
+        *count += 1;
+      }
+    }
+    match order.into_iter().find(|specifier| counts[specifier] > 1) {
+      Some(shared) => roots.push(shared),
+      None => return roots,
+    }
+  }
+}
+
+/// The modules bundled together with `root`, which are the ones statically
+/// imported from it without passing through another root.
+fn chunk_modules(
+  graph: &ModuleGraph,
+  root: &ModuleSpecifier,
+  roots: &HashSet<ModuleSpecifier>,
+  external: &[String],
+) -> Vec<ModuleSpecifier> {
+  let mut seen = HashSet::new();
+  let mut modules = Vec::new();
+  let mut stack = vec![root.clone()];
+  while let Some(specifier) = stack.pop() {
+    let module = match graph.get(&specifier) {
+      Some(module) if seen.insert(module.specifier.clone()) => module,
+      _ => continue,
+    };
+    modules.push(module.specifier.clone());
+    for (text, dependency) in module.dependencies.iter().rev() {
+      if dependency.is_dynamic {
+        continue;
+      }
+      match graph.resolve_dependency(text, &module.specifier, false) {
+        Some(resolved)
+          if !roots.contains(resolved)
+            && !is_external(external, text, Some(resolved)) =>
+        {
+          stack.push(resolved.clone())
+        }
+        _ => {}
+      }
+    }
+  }
+  modules
+}
+
+/// Returns if an import matches one of the external values.
+fn is_external(
+  external: &[String],
+  text: &str,
+  maybe_resolved: Option<&ModuleSpecifier>,
+) -> bool {
+  external.iter().any(|value| {
+    value == text
+      || maybe_resolved.map_or(false, |resolved| {
+        resolved.as_str() == value
+          || (value.ends_with('/') && resolved.as_str().starts_with(value))
+      })
+  })
+}
+
+/// Collects the static imports of the modules which are not bundled with them,
+/// mapping each import as written in the source to the chunk file it is
+/// loaded from, or to `None` when it is left as is.
+fn external_imports(
+  graph: &ModuleGraph,
+  modules: &[ModuleSpecifier],
+  external: &[String],
+  chunk_files: &HashMap<ModuleSpecifier, String>,
+) -> Result<HashMap<String, Option<String>>, AnyError> {
+  let mut imports = HashMap::new();
+  let mut bundled = HashSet::new();
+  for specifier in modules {
+    let module = match graph.get(specifier) {
+      Some(module) => module,
+      None => continue,
+    };
+    for (text, dependency) in &module.dependencies {
+      if dependency.is_dynamic {
+        continue;
+      }
+      let maybe_resolved =
+        graph.resolve_dependency(text, &module.specifier, false);
+      let maybe_target = if is_external(external, text, maybe_resolved) {
+        Some(None)
+      } else {
+        maybe_resolved
+          .and_then(|resolved| chunk_files.get(resolved))
+          .map(|file_name| Some(format!("./{}", file_name)))
+      };
+      // the bundler identifies external modules by the import as written, so
+      // it has to refer to the same module everywhere it is used
+      let is_ambiguous = match &maybe_target {
+        Some(target) => {
+          bundled.contains(text)
+            || matches!(imports.get(text), Some(other) if other != target)
+        }
+        None => imports.contains_key(text),
+      };
+      if is_ambiguous {
+        return Err(anyhow!(
+          "Cannot bundle the import \"{}\" because it refers to modules which are bundled differently. Import them with distinct specifiers.",
+          text
+        ));
+      }
+      match maybe_target {
+        Some(target) => {
+          imports.insert(text.clone(), target);
+        }
+        None => {
+          bundled.insert(text.clone());
+        }
+      }
+    }
+  }
+  Ok(imports)
+}
+
+/// Creates a file name for the bundle of a module that differs from the ones
+/// already taken.
+fn unique_chunk_file_name(
+  prefix: &str,
+  specifier: &ModuleSpecifier,
+  file_names: &mut HashSet<String>,
+) -> String {
+  let stem = std::path::Path::new(specifier.path())
+    .file_stem()
+    .map(|stem| stem.to_string_lossy().to_string())
+    .unwrap_or_default()
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.' {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect::<String>();
+  let stem = if stem.is_empty() {
+    "bundle".to_string()
+  } else {
+    stem
+  };
+  let mut file_name = format!("{}{}.js", prefix, stem);
+  let mut i = 2;
+  while !file_names.insert(file_name.to_lowercase()) {
+    file_name = format!("{}{}-{}.js", prefix, stem, i);
+    i += 1;
+  }
+  file_name
+}
+
+/// Bundles a module with the imports it does not leave external, rewriting
+/// the imports of other chunks to their file names.
+fn bundle_module(
+  graph: &ModuleGraph,
+  root: &ModuleSpecifier,
+  bundle_type: BundleType,
+  emit_options: &deno_ast::EmitOptions,
+  emit_ignore_directives: bool,
+  external_imports: &HashMap<String, Option<String>>,
 ) -> Result<(String, Option<String>), AnyError> {
   let globals = swc::common::Globals::new();
   deno_ast::swc::common::GLOBALS.set(&globals, || {
-    let emit_options: deno_ast::EmitOptions = options.ts_config.into();
     let source_map_config = deno_ast::SourceMapConfig {
       inline_sources: emit_options.inline_sources,
     };
@@ -716,12 +1076,16 @@ pub fn bundle(
     ));
     let loader = BundleLoader {
       graph,
-      emit_options: &emit_options,
+      emit_options,
       cm: cm.clone(),
     };
     let resolver = BundleResolver(graph);
     let config = swc::bundler::Config {
-      module: options.bundle_type.into(),
+      module: bundle_type.into(),
+      external_modules: external_imports
+        .keys()
+        .map(|import| import.as_str().into())
+        .collect(),
       ..Default::default()
     };
     // This hook will rewrite the `import.meta` when bundling to give a consistent
@@ -738,11 +1102,12 @@ pub fn bundle(
     let mut entries = HashMap::new();
     entries.insert(
       "bundle".to_string(),
-      swc::common::FileName::Url(graph.roots[0].0.clone()),
+      swc::common::FileName::Url(root.clone()),
     );
-    let output = bundler
+    let mut output = bundler
       .bundle(entries)
       .context("Unable to output during bundling.")?;
+    rewrite_chunk_imports(&mut output[0].module, external_imports);
     let mut buf = Vec::new();
     let mut srcmap = Vec::new();
     {
@@ -754,7 +1119,7 @@ pub fn bundle(
         Some(&mut srcmap),
       ));
 
-      if options.emit_ignore_directives {
+      if emit_ignore_directives {
         // write leading comments in bundled file
         use swc::codegen::text_writer::WriteJs;
         let cmt = IGNORE_DIRECTIVES.join("\n") + "\n";
@@ -793,6 +1158,37 @@ pub fn bundle(
   })
 }
 
+/// Points the imports of modules bundled into other chunks to the files of
+/// those chunks.
+fn rewrite_chunk_imports(
+  module: &mut swc::ast::Module,
+  external_imports: &HashMap<String, Option<String>>,
+) {
+  use deno_ast::swc::ast;
+
+  for item in module.body.iter_mut() {
+    let src = match item {
+      ast::ModuleItem::ModuleDecl(ast::ModuleDecl::Import(decl)) => {
+        &mut decl.src
+      }
+      ast::ModuleItem::ModuleDecl(ast::ModuleDecl::ExportAll(decl)) => {
+        &mut decl.src
+      }
+      ast::ModuleItem::ModuleDecl(ast::ModuleDecl::ExportNamed(
+        ast::NamedExport { src: Some(src), .. },
+      )) => src,
+      _ => continue,
+    };
+    if let Some(Some(file_name)) = external_imports.get(&*src.value) {
+      *src = ast::Str {
+        span: src.span,
+        value: file_name.as_str().into(),
+        raw: None,
+      };
+    }
+  }
+}
+
 pub struct EmitOptions {
   pub ts_config: TsConfig,
   pub reload: bool,
@@ -125,28 +125,37 @@ impl CacheSetting {
           false
         }
       }
-      CacheSetting::ReloadSome(list) => {
-        let mut url = specifier.clone();
-        url.set_fragment(None);
-        if list.contains(&url.as_str().to_string()) {
-          return false;
-        }
-        url.set_query(None);
-        let mut path = PathBuf::from(url.as_str());
-        loop {
-          if list.contains(&path.to_str().unwrap().to_string()) {
-            return false;
-          }
-          if !path.pop() {
-            break;
-          }
-        }
-        true
-      }
+      CacheSetting::ReloadSome(list) => !list
+        .iter()
+        .any(|pattern| reload_pattern_matches(pattern, specifier)),
     }
   }
 }
 
+/// Returns if a `--reload` pattern covers the specifier. Besides the
+/// specifier itself, a pattern covers every module below it when it is an
+/// origin or a path prefix, and every version of a package when its last
+/// segment has no version, so `https://esm.sh/react` covers
+/// `https://esm.sh/react@18.0.0/index.js`.
+fn reload_pattern_matches(pattern: &str, specifier: &ModuleSpecifier) -> bool {
+  let mut url = specifier.clone();
+  url.set_fragment(None);
+  if url.as_str() == pattern {
+    return true;
+  }
+  url.set_query(None);
+  match url.as_str().strip_prefix(pattern) {
+    Some("") => true,
+    Some(rest) if rest.starts_with('/') => true,
+    Some(rest) if rest.starts_with('@') => {
+      let last_segment = pattern.rsplit('/').next().unwrap();
+      // the pattern has to name a package, not just an origin
+      pattern.matches('/').count() > 2 && !last_segment.contains('@')
+    }
+    _ => false,
+  }
+}
+
 /// Fetch a source file from the local file system.
 fn fetch_local(specifier: &ModuleSpecifier) -> Result<File, AnyError> {
   let local = specifier.to_file_path().map_err(|_| {
@@ -830,6 +839,63 @@ mod tests {
     assert_eq!(file.source.as_str(), expected);
   }
 
+  #[test]
+  fn test_reload_pattern_matches() {
+    let fixtures = vec![
+      (
+        "https://deno.land/std",
+        "https://deno.land/std/fs/mod.ts",
+        true,
+      ),
+      (
+        "https://deno.land/std@0.130.0",
+        "https://deno.land/std@0.130.0/fs/mod.ts",
+        true,
+      ),
+      (
+        "https://deno.land/std@0.130.0",
+        "https://deno.land/std@0.131.0/fs/mod.ts",
+        false,
+      ),
+      (
+        "https://deno.land/std",
+        "https://deno.land/std@0.130.0/mod.ts",
+        true,
+      ),
+      ("https://esm.sh", "https://esm.sh/react@18.0.0", true),
+      ("https://esm.sh/react", "https://esm.sh/react@18.0.0", true),
+      ("https://esm.sh/react", "https://esm.sh/react?dev", true),
+      (
+        "https://esm.sh/react",
+        "https://esm.sh/react-dom@18.0.0",
+        false,
+      ),
+      ("https://esm.sh/react", "https://esm.sh/preact", false),
+      ("https://esm.sh", "https://esm.sh.example.com/mod.ts", false),
+      (
+        "https://deno.land/x/mod.ts?a=1",
+        "https://deno.land/x/mod.ts?a=1#b",
+        true,
+      ),
+      (
+        "https://deno.land/std/fs/utils.ts",
+        "https://deno.land/std/fs/mod.ts",
+        false,
+      ),
+    ];
+
+    for (pattern, specifier, expected) in fixtures {
+      let specifier = resolve_url_or_path(specifier).unwrap();
+      assert_eq!(
+        reload_pattern_matches(pattern, &specifier),
+        expected,
+        "{} {}",
+        pattern,
+        specifier
+      );
+    }
+  }
+
   #[test]
   fn test_get_validated_scheme() {
     let fixtures = vec![
@@ -1,7 +1,13 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
 use crate::colors;
+use crate::config_file;
+use crate::display;
+use crate::flags::Flags;
 use crate::fs_util::canonicalize_path;
+use crate::fs_util::glob_to_regex;
+use crate::fs_util::is_glob;
+use crate::fs_util::normalize_path;
 
 use deno_core::error::AnyError;
 use deno_core::futures::Future;
@@ -10,19 +16,23 @@ use notify::event::Event as NotifyEvent;
 use notify::event::EventKind;
 use notify::Config;
 use notify::Error as NotifyError;
+use notify::PollWatcher;
 use notify::RecommendedWatcher;
 use notify::RecursiveMode;
 use notify::Watcher;
+use regex::Regex;
 use std::collections::HashSet;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 use tokio::select;
 use tokio::sync::mpsc;
 use tokio::time::sleep;
 
 const CLEAR_SCREEN: &str = "\x1B[2J\x1B[1;1H";
-const DEBOUNCE_INTERVAL: Duration = Duration::from_millis(200);
+const DEFAULT_DEBOUNCE_INTERVAL: Duration = Duration::from_millis(200);
 
 struct DebouncedReceiver {
   // The `recv()` call could be used in a tokio `select!` macro,
@@ -30,16 +40,20 @@ struct DebouncedReceiver {
   // lose items if a `recv()` never completes
   received_items: HashSet<PathBuf>,
   receiver: mpsc::UnboundedReceiver<Vec<PathBuf>>,
+  debounce: Duration,
 }
 
 impl DebouncedReceiver {
-  fn new_with_sender() -> (Arc<mpsc::UnboundedSender<Vec<PathBuf>>>, Self) {
+  fn new_with_sender(
+    debounce: Duration,
+  ) -> (Arc<mpsc::UnboundedSender<Vec<PathBuf>>>, Self) {
     let (sender, receiver) = mpsc::unbounded_channel();
     (
       Arc::new(sender),
       Self {
         receiver,
         received_items: HashSet::new(),
+        debounce,
       },
     )
   }
@@ -56,7 +70,7 @@ impl DebouncedReceiver {
         items = self.receiver.recv() => {
           self.received_items.extend(items?);
         }
-        _ = sleep(DEBOUNCE_INTERVAL) => {
+        _ = sleep(self.debounce) => {
           return Some(self.received_items.drain().collect());
         }
       }
@@ -83,16 +97,19 @@ pub enum ResolutionResult<T> {
   Ignore,
 }
 
+/// Waits for the next change which the resolver doesn't ignore, returning the
+/// resolution along with the instant the change was received at.
 async fn next_restart<R, T, F>(
   resolver: &mut R,
   debounced_receiver: &mut DebouncedReceiver,
-) -> (Vec<PathBuf>, Result<T, AnyError>)
+) -> (Vec<PathBuf>, Result<T, AnyError>, Instant)
 where
   R: FnMut(Option<Vec<PathBuf>>) -> F,
   F: Future<Output = ResolutionResult<T>>,
 {
   loop {
     let changed = debounced_receiver.recv().await;
+    let started = Instant::now();
     match resolver(changed).await {
       ResolutionResult::Ignore => {
         log::debug!("File change ignored")
@@ -101,17 +118,84 @@ where
         paths_to_watch,
         result,
       } => {
-        return (paths_to_watch, result);
+        return (paths_to_watch, result, started);
       }
     }
   }
 }
 
-pub struct PrintConfig {
+/// The configuration of a watcher, shared by all the subcommands supporting
+/// `--watch`. It is resolved from the flags and the `"watch"` configuration
+/// of the configuration file, with the flags taking precedence.
+pub struct WatcherConfig {
   /// printing watcher status to terminal.
   pub job_name: String,
   /// determine whether to clear the terminal screen
   pub clear_screen: bool,
+  /// Whether to report how long each run took once it finished.
+  pub report_elapsed: bool,
+  /// How long to wait for further changes before restarting.
+  pub debounce: Duration,
+  /// Polls the watched paths at this interval instead of relying on file
+  /// system events, which aren't delivered for network file systems and
+  /// some container volumes.
+  pub poll_interval: Option<Duration>,
+  /// Changes of files matching these globs are ignored.
+  pub exclude: Vec<Regex>,
+}
+
+impl WatcherConfig {
+  pub fn new(job_name: &str, flags: &Flags) -> Result<Self, AnyError> {
+    let watch_config = match config_file::discover(flags)? {
+      Some(config_file) => config_file.to_watch_config()?,
+      None => None,
+    }
+    .unwrap_or_default();
+    let exclude = exclude_regexes(&watch_config.exclude)?;
+    Ok(Self {
+      job_name: job_name.to_string(),
+      clear_screen: !flags.no_clear_screen
+        && watch_config.clear_screen.unwrap_or(true),
+      report_elapsed: false,
+      debounce: flags
+        .watch_debounce
+        .or(watch_config.debounce)
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_DEBOUNCE_INTERVAL),
+      poll_interval: flags
+        .watch_poll_interval
+        .or(watch_config.poll_interval)
+        .map(Duration::from_millis),
+      exclude,
+    })
+  }
+
+  fn is_excluded(&self, path: &Path) -> bool {
+    let path = normalize_path(path).to_string_lossy().replace('\\', "/");
+    self.exclude.iter().any(|regex| regex.is_match(&path))
+  }
+}
+
+/// Converts the absolute exclude globs of the configuration to regexes
+/// matching the paths the watcher reports, which are canonicalized.
+fn exclude_regexes(globs: &[String]) -> Result<Vec<Regex>, AnyError> {
+  globs
+    .iter()
+    .map(|glob| {
+      if is_glob(glob) {
+        glob_to_regex(
+          &normalize_path(glob).to_string_lossy().replace('\\', "/"),
+        )
+      } else {
+        // paths without glob syntax exclude everything below them as well
+        let path = normalize_path(glob);
+        let path = canonicalize_path(&path).unwrap_or(path);
+        let path = path.to_string_lossy().replace('\\', "/");
+        let path = path.trim_end_matches('/');
+        glob_to_regex(&format!("{{{},{}/**}}", path, path))
+      }
+    })
+    .collect()
 }
 
 /// Creates a file watcher, which will call `resolver` with every file change.
@@ -127,7 +211,7 @@ pub struct PrintConfig {
 pub async fn watch_func<R, O, T, F1, F2>(
   mut resolver: R,
   mut operation: O,
-  print_config: PrintConfig,
+  config: WatcherConfig,
 ) -> Result<(), AnyError>
 where
   R: FnMut(Option<Vec<PathBuf>>) -> F1,
@@ -135,17 +219,19 @@ where
   F1: Future<Output = ResolutionResult<T>>,
   F2: Future<Output = Result<(), AnyError>>,
 {
-  let (sender, mut receiver) = DebouncedReceiver::new_with_sender();
-
-  let PrintConfig {
-    job_name,
-    clear_screen,
-  } = print_config;
+  let (sender, mut receiver) =
+    DebouncedReceiver::new_with_sender(config.debounce);
+  let config = Arc::new(config);
+  let job_name = &config.job_name;
+  let clear_screen = config.clear_screen;
 
   // Store previous data. If module resolution fails at some point, the watcher will try to
   // continue watching files using these data.
   let mut paths_to_watch;
   let mut resolution_result;
+  // The instant the current run started at, used to report how long
+  // rebuilding took.
+  let mut started;
 
   let print_after_restart = || {
     if clear_screen {
@@ -157,6 +243,7 @@ where
     );
   };
 
+  let initial_started = Instant::now();
   match resolver(None).await {
     ResolutionResult::Ignore => {
       // The only situation where it makes sense to ignore the initial 'change'
@@ -170,9 +257,11 @@ where
         colors::intense_blue("Watcher"),
       );
 
-      let (paths, result) = next_restart(&mut resolver, &mut receiver).await;
+      let (paths, result, instant) =
+        next_restart(&mut resolver, &mut receiver).await;
       paths_to_watch = paths;
       resolution_result = result;
+      started = instant;
 
       print_after_restart();
     }
@@ -182,23 +271,25 @@ where
     } => {
       paths_to_watch = paths;
       resolution_result = result;
+      started = initial_started;
     }
   };
 
   info!("{} {} started.", colors::intense_blue("Watcher"), job_name,);
 
   loop {
-    let watcher = new_watcher(&paths_to_watch, sender.clone())?;
+    let watcher = new_watcher(&paths_to_watch, sender.clone(), config.clone())?;
 
     match resolution_result {
       Ok(operation_arg) => {
         let fut = error_handler(operation(operation_arg));
         select! {
-          (paths, result) = next_restart(&mut resolver, &mut receiver) => {
+          (paths, result, instant) = next_restart(&mut resolver, &mut receiver) => {
             if result.is_ok() {
               paths_to_watch = paths;
             }
             resolution_result = result;
+            started = instant;
 
             print_after_restart();
             continue;
@@ -206,11 +297,20 @@ where
           _ = fut => {},
         };
 
-        info!(
-          "{} {} finished. Restarting on file change...",
-          colors::intense_blue("Watcher"),
-          job_name,
-        );
+        if config.report_elapsed {
+          info!(
+            "{} {} finished in {}. Restarting on file change...",
+            colors::intense_blue("Watcher"),
+            job_name,
+            display::human_elapsed(started.elapsed().as_millis()),
+          );
+        } else {
+          info!(
+            "{} {} finished. Restarting on file change...",
+            colors::intense_blue("Watcher"),
+            job_name,
+          );
+        }
       }
       Err(error) => {
         eprintln!("{}: {}", colors::red_bold("error"), error);
@@ -222,11 +322,13 @@ where
       }
     }
 
-    let (paths, result) = next_restart(&mut resolver, &mut receiver).await;
+    let (paths, result, instant) =
+      next_restart(&mut resolver, &mut receiver).await;
     if result.is_ok() {
       paths_to_watch = paths;
     }
     resolution_result = result;
+    started = instant;
 
     print_after_restart();
 
@@ -234,34 +336,139 @@ where
   }
 }
 
+/// Watches paths and yields debounced batches of changed files, leaving it
+/// to the caller to apply them. Used by `deno run --watch --hot`, which keeps
+/// the process alive instead of restarting it.
+pub struct ChangeWatcher {
+  sender: Arc<mpsc::UnboundedSender<Vec<PathBuf>>>,
+  receiver: DebouncedReceiver,
+  config: Arc<WatcherConfig>,
+  // Kept alive for as long as the paths should be watched.
+  _watcher: WatcherBackend,
+}
+
+impl ChangeWatcher {
+  pub fn new(
+    paths: &[PathBuf],
+    config: WatcherConfig,
+  ) -> Result<Self, AnyError> {
+    let (sender, receiver) =
+      DebouncedReceiver::new_with_sender(config.debounce);
+    let config = Arc::new(config);
+    let watcher = new_watcher(paths, sender.clone(), config.clone())?;
+    Ok(Self {
+      sender,
+      receiver,
+      config,
+      _watcher: watcher,
+    })
+  }
+
+  /// Replaces the set of watched paths.
+  pub fn watch(&mut self, paths: &[PathBuf]) -> Result<(), AnyError> {
+    self._watcher =
+      new_watcher(paths, self.sender.clone(), self.config.clone())?;
+    Ok(())
+  }
+
+  pub async fn next(&mut self) -> Option<Vec<PathBuf>> {
+    self.receiver.recv().await
+  }
+}
+
+/// The backend detecting the changes of the watched paths.
+enum WatcherBackend {
+  /// Relies on the file system events of the platform.
+  Native(RecommendedWatcher),
+  /// Compares the metadata of the watched paths at an interval.
+  Poll(PollWatcher),
+}
+
+impl WatcherBackend {
+  fn watch(&mut self, path: &Path) -> Result<(), NotifyError> {
+    match self {
+      Self::Native(watcher) => watcher.watch(path, RecursiveMode::Recursive),
+      Self::Poll(watcher) => watcher.watch(path, RecursiveMode::Recursive),
+    }
+  }
+}
+
 fn new_watcher(
   paths: &[PathBuf],
   sender: Arc<mpsc::UnboundedSender<Vec<PathBuf>>>,
-) -> Result<RecommendedWatcher, AnyError> {
-  let mut watcher: RecommendedWatcher =
-    Watcher::new(move |res: Result<NotifyEvent, NotifyError>| {
-      if let Ok(event) = res {
-        if matches!(
-          event.kind,
-          EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
-        ) {
-          let paths = event
-            .paths
-            .iter()
-            .filter_map(|path| canonicalize_path(path).ok())
-            .collect();
+  config: Arc<WatcherConfig>,
+) -> Result<WatcherBackend, AnyError> {
+  let event_handler = move |res: Result<NotifyEvent, NotifyError>| {
+    if let Ok(event) = res {
+      if matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+      ) {
+        let paths = event
+          .paths
+          .iter()
+          .filter_map(|path| canonicalize_path(path).ok())
+          .filter(|path| !config.is_excluded(path))
+          .collect::<Vec<_>>();
+        if !paths.is_empty() {
           sender.send(paths).unwrap();
         }
       }
-    })?;
+    }
+  };
 
-  watcher.configure(Config::PreciseEvents(true)).unwrap();
+  let mut watcher = match config.poll_interval {
+    Some(interval) => {
+      WatcherBackend::Poll(PollWatcher::with_delay(event_handler, interval)?)
+    }
+    None => {
+      let mut watcher: RecommendedWatcher = Watcher::new(event_handler)?;
+      watcher.configure(Config::PreciseEvents(true)).unwrap();
+      WatcherBackend::Native(watcher)
+    }
+  };
 
   log::debug!("Watching paths: {:?}", paths);
   for path in paths {
     // Ignore any error e.g. `PathNotFound`
-    let _ = watcher.watch(path, RecursiveMode::Recursive);
+    let _ = watcher.watch(path);
   }
 
   Ok(watcher)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config_file::ConfigFile;
+  use deno_core::ModuleSpecifier;
+
+  fn config_excluding(config_text: &str) -> WatcherConfig {
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let watch_config = config_file.to_watch_config().unwrap().unwrap();
+    WatcherConfig {
+      job_name: "Test".to_string(),
+      clear_screen: false,
+      report_elapsed: false,
+      debounce: DEFAULT_DEBOUNCE_INTERVAL,
+      poll_interval: None,
+      exclude: exclude_regexes(&watch_config.exclude).unwrap(),
+    }
+  }
+
+  #[cfg(not(windows))]
+  #[test]
+  fn test_is_excluded() {
+    let config = config_excluding(
+      r#"{ "watch": { "exclude": ["./dist", "src/../**/*.gen.ts"] } }"#,
+    );
+    assert!(config.is_excluded(Path::new("/deno/dist")));
+    assert!(config.is_excluded(Path::new("/deno/dist/mod.js")));
+    assert!(config.is_excluded(Path::new("/deno/src/types.gen.ts")));
+    assert!(!config.is_excluded(Path::new("/deno/distribution/mod.js")));
+    assert!(!config.is_excluded(Path::new("/deno/src/types.ts")));
+    assert!(!config.is_excluded(Path::new("/other/dist/mod.js")));
+  }
+}
@@ -1,6 +1,7 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
 use clap::Arg;
+use clap::ArgGroup;
 use clap::ArgMatches;
 use clap::ColorChoice;
 use clap::Command;
@@ -8,13 +9,15 @@ use clap::ValueHint;
 use deno_core::serde::Deserialize;
 use deno_core::serde::Serialize;
 use deno_core::url::Url;
+use deno_runtime::inspector_server::InspectorAddress;
 use deno_runtime::permissions::PermissionsOptions;
 use log::debug;
 use log::Level;
 use once_cell::sync::Lazy;
+use std::collections::HashMap;
 use std::env;
-use std::net::SocketAddr;
 use std::num::NonZeroU32;
+use std::num::NonZeroU64;
 use std::num::NonZeroU8;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
@@ -40,12 +43,19 @@ pub struct BenchFlags {
   pub ignore: Vec<PathBuf>,
   pub include: Option<Vec<String>>,
   pub filter: Option<String>,
+  pub json: bool,
+  pub baseline: Option<PathBuf>,
+  pub baseline_threshold: f64,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct BundleFlags {
   pub source_file: String,
   pub out_file: Option<PathBuf>,
+  /// Additional entry points, only allowed together with `out_dir`.
+  pub entries: Vec<String>,
+  pub out_dir: Option<PathBuf>,
+  pub external: Vec<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -65,6 +75,14 @@ pub struct CompileFlags {
   pub output: Option<PathBuf>,
   pub args: Vec<String>,
   pub target: Option<String>,
+  pub include: Vec<String>,
+  pub icon: Option<PathBuf>,
+  pub product_name: Option<String>,
+  pub product_version: Option<String>,
+  pub description: Option<String>,
+  pub copyright: Option<String>,
+  pub compress: bool,
+  pub size_report: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -80,12 +98,34 @@ pub struct CoverageFlags {
   pub include: Vec<String>,
   pub exclude: Vec<String>,
   pub lcov: bool,
+  pub html: Option<PathBuf>,
+  pub merge: bool,
+  pub fail_under: Option<CoverageThreshold>,
+}
+
+/// Source files `deno coverage` reports on unless `--include` is given.
+pub const COVERAGE_DEFAULT_INCLUDE: &str = r"^file:";
+/// Source files `deno coverage` leaves out unless `--exclude` is given.
+pub const COVERAGE_DEFAULT_EXCLUDE: &str = r"test\.(js|mjs|ts|jsx|tsx)$";
+
+/// Minimum line and branch coverage percentages required by
+/// `--coverage-fail-under`.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct CoverageThreshold {
+  pub line: Option<f64>,
+  pub branch: Option<f64>,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct DocFlags {
   pub private: bool,
   pub json: bool,
+  /// The directory to write the documentation to as an HTML site.
+  pub html: Option<PathBuf>,
+  pub lint: bool,
+  /// The percentage of completely documented symbols below which linting the
+  /// documentation fails.
+  pub lint_threshold: f64,
   pub source_file: Option<String>,
   pub filter: Option<String>,
 }
@@ -95,11 +135,15 @@ pub struct EvalFlags {
   pub print: bool,
   pub code: String,
   pub ext: String,
+  /// The directory or URL relative imports of the code are resolved against.
+  pub cwd_url: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct FmtFlags {
   pub check: bool,
+  pub unified_diff: bool,
+  pub json: bool,
   pub files: Vec<PathBuf>,
   pub ignore: Vec<PathBuf>,
   pub ext: String,
@@ -107,13 +151,28 @@ pub struct FmtFlags {
   pub line_width: Option<NonZeroU32>,
   pub indent_width: Option<NonZeroU8>,
   pub single_quote: Option<bool>,
+  pub semi_colons: Option<bool>,
   pub prose_wrap: Option<String>,
+  pub force: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct InfoFlags {
   pub json: bool,
   pub file: Option<String>,
+  pub why: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct InitFlags {
+  /// Name of a built-in template, defaults to `cli`.
+  pub template: Option<String>,
+  /// URL of a template manifest, used instead of a built-in template.
+  pub template_url: Option<String>,
+  pub dir: Option<PathBuf>,
+  pub vscode: bool,
+  pub import_map: bool,
+  pub force: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -127,8 +186,9 @@ pub struct InstallFlags {
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct UninstallFlags {
-  pub name: String,
+  pub name: Option<String>,
   pub root: Option<PathBuf>,
+  pub list: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -140,6 +200,16 @@ pub struct LintFlags {
   pub maybe_rules_include: Option<Vec<String>>,
   pub maybe_rules_exclude: Option<Vec<String>>,
   pub json: bool,
+  pub sarif: bool,
+  pub fix: bool,
+  pub dry_run: bool,
+  pub force: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct OutdatedFlags {
+  pub files: Vec<String>,
+  pub update: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -156,6 +226,7 @@ pub struct RunFlags {
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct TaskFlags {
   pub task: String,
+  pub parallel: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -170,13 +241,80 @@ pub struct TestFlags {
   pub shuffle: Option<u64>,
   pub concurrent_jobs: NonZeroUsize,
   pub trace_ops: bool,
+  pub profile: Option<PathBuf>,
+  pub reporter: TestReporterConfig,
+  pub shard: Option<TestShard>,
+  pub timeout: Option<u64>,
+  pub retries: usize,
+  pub watch_full: bool,
+  pub setup: Option<String>,
+  pub update_snapshots: bool,
+  pub reuse_workers: bool,
+  pub slow_threshold: Option<u64>,
+  pub no_diff: bool,
+  pub coverage_fail_under: Option<CoverageThreshold>,
+  pub filter_tags: Vec<String>,
+  pub skip_tags: Vec<String>,
+  pub capture_output: bool,
+  pub verbose_output: bool,
+  pub list: Option<TestListFormat>,
+}
+
+/// Selects the `index`th of `count` shards of the test modules, with `index`
+/// starting at 1.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TestShard {
+  pub index: usize,
+  pub count: usize,
+}
+
+/// Which reporter `deno test` uses to report results.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum TestReporterConfig {
+  /// Human readable output on stdout.
+  Pretty,
+  /// JUnit XML, written to the given file or to stdout.
+  Junit(Option<PathBuf>),
+  /// TAP version 13 on stdout.
+  Tap,
+  /// Newline-delimited JSON test events on stdout.
+  Json,
+  /// The JSON test events passed to the functions a user module exports.
+  Module(String),
+}
+
+impl Default for TestReporterConfig {
+  fn default() -> Self {
+    Self::Pretty
+  }
+}
+
+/// How `deno test --list` prints the tests it finds.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum TestListFormat {
+  /// The tests of each module, indented by step.
+  Pretty,
+  /// A JSON array of the modules and their tests.
+  Json,
+}
+
+/// The release channel `deno upgrade` installs from.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum UpgradeChannel {
+  /// Tagged releases, selected by semver.
+  Stable,
+  /// Builds of every commit on main, selected by commit hash.
+  Canary,
+  /// Release candidates of the next release, selected by semver.
+  Rc,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct UpgradeFlags {
   pub dry_run: bool,
   pub force: bool,
-  pub canary: bool,
+  pub no_verify: bool,
+  pub channel: UpgradeChannel,
   pub version: Option<String>,
   pub output: Option<PathBuf>,
   pub ca_file: Option<String>,
@@ -187,6 +325,8 @@ pub struct VendorFlags {
   pub specifiers: Vec<String>,
   pub output_path: Option<PathBuf>,
   pub force: bool,
+  pub check: bool,
+  pub update: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -202,10 +342,12 @@ pub enum DenoSubcommand {
   Eval(EvalFlags),
   Fmt(FmtFlags),
   Info(InfoFlags),
+  Init(InitFlags),
   Install(InstallFlags),
   Uninstall(UninstallFlags),
   Lsp,
   Lint(LintFlags),
+  Outdated(OutdatedFlags),
   Repl(ReplFlags),
   Run(RunFlags),
   Task(TaskFlags),
@@ -276,6 +418,9 @@ pub struct Flags {
   pub allow_ffi: Option<Vec<PathBuf>>,
   pub allow_read: Option<Vec<PathBuf>>,
   pub allow_run: Option<Vec<String>>,
+  /// The argument prefixes the binaries may be spawned with, as granted by a
+  /// permission set.
+  pub allow_run_args: Option<HashMap<String, Vec<Vec<String>>>>,
   pub allow_write: Option<Vec<PathBuf>>,
   pub ca_stores: Option<Vec<String>>,
   pub ca_file: Option<String>,
@@ -291,13 +436,16 @@ pub struct Flags {
   pub has_check_flag: bool,
   pub config_path: Option<String>,
   pub coverage_dir: Option<String>,
+  pub dump_op_metrics: Option<PathBuf>,
   pub enable_testing_features: bool,
   pub ignore: Vec<PathBuf>,
   pub import_map_path: Option<String>,
-  pub inspect_brk: Option<SocketAddr>,
-  pub inspect: Option<SocketAddr>,
+  pub inspect_brk: Option<InspectorAddress>,
+  pub inspect: Option<InspectorAddress>,
+  pub inspect_wait: Option<InspectorAddress>,
   pub location: Option<Url>,
   pub lock_write: bool,
+  pub max_heap_size: Option<usize>,
   pub lock: Option<PathBuf>,
   pub log_level: Option<Level>,
   pub no_remote: bool,
@@ -305,6 +453,8 @@ pub struct Flags {
   /// the import map.
   pub compat: bool,
   pub no_prompt: bool,
+  pub permission_audit: Option<PathBuf>,
+  pub permission_set: Option<String>,
   pub reload: bool,
   pub repl: bool,
   pub seed: Option<u64>,
@@ -313,7 +463,13 @@ pub struct Flags {
   pub v8_flags: Vec<String>,
   pub version: bool,
   pub watch: Option<Vec<PathBuf>>,
+  /// Milliseconds to wait for further changes before restarting.
+  pub watch_debounce: Option<u64>,
+  /// Milliseconds between polls of the watched files, which uses polling
+  /// instead of file system events.
+  pub watch_poll_interval: Option<u64>,
   pub no_clear_screen: bool,
+  pub hot: bool,
 }
 
 fn join_paths(allowlist: &[PathBuf], d: &str) -> String {
@@ -461,6 +617,7 @@ impl Flags {
       allow_ffi: self.allow_ffi.clone(),
       allow_read: self.allow_read.clone(),
       allow_run: self.allow_run.clone(),
+      allow_run_args: self.allow_run_args.clone(),
       allow_write: self.allow_write.clone(),
       prompt: !self.no_prompt,
     }
@@ -481,6 +638,12 @@ static ENV_VARIABLES_HELP: &str = r#"ENVIRONMENT VARIABLES:
                          (defaults to $HOME/.deno/bin)
     DENO_NO_PROMPT       Set to disable permission prompts on access
                          (alternative to passing --no-prompt on invocation)
+    DENO_REPL_HISTORY    Set REPL history file path. History file is disabled
+                         when the value is empty
+                         (defaults to $DENO_DIR/deno_history.txt)
+    DENO_REPL_HISTORY_SIZE
+                         Set the maximum number of entries kept in the REPL
+                         history (defaults to 1000)
     DENO_FUTURE_CHECK    Opt-in to the upcoming behavior of the `deno run`
                          subcommand that doesn't perform type-checking by
                          default.
@@ -546,9 +709,11 @@ pub fn flags_from_vec(args: Vec<String>) -> clap::Result<Flags> {
     Some(("eval", m)) => eval_parse(&mut flags, m),
     Some(("fmt", m)) => fmt_parse(&mut flags, m),
     Some(("info", m)) => info_parse(&mut flags, m),
+    Some(("init", m)) => init_parse(&mut flags, m),
     Some(("install", m)) => install_parse(&mut flags, m),
     Some(("lint", m)) => lint_parse(&mut flags, m),
     Some(("lsp", m)) => lsp_parse(&mut flags, m),
+    Some(("outdated", m)) => outdated_parse(&mut flags, m),
     Some(("repl", m)) => repl_parse(&mut flags, m),
     Some(("run", m)) => run_parse(&mut flags, m),
     Some(("task", m)) => task_parse(&mut flags, m, &args),
@@ -622,10 +787,12 @@ fn clap_root(version: &str) -> Command {
     .subcommand(eval_subcommand())
     .subcommand(fmt_subcommand())
     .subcommand(info_subcommand())
+    .subcommand(init_subcommand())
     .subcommand(install_subcommand())
     .subcommand(uninstall_subcommand())
     .subcommand(lsp_subcommand())
     .subcommand(lint_subcommand())
+    .subcommand(outdated_subcommand())
     .subcommand(repl_subcommand())
     .subcommand(run_subcommand())
     .subcommand(task_subcommand())
@@ -655,6 +822,33 @@ fn bench_subcommand<'a>() -> Command<'a> {
         .takes_value(true)
         .help("Run benchmarks with this string or pattern in the bench name"),
     )
+    .arg(
+      Arg::new("json")
+        .long("json")
+        .help("Output benchmark results in JSON format"),
+    )
+    .arg(
+      Arg::new("baseline")
+        .long("baseline")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("FILE")
+        .value_hint(ValueHint::FilePath)
+        .help("Compare results against a baseline file written by --json"),
+    )
+    .arg(
+      Arg::new("baseline-threshold")
+        .long("baseline-threshold")
+        .takes_value(true)
+        .require_equals(true)
+        .requires("baseline")
+        .value_name("PERCENT")
+        .validator(|val: &str| match val.parse::<f64>() {
+          Ok(threshold) if threshold >= 0.0 => Ok(()),
+          _ => Err("baseline threshold should be a non-negative number"),
+        })
+        .help("Percentage slowdown over the baseline that fails the run (defaults to 10)"),
+    )
     .arg(
       Arg::new("files")
         .help("List of file names to run")
@@ -664,6 +858,8 @@ fn bench_subcommand<'a>() -> Command<'a> {
     )
     .arg(watch_arg(false))
     .arg(no_clear_screen_arg())
+    .arg(watch_debounce_arg())
+    .arg(watch_poll_interval_arg())
     .arg(script_arg().last(true))
     .about("Run benchmarks")
     .long_about(
@@ -677,7 +873,18 @@ and report results to standard output:
 Directory arguments are expanded to all contained files matching the \
 glob {*_,*.,}bench.{js,mjs,ts,jsx,tsx}:
 
-  deno bench src/",
+  deno bench src/
+
+Write the results as JSON and later compare against them, failing if any \
+benchmark's average time regressed by more than the threshold:
+
+  deno bench --json > baseline.json
+  deno bench --baseline=baseline.json --baseline-threshold=5
+
+Re-run benchmarks whenever their files change, showing how each benchmark's \
+average time changed since the previous run:
+
+  deno bench --watch",
     )
 }
 
@@ -687,16 +894,31 @@ fn bundle_subcommand<'a>() -> Command<'a> {
       Arg::new("source_file")
         .takes_value(true)
         .required(true)
+        .min_values(1)
         .value_hint(ValueHint::FilePath),
     )
     .arg(
-      Arg::new("out_file")
+      Arg::new("out-dir")
+        .long("out-dir")
         .takes_value(true)
-        .required(false)
-        .value_hint(ValueHint::FilePath),
+        .require_equals(true)
+        .value_name("DIR")
+        .help("Bundle every source file as an entry point into this directory")
+        .value_hint(ValueHint::DirPath),
+    )
+    .arg(
+      Arg::new("external")
+        .long("external")
+        .takes_value(true)
+        .use_value_delimiter(true)
+        .require_equals(true)
+        .value_name("SPECIFIER")
+        .help("Leave imports of these specifiers unbundled"),
     )
     .arg(watch_arg(false))
     .arg(no_clear_screen_arg())
+    .arg(watch_debounce_arg())
+    .arg(watch_poll_interval_arg())
     .about("Bundle module and dependencies into single file")
     .long_about(
       "Output a single JavaScript file with all dependencies.
@@ -705,7 +927,18 @@ fn bundle_subcommand<'a>() -> Command<'a> {
 
 If no output file is given, the output is written to standard output:
 
-  deno bundle https://deno.land/std/examples/colors.ts",
+  deno bundle https://deno.land/std/examples/colors.ts
+
+Multiple entry points can be bundled into a directory. Modules shared by
+several entry points are emitted as separate chunks and a manifest.json
+describes the emitted files:
+
+  deno bundle --out-dir=dist main.ts worker.ts
+
+Imports that should stay unbundled can be listed with --external, either
+as they are written in the source or as a resolved URL prefix ending with '/':
+
+  deno bundle --external=https://esm.sh/ main.ts main.bundle.js",
     )
 }
 
@@ -735,15 +968,15 @@ Future runs of this module will trigger no downloads or compilation unless \
 fn check_subcommand<'a>() -> Command<'a> {
   compile_args_without_no_check(Command::new("check"))
   .arg(
-    Arg::new("remote")
-      .long("remote")
+    Arg::new("all")
+      .long("all")
+      .alias("remote")
       .help("Type-check all modules, including remote")
     )
     .arg(
       Arg::new("file")
         .takes_value(true)
-        .required(true)
-        .min_values(1)
+        .multiple_values(true)
         .value_hint(ValueHint::FilePath),
     )
     .about("Type-check the dependencies")
@@ -752,6 +985,15 @@ fn check_subcommand<'a>() -> Command<'a> {
 
   deno check https://deno.land/std/http/file_server.ts
 
+Only local modules are type-checked by default, use --all to include remote
+modules as well.
+
+Without files, all modules of the project are type-checked. They are
+discovered from \"files.include\" and \"files.exclude\" in the configuration
+file:
+
+  deno check --config deno.json
+
 Unless --reload is specified, this command will not re-download already cached dependencies.",
     )
 }
@@ -780,6 +1022,67 @@ fn compile_subcommand<'a>() -> Command<'a> {
           "aarch64-apple-darwin",
         ]),
     )
+    .arg(
+      Arg::new("include")
+        .long("include")
+        .help("Embed files matching the path or glob into the executable")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("PATH")
+        .multiple_occurrences(true)
+        .value_hint(ValueHint::AnyPath),
+    )
+    .arg(
+      Arg::new("icon")
+        .long("icon")
+        .help("Icon of the executable, as an .ico file (Windows only)")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("FILE")
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(
+      Arg::new("product-name")
+        .long("product-name")
+        .help("Product name in the version info (Windows only)")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("NAME"),
+    )
+    .arg(
+      Arg::new("product-version")
+        .long("product-version")
+        .help("Version in the version info, e.g. 1.2.3 (Windows only)")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("VERSION"),
+    )
+    .arg(
+      Arg::new("description")
+        .long("description")
+        .help("File description in the version info (Windows only)")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("TEXT"),
+    )
+    .arg(
+      Arg::new("copyright")
+        .long("copyright")
+        .help("Copyright notice in the version info (Windows only)")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("TEXT"),
+    )
+    .arg(
+      Arg::new("compress")
+        .long("compress")
+        .help("Compress the modules embedded into the executable"),
+    )
+    .arg(
+      Arg::new("size-report")
+        .long("size-report")
+        .help("Print the sizes of the largest modules embedded"),
+    )
     .about("UNSTABLE: Compile the script into a self contained executable")
     .long_about(
       "UNSTABLE: Compiles the given script into a self contained executable.
@@ -801,6 +1104,28 @@ Cross-compiling to different target architectures is supported using the \
 `--target` flag. On the first invocation with deno will download proper \
 binary and cache it in $DENO_DIR. The aarch64-apple-darwin target is not \
 supported in canary.
+
+Static files, such as templates, can be embedded into the executable with \
+`--include`, which accepts a file, directory or glob and can be repeated. The \
+program reads them from their original paths with 'Deno.readFile', \
+'Deno.readTextFile' or 'fetch' of a 'file:' URL, subject to the '--allow-read' \
+permission:
+
+  deno compile --allow-read --include=assets/** server.ts
+
+Executables for Windows targets can be given an icon and version info, which \
+are shown by the file explorer. This requires a build of deno with the \
+\"windows_metadata\" feature. These flags are ignored for other targets:
+
+  deno compile --target=x86_64-pc-windows-msvc --icon=app.ico \\
+    --product-name=\"My App\" --product-version=1.2.3 main.ts
+
+The embedded modules can be compressed with `--compress`, which makes the \
+executable smaller at the cost of decompressing all of them into memory every \
+time it starts. Use `--size-report` to find out which modules contribute most \
+to the size:
+
+  deno compile --compress --size-report main.ts
 ",
     )
 }
@@ -854,6 +1179,15 @@ Write a report using the lcov format:
 
   deno coverage --lcov --output=cov.lcov cov_profile/
 
+Merge the profiles of several runs, e.g. of sharded or separate unit and \
+integration test runs, into a single profile directory:
+
+  deno coverage --merge cov_unit/ cov_integration/ -o cov_profile/
+
+Write a static HTML report to a directory:
+
+  deno coverage --html=html_cov cov_profile/
+
 Generate html reports from lcov:
 
   genhtml -o html_cov cov.lcov
@@ -876,7 +1210,7 @@ Generate html reports from lcov:
         .multiple_values(true)
         .multiple_occurrences(true)
         .require_equals(true)
-        .default_value(r"^file:")
+        .default_value(COVERAGE_DEFAULT_INCLUDE)
         .help("Include source files in the report"),
     )
     .arg(
@@ -887,7 +1221,7 @@ Generate html reports from lcov:
         .multiple_values(true)
         .multiple_occurrences(true)
         .require_equals(true)
-        .default_value(r"test\.(js|mjs|ts|jsx|tsx)$")
+        .default_value(COVERAGE_DEFAULT_EXCLUDE)
         .help("Exclude source files from the report"),
     )
     .arg(
@@ -896,20 +1230,43 @@ Generate html reports from lcov:
         .help("Output coverage report in lcov format")
         .takes_value(false),
     )
+    .arg(
+      Arg::new("merge")
+        .long("merge")
+        .requires("output")
+        .conflicts_with_all(&["html", "coverage-fail-under"])
+        .help("Merge coverage profiles into the --output directory")
+        .takes_value(false),
+    )
+    .group(ArgGroup::new("output-format").args(&["lcov", "merge"]))
     .arg(
       Arg::new("output")
-        .requires("lcov")
+        .requires("output-format")
+        .short('o')
         .long("output")
-        .help("Output file (defaults to stdout) for lcov")
+        .help(
+          "Output file (defaults to stdout) for lcov, or directory for --merge",
+        )
         .long_help(
           "Exports the coverage report in lcov format to the given file. \
-    Filename should be passed along with '=' For example '--output=foo.lcov' \
-    If no --output arg is specified then the report is written to stdout.",
+    For example '--output=foo.lcov'. If no --output arg is specified then the \
+    report is written to stdout. With --merge, the merged coverage profiles \
+    are written to the given directory, which must be empty or not exist.",
         )
         .takes_value(true)
+        .value_hint(ValueHint::AnyPath),
+    )
+    .arg(
+      Arg::new("html")
+        .long("html")
+        .takes_value(true)
         .require_equals(true)
-        .value_hint(ValueHint::FilePath),
+        .value_name("DIR")
+        .conflicts_with("lcov")
+        .help("Write an HTML coverage report to the given directory")
+        .value_hint(ValueHint::DirPath),
     )
+    .arg(coverage_fail_under_arg())
     .arg(
       Arg::new("files")
         .takes_value(true)
@@ -938,6 +1295,19 @@ Output documentation in JSON format:
 
     deno doc --json ./path/to/module.ts
 
+Generate a static HTML documentation site, written to \"docs/\" unless an \
+output directory is given:
+
+    deno doc --html ./path/to/module.ts
+    deno doc --html --output=site/ ./path/to/module.ts
+
+Report exported symbols missing JSDoc, @param or @returns tags, or with \
+broken {@link} references, failing when less than the given percentage of \
+symbols is completely documented (100 by default):
+
+    deno doc --lint ./path/to/module.ts
+    deno doc --lint --lint-threshold=80 ./path/to/module.ts
+
 Target a specific symbol:
 
     deno doc ./path/to/module.ts MyClass.someField
@@ -955,6 +1325,42 @@ Show documentation for runtime built-ins:
         .help("Output documentation in JSON format")
         .takes_value(false),
     )
+    .arg(
+      Arg::new("html")
+        .long("html")
+        .help("Output documentation as a static HTML site")
+        .conflicts_with("json")
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("lint")
+        .long("lint")
+        .help("Report missing or incomplete documentation")
+        .conflicts_with_all(&["json", "html"])
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("lint-threshold")
+        .long("lint-threshold")
+        .help("Minimum percentage of completely documented symbols")
+        .requires("lint")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("PERCENT")
+        .validator(|val: &str| match val.parse::<f64>() {
+          Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(()),
+          _ => Err("lint-threshold should be a percentage between 0 and 100"),
+        }),
+    )
+    .arg(
+      Arg::new("output")
+        .long("output")
+        .help("Directory to write the HTML documentation to")
+        .requires("html")
+        .takes_value(true)
+        .require_equals(true)
+        .value_hint(ValueHint::DirPath),
+    )
     .arg(
       Arg::new("private")
         .long("private")
@@ -976,7 +1382,7 @@ Show documentation for runtime built-ins:
         .help("Dot separated path to symbol")
         .takes_value(true)
         .required(false)
-        .conflicts_with("json"),
+        .conflicts_with_all(&["json", "html", "lint"]),
     )
 }
 
@@ -992,6 +1398,14 @@ To evaluate as TypeScript:
 
   deno eval --ext=ts \"const v: string = 'hello'; console.log(v)\"
 
+To type check the code before evaluating it:
+
+  deno eval --check --ext=ts \"const v: string = 'hello'; console.log(v)\"
+
+To resolve relative imports against another directory or URL:
+
+  deno eval --cwd-url=./lib \"import { add } from './math.ts'; add(1, 2)\"
+
 This command has implicit access to all permissions (--allow-all).",
     )
     .arg(
@@ -1013,6 +1427,15 @@ This command has implicit access to all permissions (--allow-all).",
         .default_value("js")
         .possible_values(&["ts", "tsx", "js", "jsx"]),
     )
+    .arg(check_arg())
+    .arg(
+      Arg::new("cwd-url")
+        .long("cwd-url")
+        .help("Resolve relative imports against this directory or URL")
+        .takes_value(true)
+        .value_name("DIR_OR_URL")
+        .value_hint(ValueHint::AnyPath),
+    )
     .arg(
       Arg::new("print")
         .long("print")
@@ -1036,16 +1459,28 @@ fn fmt_subcommand<'a>() -> Command<'a> {
   Command::new("fmt")
     .about("Format source files")
     .long_about(
-      "Auto-format JavaScript, TypeScript, Markdown, and JSON files.
+      "Auto-format JavaScript, TypeScript, Markdown, JSON, CSS, YAML, and SQL \
+files.
 
   deno fmt
   deno fmt myfile1.ts myfile2.ts
   deno fmt --check
 
+Print the changes of files that are not formatted as a unified diff, or as \
+JSON:
+
+  deno fmt --check --diff=unified
+  deno fmt --check --json
+
 Format stdin and write to stdout:
 
   cat file.ts | deno fmt -
 
+CSS, YAML, and SQL files use the options of the configuration file, which can \
+be overridden for each of them under \"fmt.options.css\", \"fmt.options.yaml\" \
+and \"fmt.options.sql\". Formatting them requires a build of deno with the \
+\"extra_formatters\" feature.
+
 Ignore formatting code by preceding it with an ignore comment:
 
   // deno-fmt-ignore
@@ -1061,13 +1496,36 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
         .help("Check if the source files are formatted")
         .takes_value(false),
     )
+    .arg(
+      Arg::new("diff")
+        .long("diff")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("FORMAT")
+        .possible_values(&["unified"])
+        .requires("check")
+        .help(
+          "Print the changes needed to format the files in the given format",
+        ),
+    )
+    .arg(incremental_cache_force_arg())
+    .arg(
+      Arg::new("json")
+        .long("json")
+        .requires("check")
+        .conflicts_with("diff")
+        .help("Output the result of the check in JSON format"),
+    )
     .arg(
       Arg::new("ext")
         .long("ext")
         .help("Set standard input (stdin) content type")
         .takes_value(true)
         .default_value("ts")
-        .possible_values(&["ts", "tsx", "js", "jsx", "md", "json", "jsonc"]),
+        .possible_values(&[
+          "ts", "tsx", "js", "jsx", "md", "json", "jsonc", "css", "yml",
+          "yaml", "sql",
+        ]),
     )
     .arg(
       Arg::new("ignore")
@@ -1088,6 +1546,8 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
     )
     .arg(watch_arg(false))
     .arg(no_clear_screen_arg())
+    .arg(watch_debounce_arg())
+    .arg(watch_poll_interval_arg())
     .arg(
       Arg::new("options-use-tabs")
         .long("options-use-tabs")
@@ -1122,6 +1582,11 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
         .long("options-single-quote")
         .help("Use single quotes. Defaults to false."),
     )
+    .arg(
+      Arg::new("options-no-semicolons")
+        .long("options-no-semicolons")
+        .help("Don't use semicolons except where necessary."),
+    )
     .arg(
       Arg::new("options-prose-wrap")
         .long("options-prose-wrap")
@@ -1148,6 +1613,13 @@ type: JavaScript, TypeScript, or JSON.
 emit: Local path of compiled source code. (TypeScript only.)
 dependencies: Dependency tree of the source file.
 
+Show the shortest import chain from the module to one of its dependencies:
+
+  deno info --why https://deno.land/std/fmt/colors.ts main.ts
+
+With --json, each module also reports its \"transitiveSize\": the size of the
+module together with all of the modules it depends on.
+
 Without any additional arguments, 'deno info' shows:
 
 DENO_DIR: Directory containing Deno-managed files.
@@ -1171,6 +1643,79 @@ TypeScript compiler cache: Subdirectory containing TS compiler output.",
         .help("UNSTABLE: Outputs the information in JSON format")
         .takes_value(false),
     )
+    .arg(
+      Arg::new("why")
+        .long("why")
+        .help("Show the shortest import chain to this dependency")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("SPECIFIER")
+        .requires("file"),
+    )
+}
+
+fn init_subcommand<'a>() -> Command<'a> {
+  Command::new("init")
+    .arg(
+      Arg::new("template")
+        .help("Built-in template to generate the project from")
+        .possible_values(&["cli", "server", "library"])
+        .conflicts_with("from"),
+    )
+    .arg(
+      Arg::new("from")
+        .long("from")
+        .help("Generate the project from the template manifest at a URL")
+        .takes_value(true)
+        .value_name("URL")
+        .value_hint(ValueHint::Url),
+    )
+    .arg(
+      Arg::new("dir")
+        .long("dir")
+        .help("Directory of the project (defaults to the current directory)")
+        .takes_value(true)
+        .value_hint(ValueHint::DirPath),
+    )
+    .arg(
+      Arg::new("vscode")
+        .long("vscode")
+        .help("Generate .vscode/settings.json enabling Deno in VS Code"),
+    )
+    .arg(
+      Arg::new("import-map")
+        .long("import-map")
+        .help("Generate an import map and use it for the dependencies"),
+    )
+    .arg(
+      Arg::new("force")
+        .long("force")
+        .short('f')
+        .help("Overwrite files that already exist"),
+    )
+    .about("Initialize a new project")
+    .long_about(
+      "Initialize a new project from a template.
+
+Generates a deno.json with tasks, a main module and its tests:
+
+  deno init
+
+The built-in templates are cli (the default), server and library:
+
+  deno init server --dir my_server
+
+Optionally generate VS Code settings and an import map:
+
+  deno init --vscode --import-map
+
+Community templates are JSON manifests mapping file paths to their contents,
+of the form {\"files\": {\"main.ts\": \"...\"}}:
+
+  deno init --from https://example.com/template.json
+
+Existing files are not overwritten unless --force is passed.",
+    )
 }
 
 fn install_subcommand<'a>() -> Command<'a> {
@@ -1234,9 +1779,15 @@ fn uninstall_subcommand<'a>() -> Command<'a> {
     .trailing_var_arg(true)
     .arg(
       Arg::new("name")
-        .required(true)
+        .required_unless_present("list")
         .multiple_occurrences(false)
         .allow_hyphen_values(true))
+    .arg(
+      Arg::new("list")
+        .long("list")
+        .help("List the installed scripts and whether updates are available")
+        .conflicts_with("name")
+        .takes_value(false))
     .arg(
       Arg::new("root")
         .long("root")
@@ -1254,6 +1805,11 @@ To change the installation root, use --root:
 
   deno uninstall --root /usr/local serve
 
+To list the scripts installed with deno install, along with whether newer
+versions of their modules are available, use --list:
+
+  deno uninstall --list
+
 The installation root is determined, in order of precedence:
   - --root option
   - DENO_INSTALL_ROOT environment variable
@@ -1286,6 +1842,10 @@ Print result as JSON:
 
   deno lint --json
 
+Print result as SARIF 2.1.0, for uploading to code scanning services:
+
+  deno lint --format=sarif
+
 Read from stdin:
 
   cat file.ts | deno lint -
@@ -1295,6 +1855,19 @@ List available rules:
 
   deno lint --rules
 
+Fix problems that have a safe automatic fix, or print the changes as a diff \
+without writing them:
+
+  deno lint --fix
+  deno lint --fix --dry-run
+
+Load additional rules from the plugin modules listed in the \"lint.plugins\" \
+field of the configuration file. A plugin default exports an object with a \
+\"name\" and a map of \"rules\"; each rule is called with the file name, \
+source text and AST of every linted file and reports problems with \
+\"context.report({ span, message, hint })\". Rules compiled to WebAssembly \
+can be used by instantiating them from such a module.
+
 Ignore diagnostics on the next line by preceding it with an ignore comment and
 rule name:
 
@@ -1352,6 +1925,31 @@ Ignore linting a file by adding an ignore comment at the top of the file:
         .help("Output lint result in JSON format")
         .takes_value(false),
     )
+    .arg(
+      Arg::new("format")
+        .long("format")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("FORMAT")
+        .possible_values(&["pretty", "json", "sarif"])
+        .conflicts_with("json")
+        .help("Output lint result in the given format"),
+    )
+    .arg(
+      Arg::new("fix")
+        .long("fix")
+        .conflicts_with("rules")
+        .help("Fix any linting errors for rules that support it")
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("dry-run")
+        .long("dry-run")
+        .requires("fix")
+        .help("Print the fixes as a diff instead of writing them")
+        .takes_value(false),
+    )
+    .arg(incremental_cache_force_arg())
     .arg(
       Arg::new("files")
         .takes_value(true)
@@ -1362,6 +1960,39 @@ Ignore linting a file by adding an ignore comment at the top of the file:
     )
     .arg(watch_arg(false))
     .arg(no_clear_screen_arg())
+    .arg(watch_debounce_arg())
+    .arg(watch_poll_interval_arg())
+}
+
+fn outdated_subcommand<'a>() -> Command<'a> {
+  compile_args_without_no_check(Command::new("outdated"))
+    .arg(
+      Arg::new("update")
+        .long("update")
+        .help("Update the import map and lockfile to the latest versions"),
+    )
+    .arg(
+      Arg::new("files")
+        .takes_value(true)
+        .multiple_values(true)
+        .value_hint(ValueHint::FilePath),
+    )
+    .about("Check for newer versions of dependencies")
+    .long_about(
+      "Check for newer versions of the versioned remote modules used by the
+given modules and the import map.
+
+  deno outdated main.ts
+
+Modules of deno.land/x, deno.land/std and npm packages served by esm.sh,
+cdn.skypack.dev, unpkg.com and cdn.jsdelivr.net are supported. For semver
+ranges, like https://esm.sh/react@^17.0.0, the newest version in the range is
+shown as the wanted version.
+
+Update the import map to the latest versions and rewrite the lockfile:
+
+  deno outdated --update --lock=lock.json main.ts",
+    )
 }
 
 fn repl_subcommand<'a>() -> Command<'a> {
@@ -1395,6 +2026,9 @@ fn run_subcommand<'a>() -> Command<'a> {
         .conflicts_with("inspect-brk"),
     )
     .arg(no_clear_screen_arg())
+    .arg(watch_debounce_arg())
+    .arg(watch_poll_interval_arg())
+    .arg(hot_arg())
     .arg(check_arg())
     .trailing_var_arg(true)
     .arg(script_arg().required(true))
@@ -1429,6 +2063,11 @@ fn task_subcommand<'a>() -> Command<'a> {
   Command::new("task")
     .trailing_var_arg(true)
     .arg(config_arg())
+    .arg(
+      Arg::new("parallel")
+        .long("parallel")
+        .help("Run independent dependencies of the task concurrently"),
+    )
     .arg(Arg::new("task").help("Task to be executed"))
     .arg(
       Arg::new("task_args")
@@ -1441,7 +2080,16 @@ fn task_subcommand<'a>() -> Command<'a> {
     .long_about(
       "Run a task defined in the configuration file
 
-  deno task build",
+  deno task build
+
+Tasks listed in a task's \"dependsOn\" are run before it, in dependency
+order. Independent tasks can be run concurrently:
+
+  deno task --parallel build
+
+Arguments after the task name, or after \"--\", are forwarded to the task:
+
+  deno task build -- --release",
     )
 }
 
@@ -1469,6 +2117,132 @@ fn test_subcommand<'a>() -> Command<'a> {
         .help("Enable tracing of async ops. Useful when debugging leaking ops in test, but impacts test execution time.")
         .takes_value(false),
     )
+    .arg(
+      Arg::new("profile")
+        .long("profile")
+        .value_name("DIR")
+        .help("UNSTABLE: Write a CPU profile of each test module into DIR (defaults to the current directory)")
+        .min_values(0)
+        .max_values(1)
+        .require_equals(true)
+        .takes_value(true)
+        .value_hint(ValueHint::DirPath),
+    )
+    .arg(
+      Arg::new("reporter")
+        .long("reporter")
+        .value_name("REPORTER")
+        .help("Select the reporter to use: \"pretty\" (default), \"tap\" for TAP version 13, \"json\" for newline-delimited JSON events, \"junit[:PATH]\" to write JUnit XML to PATH or stdout, or the path or URL of a module exporting functions that receive the JSON events, e.g. \"./reporter.ts\"")
+        .require_equals(true)
+        .takes_value(true)
+        .validator(|val: &str| match parse_test_reporter(val) {
+          Ok(_) => Ok(()),
+          Err(err) => Err(err),
+        }),
+    )
+    .arg(
+      Arg::new("shard")
+        .long("shard")
+        .value_name("INDEX/COUNT")
+        .help("Only run the test modules in shard INDEX out of COUNT, e.g. --shard=2/5. Modules are split between shards by a stable hash of their path relative to the current directory.")
+        .require_equals(true)
+        .takes_value(true)
+        .validator(|val: &str| match parse_test_shard(val) {
+          Ok(_) => Ok(()),
+          Err(err) => Err(err),
+        }),
+    )
+    .arg(
+      Arg::new("timeout")
+        .long("timeout")
+        .value_name("MS")
+        .help("Fail tests that take longer than MS milliseconds, unless they set their own timeout")
+        .require_equals(true)
+        .takes_value(true)
+        .validator(|val: &str| match val.parse::<NonZeroU64>() {
+          Ok(_) => Ok(()),
+          Err(_) => Err("timeout should be a non zero integer".to_string()),
+        }),
+    )
+    .arg(
+      Arg::new("retries")
+        .long("retries")
+        .value_name("N")
+        .help("Re-run failed tests up to N times, unless they set their own retries. Tests that pass on a retry are reported as flaky.")
+        .require_equals(true)
+        .takes_value(true)
+        .validator(|val: &str| match val.parse::<usize>() {
+          Ok(_) => Ok(()),
+          Err(_) => Err("retries should be an unsigned integer".to_string()),
+        }),
+    )
+    .arg(
+      Arg::new("setup")
+        .long("setup")
+        .value_name("FILE")
+        .help("Module whose default export runs once before all tests; a function it returns runs after them. Overrides \"test.setup\" in the config file.")
+        .takes_value(true)
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(
+      Arg::new("update-snapshots")
+        .long("update-snapshots")
+        .help("Create or update the snapshots asserted with `t.assertSnapshot()` instead of comparing against them")
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("reuse-workers")
+        .long("reuse-workers")
+        .help("UNSTABLE: Run many test modules in each worker instead of starting a new worker per module. Much faster for lots of small modules, but modules share globals and module instances.")
+        .conflicts_with("coverage")
+        .conflicts_with("profile")
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("capture-output")
+        .long("capture-output")
+        .help("Buffer the output of each test and only print it if the test fails, instead of interleaving the output of concurrently running modules")
+        .conflicts_with("reuse-workers")
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("verbose-output")
+        .long("verbose-output")
+        .help("Print the captured output of passing tests too")
+        .requires("capture-output")
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("list")
+        .long("list")
+        .value_name("FORMAT")
+        .help("List the tests declared by the test modules without type checking or running them: \"pretty\" (default) or \"json\"")
+        .long_help("List the tests declared by the test modules without type checking or running them, as \"pretty\" (default) text or \"json\". Tests are found by looking for `Deno.test` calls, so tests with names that are computed at runtime are left out.")
+        .min_values(0)
+        .max_values(1)
+        .require_equals(true)
+        .takes_value(true)
+        .possible_values(&["pretty", "json"])
+        .conflicts_with_all(&["no-run", "watch"]),
+    )
+    .arg(
+      Arg::new("slow-threshold")
+        .long("slow-threshold")
+        .value_name("MS")
+        .help("Mark tests and steps taking longer than MS milliseconds as slow, and list the slowest of them after the run")
+        .require_equals(true)
+        .takes_value(true)
+        .validator(|val: &str| match val.parse::<u64>() {
+          Ok(_) => Ok(()),
+          Err(_) => Err("slow-threshold should be a number".to_string()),
+        }),
+    )
+    .arg(
+      Arg::new("no-diff")
+        .long("no-diff")
+        .help("Show failed assertions as plain errors instead of a diff of the expected and actual values")
+        .takes_value(false),
+    )
     .arg(
       Arg::new("doc")
         .long("doc")
@@ -1501,13 +2275,33 @@ fn test_subcommand<'a>() -> Command<'a> {
         .allow_hyphen_values(true)
         .long("filter")
         .takes_value(true)
-        .help("Run tests with this string or pattern in the test name"),
+        .help("Run tests with this string in the test name, or matching a /regular expression/ with optional flags"),
+    )
+    .arg(
+      Arg::new("filter-tag")
+        .long("filter-tag")
+        .takes_value(true)
+        .value_name("TAG")
+        .multiple_occurrences(true)
+        .use_value_delimiter(true)
+        .require_equals(true)
+        .help("Run only tests with one of the given tags"),
+    )
+    .arg(
+      Arg::new("skip-tag")
+        .long("skip-tag")
+        .takes_value(true)
+        .value_name("TAG")
+        .multiple_occurrences(true)
+        .use_value_delimiter(true)
+        .require_equals(true)
+        .help("Skip tests with any of the given tags"),
     )
     .arg(
       Arg::new("shuffle")
         .long("shuffle")
         .value_name("NUMBER")
-        .help("(UNSTABLE): Shuffle the order in which the tests are run")
+        .help("(UNSTABLE): Shuffle the order in which the tests are run. The seed used is printed in the summary so the order can be reproduced.")
         .min_values(0)
         .max_values(1)
         .require_equals(true)
@@ -1527,6 +2321,7 @@ fn test_subcommand<'a>() -> Command<'a> {
         .conflicts_with("inspect-brk")
         .help("UNSTABLE: Collect coverage profile data into DIR"),
     )
+    .arg(coverage_fail_under_arg().requires("coverage"))
     .arg(
       Arg::new("jobs")
         .short('j')
@@ -1542,7 +2337,7 @@ fn test_subcommand<'a>() -> Command<'a> {
     )
     .arg(
       Arg::new("files")
-        .help("List of file names to run")
+        .help("List of file names or globs to run")
         .takes_value(true)
         .multiple_values(true)
         .multiple_occurrences(true)
@@ -1550,10 +2345,24 @@ fn test_subcommand<'a>() -> Command<'a> {
     )
     .arg(
       watch_arg(false)
+        .value_name("MODE")
+        .min_values(0)
+        .max_values(1)
+        .require_equals(true)
+        .takes_value(true)
+        .possible_values(&["full"])
+        .long_help(
+          "UNSTABLE: Watch for file changes and restart process automatically. \
+          Only local files from entry point module graph are watched. \
+          By default only the test modules affected by a change are re-run; \
+          pass --watch=full to re-run all of them.",
+        )
         .conflicts_with("no-run")
         .conflicts_with("coverage"),
     )
     .arg(no_clear_screen_arg())
+    .arg(watch_debounce_arg())
+    .arg(watch_poll_interval_arg())
     .arg(script_arg().last(true))
     .about("Run tests")
     .long_about(
@@ -1597,7 +2406,18 @@ and is used to replace the current executable.
 If you want to not replace the current Deno executable but instead download an
 update to a different location, use the --output flag
 
-  deno upgrade --output $HOME/my_deno",
+  deno upgrade --output $HOME/my_deno
+
+Canary builds and release candidates are installed by selecting their channel
+
+  deno upgrade --channel=rc
+
+The downloaded archive is verified against its published signature before the
+executable is replaced, and the upgrade is aborted if no signature was
+published. To see what would be installed without replacing anything, use the
+--dry-run flag
+
+  deno upgrade --dry-run",
     )
     .arg(
       Arg::new("version")
@@ -1615,7 +2435,7 @@ update to a different location, use the --output flag
     .arg(
       Arg::new("dry-run")
         .long("dry-run")
-        .help("Perform all checks without replacing old exe"),
+        .help("Perform all checks and print what would be installed without replacing the old exe"),
     )
     .arg(
       Arg::new("force")
@@ -1623,10 +2443,25 @@ update to a different location, use the --output flag
         .short('f')
         .help("Replace current exe even if not out-of-date"),
     )
+    .arg(
+      Arg::new("no-verify")
+        .long("no-verify")
+        .help("Install the downloaded archive without verifying its signature"),
+    )
+    .arg(
+      Arg::new("channel")
+        .long("channel")
+        .help("The release channel to upgrade from")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("CHANNEL")
+        .possible_values(&["stable", "canary", "rc"])
+        .conflicts_with("canary"),
+    )
     .arg(
       Arg::new("canary")
         .long("canary")
-        .help("Upgrade to canary builds"),
+        .help("Upgrade to canary builds, same as --channel=canary"),
     )
     .arg(ca_file_arg())
 }
@@ -1646,7 +2481,18 @@ maps remote specifiers to the downloaded files.
 
 Remote modules and multiple modules may also be specified:
 
-  deno vendor main.ts test.deps.ts https://deno.land/std/path/mod.ts",
+  deno vendor main.ts test.deps.ts https://deno.land/std/path/mod.ts
+
+Check whether an existing output directory is up to date with the module
+graph, without modifying it. Exits with a non-zero code if it is not:
+
+  deno vendor --check main.ts
+
+Update an existing output directory in place, only writing the files that
+changed and removing the modules that are no longer used. Only files listed in
+the directory's manifest.json by a previous vendor are ever removed:
+
+  deno vendor --update main.ts",
     )
     .arg(
       Arg::new("specifiers")
@@ -1671,6 +2517,20 @@ Remote modules and multiple modules may also be specified:
         )
         .takes_value(false),
     )
+    .arg(
+      Arg::new("check")
+        .long("check")
+        .help("Report changes to the output directory without modifying it")
+        .conflicts_with_all(&["force", "update"])
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("update")
+        .long("update")
+        .help("Only write the files that changed in the output directory")
+        .conflicts_with("force")
+        .takes_value(false),
+    )
     .arg(config_arg())
     .arg(import_map_arg())
     .arg(lock_arg())
@@ -1711,6 +2571,12 @@ fn permission_args(app: Command) -> Command {
         .use_value_delimiter(true)
         .require_equals(true)
         .help("Allow file system read access")
+        .long_help(
+          "Allow file system read access. Optionally restricted to a list of \
+          paths, each granting the files within it, or of glob patterns, \
+          e.g. --allow-read=/etc,./data/**/*.csv. A pattern also grants the \
+          path spelled the same, unless it is prefixed with 'glob:'",
+        )
         .value_hint(ValueHint::AnyPath),
     )
     .arg(
@@ -1721,6 +2587,12 @@ fn permission_args(app: Command) -> Command {
         .use_value_delimiter(true)
         .require_equals(true)
         .help("Allow file system write access")
+        .long_help(
+          "Allow file system write access. Optionally restricted to a list of \
+          paths, each granting the files within it, or of glob patterns, \
+          e.g. --allow-write=/tmp,./out/*.json. A pattern also grants the \
+          path spelled the same, unless it is prefixed with 'glob:'",
+        )
         .value_hint(ValueHint::AnyPath),
     )
     .arg(
@@ -1731,6 +2603,12 @@ fn permission_args(app: Command) -> Command {
         .use_value_delimiter(true)
         .require_equals(true)
         .help("Allow network access")
+        .long_help(
+          "Allow network access. Optionally restricted to a list of hosts, \
+          which may include a port, a wildcard subdomain or an IP network \
+          in CIDR notation, e.g. \
+          --allow-net=deno.land:443,*.example.com,10.0.0.0/8,:8000",
+        )
         .validator(crate::flags_allow_net::validator),
     )
     .arg(unsafely_ignore_certificate_errors_arg())
@@ -1742,11 +2620,21 @@ fn permission_args(app: Command) -> Command {
         .use_value_delimiter(true)
         .require_equals(true)
         .help("Allow environment access")
+        .long_help(
+          "Allow environment access. A trailing \"*\" allows all the \
+          variables with that prefix, e.g. --allow-env=AWS_*,DENO_*",
+        )
         .validator(|keys| {
           for key in keys.split(',') {
             if key.is_empty() || key.contains(&['=', '\0'] as &[char]) {
               return Err(format!("invalid key \"{}\"", key));
             }
+            if key.trim_end_matches('*').contains('*') {
+              return Err(format!(
+                "invalid key \"{}\", only a trailing \"*\" is supported",
+                key
+              ));
+            }
           }
           Ok(())
         }),
@@ -1789,6 +2677,32 @@ fn permission_args(app: Command) -> Command {
         .long("no-prompt")
         .help("Always throw if required permission wasn't passed"),
     )
+    .arg(
+      Arg::new("permission-set")
+        .long("permission-set")
+        .value_name("FILE")
+        .takes_value(true)
+        .require_equals(true)
+        .help("Grant the permissions defined in a JSON file")
+        .long_help(
+          "Grant the permissions defined in a JSON file in addition to the \
+          ones of the flags, e.g. {\"read\": [\"./data\"], \"net\": true, \
+          \"run\": [{\"name\": \"git\", \"args\": [[\"status\"]]}]}. \
+          Permissions can also be defined in the \"permissions\" section of \
+          the config file, which are only granted when it is passed with \
+          --config.",
+        )
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(
+      Arg::new("permission-audit")
+        .long("permission-audit")
+        .value_name("FILE")
+        .takes_value(true)
+        .require_equals(true)
+        .help("Write every permission check as NDJSON to the given file")
+        .value_hint(ValueHint::FilePath),
+    )
 }
 
 fn runtime_args(
@@ -1814,6 +2728,8 @@ fn runtime_args(
     .arg(seed_arg())
     .arg(enable_testing_features_arg())
     .arg(compat_arg())
+    .arg(dump_op_metrics_arg())
+    .arg(max_heap_size_arg())
 }
 
 fn inspect_args(app: Command) -> Command {
@@ -1822,7 +2738,7 @@ fn inspect_args(app: Command) -> Command {
       Arg::new("inspect")
         .long("inspect")
         .value_name("HOST:PORT")
-        .help("Activate inspector on host:port (default: 127.0.0.1:9229)")
+        .help("Activate inspector on host:port or unix:PATH (default: 127.0.0.1:9229)")
         .min_values(0)
         .max_values(1)
         .require_equals(true)
@@ -1842,6 +2758,21 @@ fn inspect_args(app: Command) -> Command {
         .takes_value(true)
         .validator(inspect_arg_validate),
     )
+    .arg(
+      Arg::new("inspect-wait")
+        .long("inspect-wait")
+        .value_name("HOST:PORT")
+        .help(
+          "Activate inspector on host:port and wait for a debugger to attach and resume before running user code",
+        )
+        .min_values(0)
+        .max_values(1)
+        .require_equals(true)
+        .takes_value(true)
+        .conflicts_with("inspect")
+        .conflicts_with("inspect-brk")
+        .validator(inspect_arg_validate),
+    )
 }
 
 fn import_map_arg<'a>() -> Arg<'a> {
@@ -1875,13 +2806,24 @@ fn reload_arg<'a>() -> Arg<'a> {
 --reload
   Reload everything
 --reload=https://deno.land/std
-  Reload only standard modules
+  Reload only standard modules, of any version
+--reload=https://deno.land/std@0.130.0/,https://esm.sh/react
+  Reload all modules below a prefix and all versions of a package
+--reload=https://esm.sh
+  Reload all modules of an origin
 --reload=https://deno.land/std/fs/utils.ts,https://deno.land/std/fmt/colors.ts
   Reloads specific modules",
     )
     .value_hint(ValueHint::FilePath)
 }
 
+fn incremental_cache_force_arg<'a>() -> Arg<'a> {
+  Arg::new("force")
+    .long("force")
+    .help("Check all files, even those unchanged since the last run")
+    .takes_value(false)
+}
+
 fn ca_file_arg<'a>() -> Arg<'a> {
   Arg::new("cert")
     .long("cert")
@@ -1948,6 +2890,32 @@ fn seed_arg<'a>() -> Arg<'a> {
     })
 }
 
+fn dump_op_metrics_arg<'a>() -> Arg<'a> {
+  Arg::new("dump-op-metrics")
+    .long("dump-op-metrics")
+    .value_name("FILE")
+    .takes_value(true)
+    .require_equals(true)
+    .help("Write op dispatch metrics as JSON to the given file on exit")
+    .value_hint(ValueHint::FilePath)
+}
+
+fn max_heap_size_arg<'a>() -> Arg<'a> {
+  Arg::new("max-heap-size")
+    .long("max-heap-size")
+    .value_name("MEGABYTES")
+    .takes_value(true)
+    .require_equals(true)
+    .help("Terminate execution when the V8 heap approaches this size")
+    .validator(|val| match val.parse::<usize>() {
+      Ok(size) if size > 0 => match size.checked_mul(1024 * 1024) {
+        Some(_) => Ok(()),
+        None => Err("Max heap size is too large".to_string()),
+      },
+      _ => Err("Max heap size should be a positive number".to_string()),
+    })
+}
+
 fn compat_arg<'a>() -> Arg<'a> {
   Arg::new("compat")
     .long("compat")
@@ -1989,6 +2957,51 @@ fn no_clear_screen_arg<'a>() -> Arg<'a> {
     .help("Do not clear terminal screen when under watch mode")
 }
 
+fn watch_debounce_arg<'a>() -> Arg<'a> {
+  Arg::new("watch-debounce")
+    .requires("watch")
+    .long("watch-debounce")
+    .takes_value(true)
+    .require_equals(true)
+    .value_name("MILLISECONDS")
+    .help("Wait this long for further file changes before restarting")
+    .validator(|val| match val.parse::<u64>() {
+      Ok(_) => Ok(()),
+      Err(_) => Err("Watch debounce should be a number".to_string()),
+    })
+}
+
+fn watch_poll_interval_arg<'a>() -> Arg<'a> {
+  Arg::new("watch-poll-interval")
+    .requires("watch")
+    .long("watch-poll-interval")
+    .takes_value(true)
+    .require_equals(true)
+    .value_name("MILLISECONDS")
+    .help("Poll for file changes at this interval")
+    .long_help(
+      "Poll for file changes at this interval instead of relying on file \
+      system events, which aren't delivered for network file systems and some \
+      Docker volumes.",
+    )
+    .validator(|val| match val.parse::<u64>() {
+      Ok(interval) if interval > 0 => Ok(()),
+      _ => Err("Watch poll interval should be a positive number".to_string()),
+    })
+}
+
+fn hot_arg<'a>() -> Arg<'a> {
+  Arg::new("hot")
+    .requires("watch")
+    .long("hot")
+    .help("UNSTABLE: Reload changed modules in place instead of restarting")
+    .long_help(
+      "UNSTABLE: Reload changed modules in place instead of restarting \
+      the process. Changed modules and the modules importing them are \
+      evaluated again, and an \"hmr\" event is dispatched on globalThis.",
+    )
+}
+
 fn no_check_arg<'a>() -> Arg<'a> {
   Arg::new("no-check")
     .takes_value(true)
@@ -2102,6 +3115,12 @@ fn bench_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   };
 
   let filter = matches.value_of("filter").map(String::from);
+  let json = matches.is_present("json");
+  let baseline = matches.value_of("baseline").map(PathBuf::from);
+  let baseline_threshold = matches
+    .value_of("baseline-threshold")
+    .map(|val| val.parse::<f64>().unwrap())
+    .unwrap_or(10.0);
 
   if matches.is_present("script_arg") {
     let script_arg: Vec<String> = matches
@@ -2131,26 +3150,42 @@ fn bench_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     include,
     ignore,
     filter,
+    json,
+    baseline,
+    baseline_threshold,
   });
 }
 
 fn bundle_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   compile_args_parse(flags, matches);
 
-  let source_file = matches.value_of("source_file").unwrap().to_string();
+  let mut source_files = matches.values_of("source_file").unwrap();
+  let source_file = source_files.next().unwrap().to_string();
+  let out_dir = matches.value_of("out-dir").map(PathBuf::from);
 
-  let out_file = if let Some(out_file) = matches.value_of("out_file") {
-    flags.allow_write = Some(vec![]);
-    Some(PathBuf::from(out_file))
+  // without an output directory the second source file is the output file
+  let out_file = if out_dir.is_none() {
+    source_files.next().map(PathBuf::from)
   } else {
     None
   };
+  if out_file.is_some() || out_dir.is_some() {
+    flags.allow_write = Some(vec![]);
+  }
+  let entries = source_files.map(String::from).collect();
+  let external = matches
+    .values_of("external")
+    .map(|values| values.map(String::from).collect())
+    .unwrap_or_default();
 
   watch_arg_parse(flags, matches, false);
 
   flags.subcommand = DenoSubcommand::Bundle(BundleFlags {
     source_file,
     out_file,
+    entries,
+    out_dir,
+    external,
   });
 }
 
@@ -2166,12 +3201,11 @@ fn cache_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 
 fn check_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   compile_args_without_no_check_parse(flags, matches);
-  let files = matches
-    .values_of("file")
-    .unwrap()
-    .map(String::from)
-    .collect();
-  let remote = matches.is_present("remote");
+  let files = match matches.values_of("file") {
+    Some(f) => f.map(String::from).collect(),
+    None => vec![],
+  };
+  let remote = matches.is_present("all");
   flags.subcommand = DenoSubcommand::Check(CheckFlags { files, remote });
 }
 
@@ -2188,12 +3222,31 @@ fn compile_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   let source_file = script[0].to_string();
   let output = matches.value_of("output").map(PathBuf::from);
   let target = matches.value_of("target").map(String::from);
+  let include = matches
+    .values_of("include")
+    .map(|values| values.map(String::from).collect())
+    .unwrap_or_default();
+  let icon = matches.value_of("icon").map(PathBuf::from);
+  let product_name = matches.value_of("product-name").map(String::from);
+  let product_version = matches.value_of("product-version").map(String::from);
+  let description = matches.value_of("description").map(String::from);
+  let copyright = matches.value_of("copyright").map(String::from);
+  let compress = matches.is_present("compress");
+  let size_report = matches.is_present("size-report");
 
   flags.subcommand = DenoSubcommand::Compile(CompileFlags {
     source_file,
     output,
     args,
     target,
+    include,
+    icon,
+    product_name,
+    product_version,
+    description,
+    copyright,
+    compress,
+    size_report,
   });
 }
 
@@ -2241,7 +3294,10 @@ fn coverage_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     None => vec![],
   };
   let lcov = matches.is_present("lcov");
+  let html = matches.value_of("html").map(PathBuf::from);
+  let merge = matches.is_present("merge");
   let output = matches.value_of("output").map(PathBuf::from);
+  let fail_under = coverage_fail_under_arg_parse(matches);
   flags.subcommand = DenoSubcommand::Coverage(CoverageFlags {
     files,
     output,
@@ -2249,6 +3305,9 @@ fn coverage_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     include,
     exclude,
     lcov,
+    html,
+    merge,
+    fail_under,
   });
 }
 
@@ -2259,10 +3318,22 @@ fn doc_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   let source_file = matches.value_of("source_file").map(String::from);
   let private = matches.is_present("private");
   let json = matches.is_present("json");
+  let html = matches.is_present("html").then(|| {
+    matches
+      .value_of("output")
+      .map_or_else(|| PathBuf::from("docs"), PathBuf::from)
+  });
+  let lint = matches.is_present("lint");
+  let lint_threshold = matches
+    .value_of("lint-threshold")
+    .map_or(100.0, |val| val.parse().unwrap());
   let filter = matches.value_of("filter").map(String::from);
   flags.subcommand = DenoSubcommand::Doc(DocFlags {
     source_file,
     json,
+    html,
+    lint,
+    lint_threshold,
     filter,
     private,
   });
@@ -2277,6 +3348,14 @@ fn eval_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   flags.allow_write = Some(vec![]);
   flags.allow_ffi = Some(vec![]);
   flags.allow_hrtime = true;
+  check_arg_parse(flags, matches);
+  // unlike `deno run`, `--check` type checks the code right away
+  if flags.has_check_flag {
+    flags.type_check_mode = match flags.future_type_check_mode {
+      FutureTypeCheckMode::All => TypeCheckMode::All,
+      _ => TypeCheckMode::Local,
+    };
+  }
   // TODO(@satyarohith): remove this flag in 2.0.
   let as_typescript = matches.is_present("ts");
   let ext = if as_typescript {
@@ -2286,6 +3365,7 @@ fn eval_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   };
 
   let print = matches.is_present("print");
+  let cwd_url = matches.value_of("cwd-url").map(String::from);
   let mut code: Vec<String> = matches
     .values_of("code_arg")
     .unwrap()
@@ -2297,7 +3377,12 @@ fn eval_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   for v in code_args {
     flags.argv.push(v);
   }
-  flags.subcommand = DenoSubcommand::Eval(EvalFlags { print, code, ext });
+  flags.subcommand = DenoSubcommand::Eval(EvalFlags {
+    print,
+    code,
+    ext,
+    cwd_url,
+  });
 }
 
 fn fmt_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
@@ -2346,6 +3431,11 @@ fn fmt_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   } else {
     None
   };
+  let semi_colons = if matches.is_present("options-no-semicolons") {
+    Some(false)
+  } else {
+    None
+  };
   let prose_wrap = if matches.is_present("options-prose-wrap") {
     Some(matches.value_of("options-prose-wrap").unwrap().to_string())
   } else {
@@ -2354,6 +3444,8 @@ fn fmt_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 
   flags.subcommand = DenoSubcommand::Fmt(FmtFlags {
     check: matches.is_present("check"),
+    unified_diff: matches.value_of("diff") == Some("unified"),
+    json: matches.is_present("json"),
     ext,
     files,
     ignore,
@@ -2361,7 +3453,9 @@ fn fmt_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     line_width,
     indent_width,
     single_quote,
+    semi_colons,
     prose_wrap,
+    force: matches.is_present("force"),
   });
 }
 
@@ -2374,6 +3468,7 @@ fn info_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   flags.subcommand = DenoSubcommand::Info(InfoFlags {
     file: matches.value_of("file").map(|f| f.to_string()),
     json,
+    why: matches.value_of("why").map(|f| f.to_string()),
   });
 }
 
@@ -2407,6 +3502,17 @@ fn install_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   });
 }
 
+fn init_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
+  flags.subcommand = DenoSubcommand::Init(InitFlags {
+    template: matches.value_of("template").map(String::from),
+    template_url: matches.value_of("from").map(String::from),
+    dir: matches.value_of("dir").map(PathBuf::from),
+    vscode: matches.is_present("vscode"),
+    import_map: matches.is_present("import-map"),
+    force: matches.is_present("force"),
+  });
+}
+
 fn uninstall_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   let root = if matches.is_present("root") {
     let install_root = matches.value_of("root").unwrap();
@@ -2415,14 +3521,28 @@ fn uninstall_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     None
   };
 
-  let name = matches.value_of("name").unwrap().to_string();
-  flags.subcommand = DenoSubcommand::Uninstall(UninstallFlags { name, root });
+  let name = matches.value_of("name").map(|name| name.to_string());
+  let list = matches.is_present("list");
+  flags.subcommand =
+    DenoSubcommand::Uninstall(UninstallFlags { name, root, list });
 }
 
 fn lsp_parse(flags: &mut Flags, _matches: &clap::ArgMatches) {
   flags.subcommand = DenoSubcommand::Lsp;
 }
 
+fn outdated_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
+  compile_args_without_no_check_parse(flags, matches);
+  let files = match matches.values_of("files") {
+    Some(f) => f.map(String::from).collect(),
+    None => vec![],
+  };
+  flags.subcommand = DenoSubcommand::Outdated(OutdatedFlags {
+    files,
+    update: matches.is_present("update"),
+  });
+}
+
 fn lint_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   config_arg_parse(flags, matches);
   watch_arg_parse(flags, matches, false);
@@ -2447,7 +3567,11 @@ fn lint_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     .values_of("rules-exclude")
     .map(|f| f.map(String::from).collect());
 
-  let json = matches.is_present("json");
+  let format = matches.value_of("format");
+  let json = matches.is_present("json") || format == Some("json");
+  let sarif = format == Some("sarif");
+  let fix = matches.is_present("fix");
+  let dry_run = matches.is_present("dry-run");
   flags.subcommand = DenoSubcommand::Lint(LintFlags {
     files,
     rules,
@@ -2456,6 +3580,10 @@ fn lint_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     maybe_rules_exclude,
     ignore,
     json,
+    sarif,
+    fix,
+    dry_run,
+    force: matches.is_present("force"),
   });
 }
 
@@ -2495,6 +3623,7 @@ fn run_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   }
 
   watch_arg_parse(flags, matches, true);
+  flags.hot = matches.is_present("hot");
   flags.subcommand = DenoSubcommand::Run(RunFlags { script });
 }
 
@@ -2510,13 +3639,17 @@ fn task_parse(
     task_name = task.to_string();
 
     if let Some(index) = matches.index_of("task") {
-      flags
-        .argv
-        .extend(raw_args[index + 2..].iter().map(String::from));
+      let mut task_args = raw_args[index + 2..].iter().peekable();
+      // the first `--` only separates the task name from its arguments
+      task_args.next_if(|arg| *arg == "--");
+      flags.argv.extend(task_args.map(String::from));
     }
   }
 
-  flags.subcommand = DenoSubcommand::Task(TaskFlags { task: task_name });
+  flags.subcommand = DenoSubcommand::Task(TaskFlags {
+    task: task_name,
+    parallel: matches.is_present("parallel"),
+  });
 }
 
 fn test_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
@@ -2532,6 +3665,56 @@ fn test_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 
   let no_run = matches.is_present("no-run");
   let trace_ops = matches.is_present("trace-ops");
+  let profile = if matches.is_present("profile") {
+    Some(
+      matches
+        .value_of("profile")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(".")),
+    )
+  } else {
+    None
+  };
+  let reporter = matches
+    .value_of("reporter")
+    .map(|val| parse_test_reporter(val).unwrap())
+    .unwrap_or_default();
+  let shard = matches
+    .value_of("shard")
+    .map(|val| parse_test_shard(val).unwrap());
+  let timeout = matches
+    .value_of("timeout")
+    .map(|val| val.parse::<u64>().unwrap());
+  let retries = matches
+    .value_of("retries")
+    .map(|val| val.parse::<usize>().unwrap())
+    .unwrap_or(0);
+  let setup = matches.value_of("setup").map(String::from);
+  let update_snapshots = matches.is_present("update-snapshots");
+  let reuse_workers = matches.is_present("reuse-workers");
+  let slow_threshold = matches
+    .value_of("slow-threshold")
+    .map(|val| val.parse::<u64>().unwrap());
+  let no_diff = matches.is_present("no-diff");
+  let coverage_fail_under = coverage_fail_under_arg_parse(matches);
+  let filter_tags = matches
+    .values_of("filter-tag")
+    .map(|tags| tags.map(String::from).collect())
+    .unwrap_or_default();
+  let skip_tags = matches
+    .values_of("skip-tag")
+    .map(|tags| tags.map(String::from).collect())
+    .unwrap_or_default();
+  let capture_output = matches.is_present("capture-output");
+  let verbose_output = matches.is_present("verbose-output");
+  let list = if matches.is_present("list") {
+    match matches.value_of("list") {
+      Some("json") => Some(TestListFormat::Json),
+      _ => Some(TestListFormat::Pretty),
+    }
+  } else {
+    None
+  };
   let doc = matches.is_present("doc");
   let allow_none = matches.is_present("allow-none");
   let filter = matches.value_of("filter").map(String::from);
@@ -2593,6 +3776,7 @@ fn test_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   };
 
   flags.coverage_dir = matches.value_of("coverage").map(String::from);
+  let watch_full = matches.value_of("watch") == Some("full");
   watch_arg_parse(flags, matches, false);
   flags.subcommand = DenoSubcommand::Test(TestFlags {
     no_run,
@@ -2605,9 +3789,122 @@ fn test_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     allow_none,
     concurrent_jobs,
     trace_ops,
+    profile,
+    reporter,
+    shard,
+    timeout,
+    retries,
+    watch_full,
+    setup,
+    update_snapshots,
+    reuse_workers,
+    slow_threshold,
+    no_diff,
+    coverage_fail_under,
+    filter_tags,
+    skip_tags,
+    capture_output,
+    verbose_output,
+    list,
   });
 }
 
+fn parse_test_shard(val: &str) -> Result<TestShard, String> {
+  let err = || {
+    format!(
+      "invalid shard \"{}\", expected INDEX/COUNT with 1 <= INDEX <= COUNT",
+      val
+    )
+  };
+  let (index, count) = val.split_once('/').ok_or_else(err)?;
+  let index = index.parse::<usize>().map_err(|_| err())?;
+  let count = count.parse::<usize>().map_err(|_| err())?;
+  if index == 0 || index > count {
+    return Err(err());
+  }
+  Ok(TestShard { index, count })
+}
+
+fn coverage_fail_under_arg<'a>() -> Arg<'a> {
+  Arg::new("coverage-fail-under")
+    .long("coverage-fail-under")
+    .takes_value(true)
+    .require_equals(true)
+    .value_name("PERCENT")
+    .help("Exit with an error if coverage is below PERCENT")
+    .long_help(
+      "Exit with an error if line or branch coverage is below the given \
+percentage, listing the files below it. A single value applies to both line \
+and branch coverage, use 'line=80,branch=70' to set them separately.",
+    )
+    .validator(|val: &str| parse_coverage_threshold(val).map(|_| ()))
+}
+
+fn coverage_fail_under_arg_parse(
+  matches: &clap::ArgMatches,
+) -> Option<CoverageThreshold> {
+  matches
+    .value_of("coverage-fail-under")
+    .map(|val| parse_coverage_threshold(val).unwrap())
+}
+
+fn parse_coverage_threshold(val: &str) -> Result<CoverageThreshold, String> {
+  let parse_percent = |val: &str| -> Result<f64, String> {
+    match val.parse::<f64>() {
+      Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(percent),
+      _ => Err(format!(
+        "invalid coverage threshold \"{}\", expected a percentage between 0 and 100",
+        val
+      )),
+    }
+  };
+  if !val.contains('=') {
+    let percent = parse_percent(val)?;
+    return Ok(CoverageThreshold {
+      line: Some(percent),
+      branch: Some(percent),
+    });
+  }
+  let mut threshold = CoverageThreshold::default();
+  for part in val.split(',') {
+    match part.split_once('=') {
+      Some(("line", percent)) => threshold.line = Some(parse_percent(percent)?),
+      Some(("branch", percent)) => {
+        threshold.branch = Some(parse_percent(percent)?)
+      }
+      _ => {
+        return Err(format!(
+          "invalid coverage threshold \"{}\", expected \"line=PERCENT\" or \"branch=PERCENT\"",
+          part
+        ))
+      }
+    }
+  }
+  Ok(threshold)
+}
+
+fn parse_test_reporter(val: &str) -> Result<TestReporterConfig, String> {
+  let is_module = ["./", "../", "/", "file:", "http:", "https:"]
+    .iter()
+    .any(|prefix| val.starts_with(prefix));
+  if is_module {
+    return Ok(TestReporterConfig::Module(val.to_string()));
+  }
+  match val.split_once(':') {
+    None if val == "pretty" => Ok(TestReporterConfig::Pretty),
+    None if val == "junit" => Ok(TestReporterConfig::Junit(None)),
+    None if val == "tap" => Ok(TestReporterConfig::Tap),
+    None if val == "json" => Ok(TestReporterConfig::Json),
+    Some(("junit", path)) if !path.is_empty() => {
+      Ok(TestReporterConfig::Junit(Some(PathBuf::from(path))))
+    }
+    _ => Err(format!(
+      "invalid reporter \"{}\", expected \"pretty\", \"tap\", \"json\", \"junit[:PATH]\" or the path to a reporter module",
+      val
+    )),
+  }
+}
+
 fn types_parse(flags: &mut Flags, _matches: &clap::ArgMatches) {
   flags.subcommand = DenoSubcommand::Types;
 }
@@ -2617,7 +3914,16 @@ fn upgrade_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 
   let dry_run = matches.is_present("dry-run");
   let force = matches.is_present("force");
-  let canary = matches.is_present("canary");
+  let no_verify = matches.is_present("no-verify");
+  let channel = if matches.is_present("canary") {
+    UpgradeChannel::Canary
+  } else {
+    match matches.value_of("channel") {
+      Some("canary") => UpgradeChannel::Canary,
+      Some("rc") => UpgradeChannel::Rc,
+      _ => UpgradeChannel::Stable,
+    }
+  };
   let version = matches.value_of("version").map(|s| s.to_string());
   let output = if matches.is_present("output") {
     let install_root = matches.value_of("output").unwrap();
@@ -2629,7 +3935,8 @@ fn upgrade_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   flags.subcommand = DenoSubcommand::Upgrade(UpgradeFlags {
     dry_run,
     force,
-    canary,
+    no_verify,
+    channel,
     version,
     output,
     ca_file,
@@ -2650,6 +3957,8 @@ fn vendor_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
       .unwrap_or_default(),
     output_path: matches.value_of("output").map(PathBuf::from),
     force: matches.is_present("force"),
+    check: matches.is_present("check"),
+    update: matches.is_present("update"),
   });
 }
 
@@ -2740,6 +4049,9 @@ fn permission_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   if has_no_prompt_env || matches.is_present("no-prompt") {
     flags.no_prompt = true;
   }
+  flags.permission_audit =
+    matches.value_of("permission-audit").map(PathBuf::from);
+  flags.permission_set = matches.value_of("permission-set").map(String::from);
 }
 fn unsafely_ignore_certificate_errors_parse(
   flags: &mut Flags,
@@ -2771,28 +4083,25 @@ fn runtime_args_parse(
   seed_arg_parse(flags, matches);
   compat_arg_parse(flags, matches);
   enable_testing_features_arg_parse(flags, matches);
+  dump_op_metrics_arg_parse(flags, matches);
+  max_heap_size_arg_parse(flags, matches);
 }
 
 fn inspect_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
-  let default = || "127.0.0.1:9229".parse::<SocketAddr>().unwrap();
-  flags.inspect = if matches.is_present("inspect") {
-    if let Some(host) = matches.value_of("inspect") {
-      Some(host.parse().unwrap())
-    } else {
-      Some(default())
-    }
-  } else {
-    None
-  };
-  flags.inspect_brk = if matches.is_present("inspect-brk") {
-    if let Some(host) = matches.value_of("inspect-brk") {
-      Some(host.parse().unwrap())
+  let parse = |name| {
+    if matches.is_present(name) {
+      if let Some(host) = matches.value_of(name) {
+        Some(host.parse().unwrap())
+      } else {
+        Some("127.0.0.1:9229".parse().unwrap())
+      }
     } else {
-      Some(default())
+      None
     }
-  } else {
-    None
   };
+  flags.inspect = parse("inspect");
+  flags.inspect_brk = parse("inspect-brk");
+  flags.inspect_wait = parse("inspect-wait");
 }
 
 fn import_map_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
@@ -2854,6 +4163,17 @@ fn seed_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
   }
 }
 
+fn dump_op_metrics_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
+  flags.dump_op_metrics =
+    matches.value_of("dump-op-metrics").map(PathBuf::from);
+}
+
+fn max_heap_size_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
+  if let Some(size) = matches.value_of("max-heap-size") {
+    flags.max_heap_size = Some(size.parse::<usize>().unwrap());
+  }
+}
+
 fn compat_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
   if matches.is_present("compat") {
     flags.compat = true;
@@ -2914,10 +4234,7 @@ fn no_remote_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 }
 
 fn inspect_arg_validate(val: &str) -> Result<(), String> {
-  match val.parse::<SocketAddr>() {
-    Ok(_) => Ok(()),
-    Err(e) => Err(e.to_string()),
-  }
+  val.parse::<InspectorAddress>().map(|_| ())
 }
 
 fn watch_arg_parse(
@@ -2936,6 +4253,12 @@ fn watch_arg_parse(
   if matches.is_present("no-clear-screen") {
     flags.no_clear_screen = true;
   }
+  if let Some(debounce) = matches.value_of("watch-debounce") {
+    flags.watch_debounce = Some(debounce.parse().unwrap());
+  }
+  if let Some(interval) = matches.value_of("watch-poll-interval") {
+    flags.watch_poll_interval = Some(interval.parse().unwrap());
+  }
 }
 
 // TODO(ry) move this to utility module and add test.
@@ -2999,7 +4322,71 @@ mod tests {
         subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
           force: true,
           dry_run: true,
-          canary: false,
+          no_verify: false,
+          channel: UpgradeChannel::Stable,
+          version: None,
+          output: None,
+          ca_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn upgrade_channel() {
+    let r = flags_from_vec(svec!["deno", "upgrade", "--channel=rc"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: false,
+          dry_run: false,
+          no_verify: false,
+          channel: UpgradeChannel::Rc,
+          version: None,
+          output: None,
+          ca_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "upgrade", "--canary"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: false,
+          dry_run: false,
+          no_verify: false,
+          channel: UpgradeChannel::Canary,
+          version: None,
+          output: None,
+          ca_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "upgrade", "--channel=beta"]);
+    assert!(r.is_err());
+    let r =
+      flags_from_vec(svec!["deno", "upgrade", "--canary", "--channel=rc"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn upgrade_no_verify() {
+    let r = flags_from_vec(svec!["deno", "upgrade", "--no-verify"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: false,
+          dry_run: false,
+          no_verify: true,
+          channel: UpgradeChannel::Stable,
           version: None,
           output: None,
           ca_file: None,
@@ -3090,6 +4477,66 @@ mod tests {
     );
   }
 
+  #[test]
+  fn run_watch_with_debounce_and_poll_interval() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--watch",
+      "--watch-debounce=500",
+      "--watch-poll-interval=1000",
+      "script.ts"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        watch: Some(vec![]),
+        watch_debounce: Some(500),
+        watch_poll_interval: Some(1000),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--watch",
+      "--watch-poll-interval=0",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+
+    let r =
+      flags_from_vec(svec!["deno", "run", "--watch-debounce=500", "script.ts"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn run_watch_hot() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--watch", "--hot", "script.ts"]);
+
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        watch: Some(vec![]),
+        hot: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "run", "--hot", "script.ts"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn run_reload_allow_write() {
     let r =
@@ -3250,6 +4697,8 @@ mod tests {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           ignore: vec![],
           check: false,
+          unified_diff: false,
+          json: false,
           files: vec![
             PathBuf::from("script_1.ts"),
             PathBuf::from("script_2.ts")
@@ -3259,7 +4708,9 @@ mod tests {
           line_width: None,
           indent_width: None,
           single_quote: None,
+          semi_colons: None,
           prose_wrap: None,
+          force: false,
         }),
         ..Flags::default()
       }
@@ -3272,18 +4723,56 @@ mod tests {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           ignore: vec![],
           check: true,
+          unified_diff: false,
+          json: false,
           files: vec![],
           ext: "ts".to_string(),
           use_tabs: None,
           line_width: None,
           indent_width: None,
           single_quote: None,
+          semi_colons: None,
           prose_wrap: None,
+          force: false,
         }),
         ..Flags::default()
       }
     );
 
+    let r = flags_from_vec(svec!["deno", "fmt", "--check", "--diff=unified"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Fmt(FmtFlags {
+        check: true,
+        unified_diff: true,
+        json: false,
+        ..
+      })
+    ));
+
+    let r = flags_from_vec(svec!["deno", "fmt", "--check", "--json"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Fmt(FmtFlags {
+        check: true,
+        unified_diff: false,
+        json: true,
+        ..
+      })
+    ));
+
+    let r = flags_from_vec(svec!["deno", "fmt", "--json"]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "fmt",
+      "--check",
+      "--json",
+      "--diff=unified"
+    ]);
+    assert!(r.is_err());
+
     let r = flags_from_vec(svec!["deno", "fmt"]);
     assert_eq!(
       r.unwrap(),
@@ -3291,13 +4780,17 @@ mod tests {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           ignore: vec![],
           check: false,
+          unified_diff: false,
+          json: false,
           files: vec![],
           ext: "ts".to_string(),
           use_tabs: None,
           line_width: None,
           indent_width: None,
           single_quote: None,
+          semi_colons: None,
           prose_wrap: None,
+          force: false,
         }),
         ..Flags::default()
       }
@@ -3310,13 +4803,17 @@ mod tests {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           ignore: vec![],
           check: false,
+          unified_diff: false,
+          json: false,
           files: vec![],
           ext: "ts".to_string(),
           use_tabs: None,
           line_width: None,
           indent_width: None,
           single_quote: None,
+          semi_colons: None,
           prose_wrap: None,
+          force: false,
         }),
         watch: Some(vec![]),
         ..Flags::default()
@@ -3331,13 +4828,17 @@ mod tests {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           ignore: vec![],
           check: false,
+          unified_diff: false,
+          json: false,
           files: vec![],
           ext: "ts".to_string(),
           use_tabs: None,
           line_width: None,
           indent_width: None,
           single_quote: None,
+          semi_colons: None,
           prose_wrap: None,
+          force: false,
         }),
         watch: Some(vec![]),
         no_clear_screen: true,
@@ -3359,13 +4860,17 @@ mod tests {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           ignore: vec![PathBuf::from("bar.js")],
           check: true,
+          unified_diff: false,
+          json: false,
           files: vec![PathBuf::from("foo.ts")],
           ext: "ts".to_string(),
           use_tabs: None,
           line_width: None,
           indent_width: None,
           single_quote: None,
+          semi_colons: None,
           prose_wrap: None,
+          force: false,
         }),
         watch: Some(vec![]),
         ..Flags::default()
@@ -3379,13 +4884,17 @@ mod tests {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           ignore: vec![],
           check: false,
+          unified_diff: false,
+          json: false,
           files: vec![],
           ext: "ts".to_string(),
           use_tabs: None,
           line_width: None,
           indent_width: None,
           single_quote: None,
+          semi_colons: None,
           prose_wrap: None,
+          force: false,
         }),
         config_path: Some("deno.jsonc".to_string()),
         ..Flags::default()
@@ -3406,13 +4915,17 @@ mod tests {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           ignore: vec![],
           check: false,
+          unified_diff: false,
+          json: false,
           files: vec![PathBuf::from("foo.ts")],
           ext: "ts".to_string(),
           use_tabs: None,
           line_width: None,
           indent_width: None,
           single_quote: None,
+          semi_colons: None,
           prose_wrap: None,
+          force: false,
         }),
         config_path: Some("deno.jsonc".to_string()),
         watch: Some(vec![]),
@@ -3429,6 +4942,7 @@ mod tests {
       "--options-indent-width",
       "4",
       "--options-single-quote",
+      "--options-no-semicolons",
       "--options-prose-wrap",
       "never"
     ]);
@@ -3438,13 +4952,17 @@ mod tests {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           ignore: vec![],
           check: false,
+          unified_diff: false,
+          json: false,
           files: vec![],
           ext: "ts".to_string(),
           use_tabs: Some(true),
           line_width: Some(NonZeroU32::new(60).unwrap()),
           indent_width: Some(NonZeroU8::new(4).unwrap()),
           single_quote: Some(true),
+          semi_colons: Some(false),
           prose_wrap: Some("never".to_string()),
+          force: false,
         }),
         ..Flags::default()
       }
@@ -3467,7 +4985,11 @@ mod tests {
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: false,
+          sarif: false,
+          fix: false,
+          dry_run: false,
           ignore: vec![],
+          force: false,
         }),
         ..Flags::default()
       }
@@ -3493,7 +5015,11 @@ mod tests {
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: false,
+          sarif: false,
+          fix: false,
+          dry_run: false,
           ignore: vec![],
+          force: false,
         }),
         watch: Some(vec![]),
         ..Flags::default()
@@ -3521,7 +5047,11 @@ mod tests {
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: false,
+          sarif: false,
+          fix: false,
+          dry_run: false,
           ignore: vec![],
+          force: false,
         }),
         watch: Some(vec![]),
         no_clear_screen: true,
@@ -3541,10 +5071,14 @@ mod tests {
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: false,
+          sarif: false,
+          fix: false,
+          dry_run: false,
           ignore: vec![
             PathBuf::from("script_1.ts"),
             PathBuf::from("script_2.ts")
           ],
+          force: false,
         }),
         ..Flags::default()
       }
@@ -3561,7 +5095,11 @@ mod tests {
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: false,
+          sarif: false,
+          fix: false,
+          dry_run: false,
           ignore: vec![],
+          force: false,
         }),
         ..Flags::default()
       }
@@ -3584,7 +5122,11 @@ mod tests {
           maybe_rules_include: Some(svec!["ban-untagged-todo", "no-undef"]),
           maybe_rules_exclude: Some(svec!["no-const-assign"]),
           json: false,
+          sarif: false,
+          fix: false,
+          dry_run: false,
           ignore: vec![],
+          force: false,
         }),
         ..Flags::default()
       }
@@ -3601,7 +5143,11 @@ mod tests {
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: true,
+          sarif: false,
+          fix: false,
+          dry_run: false,
           ignore: vec![],
+          force: false,
         }),
         ..Flags::default()
       }
@@ -3625,7 +5171,11 @@ mod tests {
           maybe_rules_include: None,
           maybe_rules_exclude: None,
           json: true,
+          sarif: false,
+          fix: false,
+          dry_run: false,
           ignore: vec![],
+          force: false,
         }),
         config_path: Some("Deno.jsonc".to_string()),
         ..Flags::default()
@@ -3633,6 +5183,99 @@ mod tests {
     );
   }
 
+  #[test]
+  fn lint_fix() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--fix",
+      "--dry-run",
+      "script_1.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: vec![PathBuf::from("script_1.ts")],
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          sarif: false,
+          fix: true,
+          dry_run: true,
+          ignore: vec![],
+          force: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "lint", "--dry-run", "script_1.ts"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn lint_format() {
+    let r =
+      flags_from_vec(svec!["deno", "lint", "--format=sarif", "script_1.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: vec![PathBuf::from("script_1.ts")],
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          sarif: true,
+          fix: false,
+          dry_run: false,
+          ignore: vec![],
+          force: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "lint", "--format=json", "script_1.ts"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Lint(LintFlags {
+        json: true,
+        sarif: false,
+        ..
+      })
+    ));
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--json",
+      "--format=sarif",
+      "script_1.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn fmt_lint_force() {
+    let r = flags_from_vec(svec!["deno", "fmt", "--force"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Fmt(FmtFlags { force: true, .. })
+    ));
+
+    let r = flags_from_vec(svec!["deno", "lint", "--force", "script_1.ts"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Lint(LintFlags { force: true, .. })
+    ));
+  }
+
   #[test]
   fn types() {
     let r = flags_from_vec(svec!["deno", "types"]);
@@ -3659,6 +5302,29 @@ mod tests {
     );
   }
 
+  #[test]
+  fn cache_reload_patterns() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "cache",
+      "--reload=https://deno.land/std@0.130.0/,https://esm.sh/react",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["script.ts"],
+        }),
+        cache_blocklist: svec![
+          "https://deno.land/std@0.130.0",
+          "https://esm.sh/react"
+        ],
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn check() {
     let r = flags_from_vec(svec!["deno", "check", "script.ts"]);
@@ -3673,7 +5339,7 @@ mod tests {
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "check", "--remote", "script.ts"]);
+    let r = flags_from_vec(svec!["deno", "check", "--all", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
@@ -3684,17 +5350,43 @@ mod tests {
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn info() {
-    let r = flags_from_vec(svec!["deno", "info", "script.ts"]);
+    let r = flags_from_vec(svec!["deno", "check", "--remote", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Info(InfoFlags {
-          json: false,
-          file: Some("script.ts".to_string()),
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: svec!["script.ts"],
+          remote: true,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "check", "--config", "deno.json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: vec![],
+          remote: false,
+        }),
+        config_path: Some("deno.json".to_string()),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn info() {
+    let r = flags_from_vec(svec!["deno", "info", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          file: Some("script.ts".to_string()),
+          why: None,
         }),
         ..Flags::default()
       }
@@ -3707,6 +5399,7 @@ mod tests {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
           file: Some("script.ts".to_string()),
+          why: None,
         }),
         reload: true,
         ..Flags::default()
@@ -3720,6 +5413,7 @@ mod tests {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: true,
           file: Some("script.ts".to_string()),
+          why: None,
         }),
         ..Flags::default()
       }
@@ -3731,7 +5425,8 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
-          file: None
+          file: None,
+          why: None,
         }),
         ..Flags::default()
       }
@@ -3743,11 +5438,37 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: true,
-          file: None
+          file: None,
+          why: None,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn info_why() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "info",
+      "--json",
+      "--why=https://deno.land/std/fmt/colors.ts",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: true,
+          file: Some("script.ts".to_string()),
+          why: Some("https://deno.land/std/fmt/colors.ts".to_string()),
         }),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "info", "--why=colors.ts"]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -3776,6 +5497,7 @@ mod tests {
           print: false,
           code: "'console.log(\"hello\")'".to_string(),
           ext: "js".to_string(),
+          cwd_url: None,
         }),
         allow_net: Some(vec![]),
         allow_env: Some(vec![]),
@@ -3799,6 +5521,7 @@ mod tests {
           print: true,
           code: "1+2".to_string(),
           ext: "js".to_string(),
+          cwd_url: None,
         }),
         allow_net: Some(vec![]),
         allow_env: Some(vec![]),
@@ -3823,6 +5546,7 @@ mod tests {
           print: false,
           code: "'console.log(\"hello\")'".to_string(),
           ext: "ts".to_string(),
+          cwd_url: None,
         }),
         allow_net: Some(vec![]),
         allow_env: Some(vec![]),
@@ -3847,6 +5571,7 @@ mod tests {
           print: false,
           code: "42".to_string(),
           ext: "js".to_string(),
+          cwd_url: None,
         }),
         import_map_path: Some("import_map.json".to_string()),
         no_remote: true,
@@ -3873,6 +5598,44 @@ mod tests {
     );
   }
 
+  #[test]
+  fn eval_check_and_cwd_url() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "eval",
+      "--check",
+      "--ext=ts",
+      "--cwd-url=./lib",
+      "import { add } from './math.ts'; add(1, 2)"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Eval(EvalFlags {
+          print: false,
+          code: "import { add } from './math.ts'; add(1, 2)".to_string(),
+          ext: "ts".to_string(),
+          cwd_url: Some("./lib".to_string()),
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        future_type_check_mode: FutureTypeCheckMode::Local,
+        has_check_flag: true,
+        allow_net: Some(vec![]),
+        allow_env: Some(vec![]),
+        allow_run: Some(vec![]),
+        allow_read: Some(vec![]),
+        allow_write: Some(vec![]),
+        allow_ffi: Some(vec![]),
+        allow_hrtime: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "eval", "--check=all", "42"]);
+    let flags = r.unwrap();
+    assert_eq!(flags.type_check_mode, TypeCheckMode::All);
+  }
+
   #[test]
   fn eval_args() {
     let r = flags_from_vec(svec![
@@ -3889,6 +5652,7 @@ mod tests {
           print: false,
           code: "console.log(Deno.args)".to_string(),
           ext: "js".to_string(),
+          cwd_url: None,
         }),
         argv: svec!["arg1", "arg2"],
         allow_net: Some(vec![]),
@@ -4134,6 +5898,12 @@ mod tests {
     let r =
       flags_from_vec(svec!["deno", "run", "--allow-env=H\0ME", "script.ts"]);
     assert!(r.is_err());
+    let r =
+      flags_from_vec(svec!["deno", "run", "--allow-env=AWS_*", "script.ts"]);
+    assert!(r.is_ok());
+    let r =
+      flags_from_vec(svec!["deno", "run", "--allow-env=A*WS", "script.ts"]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -4145,6 +5915,9 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          entries: vec![],
+          out_dir: None,
+          external: vec![],
         }),
         ..Flags::default()
       }
@@ -4168,6 +5941,9 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: Some(PathBuf::from("bundle.js")),
+          entries: vec![],
+          out_dir: None,
+          external: vec![],
         }),
         allow_write: Some(vec![]),
         no_remote: true,
@@ -4186,6 +5962,35 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: Some(PathBuf::from("bundle.js")),
+          entries: vec![],
+          out_dir: None,
+          external: vec![],
+        }),
+        allow_write: Some(vec![]),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn bundle_with_out_dir_and_external() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "bundle",
+      "--out-dir=dist",
+      "--external=https://esm.sh/,npm:react",
+      "main.ts",
+      "worker.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bundle(BundleFlags {
+          source_file: "main.ts".to_string(),
+          out_file: None,
+          entries: svec!["worker.ts"],
+          out_dir: Some(PathBuf::from("dist")),
+          external: svec!["https://esm.sh/", "npm:react"],
         }),
         allow_write: Some(vec![]),
         ..Flags::default()
@@ -4208,6 +6013,9 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          entries: vec![],
+          out_dir: None,
+          external: vec![],
         }),
         lock_write: true,
         lock: Some(PathBuf::from("lock.json")),
@@ -4226,6 +6034,9 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          entries: vec![],
+          out_dir: None,
+          external: vec![],
         }),
         ..Flags::default()
       }
@@ -4242,6 +6053,9 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "script.ts".to_string(),
           out_file: None,
+          entries: vec![],
+          out_dir: None,
+          external: vec![],
         }),
         type_check_mode: TypeCheckMode::None,
         ..Flags::default()
@@ -4258,6 +6072,9 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          entries: vec![],
+          out_dir: None,
+          external: vec![],
         }),
         watch: Some(vec![]),
         ..Flags::default()
@@ -4280,6 +6097,9 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          entries: vec![],
+          out_dir: None,
+          external: vec![],
         }),
         watch: Some(vec![]),
         no_clear_screen: true,
@@ -4322,6 +6142,7 @@ mod tests {
         subcommand: DenoSubcommand::Info(InfoFlags {
           file: Some("script.ts".to_string()),
           json: false,
+          why: None,
         }),
         import_map_path: Some("import_map.json".to_owned()),
         ..Flags::default()
@@ -4364,6 +6185,9 @@ mod tests {
           source_file: Some("script.ts".to_owned()),
           private: false,
           json: false,
+          html: None,
+          lint: false,
+          lint_threshold: 100.0,
           filter: None,
         }),
         import_map_path: Some("import_map.json".to_owned()),
@@ -4482,6 +6306,152 @@ mod tests {
     );
   }
 
+  #[test]
+  fn init() {
+    let r = flags_from_vec(svec!["deno", "init"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Init(InitFlags {
+          template: None,
+          template_url: None,
+          dir: None,
+          vscode: false,
+          import_map: false,
+          force: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "init",
+      "server",
+      "--dir",
+      "my_server",
+      "--vscode",
+      "--import-map",
+      "--force"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Init(InitFlags {
+          template: Some("server".to_string()),
+          template_url: None,
+          dir: Some(PathBuf::from("my_server")),
+          vscode: true,
+          import_map: true,
+          force: true,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "init",
+      "--from",
+      "https://example.com/template.json"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Init(InitFlags {
+          template: None,
+          template_url: Some("https://example.com/template.json".to_string()),
+          dir: None,
+          vscode: false,
+          import_map: false,
+          force: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "init", "desktop"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "init",
+      "cli",
+      "--from",
+      "https://example.com/template.json"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn outdated() {
+    let r = flags_from_vec(svec!["deno", "outdated", "main.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Outdated(OutdatedFlags {
+          files: svec!["main.ts"],
+          update: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "outdated",
+      "--update",
+      "--import-map",
+      "import_map.json",
+      "--lock",
+      "lock.json"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Outdated(OutdatedFlags {
+          files: vec![],
+          update: true,
+        }),
+        import_map_path: Some("import_map.json".to_string()),
+        lock: Some(PathBuf::from("lock.json")),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn uninstall() {
+    let r = flags_from_vec(svec!["deno", "uninstall", "file_server"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Uninstall(UninstallFlags {
+          name: Some("file_server".to_string()),
+          root: None,
+          list: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "uninstall", "--list", "--root", "/foo"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Uninstall(UninstallFlags {
+          name: None,
+          root: Some(PathBuf::from("/foo")),
+          list: true,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "uninstall"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn log_level() {
     let r =
@@ -4781,6 +6751,40 @@ mod tests {
     );
   }
 
+  #[test]
+  fn allow_net_allowlist_with_patterns() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-net=*.example.com,10.0.0.0/8,:8000",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        allow_net: Some(svec![
+          "*.example.com",
+          "10.0.0.0/8",
+          "0.0.0.0:8000",
+          "127.0.0.1:8000",
+          "localhost:8000"
+        ]),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-net=10.0.0.0/40",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn allow_net_allowlist_with_ports() {
     let r = flags_from_vec(svec![
@@ -4880,6 +6884,23 @@ mod tests {
           shuffle: None,
           concurrent_jobs: NonZeroUsize::new(1).unwrap(),
           trace_ops: true,
+          profile: None,
+          reporter: TestReporterConfig::Pretty,
+          shard: None,
+          timeout: None,
+          retries: 0,
+          watch_full: false,
+          setup: None,
+          update_snapshots: false,
+          reuse_workers: false,
+          slow_threshold: None,
+          no_diff: false,
+          coverage_fail_under: None,
+          filter_tags: vec![],
+          skip_tags: vec![],
+          capture_output: false,
+          verbose_output: false,
+          list: None,
         }),
         unstable: true,
         no_prompt: true,
@@ -4893,38 +6914,353 @@ mod tests {
   }
 
   #[test]
-  fn run_with_cafile() {
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--cert",
-      "example.crt",
-      "script.ts"
-    ]);
+  fn test_profile() {
+    let r = flags_from_vec(svec!["deno", "test", "--profile=profiles"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: None,
+          filter: None,
+          allow_none: false,
+          include: None,
+          ignore: vec![],
+          shuffle: None,
+          concurrent_jobs: NonZeroUsize::new(1).unwrap(),
+          trace_ops: false,
+          profile: Some(PathBuf::from("profiles")),
+          reporter: TestReporterConfig::Pretty,
+          shard: None,
+          timeout: None,
+          retries: 0,
+          watch_full: false,
+          setup: None,
+          update_snapshots: false,
+          reuse_workers: false,
+          slow_threshold: None,
+          no_diff: false,
+          coverage_fail_under: None,
+          filter_tags: vec![],
+          skip_tags: vec![],
+          capture_output: false,
+          verbose_output: false,
+          list: None,
         }),
-        ca_file: Some("example.crt".to_owned()),
+        no_prompt: true,
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "test", "--profile"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags { profile: Some(dir), .. })
+        if dir == PathBuf::from(".")
+    ));
   }
 
   #[test]
-  fn run_with_enable_testing_features() {
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--enable-testing-features-do-not-use",
-      "script.ts"
-    ]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
+  fn test_reporter() {
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=junit"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        reporter: TestReporterConfig::Junit(None),
+        ..
+      })
+    ));
+
+    let r =
+      flags_from_vec(svec!["deno", "test", "--reporter=junit:report.xml"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        reporter: TestReporterConfig::Junit(Some(path)),
+        ..
+      }) if path == PathBuf::from("report.xml")
+    ));
+
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=pretty"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        reporter: TestReporterConfig::Pretty,
+        ..
+      })
+    ));
+
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=tap"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        reporter: TestReporterConfig::Tap,
+        ..
+      })
+    ));
+
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=json"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        reporter: TestReporterConfig::Json,
+        ..
+      })
+    ));
+
+    let r =
+      flags_from_vec(svec!["deno", "test", "--reporter=./my_reporter.ts"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        reporter: TestReporterConfig::Module(specifier),
+        ..
+      }) if specifier == "./my_reporter.ts"
+    ));
+
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=xunit"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_timeout() {
+    let r = flags_from_vec(svec!["deno", "test", "--timeout=5000"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        timeout: Some(5000),
+        ..
+      })
+    ));
+
+    let r = flags_from_vec(svec!["deno", "test", "--timeout=0"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_retries() {
+    let r = flags_from_vec(svec!["deno", "test", "--retries=2"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags { retries: 2, .. })
+    ));
+
+    let r = flags_from_vec(svec!["deno", "test", "--retries=-1"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_setup() {
+    let r = flags_from_vec(svec!["deno", "test", "--setup", "setup.ts"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags { setup: Some(setup), .. })
+        if setup == "setup.ts"
+    ));
+  }
+
+  #[test]
+  fn test_update_snapshots() {
+    let r = flags_from_vec(svec!["deno", "test", "--update-snapshots"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        update_snapshots: true,
+        ..
+      })
+    ));
+  }
+
+  #[test]
+  fn test_reuse_workers() {
+    let r = flags_from_vec(svec!["deno", "test", "--reuse-workers", "-j"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        reuse_workers: true,
+        ..
+      })
+    ));
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--reuse-workers",
+      "--coverage=cov"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_slow_threshold() {
+    let r = flags_from_vec(svec!["deno", "test", "--slow-threshold=500"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        slow_threshold: Some(500),
+        ..
+      })
+    ));
+
+    let r = flags_from_vec(svec!["deno", "test", "--slow-threshold=fast"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_no_diff() {
+    let r = flags_from_vec(svec!["deno", "test", "--no-diff"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags { no_diff: true, .. })
+    ));
+  }
+
+  #[test]
+  fn test_coverage_fail_under() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--coverage=cov",
+      "--coverage-fail-under=80"
+    ]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        coverage_fail_under: Some(CoverageThreshold {
+          line: Some(line),
+          branch: Some(branch),
+        }),
+        ..
+      }) if line == 80.0 && branch == 80.0
+    ));
+
+    let r = flags_from_vec(svec!["deno", "test", "--coverage-fail-under=80"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_tags() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--filter-tag=integration,slow",
+      "--filter-tag=network",
+      "--skip-tag=flaky"
+    ]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags { filter_tags, skip_tags, .. })
+        if filter_tags == svec!["integration", "slow", "network"]
+          && skip_tags == svec!["flaky"]
+    ));
+  }
+
+  #[test]
+  fn test_capture_output() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--capture-output",
+      "--verbose-output"
+    ]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        capture_output: true,
+        verbose_output: true,
+        ..
+      })
+    ));
+
+    let r = flags_from_vec(svec!["deno", "test", "--verbose-output"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--capture-output",
+      "--reuse-workers"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_list() {
+    let r = flags_from_vec(svec!["deno", "test", "--list"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        list: Some(TestListFormat::Pretty),
+        ..
+      })
+    ));
+
+    let r = flags_from_vec(svec!["deno", "test", "--list=json"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        list: Some(TestListFormat::Json),
+        ..
+      })
+    ));
+
+    let r = flags_from_vec(svec!["deno", "test", "--list=xml"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "test", "--list", "--no-run"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_shard() {
+    let r = flags_from_vec(svec!["deno", "test", "--shard=2/5"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        shard: Some(TestShard { index: 2, count: 5 }),
+        ..
+      })
+    ));
+
+    for invalid in ["0/5", "6/5", "1", "a/b", "1/0"] {
+      let r =
+        flags_from_vec(svec!["deno", "test", format!("--shard={}", invalid)]);
+      assert!(r.is_err(), "{}", invalid);
+    }
+  }
+
+  #[test]
+  fn run_with_cafile() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--cert",
+      "example.crt",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        ca_file: Some("example.crt".to_owned()),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn run_with_enable_testing_features() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--enable-testing-features-do-not-use",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
           script: "script.ts".to_string(),
         }),
         enable_testing_features: true,
@@ -4950,6 +7286,23 @@ mod tests {
           ignore: vec![],
           concurrent_jobs: NonZeroUsize::new(4).unwrap(),
           trace_ops: false,
+          profile: None,
+          reporter: TestReporterConfig::Pretty,
+          shard: None,
+          timeout: None,
+          retries: 0,
+          watch_full: false,
+          setup: None,
+          update_snapshots: false,
+          reuse_workers: false,
+          slow_threshold: None,
+          no_diff: false,
+          coverage_fail_under: None,
+          filter_tags: vec![],
+          skip_tags: vec![],
+          capture_output: false,
+          verbose_output: false,
+          list: None,
         }),
         no_prompt: true,
         ..Flags::default()
@@ -4977,6 +7330,23 @@ mod tests {
           ignore: vec![],
           concurrent_jobs: NonZeroUsize::new(1).unwrap(),
           trace_ops: false,
+          profile: None,
+          reporter: TestReporterConfig::Pretty,
+          shard: None,
+          timeout: None,
+          retries: 0,
+          watch_full: false,
+          setup: None,
+          update_snapshots: false,
+          reuse_workers: false,
+          slow_threshold: None,
+          no_diff: false,
+          coverage_fail_under: None,
+          filter_tags: vec![],
+          skip_tags: vec![],
+          capture_output: false,
+          verbose_output: false,
+          list: None,
         }),
         no_prompt: true,
         ..Flags::default()
@@ -5008,6 +7378,23 @@ mod tests {
           ignore: vec![],
           concurrent_jobs: NonZeroUsize::new(1).unwrap(),
           trace_ops: false,
+          profile: None,
+          reporter: TestReporterConfig::Pretty,
+          shard: None,
+          timeout: None,
+          retries: 0,
+          watch_full: false,
+          setup: None,
+          update_snapshots: false,
+          reuse_workers: false,
+          slow_threshold: None,
+          no_diff: false,
+          coverage_fail_under: None,
+          filter_tags: vec![],
+          skip_tags: vec![],
+          capture_output: false,
+          verbose_output: false,
+          list: None,
         }),
         no_prompt: true,
         enable_testing_features: true,
@@ -5033,6 +7420,23 @@ mod tests {
           ignore: vec![],
           concurrent_jobs: NonZeroUsize::new(1).unwrap(),
           trace_ops: false,
+          profile: None,
+          reporter: TestReporterConfig::Pretty,
+          shard: None,
+          timeout: None,
+          retries: 0,
+          watch_full: false,
+          setup: None,
+          update_snapshots: false,
+          reuse_workers: false,
+          slow_threshold: None,
+          no_diff: false,
+          coverage_fail_under: None,
+          filter_tags: vec![],
+          skip_tags: vec![],
+          capture_output: false,
+          verbose_output: false,
+          list: None,
         }),
         no_prompt: true,
         watch: None,
@@ -5058,6 +7462,23 @@ mod tests {
           ignore: vec![],
           concurrent_jobs: NonZeroUsize::new(1).unwrap(),
           trace_ops: false,
+          profile: None,
+          reporter: TestReporterConfig::Pretty,
+          shard: None,
+          timeout: None,
+          retries: 0,
+          watch_full: false,
+          setup: None,
+          update_snapshots: false,
+          reuse_workers: false,
+          slow_threshold: None,
+          no_diff: false,
+          coverage_fail_under: None,
+          filter_tags: vec![],
+          skip_tags: vec![],
+          capture_output: false,
+          verbose_output: false,
+          list: None,
         }),
         no_prompt: true,
         watch: Some(vec![]),
@@ -5066,6 +7487,23 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_watch_full() {
+    let r = flags_from_vec(svec!["deno", "test", "--watch=full"]);
+    let flags = r.unwrap();
+    assert_eq!(flags.watch, Some(vec![]));
+    assert!(matches!(
+      flags.subcommand,
+      DenoSubcommand::Test(TestFlags {
+        watch_full: true,
+        ..
+      })
+    ));
+
+    let r = flags_from_vec(svec!["deno", "test", "--watch=partial"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn test_watch_with_no_clear_screen() {
     let r =
@@ -5084,6 +7522,23 @@ mod tests {
           ignore: vec![],
           concurrent_jobs: NonZeroUsize::new(1).unwrap(),
           trace_ops: false,
+          profile: None,
+          reporter: TestReporterConfig::Pretty,
+          shard: None,
+          timeout: None,
+          retries: 0,
+          watch_full: false,
+          setup: None,
+          update_snapshots: false,
+          reuse_workers: false,
+          slow_threshold: None,
+          no_diff: false,
+          coverage_fail_under: None,
+          filter_tags: vec![],
+          skip_tags: vec![],
+          capture_output: false,
+          verbose_output: false,
+          list: None,
         }),
         watch: Some(vec![]),
         no_clear_screen: true,
@@ -5108,6 +7563,9 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          entries: vec![],
+          out_dir: None,
+          external: vec![],
         }),
         ca_file: Some("example.crt".to_owned()),
         ..Flags::default()
@@ -5124,7 +7582,8 @@ mod tests {
         subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
           force: false,
           dry_run: false,
-          canary: false,
+          no_verify: false,
+          channel: UpgradeChannel::Stable,
           version: None,
           output: None,
           ca_file: Some("example.crt".to_owned()),
@@ -5172,6 +7631,7 @@ mod tests {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
           file: Some("https://example.com".to_string()),
+          why: None,
         }),
         ca_file: Some("example.crt".to_owned()),
         ..Flags::default()
@@ -5188,6 +7648,9 @@ mod tests {
         subcommand: DenoSubcommand::Doc(DocFlags {
           private: false,
           json: true,
+          html: None,
+          lint: false,
+          lint_threshold: 100.0,
           source_file: Some("path/to/module.ts".to_string()),
           filter: None,
         }),
@@ -5204,54 +7667,358 @@ mod tests {
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Doc(DocFlags {
-          private: false,
-          json: false,
-          source_file: Some("path/to/module.ts".to_string()),
-          filter: Some("SomeClass.someField".to_string()),
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          html: None,
+          lint: false,
+          lint_threshold: 100.0,
+          source_file: Some("path/to/module.ts".to_string()),
+          filter: Some("SomeClass.someField".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "doc"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          html: None,
+          lint: false,
+          lint_threshold: 100.0,
+          source_file: None,
+          filter: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "doc", "--builtin", "Deno.Listener"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          html: None,
+          lint: false,
+          lint_threshold: 100.0,
+          source_file: Some("--builtin".to_string()),
+          filter: Some("Deno.Listener".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "doc", "--private", "path/to/module.js"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: true,
+          json: false,
+          html: None,
+          lint: false,
+          lint_threshold: 100.0,
+          source_file: Some("path/to/module.js".to_string()),
+          filter: None,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn doc_html() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--html",
+      "--output=site/",
+      "path/to/module.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          html: Some(PathBuf::from("site/")),
+          lint: false,
+          lint_threshold: 100.0,
+          source_file: Some("path/to/module.ts".to_string()),
+          filter: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "doc", "--html", "path/to/module.ts"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Doc(DocFlags { html: Some(output), .. }) if output == PathBuf::from("docs")
+    ));
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--output=site/",
+      "path/to/module.ts"
+    ]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--lint",
+      "--lint-threshold=80",
+      "path/to/module.ts"
+    ]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Doc(DocFlags {
+        lint: true,
+        lint_threshold,
+        ..
+      }) if lint_threshold == 80.0
+    ));
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--lint-threshold=80",
+      "path/to/module.ts"
+    ]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--lint",
+      "--lint-threshold=120",
+      "path/to/module.ts"
+    ]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--html",
+      "--json",
+      "path/to/module.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn inspect_default_host() {
+    let r = flags_from_vec(svec!["deno", "run", "--inspect", "foo.js"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "foo.js".to_string(),
+        }),
+        inspect: Some("127.0.0.1:9229".parse().unwrap()),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn inspect_wait() {
+    let r = flags_from_vec(svec!["deno", "run", "--inspect-wait", "foo.js"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "foo.js".to_string(),
+        }),
+        inspect_wait: Some("127.0.0.1:9229".parse().unwrap()),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--inspect-wait",
+      "--inspect-brk",
+      "foo.js"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn inspect_unix_socket() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--inspect=unix:/tmp/deno.sock",
+      "foo.js"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "foo.js".to_string(),
+        }),
+        inspect: Some(InspectorAddress::Unix(PathBuf::from("/tmp/deno.sock"))),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "run", "--inspect=unix:", "foo.js"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn dump_op_metrics() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--dump-op-metrics=metrics.json",
+      "foo.js"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "foo.js".to_string(),
+        }),
+        dump_op_metrics: Some(PathBuf::from("metrics.json")),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn permission_set() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--permission-set=perms.json",
+      "foo.js"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "foo.js".to_string(),
+        }),
+        permission_set: Some("perms.json".to_string()),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn permission_audit() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--permission-audit=audit.ndjson",
+      "foo.js"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "foo.js".to_string(),
         }),
+        permission_audit: Some(PathBuf::from("audit.ndjson")),
         ..Flags::default()
       }
     );
+  }
 
-    let r = flags_from_vec(svec!["deno", "doc"]);
+  #[test]
+  fn max_heap_size() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--max-heap-size=512", "foo.js"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Doc(DocFlags {
-          private: false,
-          json: false,
-          source_file: None,
-          filter: None,
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "foo.js".to_string(),
         }),
+        max_heap_size: Some(512),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "doc", "--builtin", "Deno.Listener"]);
+    let r = flags_from_vec(svec!["deno", "run", "--max-heap-size=0", "foo.js"]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--max-heap-size=18446744073709551615",
+      "foo.js"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn compile() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "https://deno.land/std/examples/colors.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Doc(DocFlags {
-          private: false,
-          json: false,
-          source_file: Some("--builtin".to_string()),
-          filter: Some("Deno.Listener".to_string()),
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "https://deno.land/std/examples/colors.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: None,
+          include: vec![],
+          icon: None,
+          product_name: None,
+          product_version: None,
+          description: None,
+          copyright: None,
+          compress: false,
+          size_report: false,
         }),
         ..Flags::default()
       }
     );
+  }
 
-    let r =
-      flags_from_vec(svec!["deno", "doc", "--private", "path/to/module.js"]);
+  #[test]
+  fn compile_with_include() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--include=assets/**",
+      "--include=config.json",
+      "main.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Doc(DocFlags {
-          private: true,
-          json: false,
-          source_file: Some("path/to/module.js".to_string()),
-          filter: None,
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "main.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: None,
+          include: svec!["assets/**", "config.json"],
+          icon: None,
+          product_name: None,
+          product_version: None,
+          description: None,
+          copyright: None,
+          compress: false,
+          size_report: false,
         }),
         ..Flags::default()
       }
@@ -5259,35 +8026,65 @@ mod tests {
   }
 
   #[test]
-  fn inspect_default_host() {
-    let r = flags_from_vec(svec!["deno", "run", "--inspect", "foo.js"]);
+  fn compile_with_windows_metadata() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--target=x86_64-pc-windows-msvc",
+      "--icon=app.ico",
+      "--product-name=My App",
+      "--product-version=1.2.3",
+      "--description=Does things",
+      "--copyright=(c) Me",
+      "main.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "foo.js".to_string(),
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "main.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: Some("x86_64-pc-windows-msvc".to_string()),
+          include: vec![],
+          icon: Some(PathBuf::from("app.ico")),
+          product_name: Some("My App".to_string()),
+          product_version: Some("1.2.3".to_string()),
+          description: Some("Does things".to_string()),
+          copyright: Some("(c) Me".to_string()),
+          compress: false,
+          size_report: false,
         }),
-        inspect: Some("127.0.0.1:9229".parse().unwrap()),
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn compile() {
+  fn compile_with_compress_and_size_report() {
     let r = flags_from_vec(svec![
       "deno",
       "compile",
-      "https://deno.land/std/examples/colors.ts"
+      "--compress",
+      "--size-report",
+      "main.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Compile(CompileFlags {
-          source_file: "https://deno.land/std/examples/colors.ts".to_string(),
+          source_file: "main.ts".to_string(),
           output: None,
           args: vec![],
           target: None,
+          include: vec![],
+          icon: None,
+          product_name: None,
+          product_version: None,
+          description: None,
+          copyright: None,
+          compress: true,
+          size_report: true,
         }),
         ..Flags::default()
       }
@@ -5306,6 +8103,14 @@ mod tests {
           output: Some(PathBuf::from("colors")),
           args: svec!["foo", "bar"],
           target: None,
+          include: vec![],
+          icon: None,
+          product_name: None,
+          product_version: None,
+          description: None,
+          copyright: None,
+          compress: false,
+          size_report: false,
         }),
         import_map_path: Some("import_map.json".to_string()),
         no_remote: true,
@@ -5340,6 +8145,9 @@ mod tests {
           include: vec![r"^file:".to_string()],
           exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
           lcov: false,
+          html: None,
+          merge: false,
+          fail_under: None,
         }),
         ..Flags::default()
       }
@@ -5364,12 +8172,126 @@ mod tests {
           include: vec![r"^file:".to_string()],
           exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
           lcov: true,
+          html: None,
+          merge: false,
+          fail_under: None,
           output: Some(PathBuf::from("foo.lcov")),
         }),
         ..Flags::default()
       }
     );
   }
+
+  #[test]
+  fn coverage_with_fail_under() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--coverage-fail-under=line=80,branch=70.5",
+      "foo.json"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
+          files: vec![PathBuf::from("foo.json")],
+          ignore: vec![],
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          lcov: false,
+          html: None,
+          merge: false,
+          fail_under: Some(CoverageThreshold {
+            line: Some(80.0),
+            branch: Some(70.5),
+          }),
+          output: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--coverage-fail-under=101",
+      "foo.json"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn coverage_with_merge() {
+    let r = flags_from_vec(svec![
+      "deno", "coverage", "--merge", "cov1/", "cov2/", "-o", "merged/"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
+          files: vec![PathBuf::from("cov1/"), PathBuf::from("cov2/")],
+          ignore: vec![],
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          lcov: false,
+          html: None,
+          merge: true,
+          fail_under: None,
+          output: Some(PathBuf::from("merged/")),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "coverage", "--merge", "cov1/"]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--merge",
+      "--lcov",
+      "--output=merged/",
+      "cov1/"
+    ]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec!["deno", "coverage", "-o", "out", "cov1/"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn coverage_with_html() {
+    let r =
+      flags_from_vec(svec!["deno", "coverage", "--html=html_cov", "foo.json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
+          files: vec![PathBuf::from("foo.json")],
+          ignore: vec![],
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          lcov: false,
+          html: Some(PathBuf::from("html_cov")),
+          merge: false,
+          fail_under: None,
+          output: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--html=html_cov",
+      "--lcov",
+      "foo.json"
+    ]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn location_with_bad_scheme() {
     #[rustfmt::skip]
@@ -5449,6 +8371,8 @@ mod tests {
           specifiers: svec!["mod.ts"],
           force: false,
           output_path: None,
+          check: false,
+          update: false,
         }),
         ..Flags::default()
       }
@@ -5480,6 +8404,8 @@ mod tests {
           specifiers: svec!["mod.ts", "deps.test.ts"],
           force: true,
           output_path: Some(PathBuf::from("out_dir")),
+          check: false,
+          update: false,
         }),
         config_path: Some("deno.json".to_string()),
         import_map_path: Some("import_map.json".to_string()),
@@ -5490,6 +8416,43 @@ mod tests {
     );
   }
 
+  #[test]
+  fn vendor_check_and_update() {
+    let r = flags_from_vec(svec!["deno", "vendor", "--check", "mod.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Vendor(VendorFlags {
+          specifiers: svec!["mod.ts"],
+          force: false,
+          output_path: None,
+          check: true,
+          update: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "vendor", "--update", "mod.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Vendor(VendorFlags {
+          specifiers: svec!["mod.ts"],
+          force: false,
+          output_path: None,
+          check: false,
+          update: true,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "vendor", "--check", "--force", "mod.ts"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn task_subcommand() {
     let r = flags_from_vec(svec!["deno", "task", "build", "hello", "world",]);
@@ -5498,6 +8461,7 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Task(TaskFlags {
           task: "build".to_string(),
+          parallel: false,
         }),
         argv: svec!["hello", "world"],
         ..Flags::default()
@@ -5510,6 +8474,7 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Task(TaskFlags {
           task: "build".to_string(),
+          parallel: false,
         }),
         ..Flags::default()
       }
@@ -5533,8 +8498,9 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Task(TaskFlags {
           task: "build".to_string(),
+          parallel: false,
         }),
-        argv: svec!["--", "hello", "world"],
+        argv: svec!["hello", "world"],
         config_path: Some("deno.json".to_string()),
         ..Flags::default()
       }
@@ -5543,13 +8509,26 @@ mod tests {
 
   #[test]
   fn task_subcommand_double_hyphen_only() {
-    // edge case, but it should forward
     let r = flags_from_vec(svec!["deno", "task", "build", "--"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Task(TaskFlags {
           task: "build".to_string(),
+          parallel: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    // only the first separator is removed
+    let r = flags_from_vec(svec!["deno", "task", "build", "--", "--"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          task: "build".to_string(),
+          parallel: false,
         }),
         argv: svec!["--"],
         ..Flags::default()
@@ -5557,6 +8536,29 @@ mod tests {
     );
   }
 
+  #[test]
+  fn task_subcommand_parallel() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "task",
+      "--parallel",
+      "build",
+      "--",
+      "--release"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          task: "build".to_string(),
+          parallel: true,
+        }),
+        argv: svec!["--release"],
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn task_following_arg() {
     let r = flags_from_vec(svec!["deno", "task", "build", "-1", "--test"]);
@@ -5565,6 +8567,7 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Task(TaskFlags {
           task: "build".to_string(),
+          parallel: false,
         }),
         argv: svec!["-1", "--test"],
         ..Flags::default()
@@ -5580,6 +8583,7 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Task(TaskFlags {
           task: "".to_string(),
+          parallel: false,
         }),
         ..Flags::default()
       }
@@ -5594,6 +8598,7 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Task(TaskFlags {
           task: "".to_string(),
+          parallel: false,
         }),
         config_path: Some("deno.jsonc".to_string()),
         ..Flags::default()
@@ -5625,6 +8630,9 @@ mod tests {
           filter: Some("- foo".to_string()),
           include: Some(svec!["dir1/", "dir2/"]),
           ignore: vec![],
+          json: false,
+          baseline: None,
+          baseline_threshold: 10.0,
         }),
         unstable: true,
         location: Some(Url::parse("https://foo/").unwrap()),
@@ -5636,6 +8644,43 @@ mod tests {
     );
   }
 
+  #[test]
+  fn bench_json_and_baseline() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--json",
+      "--baseline=baseline.json",
+      "--baseline-threshold=2.5",
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bench(BenchFlags {
+          filter: None,
+          include: None,
+          ignore: vec![],
+          json: true,
+          baseline: Some(PathBuf::from("baseline.json")),
+          baseline_threshold: 2.5,
+        }),
+        no_prompt: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "bench", "--baseline-threshold=5"]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--baseline=baseline.json",
+      "--baseline-threshold=-1"
+    ]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn run_with_check() {
     let r = flags_from_vec(svec!["deno", "run", "--check", "script.ts",]);
@@ -1,6 +1,7 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
 use deno_core::url::Url;
+use deno_runtime::permissions::NetDescriptor;
 use std::net::IpAddr;
 use std::str::FromStr;
 
@@ -26,8 +27,20 @@ impl FromStr for BarePort {
   }
 }
 
+/// Returns if the host is a wildcard subdomain, like `*.example.com`, or an IP
+/// network in CIDR notation, like `10.0.0.0/8`.
+fn is_host_pattern(host_and_port: &str) -> bool {
+  host_and_port.starts_with("*.") || host_and_port.contains('/')
+}
+
 pub fn validator(host_and_port: &str) -> Result<(), String> {
-  if Url::parse(&format!("deno://{}", host_and_port)).is_ok()
+  if is_host_pattern(host_and_port) {
+    if NetDescriptor::parse(host_and_port).is_some() {
+      Ok(())
+    } else {
+      Err(format!("Bad host pattern: {}", host_and_port))
+    }
+  } else if Url::parse(&format!("deno://{}", host_and_port)).is_ok()
     || host_and_port.parse::<IpAddr>().is_ok()
     || host_and_port.parse::<BarePort>().is_ok()
   {
@@ -43,7 +56,15 @@ pub fn validator(host_and_port: &str) -> Result<(), String> {
 pub fn parse(paths: Vec<String>) -> clap::Result<Vec<String>> {
   let mut out: Vec<String> = vec![];
   for host_and_port in paths.iter() {
-    if Url::parse(&format!("deno://{}", host_and_port)).is_ok()
+    if is_host_pattern(host_and_port) {
+      if NetDescriptor::parse(host_and_port).is_none() {
+        return Err(clap::Error::raw(
+          clap::ErrorKind::InvalidValue,
+          format!("Bad host pattern: {}", host_and_port),
+        ));
+      }
+      out.push(host_and_port.to_owned())
+    } else if Url::parse(&format!("deno://{}", host_and_port)).is_ok()
       || host_and_port.parse::<IpAddr>().is_ok()
     {
       out.push(host_and_port.to_owned())
@@ -171,6 +192,18 @@ mod tests {
     assert_eq!(actual, expected);
   }
 
+  #[test]
+  fn parse_net_args_patterns() {
+    let entries = svec!["*.example.com", "*.deno.land:443", "10.0.0.0/8"];
+    let expected = svec!["*.example.com", "*.deno.land:443", "10.0.0.0/8"];
+    let actual = parse(entries).unwrap();
+    assert_eq!(actual, expected);
+
+    assert!(parse(svec!["10.0.0.0/33"]).is_err());
+    assert!(parse(svec!["deno.land/8"]).is_err());
+    assert!(parse(svec!["*.127.0.0.1"]).is_err());
+  }
+
   #[test]
   fn parse_net_args_ipv6() {
     let entries =
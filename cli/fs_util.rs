@@ -5,6 +5,7 @@ use deno_core::error::{uri_error, AnyError};
 pub use deno_core::normalize_path;
 use deno_core::ModuleSpecifier;
 use deno_runtime::deno_crypto::rand;
+use regex::Regex;
 use std::env::current_dir;
 use std::fs::OpenOptions;
 use std::io::{Error, Write};
@@ -236,6 +237,85 @@ where
   Ok(target_files)
 }
 
+/// Returns true if the path contains glob syntax.
+pub fn is_glob(path: &str) -> bool {
+  path.contains(|c| matches!(c, '*' | '?' | '[' | '{'))
+}
+
+/// Translates a glob to a regular expression matching whole paths with `/`
+/// separators. `**` matches any number of directories, `*` and `?` match
+/// within a path component, `[...]` matches a character class and `{a,b}`
+/// either alternative.
+pub fn glob_to_regex(glob: &str) -> Result<Regex, AnyError> {
+  let mut pattern = String::from("^");
+  let mut chars = glob.chars().peekable();
+  let mut alternatives = 0;
+  while let Some(c) = chars.next() {
+    match c {
+      '*' if chars.peek() == Some(&'*') => {
+        chars.next();
+        if chars.peek() == Some(&'/') {
+          chars.next();
+          pattern.push_str("(?:[^/]*/)*");
+        } else {
+          pattern.push_str(".*");
+        }
+      }
+      '*' => pattern.push_str("[^/]*"),
+      '?' => pattern.push_str("[^/]"),
+      '[' => {
+        pattern.push('[');
+        if chars.peek() == Some(&'!') {
+          chars.next();
+          pattern.push('^');
+        }
+        for c in chars.by_ref() {
+          if c == ']' {
+            break;
+          }
+          if c == '\\' || c == '[' {
+            pattern.push('\\');
+          }
+          pattern.push(c);
+        }
+        pattern.push(']');
+      }
+      '{' => {
+        alternatives += 1;
+        pattern.push_str("(?:");
+      }
+      ',' if alternatives > 0 => pattern.push('|'),
+      '}' if alternatives > 0 => {
+        alternatives -= 1;
+        pattern.push(')');
+      }
+      c => pattern.push_str(&regex::escape(&c.to_string())),
+    }
+  }
+  pattern.push('$');
+  Regex::new(&pattern).with_context(|| format!("Invalid glob \"{}\"", glob))
+}
+
+/// Collects the files matching `glob`, relative to the current directory
+/// unless it is absolute, sorted by path. Directories are walked from the
+/// longest leading path without glob syntax.
+pub fn expand_glob(
+  glob: &str,
+  ignore: &[PathBuf],
+) -> Result<Vec<PathBuf>, AnyError> {
+  let glob_path = normalize_path(&current_dir()?.join(glob));
+  let base = glob_path
+    .components()
+    .take_while(|component| !is_glob(&component.as_os_str().to_string_lossy()))
+    .collect::<PathBuf>();
+  let regex = glob_to_regex(&glob_path.to_string_lossy().replace('\\', "/"))?;
+  let mut files = collect_files(&[base], ignore, |path| {
+    regex.is_match(&path.to_string_lossy().replace('\\', "/"))
+  })?;
+  files.sort();
+  Ok(files)
+}
+
 /// Collects module specifiers that satisfy the given predicate as a file path, by recursively walking `include`.
 /// Specifiers that start with http and https are left intact.
 pub fn collect_specifiers<P>(
@@ -259,8 +339,18 @@ where
       continue;
     }
 
-    let p = normalize_path(&root_path.join(path));
-    if p.is_dir() {
+    let p = normalize_path(&root_path.join(&path));
+    if !p.exists() && is_glob(&path) {
+      // Globs are expanded here rather than by the shell, so that they work
+      // the same everywhere and can use `**`. Like the files found in
+      // directories, only those satisfying the predicate are kept.
+      prepared.extend(
+        expand_glob(&path, ignore)?
+          .iter()
+          .filter(|f| predicate(f))
+          .map(|f| ModuleSpecifier::from_file_path(f).unwrap()),
+      );
+    } else if p.is_dir() {
       let test_files = collect_files(&[p], ignore, &predicate).unwrap();
       let mut test_files_as_urls = test_files
         .iter()
@@ -663,6 +753,50 @@ mod tests {
     assert_eq!(result, expected);
   }
 
+  #[test]
+  fn test_expand_glob() {
+    let t = TempDir::new();
+    let root_dir_path = t.path().join("tests");
+    for file in [
+      "api_user.ts",
+      "api_user.js",
+      "user.ts",
+      "nested/api_order.ts",
+      "nested/deeper/api_item.ts",
+      "nested/deeper/item.ts",
+    ] {
+      let path = root_dir_path.join(file);
+      std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+      std::fs::write(path, "").unwrap();
+    }
+    let root_dir = canonicalize_path(&root_dir_path).unwrap();
+    let glob = |glob: &str| {
+      expand_glob(
+        &format!("{}/{}", root_dir_path.to_string_lossy(), glob),
+        &[],
+      )
+      .unwrap()
+    };
+
+    assert_eq!(
+      glob("**/api_*.ts"),
+      vec![
+        root_dir.join("api_user.ts"),
+        root_dir.join("nested/api_order.ts"),
+        root_dir.join("nested/deeper/api_item.ts"),
+      ]
+    );
+    assert_eq!(
+      glob("api_user.{js,ts}"),
+      vec![root_dir.join("api_user.js"), root_dir.join("api_user.ts")]
+    );
+    assert_eq!(
+      glob("nested/*/[!a]*.ts"),
+      vec![root_dir.join("nested/deeper/item.ts")]
+    );
+    assert_eq!(glob("?ser.ts"), vec![root_dir.join("user.ts")]);
+  }
+
   #[cfg(windows)]
   #[test]
   fn test_strip_unc_prefix() {
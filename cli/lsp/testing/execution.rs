@@ -5,32 +5,21 @@ use super::definitions::TestDefinitions;
 use super::lsp_custom;
 
 use crate::checksum;
-use crate::create_main_worker;
 use crate::emit;
 use crate::flags;
-use crate::located_script_name;
 use crate::lsp::client::Client;
 use crate::lsp::client::TestingNotification;
 use crate::lsp::config;
 use crate::lsp::logging::lsp_log;
-use crate::ops;
 use crate::proc_state;
 use crate::tools::test;
-use crate::tools::test::TestEventSender;
 
 use deno_core::anyhow::anyhow;
 use deno_core::error::AnyError;
 use deno_core::futures::future;
-use deno_core::futures::stream;
-use deno_core::futures::StreamExt;
 use deno_core::parking_lot::Mutex;
-use deno_core::serde_json::json;
-use deno_core::serde_json::Value;
 use deno_core::ModuleSpecifier;
-use deno_runtime::ops::io::Stdio;
-use deno_runtime::ops::io::StdioPipe;
 use deno_runtime::permissions::Permissions;
-use deno_runtime::tokio_util::run_basic;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::sync::Arc;
@@ -156,73 +145,18 @@ impl TestFilter {
     }
   }
 
-  /// return the filter as a JSON value, suitable for sending as a filter to the
-  /// test runner.
-  fn as_test_options(&self) -> Value {
-    let maybe_include: Option<Vec<String>> = self
+  /// Return the filter in the form the test runner takes it.
+  fn as_name_filter(&self) -> test::TestNameFilter {
+    let include: Option<Vec<String>> = self
       .maybe_include
       .as_ref()
       .map(|inc| inc.iter().map(|(_, td)| td.name.clone()).collect());
-    let maybe_exclude: Option<Vec<String>> = self
+    let exclude: Option<Vec<String>> = self
       .maybe_exclude
       .as_ref()
       .map(|ex| ex.iter().map(|(_, td)| td.name.clone()).collect());
-    json!({
-      "filter": {
-        "include": maybe_include,
-        "exclude": maybe_exclude,
-      }
-    })
-  }
-}
-
-async fn test_specifier(
-  ps: proc_state::ProcState,
-  permissions: Permissions,
-  specifier: ModuleSpecifier,
-  mode: test::TestMode,
-  sender: TestEventSender,
-  token: CancellationToken,
-  options: Option<Value>,
-) -> Result<(), AnyError> {
-  if !token.is_cancelled() {
-    let mut worker = create_main_worker(
-      &ps,
-      specifier.clone(),
-      permissions,
-      vec![ops::testing::init(sender.clone())],
-      Stdio {
-        stdin: StdioPipe::Inherit,
-        stdout: StdioPipe::File(sender.stdout()),
-        stderr: StdioPipe::File(sender.stderr()),
-      },
-    );
-
-    worker
-      .execute_script(
-        &located_script_name!(),
-        "Deno.core.enableOpCallTracing();",
-      )
-      .unwrap();
-
-    if mode != test::TestMode::Documentation {
-      worker.execute_side_module(&specifier).await?;
-    }
-
-    worker.dispatch_load_event(&located_script_name!())?;
-
-    let options = options.unwrap_or_else(|| json!({}));
-    let test_result = worker.js_runtime.execute_script(
-      &located_script_name!(),
-      &format!(r#"Deno[Deno.internal].runTests({})"#, json!(options)),
-    )?;
-
-    worker.js_runtime.resolve_value(test_result).await?;
-
-    worker.dispatch_unload_event(&located_script_name!())?;
+    test::TestNameFilter::Names { include, exclude }
   }
-
-  Ok(())
 }
 
 #[derive(Debug, Clone)]
@@ -318,47 +252,38 @@ impl TestRun {
     .await?;
 
     let (sender, mut receiver) = mpsc::unbounded_channel::<test::TestEvent>();
-    let sender = TestEventSender::new(sender);
 
-    let (concurrent_jobs, fail_fast) =
+    let mut options =
       if let flags::DenoSubcommand::Test(test_flags) = &ps.flags.subcommand {
-        (
-          test_flags.concurrent_jobs.into(),
-          test_flags.fail_fast.map(|count| count.into()),
-        )
+        test::TestRunOptions::new(&ps.flags, test_flags)
       } else {
         unreachable!("Should always be Test subcommand.");
       };
+    // Traces make op sanitizer failures much easier to act on in the editor.
+    options.trace_ops = true;
+    options.module_filters = self
+      .filters
+      .iter()
+      .map(|(specifier, filter)| (specifier.clone(), filter.as_name_filter()))
+      .collect();
+    let fail_fast = options.fail_fast;
 
-    let mut queue = self.queue.iter().collect::<Vec<&ModuleSpecifier>>();
+    let mut queue = self.queue.iter().cloned().collect::<Vec<_>>();
     queue.sort();
-
-    let join_handles = queue.into_iter().map(move |specifier| {
-      let specifier = specifier.clone();
-      let ps = ps.clone();
-      let permissions = permissions.clone();
-      let sender = sender.clone();
-      let options = self.filters.get(&specifier).map(|f| f.as_test_options());
-      let token = self.token.clone();
-
-      tokio::task::spawn_blocking(move || {
-        let future = test_specifier(
-          ps,
-          permissions,
-          specifier,
-          test::TestMode::Executable,
-          sender,
-          token,
-          options,
-        );
-
-        run_basic(future)
-      })
-    });
-
-    let join_stream = stream::iter(join_handles)
-      .buffer_unordered(concurrent_jobs)
-      .collect::<Vec<Result<Result<(), AnyError>, tokio::task::JoinError>>>();
+    let specifiers_with_mode = queue
+      .into_iter()
+      .map(|specifier| (specifier, test::TestMode::Executable))
+      .collect();
+
+    let cancellation = test::TestCancellation::new(self.token.clone());
+    let run = test::run_test_modules(
+      ps,
+      permissions,
+      specifiers_with_mode,
+      options,
+      sender,
+      cancellation.clone(),
+    );
 
     let mut reporter: Box<dyn test::TestReporter + Send> =
       Box::new(LspTestReporter::new(
@@ -369,6 +294,7 @@ impl TestRun {
       ));
 
     let handler = {
+      let cancellation = cancellation.clone();
       tokio::task::spawn(async move {
         let earlier = Instant::now();
         let mut summary = test::TestSummary::new();
@@ -389,16 +315,21 @@ impl TestRun {
             test::TestEvent::Wait(description) => {
               reporter.report_wait(&description);
             }
-            test::TestEvent::Output(output) => {
+            test::TestEvent::Output(output)
+            | test::TestEvent::CapturedOutput(_, output) => {
               reporter.report_output(&output);
             }
             test::TestEvent::Result(description, result, elapsed) => {
               match &result {
                 test::TestResult::Ok => summary.passed += 1,
                 test::TestResult::Ignored => summary.ignored += 1,
-                test::TestResult::Failed(error) => {
+                test::TestResult::Flaky(_) => summary.flaky += 1,
+                test::TestResult::Failed(failure)
+                | test::TestResult::Quarantined(failure) => {
                   summary.failed += 1;
-                  summary.failures.push((description.clone(), error.clone()));
+                  summary
+                    .failures
+                    .push((description.clone(), failure.clone()));
                 }
               }
 
@@ -427,7 +358,8 @@ impl TestRun {
           }
 
           if let Some(count) = fail_fast {
-            if summary.failed >= count {
+            if summary.failed >= count.get() {
+              cancellation.cancel();
               break;
             }
           }
@@ -450,13 +382,9 @@ impl TestRun {
       })
     };
 
-    let (join_results, result) = future::join(join_stream, handler).await;
-
-    // propagate any errors
-    for join_result in join_results {
-      join_result??;
-    }
+    let (run_result, result) = future::join(run, handler).await;
 
+    run_result?;
     result??;
 
     Ok(())
@@ -783,7 +711,7 @@ impl test::TestReporter for LspTestReporter {
     assert_eq!(stack.pop(), Some(desc.into()));
     self.current_origin = None;
     match result {
-      test::TestResult::Ok => {
+      test::TestResult::Ok | test::TestResult::Flaky(_) => {
         self.progress(lsp_custom::TestRunProgressMessage::Passed {
           test: desc.into(),
           duration: Some(elapsed as u32),
@@ -794,11 +722,19 @@ impl test::TestReporter for LspTestReporter {
           test: desc.into(),
         })
       }
-      test::TestResult::Failed(js_error) => {
-        let err_string = test::format_test_error(js_error);
+      test::TestResult::Failed(failure)
+      | test::TestResult::Quarantined(failure) => {
+        let err_string = test::format_test_error(&failure.error);
+        let mut messages = as_test_messages(err_string, false);
+        if let Some(diff) = &failure.diff {
+          for message in &mut messages {
+            message.expected_output = Some(diff.expected.clone());
+            message.actual_output = Some(diff.actual.clone());
+          }
+        }
         self.progress(lsp_custom::TestRunProgressMessage::Failed {
           test: desc.into(),
-          messages: as_test_messages(err_string, false),
+          messages,
           duration: Some(elapsed as u32),
         })
       }
@@ -871,6 +807,7 @@ impl test::TestReporter for LspTestReporter {
 mod tests {
   use super::*;
   use crate::lsp::testing::collectors::tests::new_span;
+  use deno_core::serde_json::json;
 
   #[test]
   fn test_as_queue_and_filters() {
@@ -945,13 +882,11 @@ mod tests {
       ]
     );
     assert_eq!(
-      filter.as_test_options(),
-      json!({
-        "filter": {
-          "include": null,
-          "exclude": vec!["test b"],
-        }
-      })
+      filter.as_name_filter(),
+      test::TestNameFilter::Names {
+        include: None,
+        exclude: Some(vec!["test b".to_string()]),
+      }
     );
   }
 }
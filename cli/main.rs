@@ -26,7 +26,10 @@ mod lockfile;
 mod logger;
 mod lsp;
 mod module_loader;
+mod module_map_cache;
 mod ops;
+mod permission_audit;
+mod permissions_file;
 mod proc_state;
 mod resolver;
 mod standalone;
@@ -53,8 +56,10 @@ use crate::flags::Flags;
 use crate::flags::FmtFlags;
 use crate::flags::FutureTypeCheckMode;
 use crate::flags::InfoFlags;
+use crate::flags::InitFlags;
 use crate::flags::InstallFlags;
 use crate::flags::LintFlags;
+use crate::flags::OutdatedFlags;
 use crate::flags::ReplFlags;
 use crate::flags::RunFlags;
 use crate::flags::TaskFlags;
@@ -71,6 +76,7 @@ use crate::proc_state::ProcState;
 use crate::resolver::ImportMapResolver;
 use crate::resolver::JsxResolver;
 use deno_ast::MediaType;
+use deno_core::anyhow::Context;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::error::JsError;
@@ -101,6 +107,7 @@ use std::env;
 use std::io::Read;
 use std::io::Write;
 use std::iter::once;
+use std::path::Path;
 use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -182,6 +189,7 @@ fn create_web_worker_callback(
       compiled_wasm_module_store: Some(ps.compiled_wasm_module_store.clone()),
       maybe_exit_code: args.maybe_exit_code,
       stdio: stdio.clone(),
+      limits: args.limits,
     };
 
     WebWorker::bootstrap_from_options(
@@ -269,6 +277,7 @@ pub fn create_main_worker(
     web_worker_preload_module_cb,
     maybe_inspector_server,
     should_break_on_first_statement,
+    should_wait_for_inspector_session: ps.flags.inspect_wait.is_some(),
     module_loader,
     get_error_class_fn: Some(&crate::errors::get_error_class_name),
     origin_storage_dir,
@@ -277,6 +286,10 @@ pub fn create_main_worker(
     shared_array_buffer_store: Some(ps.shared_array_buffer_store.clone()),
     compiled_wasm_module_store: Some(ps.compiled_wasm_module_store.clone()),
     stdio,
+    dump_op_metrics: ps.flags.dump_op_metrics.clone(),
+    // the flag is validated to fit in bytes
+    max_heap_size: ps.flags.max_heap_size.map(|mb| mb * 1024 * 1024),
+    module_map_data: module_map_cache::load(ps, &main_module),
   };
 
   MainWorker::bootstrap_from_options(main_module, permissions, options)
@@ -418,16 +431,32 @@ async fn compile_command(
   let output_path =
     tools::standalone::resolve_compile_executable_output_path(&compile_flags)?;
 
-  let graph = Arc::try_unwrap(
-    create_graph_and_maybe_check(module_specifier.clone(), &ps, debug).await?,
-  )
-  .map_err(|_| {
+  // workers are loaded from the embedded modules as well, so add the graphs
+  // of the workers the program creates until no new ones are found
+  let mut roots = vec![module_specifier.clone()];
+  let graph = loop {
+    let graph = create_graph_and_maybe_check(roots.clone(), &ps, debug).await?;
+    let worker_entry_points =
+      tools::standalone::find_worker_entry_points(&graph);
+    if worker_entry_points.is_empty() {
+      break graph;
+    }
+    roots.extend(worker_entry_points);
+  };
+  let graph = Arc::try_unwrap(graph).map_err(|_| {
     generic_error("There should only be one reference to ModuleGraph")
   })?;
 
   graph.valid().unwrap();
 
+  let module_sizes = if compile_flags.size_report {
+    tools::standalone::get_module_sizes(&graph)
+  } else {
+    Vec::new()
+  };
   let eszip = eszip::EszipV2::from_graph(graph, Default::default())?;
+  let eszip_archive =
+    tools::standalone::encode_eszip(eszip, compile_flags.compress)?;
 
   info!(
     "{} {}",
@@ -439,11 +468,28 @@ async fn compile_command(
   let original_binary =
     tools::standalone::get_base_binary(deno_dir, compile_flags.target.clone())
       .await?;
+  let original_binary = tools::standalone::apply_binary_metadata(
+    original_binary,
+    &compile_flags,
+    &output_path,
+  )?;
+
+  let included_files =
+    tools::standalone::collect_included_files(&compile_flags.include)?;
+
+  if compile_flags.size_report {
+    tools::standalone::print_size_report(
+      &module_sizes,
+      &eszip_archive,
+      &included_files,
+    )?;
+  }
 
   let final_bin = tools::standalone::create_standalone_binary(
     original_binary,
-    eszip,
+    eszip_archive,
     module_specifier.clone(),
+    included_files,
     run_flags,
     ps,
   )
@@ -495,8 +541,26 @@ async fn info_command(
     )
     .await;
 
+    let maybe_why = match &info_flags.why {
+      Some(why) => {
+        let target = tools::info::resolve_why_specifier(&graph, why)?;
+        let chains = tools::info::import_chains(&graph, &target);
+        Some((target, chains))
+      }
+      None => None,
+    };
+
     if info_flags.json {
-      write_json_to_stdout(&json!(graph))?;
+      let mut value = json!(graph);
+      tools::info::add_transitive_sizes(&graph, &mut value);
+      if let Some((target, chains)) = &maybe_why {
+        value["why"] = tools::info::why_json(target, chains);
+      }
+      write_json_to_stdout(&value)?;
+    } else if let Some((target, chains)) = &maybe_why {
+      write_to_stdout_ignore_sigpipe(
+        tools::info::format_chains(target, chains).as_bytes(),
+      )?;
     } else {
       write_to_stdout_ignore_sigpipe(graph.to_string().as_bytes())?;
     }
@@ -514,6 +578,7 @@ async fn install_command(
   let mut preload_flags = flags.clone();
   preload_flags.inspect = None;
   preload_flags.inspect_brk = None;
+  preload_flags.inspect_wait = None;
   let permissions =
     Permissions::from_options(&preload_flags.permissions_options());
   let ps = ProcState::build(Arc::new(preload_flags)).await?;
@@ -531,10 +596,22 @@ async fn install_command(
   Ok(0)
 }
 
+async fn init_command(
+  flags: Flags,
+  init_flags: InitFlags,
+) -> Result<i32, AnyError> {
+  tools::init::init_project(flags, init_flags).await?;
+  Ok(0)
+}
+
 async fn uninstall_command(
   uninstall_flags: UninstallFlags,
 ) -> Result<i32, AnyError> {
-  tools::installer::uninstall(uninstall_flags.name, uninstall_flags.root)?;
+  if uninstall_flags.list {
+    tools::installer::list(uninstall_flags.root).await?;
+  } else if let Some(name) = uninstall_flags.name {
+    tools::installer::uninstall(name, uninstall_flags.root)?;
+  }
   Ok(0)
 }
 
@@ -556,6 +633,14 @@ async fn lint_command(
   Ok(0)
 }
 
+async fn outdated_command(
+  flags: Flags,
+  outdated_flags: OutdatedFlags,
+) -> Result<i32, AnyError> {
+  tools::outdated::outdated(flags, outdated_flags).await?;
+  Ok(0)
+}
+
 async fn cache_command(
   flags: Flags,
   cache_flags: CacheFlags,
@@ -587,25 +672,82 @@ async fn check_command(
   flags: Flags,
   check_flags: CheckFlags,
 ) -> Result<i32, AnyError> {
-  // NOTE(bartlomieju): currently just an alias for `deno cache`, but
-  // it will be changed in Deno 2.0.
   let mut flags = flags.clone();
 
   // In `deno check` the default mode is to check only
-  // local modules, with `--remote` we check remote modules too.
+  // local modules, with `--all` we check remote modules too.
   flags.type_check_mode = if check_flags.remote {
     TypeCheckMode::All
   } else {
     TypeCheckMode::Local
   };
+  let lib = if flags.unstable {
+    emit::TypeLib::UnstableDenoWindow
+  } else {
+    emit::TypeLib::DenoWindow
+  };
+  let ps = ProcState::build(Arc::new(flags)).await?;
 
-  cache_command(
-    flags,
-    CacheFlags {
-      files: check_flags.files,
-    },
+  let specifiers = if check_flags.files.is_empty() {
+    get_project_check_specifiers(&ps)?
+  } else {
+    check_flags
+      .files
+      .iter()
+      .map(|file| resolve_url_or_path(file))
+      .collect::<Result<Vec<_>, _>>()?
+  };
+
+  ps.prepare_module_load(
+    specifiers,
+    false,
+    lib,
+    Permissions::allow_all(),
+    Permissions::allow_all(),
+    false,
+  )
+  .await?;
+
+  Ok(0)
+}
+
+/// Collects the modules of the project, included by the `"files"`
+/// configuration, that `deno check` type-checks when no files are passed.
+fn get_project_check_specifiers(
+  ps: &ProcState,
+) -> Result<Vec<ModuleSpecifier>, AnyError> {
+  let maybe_files_config = match &ps.maybe_config_file {
+    Some(config_file) => config_file.to_files_config()?,
+    None => None,
+  };
+  let files_config = match maybe_files_config {
+    Some(files_config) if !files_config.include.is_empty() => files_config,
+    _ => {
+      return Err(generic_error(
+        "No files to check. Pass the files to check or set \"files.include\" in the configuration file.",
+      ))
+    }
+  };
+  let to_file_paths = |specifiers: &[ModuleSpecifier]| {
+    specifiers
+      .iter()
+      .filter_map(|s| fs_util::specifier_to_file_path(s).ok())
+      .collect::<Vec<_>>()
+  };
+  let files = fs_util::collect_files(
+    &to_file_paths(&files_config.include),
+    &to_file_paths(&files_config.exclude),
+    fs_util::is_supported_ext,
+  )?;
+  if files.is_empty() {
+    return Err(generic_error("No target files found."));
+  }
+  Ok(
+    files
+      .into_iter()
+      .map(|file| ModuleSpecifier::from_file_path(file).unwrap())
+      .collect(),
   )
-  .await
 }
 
 async fn eval_command(
@@ -614,8 +756,11 @@ async fn eval_command(
 ) -> Result<i32, AnyError> {
   // deno_graph works off of extensions for local files to determine the media
   // type, and so our "fake" specifier needs to have the proper extension.
-  let main_module =
-    resolve_url_or_path(&format!("./$deno$eval.{}", eval_flags.ext)).unwrap();
+  let file_name = format!("$deno$eval.{}", eval_flags.ext);
+  let main_module = match &eval_flags.cwd_url {
+    Some(cwd_url) => resolve_eval_base(cwd_url)?.join(&file_name)?,
+    None => resolve_url_or_path(&format!("./{}", file_name)).unwrap(),
+  };
   let permissions = Permissions::from_options(&flags.permissions_options());
   let ps = ProcState::build(Arc::new(flags)).await?;
   let mut worker = create_main_worker(
@@ -634,7 +779,7 @@ async fn eval_command(
   .into_bytes();
 
   let file = File {
-    local: main_module.clone().to_file_path().unwrap(),
+    local: main_module.to_file_path().unwrap_or_default(),
     maybe_types: None,
     media_type: MediaType::Unknown,
     source: Arc::new(String::from_utf8(source_code)?),
@@ -656,8 +801,20 @@ async fn eval_command(
   Ok(0)
 }
 
+/// Resolves the `--cwd-url` of `deno eval`, which is either a URL or a path
+/// relative to the current directory, to the URL of a directory.
+fn resolve_eval_base(cwd_url: &str) -> Result<ModuleSpecifier, AnyError> {
+  let mut base = resolve_url_or_path(cwd_url)
+    .with_context(|| format!("Invalid --cwd-url: {}", cwd_url))?;
+  if !base.path().ends_with('/') {
+    let path = format!("{}/", base.path());
+    base.set_path(&path);
+  }
+  Ok(base)
+}
+
 async fn create_graph_and_maybe_check(
-  root: ModuleSpecifier,
+  roots: Vec<ModuleSpecifier>,
   ps: &ProcState,
   debug: bool,
 ) -> Result<Arc<deno_graph::ModuleGraph>, AnyError> {
@@ -688,7 +845,10 @@ async fn create_graph_and_maybe_check(
   };
   let graph = Arc::new(
     deno_graph::create_graph(
-      vec![(root, deno_graph::ModuleKind::Esm)],
+      roots
+        .into_iter()
+        .map(|root| (root, deno_graph::ModuleKind::Esm))
+        .collect(),
       false,
       maybe_imports,
       &mut cache,
@@ -755,12 +915,15 @@ async fn create_graph_and_maybe_check(
   Ok(graph)
 }
 
-fn bundle_module_graph(
+fn bundle_options(
   graph: &deno_graph::ModuleGraph,
   ps: &ProcState,
   flags: &Flags,
-) -> Result<(String, Option<String>), AnyError> {
-  info!("{} {}", colors::green("Bundle"), graph.roots[0].0);
+  external: Vec<String>,
+) -> Result<emit::BundleOptions, AnyError> {
+  for (root, _) in &graph.roots {
+    info!("{} {}", colors::green("Bundle"), root);
+  }
 
   let (ts_config, maybe_ignored_options) = emit::get_ts_config(
     emit::ConfigType::Bundle,
@@ -773,34 +936,113 @@ fn bundle_module_graph(
     }
   }
 
-  emit::bundle(
-    graph,
-    emit::BundleOptions {
-      bundle_type: emit::BundleType::Module,
-      ts_config,
-      emit_ignore_directives: true,
-    },
-  )
+  Ok(emit::BundleOptions {
+    bundle_type: emit::BundleType::Module,
+    ts_config,
+    emit_ignore_directives: true,
+    external,
+  })
+}
+
+fn write_bundle_file(
+  out_file: &Path,
+  bundle_emit: &str,
+  maybe_bundle_map: Option<String>,
+) -> Result<(), AnyError> {
+  let output_bytes = bundle_emit.as_bytes();
+  let output_len = output_bytes.len();
+  fs_util::write_file(out_file, output_bytes, 0o644)?;
+  info!(
+    "{} {:?} ({})",
+    colors::green("Emit"),
+    out_file,
+    colors::gray(display::human_size(output_len as f64))
+  );
+  if let Some(bundle_map) = maybe_bundle_map {
+    let map_bytes = bundle_map.as_bytes();
+    let map_len = map_bytes.len();
+    let ext = if let Some(curr_ext) = out_file.extension() {
+      format!("{}.map", curr_ext.to_string_lossy())
+    } else {
+      "map".to_string()
+    };
+    let map_out_file = out_file.with_extension(ext);
+    fs_util::write_file(&map_out_file, map_bytes, 0o644)?;
+    info!(
+      "{} {:?} ({})",
+      colors::green("Emit"),
+      map_out_file,
+      colors::gray(display::human_size(map_len as f64))
+    );
+  }
+  Ok(())
+}
+
+/// Writes the bundles of multiple entry points and a `manifest.json`, which
+/// describes the entry point, modules and imported chunks of every file.
+fn write_bundle_chunks(
+  out_dir: &Path,
+  chunks: Vec<emit::BundleChunk>,
+) -> Result<(), AnyError> {
+  std::fs::create_dir_all(out_dir).with_context(|| {
+    format!("Failed to create directory: {}", out_dir.display())
+  })?;
+  let mut manifest = serde_json::Map::new();
+  for chunk in chunks {
+    manifest.insert(
+      chunk.file_name.clone(),
+      json!({
+        "entryPoint": chunk.entry_point.map(|s| s.to_string()),
+        "imports": chunk.imports,
+        "modules": chunk
+          .modules
+          .iter()
+          .map(|s| s.to_string())
+          .collect::<Vec<_>>(),
+      }),
+    );
+    write_bundle_file(
+      &out_dir.join(&chunk.file_name),
+      &chunk.code,
+      chunk.maybe_map,
+    )?;
+  }
+  let manifest_file = out_dir.join("manifest.json");
+  let manifest = serde_json::to_string_pretty(&manifest)? + "\n";
+  fs_util::write_file(&manifest_file, manifest.as_bytes(), 0o644)?;
+  info!("{} {:?}", colors::green("Emit"), manifest_file);
+  Ok(())
 }
 
 async fn bundle_command(
   flags: Flags,
   bundle_flags: BundleFlags,
 ) -> Result<i32, AnyError> {
+  if !bundle_flags.entries.is_empty() && bundle_flags.out_dir.is_none() {
+    return Err(generic_error(
+      "Bundling multiple entry points requires --out-dir.",
+    ));
+  }
   let debug = flags.log_level == Some(log::Level::Debug);
   let flags = Arc::new(flags);
   let resolver = |_| {
     let flags = flags.clone();
-    let source_file1 = bundle_flags.source_file.clone();
+    let source_files = once(&bundle_flags.source_file)
+      .chain(&bundle_flags.entries)
+      .cloned()
+      .collect::<Vec<_>>();
     let source_file2 = bundle_flags.source_file.clone();
     async move {
-      let module_specifier = resolve_url_or_path(&source_file1)?;
+      let module_specifiers = source_files
+        .iter()
+        .map(|source_file| resolve_url_or_path(source_file))
+        .collect::<Result<Vec<_>, _>>()?;
 
       debug!(">>>>> bundle START");
       let ps = ProcState::build(flags).await?;
 
       let graph =
-        create_graph_and_maybe_check(module_specifier, &ps, debug).await?;
+        create_graph_and_maybe_check(module_specifiers, &ps, debug).await?;
 
       let mut paths_to_watch: Vec<PathBuf> = graph
         .specifiers()
@@ -836,38 +1078,23 @@ async fn bundle_command(
 
   let operation = |(ps, graph): (ProcState, Arc<deno_graph::ModuleGraph>)| {
     let out_file = bundle_flags.out_file.clone();
+    let out_dir = bundle_flags.out_dir.clone();
+    let external = bundle_flags.external.clone();
     async move {
+      let options = bundle_options(graph.as_ref(), &ps, &ps.flags, external)?;
+      if let Some(out_dir) = out_dir {
+        let chunks = emit::bundle_chunks(graph.as_ref(), options)?;
+        debug!(">>>>> bundle END");
+        write_bundle_chunks(&out_dir, chunks)?;
+        return Ok(());
+      }
+
       let (bundle_emit, maybe_bundle_map) =
-        bundle_module_graph(graph.as_ref(), &ps, &ps.flags)?;
+        emit::bundle(graph.as_ref(), options)?;
       debug!(">>>>> bundle END");
 
       if let Some(out_file) = out_file.as_ref() {
-        let output_bytes = bundle_emit.as_bytes();
-        let output_len = output_bytes.len();
-        fs_util::write_file(out_file, output_bytes, 0o644)?;
-        info!(
-          "{} {:?} ({})",
-          colors::green("Emit"),
-          out_file,
-          colors::gray(display::human_size(output_len as f64))
-        );
-        if let Some(bundle_map) = maybe_bundle_map {
-          let map_bytes = bundle_map.as_bytes();
-          let map_len = map_bytes.len();
-          let ext = if let Some(curr_ext) = out_file.extension() {
-            format!("{}.map", curr_ext.to_string_lossy())
-          } else {
-            "map".to_string()
-          };
-          let map_out_file = out_file.with_extension(ext);
-          fs_util::write_file(&map_out_file, map_bytes, 0o644)?;
-          info!(
-            "{} {:?} ({})",
-            colors::green("Emit"),
-            map_out_file,
-            colors::gray(display::human_size(map_len as f64))
-          );
-        }
+        write_bundle_file(out_file, &bundle_emit, maybe_bundle_map)?;
       } else {
         println!("{}", bundle_emit);
       }
@@ -877,15 +1104,10 @@ async fn bundle_command(
   };
 
   if flags.watch.is_some() {
-    file_watcher::watch_func(
-      resolver,
-      operation,
-      file_watcher::PrintConfig {
-        job_name: "Bundle".to_string(),
-        clear_screen: !flags.no_clear_screen,
-      },
-    )
-    .await?;
+    let mut watcher_config =
+      file_watcher::WatcherConfig::new("Bundle", &flags)?;
+    watcher_config.report_elapsed = true;
+    file_watcher::watch_func(resolver, operation, watcher_config).await?;
   } else {
     let module_graph =
       if let ResolutionResult::Restart { result, .. } = resolver(None).await {
@@ -1172,15 +1394,162 @@ async fn run_with_watch(flags: Flags, script: String) -> Result<i32, AnyError> {
   file_watcher::watch_func(
     resolver,
     operation,
-    file_watcher::PrintConfig {
-      job_name: "Process".to_string(),
-      clear_screen: !flags.no_clear_screen,
-    },
+    file_watcher::WatcherConfig::new("Process", &flags)?,
   )
   .await?;
   Ok(0)
 }
 
+/// Keeps the program running across file changes: changed modules are
+/// reloaded in place and an "hmr" event is dispatched on `globalThis`. Falls
+/// back to restarting the program when a change can't be applied in place.
+async fn run_with_hot_reload(
+  flags: Flags,
+  script: String,
+) -> Result<i32, AnyError> {
+  let flags = Arc::new(flags);
+  let main_module = resolve_url_or_path(&script)?;
+  let lib = if flags.unstable {
+    emit::TypeLib::UnstableDenoWindow
+  } else {
+    emit::TypeLib::DenoWindow
+  };
+  let extra_paths = flags.watch.clone().unwrap_or_default();
+  let mut watcher = file_watcher::ChangeWatcher::new(
+    &[PathBuf::from(&script)],
+    file_watcher::WatcherConfig::new("Process", &flags)?,
+  )?;
+
+  info!("{} Process started.", colors::intense_blue("Watcher"));
+  loop {
+    let ps = ProcState::build(flags.clone()).await?;
+    let permissions = Permissions::from_options(&flags.permissions_options());
+    let mut worker = create_main_worker(
+      &ps,
+      main_module.clone(),
+      permissions,
+      vec![],
+      Default::default(),
+    );
+
+    let result = async {
+      if flags.compat {
+        worker.execute_side_module(&compat::GLOBAL_URL).await?;
+      }
+      worker.execute_main_module(&main_module).await?;
+      worker.dispatch_load_event(&located_script_name!())
+    }
+    .await;
+    let mut paths_to_watch = ps.local_module_paths();
+    paths_to_watch.extend(extra_paths.iter().cloned());
+    watcher.watch(&paths_to_watch)?;
+    if let Err(err) = result {
+      eprintln!("{}: {}", colors::red_bold("error"), err);
+      info!(
+        "{} Process failed. Restarting on file change...",
+        colors::intense_blue("Watcher"),
+      );
+      watcher.next().await;
+      print_hot_restart(&flags);
+      continue;
+    }
+
+    // Apply changes in place until one of them requires a restart.
+    loop {
+      let maybe_changed = tokio::select! {
+        result = worker.run_event_loop(false) => {
+          if let Err(err) = result {
+            eprintln!("{}: {}", colors::red_bold("error"), err);
+          }
+          info!(
+            "{} Process finished. Reloading on file change...",
+            colors::intense_blue("Watcher"),
+          );
+          None
+        }
+        changed = watcher.next() => Some(changed),
+      };
+      let changed = match maybe_changed {
+        Some(changed) => changed,
+        None => watcher.next().await,
+      };
+      let changed = match changed {
+        Some(changed) => changed,
+        None => break,
+      };
+      let changed = changed
+        .iter()
+        .filter_map(|path| ModuleSpecifier::from_file_path(path).ok())
+        .collect::<Vec<_>>();
+      match hot_reload(&ps, &mut worker, &main_module, lib.clone(), &changed)
+        .await
+      {
+        Ok(true) => {
+          info!("{} Modules reloaded.", colors::intense_blue("Watcher"));
+          let mut paths_to_watch = ps.local_module_paths();
+          paths_to_watch.extend(extra_paths.iter().cloned());
+          watcher.watch(&paths_to_watch)?;
+        }
+        Ok(false) => break,
+        Err(err) => {
+          eprintln!("{}: {}", colors::red_bold("error"), err);
+          break;
+        }
+      }
+    }
+
+    worker.dispatch_unload_event(&located_script_name!())?;
+    print_hot_restart(&flags);
+  }
+}
+
+fn print_hot_restart(flags: &Flags) {
+  if !flags.no_clear_screen {
+    eprint!("\x1B[2J\x1B[1;1H");
+  }
+  info!(
+    "{} File change detected! Restarting!",
+    colors::intense_blue("Watcher"),
+  );
+}
+
+/// Reloads the loaded modules among `changed` and notifies the program.
+/// Returns `false` if none of them is loaded, in which case the program has
+/// to be restarted to observe the change.
+async fn hot_reload(
+  ps: &ProcState,
+  worker: &mut MainWorker,
+  main_module: &ModuleSpecifier,
+  lib: emit::TypeLib,
+  changed: &[ModuleSpecifier],
+) -> Result<bool, AnyError> {
+  ps.prepare_module_load(
+    vec![main_module.clone()],
+    false,
+    lib,
+    Permissions::allow_all(),
+    Permissions::allow_all(),
+    true,
+  )
+  .await?;
+  let reloaded = worker.js_runtime.reload_modules(changed).await?;
+  if reloaded.is_empty() {
+    return Ok(false);
+  }
+  let detail = json!({
+    "changed": changed.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+    "reloaded": reloaded.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+  });
+  worker.execute_script(
+    &located_script_name!(),
+    &format!(
+      "globalThis.dispatchEvent(new CustomEvent(\"hmr\", {{ detail: {} }}));",
+      detail
+    ),
+  )?;
+  Ok(true)
+}
+
 async fn run_command(
   flags: Flags,
   run_flags: RunFlags,
@@ -1191,6 +1560,9 @@ async fn run_command(
   }
 
   if flags.watch.is_some() {
+    if flags.hot {
+      return run_with_hot_reload(flags, run_flags.script).await;
+    }
     return run_with_watch(flags, run_flags.script).await;
   }
 
@@ -1212,13 +1584,11 @@ async fn run_command(
 
   let mut maybe_coverage_collector =
     if let Some(ref coverage_dir) = ps.coverage_dir {
-      let session = worker.create_inspector_session().await;
-
       let coverage_dir = PathBuf::from(coverage_dir);
       let mut coverage_collector =
-        tools::coverage::CoverageCollector::new(coverage_dir, session);
-      worker
-        .with_event_loop(coverage_collector.start_collecting().boxed_local())
+        tools::coverage::CoverageCollector::new(coverage_dir);
+      coverage_collector
+        .start_collecting(&mut worker.js_runtime)
         .await?;
       Some(coverage_collector)
     } else {
@@ -1259,19 +1629,64 @@ async fn run_command(
   }
 
   worker.dispatch_load_event(&located_script_name!())?;
-  worker
-    .run_event_loop(maybe_coverage_collector.is_none())
-    .await?;
+  run_event_loop_with_heap_snapshot_signal(
+    &mut worker,
+    maybe_coverage_collector.is_none(),
+  )
+  .await?;
   worker.dispatch_unload_event(&located_script_name!())?;
 
   if let Some(coverage_collector) = maybe_coverage_collector.as_mut() {
-    worker
-      .with_event_loop(coverage_collector.stop_collecting().boxed_local())
+    coverage_collector
+      .stop_collecting(&mut worker.js_runtime)
       .await?;
   }
+
+  let module_map_data = worker.js_runtime.serialize_module_map();
+  if let Err(err) = module_map_cache::save(&ps, &main_module, &module_map_data)
+  {
+    debug!("Failed to write module map cache: {}", err);
+  }
   Ok(worker.get_exit_code())
 }
 
+/// Runs the event loop, writing a heap snapshot to the current directory
+/// whenever the process receives SIGUSR2.
+#[cfg(unix)]
+async fn run_event_loop_with_heap_snapshot_signal(
+  worker: &mut MainWorker,
+  wait_for_inspector: bool,
+) -> Result<(), AnyError> {
+  use std::time::SystemTime;
+  use std::time::UNIX_EPOCH;
+  use tokio::signal::unix::signal;
+  use tokio::signal::unix::SignalKind;
+
+  let mut sigusr2 = signal(SignalKind::user_defined2())?;
+  loop {
+    tokio::select! {
+      result = worker.run_event_loop(wait_for_inspector) => return result,
+      _ = sigusr2.recv() => {}
+    }
+    let path = format!(
+      "Heap.{}.{}.heapsnapshot",
+      SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis(),
+      std::process::id()
+    );
+    let snapshot = worker.js_runtime.heap_snapshot().await?;
+    std::fs::write(&path, snapshot)?;
+    eprintln!("Wrote heap snapshot to {}", path);
+  }
+}
+
+#[cfg(not(unix))]
+async fn run_event_loop_with_heap_snapshot_signal(
+  worker: &mut MainWorker,
+  wait_for_inspector: bool,
+) -> Result<(), AnyError> {
+  worker.run_event_loop(wait_for_inspector).await
+}
+
 async fn task_command(
   flags: Flags,
   task_flags: TaskFlags,
@@ -1319,7 +1734,19 @@ async fn test_command(
   if flags.watch.is_some() {
     tools::test::run_tests_with_watch(flags, test_flags).await?;
   } else {
-    tools::test::run_tests(flags, test_flags).await?;
+    let coverage_check = flags
+      .coverage_dir
+      .clone()
+      .zip(test_flags.coverage_fail_under.clone());
+    tools::test::run_tests(flags.clone(), test_flags).await?;
+    if let Some((coverage_dir, threshold)) = coverage_check {
+      tools::coverage::check_coverage(
+        flags,
+        PathBuf::from(coverage_dir),
+        threshold,
+      )
+      .await?;
+    }
   }
 
   Ok(0)
@@ -1414,6 +1841,9 @@ fn get_subcommand(
     DenoSubcommand::Info(info_flags) => {
       info_command(flags, info_flags).boxed_local()
     }
+    DenoSubcommand::Init(init_flags) => {
+      init_command(flags, init_flags).boxed_local()
+    }
     DenoSubcommand::Install(install_flags) => {
       install_command(flags, install_flags).boxed_local()
     }
@@ -1424,6 +1854,9 @@ fn get_subcommand(
     DenoSubcommand::Lint(lint_flags) => {
       lint_command(flags, lint_flags).boxed_local()
     }
+    DenoSubcommand::Outdated(outdated_flags) => {
+      outdated_command(flags, outdated_flags).boxed_local()
+    }
     DenoSubcommand::Repl(repl_flags) => {
       repl_command(flags, repl_flags).boxed_local()
     }
@@ -1508,7 +1941,9 @@ pub fn main() {
   let exit_code = async move {
     let standalone_res =
       match standalone::extract_standalone(args.clone()).await {
-        Ok(Some((metadata, eszip))) => standalone::run(eszip, metadata).await,
+        Ok(Some((metadata, eszip, virtual_fs))) => {
+          standalone::run(eszip, metadata, virtual_fs).await
+        }
         Ok(None) => Ok(()),
         Err(err) => Err(err),
       };
@@ -1547,6 +1982,21 @@ pub fn main() {
       }
     }
 
+    if let Some(path) = &flags.permission_audit {
+      unwrap_or_exit(permission_audit::init(path));
+    }
+    if matches!(
+      flags.subcommand,
+      DenoSubcommand::Bench(_)
+        | DenoSubcommand::Compile(_)
+        | DenoSubcommand::Eval(_)
+        | DenoSubcommand::Repl(_)
+        | DenoSubcommand::Run(_)
+        | DenoSubcommand::Test(_)
+    ) {
+      unwrap_or_exit(permissions_file::load(&mut flags));
+    }
+
     let exit_code = get_subcommand(flags).await;
 
     exit_code
@@ -1,7 +1,9 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
+use crate::checksum;
 use crate::emit::TypeLib;
 use crate::proc_state::ProcState;
+use crate::version;
 
 use deno_core::error::AnyError;
 use deno_core::futures::future::FutureExt;
@@ -11,6 +13,7 @@ use deno_core::ModuleSpecifier;
 use deno_core::OpState;
 use deno_runtime::permissions::Permissions;
 use std::cell::RefCell;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::rc::Rc;
 use std::str;
@@ -54,6 +57,13 @@ impl CliModuleLoader {
   }
 }
 
+/// Name of the file in the gen cache holding V8 code cache for `specifier`.
+/// The data is prefixed with the hash of the source it was created for.
+fn code_cache_filename(specifier: &ModuleSpecifier) -> PathBuf {
+  let key = checksum::gen(&[specifier.as_str(), version::deno().as_str()]);
+  PathBuf::from("code_cache").join(format!("{}.bin", key))
+}
+
 impl ModuleLoader for CliModuleLoader {
   fn resolve(
     &self,
@@ -119,4 +129,49 @@ impl ModuleLoader for CliModuleLoader {
     }
     .boxed_local()
   }
+
+  fn get_code_cache(
+    &self,
+    module_specifier: &ModuleSpecifier,
+    source_hash: u64,
+  ) -> Option<Vec<u8>> {
+    let filename = code_cache_filename(module_specifier);
+    let data = self.ps.dir.gen_cache.get(&filename).ok()?;
+    if data.len() < 8 || data[..8] != source_hash.to_le_bytes() {
+      return None;
+    }
+    Some(data[8..].to_vec())
+  }
+
+  fn discard_code_cache(
+    &self,
+    module_specifier: &ModuleSpecifier,
+    _source_hash: u64,
+  ) {
+    let path = self
+      .ps
+      .dir
+      .gen_cache
+      .location
+      .join(code_cache_filename(module_specifier));
+    let _ = std::fs::remove_file(path);
+  }
+
+  fn set_code_cache(
+    &self,
+    module_specifier: &ModuleSpecifier,
+    source_hash: u64,
+    data: &[u8],
+  ) {
+    let filename = code_cache_filename(module_specifier);
+    let mut contents = source_hash.to_le_bytes().to_vec();
+    contents.extend_from_slice(data);
+    if let Err(err) = self.ps.dir.gen_cache.set(&filename, &contents) {
+      log::debug!(
+        "Failed to write code cache for {}: {}",
+        module_specifier,
+        err
+      );
+    }
+  }
 }
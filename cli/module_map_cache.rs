@@ -0,0 +1,54 @@
+// Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
+
+//! Persists the module map of `deno run` in `$DENO_DIR` so that the next run
+//! of the same main module can skip resolving imports of unchanged modules.
+
+use crate::checksum;
+use crate::proc_state::ProcState;
+use crate::version;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::ModuleMapData;
+use deno_core::ModuleSpecifier;
+use std::path::PathBuf;
+
+/// Returns `None` when caching isn't safe: import maps and compat mode make
+/// resolution depend on more than the module sources.
+fn cache_file_path(
+  ps: &ProcState,
+  main_module: &ModuleSpecifier,
+) -> Option<PathBuf> {
+  if ps.maybe_import_map.is_some() || ps.flags.compat {
+    return None;
+  }
+  let key = checksum::gen(&[main_module.as_str(), version::deno().as_str()]);
+  Some(ps.dir.module_map_cache_dir().join(format!("{}.json", key)))
+}
+
+pub fn load(
+  ps: &ProcState,
+  main_module: &ModuleSpecifier,
+) -> Option<ModuleMapData> {
+  let path = cache_file_path(ps, main_module)?;
+  let text = std::fs::read_to_string(&path).ok()?;
+  match serde_json::from_str(&text) {
+    Ok(data) => Some(data),
+    Err(err) => {
+      log::debug!("Ignoring corrupt module map cache {:?}: {}", path, err);
+      let _ = std::fs::remove_file(&path);
+      None
+    }
+  }
+}
+
+pub fn save(
+  ps: &ProcState,
+  main_module: &ModuleSpecifier,
+  data: &ModuleMapData,
+) -> Result<(), AnyError> {
+  if let Some(path) = cache_file_path(ps, main_module) {
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    std::fs::write(path, serde_json::to_string(data)?)?;
+  }
+  Ok(())
+}
@@ -7,6 +7,7 @@ pub mod bench;
 mod errors;
 mod runtime_compiler;
 pub mod testing;
+pub mod virtual_fs;
 
 pub fn cli_exts(ps: ProcState, enable_compiler: bool) -> Vec<Extension> {
   if enable_compiler {
@@ -289,6 +289,7 @@ async fn op_emit(
         bundle_type: bundle.into(),
         ts_config,
         emit_ignore_directives: true,
+        external: Vec::new(),
       },
     )?;
     let mut files = HashMap::new();
@@ -3,27 +3,38 @@
 use crate::tools::test::TestEvent;
 use crate::tools::test::TestEventSender;
 
+use deno_core::anyhow::Context;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::op;
+use deno_core::serde_json;
 use deno_core::Extension;
 use deno_core::ModuleSpecifier;
 use deno_core::OpState;
 use deno_runtime::permissions::create_child_permissions;
 use deno_runtime::permissions::ChildPermissionsArg;
 use deno_runtime::permissions::Permissions;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use uuid::Uuid;
 
-pub fn init(sender: TestEventSender) -> Extension {
+pub fn init(sender: TestEventSender, update_snapshots: bool) -> Extension {
   Extension::builder()
     .ops(vec![
       op_pledge_test_permissions::decl(),
       op_restore_test_permissions::decl(),
       op_get_test_origin::decl(),
       op_dispatch_test_event::decl(),
+      op_assert_snapshot::decl(),
     ])
     .state(move |state| {
       state.put(sender.clone());
+      state.put(SnapshotState {
+        update: update_snapshots,
+        files: HashMap::new(),
+      });
       Ok(())
     })
     .build()
@@ -85,3 +96,79 @@ fn op_dispatch_test_event(
   sender.send(event).ok();
   Ok(())
 }
+
+/// Snapshot files loaded by the test module, keyed by path. Each file maps
+/// snapshot names to serialized values.
+struct SnapshotState {
+  update: bool,
+  files: HashMap<PathBuf, BTreeMap<String, String>>,
+}
+
+#[derive(Deserialize)]
+struct AssertSnapshotArgs {
+  origin: String,
+  name: String,
+  actual: String,
+}
+
+/// Returns the path of the snapshot file for a test module, which lives in a
+/// `__snapshots__` directory next to it.
+fn snapshot_path(origin: &str) -> Result<PathBuf, AnyError> {
+  let path = ModuleSpecifier::parse(origin)?
+    .to_file_path()
+    .map_err(|_| {
+      generic_error(format!(
+        "Snapshots are only supported for local test modules: {}",
+        origin
+      ))
+    })?;
+  let file_name = path.file_name().unwrap().to_string_lossy();
+  Ok(
+    path
+      .with_file_name("__snapshots__")
+      .join(format!("{}.snap", file_name)),
+  )
+}
+
+/// Compares `actual` to the stored snapshot, returning the stored value if it
+/// differs. With `--update-snapshots` the snapshot is written instead.
+#[op]
+fn op_assert_snapshot(
+  state: &mut OpState,
+  args: AssertSnapshotArgs,
+) -> Result<Option<String>, AnyError> {
+  let path = snapshot_path(&args.origin)?;
+  let snapshot_state = state.borrow_mut::<SnapshotState>();
+  if !snapshot_state.files.contains_key(&path) {
+    let snapshots = match std::fs::read_to_string(&path) {
+      Ok(text) => serde_json::from_str(&text).with_context(|| {
+        format!("Failed to parse snapshot file {}", path.display())
+      })?,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+      Err(err) => return Err(err.into()),
+    };
+    snapshot_state.files.insert(path.clone(), snapshots);
+  }
+  let update = snapshot_state.update;
+  let snapshots = snapshot_state.files.get_mut(&path).unwrap();
+
+  if update {
+    if snapshots.get(&args.name) != Some(&args.actual) {
+      snapshots.insert(args.name, args.actual);
+      std::fs::create_dir_all(path.parent().unwrap())?;
+      let mut text = serde_json::to_string_pretty(&snapshots)?;
+      text.push('\n');
+      std::fs::write(&path, text)?;
+    }
+    return Ok(None);
+  }
+
+  match snapshots.get(&args.name) {
+    Some(expected) if *expected == args.actual => Ok(None),
+    Some(expected) => Ok(Some(expected.clone())),
+    None => Err(generic_error(format!(
+      "Snapshot \"{}\" does not exist. Run \"deno test --update-snapshots\" to create it.",
+      args.name
+    ))),
+  }
+}
@@ -0,0 +1,200 @@
+// Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
+
+//! Serves the files embedded into standalone binaries with
+//! `deno compile --include` to `Deno.readFile`, `Deno.readTextFile` and
+//! `fetch` of `file:` URLs, before falling back to the real file system.
+
+use crate::fs_util::normalize_path;
+use deno_core::error::type_error;
+use deno_core::error::AnyError;
+use deno_core::op;
+use deno_core::url::Url;
+use deno_core::CancelFuture;
+use deno_core::CancelHandle;
+use deno_core::Extension;
+use deno_core::OpState;
+use deno_core::ResourceId;
+use deno_core::ZeroCopyBuf;
+use deno_runtime::deno_fetch;
+use deno_runtime::deno_fetch::reqwest;
+use deno_runtime::deno_fetch::CancelableResponseFuture;
+use deno_runtime::deno_fetch::FetchHandler;
+use deno_runtime::deno_fetch::FetchRequestBodyResource;
+use deno_runtime::permissions::Permissions;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// The embedded files, by their path relative to `root`, see
+/// `EmbeddedFile`.
+#[derive(Clone, Default)]
+pub struct VirtualFs {
+  root: PathBuf,
+  files: Arc<HashMap<String, Vec<u8>>>,
+}
+
+impl VirtualFs {
+  pub fn new(root: PathBuf, files: HashMap<String, Vec<u8>>) -> Self {
+    Self {
+      root,
+      files: Arc::new(files),
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.files.is_empty()
+  }
+
+  /// Returns the contents of an embedded file. Absolute paths are looked up
+  /// under the root, as the main module sees it. Relative paths are resolved
+  /// from the root rather than the current directory, so that the embedded
+  /// files are found wherever the binary is run from.
+  pub fn read(&self, path: &Path) -> Option<Vec<u8>> {
+    let path = normalize_path(path);
+    let relative_path = if path.is_absolute() {
+      path.strip_prefix(&self.root).ok()?
+    } else {
+      &path
+    };
+    self.files.get(&embedded_file_key(relative_path)).cloned()
+  }
+}
+
+/// Turns a path relative to the root into the key of its embedded file.
+pub fn embedded_file_key(relative_path: &Path) -> String {
+  relative_path
+    .components()
+    .map(|component| component.as_os_str().to_string_lossy())
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+pub fn init(virtual_fs: VirtualFs) -> Extension {
+  Extension::builder()
+    .middleware(|op| match op.name {
+      "op_readfile_sync" => op_readfile_sync::decl(),
+      "op_readfile_text_sync" => op_readfile_text_sync::decl(),
+      "op_readfile_async" => op_readfile_async::decl(),
+      "op_readfile_text_async" => op_readfile_text_async::decl(),
+      _ => op,
+    })
+    .state(move |state| {
+      state.borrow_mut::<deno_fetch::Options>().file_fetch_handler =
+        Rc::new(EmbeddedFetchHandler(virtual_fs.clone()));
+      state.put(virtual_fs.clone());
+      Ok(())
+    })
+    .build()
+}
+
+fn read_file(state: &mut OpState, path: &str) -> Result<Vec<u8>, AnyError> {
+  let path = Path::new(path);
+  state.borrow_mut::<Permissions>().check_read(path)?;
+  match state.borrow::<VirtualFs>().read(path) {
+    Some(data) => Ok(data),
+    None => Ok(std::fs::read(path)?),
+  }
+}
+
+async fn read_file_async(
+  state: Rc<RefCell<OpState>>,
+  path: String,
+  cancel_rid: Option<ResourceId>,
+) -> Result<Vec<u8>, AnyError> {
+  {
+    let mut state = state.borrow_mut();
+    state
+      .borrow_mut::<Permissions>()
+      .check_read(Path::new(&path))?;
+    if let Some(data) = state.borrow::<VirtualFs>().read(Path::new(&path)) {
+      return Ok(data);
+    }
+  }
+  let fut = tokio::task::spawn_blocking(move || -> Result<_, AnyError> {
+    Ok(std::fs::read(path)?)
+  });
+  if let Some(cancel_rid) = cancel_rid {
+    let cancel_handle = state
+      .borrow_mut()
+      .resource_table
+      .get::<CancelHandle>(cancel_rid);
+    if let Ok(cancel_handle) = cancel_handle {
+      return fut.or_cancel(cancel_handle).await??;
+    }
+  }
+  fut.await?
+}
+
+fn string_from_utf8_lossy(buf: Vec<u8>) -> String {
+  String::from_utf8(buf)
+    .unwrap_or_else(|err| String::from_utf8_lossy(err.as_bytes()).into_owned())
+}
+
+#[op]
+fn op_readfile_sync(
+  state: &mut OpState,
+  path: String,
+) -> Result<ZeroCopyBuf, AnyError> {
+  Ok(read_file(state, &path)?.into())
+}
+
+#[op]
+fn op_readfile_text_sync(
+  state: &mut OpState,
+  path: String,
+) -> Result<String, AnyError> {
+  Ok(string_from_utf8_lossy(read_file(state, &path)?))
+}
+
+#[op]
+async fn op_readfile_async(
+  state: Rc<RefCell<OpState>>,
+  path: String,
+  cancel_rid: Option<ResourceId>,
+) -> Result<ZeroCopyBuf, AnyError> {
+  Ok(read_file_async(state, path, cancel_rid).await?.into())
+}
+
+#[op]
+async fn op_readfile_text_async(
+  state: Rc<RefCell<OpState>>,
+  path: String,
+  cancel_rid: Option<ResourceId>,
+) -> Result<String, AnyError> {
+  Ok(string_from_utf8_lossy(
+    read_file_async(state, path, cancel_rid).await?,
+  ))
+}
+
+/// Responds to `fetch` of `file:` URLs with the embedded files, deferring to
+/// the file system for all other files.
+#[derive(Clone)]
+struct EmbeddedFetchHandler(VirtualFs);
+
+impl FetchHandler for EmbeddedFetchHandler {
+  fn fetch_file(
+    &self,
+    state: &mut OpState,
+    url: Url,
+  ) -> (
+    CancelableResponseFuture,
+    Option<FetchRequestBodyResource>,
+    Option<Rc<CancelHandle>>,
+  ) {
+    let data = match url.to_file_path().ok().and_then(|p| self.0.read(&p)) {
+      Some(data) => data,
+      None => return deno_fetch::FsFetchHandler.fetch_file(state, url),
+    };
+    let response = http::Response::builder()
+      .status(http::StatusCode::OK)
+      .body(reqwest::Body::from(data))
+      .map(reqwest::Response::from)
+      .map_err(|_| {
+        type_error("NetworkError when attempting to fetch resource.")
+      });
+    (Box::pin(async move { Ok(response) }), None, None)
+  }
+}
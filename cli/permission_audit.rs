@@ -0,0 +1,60 @@
+// Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
+
+//! Writes the permission checks of a run as NDJSON, see
+//! `--permission-audit`.
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use deno_runtime::permissions::set_permission_audit_handler;
+use deno_runtime::permissions::PermissionAuditEntry;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+fn to_ndjson_line(entry: &PermissionAuditEntry) -> String {
+  format!("{}\n", serde_json::to_string(entry).unwrap())
+}
+
+/// Creates the audit log file and reports every permission check to it.
+pub fn init(path: &Path) -> Result<(), AnyError> {
+  let file = File::create(path).with_context(|| {
+    format!("Failed to create permission audit log {}", path.display())
+  })?;
+  let file = Mutex::new(file);
+  set_permission_audit_handler(Box::new(move |entry| {
+    // each line is written at once so that checks of concurrent workers
+    // don't interleave
+    let _ = file.lock().write_all(to_ndjson_line(entry).as_bytes());
+  }));
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use deno_core::serde_json::json;
+
+  #[test]
+  fn ndjson_line() {
+    let line = to_ndjson_line(&PermissionAuditEntry {
+      timestamp: 1654000000000,
+      name: "read",
+      descriptor: Some("/etc/passwd"),
+      granted: false,
+      prompted: true,
+    });
+    assert!(line.ends_with('\n'));
+    assert_eq!(
+      serde_json::from_str::<serde_json::Value>(&line).unwrap(),
+      json!({
+        "timestamp": 1654000000000u64,
+        "name": "read",
+        "descriptor": "/etc/passwd",
+        "granted": false,
+        "prompted": true,
+      })
+    );
+  }
+}
@@ -0,0 +1,314 @@
+// Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
+
+//! Grants the permissions defined in files: the permission set passed to
+//! `--permission-set`, the `"permissions"` section of the config file passed
+//! to `--config`, and the permissions granted with the "always allow" answer
+//! of a permission prompt, which are remembered so that subsequent runs in
+//! the project don't prompt for them again.
+//!
+//! Permissions are only granted by files the user chose, so that a project
+//! can't grant itself permissions. The `"permissions"` section of a config
+//! file that was discovered in the project is ignored with a warning, and
+//! the remembered permissions are stored in `DENO_DIR`, keyed by the
+//! canonical path of the project directory, and only written once the user
+//! accepted a prompt.
+
+use crate::colors;
+use crate::config_file;
+use crate::config_file::PermissionsConfig;
+use crate::deno_dir::DenoDir;
+use crate::flags::DenoSubcommand;
+use crate::flags::Flags;
+use crate::fs_util::canonicalize_path;
+use crate::fs_util::specifier_to_file_path;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_runtime::permissions::set_always_allow_handler;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::Path;
+use std::path::PathBuf;
+
+#[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+struct PersistedPermissions {
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  read: Vec<PathBuf>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  write: Vec<PathBuf>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  net: Vec<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  env: Vec<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  run: Vec<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  ffi: Vec<PathBuf>,
+}
+
+impl PersistedPermissions {
+  fn read(path: &Path) -> Result<Self, AnyError> {
+    if !path.exists() {
+      return Ok(Self::default());
+    }
+    let text = std::fs::read_to_string(path)?;
+    serde_json::from_str(&text)
+      .with_context(|| format!("Failed to parse {}", path.display()))
+  }
+
+  fn write(&self, path: &Path) -> Result<(), AnyError> {
+    let text = format!("{}\n", serde_json::to_string_pretty(self)?);
+    if let Some(dir) = path.parent() {
+      std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed creating {}", dir.display()))?;
+    }
+    std::fs::write(path, text)
+      .with_context(|| format!("Failed writing {}", path.display()))
+  }
+
+  /// Adds the descriptor to the permission with the given name. Returns
+  /// `false` if it was already present.
+  fn add(&mut self, name: &str, descriptor: &str) -> bool {
+    match name {
+      "read" => add_entry(&mut self.read, PathBuf::from(descriptor)),
+      "write" => add_entry(&mut self.write, PathBuf::from(descriptor)),
+      "net" => add_entry(&mut self.net, descriptor.to_string()),
+      "env" => add_entry(&mut self.env, descriptor.to_string()),
+      "run" => add_entry(&mut self.run, descriptor.to_string()),
+      "ffi" => add_entry(&mut self.ffi, PathBuf::from(descriptor)),
+      _ => false,
+    }
+  }
+
+  fn apply(self, flags: &mut Flags) {
+    extend_allowlist(&mut flags.allow_read, self.read);
+    extend_allowlist(&mut flags.allow_write, self.write);
+    extend_allowlist(&mut flags.allow_net, self.net);
+    extend_allowlist(&mut flags.allow_env, self.env);
+    extend_allowlist(&mut flags.allow_run, self.run);
+    extend_allowlist(&mut flags.allow_ffi, self.ffi);
+  }
+}
+
+fn add_entry<T: PartialEq>(entries: &mut Vec<T>, entry: T) -> bool {
+  if entries.contains(&entry) {
+    false
+  } else {
+    entries.push(entry);
+    true
+  }
+}
+
+fn extend_allowlist<T: PartialEq>(
+  allowlist: &mut Option<Vec<T>>,
+  entries: Vec<T>,
+) {
+  if entries.is_empty() {
+    return;
+  }
+  match allowlist {
+    // an empty allowlist already grants everything
+    Some(list) if list.is_empty() => {}
+    Some(list) => {
+      for entry in entries {
+        add_entry(list, entry);
+      }
+    }
+    None => *allowlist = Some(entries),
+  }
+}
+
+fn merge_allowlist<T: PartialEq>(
+  allowlist: &mut Option<Vec<T>>,
+  grant: Option<Vec<T>>,
+) {
+  match grant {
+    Some(entries) if entries.is_empty() => *allowlist = Some(entries),
+    Some(entries) => extend_allowlist(allowlist, entries),
+    None => {}
+  }
+}
+
+fn apply_permissions_config(config: PermissionsConfig, flags: &mut Flags) {
+  merge_allowlist(&mut flags.allow_read, config.allow_read);
+  merge_allowlist(&mut flags.allow_write, config.allow_write);
+  merge_allowlist(&mut flags.allow_net, config.allow_net);
+  merge_allowlist(&mut flags.allow_env, config.allow_env);
+  merge_allowlist(&mut flags.allow_run, config.allow_run);
+  merge_allowlist(&mut flags.allow_ffi, config.allow_ffi);
+  flags.allow_hrtime |= config.allow_hrtime;
+  if !config.allow_run_args.is_empty() {
+    flags
+      .allow_run_args
+      .get_or_insert_with(Default::default)
+      .extend(config.allow_run_args);
+  }
+}
+
+/// Grants the permissions of the permission set, of the config file if it was
+/// passed with `--config` and the ones remembered for its project, and
+/// remembers the ones granted with "always allow" from now on.
+pub fn load(flags: &mut Flags) -> Result<(), AnyError> {
+  if let Some(permission_set) = &flags.permission_set {
+    let config = config_file::read_permission_set(permission_set)?;
+    apply_permissions_config(config, flags);
+  }
+  let config_file = match config_file::discover(flags)? {
+    Some(config_file) => config_file,
+    None => return Ok(()),
+  };
+  match config_file.to_permissions_config()? {
+    Some(config) if flags.config_path.is_some() => {
+      apply_permissions_config(config, flags);
+    }
+    Some(_) => log::warn!(
+      "{} Ignoring the \"permissions\" of {}, which are only granted when it is passed with --config",
+      colors::yellow("Warning"),
+      config_file.specifier
+    ),
+    None => {}
+  }
+
+  // the answers persisted for the project shouldn't be baked into compiled
+  // binaries
+  if flags.no_prompt || matches!(flags.subcommand, DenoSubcommand::Compile(_)) {
+    return Ok(());
+  }
+  let config_path = specifier_to_file_path(&config_file.specifier)?;
+  let project_dir = config_path.parent().unwrap();
+  let maybe_custom_root = flags
+    .cache_path
+    .clone()
+    .or_else(|| std::env::var("DENO_DIR").map(String::into).ok());
+  let deno_dir = DenoDir::new(maybe_custom_root)?;
+  let path = permissions_path(&deno_dir, project_dir)?;
+  PersistedPermissions::read(&path)?.apply(flags);
+  set_always_allow_handler(Box::new(move |name, descriptor| {
+    if let Err(err) = record(&path, name, descriptor) {
+      log::warn!(
+        "{} Failed to record the permission: {:#}",
+        colors::yellow("Warning"),
+        err
+      );
+    }
+  }));
+  Ok(())
+}
+
+/// Gets the path of the file in `DENO_DIR` holding the permissions
+/// remembered for the project directory.
+fn permissions_path(
+  deno_dir: &DenoDir,
+  project_dir: &Path,
+) -> Result<PathBuf, AnyError> {
+  let project_dir = canonicalize_path(project_dir).with_context(|| {
+    format!(
+      "Failed resolving project directory {}",
+      project_dir.display()
+    )
+  })?;
+  Ok(deno_dir.project_permissions_file_path(&project_dir))
+}
+
+fn record(path: &Path, name: &str, descriptor: &str) -> Result<(), AnyError> {
+  let mut permissions = PersistedPermissions::read(path)?;
+  if permissions.add(name, descriptor) {
+    permissions.write(path)?;
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use test_util::TempDir;
+
+  #[test]
+  fn record_and_apply() {
+    let temp_dir = TempDir::new();
+    let path = temp_dir.path().join("permissions/project.json");
+    record(&path, "read", "/project/data").unwrap();
+    record(&path, "net", "deno.land:443").unwrap();
+    record(&path, "net", "deno.land:443").unwrap();
+    record(&path, "hrtime", "").unwrap();
+
+    let permissions = PersistedPermissions::read(&path).unwrap();
+    assert_eq!(
+      permissions,
+      PersistedPermissions {
+        read: vec![PathBuf::from("/project/data")],
+        net: vec!["deno.land:443".to_string()],
+        ..Default::default()
+      }
+    );
+
+    let mut flags = Flags {
+      allow_net: Some(vec!["example.com".to_string()]),
+      allow_write: Some(vec![]),
+      ..Default::default()
+    };
+    permissions.apply(&mut flags);
+    assert_eq!(flags.allow_read, Some(vec![PathBuf::from("/project/data")]));
+    assert_eq!(
+      flags.allow_net,
+      Some(vec!["example.com".to_string(), "deno.land:443".to_string()])
+    );
+    assert_eq!(flags.allow_write, Some(vec![]));
+    assert_eq!(flags.allow_env, None);
+  }
+
+  #[test]
+  fn permissions_path_is_keyed_by_project() {
+    let temp_dir = TempDir::new();
+    let deno_dir =
+      DenoDir::new(Some(temp_dir.path().join("deno_dir"))).unwrap();
+    let project = temp_dir.path().join("project");
+    let other_project = temp_dir.path().join("other_project");
+    std::fs::create_dir_all(project.join("sub")).unwrap();
+    std::fs::create_dir(&other_project).unwrap();
+
+    let path = permissions_path(&deno_dir, &project).unwrap();
+    assert!(path.starts_with(&deno_dir.root));
+    assert!(!path.starts_with(&project));
+    assert_eq!(
+      permissions_path(&deno_dir, &project.join("sub/..")).unwrap(),
+      path
+    );
+    assert_ne!(permissions_path(&deno_dir, &other_project).unwrap(), path);
+  }
+
+  #[test]
+  fn apply_permissions() {
+    let mut flags = Flags {
+      allow_read: Some(vec![PathBuf::from("/a")]),
+      allow_net: Some(vec!["example.com".to_string()]),
+      ..Default::default()
+    };
+    apply_permissions_config(
+      PermissionsConfig {
+        allow_read: Some(vec![PathBuf::from("/b")]),
+        allow_net: Some(vec![]),
+        allow_run: Some(vec!["git".to_string()]),
+        allow_run_args: [("git".to_string(), vec![vec!["status".to_string()]])]
+          .into_iter()
+          .collect(),
+        allow_hrtime: true,
+        ..Default::default()
+      },
+      &mut flags,
+    );
+    assert_eq!(
+      flags.allow_read,
+      Some(vec![PathBuf::from("/a"), PathBuf::from("/b")])
+    );
+    assert_eq!(flags.allow_net, Some(vec![]));
+    assert_eq!(flags.allow_run, Some(vec!["git".to_string()]));
+    assert_eq!(
+      flags.allow_run_args.unwrap()["git"],
+      vec![vec!["status".to_string()]]
+    );
+    assert!(flags.allow_hrtime);
+    assert_eq!(flags.allow_write, None);
+  }
+}
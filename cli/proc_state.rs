@@ -1,6 +1,7 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
 use crate::cache;
+use crate::checksum;
 use crate::colors;
 use crate::compat;
 use crate::compat::NodeEsmResolver;
@@ -56,6 +57,7 @@ use log::warn;
 use std::collections::HashSet;
 use std::env;
 use std::ops::Deref;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 /// This structure represents state of single "deno" program.
@@ -174,7 +176,11 @@ impl ProcState {
         None
       };
 
-    let maybe_inspect_host = flags.inspect.or(flags.inspect_brk);
+    let maybe_inspect_host = flags
+      .inspect
+      .clone()
+      .or_else(|| flags.inspect_brk.clone())
+      .or_else(|| flags.inspect_wait.clone());
     let maybe_inspector_server = maybe_inspect_host.map(|host| {
       Arc::new(InspectorServer::new(host, version::get_user_agent()))
     });
@@ -491,6 +497,62 @@ impl ProcState {
     Ok(())
   }
 
+  /// A checksum of the code of `roots` and of the modules they statically
+  /// depend on, so that it changes whenever any of them does. Returns `None`
+  /// if they have not all been loaded.
+  pub fn graph_checksum(&self, roots: &[ModuleSpecifier]) -> Option<String> {
+    let roots = roots
+      .iter()
+      .map(|specifier| (specifier.clone(), ModuleKind::Esm))
+      .collect::<Vec<_>>();
+    let graph_data = self.graph_data.read();
+    let mut modules = graph_data
+      .walk(&roots, false, false, false)?
+      .into_iter()
+      .filter_map(|(specifier, entry)| match entry {
+        ModuleEntry::Module { code, .. } => Some((specifier, code)),
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+    modules.sort_by_key(|(specifier, _)| *specifier);
+    let parts = modules
+      .iter()
+      .flat_map(|(specifier, code)| [specifier.as_str(), code.as_str()])
+      .map(str::as_bytes)
+      .collect::<Vec<_>>();
+    Some(checksum::gen(&parts))
+  }
+
+  /// Paths of the local modules in the graph of the given roots.
+  pub fn graph_local_paths(&self, roots: &[ModuleSpecifier]) -> Vec<PathBuf> {
+    let roots = roots
+      .iter()
+      .map(|specifier| (specifier.clone(), ModuleKind::Esm))
+      .collect::<Vec<_>>();
+    let graph_data = self.graph_data.read();
+    let paths = graph_data
+      .walk(&roots, false, false, false)
+      .map(|modules| {
+        modules
+          .into_keys()
+          .filter_map(|specifier| specifier.to_file_path().ok())
+          .collect()
+      })
+      .unwrap_or_default();
+    paths
+  }
+
+  /// Paths of all local modules that have been loaded so far.
+  pub fn local_module_paths(&self) -> Vec<PathBuf> {
+    self
+      .graph_data
+      .read()
+      .entries()
+      .into_keys()
+      .filter_map(|specifier| specifier.to_file_path().ok())
+      .collect()
+  }
+
   pub fn resolve(
     &self,
     specifier: &str,
@@ -644,9 +706,9 @@ impl SourceMapGetter for ProcState {
       }
       if let Some((code, maybe_map)) = self.get_emit(&specifier) {
         let code = String::from_utf8(code).unwrap();
-        source_map_from_code(code).or(maybe_map)
+        source_map_from_code(&code).or(maybe_map)
       } else if let Ok(source) = self.load(specifier, None, false) {
-        source_map_from_code(source.code)
+        source_map_from_code(&source.code)
       } else {
         None
       }
@@ -699,22 +761,10 @@ pub fn import_map_from_text(
   Ok(result.import_map)
 }
 
-fn source_map_from_code(code: String) -> Option<Vec<u8>> {
-  let lines: Vec<&str> = code.split('\n').collect();
-  if let Some(last_line) = lines.last() {
-    if last_line
-      .starts_with("//# sourceMappingURL=data:application/json;base64,")
-    {
-      let input = last_line.trim_start_matches(
-        "//# sourceMappingURL=data:application/json;base64,",
-      );
-      let decoded_map = base64::decode(input)
-        .expect("Unable to decode source map from emitted file.");
-      Some(decoded_map)
-    } else {
-      None
-    }
-  } else {
-    None
-  }
+/// Extracts an inline base64 source map from the last line of `code`.
+pub fn source_map_from_code(code: &str) -> Option<Vec<u8>> {
+  let last_line = code.rsplit('\n').next()?;
+  let input = last_line
+    .strip_prefix("//# sourceMappingURL=data:application/json;base64,")?;
+  base64::decode(input).ok()
 }
@@ -5,6 +5,8 @@ use crate::file_fetcher::get_source_from_data_url;
 use crate::flags::Flags;
 use crate::fmt_errors::format_js_error;
 use crate::ops;
+use crate::ops::virtual_fs::VirtualFs;
+use crate::proc_state::source_map_from_code;
 use crate::proc_state::ProcState;
 use crate::version;
 use crate::ImportMapResolver;
@@ -12,6 +14,8 @@ use deno_core::anyhow::anyhow;
 use deno_core::anyhow::Context;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
+use deno_core::futures::future::ready;
+use deno_core::futures::future::LocalFutureObj;
 use deno_core::futures::FutureExt;
 use deno_core::located_script_name;
 use deno_core::serde::Deserialize;
@@ -19,25 +23,35 @@ use deno_core::serde::Serialize;
 use deno_core::serde_json;
 use deno_core::url::Url;
 use deno_core::v8_set_flags;
+use deno_core::Extension;
 use deno_core::ModuleLoader;
 use deno_core::ModuleSpecifier;
+use deno_core::SourceMapGetter;
 use deno_graph::source::Resolver;
 use deno_runtime::deno_broadcast_channel::InMemoryBroadcastChannel;
 use deno_runtime::deno_tls::create_default_root_cert_store;
+use deno_runtime::deno_tls::rustls::RootCertStore;
 use deno_runtime::deno_tls::rustls_pemfile;
 use deno_runtime::deno_web::BlobStore;
+use deno_runtime::ops::worker_host::CreateWebWorkerCb;
+use deno_runtime::ops::worker_host::PreloadModuleCb;
 use deno_runtime::permissions::Permissions;
 use deno_runtime::permissions::PermissionsOptions;
+use deno_runtime::web_worker::WebWorker;
+use deno_runtime::web_worker::WebWorkerOptions;
 use deno_runtime::worker::MainWorker;
 use deno_runtime::worker::WorkerOptions;
 use deno_runtime::BootstrapOptions;
 use import_map::parse_from_json;
 use log::Level;
+use std::collections::HashMap;
 use std::env::current_exe;
 use std::io::BufReader;
 use std::io::Cursor;
 use std::io::SeekFrom;
 use std::iter::once;
+use std::path::Path;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -57,6 +71,29 @@ pub struct Metadata {
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
   pub maybe_import_map: Option<(Url, String)>,
   pub entrypoint: ModuleSpecifier,
+  pub embedded_files: Vec<EmbeddedFile>,
+  /// The length of the eszip archive when it was compressed with
+  /// `--compress`, in which case the whole archive is decompressed into
+  /// memory at startup, before parsing it.
+  pub compressed_eszip_len: Option<u64>,
+}
+
+/// A file embedded with `--include`, stored in the binary between the eszip
+/// archive and the metadata.
+#[derive(Deserialize, Serialize)]
+pub struct EmbeddedFile {
+  /// The path of the file relative to the embedded files root, with `/`
+  /// separators whatever the platform.
+  pub path: String,
+  pub offset: u64,
+  pub len: u64,
+}
+
+/// The directory the embedded files are stored relative to, which is the
+/// directory of the main module. Only local main modules have one.
+pub fn embedded_files_root(entrypoint: &ModuleSpecifier) -> Option<PathBuf> {
+  let path = entrypoint.to_file_path().ok()?;
+  path.parent().map(Path::to_path_buf)
 }
 
 pub const MAGIC_TRAILER: &[u8; 8] = b"d3n0l4nd";
@@ -68,11 +105,12 @@ pub const MAGIC_TRAILER: &[u8; 8] = b"d3n0l4nd";
 /// - a u64 pointer to the JS bundle embedded in the binary
 /// - a u64 pointer to JSON metadata (serialized flags) embedded in the binary
 /// These are dereferenced, and the bundle is executed under the configuration
-/// specified by the metadata. If no magic trailer is present, this function
-/// exits with `Ok(None)`.
+/// specified by the metadata, with the embedded files listed in the metadata
+/// served from memory. If no magic trailer is present, this function exits
+/// with `Ok(None)`.
 pub async fn extract_standalone(
   args: Vec<String>,
-) -> Result<Option<(Metadata, eszip::EszipV2)>, AnyError> {
+) -> Result<Option<(Metadata, eszip::EszipV2, VirtualFs)>, AnyError> {
   let current_exe_path = current_exe()?;
 
   let file = tokio::fs::File::open(current_exe_path).await?;
@@ -93,19 +131,11 @@ pub async fn extract_standalone(
   let metadata_pos = u64_from_bytes(metadata_pos)?;
   let metadata_len = trailer_pos - metadata_pos;
 
-  bufreader.seek(SeekFrom::Start(eszip_archive_pos)).await?;
-
-  let (eszip, loader) = eszip::EszipV2::parse(bufreader)
-    .await
-    .context("Failed to parse eszip header")?;
-
-  let mut bufreader = loader.await.context("Failed to parse eszip archive")?;
-
   bufreader.seek(SeekFrom::Start(metadata_pos)).await?;
 
   let mut metadata = String::new();
 
-  bufreader
+  (&mut bufreader)
     .take(metadata_len)
     .read_to_string(&mut metadata)
     .await
@@ -114,7 +144,47 @@ pub async fn extract_standalone(
   let mut metadata: Metadata = serde_json::from_str(&metadata).unwrap();
   metadata.argv.append(&mut args[1..].to_vec());
 
-  Ok(Some((metadata, eszip)))
+  bufreader.seek(SeekFrom::Start(eszip_archive_pos)).await?;
+
+  // The compressed archive is decompressed eagerly. The main module is
+  // loaded from it right away, so deferring it would not save any work.
+  let eszip = if let Some(compressed_len) = metadata.compressed_eszip_len {
+    let mut compressed = vec![0; compressed_len as usize];
+    bufreader
+      .read_exact(&mut compressed)
+      .await
+      .context("Failed to read compressed eszip archive")?;
+    let archive = zstd::stream::decode_all(compressed.as_slice())
+      .context("Failed to decompress eszip archive")?;
+    let (eszip, loader) =
+      eszip::EszipV2::parse(tokio::io::BufReader::new(Cursor::new(archive)))
+        .await
+        .context("Failed to parse eszip header")?;
+    loader.await.context("Failed to parse eszip archive")?;
+    eszip
+  } else {
+    let (eszip, loader) = eszip::EszipV2::parse(bufreader)
+      .await
+      .context("Failed to parse eszip header")?;
+    bufreader = loader.await.context("Failed to parse eszip archive")?;
+    eszip
+  };
+
+  let mut files = HashMap::new();
+  for file in &metadata.embedded_files {
+    bufreader.seek(SeekFrom::Start(file.offset)).await?;
+    let mut data = vec![0; file.len as usize];
+    bufreader.read_exact(&mut data).await.with_context(|| {
+      format!("Failed to read embedded file: {}", file.path)
+    })?;
+    files.insert(file.path.clone(), data);
+  }
+  let virtual_fs = match embedded_files_root(&metadata.entrypoint) {
+    Some(root) => VirtualFs::new(root, files),
+    None => VirtualFs::default(),
+  };
+
+  Ok(Some((metadata, eszip, virtual_fs)))
 }
 
 fn u64_from_bytes(arr: &[u8]) -> Result<u64, AnyError> {
@@ -125,10 +195,36 @@ fn u64_from_bytes(arr: &[u8]) -> Result<u64, AnyError> {
 }
 
 struct EmbeddedModuleLoader {
-  eszip: eszip::EszipV2,
+  eszip: Arc<eszip::EszipV2>,
   maybe_import_map_resolver: Option<ImportMapResolver>,
 }
 
+/// Maps stack traces of standalone binaries back to the original sources
+/// using the inline source maps of the embedded modules.
+struct EmbeddedSourceMapGetter {
+  eszip: Arc<eszip::EszipV2>,
+}
+
+impl SourceMapGetter for EmbeddedSourceMapGetter {
+  fn get_source_map(&self, file_name: &str) -> Option<Vec<u8>> {
+    let module = self.eszip.get_module(file_name)?;
+    // The archive has been fully loaded by the time any code runs, so this
+    // resolves immediately.
+    let code = deno_core::futures::executor::block_on(module.source());
+    source_map_from_code(std::str::from_utf8(&code).ok()?)
+  }
+
+  fn get_source_line(
+    &self,
+    _file_name: &str,
+    _line_number: usize,
+  ) -> Option<String> {
+    // Original sources are only available through `sourcesContent` of the
+    // source maps, which is already consulted before falling back to this.
+    None
+  }
+}
+
 impl ModuleLoader for EmbeddedModuleLoader {
   fn resolve(
     &self,
@@ -210,6 +306,7 @@ fn metadata_to_flags(metadata: &Metadata) -> Flags {
     allow_ffi: permissions.allow_ffi,
     allow_read: permissions.allow_read,
     allow_run: permissions.allow_run,
+    allow_run_args: permissions.allow_run_args,
     allow_write: permissions.allow_write,
     v8_flags: metadata.v8_flags.clone(),
     log_level: metadata.log_level,
@@ -218,32 +315,121 @@ fn metadata_to_flags(metadata: &Metadata) -> Flags {
   }
 }
 
+/// The state shared by the main worker and the web workers of a standalone
+/// binary.
+#[derive(Clone)]
+struct SharedWorkerState {
+  ps: ProcState,
+  eszip: Arc<eszip::EszipV2>,
+  maybe_import_map_resolver: Option<ImportMapResolver>,
+  virtual_fs: VirtualFs,
+  root_cert_store: RootCertStore,
+  blob_store: BlobStore,
+  broadcast_channel: InMemoryBroadcastChannel,
+  argv: Vec<String>,
+  unstable: bool,
+  seed: Option<u64>,
+  log_level: Option<Level>,
+  unsafely_ignore_certificate_errors: Option<Vec<String>>,
+}
+
+impl SharedWorkerState {
+  fn module_loader(&self) -> Rc<EmbeddedModuleLoader> {
+    Rc::new(EmbeddedModuleLoader {
+      eszip: self.eszip.clone(),
+      maybe_import_map_resolver: self.maybe_import_map_resolver.clone(),
+    })
+  }
+
+  fn source_map_getter(&self) -> Box<dyn SourceMapGetter> {
+    Box::new(EmbeddedSourceMapGetter {
+      eszip: self.eszip.clone(),
+    })
+  }
+
+  fn extensions(&self, enable_compiler: bool) -> Vec<Extension> {
+    let mut extensions = ops::cli_exts(self.ps.clone(), enable_compiler);
+    if !self.virtual_fs.is_empty() {
+      extensions.push(ops::virtual_fs::init(self.virtual_fs.clone()));
+    }
+    extensions
+  }
+
+  fn bootstrap_options(&self, location: Option<Url>) -> BootstrapOptions {
+    BootstrapOptions {
+      args: self.argv.clone(),
+      cpu_count: std::thread::available_parallelism()
+        .map(|p| p.get())
+        .unwrap_or(1),
+      debug_flag: self.log_level.map_or(false, |l| l == log::Level::Debug),
+      enable_testing_features: false,
+      location,
+      no_color: !colors::use_color(),
+      is_tty: colors::is_tty(),
+      runtime_version: version::deno(),
+      ts_version: version::TYPESCRIPT.to_string(),
+      unstable: self.unstable,
+    }
+  }
+}
+
+fn create_web_worker_preload_module_callback() -> Arc<PreloadModuleCb> {
+  Arc::new(|worker| LocalFutureObj::new(Box::new(ready(Ok(worker)))))
+}
+
+/// Creates web workers which load their modules from the embedded eszip,
+/// which contains the module graphs of the statically known worker entry
+/// points.
+fn create_web_worker_callback(
+  shared: SharedWorkerState,
+) -> Arc<CreateWebWorkerCb> {
+  Arc::new(move |args| {
+    let options = WebWorkerOptions {
+      bootstrap: shared.bootstrap_options(Some(args.main_module.clone())),
+      extensions: shared.extensions(args.use_deno_namespace),
+      unsafely_ignore_certificate_errors: shared
+        .unsafely_ignore_certificate_errors
+        .clone(),
+      root_cert_store: Some(shared.root_cert_store.clone()),
+      user_agent: version::get_user_agent(),
+      seed: shared.seed,
+      module_loader: shared.module_loader(),
+      create_web_worker_cb: create_web_worker_callback(shared.clone()),
+      preload_module_cb: create_web_worker_preload_module_callback(),
+      format_js_error_fn: Some(Arc::new(format_js_error)),
+      source_map_getter: Some(shared.source_map_getter()),
+      use_deno_namespace: args.use_deno_namespace,
+      worker_type: args.worker_type,
+      maybe_inspector_server: None,
+      get_error_class_fn: Some(&get_error_class_name),
+      blob_store: shared.blob_store.clone(),
+      broadcast_channel: shared.broadcast_channel.clone(),
+      shared_array_buffer_store: None,
+      compiled_wasm_module_store: None,
+      maybe_exit_code: args.maybe_exit_code,
+      stdio: Default::default(),
+      limits: args.limits,
+    };
+
+    WebWorker::bootstrap_from_options(
+      args.name,
+      args.permissions,
+      args.main_module,
+      args.worker_id,
+      options,
+    )
+  })
+}
+
 pub async fn run(
   eszip: eszip::EszipV2,
   metadata: Metadata,
+  virtual_fs: VirtualFs,
 ) -> Result<(), AnyError> {
   let flags = metadata_to_flags(&metadata);
   let main_module = &metadata.entrypoint;
   let ps = ProcState::build(Arc::new(flags)).await?;
   let permissions = Permissions::from_options(&metadata.permissions);
-  let blob_store = BlobStore::default();
-  let broadcast_channel = InMemoryBroadcastChannel::default();
-  let module_loader = Rc::new(EmbeddedModuleLoader {
-    eszip,
-    maybe_import_map_resolver: metadata.maybe_import_map.map(
-      |(base, source)| {
-        ImportMapResolver::new(Arc::new(
-          parse_from_json(&base, &source).unwrap().import_map,
-        ))
-      },
-    ),
-  });
-  let create_web_worker_cb = Arc::new(|_| {
-    todo!("Worker are currently not supported in standalone binaries");
-  });
-  let web_worker_preload_module_cb = Arc::new(|_| {
-    todo!("Worker are currently not supported in standalone binaries");
-  });
 
   // Keep in sync with `main.rs`.
   v8_set_flags(
@@ -272,41 +458,55 @@ pub async fn run(
     }
   }
 
-  let options = WorkerOptions {
-    bootstrap: BootstrapOptions {
-      args: metadata.argv,
-      cpu_count: std::thread::available_parallelism()
-        .map(|p| p.get())
-        .unwrap_or(1),
-      debug_flag: metadata.log_level.map_or(false, |l| l == log::Level::Debug),
-      enable_testing_features: false,
-      location: metadata.location,
-      no_color: !colors::use_color(),
-      is_tty: colors::is_tty(),
-      runtime_version: version::deno(),
-      ts_version: version::TYPESCRIPT.to_string(),
-      unstable: metadata.unstable,
-    },
-    extensions: ops::cli_exts(ps.clone(), true),
-    user_agent: version::get_user_agent(),
+  let shared = SharedWorkerState {
+    ps,
+    eszip: Arc::new(eszip),
+    maybe_import_map_resolver: metadata.maybe_import_map.map(
+      |(base, source)| {
+        ImportMapResolver::new(Arc::new(
+          parse_from_json(&base, &source).unwrap().import_map,
+        ))
+      },
+    ),
+    virtual_fs,
+    root_cert_store,
+    blob_store: BlobStore::default(),
+    broadcast_channel: InMemoryBroadcastChannel::default(),
+    argv: metadata.argv,
+    unstable: metadata.unstable,
+    seed: metadata.seed,
+    log_level: metadata.log_level,
     unsafely_ignore_certificate_errors: metadata
       .unsafely_ignore_certificate_errors,
-    root_cert_store: Some(root_cert_store),
-    seed: metadata.seed,
-    source_map_getter: None,
+  };
+
+  let options = WorkerOptions {
+    bootstrap: shared.bootstrap_options(metadata.location),
+    extensions: shared.extensions(true),
+    user_agent: version::get_user_agent(),
+    unsafely_ignore_certificate_errors: shared
+      .unsafely_ignore_certificate_errors
+      .clone(),
+    root_cert_store: Some(shared.root_cert_store.clone()),
+    seed: shared.seed,
+    source_map_getter: Some(shared.source_map_getter()),
     format_js_error_fn: Some(Arc::new(format_js_error)),
-    create_web_worker_cb,
-    web_worker_preload_module_cb,
+    create_web_worker_cb: create_web_worker_callback(shared.clone()),
+    web_worker_preload_module_cb: create_web_worker_preload_module_callback(),
     maybe_inspector_server: None,
     should_break_on_first_statement: false,
-    module_loader,
+    should_wait_for_inspector_session: false,
+    module_loader: shared.module_loader(),
     get_error_class_fn: Some(&get_error_class_name),
     origin_storage_dir: None,
-    blob_store,
-    broadcast_channel,
+    blob_store: shared.blob_store.clone(),
+    broadcast_channel: shared.broadcast_channel.clone(),
     shared_array_buffer_store: None,
     compiled_wasm_module_store: None,
     stdio: Default::default(),
+    dump_op_metrics: None,
+    max_heap_size: None,
+    module_map_data: None,
   };
   let mut worker = MainWorker::bootstrap_from_options(
     main_module.clone(),
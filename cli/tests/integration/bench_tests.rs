@@ -154,6 +154,26 @@ itest!(no_prompt_with_denied_perms {
   output: "bench/no_prompt_with_denied_perms.out",
 });
 
+itest!(json {
+  args: "bench --unstable --json bench/baseline/bench.ts",
+  exit_code: 0,
+  output: "bench/json.out",
+});
+
+itest!(baseline_regressed {
+  args:
+    "bench --unstable --baseline=bench/baseline/regressed.json bench/baseline/bench.ts",
+  exit_code: 1,
+  output: "bench/baseline_regressed.out",
+});
+
+itest!(baseline_unchanged {
+  args:
+    "bench --unstable --baseline=bench/baseline/unchanged.json --baseline-threshold=5 bench/baseline/bench.ts",
+  exit_code: 0,
+  output: "bench/baseline_unchanged.out",
+});
+
 #[test]
 fn recursive_permissions_pledge() {
   let output = util::deno_cmd()
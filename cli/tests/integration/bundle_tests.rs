@@ -1,6 +1,7 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
 use crate::itest;
+use deno_core::serde_json;
 use test_util as util;
 use test_util::TempDir;
 
@@ -419,6 +420,93 @@ fn bundle_json_module_escape_sub() {
     .contains("${globalThis}`and string literal`"));
 }
 
+#[test]
+fn bundle_multiple_entry_points() {
+  let t = TempDir::new();
+  let out_dir = t.path().join("dist");
+  let status = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("bundle")
+    .arg(format!("--out-dir={}", out_dir.display()))
+    .arg("bundle/chunks/main.ts")
+    .arg("bundle/chunks/worker.ts")
+    .spawn()
+    .unwrap()
+    .wait()
+    .unwrap();
+  assert!(status.success());
+
+  let manifest: serde_json::Value = serde_json::from_str(
+    &std::fs::read_to_string(out_dir.join("manifest.json")).unwrap(),
+  )
+  .unwrap();
+  for entry in ["main.js", "worker.js"] {
+    assert_eq!(
+      manifest[entry]["imports"],
+      serde_json::json!(["chunk-shared.js"])
+    );
+    assert!(manifest[entry]["entryPoint"].is_string());
+    let code = std::fs::read_to_string(out_dir.join(entry)).unwrap();
+    assert!(code.contains("\"./chunk-shared.js\""));
+    assert!(!code.contains("Hello from"));
+  }
+  assert!(manifest["chunk-shared.js"]["entryPoint"].is_null());
+  let modules = manifest["chunk-shared.js"]["modules"].as_array().unwrap();
+  assert_eq!(modules.len(), 1);
+  assert!(modules[0]
+    .as_str()
+    .unwrap()
+    .ends_with("/bundle/chunks/shared.ts"));
+
+  for (entry, expected) in [
+    ("main.js", "Hello from main"),
+    ("worker.js", "Hello from worker"),
+  ] {
+    let output = util::deno_cmd()
+      .current_dir(util::testdata_path())
+      .arg("run")
+      .arg(out_dir.join(entry))
+      .output()
+      .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+      std::str::from_utf8(&output.stdout).unwrap().trim(),
+      expected
+    );
+  }
+}
+
+#[test]
+fn bundle_multiple_entry_points_requires_out_dir() {
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("bundle")
+    .arg("bundle/chunks/main.ts")
+    .arg("main.bundle.js")
+    .arg("bundle/chunks/worker.ts")
+    .output()
+    .unwrap();
+  assert!(!output.status.success());
+  assert!(std::str::from_utf8(&output.stderr)
+    .unwrap()
+    .contains("Bundling multiple entry points requires --out-dir."));
+}
+
+#[test]
+fn bundle_external() {
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("bundle")
+    .arg("--external=./shared.ts")
+    .arg("bundle/chunks/main.ts")
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  let code = std::str::from_utf8(&output.stdout).unwrap();
+  assert!(code.contains("from \"./shared.ts\""));
+  assert!(!code.contains("Hello from"));
+}
+
 itest!(lock_check_err_with_bundle {
   args: "bundle --lock=lock_check_err_with_bundle.json http://127.0.0.1:4545/subdir/mod1.ts",
   output: "lock_check_err_with_bundle.out",
@@ -32,3 +32,20 @@ itest!(check_all_local {
   output_str: Some(""),
   http_server: true,
 });
+
+itest!(check_project {
+  args: "check --quiet --config check/project/deno.json",
+  output_str: Some(""),
+});
+
+itest!(check_project_error {
+  args: "check --quiet --config check/project_error/deno.json",
+  output: "check/project_error.out",
+  exit_code: 1,
+});
+
+itest!(check_project_no_files {
+  args: "check --quiet --config check/no_files/deno.json",
+  output: "check/project_no_files.out",
+  exit_code: 1,
+});
@@ -36,6 +36,45 @@ fn compile() {
   assert_eq!(output.stdout, "Welcome to Deno!\n".as_bytes());
 }
 
+#[test]
+fn compile_with_compress_and_size_report() {
+  let dir = TempDir::new();
+  let exe = if cfg!(windows) {
+    dir.path().join("welcome.exe")
+  } else {
+    dir.path().join("welcome")
+  };
+  let output = util::deno_cmd()
+    .current_dir(util::root_path())
+    .arg("compile")
+    .arg("--unstable")
+    .arg("--compress")
+    .arg("--size-report")
+    .arg("--output")
+    .arg(&exe)
+    .arg("./test_util/std/examples/welcome.ts")
+    .env("NO_COLOR", "1")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert!(stdout.contains("Size report"));
+  assert!(stdout.contains("Eszip archive: "));
+  assert!(stdout.contains("compressed to"));
+  assert!(stdout.contains("examples/welcome.ts"));
+  let output = Command::new(exe)
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(output.stdout, "Welcome to Deno!\n".as_bytes());
+}
+
 #[test]
 fn standalone_args() {
   let dir = TempDir::new();
@@ -549,3 +588,137 @@ fn skip_rebundle() {
   assert!(output.status.success());
   assert_eq!(output.stdout, "Hello World\n".as_bytes());
 }
+
+#[test]
+fn standalone_embedded_files() {
+  let dir = TempDir::new();
+  let project = dir.path().join("project");
+  std::fs::create_dir_all(project.join("assets")).unwrap();
+  std::fs::write(project.join("assets/greeting.txt"), "Hello from a file")
+    .unwrap();
+  std::fs::write(
+    project.join("main.ts"),
+    r#"const url = new URL("./assets/greeting.txt", import.meta.url);
+console.log(await Deno.readTextFile(url));
+console.log(new TextDecoder().decode(Deno.readFileSync(url)));
+console.log(await (await fetch(url)).text());
+console.log(await Deno.readTextFile("assets/greeting.txt"));
+"#,
+  )
+  .unwrap();
+  let exe = if cfg!(windows) {
+    dir.path().join("embedded.exe")
+  } else {
+    dir.path().join("embedded")
+  };
+  let output = util::deno_cmd()
+    .current_dir(&project)
+    .arg("compile")
+    .arg("--allow-read")
+    .arg("--include=assets/**")
+    .arg("--output")
+    .arg(&exe)
+    .arg("./main.ts")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+
+  // the files are read from the executable, not from the disk
+  std::fs::remove_dir_all(&project).unwrap();
+  let output = Command::new(exe)
+    .current_dir(dir.path())
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(output.stdout, "Hello from a file\n".repeat(4).as_bytes());
+}
+
+#[test]
+fn compile_include_not_found() {
+  let dir = TempDir::new();
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("compile")
+    .arg("--include=does_not_exist/**")
+    .arg("--output")
+    .arg(dir.path().join("not_found"))
+    .arg("./001_hello.js")
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(!output.status.success());
+  assert!(String::from_utf8(output.stderr)
+    .unwrap()
+    .contains("No files to include were found for \"does_not_exist/**\"."));
+}
+
+#[test]
+fn standalone_web_worker() {
+  let dir = TempDir::new();
+  let exe = if cfg!(windows) {
+    dir.path().join("workers.exe")
+  } else {
+    dir.path().join("workers")
+  };
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("compile")
+    .arg("--output")
+    .arg(&exe)
+    .arg("./compile/workers/main.ts")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let output = Command::new(exe)
+    .current_dir(dir.path())
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(output.stdout, "Hello main from a worker\n".as_bytes());
+}
+
+#[cfg(not(windows))]
+#[test]
+fn compile_windows_metadata_ignored_for_other_targets() {
+  let dir = TempDir::new();
+  let exe = dir.path().join("hello_world");
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("compile")
+    .arg("--product-name=Hello")
+    .arg("--product-version=1.2.3")
+    .arg("--output")
+    .arg(&exe)
+    .arg("./001_hello.js")
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  assert!(String::from_utf8(output.stderr).unwrap().contains(
+    "the icon and version info are only supported for Windows targets and were ignored."
+  ));
+  let output = Command::new(exe)
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(output.stdout, "Hello World\n".as_bytes());
+}
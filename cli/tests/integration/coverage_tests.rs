@@ -177,3 +177,215 @@ fn multifile_coverage() {
 
   assert!(output.status.success());
 }
+
+#[test]
+fn fail_under() {
+  let deno_dir = TempDir::new();
+  let tempdir = TempDir::new();
+  let tempdir = tempdir.path().join("cov");
+
+  let status = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(util::testdata_path())
+    .arg("test")
+    .arg("--quiet")
+    .arg("--unstable")
+    .arg(format!("--coverage={}", tempdir.to_str().unwrap()))
+    .arg("coverage/branch_test.ts")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::inherit())
+    .status()
+    .unwrap();
+
+  assert!(status.success());
+
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(util::testdata_path())
+    .arg("coverage")
+    .arg("--unstable")
+    .arg("--coverage-fail-under=line=30")
+    .arg(format!("{}/", tempdir.to_str().unwrap()))
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .output()
+    .unwrap();
+
+  assert!(output.status.success());
+
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(util::testdata_path())
+    .arg("coverage")
+    .arg("--unstable")
+    .arg("--coverage-fail-under=line=80")
+    .arg(format!("{}/", tempdir.to_str().unwrap()))
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .output()
+    .unwrap();
+
+  let stderr =
+    util::strip_ansi_codes(std::str::from_utf8(&output.stderr).unwrap())
+      .to_string();
+  let expected = "
+coverage is below the threshold:
+
+  lines: 35.714% (required 80%)
+
+files below the threshold:
+
+  file:///[WILDCARD]/coverage/branch.ts lines 35.714% (5/14), branches [WILDCARD]
+
+error: Coverage is below the threshold
+";
+  if !util::wildcard_match(expected, &stderr) {
+    println!("OUTPUT\n{}\nOUTPUT", stderr);
+    println!("EXPECTED\n{}\nEXPECTED", expected);
+    panic!("pattern match failed");
+  }
+
+  assert!(!output.status.success());
+}
+
+#[test]
+fn html_report() {
+  let deno_dir = TempDir::new();
+  let tempdir = TempDir::new();
+  let html_dir = tempdir.path().join("html");
+  let tempdir = tempdir.path().join("cov");
+
+  let status = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(util::testdata_path())
+    .arg("test")
+    .arg("--quiet")
+    .arg("--unstable")
+    .arg(format!("--coverage={}", tempdir.to_str().unwrap()))
+    .arg("coverage/branch_test.ts")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::inherit())
+    .status()
+    .unwrap();
+
+  assert!(status.success());
+
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(util::testdata_path())
+    .arg("coverage")
+    .arg("--unstable")
+    .arg(format!("--html={}", html_dir.to_str().unwrap()))
+    .arg(format!("{}/", tempdir.to_str().unwrap()))
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .output()
+    .unwrap();
+
+  assert!(output.status.success());
+  let stdout = std::str::from_utf8(&output.stdout).unwrap();
+  assert!(stdout.contains("HTML coverage report written to"));
+
+  let index = fs::read_to_string(html_dir.join("index.html")).unwrap();
+  let expected = "[WILDCARD]<tr><td><a href=\"[WILDCARD]coverage_branch.ts.html\">file:///[WILDCARD]/coverage/branch.ts</a></td><td class=\"low\">35.714% (5/14)</td>[WILDCARD]";
+  if !util::wildcard_match(expected, &index) {
+    println!("OUTPUT\n{}\nOUTPUT", index);
+    println!("EXPECTED\n{}\nEXPECTED", expected);
+    panic!("pattern match failed");
+  }
+
+  let page = fs::read_dir(&html_dir)
+    .unwrap()
+    .map(|entry| entry.unwrap().path())
+    .find(|path| path.to_str().unwrap().ends_with("coverage_branch.ts.html"))
+    .unwrap();
+  let page = fs::read_to_string(page).unwrap();
+  assert!(page.contains("<tr class=\"miss\"><td class=\"line-number\">11</td><td class=\"count\">0</td><td class=\"code\">    return false;</td></tr>"));
+  assert!(page.contains("<td class=\"line-number\">1</td>"));
+}
+
+#[test]
+fn merge() {
+  let deno_dir = TempDir::new();
+  let tempdir = TempDir::new();
+  let merged_dir = tempdir.path().join("merged");
+  let cov_dirs = [tempdir.path().join("cov1"), tempdir.path().join("cov2")];
+
+  for cov_dir in &cov_dirs {
+    let status = util::deno_cmd_with_deno_dir(&deno_dir)
+      .current_dir(util::testdata_path())
+      .arg("test")
+      .arg("--quiet")
+      .arg("--unstable")
+      .arg(format!("--coverage={}", cov_dir.to_str().unwrap()))
+      .arg("coverage/branch_test.ts")
+      .stdout(std::process::Stdio::piped())
+      .stderr(std::process::Stdio::inherit())
+      .status()
+      .unwrap();
+
+    assert!(status.success());
+  }
+
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(util::testdata_path())
+    .arg("coverage")
+    .arg("--merge")
+    .arg(&cov_dirs[0])
+    .arg(&cov_dirs[1])
+    .arg("-o")
+    .arg(&merged_dir)
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::inherit())
+    .output()
+    .unwrap();
+
+  assert!(output.status.success());
+  let profile_count =
+    |dir: &std::path::Path| fs::read_dir(dir).unwrap().count();
+  assert!(
+    profile_count(&merged_dir)
+      < profile_count(&cov_dirs[0]) + profile_count(&cov_dirs[1])
+  );
+
+  // Merging into a directory that already has profiles is refused
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(util::testdata_path())
+    .arg("coverage")
+    .arg("--merge")
+    .arg(&cov_dirs[0])
+    .arg("-o")
+    .arg(&merged_dir)
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .output()
+    .unwrap();
+
+  assert!(!output.status.success());
+  assert!(std::str::from_utf8(&output.stderr)
+    .unwrap()
+    .contains("is not empty"));
+
+  // The merged profile reports the same coverage as a single run
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(util::testdata_path())
+    .arg("coverage")
+    .arg("--unstable")
+    .arg(format!("{}/", merged_dir.to_str().unwrap()))
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .output()
+    .unwrap();
+
+  let actual =
+    util::strip_ansi_codes(std::str::from_utf8(&output.stdout).unwrap())
+      .to_string();
+
+  let expected = fs::read_to_string(
+    util::testdata_path().join("coverage/branch_expected.out"),
+  )
+  .unwrap();
+
+  if !util::wildcard_match(&expected, &actual) {
+    println!("OUTPUT\n{}\nOUTPUT", actual);
+    println!("EXPECTED\n{}\nEXPECTED", expected);
+    panic!("pattern match failed");
+  }
+
+  assert!(output.status.success());
+}
@@ -1,6 +1,8 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
 use crate::itest;
+use deno_core::serde_json;
+use test_util as util;
 
 itest!(deno_doc_builtin {
   args: "doc",
@@ -49,3 +51,47 @@ itest!(deno_doc_invalid_url {
   output: "doc/invalid_url.out",
   exit_code: 1,
 });
+
+itest!(deno_doc_lint {
+  args: "doc --lint doc/lint.ts",
+  output: "doc/lint.out",
+  exit_code: 1,
+});
+
+itest!(deno_doc_lint_threshold {
+  args: "doc --lint --lint-threshold=0 doc/lint.ts",
+  output: "doc/lint_threshold.out",
+});
+
+#[test]
+fn deno_doc_html() {
+  let t = test_util::TempDir::new();
+  let output_dir = t.path().join("site");
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("doc")
+    .arg("--html")
+    .arg(format!("--output={}", output_dir.display()))
+    .arg("deno_doc.ts")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  assert!(String::from_utf8_lossy(&output.stdout)
+    .contains("Documentation site written to"));
+
+  let index = std::fs::read_to_string(output_dir.join("index.html")).unwrap();
+  assert!(index.contains("<a href=\"symbols/foo.html\">foo</a>"));
+  assert!(index.contains("Some JSDoc"));
+  let page =
+    std::fs::read_to_string(output_dir.join("symbols/foo.html")).unwrap();
+  assert!(page.contains("function foo()"));
+  let search_index: serde_json::Value = serde_json::from_str(
+    &std::fs::read_to_string(output_dir.join("search_index.json")).unwrap(),
+  )
+  .unwrap();
+  assert_eq!(search_index[0]["name"], "foo");
+  assert_eq!(search_index[0]["url"], "symbols/foo.html");
+}
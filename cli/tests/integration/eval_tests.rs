@@ -42,3 +42,14 @@ itest!(v8_flags_eval {
   args: "eval --v8-flags=--expose-gc console.log(typeof(gc))",
   output: "v8_flags.js.out",
 });
+
+itest!(eval_cwd_url {
+  args: "eval --cwd-url=subdir import('./mod4.js').then(console.log)",
+  output: "041_dyn_import_eval.out",
+});
+
+itest!(eval_check_error {
+  args: "eval --check --ext=ts console.log((1)as(string))",
+  output: "eval/check_error.out",
+  exit_code: 1,
+});
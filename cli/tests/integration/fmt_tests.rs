@@ -195,6 +195,20 @@ itest!(fmt_stdin_json {
   output_str: Some("{ \"key\": \"value\" }\n"),
 });
 
+#[cfg(feature = "extra_formatters")]
+itest!(fmt_stdin_css {
+  args: "fmt --ext=css -",
+  input: Some("a{color:red}"),
+  output_str: Some("a {\n  color: red;\n}\n"),
+});
+
+#[cfg(feature = "extra_formatters")]
+itest!(fmt_stdin_yaml {
+  args: "fmt --ext=yaml -",
+  input: Some("key:   value\nlist:\n    - a\n"),
+  output_str: Some("key: value\nlist:\n  - a\n"),
+});
+
 itest!(fmt_stdin_check_formatted {
   args: "fmt --check -",
   input: Some("const a = 1;\n"),
@@ -207,6 +221,38 @@ itest!(fmt_stdin_check_not_formatted {
   output_str: Some("Not formatted stdin\n"),
 });
 
+itest!(fmt_stdin_no_semicolons {
+  args: "fmt --options-no-semicolons -",
+  input: Some("const a = 1;\n"),
+  output_str: Some("const a = 1\n"),
+});
+
+itest!(fmt_stdin_no_semicolons_config {
+  args: "fmt --config fmt/no_semicolons.jsonc -",
+  input: Some("const a = 1;\n"),
+  output_str: Some("const a = 1\n"),
+});
+
+itest!(fmt_stdin_check_unified_diff {
+  args: "fmt --check --diff=unified -",
+  input: Some("const a = 1\n"),
+  output_str: Some(
+    "--- a/stdin\n+++ b/stdin\n@@ -1,1 +1,1 @@\n-const a = 1\n+const a = 1;\n"
+  ),
+});
+
+itest!(fmt_check_unified_diff {
+  args: "fmt --check --diff=unified fmt/check/unformatted.ts",
+  output: "fmt/check_unified_diff.out",
+  exit_code: 1,
+});
+
+itest!(fmt_check_json {
+  args: "fmt --check --json fmt/check/unformatted.ts",
+  output: "fmt/check_json.out",
+  exit_code: 1,
+});
+
 itest!(fmt_with_config {
   args: "fmt --config fmt/with_config/deno.jsonc fmt/with_config/subdir",
   output: "fmt/fmt_with_config.out",
@@ -90,6 +90,23 @@ itest!(_076_info_json_deps_order {
   output: "076_info_json_deps_order.out",
 });
 
+itest!(info_why {
+  args:
+    "info --quiet --why=recursive_imports/common.ts 076_info_json_deps_order.ts",
+  output: "info_why.out",
+});
+
+itest!(info_why_json {
+  args: "info --quiet --json --unstable --why=subdir/print_hello.ts 005_more_imports.ts",
+  output: "info_why_json.out",
+});
+
+itest!(info_why_not_found {
+  args: "info --quiet --why=subdir/mod3.js 005_more_imports.ts",
+  output: "info_why_not_found.out",
+  exit_code: 1,
+});
+
 itest!(info_missing_module {
   args: "info error_009_missing_js_module.js",
   output: "info_missing_module.out",
@@ -0,0 +1,67 @@
+// Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
+
+use std::fs;
+use test_util as util;
+use test_util::TempDir;
+
+#[test]
+fn init_builtin_template() {
+  let temp_dir = TempDir::new();
+  let output = util::deno_cmd()
+    .current_dir(temp_dir.path())
+    .arg("init")
+    .arg("--dir")
+    .arg("project")
+    .arg("--vscode")
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+
+  let project_dir = temp_dir.path().join("project");
+  assert!(project_dir.join("deno.json").exists());
+  assert!(project_dir.join("main.ts").exists());
+  assert!(project_dir.join("main_test.ts").exists());
+  assert!(project_dir.join(".vscode/settings.json").exists());
+
+  let output = util::deno_cmd()
+    .current_dir(&project_dir)
+    .arg("run")
+    .arg("main.ts")
+    .arg("2")
+    .arg("3")
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(String::from_utf8(output.stdout).unwrap(), "2 + 3 = 5\n");
+
+  // does not overwrite the generated files
+  let output = util::deno_cmd()
+    .current_dir(temp_dir.path())
+    .arg("init")
+    .arg("--dir")
+    .arg("project")
+    .output()
+    .unwrap();
+  assert!(!output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(stderr.contains("File already exists"));
+  assert!(stderr.contains("Use --force to overwrite it."));
+}
+
+#[test]
+fn init_template_from_url() {
+  let temp_dir = TempDir::new();
+  let output = util::deno_cmd()
+    .current_dir(temp_dir.path())
+    .arg("init")
+    .arg("--from")
+    .arg(util::testdata_path().join("init/template.json"))
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(
+    fs::read_to_string(temp_dir.path().join("app/main.ts")).unwrap(),
+    "console.log(\"from template\");\n"
+  );
+  assert!(temp_dir.path().join("deno.json").exists());
+}
@@ -164,6 +164,50 @@ async fn inspector_connect() {
   child.wait().unwrap();
 }
 
+#[cfg(unix)]
+#[tokio::test]
+async fn inspector_connect_unix_socket() {
+  let temp_dir = util::TempDir::new();
+  let socket_path = temp_dir.path().join("inspector.sock");
+  let script = util::testdata_path().join("inspector/inspector1.js");
+  let mut child = util::deno_cmd()
+    .env("DENO_FUTURE_CHECK", "1")
+    .arg("run")
+    .arg(format!("--inspect=unix:{}", socket_path.display()))
+    .arg(script)
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+
+  let stderr = child.stderr.as_mut().unwrap();
+  let mut stderr_lines =
+    std::io::BufReader::new(stderr).lines().map(|r| r.unwrap());
+  let stderr_first_line = skip_check_line(&mut stderr_lines);
+  let ws_url = stderr_first_line
+    .strip_prefix("Debugger listening on ")
+    .unwrap();
+  let ws_url = url::Url::parse(ws_url).unwrap();
+  assert_eq!(ws_url.scheme(), "ws+unix");
+  let encoded_path = ws_url.host_str().unwrap();
+  assert!(!encoded_path.contains('/'));
+  assert_eq!(
+    percent_encoding::percent_decode_str(encoded_path)
+      .decode_utf8()
+      .unwrap(),
+    socket_path.to_string_lossy()
+  );
+  assert_starts_with!(ws_url.path(), "/ws/");
+
+  let stream = tokio::net::UnixStream::connect(&socket_path).await.unwrap();
+  let request = format!("ws://localhost{}", ws_url.path());
+  let (_socket, response) = tokio_tungstenite::client_async(request, stream)
+    .await
+    .unwrap();
+  assert_eq!("101 Switching Protocols", response.status().to_string());
+  child.kill().unwrap();
+  child.wait().unwrap();
+}
+
 #[tokio::test]
 async fn inspector_break_on_first_line() {
   let script = util::testdata_path().join("inspector/inspector2.js");
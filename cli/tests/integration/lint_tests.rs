@@ -42,6 +42,12 @@ itest!(json {
     exit_code: 1,
 });
 
+itest!(sarif {
+  args: "lint --format=sarif lint/without_config/file2.ts",
+  output: "lint/expected_sarif.out",
+  exit_code: 1,
+});
+
 itest!(ignore {
   args:
     "lint --ignore=lint/without_config/file1.js,lint/without_config/malformed.js,lint/without_config/lint_with_config/ lint/without_config/",
@@ -112,3 +118,45 @@ itest!(lint_with_malformed_config2 {
   output: "lint/with_malformed_config2.out",
   exit_code: 1,
 });
+
+itest!(lint_with_plugins {
+  args: "lint --config lint/plugins/deno.json",
+  output: "lint/plugins.out",
+  exit_code: 1,
+});
+
+itest!(fix_dry_run {
+  args: "lint --fix --dry-run lint/fix/fixable.ts",
+  output: "lint/fix_dry_run.out",
+  exit_code: 0,
+});
+
+#[test]
+fn fix_writes_fixed_files() {
+  let t = test_util::TempDir::new();
+  let file_path = t.path().join("fixable.ts");
+  std::fs::copy(
+    util::testdata_path().join("lint/fix/fixable.ts"),
+    &file_path,
+  )
+  .unwrap();
+
+  let output = util::deno_cmd()
+    .current_dir(t.path())
+    .env("NO_COLOR", "1")
+    .arg("lint")
+    .arg("--fix")
+    .arg(&file_path)
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  assert!(String::from_utf8_lossy(&output.stderr).contains("Fixed 1 file"));
+  assert_eq!(
+    std::fs::read_to_string(&file_path).unwrap(),
+    "const a = 1;\nconsole.log(a!);\n"
+  );
+}
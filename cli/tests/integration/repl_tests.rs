@@ -1,6 +1,7 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
 use test_util as util;
+use test_util::TempDir;
 
 #[test]
 fn pty_multiline() {
@@ -59,6 +60,24 @@ fn pty_unpaired_braces() {
   });
 }
 
+#[cfg(unix)]
+#[test]
+fn pty_interrupt_evaluation() {
+  util::with_pty(&["repl"], |mut console| {
+    console.write_line("let count = 0;");
+    console.write_line("while (true) { count++; }");
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    // Ctrl-C
+    console.write_text("\u{3}");
+    console.write_line("count > 0 ? 'kept' : 'lost'");
+    console.write_line("close();");
+
+    let output = console.read_all_output();
+    assert!(output.contains("Interrupted"));
+    assert!(output.contains("\"kept\""));
+  });
+}
+
 #[test]
 fn pty_bad_input() {
   util::with_pty(&["repl"], |mut console| {
@@ -181,6 +200,40 @@ fn pty_complete_imports() {
   });
 }
 
+#[test]
+fn pty_complete_file_paths() {
+  util::with_pty(&["repl", "--allow-read"], |mut console| {
+    console.write_line(
+      "(await Deno.readTextFile('./001_hel\t')).includes('Hello World')",
+    );
+    console
+      .write_line("Deno.readTextFileSync(\"./subdir/print_hel\t\").length");
+    console.write_line("close();");
+
+    let output = console.read_all_output();
+    assert!(output.contains("001_hello.js"));
+    assert!(output.contains("true"));
+    assert!(output.contains("./subdir/print_hello.ts"));
+    assert!(output.contains("57"));
+  });
+}
+
+#[test]
+fn pty_complete_import_map_specifiers() {
+  util::with_pty(
+    &["repl", "--import-map=import_maps/import_map.json"],
+    |mut console| {
+      console.write_line("import \"lod\t\"");
+      console.write_line("await import(\"mom\t\")");
+      console.write_line("close();");
+
+      let output = console.read_all_output();
+      assert!(output.contains("Hello from remapped lodash!"));
+      assert!(output.contains("Hello from remapped moment!"));
+    },
+  );
+}
+
 #[test]
 fn pty_ignore_symbols() {
   util::with_pty(&["repl"], |mut console| {
@@ -594,6 +647,72 @@ fn missing_deno_dir() {
   assert!(err.is_empty());
 }
 
+#[test]
+fn history_file() {
+  let t = TempDir::new();
+  let history_path = t.path().join("history.txt");
+  let envs = vec![
+    (
+      "DENO_REPL_HISTORY".to_owned(),
+      history_path.to_string_lossy().to_string(),
+    ),
+    ("DENO_REPL_HISTORY_SIZE".to_owned(), "3".to_owned()),
+    ("NO_COLOR".to_owned(), "1".to_owned()),
+  ];
+  let (_, err) = util::run_and_collect_output(
+    true,
+    "repl",
+    Some(vec!["1", "2", "1", "3", "4"]),
+    Some(envs.clone()),
+    false,
+  );
+  assert!(err.is_empty());
+  let history = std::fs::read_to_string(&history_path).unwrap();
+  let entries = history
+    .lines()
+    .filter(|line| !line.starts_with('#'))
+    .collect::<Vec<_>>();
+  assert_eq!(entries, vec!["1", "3", "4"]);
+
+  // history is loaded in the next session
+  let (_, err) = util::run_and_collect_output(
+    true,
+    "repl",
+    Some(vec!["3"]),
+    Some(envs),
+    false,
+  );
+  assert!(err.is_empty());
+  let history = std::fs::read_to_string(&history_path).unwrap();
+  let entries = history
+    .lines()
+    .filter(|line| !line.starts_with('#'))
+    .collect::<Vec<_>>();
+  assert_eq!(entries, vec!["1", "4", "3"]);
+}
+
+#[test]
+fn history_file_disabled() {
+  let t = TempDir::new();
+  let (out, err) = util::run_and_collect_output(
+    true,
+    "repl",
+    Some(vec!["1"]),
+    Some(vec![
+      (
+        "DENO_DIR".to_owned(),
+        t.path().to_string_lossy().to_string(),
+      ),
+      ("DENO_REPL_HISTORY".to_owned(), "".to_owned()),
+      ("NO_COLOR".to_owned(), "1".to_owned()),
+    ]),
+    false,
+  );
+  assert!(out.ends_with("1\n"));
+  assert!(err.is_empty());
+  assert!(!t.path().join("deno_history.txt").exists());
+}
+
 #[test]
 fn save_last_eval() {
   let (out, err) = util::run_and_collect_output(
@@ -1,5 +1,6 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::serde_json;
 use deno_core::url;
 use std::process::Command;
 use test_util as util;
@@ -535,6 +536,11 @@ fn _090_run_permissions_request() {
   ]);
 }
 
+itest!(permission_set {
+  args: "run --quiet --permission-set=permission_set/perms.json permission_set/main.ts",
+  output: "permission_set/main.out",
+});
+
 itest!(_091_use_define_for_class_fields {
   args: "run 091_use_define_for_class_fields.ts",
   output: "091_use_define_for_class_fields.ts.out",
@@ -2779,3 +2785,35 @@ itest!(spawn_stdout_inherit {
   args: "run --quiet --unstable -A spawn_stdout_inherit.ts",
   output: "spawn_stdout_inherit.ts.out",
 });
+
+#[test]
+fn permission_audit() {
+  let temp_dir = TempDir::new();
+  let audit_path = temp_dir.path().join("audit.ndjson");
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("run")
+    .arg("--allow-env=HOME")
+    .arg("--no-prompt")
+    .arg(format!("--permission-audit={}", audit_path.display()))
+    .arg("permission_audit.ts")
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(std::str::from_utf8(&output.stdout).unwrap(), "denied\n");
+
+  let audit = std::fs::read_to_string(&audit_path).unwrap();
+  let entries = audit
+    .lines()
+    .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+    // module loading is audited as well
+    .filter(|entry| entry["name"] == "env")
+    .collect::<Vec<_>>();
+  assert_eq!(entries.len(), 2);
+  assert_eq!(entries[0]["descriptor"], "HOME");
+  assert_eq!(entries[0]["granted"], true);
+  assert_eq!(entries[1]["descriptor"], "SECRET");
+  assert_eq!(entries[1]["granted"], false);
+  assert_eq!(entries[1]["prompted"], false);
+  assert!(entries[1]["timestamp"].is_u64());
+}
@@ -38,6 +38,18 @@ itest!(task_additional_args {
   envs: vec![("NO_COLOR".to_string(), "1".to_string())],
 });
 
+itest!(task_depends_on {
+  args: "task -q --config task/deno.json dependent -- 4",
+  output: "task/task_depends_on.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+});
+
+itest!(task_depends_on_parallel {
+  args: "task -q --parallel --config task/deno.json dependent",
+  output: "task/task_depends_on_parallel.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+});
+
 itest!(task_additional_args_no_shell_expansion {
   args_vec: vec![
     "task",
@@ -78,3 +90,24 @@ itest!(task_additional_args_no_logic {
   output: "task/task_additional_args_no_logic.out",
   envs: vec![("NO_COLOR".to_string(), "1".to_string())],
 });
+
+itest!(task_pipe {
+  args: "task -q --config task/deno.json pipe",
+  output: "task/task_pipe.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+});
+
+itest!(task_env_defaults {
+  args: "task -q --config task/deno.json env_defaults",
+  output: "task/task_env_defaults.out",
+  envs: vec![
+    ("NO_COLOR".to_string(), "1".to_string()),
+    ("DENO_TASK_TEST_SET".to_string(), "set".to_string()),
+  ],
+});
+
+itest!(task_glob {
+  args: "task -q --config task/deno.json glob",
+  output: "task/task_glob.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+});
@@ -2,6 +2,7 @@
 
 use crate::itest;
 use test_util as util;
+use test_util::TempDir;
 
 #[test]
 fn no_color() {
@@ -37,6 +38,12 @@ itest!(pass {
   output: "test/pass.out",
 });
 
+itest!(pass_reuse_workers {
+  args: "test --reuse-workers test/pass.ts",
+  exit_code: 0,
+  output: "test/pass.out",
+});
+
 itest!(ignore {
   args: "test test/ignore.ts",
   exit_code: 0,
@@ -115,6 +122,40 @@ itest!(text {
   output: "test/text.out",
 });
 
+itest!(doc_output {
+  args: "test --config test/doc_output/deno.json test/doc_output/mod_test.ts",
+  exit_code: 0,
+  output: "test/doc_output.out",
+});
+
+itest!(doc_output_mismatch {
+  args: "test --doc test/doc_output/mismatch.md",
+  exit_code: 1,
+  output: "test/doc_output_mismatch.out",
+});
+
+itest!(glob {
+  args: "test test/glob/**/api_*.ts --filter /user.*create/i",
+  output: "test/glob.out",
+});
+
+itest!(list {
+  args: "test --list test/list.ts",
+  output: "test/list.out",
+});
+
+itest!(list_json {
+  args: "test --list=json test/list.ts",
+  output: "test/list_json.out",
+});
+
+itest!(reporter_module {
+  args:
+    "test --reporter=./test/reporter_module/reporter.ts test/reporter_module/test.ts",
+  exit_code: 1,
+  output: "test/reporter_module.out",
+});
+
 itest!(quiet {
   args: "test --quiet test/quiet.ts",
   exit_code: 0,
@@ -127,6 +168,29 @@ itest!(fail_fast {
   output: "test/fail_fast.out",
 });
 
+itest!(fail_fast_cancel {
+  args: "test --fail-fast --jobs=2 test/fail_fast_cancel/",
+  exit_code: 1,
+  output: "test/fail_fast_cancel.out",
+});
+
+itest!(tags {
+  args: "test --filter-tag=integration --skip-tag=slow test/tags.ts",
+  output: "test/tags.out",
+});
+
+itest!(capture_output {
+  args: "test --capture-output test/capture_output.ts",
+  exit_code: 1,
+  output: "test/capture_output.out",
+});
+
+itest!(capture_output_verbose {
+  args: "test --capture-output --verbose-output test/capture_output.ts",
+  exit_code: 1,
+  output: "test/capture_output_verbose.out",
+});
+
 itest!(only {
   args: "test test/only.ts",
   exit_code: 1,
@@ -200,12 +264,59 @@ itest!(resource_sanitizer {
   output: "test/resource_sanitizer.out",
 });
 
+itest!(resource_sanitizer_trace_ops {
+  args: "test --allow-read --trace-ops test/resource_sanitizer.ts",
+  exit_code: 1,
+  output: "test/resource_sanitizer_trace_ops.out",
+});
+
 itest!(exit_sanitizer {
   args: "test test/exit_sanitizer.ts",
   output: "test/exit_sanitizer.out",
   exit_code: 1,
 });
 
+itest!(timeout {
+  args: "test --timeout=100 --allow-read test/timeout.ts",
+  output: "test/timeout.out",
+  exit_code: 1,
+});
+
+itest!(retries {
+  args: "test --retries=1 test/retries.ts",
+  output: "test/retries.out",
+  exit_code: 1,
+});
+
+itest!(setup {
+  args: "test --allow-env --setup=test/setup/setup.ts test/setup/setup_test.ts",
+  output: "test/setup.out",
+});
+
+itest!(quarantine {
+  args:
+    "test --config test/quarantine/deno.json test/quarantine/quarantine_test.ts",
+  output: "test/quarantine.out",
+});
+
+itest!(snapshot {
+  args: "test test/snapshot.ts",
+  output: "test/snapshot.out",
+  exit_code: 1,
+});
+
+itest!(assertion_diff {
+  args: "test test/assertion_diff.ts",
+  output: "test/assertion_diff.out",
+  exit_code: 1,
+});
+
+itest!(assertion_diff_no_diff {
+  args: "test --no-diff test/assertion_diff.ts",
+  output: "test/assertion_diff_no_diff.out",
+  exit_code: 1,
+});
+
 itest!(clear_timeout {
   args: "test test/clear_timeout.ts",
   exit_code: 0,
@@ -351,3 +462,47 @@ fn recursive_permissions_pledge() {
     "pledge test permissions called before restoring previous pledge"
   ));
 }
+
+#[test]
+fn check_cache_skips_unchanged_modules() {
+  let deno_dir = util::new_deno_dir();
+  let t = TempDir::new();
+  let a_test = t.path().join("a_test.ts");
+  let b_test = t.path().join("b_test.ts");
+  std::fs::write(&a_test, "Deno.test(\"a\", () => {});\n").unwrap();
+  std::fs::write(&b_test, "Deno.test(\"b\", () => {});\n").unwrap();
+
+  // Runs the tests, returning the modules that were type checked.
+  let run = || {
+    let output = util::deno_cmd_with_deno_dir(&deno_dir)
+      .current_dir(t.path())
+      .arg("test")
+      .arg("a_test.ts")
+      .arg("b_test.ts")
+      .env("NO_COLOR", "1")
+      .stdout(std::process::Stdio::piped())
+      .stderr(std::process::Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    stderr
+      .lines()
+      .chain(stdout.lines())
+      .filter_map(|line| line.strip_prefix("Check "))
+      .map(|specifier| specifier.rsplit('/').next().unwrap().to_string())
+      .collect::<Vec<_>>()
+  };
+
+  assert_eq!(run(), vec!["a_test.ts", "b_test.ts"]);
+
+  // Only the module that changed is checked again.
+  std::fs::write(&b_test, "Deno.test(\"b\", () => {});\n// changed\n").unwrap();
+  assert_eq!(run(), vec!["b_test.ts"]);
+
+  // Nothing is checked when nothing changed.
+  assert!(run().is_empty());
+}
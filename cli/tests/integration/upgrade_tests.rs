@@ -16,6 +16,7 @@ fn upgrade_in_tmpdir() {
   let _mtime1 = std::fs::metadata(&exe_path).unwrap().modified().unwrap();
   let status = Command::new(&exe_path)
     .arg("upgrade")
+    .arg("--no-verify")
     .arg("--force")
     .spawn()
     .unwrap()
@@ -37,6 +38,7 @@ fn upgrade_with_space_in_path() {
   assert!(exe_path.exists());
   let status = Command::new(&exe_path)
     .arg("upgrade")
+    .arg("--no-verify")
     .arg("--force")
     .env("TMP", temp_dir.path())
     .spawn()
@@ -58,6 +60,7 @@ fn upgrade_with_version_in_tmpdir() {
   let _mtime1 = std::fs::metadata(&exe_path).unwrap().modified().unwrap();
   let status = Command::new(&exe_path)
     .arg("upgrade")
+    .arg("--no-verify")
     .arg("--force")
     .arg("--version")
     .arg("1.11.5")
@@ -87,6 +90,7 @@ fn upgrade_with_canary_in_tmpdir() {
   let _mtime1 = std::fs::metadata(&exe_path).unwrap().modified().unwrap();
   let status = Command::new(&exe_path)
     .arg("upgrade")
+    .arg("--no-verify")
     .arg("--canary")
     .arg("--version")
     .arg("e6685f0f01b8a11a5eaff020f5babcfde76b3038")
@@ -117,6 +121,7 @@ fn upgrade_with_out_in_tmpdir() {
   let mtime1 = std::fs::metadata(&exe_path).unwrap().modified().unwrap();
   let status = Command::new(&exe_path)
     .arg("upgrade")
+    .arg("--no-verify")
     .arg("--version")
     .arg("1.11.5")
     .arg("--output")
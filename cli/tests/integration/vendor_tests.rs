@@ -236,6 +236,71 @@ fn remote_module_test() {
   );
 }
 
+#[test]
+fn check_and_update() {
+  let _server = http_server();
+  let t = TempDir::new();
+  let vendor_dir = t.path().join("vendor");
+  let run_vendor = |args: &[&str]| {
+    util::deno_cmd()
+      .current_dir(t.path())
+      .env("NO_COLOR", "1")
+      .arg("vendor")
+      .args(args)
+      .arg("http://localhost:4545/vendor/query_reexport.ts")
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap()
+  };
+
+  let output = run_vendor(&[]);
+  assert!(output.status.success());
+
+  let output = run_vendor(&["--check"]);
+  assert_eq!(
+    String::from_utf8_lossy(&output.stderr).trim(),
+    "vendor/ is up to date."
+  );
+  assert!(output.status.success());
+
+  // modify a vendored module, list a stale one in the manifest as if it had
+  // been vendored before, and add a file that was not vendored
+  let logger_path = vendor_dir.join("localhost_4545/vendor/logger.ts");
+  fs::write(&logger_path, "export {};").unwrap();
+  fs::write(vendor_dir.join("localhost_4545/stale.ts"), "").unwrap();
+  fs::write(vendor_dir.join("localhost_4545/user.ts"), "").unwrap();
+  let manifest_path = vendor_dir.join("manifest.json");
+  let mut manifest: Vec<String> =
+    serde_json::from_str(&fs::read_to_string(&manifest_path).unwrap()).unwrap();
+  manifest.push("localhost_4545/stale.ts".to_string());
+  fs::write(&manifest_path, serde_json::to_string(&manifest).unwrap()).unwrap();
+
+  let output = run_vendor(&["--check"]);
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  assert!(stderr.contains("Changed vendor/localhost_4545/vendor/logger.ts"));
+  assert!(stderr.contains("Changed vendor/manifest.json"));
+  assert!(stderr.contains("Removed vendor/localhost_4545/stale.ts"));
+  assert!(!stderr.contains("user.ts"));
+  assert!(stderr.contains("error: Found 3 out of date files in vendor/."));
+  assert!(!output.status.success());
+  // checking doesn't modify the directory
+  assert_eq!(fs::read_to_string(&logger_path).unwrap(), "export {};");
+
+  let output = run_vendor(&["--update"]);
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  assert!(stderr.contains("Updated 3 files in vendor/ directory."));
+  assert!(output.status.success());
+  assert_ne!(fs::read_to_string(&logger_path).unwrap(), "export {};");
+  assert!(!vendor_dir.join("localhost_4545/stale.ts").exists());
+  assert!(vendor_dir.join("localhost_4545/user.ts").exists());
+
+  let output = run_vendor(&["--check"]);
+  assert!(output.status.success());
+}
+
 #[test]
 fn existing_import_map() {
   let _server = http_server();
@@ -420,7 +420,9 @@ fn bundle_js_watch() {
   assert_contains!(stderr_lines.next().unwrap(), "mod6.bundle.js");
   let file = PathBuf::from(&bundle);
   assert!(file.is_file());
-  wait_for("Bundle finished", &mut stderr_lines);
+  // the time the rebuild took is reported
+  let finished_line = read_line("Bundle finished", &mut stderr_lines);
+  assert_contains!(&finished_line, "Bundle finished in ");
 
   // Confirm that the watcher keeps on working even if the file is updated and has invalid syntax
   write(&file_to_watch, "syntax error ^^").unwrap();
@@ -900,6 +902,99 @@ fn test_watch() {
   check_alive_then_kill(child);
 }
 
+#[flaky_test]
+fn test_watch_runs_setup_every_time() {
+  let t = TempDir::new();
+  let setup_file = t.path().join("setup.js");
+  let foo_test = t.path().join("foo_test.js");
+  write(
+    &setup_file,
+    r#"export default function setup() {
+      Deno.env.set("DENO_TEST_SETUP", "ready");
+      return () => Deno.env.delete("DENO_TEST_SETUP");
+    }"#,
+  )
+  .unwrap();
+  write(
+    &foo_test,
+    "Deno.test('foo', () => { if (!Deno.env.get('DENO_TEST_SETUP')) throw new Error('setup did not run'); });",
+  )
+  .unwrap();
+
+  let mut child = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("test")
+    .arg("--watch")
+    .arg("--unstable")
+    .arg("--no-check")
+    .arg("--allow-env")
+    .arg("--setup")
+    .arg(&setup_file)
+    .arg(&t.path())
+    .env("NO_COLOR", "1")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+  let (mut stdout_lines, mut stderr_lines) = child_lines(&mut child);
+
+  assert_contains!(read_line("test result", &mut stdout_lines), "1 passed");
+  wait_for("Test finished", &mut stderr_lines);
+
+  // The teardown of the previous run unset the variable, so the test only
+  // passes again if the setup module ran again.
+  write(
+    &foo_test,
+    "Deno.test('bar', () => { if (!Deno.env.get('DENO_TEST_SETUP')) throw new Error('setup did not run'); });",
+  )
+  .unwrap();
+  assert_contains!(stderr_lines.next().unwrap(), "Restarting");
+  assert_contains!(read_line("test result", &mut stdout_lines), "1 passed");
+  wait_for("Test finished", &mut stderr_lines);
+
+  check_alive_then_kill(child);
+}
+
+#[flaky_test]
+fn bench_watch() {
+  let t = TempDir::new();
+  let foo_bench = t.path().join("foo_bench.js");
+  write(&foo_bench, "Deno.bench('foo', () => {});").unwrap();
+
+  let mut child = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("bench")
+    .arg("--watch")
+    .arg("--unstable")
+    .arg("--no-check")
+    .arg(&t.path())
+    .env("NO_COLOR", "1")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+  let (mut stdout_lines, mut stderr_lines) = child_lines(&mut child);
+
+  wait_for("foo_bench.js", &mut stdout_lines);
+  wait_for("Bench finished", &mut stderr_lines);
+
+  // Re-running a benchmark compares it against the previous iteration
+  write(
+    &foo_bench,
+    "Deno.bench('foo', () => {}); Deno.bench('bar', () => {});",
+  )
+  .unwrap();
+  assert_contains!(stderr_lines.next().unwrap(), "Restarting");
+  wait_for("compared to previous run:", &mut stdout_lines);
+  stdout_lines.next();
+  let comparison = stdout_lines.next().unwrap();
+  assert_contains!(comparison, "foo_bench.js: foo ");
+  assert_contains!(comparison, "%)");
+  wait_for("Bench finished", &mut stderr_lines);
+
+  check_alive_then_kill(child);
+}
+
 #[flaky_test]
 fn test_watch_doc() {
   let t = TempDir::new();
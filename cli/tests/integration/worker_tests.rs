@@ -86,6 +86,17 @@ itest!(worker_permissions_blob_remote {
   exit_code: 1,
 });
 
+itest!(worker_revoke_permission {
+  args:
+    "run --quiet --unstable --no-check --allow-env workers/revoke_permission.ts",
+  output: "workers/revoke_permission.ts.out",
+});
+
+itest!(worker_limits {
+  args: "run --quiet --unstable --no-check workers/limits.ts",
+  output: "workers/limits.ts.out",
+});
+
 itest!(worker_permissions_data_local {
   args: "run --quiet --reload --allow-net=localhost:4545 workers/permissions_data_local.ts",
   output: "workers/permissions_data_local.ts.out",
@@ -0,0 +1,3 @@
+import { greet } from "./shared.ts";
+
+console.log(greet("worker"));
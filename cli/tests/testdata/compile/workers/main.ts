@@ -0,0 +1,8 @@
+const worker = new Worker(new URL("./worker.js", import.meta.url), {
+  type: "module",
+});
+worker.onmessage = (e: MessageEvent) => {
+  console.log(e.data);
+  worker.terminate();
+};
+worker.postMessage("main");
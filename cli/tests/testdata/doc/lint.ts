@@ -0,0 +1,14 @@
+/**
+ * Adds two numbers.
+ *
+ * @param a The first number.
+ * @returns The sum.
+ */
+export function add(a: number, b: number): number {
+  return a + b;
+}
+
+export const undocumented = 1;
+
+/** A calculator, see {@link add} and {@link Missing}. */
+export class Calculator {}
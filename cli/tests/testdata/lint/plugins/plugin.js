@@ -0,0 +1,16 @@
+export default {
+  name: "example",
+  rules: {
+    "no-foo"(context) {
+      const start = context.ast.span.start;
+      const index = context.source.indexOf("foo");
+      if (index !== -1) {
+        context.report({
+          span: { start: start + index, end: start + index + 3 },
+          message: "`foo` is not allowed",
+          hint: "Rename it to `bar`",
+        });
+      }
+    },
+  },
+};
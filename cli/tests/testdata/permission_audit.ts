@@ -0,0 +1,6 @@
+Deno.env.get("HOME");
+try {
+  Deno.env.get("SECRET");
+} catch {
+  console.log("denied");
+}
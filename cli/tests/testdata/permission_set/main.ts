@@ -0,0 +1,4 @@
+const perms = await Deno.readTextFile(new URL("./perms.json", import.meta.url));
+console.log(JSON.parse(perms).hrtime);
+console.log((await Deno.permissions.query({ name: "hrtime" })).state);
+console.log((await Deno.permissions.query({ name: "write" })).state);
@@ -0,0 +1,18 @@
+class AssertionError extends Error {
+  constructor(
+    message: string,
+    public expected: unknown,
+    public actual: unknown,
+  ) {
+    super(message);
+    this.name = "AssertionError";
+  }
+}
+
+Deno.test("differs", () => {
+  throw new AssertionError(
+    "Values are not equal:\n\n    { a: 1, b: 2 }\n    { a: 1, b: 3 }",
+    { a: 1, b: 2 },
+    { a: 1, b: 3 },
+  );
+});
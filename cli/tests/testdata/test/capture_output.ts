@@ -0,0 +1,8 @@
+Deno.test("passes", () => {
+  console.log("output of passing test");
+});
+
+Deno.test("fails", () => {
+  console.log("output of failing test");
+  throw new Error("fail");
+});
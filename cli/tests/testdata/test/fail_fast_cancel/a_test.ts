@@ -0,0 +1,5 @@
+Deno.test("fails", async () => {
+  // Give the other module time to start its test.
+  await new Promise((resolve) => setTimeout(resolve, 100));
+  throw new Error("fail");
+});
@@ -0,0 +1,5 @@
+Deno.test("never finishes", () => {
+  while (true) {
+    // Only terminating the isolate stops this test.
+  }
+});
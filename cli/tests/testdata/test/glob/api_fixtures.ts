@@ -0,0 +1,2 @@
+// Matched by the glob, but not a test module and therefore never type checked.
+const value: string = 42;
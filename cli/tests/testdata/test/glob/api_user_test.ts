@@ -0,0 +1,3 @@
+Deno.test("User create", () => {});
+Deno.test("user update", () => {});
+Deno.test("account create", () => {});
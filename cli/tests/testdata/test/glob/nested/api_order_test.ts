@@ -0,0 +1,2 @@
+Deno.test("order create", () => {});
+Deno.test("user can create orders", () => {});
@@ -0,0 +1,2 @@
+// Not matched by the glob, and therefore never type checked.
+const value: string = 42;
@@ -0,0 +1,19 @@
+Deno.test("first", () => {});
+
+Deno.test({
+  name: "second",
+  fn() {},
+});
+
+Deno.test("with steps", async (t) => {
+  await t.step("a step", async (t) => {
+    await t.step("a nested step", () => {});
+  });
+});
+
+// Names computed at runtime can't be listed.
+const name = ["computed", "name"].join(" ");
+Deno.test(name, () => {});
+
+// Listing doesn't type check.
+const value: string = 42;
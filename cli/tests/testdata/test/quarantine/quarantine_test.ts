@@ -0,0 +1,11 @@
+Deno.test("flaky", () => {
+  throw new Error("flaky failure");
+});
+
+Deno.test("network: passes", () => {});
+
+Deno.test("network: fails", () => {
+  throw new Error("network failure");
+});
+
+Deno.test("stable", () => {});
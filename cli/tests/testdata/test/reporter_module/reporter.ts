@@ -0,0 +1,25 @@
+const results: string[] = [];
+
+export function onPlan(event: { plan: { origin: string; total: number } }) {
+  console.log(`plan: ${event.plan.total} tests`);
+}
+
+export async function onResult(
+  event: { description: { name: string }; result: unknown },
+) {
+  // Handlers may be async, the next event waits for them.
+  await new Promise((resolve) => setTimeout(resolve, 10));
+  const status = typeof event.result === "string"
+    ? event.result
+    : Object.keys(event.result as object)[0];
+  results.push(`${event.description.name}: ${status}`);
+}
+
+export function onSummary(
+  event: { summary: { passed: number; failed: number } },
+) {
+  console.log(results.join("\n"));
+  console.log(
+    `passed: ${event.summary.passed}, failed: ${event.summary.failed}`,
+  );
+}
@@ -0,0 +1,11 @@
+Deno.test("passes", () => {});
+
+Deno.test("fails", () => {
+  throw new Error("failed");
+});
+
+Deno.test({
+  name: "ignored",
+  ignore: true,
+  fn() {},
+});
@@ -0,0 +1,27 @@
+let attempts = 0;
+
+Deno.test("passes on retry", () => {
+  attempts++;
+  if (attempts < 2) {
+    throw new Error("first attempt fails");
+  }
+});
+
+Deno.test({
+  name: "always fails",
+  retries: 2,
+  fn() {
+    throw new Error("fails every time");
+  },
+});
+
+let stepAttempts = 0;
+
+Deno.test("reports the steps of the last attempt", async (t) => {
+  stepAttempts++;
+  await t.step("step", () => {
+    if (stepAttempts < 2) {
+      throw new Error("first attempt fails");
+    }
+  });
+});
@@ -0,0 +1,6 @@
+export default function setup() {
+  Deno.env.set("DENO_TEST_SETUP", "ready");
+  return () => {
+    console.log("teardown");
+  };
+}
@@ -0,0 +1,5 @@
+Deno.test("setup ran before tests", () => {
+  if (Deno.env.get("DENO_TEST_SETUP") !== "ready") {
+    throw new Error("setup did not run");
+  }
+});
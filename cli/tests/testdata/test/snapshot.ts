@@ -0,0 +1,7 @@
+Deno.test("matches", (t) => {
+  t.assertSnapshot(42);
+});
+
+Deno.test("differs", (t) => {
+  t.assertSnapshot(43);
+});
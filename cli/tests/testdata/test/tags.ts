@@ -0,0 +1,19 @@
+Deno.test({
+  name: "unit",
+  tags: ["unit"],
+  fn() {},
+});
+
+Deno.test({
+  name: "integration",
+  tags: ["integration"],
+  fn() {},
+});
+
+Deno.test({
+  name: "slow integration",
+  tags: ["integration", "slow"],
+  fn() {},
+});
+
+Deno.test("untagged", () => {});
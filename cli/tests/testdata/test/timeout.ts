@@ -0,0 +1,22 @@
+Deno.test({
+  name: "own timeout",
+  timeout: 50,
+  fn() {
+    return new Promise(() => {});
+  },
+});
+
+Deno.test("default timeout", () => new Promise(() => {}));
+
+Deno.test({
+  name: "leaks a resource and times out",
+  timeout: 50,
+  fn() {
+    Deno.openSync("./test/timeout.ts");
+    return new Promise(() => {});
+  },
+});
+
+Deno.test("finishes in time", async () => {
+  await new Promise((resolve) => setTimeout(resolve, 1));
+});
@@ -0,0 +1,24 @@
+function runWorker(name: string, limits: Record<string, number>) {
+  return new Promise<void>((resolve) => {
+    const worker = new Worker(
+      new URL(`./limits_${name}_worker.js`, import.meta.url).href,
+      { type: "module", name, deno: { limits } },
+    );
+    worker.onerror = (e) => {
+      e.preventDefault();
+      console.log(`${name}: ${e.message}`);
+      resolve();
+    };
+  });
+}
+
+await runWorker("cpu", { cpuMs: 100 });
+await runWorker("memory", { memoryMb: 32 });
+try {
+  new Worker(new URL("./limits_cpu_worker.js", import.meta.url).href, {
+    type: "module",
+    deno: { limits: { memoryMb: Number.MAX_SAFE_INTEGER } },
+  });
+} catch (e) {
+  console.log(`${e.name}: ${e.message}`);
+}
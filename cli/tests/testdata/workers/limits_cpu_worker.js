@@ -0,0 +1,4 @@
+// infinite loop
+while (true) {
+  // busy
+}
@@ -0,0 +1,14 @@
+const worker = new Worker(
+  new URL("./revoke_permission_worker.ts", import.meta.url).href,
+  { type: "module", deno: { namespace: true } },
+);
+let messages = 0;
+worker.onmessage = async (e) => {
+  console.log(e.data);
+  if (++messages == 1) {
+    await Deno.permissions.revoke({ name: "env" });
+    worker.postMessage("query");
+  } else {
+    worker.terminate();
+  }
+};
@@ -0,0 +1,10 @@
+self.onmessage = async () => {
+  const { state } = await Deno.permissions.query({ name: "env" });
+  try {
+    const status = await Deno.permissions.request({ name: "env" });
+    self.postMessage(`${state} ${status.state}`);
+  } catch (e) {
+    self.postMessage(`${state} ${e.name}`);
+  }
+};
+self.postMessage((await Deno.permissions.query({ name: "env" })).state);
@@ -646,6 +646,133 @@ Deno.test(
   },
 );
 
+Deno.test(
+  {
+    permissions: { run: true, read: true },
+    ignore: Deno.build.os === "windows",
+  },
+  async function spawnPty() {
+    const child = Deno.spawnChild(Deno.execPath(), {
+      args: [
+        "eval",
+        "--unstable",
+        "console.log([Deno.isatty(Deno.stdin.rid), Deno.isatty(Deno.stdout.rid), Deno.consoleSize(Deno.stdout.rid).columns].join(' '))",
+      ],
+      pty: { columns: 100, rows: 30 },
+    });
+
+    assert(child.stdin !== null);
+    assert(child.stdout !== null);
+    assert(child.stderr === null);
+
+    const { status, stdout } = await child.output();
+    assertEquals(status.success, true);
+    assertStringIncludes(new TextDecoder().decode(stdout), "true true 100");
+  },
+);
+
+Deno.test(
+  {
+    permissions: { run: true, read: true },
+    ignore: Deno.build.os === "windows",
+  },
+  async function spawnPtyResize() {
+    const child = Deno.spawnChild(Deno.execPath(), {
+      args: [
+        "eval",
+        "--unstable",
+        "await Deno.stdin.read(new Uint8Array(1)); const { columns, rows } = Deno.consoleSize(Deno.stdout.rid); console.log(`${columns}x${rows}`)",
+      ],
+      pty: true,
+    });
+
+    child.resizePty({ columns: 120, rows: 40 });
+    const writer = child.stdin.getWriter();
+    await writer.write(new TextEncoder().encode("\n"));
+    writer.releaseLock();
+    await child.stdin.close();
+
+    const { status, stdout } = await child.output();
+    assertEquals(status.success, true);
+    assertStringIncludes(new TextDecoder().decode(stdout), "120x40");
+  },
+);
+
+Deno.test(
+  {
+    permissions: { run: true, read: true },
+    ignore: Deno.build.os === "windows",
+  },
+  async function spawnNewProcessGroup() {
+    const child = Deno.spawnChild("sh", {
+      args: ["-c", "sleep 100 & echo ready; wait"],
+      stderr: "null",
+      newProcessGroup: true,
+    });
+
+    // wait for the shell to start `sleep`, so that the group has more than
+    // one process to signal
+    const reader = child.stdout.getReader();
+    await reader.read();
+    reader.releaseLock();
+    Deno.kill(-child.pid, "SIGTERM");
+    const { status } = await child.output();
+    assertEquals(status.success, false);
+    assertEquals(status.signal, 15);
+  },
+);
+
+Deno.test(
+  { permissions: { run: true, read: true } },
+  async function spawnDetached() {
+    const child = Deno.spawnChild(Deno.execPath(), {
+      args: ["eval", "console.log('hello')"],
+      detached: true,
+    });
+
+    child.unref();
+    child.ref();
+    const { status, stdout } = await child.output();
+    assertEquals(status.success, true);
+    assertEquals(new TextDecoder().decode(stdout), "hello\n");
+  },
+);
+
+Deno.test(
+  {
+    permissions: { run: true, read: true },
+    ignore: Deno.build.os === "windows",
+  },
+  function spawnPtyDetachedFails() {
+    assertThrows(
+      () =>
+        Deno.spawnChild(Deno.execPath(), {
+          args: ["eval", "console.log('hello')"],
+          pty: true,
+          detached: true,
+        }),
+      TypeError,
+      "The pty option can't be combined with detached or newProcessGroup",
+    );
+  },
+);
+
+Deno.test(
+  { permissions: { run: true, read: true } },
+  async function spawnResizePtyWithoutPtyFails() {
+    const child = Deno.spawnChild(Deno.execPath(), {
+      args: ["eval", "console.log('hello')"],
+    });
+
+    assertThrows(
+      () => child.resizePty({ columns: 120, rows: 40 }),
+      TypeError,
+      "Child process wasn't spawned with a pty.",
+    );
+    await child.output();
+  },
+);
+
 Deno.test(async function spawnStdinPipedFails() {
   await assertRejects(
     () =>
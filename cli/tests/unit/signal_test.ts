@@ -3,94 +3,91 @@ import { assertEquals, assertThrows, deferred, delay } from "./test_util.ts";
 
 Deno.test(
   { ignore: Deno.build.os !== "windows" },
-  function signalsNotImplemented() {
-    assertThrows(
-      () => {
-        Deno.addSignalListener("SIGINT", () => {});
-      },
-      Error,
-      "not implemented",
-    );
+  function signalsNotSupported() {
     assertThrows(
       () => {
         Deno.addSignalListener("SIGALRM", () => {});
       },
-      Error,
-      "not implemented",
+      TypeError,
+      "Windows only supports ctrl-c (SIGINT) and ctrl-break (SIGBREAK)",
     );
     assertThrows(
       () => {
         Deno.addSignalListener("SIGCHLD", () => {});
       },
-      Error,
-      "not implemented",
+      TypeError,
+      "Windows only supports ctrl-c (SIGINT) and ctrl-break (SIGBREAK)",
     );
     assertThrows(
       () => {
         Deno.addSignalListener("SIGHUP", () => {});
       },
-      Error,
-      "not implemented",
-    );
-    assertThrows(
-      () => {
-        Deno.addSignalListener("SIGINT", () => {});
-      },
-      Error,
-      "not implemented",
+      TypeError,
+      "Windows only supports ctrl-c (SIGINT) and ctrl-break (SIGBREAK)",
     );
     assertThrows(
       () => {
         Deno.addSignalListener("SIGIO", () => {});
       },
-      Error,
-      "not implemented",
+      TypeError,
+      "Windows only supports ctrl-c (SIGINT) and ctrl-break (SIGBREAK)",
     );
     assertThrows(
       () => {
         Deno.addSignalListener("SIGPIPE", () => {});
       },
-      Error,
-      "not implemented",
+      TypeError,
+      "Windows only supports ctrl-c (SIGINT) and ctrl-break (SIGBREAK)",
     );
     assertThrows(
       () => {
         Deno.addSignalListener("SIGQUIT", () => {});
       },
-      Error,
-      "not implemented",
+      TypeError,
+      "Windows only supports ctrl-c (SIGINT) and ctrl-break (SIGBREAK)",
     );
     assertThrows(
       () => {
         Deno.addSignalListener("SIGTERM", () => {});
       },
-      Error,
-      "not implemented",
+      TypeError,
+      "Windows only supports ctrl-c (SIGINT) and ctrl-break (SIGBREAK)",
     );
     assertThrows(
       () => {
         Deno.addSignalListener("SIGUSR1", () => {});
       },
-      Error,
-      "not implemented",
+      TypeError,
+      "Windows only supports ctrl-c (SIGINT) and ctrl-break (SIGBREAK)",
     );
     assertThrows(
       () => {
         Deno.addSignalListener("SIGUSR2", () => {});
       },
-      Error,
-      "not implemented",
+      TypeError,
+      "Windows only supports ctrl-c (SIGINT) and ctrl-break (SIGBREAK)",
     );
     assertThrows(
       () => {
         Deno.addSignalListener("SIGWINCH", () => {});
       },
-      Error,
-      "not implemented",
+      TypeError,
+      "Windows only supports ctrl-c (SIGINT) and ctrl-break (SIGBREAK)",
     );
   },
 );
 
+Deno.test(
+  { ignore: Deno.build.os !== "windows" },
+  function signalListenerWindows() {
+    const listener = () => {};
+    Deno.addSignalListener("SIGINT", listener);
+    Deno.addSignalListener("SIGBREAK", listener);
+    Deno.removeSignalListener("SIGINT", listener);
+    Deno.removeSignalListener("SIGBREAK", listener);
+  },
+);
+
 Deno.test(
   {
     ignore: Deno.build.os === "windows",
@@ -168,17 +165,15 @@ Deno.test(
 
 // This tests that pending op_signal_poll doesn't block the runtime from exiting the process.
 Deno.test(
-  {
-    ignore: Deno.build.os === "windows",
-    permissions: { run: true, read: true },
-  },
+  { permissions: { run: true, read: true } },
   async function canExitWhileListeningToSignal() {
+    const signal = Deno.build.os === "windows" ? "SIGINT" : "SIGIO";
     const p = Deno.run({
       cmd: [
         Deno.execPath(),
         "eval",
         "--unstable",
-        "Deno.addSignalListener('SIGIO', () => {})",
+        `Deno.addSignalListener('${signal}', () => {})`,
       ],
     });
     const res = await p.status();
@@ -188,10 +183,7 @@ Deno.test(
 );
 
 Deno.test(
-  {
-    ignore: Deno.build.os === "windows",
-    permissions: { run: true },
-  },
+  { permissions: { run: true } },
   function signalInvalidHandlerTest() {
     assertThrows(() => {
       // deno-lint-ignore no-explicit-any
@@ -18,6 +18,21 @@ Deno.test(function consoleSizeError() {
   }, Deno.errors.BadResource);
 });
 
+Deno.test({ permissions: { read: true } }, function watchConsoleSizeFile() {
+  const file = Deno.openSync("cli/tests/testdata/hello.txt");
+  assertThrows(() => {
+    Deno.watchConsoleSize(file.rid);
+  }, Error);
+  file.close();
+});
+
+Deno.test(function watchConsoleSizeError() {
+  assertThrows(() => {
+    // Absurdly large rid.
+    Deno.watchConsoleSize(0x7fffffff);
+  }, Deno.errors.BadResource);
+});
+
 Deno.test({ permissions: { read: true } }, function isatty() {
   // CI not under TTY, so cannot test stdin/stdout/stderr.
   const f = Deno.openSync("cli/tests/testdata/hello.txt");
@@ -54,6 +54,22 @@ pub fn strip_bom(text: &str) -> &str {
   }
 }
 
+/// Escapes the characters of the text that have a special meaning in HTML.
+pub fn escape_html(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&#39;"),
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -95,6 +111,14 @@ mod tests {
     assert!(err.kind() == ErrorKind::InvalidInput);
   }
 
+  #[test]
+  fn test_escape_html() {
+    assert_eq!(
+      escape_html("<a href=\"x\">'&'</a>"),
+      "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;"
+    );
+  }
+
   #[test]
   fn test_decoding_invalid_utf8() {
     let test_data = b"\xFE\xFE\xFF\xFF".to_vec();
@@ -21,6 +21,9 @@ use crate::ops;
 use crate::proc_state::ProcState;
 use crate::resolver::ImportMapResolver;
 use crate::resolver::JsxResolver;
+use crate::tools::test::to_relative_path_or_remote_url;
+
+use deno_core::anyhow::Context;
 
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
@@ -28,7 +31,10 @@ use deno_core::futures::future;
 use deno_core::futures::stream;
 use deno_core::futures::FutureExt;
 use deno_core::futures::StreamExt;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
 use deno_core::serde_json::json;
+use deno_core::url::Url;
 use deno_core::ModuleSpecifier;
 use deno_graph::ModuleKind;
 use deno_runtime::permissions::Permissions;
@@ -36,7 +42,10 @@ use deno_runtime::tokio_util::run_basic;
 use log::Level;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::HashMap;
 use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::mpsc::unbounded_channel;
@@ -48,6 +57,14 @@ struct BenchSpecifierOptions {
   filter: Option<String>,
 }
 
+/// Options controlling how results are reported once benchmarks have run.
+#[derive(Debug, Clone)]
+struct BenchReportOptions {
+  json: bool,
+  maybe_baseline: Option<BenchBaseline>,
+  maybe_history: Option<BenchHistory>,
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum BenchOutput {
@@ -134,8 +151,39 @@ impl BenchReport {
   }
 }
 
-fn create_reporter(show_output: bool) -> Box<dyn BenchReporter + Send> {
-  Box::new(ConsoleReporter::new(show_output))
+/// A single benchmark as written by `--json` and read back by `--baseline`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct JsonBench {
+  origin: String,
+  name: String,
+  group: Option<String>,
+  baseline: bool,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  stats: Option<BenchStats>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct JsonBenchOutput {
+  runtime: String,
+  cpu: String,
+  benches: Vec<JsonBench>,
+}
+
+fn get_cwd_url() -> Url {
+  Url::from_directory_path(std::env::current_dir().unwrap()).unwrap()
+}
+
+fn create_reporter(
+  show_output: bool,
+  json: bool,
+) -> Box<dyn BenchReporter + Send> {
+  if json {
+    Box::new(JsonReporter::new())
+  } else {
+    Box::new(ConsoleReporter::new(show_output))
+  }
 }
 
 pub trait BenchReporter {
@@ -352,6 +400,225 @@ impl BenchReporter for ConsoleReporter {
   }
 }
 
+/// Collects every result and writes them to stdout as a single JSON document
+/// once all benchmarks have finished. Console output from benchmarks is
+/// discarded so that stdout remains machine readable.
+struct JsonReporter {
+  cwd: Url,
+  origin: String,
+  benches: Vec<JsonBench>,
+}
+
+impl JsonReporter {
+  fn new() -> Self {
+    Self {
+      cwd: get_cwd_url(),
+      origin: String::new(),
+      benches: Vec::new(),
+    }
+  }
+}
+
+impl BenchReporter for JsonReporter {
+  fn report_group_summary(&mut self) {}
+
+  fn report_plan(&mut self, plan: &BenchPlan) {
+    self.origin = to_relative_path_or_remote_url(&self.cwd, &plan.origin);
+  }
+
+  fn report_wait(&mut self, _wait: &BenchMetadata) {}
+
+  fn report_output(&mut self, _output: &BenchOutput) {}
+
+  fn report_result(&mut self, result: &BenchResult) {
+    let bench = match result {
+      BenchResult::Ok(measurement) => JsonBench {
+        origin: self.origin.clone(),
+        name: measurement.name.clone(),
+        group: measurement.group.clone(),
+        baseline: measurement.baseline,
+        stats: Some(measurement.stats.clone()),
+        error: None,
+      },
+      BenchResult::Failed(failure) => JsonBench {
+        origin: self.origin.clone(),
+        name: failure.name.clone(),
+        group: failure.group.clone(),
+        baseline: failure.baseline,
+        stats: None,
+        error: Some(failure.error.clone()),
+      },
+    };
+    self.benches.push(bench);
+  }
+
+  fn report_end(&mut self, _report: &BenchReport) {
+    let output = JsonBenchOutput {
+      runtime: format!("deno {} ({})", crate::version::deno(), env!("TARGET")),
+      cpu: mitata::cpu::name().to_string(),
+      benches: std::mem::take(&mut self.benches),
+    };
+    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+  }
+}
+
+/// Average timings loaded from a previous `--json` run, keyed by origin and
+/// bench name.
+#[derive(Debug, Clone)]
+struct BenchBaseline {
+  threshold: f64,
+  averages: HashMap<(String, String), f64>,
+}
+
+/// Average timings of the previous watch iteration, so each run can show how
+/// benchmarks changed since the last one.
+#[derive(Debug, Clone, Default)]
+struct BenchHistory(Arc<Mutex<HashMap<(String, String), f64>>>);
+
+impl BenchHistory {
+  fn compare(
+    &self,
+    origin: &str,
+    measurement: &BenchMeasurement,
+  ) -> Option<BenchComparison> {
+    let previous_avg = *self
+      .0
+      .lock()
+      .get(&(origin.to_string(), measurement.name.clone()))?;
+    Some(BenchComparison {
+      origin: origin.to_string(),
+      name: measurement.name.clone(),
+      previous_avg,
+      avg: measurement.stats.avg,
+    })
+  }
+
+  fn record(&self, origin: &str, measurement: &BenchMeasurement) {
+    self.0.lock().insert(
+      (origin.to_string(), measurement.name.clone()),
+      measurement.stats.avg,
+    );
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct BenchComparison {
+  origin: String,
+  name: String,
+  previous_avg: f64,
+  avg: f64,
+}
+
+impl BenchComparison {
+  fn change(&self) -> String {
+    if self.previous_avg > 0.0 {
+      format!("{:+.1}%", (self.avg / self.previous_avg - 1.0) * 100.0)
+    } else {
+      "+∞%".to_string()
+    }
+  }
+
+  fn is_slower(&self) -> bool {
+    self.avg > self.previous_avg
+  }
+}
+
+impl fmt::Display for BenchComparison {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let change = if self.is_slower() {
+      colors::red(self.change()).to_string()
+    } else {
+      colors::green(self.change()).to_string()
+    };
+    write!(
+      f,
+      "{} {} {} → {} ({})",
+      colors::gray(format!("{}:", self.origin)),
+      self.name,
+      fmt_duration(self.previous_avg),
+      fmt_duration(self.avg),
+      change
+    )
+  }
+}
+
+impl BenchBaseline {
+  fn load(path: &Path, threshold: f64) -> Result<Self, AnyError> {
+    let text = std::fs::read_to_string(path).with_context(|| {
+      format!("Failed to read baseline file \"{}\"", path.display())
+    })?;
+    let output: JsonBenchOutput =
+      serde_json::from_str(&text).with_context(|| {
+        format!("Failed to parse baseline file \"{}\"", path.display())
+      })?;
+    let averages = output
+      .benches
+      .into_iter()
+      .filter_map(|bench| {
+        let stats = bench.stats?;
+        Some(((bench.origin, bench.name), stats.avg))
+      })
+      .collect();
+    Ok(Self {
+      threshold,
+      averages,
+    })
+  }
+
+  /// Returns a regression when the measurement's average exceeds the
+  /// baseline average by more than the threshold. Benchmarks that are not in
+  /// the baseline are never considered regressions.
+  fn compare(
+    &self,
+    origin: &str,
+    measurement: &BenchMeasurement,
+  ) -> Option<BenchComparison> {
+    let baseline_avg = *self
+      .averages
+      .get(&(origin.to_string(), measurement.name.clone()))?;
+    let limit = baseline_avg * (1.0 + self.threshold / 100.0);
+    if measurement.stats.avg > limit {
+      Some(BenchComparison {
+        origin: origin.to_string(),
+        name: measurement.name.clone(),
+        previous_avg: baseline_avg,
+        avg: measurement.stats.avg,
+      })
+    } else {
+      None
+    }
+  }
+}
+
+fn fmt_duration(ns: f64) -> String {
+  if ns < 1e3 {
+    format!("{:.2} ns", ns)
+  } else if ns < 1e6 {
+    format!("{:.2} µs", ns / 1e3)
+  } else if ns < 1e9 {
+    format!("{:.2} ms", ns / 1e6)
+  } else {
+    format!("{:.2} s", ns / 1e9)
+  }
+}
+
+fn report_regressions(regressions: &[BenchComparison], threshold: f64) {
+  eprintln!(
+    "\n{}\n",
+    colors::red_bold(format!("regressions beyond {}% of baseline:", threshold))
+  );
+  for regression in regressions {
+    eprintln!("{}", regression);
+  }
+}
+
+fn report_previous_run_comparisons(comparisons: &[BenchComparison]) {
+  println!("\n{}\n", colors::bold("compared to previous run:"));
+  for comparison in comparisons {
+    println!("{}", comparison);
+  }
+}
+
 /// Type check a collection of module and document specifiers.
 async fn check_specifiers(
   ps: &ProcState,
@@ -434,6 +701,7 @@ async fn bench_specifiers(
   permissions: Permissions,
   specifiers: Vec<ModuleSpecifier>,
   options: BenchSpecifierOptions,
+  report_options: BenchReportOptions,
 ) -> Result<(), AnyError> {
   let log_level = ps.flags.log_level;
 
@@ -461,7 +729,11 @@ async fn bench_specifiers(
     tokio::task::spawn(async move {
       let mut used_only = false;
       let mut report = BenchReport::new();
-      let mut reporter = create_reporter(log_level != Some(Level::Error));
+      let mut regressions = Vec::new();
+      let mut comparisons = Vec::new();
+      let mut reporter =
+        create_reporter(log_level != Some(Level::Error), report_options.json);
+      let cwd = get_cwd_url();
 
       while let Some(event) = receiver.recv().await {
         match event {
@@ -482,9 +754,21 @@ async fn bench_specifiers(
             reporter.report_output(&output);
           }
 
-          BenchEvent::Result(_origin, result) => {
+          BenchEvent::Result(origin, result) => {
             match &result {
               BenchResult::Ok(bench) => {
+                let origin = to_relative_path_or_remote_url(&cwd, &origin);
+                if let Some(baseline) = &report_options.maybe_baseline {
+                  if let Some(regression) = baseline.compare(&origin, bench) {
+                    regressions.push(regression);
+                  }
+                }
+                if let Some(history) = &report_options.maybe_history {
+                  if let Some(comparison) = history.compare(&origin, bench) {
+                    comparisons.push(comparison);
+                  }
+                  history.record(&origin, bench);
+                }
                 report.measurements.push(bench.clone());
               }
 
@@ -501,6 +785,10 @@ async fn bench_specifiers(
 
       reporter.report_end(&report);
 
+      if !report_options.json && !comparisons.is_empty() {
+        report_previous_run_comparisons(&comparisons);
+      }
+
       if used_only {
         return Err(generic_error(
           "Bench failed because the \"only\" option was used",
@@ -511,6 +799,16 @@ async fn bench_specifiers(
         return Err(generic_error("Bench failed"));
       }
 
+      if let Some(baseline) = &report_options.maybe_baseline {
+        if !regressions.is_empty() {
+          report_regressions(&regressions, baseline.threshold);
+          return Err(generic_error(format!(
+            "Bench failed because {} benchmark(s) regressed beyond the baseline",
+            regressions.len()
+          )));
+        }
+      }
+
       Ok(())
     })
   };
@@ -533,6 +831,15 @@ pub async fn run_benchmarks(
 ) -> Result<(), AnyError> {
   let ps = ProcState::build(Arc::new(flags)).await?;
   let permissions = Permissions::from_options(&ps.flags.permissions_options());
+  let report_options = BenchReportOptions {
+    json: bench_flags.json,
+    maybe_baseline: bench_flags
+      .baseline
+      .as_ref()
+      .map(|path| BenchBaseline::load(path, bench_flags.baseline_threshold))
+      .transpose()?,
+    maybe_history: None,
+  };
   let specifiers = collect_specifiers(
     bench_flags.include.unwrap_or_else(|| vec![".".to_string()]),
     &bench_flags.ignore.clone(),
@@ -560,6 +867,7 @@ pub async fn run_benchmarks(
       compat_mode: compat,
       filter: bench_flags.filter,
     },
+    report_options,
   )
   .await?;
 
@@ -581,6 +889,15 @@ pub async fn run_benchmarks_with_watch(
     emit::TypeLib::DenoWindow
   };
 
+  let report_options = BenchReportOptions {
+    json: bench_flags.json,
+    maybe_baseline: bench_flags
+      .baseline
+      .as_ref()
+      .map(|path| BenchBaseline::load(path, bench_flags.baseline_threshold))
+      .transpose()?,
+    maybe_history: Some(BenchHistory::default()),
+  };
   let include = bench_flags.include.unwrap_or_else(|| vec![".".to_string()]);
   let ignore = bench_flags.ignore.clone();
   let paths_to_watch: Vec<_> = include.iter().map(PathBuf::from).collect();
@@ -753,6 +1070,7 @@ pub async fn run_benchmarks_with_watch(
     let lib = lib.clone();
     let permissions = permissions.clone();
     let ps = ps.clone();
+    let report_options = report_options.clone();
 
     async move {
       let specifiers =
@@ -773,6 +1091,7 @@ pub async fn run_benchmarks_with_watch(
           compat_mode: flags.compat,
           filter: filter.clone(),
         },
+        report_options,
       )
       .await?;
 
@@ -783,10 +1102,7 @@ pub async fn run_benchmarks_with_watch(
   file_watcher::watch_func(
     resolver,
     operation,
-    file_watcher::PrintConfig {
-      job_name: "Bench".to_string(),
-      clear_screen: !flags.no_clear_screen,
-    },
+    file_watcher::WatcherConfig::new("Bench", &flags)?,
   )
   .await?;
 
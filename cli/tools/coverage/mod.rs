@@ -2,26 +2,32 @@
 
 use crate::colors;
 use crate::flags::CoverageFlags;
+use crate::flags::CoverageThreshold;
 use crate::flags::Flags;
+use crate::flags::COVERAGE_DEFAULT_EXCLUDE;
+use crate::flags::COVERAGE_DEFAULT_INCLUDE;
 use crate::fs_util::collect_files;
 use crate::proc_state::ProcState;
+use crate::text_encoding::escape_html;
 use crate::tools::fmt::format_json;
 
 use deno_ast::MediaType;
 use deno_ast::ModuleSpecifier;
 use deno_core::anyhow::anyhow;
 use deno_core::anyhow::Context;
+use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::serde_json;
 use deno_core::sourcemap::SourceMap;
 use deno_core::url::Url;
-use deno_core::LocalInspectorSession;
+use deno_core::JsRuntime;
 use deno_core::SourceMapGetter;
 use regex::Regex;
 use std::fs;
 use std::fs::File;
 use std::io::BufWriter;
 use std::io::{self, Error, Write};
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use text_lines::TextLines;
@@ -35,113 +41,54 @@ use json_types::*;
 
 pub struct CoverageCollector {
   pub dir: PathBuf,
-  session: LocalInspectorSession,
 }
 
 impl CoverageCollector {
-  pub fn new(dir: PathBuf, session: LocalInspectorSession) -> Self {
-    Self { dir, session }
+  pub fn new(dir: PathBuf) -> Self {
+    Self { dir }
   }
 
-  async fn enable_debugger(&mut self) -> Result<(), AnyError> {
-    self
-      .session
-      .post_message::<()>("Debugger.enable", None)
-      .await?;
-    Ok(())
-  }
-
-  async fn enable_profiler(&mut self) -> Result<(), AnyError> {
-    self
-      .session
-      .post_message::<()>("Profiler.enable", None)
-      .await?;
-    Ok(())
-  }
-
-  async fn disable_debugger(&mut self) -> Result<(), AnyError> {
-    self
-      .session
-      .post_message::<()>("Debugger.disable", None)
-      .await?;
-    Ok(())
-  }
-
-  async fn disable_profiler(&mut self) -> Result<(), AnyError> {
-    self
-      .session
-      .post_message::<()>("Profiler.disable", None)
-      .await?;
-    Ok(())
-  }
-
-  async fn start_precise_coverage(
+  pub async fn start_collecting(
     &mut self,
-    parameters: StartPreciseCoverageParameters,
-  ) -> Result<StartPreciseCoverageReturnObject, AnyError> {
-    let return_value = self
-      .session
-      .post_message("Profiler.startPreciseCoverage", Some(parameters))
-      .await?;
-
-    let return_object = serde_json::from_value(return_value)?;
-
-    Ok(return_object)
+    js_runtime: &mut JsRuntime,
+  ) -> Result<(), AnyError> {
+    js_runtime.start_precise_coverage().await
   }
 
-  async fn take_precise_coverage(
+  pub async fn stop_collecting(
     &mut self,
-  ) -> Result<TakePreciseCoverageReturnObject, AnyError> {
-    let return_value = self
-      .session
-      .post_message::<()>("Profiler.takePreciseCoverage", None)
-      .await?;
-
-    let return_object = serde_json::from_value(return_value)?;
-
-    Ok(return_object)
-  }
-
-  pub async fn start_collecting(&mut self) -> Result<(), AnyError> {
-    self.enable_debugger().await?;
-    self.enable_profiler().await?;
-    self
-      .start_precise_coverage(StartPreciseCoverageParameters {
-        call_count: true,
-        detailed: true,
-        allow_triggered_updates: false,
-      })
-      .await?;
-
-    Ok(())
-  }
-
-  pub async fn stop_collecting(&mut self) -> Result<(), AnyError> {
+    js_runtime: &mut JsRuntime,
+  ) -> Result<(), AnyError> {
     fs::create_dir_all(&self.dir)?;
 
-    let script_coverages = self.take_precise_coverage().await?.result;
+    let script_coverages = js_runtime.take_precise_coverage().await?;
     for script_coverage in script_coverages {
       let filename = format!("{}.json", Uuid::new_v4());
-      let filepath = self.dir.join(filename);
-
-      let mut out = BufWriter::new(File::create(filepath)?);
-      let coverage = serde_json::to_string(&script_coverage)?;
-      let formated_coverage = format_json(&coverage, &Default::default())
-        .ok()
-        .flatten()
-        .unwrap_or(coverage);
-
-      out.write_all(formated_coverage.as_bytes())?;
-      out.flush()?;
+      write_script_coverage(&self.dir.join(filename), &script_coverage)?;
     }
 
-    self.disable_debugger().await?;
-    self.disable_profiler().await?;
+    js_runtime.stop_precise_coverage().await?;
 
     Ok(())
   }
 }
 
+fn write_script_coverage(
+  path: &Path,
+  script_coverage: &ScriptCoverage,
+) -> Result<(), AnyError> {
+  let mut out = BufWriter::new(File::create(path)?);
+  let coverage = serde_json::to_string(script_coverage)?;
+  let formated_coverage = format_json(&coverage, &Default::default())
+    .ok()
+    .flatten()
+    .unwrap_or(coverage);
+
+  out.write_all(formated_coverage.as_bytes())?;
+  out.flush()?;
+  Ok(())
+}
+
 struct BranchCoverageItem {
   line_index: usize,
   block_number: usize,
@@ -350,7 +297,11 @@ fn generate_coverage_report(
 
 enum CoverageReporterKind {
   Pretty,
+  /// Like `Pretty`, without listing the uncovered lines.
+  Summary,
   Lcov,
+  /// A static HTML report written to the given directory.
+  Html(PathBuf),
 }
 
 fn create_reporter(
@@ -358,7 +309,11 @@ fn create_reporter(
 ) -> Box<dyn CoverageReporter + Send> {
   match kind {
     CoverageReporterKind::Lcov => Box::new(LcovCoverageReporter::new()),
-    CoverageReporterKind::Pretty => Box::new(PrettyCoverageReporter::new()),
+    CoverageReporterKind::Pretty => Box::new(PrettyCoverageReporter::new(true)),
+    CoverageReporterKind::Summary => {
+      Box::new(PrettyCoverageReporter::new(false))
+    }
+    CoverageReporterKind::Html(dir) => Box::new(HtmlCoverageReporter::new(dir)),
   }
 }
 
@@ -369,7 +324,7 @@ trait CoverageReporter {
     file_text: &str,
   ) -> Result<(), AnyError>;
 
-  fn done(&mut self);
+  fn done(&mut self) -> Result<(), AnyError>;
 }
 
 struct LcovCoverageReporter {}
@@ -471,14 +426,32 @@ impl CoverageReporter for LcovCoverageReporter {
     Ok(())
   }
 
-  fn done(&mut self) {}
+  fn done(&mut self) -> Result<(), AnyError> {
+    Ok(())
+  }
+}
+
+/// Formats `hit` out of `found` as a percentage, colored by how well covered
+/// it is.
+fn fmt_coverage_ratio(hit: usize, found: usize) -> String {
+  let percent = coverage_percent(hit, found);
+  let coverage = format!("{:.3}% ({}/{})", percent, hit, found);
+  if percent >= 90.0 {
+    colors::green(&coverage).to_string()
+  } else if percent >= 75.0 {
+    colors::yellow(&coverage).to_string()
+  } else {
+    colors::red(&coverage).to_string()
+  }
 }
 
-struct PrettyCoverageReporter {}
+struct PrettyCoverageReporter {
+  show_missed_lines: bool,
+}
 
 impl PrettyCoverageReporter {
-  pub fn new() -> PrettyCoverageReporter {
-    PrettyCoverageReporter {}
+  pub fn new(show_missed_lines: bool) -> PrettyCoverageReporter {
+    PrettyCoverageReporter { show_missed_lines }
   }
 }
 
@@ -505,17 +478,18 @@ impl CoverageReporter for PrettyCoverageReporter {
 
     let lines_found = coverage_report.found_lines.len();
     let lines_hit = hit_lines.count();
-    let line_ratio = lines_hit as f32 / lines_found as f32;
+    let branches_found = coverage_report.branches.len();
+    let branches_hit =
+      coverage_report.branches.iter().filter(|b| b.is_hit).count();
 
-    let line_coverage =
-      format!("{:.3}% ({}/{})", line_ratio * 100.0, lines_hit, lines_found);
+    println!(
+      "{} lines, {} branches",
+      fmt_coverage_ratio(lines_hit, lines_found),
+      fmt_coverage_ratio(branches_hit, branches_found)
+    );
 
-    if line_ratio >= 0.9 {
-      println!("{}", colors::green(&line_coverage));
-    } else if line_ratio >= 0.75 {
-      println!("{}", colors::yellow(&line_coverage));
-    } else {
-      println!("{}", colors::red(&line_coverage));
+    if !self.show_missed_lines {
+      return Ok(());
     }
 
     let mut last_line = None;
@@ -544,7 +518,304 @@ impl CoverageReporter for PrettyCoverageReporter {
     Ok(())
   }
 
-  fn done(&mut self) {}
+  fn done(&mut self) -> Result<(), AnyError> {
+    Ok(())
+  }
+}
+
+/// Writes one page per covered file, with every source line highlighted by
+/// whether it was hit, missed or only partially covered by its branches, and
+/// an `index.html` summarizing all files.
+struct HtmlCoverageReporter {
+  dir: PathBuf,
+  files: Vec<(String, FileCoverageTotals)>,
+}
+
+impl HtmlCoverageReporter {
+  pub fn new(dir: PathBuf) -> HtmlCoverageReporter {
+    HtmlCoverageReporter {
+      dir,
+      files: Vec::new(),
+    }
+  }
+}
+
+const HTML_STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+th, td { padding: 0.2em 0.8em; text-align: left; }
+.source td { font-family: monospace; white-space: pre; padding: 0 0.8em; }
+.source td.line-number, .source td.count { color: #888; text-align: right; }
+tr.hit td.code { background: #dfd; }
+tr.miss td.code { background: #fdd; }
+tr.partial td.code { background: #ffd; }
+.low { color: #c00; }
+.medium { color: #a80; }
+.high { color: #080; }";
+
+/// Flattens a module url into a file name that is safe to write on any
+/// platform, e.g. `file:///src/mod.ts` becomes `0_src_mod.ts.html` for the
+/// first file reported. Flattening can map different urls (like `src/mod.ts`
+/// and `src_mod.ts`) to the same name, so it is prefixed with the index of
+/// the file in the report.
+fn html_file_name(index: usize, url: &ModuleSpecifier) -> String {
+  let path = url
+    .as_str()
+    .split_once(':')
+    .map_or(url.as_str(), |(_, p)| p);
+  let name = path
+    .trim_start_matches('/')
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect::<String>();
+  format!("{}_{}.html", index, name)
+}
+
+fn html_percent_cell(hit: usize, found: usize) -> String {
+  let percent = coverage_percent(hit, found);
+  let class = if percent >= 90.0 {
+    "high"
+  } else if percent >= 75.0 {
+    "medium"
+  } else {
+    "low"
+  };
+  format!(
+    "<td class=\"{}\">{:.3}% ({}/{})</td>",
+    class, percent, hit, found
+  )
+}
+
+fn html_page(title: &str, body: &str) -> String {
+  format!(
+    "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{}</title>
+<style>
+{}
+</style>
+</head>
+<body>
+{}
+</body>
+</html>
+",
+    escape_html(title),
+    HTML_STYLE,
+    body
+  )
+}
+
+impl CoverageReporter for HtmlCoverageReporter {
+  fn report(
+    &mut self,
+    coverage_report: &CoverageReport,
+    file_text: &str,
+  ) -> Result<(), AnyError> {
+    let mut line_counts = vec![None; file_text.split('\n').count()];
+    for (index, count) in &coverage_report.found_lines {
+      if let Some(line_count) = line_counts.get_mut(*index) {
+        *line_count = Some(*count);
+      }
+    }
+    let mut missed_branch_lines = vec![false; line_counts.len()];
+    for branch in &coverage_report.branches {
+      if !branch.is_hit {
+        if let Some(missed) = missed_branch_lines.get_mut(branch.line_index) {
+          *missed = true;
+        }
+      }
+    }
+
+    let mut rows = String::new();
+    for (index, line) in file_text.split('\n').enumerate() {
+      let (class, count) = match line_counts[index] {
+        None => ("", String::new()),
+        Some(0) => ("miss", "0".to_string()),
+        Some(count) if missed_branch_lines[index] => {
+          ("partial", count.to_string())
+        }
+        Some(count) => ("hit", count.to_string()),
+      };
+      rows.push_str(&format!(
+        "<tr class=\"{}\"><td class=\"line-number\">{}</td><td class=\"count\">{}</td><td class=\"code\">{}</td></tr>\n",
+        class,
+        index + 1,
+        count,
+        escape_html(line.trim_end_matches('\r'))
+      ));
+    }
+
+    let totals = FileCoverageTotals::from_report(coverage_report);
+    let url = coverage_report.url.as_str();
+    let body = format!(
+      "<p><a href=\"index.html\">All files</a></p>
+<h1>{}</h1>
+<table>
+<tr><th>Lines</th><th>Branches</th></tr>
+<tr>{}{}</tr>
+</table>
+<table class=\"source\">
+{}</table>",
+      escape_html(url),
+      html_percent_cell(totals.lines_hit, totals.lines_found),
+      html_percent_cell(totals.branches_hit, totals.branches_found),
+      rows
+    );
+
+    fs::create_dir_all(&self.dir)?;
+    let file_name = html_file_name(self.files.len(), &coverage_report.url);
+    fs::write(self.dir.join(&file_name), html_page(url, &body)).with_context(
+      || format!("Failed to write HTML coverage report for \"{}\"", url),
+    )?;
+    self.files.push((file_name, totals));
+    Ok(())
+  }
+
+  fn done(&mut self) -> Result<(), AnyError> {
+    let mut rows = String::new();
+    for (file_name, totals) in &self.files {
+      rows.push_str(&format!(
+        "<tr><td><a href=\"{}\">{}</a></td>{}{}</tr>\n",
+        escape_html(file_name),
+        escape_html(totals.url.as_str()),
+        html_percent_cell(totals.lines_hit, totals.lines_found),
+        html_percent_cell(totals.branches_hit, totals.branches_found)
+      ));
+    }
+    let body = format!(
+      "<h1>Coverage report</h1>
+<table>
+<tr><th>File</th><th>Lines</th><th>Branches</th></tr>
+{}<tr><th>All files</th>{}{}</tr>
+</table>",
+      rows,
+      html_percent_cell(
+        self.files.iter().map(|(_, t)| t.lines_hit).sum(),
+        self.files.iter().map(|(_, t)| t.lines_found).sum()
+      ),
+      html_percent_cell(
+        self.files.iter().map(|(_, t)| t.branches_hit).sum(),
+        self.files.iter().map(|(_, t)| t.branches_found).sum()
+      )
+    );
+
+    fs::create_dir_all(&self.dir)?;
+    let index_path = self.dir.join("index.html");
+    fs::write(&index_path, html_page("Coverage report", &body))
+      .context("Failed to write HTML coverage report index")?;
+    println!("HTML coverage report written to {}", index_path.display());
+    Ok(())
+  }
+}
+
+/// Line and branch totals of a single file, used to enforce
+/// `--coverage-fail-under`.
+struct FileCoverageTotals {
+  url: ModuleSpecifier,
+  lines_found: usize,
+  lines_hit: usize,
+  branches_found: usize,
+  branches_hit: usize,
+}
+
+impl FileCoverageTotals {
+  fn from_report(coverage_report: &CoverageReport) -> Self {
+    Self {
+      url: coverage_report.url.clone(),
+      lines_found: coverage_report.found_lines.len(),
+      lines_hit: coverage_report
+        .found_lines
+        .iter()
+        .filter(|(_, count)| *count > 0)
+        .count(),
+      branches_found: coverage_report.branches.len(),
+      branches_hit: coverage_report
+        .branches
+        .iter()
+        .filter(|b| b.is_hit)
+        .count(),
+    }
+  }
+}
+
+/// Percentage of `hit` out of `found`, where nothing to cover counts as fully
+/// covered.
+fn coverage_percent(hit: usize, found: usize) -> f64 {
+  if found == 0 {
+    100.0
+  } else {
+    hit as f64 * 100.0 / found as f64
+  }
+}
+
+/// Fails if the overall line or branch coverage is below `threshold`, after
+/// printing the totals and the files that are below it.
+fn check_coverage_threshold(
+  totals: &[FileCoverageTotals],
+  threshold: &CoverageThreshold,
+) -> Result<(), AnyError> {
+  let is_below = |percent: f64, threshold: Option<f64>| matches!(threshold, Some(threshold) if percent < threshold);
+  let lines_percent = coverage_percent(
+    totals.iter().map(|t| t.lines_hit).sum(),
+    totals.iter().map(|t| t.lines_found).sum(),
+  );
+  let branches_percent = coverage_percent(
+    totals.iter().map(|t| t.branches_hit).sum(),
+    totals.iter().map(|t| t.branches_found).sum(),
+  );
+  if !is_below(lines_percent, threshold.line)
+    && !is_below(branches_percent, threshold.branch)
+  {
+    return Ok(());
+  }
+
+  eprintln!("\n{}\n", colors::red("coverage is below the threshold:"));
+  if let Some(line) = threshold.line {
+    eprintln!("  lines: {:.3}% (required {}%)", lines_percent, line);
+  }
+  if let Some(branch) = threshold.branch {
+    eprintln!(
+      "  branches: {:.3}% (required {}%)",
+      branches_percent, branch
+    );
+  }
+
+  let offenders = totals
+    .iter()
+    .filter(|t| {
+      is_below(coverage_percent(t.lines_hit, t.lines_found), threshold.line)
+        || is_below(
+          coverage_percent(t.branches_hit, t.branches_found),
+          threshold.branch,
+        )
+    })
+    .collect::<Vec<_>>();
+  if !offenders.is_empty() {
+    eprintln!("\nfiles below the threshold:\n");
+    for t in offenders {
+      eprintln!(
+        "  {} lines {:.3}% ({}/{}), branches {:.3}% ({}/{})",
+        t.url,
+        coverage_percent(t.lines_hit, t.lines_found),
+        t.lines_hit,
+        t.lines_found,
+        coverage_percent(t.branches_hit, t.branches_found),
+        t.branches_hit,
+        t.branches_found,
+      );
+    }
+  }
+  eprintln!();
+
+  Err(generic_error("Coverage is below the threshold"))
 }
 
 fn collect_coverages(
@@ -552,9 +823,12 @@ fn collect_coverages(
   ignore: Vec<PathBuf>,
 ) -> Result<Vec<ScriptCoverage>, AnyError> {
   let mut coverages: Vec<ScriptCoverage> = Vec::new();
-  let file_paths = collect_files(&files, &ignore, |file_path| {
+  let mut file_paths = collect_files(&files, &ignore, |file_path| {
     file_path.extension().map_or(false, |ext| ext == "json")
   })?;
+  // The same profile may be reached through several of the given paths.
+  file_paths.sort();
+  file_paths.dedup();
 
   for file_path in file_paths {
     let json = fs::read_to_string(file_path.as_path())?;
@@ -593,9 +867,90 @@ fn filter_coverages(
     .collect::<Vec<ScriptCoverage>>()
 }
 
+/// Merges the profiles in `coverage_flags.files` by script url and writes the
+/// result to the `--output` directory. Script ids are only unique within the
+/// process that collected them, so the merged scripts are given new ids.
+fn merge_coverage_files(coverage_flags: CoverageFlags) -> Result<(), AnyError> {
+  let output = coverage_flags
+    .output
+    .ok_or_else(|| generic_error("--merge requires an --output directory"))?;
+  if output.is_dir() && fs::read_dir(&output)?.next().is_some() {
+    return Err(anyhow!(
+      "Output directory \"{}\" is not empty.",
+      output.display()
+    ));
+  }
+
+  let script_coverages =
+    collect_coverages(coverage_flags.files, coverage_flags.ignore)?;
+  let script_count = script_coverages.len();
+  let proc_coverages: Vec<_> = script_coverages
+    .into_iter()
+    .map(|cov| ProcessCoverage { result: vec![cov] })
+    .collect();
+  let script_coverages = match merge::merge_processes(proc_coverages) {
+    Some(c) => c.result,
+    None => vec![],
+  };
+
+  fs::create_dir_all(&output)?;
+  for script_coverage in &script_coverages {
+    let filename = format!("{}.json", script_coverage.script_id);
+    write_script_coverage(&output.join(filename), script_coverage)?;
+  }
+
+  println!(
+    "Merged {} coverage profiles into {} scripts in {}",
+    script_count,
+    script_coverages.len(),
+    output.display()
+  );
+  Ok(())
+}
+
 pub async fn cover_files(
   flags: Flags,
   coverage_flags: CoverageFlags,
+) -> Result<(), AnyError> {
+  if coverage_flags.merge {
+    return merge_coverage_files(coverage_flags);
+  }
+
+  let reporter_kind = if let Some(dir) = &coverage_flags.html {
+    CoverageReporterKind::Html(dir.clone())
+  } else if coverage_flags.lcov {
+    CoverageReporterKind::Lcov
+  } else {
+    CoverageReporterKind::Pretty
+  };
+  report_coverage(flags, coverage_flags, reporter_kind).await
+}
+
+/// Prints a summary of the coverage profiles in `coverage_dir`, failing if it
+/// is below `threshold`. Used by `deno test --coverage-fail-under`.
+pub async fn check_coverage(
+  flags: Flags,
+  coverage_dir: PathBuf,
+  threshold: CoverageThreshold,
+) -> Result<(), AnyError> {
+  let coverage_flags = CoverageFlags {
+    files: vec![coverage_dir],
+    output: None,
+    ignore: vec![],
+    include: vec![COVERAGE_DEFAULT_INCLUDE.to_string()],
+    exclude: vec![COVERAGE_DEFAULT_EXCLUDE.to_string()],
+    lcov: false,
+    html: None,
+    merge: false,
+    fail_under: Some(threshold),
+  };
+  report_coverage(flags, coverage_flags, CoverageReporterKind::Summary).await
+}
+
+async fn report_coverage(
+  flags: Flags,
+  coverage_flags: CoverageFlags,
+  reporter_kind: CoverageReporterKind,
 ) -> Result<(), AnyError> {
   let ps = ProcState::build(Arc::new(flags)).await?;
 
@@ -619,13 +974,8 @@ pub async fn cover_files(
     vec![]
   };
 
-  let reporter_kind = if coverage_flags.lcov {
-    CoverageReporterKind::Lcov
-  } else {
-    CoverageReporterKind::Pretty
-  };
-
   let mut reporter = create_reporter(reporter_kind);
+  let mut totals = Vec::new();
 
   let out_mode = match coverage_flags.output {
     Some(ref path) => match File::create(path) {
@@ -704,9 +1054,14 @@ pub async fn cover_files(
     );
 
     reporter.report(&coverage_report, original_source)?;
+    totals.push(FileCoverageTotals::from_report(&coverage_report));
   }
 
-  reporter.done();
+  reporter.done()?;
+
+  if let Some(threshold) = &coverage_flags.fail_under {
+    check_coverage_threshold(&totals, threshold)?;
+  }
 
   Ok(())
 }
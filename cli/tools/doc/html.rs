@@ -0,0 +1,256 @@
+// Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
+
+//! Renders the output of the doc parser as a static, multi-page HTML site.
+
+use crate::text_encoding::escape_html;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_core::url::Url;
+use deno_doc as doc;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+const HTML_STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: 0.2em 0.5em; vertical-align: top; }
+tr:nth-child(even) { background: #f6f6f6; }
+pre { background: #f6f6f6; padding: 1em; overflow-x: auto; }
+.kind { color: #666; font-family: monospace; }
+#search { font-size: 1em; padding: 0.3em; width: 20em; }";
+
+/// Filters the rows of the index by the name typed in the search box.
+const SEARCH_SCRIPT: &str =
+  "const search = document.getElementById(\"search\");
+search.addEventListener(\"input\", () => {
+  const query = search.value.toLowerCase();
+  for (const row of document.querySelectorAll(\"tr[data-name]\")) {
+    row.hidden = !row.dataset.name.toLowerCase().includes(query);
+  }
+});";
+
+/// The symbols sharing a name, e.g. the overloads of a function, which are
+/// documented on a single page.
+struct Symbol<'a> {
+  name: &'a str,
+  page: String,
+  nodes: Vec<&'a doc::DocNode>,
+}
+
+/// Writes `index.html`, a page per symbol under `symbols/` and
+/// `search_index.json` to the output directory.
+pub fn write_html_site(
+  doc_nodes: &[doc::DocNode],
+  title: &str,
+  private: bool,
+  output: &Path,
+) -> Result<(), AnyError> {
+  let cwd = std::env::current_dir()
+    .ok()
+    .and_then(|cwd| Url::from_directory_path(cwd).ok());
+  let display_module = |filename: &str| -> String {
+    match (&cwd, Url::parse(filename)) {
+      (Some(cwd), Ok(url)) if url.scheme() == "file" => cwd
+        .make_relative(&url)
+        .filter(|relative| !relative.starts_with("../"))
+        .unwrap_or_else(|| filename.to_string()),
+      _ => filename.to_string(),
+    }
+  };
+
+  let mut symbols = BTreeMap::<&str, Symbol>::new();
+  let mut pages = HashSet::new();
+  for node in doc_nodes {
+    let symbol = symbols.entry(&node.name).or_insert_with(|| Symbol {
+      name: &node.name,
+      page: unique_page_name(&node.name, &mut pages),
+      nodes: Vec::new(),
+    });
+    symbol.nodes.push(node);
+  }
+
+  let symbols_dir = output.join("symbols");
+  fs::create_dir_all(&symbols_dir).with_context(|| {
+    format!("Failed to create directory: {}", symbols_dir.display())
+  })?;
+
+  // the rows of the index, grouped by the module the symbols are defined in
+  let mut modules = BTreeMap::<String, Vec<String>>::new();
+  let mut search_index = Vec::new();
+  for symbol in symbols.values() {
+    let mut body = format!(
+      "<p><a href=\"../index.html\">{}</a></p>\n<h1>{}</h1>\n",
+      escape_html(title),
+      escape_html(symbol.name)
+    );
+    for node in &symbol.nodes {
+      let value = serde_json::to_value(node)?;
+      let kind = node_kind(&value);
+      let module = display_module(&node.location.filename);
+      let summary = doc_summary(&value);
+      body.push_str(&format!(
+        "<p><span class=\"kind\">{}</span> defined in <code>{}:{}:{}</code></p>\n",
+        escape_html(&kind),
+        escape_html(&module),
+        node.location.line,
+        node.location.col
+      ));
+      modules.entry(module.clone()).or_default().push(format!(
+        "<tr data-name=\"{name}\"><td class=\"kind\">{kind}</td><td><a href=\"symbols/{page}\">{name}</a></td><td>{summary}</td></tr>",
+        name = escape_html(symbol.name),
+        kind = escape_html(&kind),
+        page = symbol.page,
+        summary = escape_html(&summary),
+      ));
+      search_index.push(json!({
+        "name": symbol.name,
+        "kind": kind,
+        "module": module,
+        "url": format!("symbols/{}", symbol.page),
+        "summary": summary,
+      }));
+    }
+    let details = format!(
+      "{}",
+      doc::DocPrinter::new(
+        &symbol
+          .nodes
+          .iter()
+          .map(|n| (*n).clone())
+          .collect::<Vec<_>>(),
+        false,
+        private
+      )
+    );
+    body.push_str(&format!("<pre>{}</pre>\n", escape_html(&details)));
+    write_file(
+      &symbols_dir.join(&symbol.page),
+      &html_page(&format!("{} - {}", symbol.name, title), &body),
+    )?;
+  }
+
+  let mut body = format!(
+    "<h1>{}</h1>\n<p><input id=\"search\" type=\"search\" placeholder=\"Search symbols\"></p>\n",
+    escape_html(title)
+  );
+  for (module, rows) in &modules {
+    body.push_str(&format!(
+      "<h2>{}</h2>\n<table>\n<tr><th>Kind</th><th>Name</th><th>Description</th></tr>\n{}\n</table>\n",
+      escape_html(module),
+      rows.join("\n")
+    ));
+  }
+  body.push_str(&format!("<script>\n{}\n</script>\n", SEARCH_SCRIPT));
+  write_file(&output.join("index.html"), &html_page(title, &body))?;
+  write_file(
+    &output.join("search_index.json"),
+    &serde_json::to_string_pretty(&search_index)?,
+  )?;
+
+  Ok(())
+}
+
+/// Creates a file name for the page of a symbol that is safe to use on any
+/// platform and in urls, and differs from the ones already taken.
+fn unique_page_name(name: &str, pages: &mut HashSet<String>) -> String {
+  let base = name
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect::<String>();
+  let mut page = format!("{}.html", base);
+  let mut i = 2;
+  while !pages.insert(page.to_lowercase()) {
+    page = format!("{}-{}.html", base, i);
+    i += 1;
+  }
+  page
+}
+
+fn node_kind(value: &Value) -> String {
+  value
+    .get("kind")
+    .and_then(|kind| kind.as_str())
+    .unwrap_or("unknown")
+    .to_string()
+}
+
+/// The first paragraph of the JSDoc of a node, on a single line.
+fn doc_summary(value: &Value) -> String {
+  let js_doc = match value.get("jsDoc") {
+    Some(Value::String(doc)) => doc.as_str(),
+    Some(Value::Object(js_doc)) => {
+      js_doc.get("doc").and_then(|doc| doc.as_str()).unwrap_or("")
+    }
+    _ => "",
+  };
+  js_doc
+    .split("\n\n")
+    .next()
+    .unwrap_or("")
+    .split_whitespace()
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+fn html_page(title: &str, body: &str) -> String {
+  format!(
+    "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{}</title>
+<style>
+{}
+</style>
+</head>
+<body>
+{}
+</body>
+</html>
+",
+    escape_html(title),
+    HTML_STYLE,
+    body
+  )
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), AnyError> {
+  fs::write(path, contents)
+    .with_context(|| format!("Failed to write file: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_unique_page_name() {
+    let mut pages = HashSet::new();
+    assert_eq!(unique_page_name("Foo", &mut pages), "Foo.html");
+    assert_eq!(unique_page_name("foo", &mut pages), "foo-2.html");
+    assert_eq!(unique_page_name("$foo", &mut pages), "_foo.html");
+    assert_eq!(unique_page_name("_foo", &mut pages), "_foo-2.html");
+  }
+
+  #[test]
+  fn test_doc_summary() {
+    assert_eq!(
+      doc_summary(
+        &json!({ "jsDoc": { "doc": "Adds two\nnumbers.\n\nMore." } })
+      ),
+      "Adds two numbers."
+    );
+    assert_eq!(doc_summary(&json!({ "jsDoc": "A string." })), "A string.");
+    assert_eq!(doc_summary(&json!({})), "");
+  }
+}
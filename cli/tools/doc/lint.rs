@@ -0,0 +1,382 @@
+// Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
+
+//! Reports exported symbols whose documentation is missing or incomplete.
+
+use crate::colors;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::Value;
+use deno_doc as doc;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashSet;
+
+static LINK_RE: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r"\{@link(?:code|plain)?\s+([^}\s|]+)").unwrap());
+
+#[derive(Debug, PartialEq)]
+struct DocProblem {
+  code: &'static str,
+  message: String,
+  filename: String,
+  line: u64,
+  col: u64,
+}
+
+/// The documentation of a node, either the JSDoc object of the doc parser or
+/// the raw comment text.
+struct NodeDoc {
+  text: String,
+  param_names: HashSet<String>,
+  has_returns: bool,
+}
+
+impl NodeDoc {
+  fn from_node(node: &Value) -> Option<Self> {
+    match node.get("jsDoc")? {
+      Value::Object(js_doc) => {
+        let mut text = js_doc
+          .get("doc")
+          .and_then(|doc| doc.as_str())
+          .unwrap_or("")
+          .to_string();
+        let mut param_names = HashSet::new();
+        let mut has_returns = false;
+        let tags = js_doc.get("tags").and_then(|tags| tags.as_array());
+        for tag in tags.into_iter().flatten() {
+          match tag.get("kind").and_then(|kind| kind.as_str()) {
+            Some("param") => {
+              if let Some(name) = tag.get("name").and_then(|n| n.as_str()) {
+                param_names.insert(name.to_string());
+              }
+            }
+            Some("return") | Some("returns") => has_returns = true,
+            _ => {}
+          }
+          if let Some(doc) = tag.get("doc").and_then(|doc| doc.as_str()) {
+            text.push('\n');
+            text.push_str(doc);
+          }
+        }
+        Some(Self {
+          text,
+          param_names,
+          has_returns,
+        })
+      }
+      Value::String(text) => {
+        let mut param_names = HashSet::new();
+        let mut has_returns = false;
+        for line in text.lines() {
+          let mut words = line.split_whitespace();
+          match words.next() {
+            Some("@param") => {
+              // skip the type, e.g. `@param {string} name`
+              let name = words.find(|word| !word.starts_with('{'));
+              if let Some(name) = name {
+                param_names.insert(
+                  name.trim_matches(|c| c == '[' || c == ']').to_string(),
+                );
+              }
+            }
+            Some("@return") | Some("@returns") => has_returns = true,
+            _ => {}
+          }
+        }
+        Some(Self {
+          text: text.clone(),
+          param_names,
+          has_returns,
+        })
+      }
+      _ => None,
+    }
+  }
+}
+
+/// The result of linting the documentation of a module.
+pub struct DocLintReport {
+  problems: Vec<DocProblem>,
+  symbols_count: usize,
+  documented_count: usize,
+}
+
+impl DocLintReport {
+  pub fn coverage(&self) -> f64 {
+    if self.symbols_count == 0 {
+      100.0
+    } else {
+      self.documented_count as f64 / self.symbols_count as f64 * 100.0
+    }
+  }
+
+  /// Prints the problems and summary, failing when the share of completely
+  /// documented symbols is below the threshold.
+  pub fn report(&self, threshold: f64) -> Result<(), AnyError> {
+    for problem in &self.problems {
+      eprintln!(
+        "({}) {}\n    at {}:{}:{}\n",
+        colors::red(problem.code),
+        problem.message,
+        colors::cyan(&problem.filename),
+        colors::yellow(&problem.line.to_string()),
+        colors::yellow(&problem.col.to_string()),
+      );
+    }
+    println!(
+      "Documented {} of {} symbols ({:.2}%)",
+      self.documented_count,
+      self.symbols_count,
+      self.coverage()
+    );
+    if self.coverage() < threshold {
+      Err(generic_error(format!(
+        "Documentation coverage {:.2}% is below the threshold of {}%",
+        self.coverage(),
+        threshold
+      )))
+    } else {
+      Ok(())
+    }
+  }
+}
+
+/// Checks that every exported symbol has JSDoc, that the JSDoc of functions
+/// documents their parameters and return value, and that `{@link}` tags refer
+/// to existing symbols.
+pub fn lint_docs(
+  doc_nodes: &[doc::DocNode],
+) -> Result<DocLintReport, AnyError> {
+  let nodes = doc_nodes
+    .iter()
+    .map(serde_json::to_value)
+    .collect::<Result<Vec<_>, _>>()?;
+  let mut names = HashSet::new();
+  collect_names(&nodes, &mut names);
+
+  let mut report = DocLintReport {
+    problems: Vec::new(),
+    symbols_count: 0,
+    documented_count: 0,
+  };
+  lint_nodes(&nodes, "", &names, &mut report);
+  report.problems.sort_by(|a, b| {
+    (&a.filename, a.line, a.col).cmp(&(&b.filename, b.line, b.col))
+  });
+  Ok(report)
+}
+
+fn collect_names(nodes: &[Value], names: &mut HashSet<String>) {
+  for node in nodes {
+    if let Some(name) = node.get("name").and_then(|name| name.as_str()) {
+      names.insert(name.to_string());
+    }
+    if let Some(elements) = namespace_elements(node) {
+      collect_names(elements, names);
+    }
+  }
+}
+
+fn namespace_elements(node: &Value) -> Option<&Vec<Value>> {
+  node
+    .get("namespaceDef")
+    .and_then(|def| def.get("elements"))
+    .and_then(|elements| elements.as_array())
+}
+
+fn lint_nodes(
+  nodes: &[Value],
+  prefix: &str,
+  names: &HashSet<String>,
+  report: &mut DocLintReport,
+) {
+  for node in nodes {
+    let kind = node.get("kind").and_then(|k| k.as_str()).unwrap_or("");
+    if kind == "import" {
+      continue;
+    }
+    let name = format!(
+      "{}{}",
+      prefix,
+      node
+        .get("name")
+        .and_then(|n| n.as_str())
+        .unwrap_or("default")
+    );
+    let problems_count = report.problems.len();
+    let mut add_problem = |code: &'static str, message: String| {
+      let location = node.get("location");
+      let get = |key: &str| location.and_then(|l| l.get(key));
+      report.problems.push(DocProblem {
+        code,
+        message,
+        filename: get("filename")
+          .and_then(|f| f.as_str())
+          .unwrap_or("")
+          .to_string(),
+        line: get("line").and_then(|l| l.as_u64()).unwrap_or(0),
+        col: get("col").and_then(|c| c.as_u64()).unwrap_or(0),
+      });
+    };
+
+    match NodeDoc::from_node(node).filter(|doc| !doc.text.trim().is_empty()) {
+      None => {
+        add_problem("doc-missing-jsdoc", format!("`{}` is missing JSDoc", name))
+      }
+      Some(node_doc) => {
+        if let Some(function_def) = node.get("functionDef") {
+          let params = function_def
+            .get("params")
+            .and_then(|params| params.as_array());
+          for param in params.into_iter().flatten() {
+            let param_name = match param.get("name").and_then(|n| n.as_str()) {
+              // destructured parameters have no name to document
+              Some(param_name) => param_name,
+              None => continue,
+            };
+            if !node_doc.param_names.contains(param_name) {
+              add_problem(
+                "doc-missing-param",
+                format!(
+                  "`{}` is missing a @param tag for `{}`",
+                  name, param_name
+                ),
+              );
+            }
+          }
+          let return_type = function_def
+            .get("returnType")
+            .and_then(|return_type| return_type.get("repr"))
+            .and_then(|repr| repr.as_str());
+          if matches!(return_type, Some(repr) if repr != "void")
+            && !node_doc.has_returns
+          {
+            add_problem(
+              "doc-missing-returns",
+              format!("`{}` is missing a @returns tag", name),
+            );
+          }
+        }
+        for link in LINK_RE.captures_iter(&node_doc.text) {
+          let target = &link[1];
+          if target.contains("://") {
+            continue;
+          }
+          let target_name = target
+            .split(|c| c == '.' || c == '#')
+            .next()
+            .unwrap_or(target);
+          if !names.contains(target_name) {
+            add_problem(
+              "doc-broken-link",
+              format!("`{}` links to `{}`, which does not exist", name, target),
+            );
+          }
+        }
+      }
+    }
+
+    report.symbols_count += 1;
+    if report.problems.len() == problems_count {
+      report.documented_count += 1;
+    }
+    if let Some(elements) = namespace_elements(node) {
+      lint_nodes(elements, &format!("{}.", name), names, report);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use deno_core::serde_json::json;
+
+  fn lint(nodes: Vec<Value>) -> DocLintReport {
+    let mut names = HashSet::new();
+    collect_names(&nodes, &mut names);
+    let mut report = DocLintReport {
+      problems: Vec::new(),
+      symbols_count: 0,
+      documented_count: 0,
+    };
+    lint_nodes(&nodes, "", &names, &mut report);
+    report
+  }
+
+  fn location() -> Value {
+    json!({ "filename": "file:///mod.ts", "line": 1, "col": 0 })
+  }
+
+  #[test]
+  fn reports_missing_docs() {
+    let report = lint(vec![
+      json!({ "kind": "variable", "name": "a", "location": location() }),
+      json!({
+        "kind": "function",
+        "name": "add",
+        "location": location(),
+        "jsDoc": { "doc": "Adds numbers.", "tags": [
+          { "kind": "param", "name": "a", "doc": "The first number." },
+        ] },
+        "functionDef": {
+          "params": [{ "kind": "identifier", "name": "a" }, { "kind": "identifier", "name": "b" }],
+          "returnType": { "repr": "number" },
+        },
+      }),
+      json!({
+        "kind": "class",
+        "name": "B",
+        "location": location(),
+        "jsDoc": { "doc": "See {@link add} and {@link Missing.prop}." },
+      }),
+    ]);
+    let messages = report
+      .problems
+      .iter()
+      .map(|p| (p.code, p.message.as_str()))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      messages,
+      vec![
+        ("doc-missing-jsdoc", "`a` is missing JSDoc"),
+        ("doc-missing-param", "`add` is missing a @param tag for `b`"),
+        ("doc-missing-returns", "`add` is missing a @returns tag"),
+        (
+          "doc-broken-link",
+          "`B` links to `Missing.prop`, which does not exist"
+        ),
+      ]
+    );
+    assert_eq!(report.symbols_count, 3);
+    assert_eq!(report.documented_count, 0);
+  }
+
+  #[test]
+  fn accepts_complete_docs() {
+    let report = lint(vec![
+      json!({
+        "kind": "function",
+        "name": "add",
+        "location": location(),
+        "jsDoc": "Adds numbers.\n@param {number} a The number.\n@returns The sum.",
+        "functionDef": {
+          "params": [{ "kind": "identifier", "name": "a" }],
+          "returnType": { "repr": "number" },
+        },
+      }),
+      json!({
+        "kind": "namespace",
+        "name": "NS",
+        "location": location(),
+        "jsDoc": { "doc": "A namespace, see {@link add} and {@link https://deno.land}." },
+        "namespaceDef": { "elements": [
+          { "kind": "variable", "name": "b", "location": location(), "jsDoc": { "doc": "B." } },
+        ] },
+      }),
+    ]);
+    assert_eq!(report.problems, vec![]);
+    assert_eq!(report.symbols_count, 3);
+    assert_eq!(report.coverage(), 100.0);
+  }
+}
@@ -27,6 +27,9 @@ use import_map::ImportMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+mod html;
+mod lint;
+
 struct StubDocLoader;
 
 impl Loader for StubDocLoader {
@@ -172,6 +175,18 @@ pub async fn print_docs(
 
   if doc_flags.json {
     write_json_to_stdout(&doc_nodes)
+  } else if doc_flags.lint {
+    lint::lint_docs(&doc_nodes)?.report(doc_flags.lint_threshold)
+  } else if let Some(output) = doc_flags.html {
+    doc_nodes.retain(|doc_node| doc_node.kind != doc::DocNodeKind::Import);
+    let title = if source_file == "--builtin" {
+      "Deno"
+    } else {
+      &source_file
+    };
+    html::write_html_site(&doc_nodes, title, doc_flags.private, &output)?;
+    println!("Documentation site written to {}", output.display());
+    Ok(())
   } else {
     doc_nodes.retain(|doc_node| doc_node.kind != doc::DocNodeKind::Import);
     let details = if let Some(filter) = doc_flags.filter {
@@ -9,10 +9,14 @@
 
 use crate::colors;
 use crate::config_file::FmtConfig;
+use crate::config_file::FmtLanguageOptionsConfig;
 use crate::config_file::FmtOptionsConfig;
 use crate::config_file::ProseWrap;
 use crate::deno_dir::DenoDir;
 use crate::diff::diff;
+use crate::diff::diff_hunks;
+use crate::diff::unified_diff;
+use crate::diff::DiffHunk;
 use crate::file_watcher;
 use crate::file_watcher::ResolutionResult;
 use crate::flags::Flags;
@@ -28,8 +32,11 @@ use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::futures;
 use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use deno_core::ModuleSpecifier;
 use log::debug;
 use log::info;
+use serde::Serialize;
 use std::fs;
 use std::io::stdin;
 use std::io::stdout;
@@ -42,6 +49,7 @@ use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use super::incremental_cache::IncrementalCache;
+use super::incremental_cache::IncrementalCacheKind;
 
 /// Format JavaScript/TypeScript files.
 pub async fn format(
@@ -54,8 +62,10 @@ pub async fn format(
     files,
     ignore,
     check,
+    force,
     ..
   } = fmt_flags.clone();
+  let check_output = CheckOutput::from_flags(&fmt_flags);
 
   // First, prepare final configuration.
   // Collect included and ignored files. CLI flags take precendence
@@ -137,12 +147,20 @@ pub async fn format(
   };
   let operation = |(paths, fmt_options): (Vec<PathBuf>, FmtOptionsConfig)| async move {
     let incremental_cache = Arc::new(IncrementalCache::new(
-      &deno_dir.fmt_incremental_cache_db_file_path(),
+      &deno_dir.incremental_cache_db_file_path(),
+      IncrementalCacheKind::Fmt,
       &fmt_options,
       &paths,
+      force,
     ));
     if check {
-      check_source_files(paths, fmt_options, incremental_cache.clone()).await?;
+      check_source_files(
+        paths,
+        fmt_options,
+        check_output,
+        incremental_cache.clone(),
+      )
+      .await?;
     } else {
       format_source_files(paths, fmt_options, incremental_cache.clone())
         .await?;
@@ -155,10 +173,7 @@ pub async fn format(
     file_watcher::watch_func(
       resolver,
       operation,
-      file_watcher::PrintConfig {
-        job_name: "Fmt".to_string(),
-        clear_screen: !flags.no_clear_screen,
-      },
+      file_watcher::WatcherConfig::new("Fmt", flags)?,
     )
     .await?;
   } else {
@@ -223,6 +238,13 @@ fn format_markdown(
             &codeblock_config,
           )
         }
+      } else if HAS_EXTRA_FORMATTERS
+        && matches!(tag.as_str(), "css" | "yml" | "yaml" | "sql")
+      {
+        let fake_filename = PathBuf::from(format!("deno_fmt_stdin.{}", tag));
+        let mut options = get_language_options(fmt_options, &tag);
+        options.line_width = Some(line_width);
+        format_language(&fake_filename, &tag, text, &options)
       } else {
         Ok(None)
       }
@@ -241,7 +263,143 @@ pub fn format_json(
   dprint_plugin_json::format_text(file_text, &config)
 }
 
-/// Formats a single TS, TSX, JS, JSX, JSONC, JSON, or MD file.
+/// Whether `deno fmt` was built with the `extra_formatters` feature, which
+/// adds the CSS, YAML and SQL formatters.
+const HAS_EXTRA_FORMATTERS: bool = cfg!(feature = "extra_formatters");
+
+/// Formats CSS using <https://github.com/g-plane/malva>.
+#[cfg(feature = "extra_formatters")]
+fn format_css(
+  file_text: &str,
+  options: &FmtLanguageOptionsConfig,
+) -> Result<Option<String>, AnyError> {
+  let config = malva::config::FormatOptions {
+    layout: malva::config::LayoutOptions {
+      print_width: options.line_width.unwrap_or(80) as usize,
+      use_tabs: options.use_tabs.unwrap_or(false),
+      indent_width: options.indent_width.unwrap_or(2) as usize,
+      ..Default::default()
+    },
+    language: malva::config::LanguageOptions {
+      quotes: if options.single_quote == Some(true) {
+        malva::config::Quotes::PreferSingle
+      } else {
+        malva::config::Quotes::PreferDouble
+      },
+      ..Default::default()
+    },
+  };
+  let formatted = malva::format_text(file_text, malva::Syntax::Css, &config)
+    .map_err(|err| generic_error(err.to_string()))?;
+  Ok(changed_text(file_text, formatted))
+}
+
+/// Formats YAML using <https://github.com/g-plane/pretty_yaml>.
+#[cfg(feature = "extra_formatters")]
+fn format_yaml(
+  file_text: &str,
+  options: &FmtLanguageOptionsConfig,
+) -> Result<Option<String>, AnyError> {
+  let config = pretty_yaml::config::FormatOptions {
+    layout: pretty_yaml::config::LayoutOptions {
+      print_width: options.line_width.unwrap_or(80) as usize,
+      use_tabs: options.use_tabs.unwrap_or(false),
+      indent_width: options.indent_width.unwrap_or(2) as usize,
+      ..Default::default()
+    },
+    language: pretty_yaml::config::LanguageOptions {
+      quotes: if options.single_quote == Some(true) {
+        pretty_yaml::config::Quotes::PreferSingle
+      } else {
+        pretty_yaml::config::Quotes::PreferDouble
+      },
+      ..Default::default()
+    },
+  };
+  let formatted = pretty_yaml::format_text(file_text, &config)
+    .map_err(|err| generic_error(err.to_string()))?;
+  Ok(changed_text(file_text, formatted))
+}
+
+/// Formats SQL using <https://github.com/dprint/dprint-plugin-sql>.
+#[cfg(feature = "extra_formatters")]
+fn format_sql(
+  file_path: &Path,
+  file_text: &str,
+  options: &FmtLanguageOptionsConfig,
+) -> Result<Option<String>, AnyError> {
+  let mut builder =
+    dprint_plugin_sql::configuration::ConfigurationBuilder::new();
+
+  if let Some(use_tabs) = options.use_tabs {
+    builder.use_tabs(use_tabs);
+  }
+
+  if let Some(indent_width) = options.indent_width {
+    builder.indent_width(indent_width);
+  }
+
+  if let Some(uppercase_keywords) = options.uppercase_keywords {
+    builder.uppercase(uppercase_keywords);
+  }
+
+  let formatted =
+    dprint_plugin_sql::format_text(file_path, file_text, &builder.build())?;
+  Ok(changed_text(file_text, formatted))
+}
+
+/// Returns `None` when formatting left the text unchanged, as the dprint
+/// plugins do.
+#[cfg(feature = "extra_formatters")]
+fn changed_text(file_text: &str, formatted: String) -> Option<String> {
+  if formatted == file_text {
+    None
+  } else {
+    Some(formatted)
+  }
+}
+
+/// Resolves the options for a file formatted with `format_language`.
+fn get_language_options(
+  fmt_options: &FmtOptionsConfig,
+  ext: &str,
+) -> FmtLanguageOptionsConfig {
+  fmt_options.for_language(match ext {
+    "css" => fmt_options.css.as_ref(),
+    "sql" => fmt_options.sql.as_ref(),
+    _ => fmt_options.yaml.as_ref(),
+  })
+}
+
+/// Formats a CSS, YAML or SQL file, given its extension.
+#[cfg(feature = "extra_formatters")]
+fn format_language(
+  file_path: &Path,
+  ext: &str,
+  file_text: &str,
+  options: &FmtLanguageOptionsConfig,
+) -> Result<Option<String>, AnyError> {
+  match ext {
+    "css" => format_css(file_text, options),
+    "sql" => format_sql(file_path, file_text, options),
+    _ => format_yaml(file_text, options),
+  }
+}
+
+#[cfg(not(feature = "extra_formatters"))]
+fn format_language(
+  _file_path: &Path,
+  ext: &str,
+  _file_text: &str,
+  _options: &FmtLanguageOptionsConfig,
+) -> Result<Option<String>, AnyError> {
+  Err(generic_error(format!(
+    "Formatting .{} files requires deno to be built with the \"extra_formatters\" feature",
+    ext
+  )))
+}
+
+/// Formats a single TS, TSX, JS, JSX, JSONC, JSON, MD, CSS, YAML or SQL file.
 pub fn format_file(
   file_path: &Path,
   file_text: &str,
@@ -255,6 +413,9 @@ pub fn format_file(
     format_markdown(file_text, fmt_options)
   } else if matches!(ext.as_str(), "json" | "jsonc") {
     format_json(file_text, fmt_options)
+  } else if matches!(ext.as_str(), "css" | "yml" | "yaml" | "sql") {
+    let options = get_language_options(fmt_options, &ext);
+    format_language(file_path, &ext, file_text, &options)
   } else {
     let config = get_resolved_typescript_config(fmt_options);
     dprint_plugin_typescript::format_text(file_path, file_text, &config)
@@ -271,13 +432,72 @@ pub fn format_parsed_source(
   )
 }
 
+/// How `deno fmt --check` reports the files that are not formatted.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CheckOutput {
+  /// A colored diff of every file, for humans.
+  Pretty,
+  /// A diff in the unified format, as understood by `patch` and `git apply`.
+  UnifiedDiff,
+  /// A JSON document printed once all files were checked.
+  Json,
+}
+
+impl CheckOutput {
+  fn from_flags(fmt_flags: &FmtFlags) -> Self {
+    if fmt_flags.json {
+      CheckOutput::Json
+    } else if fmt_flags.unified_diff {
+      CheckOutput::UnifiedDiff
+    } else {
+      CheckOutput::Pretty
+    }
+  }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonCheckFile {
+  file_path: String,
+  hunks: Vec<DiffHunk>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonCheckError {
+  file_path: String,
+  message: String,
+}
+
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonCheckResult {
+  checked_files_count: usize,
+  not_formatted_files: Vec<JsonCheckFile>,
+  errors: Vec<JsonCheckError>,
+}
+
+/// Displays a path relative to the current directory when it's inside of it,
+/// so that diffs can be applied from there.
+fn display_relative_path(file_path: &Path) -> String {
+  std::env::current_dir()
+    .ok()
+    .and_then(|cwd| ModuleSpecifier::from_directory_path(cwd).ok())
+    .zip(ModuleSpecifier::from_file_path(file_path).ok())
+    .and_then(|(cwd, url)| cwd.make_relative(&url))
+    .filter(|relative| !relative.starts_with("../"))
+    .unwrap_or_else(|| file_path.display().to_string())
+}
+
 async fn check_source_files(
   paths: Vec<PathBuf>,
   fmt_options: FmtOptionsConfig,
+  check_output: CheckOutput,
   incremental_cache: Arc<IncrementalCache>,
 ) -> Result<(), AnyError> {
   let not_formatted_files_count = Arc::new(AtomicUsize::new(0));
   let checked_files_count = Arc::new(AtomicUsize::new(0));
+  let json_result = Arc::new(Mutex::new(JsonCheckResult::default()));
 
   // prevent threads outputting at the same time
   let output_lock = Arc::new(Mutex::new(0));
@@ -285,6 +505,7 @@ async fn check_source_files(
   run_parallelized(paths, {
     let not_formatted_files_count = not_formatted_files_count.clone();
     let checked_files_count = checked_files_count.clone();
+    let json_result = json_result.clone();
     move |file_path| {
       checked_files_count.fetch_add(1, Ordering::Relaxed);
       let file_text = read_file_contents(&file_path)?.text;
@@ -297,11 +518,32 @@ async fn check_source_files(
       match format_file(&file_path, &file_text, &fmt_options) {
         Ok(Some(formatted_text)) => {
           not_formatted_files_count.fetch_add(1, Ordering::Relaxed);
-          let _g = output_lock.lock();
-          let diff = diff(&file_text, &formatted_text);
-          info!("");
-          info!("{} {}:", colors::bold("from"), file_path.display());
-          info!("{}", diff);
+          match check_output {
+            CheckOutput::Pretty => {
+              let _g = output_lock.lock();
+              let diff = diff(&file_text, &formatted_text);
+              info!("");
+              info!("{} {}:", colors::bold("from"), file_path.display());
+              info!("{}", diff);
+            }
+            CheckOutput::UnifiedDiff => {
+              let path = display_relative_path(&file_path);
+              let diff = unified_diff(
+                &format!("a/{}", path),
+                &format!("b/{}", path),
+                &file_text,
+                &formatted_text,
+              );
+              let _g = output_lock.lock();
+              print!("{}", diff);
+            }
+            CheckOutput::Json => {
+              json_result.lock().not_formatted_files.push(JsonCheckFile {
+                file_path: display_relative_path(&file_path),
+                hunks: diff_hunks(&file_text, &formatted_text, 3),
+              });
+            }
+          }
         }
         Ok(None) => {
           // When checking formatting, only update the incremental cache when
@@ -311,6 +553,12 @@ async fn check_source_files(
           // incremental formatting
           incremental_cache.update_file(&file_path, &file_text);
         }
+        Err(e) if check_output == CheckOutput::Json => {
+          json_result.lock().errors.push(JsonCheckError {
+            file_path: display_relative_path(&file_path),
+            message: e.to_string(),
+          });
+        }
         Err(e) => {
           let _g = output_lock.lock();
           eprintln!("Error checking: {}", file_path.to_string_lossy());
@@ -325,6 +573,18 @@ async fn check_source_files(
   let not_formatted_files_count =
     not_formatted_files_count.load(Ordering::Relaxed);
   let checked_files_count = checked_files_count.load(Ordering::Relaxed);
+  if check_output == CheckOutput::Json {
+    let mut json_result = json_result.lock();
+    json_result.checked_files_count = checked_files_count;
+    // sort for a deterministic output
+    json_result
+      .not_formatted_files
+      .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+    json_result
+      .errors
+      .sort_by(|a, b| a.file_path.cmp(&b.file_path));
+    println!("{}", serde_json::to_string_pretty(&*json_result)?);
+  }
   let checked_files_str =
     format!("{} {}", checked_files_count, files_str(checked_files_count));
   if not_formatted_files_count == 0 {
@@ -486,8 +746,35 @@ pub fn format_stdin(
 
   let formatted_text = format_file(&file_path, &source, &fmt_options)?;
   if fmt_flags.check {
-    if formatted_text.is_some() {
-      println!("Not formatted stdin");
+    match CheckOutput::from_flags(&fmt_flags) {
+      CheckOutput::Pretty => {
+        if formatted_text.is_some() {
+          println!("Not formatted stdin");
+        }
+      }
+      CheckOutput::UnifiedDiff => {
+        if let Some(formatted_text) = formatted_text {
+          print!(
+            "{}",
+            unified_diff("a/stdin", "b/stdin", &source, &formatted_text)
+          );
+        }
+      }
+      CheckOutput::Json => {
+        let not_formatted_files = formatted_text
+          .map(|formatted_text| JsonCheckFile {
+            file_path: "stdin".to_string(),
+            hunks: diff_hunks(&source, &formatted_text, 3),
+          })
+          .into_iter()
+          .collect();
+        let json_result = JsonCheckResult {
+          checked_files_count: 1,
+          not_formatted_files,
+          errors: vec![],
+        };
+        println!("{}", serde_json::to_string_pretty(&json_result)?);
+      }
     }
   } else {
     stdout().write_all(formatted_text.unwrap_or(source).as_bytes())?;
@@ -525,6 +812,10 @@ fn resolve_fmt_options(
     options.single_quote = Some(single_quote);
   }
 
+  if let Some(semi_colons) = fmt_flags.semi_colons {
+    options.semi_colons = Some(semi_colons);
+  }
+
   if let Some(prose_wrap) = &fmt_flags.prose_wrap {
     options.prose_wrap = Some(match prose_wrap.as_str() {
       "always" => ProseWrap::Always,
@@ -565,6 +856,14 @@ fn get_resolved_typescript_config(
     }
   }
 
+  if let Some(semi_colons) = options.semi_colons {
+    builder.semi_colons(if semi_colons {
+      dprint_plugin_typescript::configuration::SemiColons::Prefer
+    } else {
+      dprint_plugin_typescript::configuration::SemiColons::Asi
+    });
+  }
+
   builder.build()
 }
 
@@ -716,7 +1015,8 @@ fn is_supported_ext_fmt(path: &Path) -> bool {
         | "mdwn"
         | "mdown"
         | "markdown"
-    )
+    ) || HAS_EXTRA_FORMATTERS
+      && matches!(ext.as_str(), "css" | "yml" | "yaml" | "sql")
   } else {
     false
   }
@@ -755,6 +1055,27 @@ mod test {
     assert!(is_supported_ext_fmt(Path::new("foo.JSONC")));
     assert!(is_supported_ext_fmt(Path::new("foo.json")));
     assert!(is_supported_ext_fmt(Path::new("foo.JsON")));
+    assert_eq!(
+      is_supported_ext_fmt(Path::new("styles.css")),
+      HAS_EXTRA_FORMATTERS
+    );
+    assert_eq!(
+      is_supported_ext_fmt(Path::new("styles.CSS")),
+      HAS_EXTRA_FORMATTERS
+    );
+    assert_eq!(
+      is_supported_ext_fmt(Path::new("config.yml")),
+      HAS_EXTRA_FORMATTERS
+    );
+    assert_eq!(
+      is_supported_ext_fmt(Path::new("config.yaml")),
+      HAS_EXTRA_FORMATTERS
+    );
+    assert_eq!(
+      is_supported_ext_fmt(Path::new("schema.sql")),
+      HAS_EXTRA_FORMATTERS
+    );
+    assert!(!is_supported_ext_fmt(Path::new("styles.scss")));
   }
 
   #[test]
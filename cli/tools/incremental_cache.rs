@@ -10,20 +10,49 @@ use deno_runtime::deno_webstorage::rusqlite::Connection;
 use serde::Serialize;
 use tokio::task::JoinHandle;
 
+/// The tool an incremental cache entry belongs to. Every tool has its own
+/// entries in the shared cache database.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IncrementalCacheKind {
+  Fmt,
+  Lint,
+}
+
+impl IncrementalCacheKind {
+  fn as_str(&self) -> &'static str {
+    match self {
+      IncrementalCacheKind::Fmt => "fmt",
+      IncrementalCacheKind::Lint => "lint",
+    }
+  }
+}
+
 /// Cache used to skip formatting/linting a file again when we
 /// know it is already formatted or has no lint diagnostics.
 pub struct IncrementalCache(Option<IncrementalCacheInner>);
 
 impl IncrementalCache {
+  /// Opens the cache of `kind` in the database at `db_file_path`. Entries
+  /// only apply when they were stored with the same `state`, which should
+  /// capture everything other than the file text that affects the result.
+  /// When `force` is set, previous entries are ignored, but the cache is
+  /// still updated with the results of this run.
   pub fn new<TState: Serialize>(
     db_file_path: &Path,
+    kind: IncrementalCacheKind,
     state: &TState,
     initial_file_paths: &[PathBuf],
+    force: bool,
   ) -> Self {
     // if creating the incremental cache fails, then we
     // treat it as not having a cache
-    let result =
-      IncrementalCacheInner::new(db_file_path, state, initial_file_paths);
+    let result = IncrementalCacheInner::new(
+      db_file_path,
+      kind,
+      state,
+      initial_file_paths,
+      force,
+    );
     IncrementalCache(match result {
       Ok(inner) => Some(inner),
       Err(err) => {
@@ -71,12 +100,17 @@ struct IncrementalCacheInner {
 impl IncrementalCacheInner {
   pub fn new<TState: Serialize>(
     db_file_path: &Path,
+    kind: IncrementalCacheKind,
     state: &TState,
     initial_file_paths: &[PathBuf],
+    force: bool,
   ) -> Result<Self, AnyError> {
     let state_hash =
       fast_insecure_hash(serde_json::to_string(state).unwrap().as_bytes());
-    let sql_cache = SqlIncrementalCache::new(db_file_path, state_hash)?;
+    let sql_cache = SqlIncrementalCache::new(db_file_path, kind, state_hash)?;
+    // when forced, start out as if nothing was cached
+    let initial_file_paths: &[PathBuf] =
+      if force { &[] } else { initial_file_paths };
     Ok(Self::from_sql_incremental_cache(
       sql_cache,
       initial_file_paths,
@@ -148,6 +182,7 @@ impl IncrementalCacheInner {
 
 struct SqlIncrementalCache {
   conn: Connection,
+  kind: IncrementalCacheKind,
   /// A hash of the state used to produce the formatting/linting other than
   /// the CLI version. This state is a hash of the configuration and ensures
   /// we format/lint a file when the configuration changes.
@@ -155,20 +190,29 @@ struct SqlIncrementalCache {
 }
 
 impl SqlIncrementalCache {
-  pub fn new(db_file_path: &Path, state_hash: u64) -> Result<Self, AnyError> {
+  pub fn new(
+    db_file_path: &Path,
+    kind: IncrementalCacheKind,
+    state_hash: u64,
+  ) -> Result<Self, AnyError> {
     let conn = Connection::open(db_file_path)?;
-    Self::from_connection(conn, state_hash, crate::version::deno())
+    Self::from_connection(conn, kind, state_hash, crate::version::deno())
   }
 
   fn from_connection(
     conn: Connection,
+    kind: IncrementalCacheKind,
     state_hash: u64,
     cli_version: String,
   ) -> Result<Self, AnyError> {
     run_pragma(&conn)?;
     create_tables(&conn, cli_version)?;
 
-    Ok(Self { conn, state_hash })
+    Ok(Self {
+      conn,
+      kind,
+      state_hash,
+    })
   }
 
   pub fn get_source_hash(&self, path: &Path) -> Option<u64> {
@@ -195,12 +239,16 @@ impl SqlIncrementalCache {
       FROM
         incrementalcache
       WHERE
-        file_path=?1
-        AND state_hash=?2
+        kind=?1
+        AND file_path=?2
+        AND state_hash=?3
       LIMIT 1";
     let mut stmt = self.conn.prepare_cached(query)?;
-    let mut rows = stmt
-      .query(params![path.to_string_lossy(), self.state_hash.to_string()])?;
+    let mut rows = stmt.query(params![
+      self.kind.as_str(),
+      path.to_string_lossy(),
+      self.state_hash.to_string()
+    ])?;
     if let Some(row) = rows.next()? {
       let hash: String = row.get(0)?;
       Ok(Some(hash.parse::<u64>()?))
@@ -216,11 +264,12 @@ impl SqlIncrementalCache {
   ) -> Result<(), AnyError> {
     let sql = "
       INSERT OR REPLACE INTO
-        incrementalcache (file_path, state_hash, source_hash)
+        incrementalcache (kind, file_path, state_hash, source_hash)
       VALUES
-        (?1, ?2, ?3)";
+        (?1, ?2, ?3, ?4)";
     let mut stmt = self.conn.prepare_cached(sql)?;
     stmt.execute(params![
+      self.kind.as_str(),
       path.to_string_lossy(),
       &self.state_hash.to_string(),
       &source_hash.to_string(),
@@ -252,9 +301,11 @@ fn create_tables(
   // INT doesn't store up to u64, so use TEXT
   conn.execute(
     "CREATE TABLE IF NOT EXISTS incrementalcache (
-        file_path TEXT PRIMARY KEY,
+        kind TEXT NOT NULL,
+        file_path TEXT NOT NULL,
         state_hash TEXT NOT NULL,
-        source_hash TEXT NOT NULL
+        source_hash TEXT NOT NULL,
+        PRIMARY KEY (kind, file_path)
       )",
     [],
   )?;
@@ -300,11 +351,13 @@ mod test {
 
   use super::*;
 
+  const FMT: IncrementalCacheKind = IncrementalCacheKind::Fmt;
+
   #[test]
   pub fn sql_cache_general_use() {
     let conn = Connection::open_in_memory().unwrap();
     let cache =
-      SqlIncrementalCache::from_connection(conn, 1, "1.0.0".to_string())
+      SqlIncrementalCache::from_connection(conn, FMT, 1, "1.0.0".to_string())
         .unwrap();
     let path = PathBuf::from("/mod.ts");
 
@@ -315,7 +368,7 @@ mod test {
     // try changing the cli version (should clear)
     let conn = cache.conn;
     let mut cache =
-      SqlIncrementalCache::from_connection(conn, 1, "2.0.0".to_string())
+      SqlIncrementalCache::from_connection(conn, FMT, 1, "2.0.0".to_string())
         .unwrap();
     assert_eq!(cache.get_source_hash(&path), None);
 
@@ -334,7 +387,7 @@ mod test {
     // recreating the cache should not remove the data because the CLI version and state hash is the same
     let conn = cache.conn;
     let cache =
-      SqlIncrementalCache::from_connection(conn, 1, "2.0.0".to_string())
+      SqlIncrementalCache::from_connection(conn, FMT, 1, "2.0.0".to_string())
         .unwrap();
     assert_eq!(cache.get_source_hash(&path), Some(2));
 
@@ -345,13 +398,31 @@ mod test {
     cache.set_source_hash(&path2, 5).unwrap();
     assert_eq!(cache.get_source_hash(&path), Some(4));
     assert_eq!(cache.get_source_hash(&path2), Some(5));
+
+    // entries of another kind are kept separately
+    let conn = cache.conn;
+    let cache = SqlIncrementalCache::from_connection(
+      conn,
+      IncrementalCacheKind::Lint,
+      1,
+      "2.0.0".to_string(),
+    )
+    .unwrap();
+    assert_eq!(cache.get_source_hash(&path), None);
+    cache.set_source_hash(&path, 6).unwrap();
+    assert_eq!(cache.get_source_hash(&path), Some(6));
+    let conn = cache.conn;
+    let cache =
+      SqlIncrementalCache::from_connection(conn, FMT, 1, "2.0.0".to_string())
+        .unwrap();
+    assert_eq!(cache.get_source_hash(&path), Some(4));
   }
 
   #[tokio::test]
   pub async fn incremental_cache_general_use() {
     let conn = Connection::open_in_memory().unwrap();
     let sql_cache =
-      SqlIncrementalCache::from_connection(conn, 1, "1.0.0".to_string())
+      SqlIncrementalCache::from_connection(conn, FMT, 1, "1.0.0".to_string())
         .unwrap();
     let file_path = PathBuf::from("/mod.ts");
     let file_text = "test";
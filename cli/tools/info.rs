@@ -0,0 +1,216 @@
+// Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::anyhow;
+use deno_core::error::AnyError;
+use deno_core::resolve_url_or_path;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_graph::ModuleGraph;
+use deno_graph::ModuleSpecifier;
+use deno_runtime::colors;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+/// Resolves the module passed to `--why`, returning an error when it is not
+/// part of the module graph.
+pub fn resolve_why_specifier(
+  graph: &ModuleGraph,
+  why: &str,
+) -> Result<ModuleSpecifier, AnyError> {
+  let specifier = resolve_url_or_path(why)?;
+  match graph.get(&specifier) {
+    Some(module) => Ok(module.specifier.clone()),
+    None => Err(anyhow!(
+      "Module \"{}\" is not part of the module graph.",
+      specifier
+    )),
+  }
+}
+
+/// Gets the shortest import chain from each root of the graph to the target
+/// module, where each chain starts at a root and ends at the target. Roots
+/// that do not lead to the target have no chain.
+pub fn import_chains(
+  graph: &ModuleGraph,
+  target: &ModuleSpecifier,
+) -> Vec<Vec<ModuleSpecifier>> {
+  let mut chains = Vec::new();
+  for (root, _) in &graph.roots {
+    let root = match graph.get(root) {
+      Some(module) => module.specifier.clone(),
+      None => continue,
+    };
+    if let Some(chain) = shortest_chain(graph, &root, target) {
+      if !chains.contains(&chain) {
+        chains.push(chain);
+      }
+    }
+  }
+  chains
+}
+
+/// Searches the graph breadth first, so that every module is visited once
+/// and the first chain found is a shortest one.
+fn shortest_chain(
+  graph: &ModuleGraph,
+  root: &ModuleSpecifier,
+  target: &ModuleSpecifier,
+) -> Option<Vec<ModuleSpecifier>> {
+  // maps each visited module to the module that first imported it
+  let mut importers: HashMap<ModuleSpecifier, Option<ModuleSpecifier>> =
+    HashMap::new();
+  let mut pending = VecDeque::new();
+  importers.insert(root.clone(), None);
+  pending.push_back(root.clone());
+  while let Some(specifier) = pending.pop_front() {
+    if &specifier == target {
+      let mut chain = vec![specifier];
+      while let Some(Some(importer)) = importers.get(chain.last().unwrap()) {
+        chain.push(importer.clone());
+      }
+      chain.reverse();
+      return Some(chain);
+    }
+    for dependency in dependencies(graph, &specifier) {
+      if !importers.contains_key(&dependency) {
+        importers.insert(dependency.clone(), Some(specifier.clone()));
+        pending.push_back(dependency);
+      }
+    }
+  }
+  None
+}
+
+/// Gets the size of each module along with all the modules it depends on,
+/// counting every module only once.
+pub fn transitive_sizes(
+  graph: &ModuleGraph,
+) -> HashMap<ModuleSpecifier, usize> {
+  let sizes = graph
+    .modules()
+    .into_iter()
+    .map(|m| {
+      let size = m.maybe_source.as_ref().map(|s| s.len()).unwrap_or(0);
+      (m.specifier.clone(), size)
+    })
+    .collect::<HashMap<_, _>>();
+  sizes
+    .keys()
+    .map(|specifier| {
+      let mut seen = HashSet::new();
+      let mut pending = vec![specifier.clone()];
+      let mut total = 0;
+      while let Some(specifier) = pending.pop() {
+        if seen.insert(specifier.clone()) {
+          total += sizes.get(&specifier).copied().unwrap_or(0);
+          pending.extend(dependencies(graph, &specifier));
+        }
+      }
+      (specifier.clone(), total)
+    })
+    .collect()
+}
+
+/// Adds the `transitiveSize` of each module to the JSON output of the graph.
+pub fn add_transitive_sizes(graph: &ModuleGraph, value: &mut Value) {
+  let sizes = transitive_sizes(graph);
+  if let Some(modules) = value.get_mut("modules").and_then(|m| m.as_array_mut())
+  {
+    for module in modules {
+      let size = module
+        .get("specifier")
+        .and_then(|s| s.as_str())
+        .and_then(|s| ModuleSpecifier::parse(s).ok())
+        .and_then(|s| sizes.get(&s).copied());
+      if let (Some(size), Some(module)) = (size, module.as_object_mut()) {
+        module.insert("transitiveSize".to_string(), json!(size));
+      }
+    }
+  }
+}
+
+/// The JSON value of `--why`.
+pub fn why_json(
+  target: &ModuleSpecifier,
+  chains: &[Vec<ModuleSpecifier>],
+) -> Value {
+  json!({
+    "specifier": target,
+    "chains": chains,
+  })
+}
+
+/// Formats the import chains for display, one indented line per import.
+pub fn format_chains(
+  target: &ModuleSpecifier,
+  chains: &[Vec<ModuleSpecifier>],
+) -> String {
+  let mut text = String::new();
+  if chains.is_empty() {
+    writeln!(
+      text,
+      "No import chains lead to {}",
+      colors::bold(target.as_str())
+    )
+    .unwrap();
+    return text;
+  }
+  writeln!(
+    text,
+    "{} {} {}:",
+    colors::bold(chains.len().to_string()),
+    if chains.len() == 1 {
+      "import chain leads to"
+    } else {
+      "import chains lead to"
+    },
+    colors::bold(target.as_str())
+  )
+  .unwrap();
+  for chain in chains {
+    writeln!(text).unwrap();
+    for (depth, specifier) in chain.iter().enumerate() {
+      if depth == 0 {
+        writeln!(text, "{}", specifier).unwrap();
+      } else {
+        writeln!(
+          text,
+          "{}{} {}",
+          "  ".repeat(depth - 1),
+          colors::gray("└─"),
+          specifier
+        )
+        .unwrap();
+      }
+    }
+  }
+  text
+}
+
+/// Gets the unique code and type dependencies of a module.
+fn dependencies(
+  graph: &ModuleGraph,
+  specifier: &ModuleSpecifier,
+) -> Vec<ModuleSpecifier> {
+  let module = match graph.get(specifier) {
+    Some(module) => module,
+    None => return Vec::new(),
+  };
+  let mut result = Vec::new();
+  for text in module.dependencies.keys() {
+    for prefer_types in [false, true] {
+      if let Some(resolved) =
+        graph.resolve_dependency(text, &module.specifier, prefer_types)
+      {
+        if let Some(module) = graph.get(resolved) {
+          if !result.contains(&module.specifier) {
+            result.push(module.specifier.clone());
+          }
+        }
+      }
+    }
+  }
+  result
+}
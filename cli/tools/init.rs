@@ -0,0 +1,289 @@
+// Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
+
+//! This module provides the `deno init` scaffolding of new projects.
+
+use crate::colors;
+use crate::compat::STD_URL_STR;
+use crate::flags::Flags;
+use crate::flags::InitFlags;
+use crate::proc_state::ProcState;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::resolve_url_or_path;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_runtime::permissions::Permissions;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// A template manifest fetched with `deno init --from`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TemplateManifest {
+  /// The contents of the files of the project by their relative path.
+  files: BTreeMap<String, String>,
+}
+
+const CLI_MAIN: &str = r#"export function add(a: number, b: number): number {
+  return a + b;
+}
+
+// Learn more at https://deno.land/manual/examples/module_metadata#concepts
+if (import.meta.main) {
+  const [a, b] = Deno.args.map(Number);
+  console.log(`${a} + ${b} = ${add(a, b)}`);
+}
+"#;
+
+const CLI_TEST: &str = r#"import { assertEquals } from "{std}testing/asserts.ts";
+import { add } from "./main.ts";
+
+Deno.test(function addTest() {
+  assertEquals(add(2, 3), 5);
+});
+"#;
+
+const SERVER_MAIN: &str = r#"import { serve } from "{std}http/server.ts";
+
+export function handler(req: Request): Response {
+  const { pathname } = new URL(req.url);
+  return new Response(`Hello from ${pathname}`);
+}
+
+if (import.meta.main) {
+  serve(handler, { port: 8000 });
+}
+"#;
+
+const SERVER_TEST: &str = r#"import { assertEquals } from "{std}testing/asserts.ts";
+import { handler } from "./main.ts";
+
+Deno.test(async function handlerTest() {
+  const res = handler(new Request("http://localhost:8000/deno"));
+  assertEquals(await res.text(), "Hello from /deno");
+});
+"#;
+
+const LIBRARY_MOD: &str = r#"/** Returns a greeting for `name`. */
+export function greet(name: string): string {
+  return `Hello, ${name}!`;
+}
+"#;
+
+const LIBRARY_TEST: &str = r#"import { assertEquals } from "{std}testing/asserts.ts";
+import { greet } from "./mod.ts";
+
+Deno.test(function greetTest() {
+  assertEquals(greet("Deno"), "Hello, Deno!");
+});
+"#;
+
+/// Returns the files of a built-in template by their relative path.
+fn get_builtin_template_files(
+  template: &str,
+  import_map: bool,
+) -> Result<BTreeMap<String, String>, AnyError> {
+  let (files, tasks) = match template {
+    "cli" => (
+      vec![("main.ts", CLI_MAIN), ("main_test.ts", CLI_TEST)],
+      json!({
+        "start": "deno run main.ts",
+        "dev": "deno run --watch main.ts",
+        "test": "deno test",
+      }),
+    ),
+    "server" => (
+      vec![("main.ts", SERVER_MAIN), ("main_test.ts", SERVER_TEST)],
+      json!({
+        "start": "deno run --allow-net main.ts",
+        "dev": "deno run --allow-net --watch main.ts",
+        "test": "deno test",
+      }),
+    ),
+    "library" => (
+      vec![("mod.ts", LIBRARY_MOD), ("mod_test.ts", LIBRARY_TEST)],
+      json!({
+        "check": "deno check mod.ts",
+        "doc": "deno doc mod.ts",
+        "test": "deno test",
+      }),
+    ),
+    _ => bail!("Unknown template: {}", template),
+  };
+
+  // with an import map the modules import the standard library by a bare
+  // specifier, so that its version is only defined in one place
+  let std_prefix = if import_map { "std/" } else { STD_URL_STR };
+  let mut config = json!({ "tasks": tasks });
+  let mut template_files = files
+    .into_iter()
+    .map(|(path, text)| (path.to_string(), text.replace("{std}", std_prefix)))
+    .collect::<BTreeMap<_, _>>();
+  if import_map {
+    config["importMap"] = json!("./import_map.json");
+    template_files.insert(
+      "import_map.json".to_string(),
+      to_json_text(&json!({ "imports": { "std/": STD_URL_STR } })),
+    );
+  }
+  template_files.insert("deno.json".to_string(), to_json_text(&config));
+  Ok(template_files)
+}
+
+fn get_vscode_settings() -> String {
+  to_json_text(&json!({
+    "deno.enable": true,
+    "deno.lint": true,
+  }))
+}
+
+fn to_json_text(value: &serde_json::Value) -> String {
+  format!("{}\n", serde_json::to_string_pretty(value).unwrap())
+}
+
+/// Ensures a path of a template file stays within the project directory.
+fn validate_template_path(path: &str) -> Result<(), AnyError> {
+  let is_valid = !path.is_empty()
+    && Path::new(path)
+      .components()
+      .all(|component| matches!(component, Component::Normal(_)));
+  if !is_valid {
+    bail!(
+      "Template file paths must be relative and within the project: {}",
+      path
+    );
+  }
+  Ok(())
+}
+
+async fn fetch_template_files(
+  flags: Flags,
+  template_url: &str,
+) -> Result<BTreeMap<String, String>, AnyError> {
+  let specifier = resolve_url_or_path(template_url)?;
+  let ps = ProcState::build(Arc::new(flags)).await?;
+  let file = ps
+    .file_fetcher
+    .fetch(&specifier, &mut Permissions::allow_all())
+    .await?;
+  let manifest: TemplateManifest = serde_json::from_str(&file.source)
+    .with_context(|| format!("Failed to parse template: {}", specifier))?;
+  Ok(manifest.files)
+}
+
+pub async fn init_project(
+  flags: Flags,
+  init_flags: InitFlags,
+) -> Result<(), AnyError> {
+  let mut files = if let Some(template_url) = &init_flags.template_url {
+    fetch_template_files(flags, template_url).await?
+  } else {
+    let template = init_flags.template.as_deref().unwrap_or("cli");
+    get_builtin_template_files(template, init_flags.import_map)?
+  };
+  if init_flags.vscode {
+    files.insert(".vscode/settings.json".to_string(), get_vscode_settings());
+  }
+
+  let dir = match init_flags.dir {
+    Some(dir) => std::env::current_dir()?.join(dir),
+    None => std::env::current_dir()?,
+  };
+  let mut file_paths = Vec::with_capacity(files.len());
+  for path in files.keys() {
+    validate_template_path(path)?;
+    let file_path = dir.join(path);
+    if file_path.exists() && !init_flags.force {
+      bail!(
+        "File already exists: {}\nUse --force to overwrite it.",
+        file_path.display()
+      );
+    }
+    file_paths.push(file_path);
+  }
+
+  for (file_path, text) in file_paths.iter().zip(files.values()) {
+    if let Some(parent) = file_path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(file_path, text)
+      .with_context(|| format!("Failed writing {}", file_path.display()))?;
+  }
+
+  log::info!(
+    "{} {}",
+    colors::green("Project initialized in"),
+    dir.display()
+  );
+  for path in files.keys() {
+    log::info!("  {}", path);
+  }
+  print_next_steps(&dir, &files);
+  Ok(())
+}
+
+fn print_next_steps(dir: &Path, files: &BTreeMap<String, String>) {
+  let maybe_tasks = files
+    .get("deno.json")
+    .and_then(|text| serde_json::from_str::<serde_json::Value>(text).ok())
+    .and_then(|config| config.get("tasks").cloned())
+    .and_then(|tasks| tasks.as_object().cloned());
+  if let Some(tasks) = maybe_tasks {
+    log::info!("");
+    log::info!("Run the tasks of the project with:");
+    if dir != std::env::current_dir().unwrap_or_else(|_| PathBuf::new()) {
+      log::info!("  cd {}", dir.display());
+    }
+    for name in tasks.keys() {
+      log::info!("  deno task {}", name);
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn builtin_templates() {
+    let files = get_builtin_template_files("cli", false).unwrap();
+    assert_eq!(
+      files.keys().collect::<Vec<_>>(),
+      vec!["deno.json", "main.ts", "main_test.ts"]
+    );
+    assert!(files["main_test.ts"]
+      .contains(&format!("{}testing/asserts.ts", STD_URL_STR)));
+
+    let files = get_builtin_template_files("server", true).unwrap();
+    assert_eq!(
+      files.keys().collect::<Vec<_>>(),
+      vec!["deno.json", "import_map.json", "main.ts", "main_test.ts"]
+    );
+    assert!(files["main.ts"].contains("from \"std/http/server.ts\""));
+    let config: serde_json::Value =
+      serde_json::from_str(&files["deno.json"]).unwrap();
+    assert_eq!(config["importMap"], "./import_map.json");
+    assert_eq!(config["tasks"]["start"], "deno run --allow-net main.ts");
+
+    let files = get_builtin_template_files("library", false).unwrap();
+    assert!(files.contains_key("mod.ts"));
+    assert!(files.contains_key("mod_test.ts"));
+
+    assert!(get_builtin_template_files("desktop", false).is_err());
+  }
+
+  #[test]
+  fn template_paths() {
+    assert!(validate_template_path("main.ts").is_ok());
+    assert!(validate_template_path("src/lib/mod.ts").is_ok());
+    assert!(validate_template_path("").is_err());
+    assert!(validate_template_path("../main.ts").is_err());
+    assert!(validate_template_path("src/../../main.ts").is_err());
+    assert!(validate_template_path("/etc/passwd").is_err());
+  }
+}
@@ -1,17 +1,24 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
+use crate::colors;
 use crate::flags::Flags;
 use crate::flags::InstallFlags;
 use crate::flags::TypeCheckMode;
 use crate::fs_util::canonicalize_path;
+use deno_core::anyhow::Context;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::resolve_url_or_path;
+use deno_core::serde_json;
 use deno_core::url::Url;
+use deno_runtime::deno_fetch::reqwest::Client;
 use log::Level;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use regex::RegexBuilder;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::fs::File;
@@ -123,6 +130,54 @@ fn get_installer_root() -> Result<PathBuf, io::Error> {
   Ok(home_path)
 }
 
+/// Records the scripts installed into an installation root.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct InstallManifest {
+  scripts: BTreeMap<String, InstalledScript>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InstalledScript {
+  entrypoint: String,
+  flags: Vec<String>,
+  args: Vec<String>,
+  installed_at: String,
+}
+
+fn get_manifest_path(root: &Path) -> PathBuf {
+  root.join("install_manifest.json")
+}
+
+fn read_manifest(root: &Path) -> Result<InstallManifest, AnyError> {
+  let manifest_path = get_manifest_path(root);
+  let text = match fs::read_to_string(&manifest_path) {
+    Ok(text) => text,
+    Err(err) if err.kind() == io::ErrorKind::NotFound => {
+      return Ok(InstallManifest::default())
+    }
+    Err(err) => return Err(err.into()),
+  };
+  serde_json::from_str(&text)
+    .with_context(|| format!("Failed to parse {}", manifest_path.display()))
+}
+
+fn write_manifest(
+  root: &Path,
+  manifest: &InstallManifest,
+) -> Result<(), AnyError> {
+  let text = serde_json::to_string_pretty(manifest)?;
+  fs::write(get_manifest_path(root), text)?;
+  Ok(())
+}
+
+fn resolve_root(root: Option<PathBuf>) -> Result<PathBuf, AnyError> {
+  Ok(match root {
+    Some(root) => canonicalize_path(&root)?,
+    None => get_installer_root()?,
+  })
+}
+
 pub fn infer_name_from_url(url: &Url) -> Option<String> {
   let path = PathBuf::from(url.path());
   let mut stem = match path.file_stem() {
@@ -139,11 +194,7 @@ pub fn infer_name_from_url(url: &Url) -> Option<String> {
 }
 
 pub fn uninstall(name: String, root: Option<PathBuf>) -> Result<(), AnyError> {
-  let root = if let Some(root) = root {
-    canonicalize_path(&root)?
-  } else {
-    get_installer_root()?
-  };
+  let root = resolve_root(root)?;
   let installation_dir = root.join("bin");
 
   // ensure directory exists
@@ -172,6 +223,12 @@ pub fn uninstall(name: String, root: Option<PathBuf>) -> Result<(), AnyError> {
     }
   }
 
+  let mut manifest = read_manifest(&root)?;
+  if manifest.scripts.remove(&name).is_some() {
+    write_manifest(&root, &manifest)?;
+    removed = true;
+  }
+
   if !removed {
     return Err(generic_error(format!("No installation found for {}", name)));
   }
@@ -211,10 +268,23 @@ pub fn install(
   };
 
   generate_executable_file(&shim_data)?;
-  for (path, contents) in shim_data.extra_files {
+  for (path, contents) in &shim_data.extra_files {
     fs::write(path, contents)?;
   }
 
+  let root = shim_data.installation_dir.parent().unwrap();
+  let mut manifest = read_manifest(root)?;
+  manifest.scripts.insert(
+    shim_data.name.clone(),
+    InstalledScript {
+      entrypoint: shim_data.entrypoint.clone(),
+      flags: shim_data.flags.clone(),
+      args: install_flags.args.clone(),
+      installed_at: chrono::Utc::now().to_rfc3339(),
+    },
+  );
+  write_manifest(root, &manifest)?;
+
   println!("✅ Successfully installed {}", shim_data.name);
   println!("{}", shim_data.file_path.display());
   if cfg!(windows) {
@@ -235,12 +305,93 @@ pub fn install(
   Ok(())
 }
 
+/// Prints the scripts installed into the installation root, along with
+/// whether newer versions of their modules are published.
+pub async fn list(root: Option<PathBuf>) -> Result<(), AnyError> {
+  let root = resolve_root(root)?;
+  let manifest = read_manifest(&root)?;
+  if manifest.scripts.is_empty() {
+    println!("No scripts installed in {}", root.display());
+    return Ok(());
+  }
+
+  let client = Client::builder().build()?;
+  let installation_dir = root.join("bin");
+  for (name, script) in &manifest.scripts {
+    let installed_at =
+      chrono::DateTime::parse_from_rfc3339(&script.installed_at)
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|_| script.installed_at.clone());
+    let mut line = format!(
+      "{} {} (installed {})",
+      colors::bold(name),
+      script.entrypoint,
+      installed_at
+    );
+    let mut file_path = installation_dir.join(name);
+    if cfg!(windows) {
+      file_path = file_path.with_extension("cmd");
+    }
+    if !file_path.exists() {
+      line.push_str(&format!(" {}", colors::red("missing executable")));
+    }
+    if let Some(latest_version) =
+      get_newer_version(&client, &script.entrypoint).await
+    {
+      line.push_str(&format!(
+        " {}",
+        colors::green(format!("update available: {}", latest_version))
+      ));
+    }
+    println!("{}", line);
+  }
+  Ok(())
+}
+
+static DENO_LAND_URL_RE: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r"^https://deno\.land/(?:x/)?([^/@]+)@([^/]+)/").unwrap()
+});
+
+/// Gets the latest version of a versioned `deno.land/x` or `deno.land/std`
+/// module when it differs from the installed one.
+async fn get_newer_version(
+  client: &Client,
+  entrypoint: &str,
+) -> Option<String> {
+  #[derive(Deserialize)]
+  struct VersionsResponse {
+    latest: String,
+  }
+
+  let captures = DENO_LAND_URL_RE.captures(entrypoint)?;
+  let module_name = captures.get(1)?.as_str();
+  let version = captures.get(2)?.as_str();
+  let response = client
+    .get(format!(
+      "https://cdn.deno.land/{}/meta/versions.json",
+      module_name
+    ))
+    .send()
+    .await
+    .ok()?;
+  let text = response.text().await.ok()?;
+  let versions: VersionsResponse = serde_json::from_str(&text).ok()?;
+  if versions.latest != version {
+    Some(versions.latest)
+  } else {
+    None
+  }
+}
+
 struct ShimData {
   name: String,
   installation_dir: PathBuf,
   file_path: PathBuf,
   args: Vec<String>,
   extra_files: Vec<(PathBuf, String)>,
+  entrypoint: String,
+  /// The flags passed to `deno run`.
+  flags: Vec<String>,
 }
 
 fn resolve_shim_data(
@@ -347,14 +498,18 @@ fn resolve_shim_data(
     executable_args.push(seed.to_string());
   }
 
-  if let Some(inspect) = flags.inspect {
+  if let Some(inspect) = &flags.inspect {
     executable_args.push(format!("--inspect={}", inspect));
   }
 
-  if let Some(inspect_brk) = flags.inspect_brk {
+  if let Some(inspect_brk) = &flags.inspect_brk {
     executable_args.push(format!("--inspect-brk={}", inspect_brk));
   }
 
+  if let Some(inspect_wait) = &flags.inspect_wait {
+    executable_args.push(format!("--inspect-wait={}", inspect_wait));
+  }
+
   if let Some(import_map_path) = &flags.import_map_path {
     let import_map_url = resolve_url_or_path(import_map_path)?;
     executable_args.push("--import-map".to_string());
@@ -377,6 +532,7 @@ fn resolve_shim_data(
     extra_files.push((copy_path, fs::read_to_string(lock_path)?));
   }
 
+  let run_flags = executable_args[1..].to_vec();
   executable_args.push(module_url.to_string());
   executable_args.extend_from_slice(&install_flags.args);
 
@@ -386,6 +542,8 @@ fn resolve_shim_data(
     file_path,
     args: executable_args,
     extra_files,
+    entrypoint: module_url.to_string(),
+    flags: run_flags,
   })
 }
 
@@ -985,4 +1143,64 @@ mod tests {
       assert!(!file_path.exists());
     }
   }
+
+  #[test]
+  fn install_and_uninstall_manifest() {
+    let temp_dir = TempDir::new();
+    let bin_dir = temp_dir.path().join("bin");
+    std::fs::create_dir(&bin_dir).unwrap();
+
+    install(
+      Flags {
+        unstable: true,
+        ..Flags::default()
+      },
+      InstallFlags {
+        module_url: "http://localhost:4545/echo_server.ts".to_string(),
+        args: vec!["--foobar".to_string()],
+        name: Some("echo_test".to_string()),
+        root: Some(temp_dir.path().to_path_buf()),
+        force: false,
+      },
+    )
+    .unwrap();
+
+    let manifest = read_manifest(temp_dir.path()).unwrap();
+    let script = manifest.scripts.get("echo_test").unwrap();
+    assert_eq!(script.entrypoint, "http://localhost:4545/echo_server.ts");
+    assert_eq!(script.flags, vec!["--unstable".to_string()]);
+    assert_eq!(script.args, vec!["--foobar".to_string()]);
+    assert!(chrono::DateTime::parse_from_rfc3339(&script.installed_at).is_ok());
+
+    uninstall("echo_test".to_string(), Some(temp_dir.path().to_path_buf()))
+      .unwrap();
+    let manifest = read_manifest(temp_dir.path()).unwrap();
+    assert!(manifest.scripts.is_empty());
+  }
+
+  #[test]
+  fn uninstall_manifest_entry_without_executable() {
+    let temp_dir = TempDir::new();
+    let mut manifest = InstallManifest::default();
+    manifest.scripts.insert(
+      "echo_test".to_string(),
+      InstalledScript {
+        entrypoint: "http://localhost:4545/echo_server.ts".to_string(),
+        flags: vec![],
+        args: vec![],
+        installed_at: "2022-05-01T00:00:00+00:00".to_string(),
+      },
+    );
+    write_manifest(temp_dir.path(), &manifest).unwrap();
+
+    uninstall("echo_test".to_string(), Some(temp_dir.path().to_path_buf()))
+      .unwrap();
+    let manifest = read_manifest(temp_dir.path()).unwrap();
+    assert!(manifest.scripts.is_empty());
+    assert!(uninstall(
+      "echo_test".to_string(),
+      Some(temp_dir.path().to_path_buf())
+    )
+    .is_err());
+  }
 }
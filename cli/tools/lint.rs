@@ -7,47 +7,78 @@
 //! the future it can be easily extended to provide
 //! the same functions as ops available in JS runtime.
 use crate::config_file::LintConfig;
+use crate::create_main_worker;
+use crate::diff::diff;
 use crate::file_watcher::ResolutionResult;
 use crate::flags::{Flags, LintFlags};
 use crate::fmt_errors;
 use crate::fs_util::{collect_files, is_supported_ext, specifier_to_file_path};
+use crate::located_script_name;
 use crate::proc_state::ProcState;
 use crate::tools::fmt::run_parallelized;
 use crate::{colors, file_watcher};
+use deno_ast::swc::ast;
+use deno_ast::swc::common::BytePos;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
 use deno_ast::MediaType;
+use deno_ast::ParsedSource;
 use deno_core::anyhow::anyhow;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
+use deno_core::error::JsError;
 use deno_core::error::JsStackFrame;
+use deno_core::futures::channel::oneshot;
 use deno_core::serde_json;
+use deno_core::serde_v8;
+use deno_core::v8;
+use deno_core::ModuleSpecifier;
 use deno_lint::diagnostic::LintDiagnostic;
+use deno_lint::diagnostic::Position;
+use deno_lint::diagnostic::Range;
 use deno_lint::linter::Linter;
 use deno_lint::linter::LinterBuilder;
 use deno_lint::rules;
 use deno_lint::rules::LintRule;
+use deno_runtime::permissions::Permissions;
+use deno_runtime::permissions::PermissionsOptions;
+use deno_runtime::tokio_util::run_basic;
 use log::debug;
 use log::info;
+use serde::Deserialize;
 use serde::Serialize;
+use std::collections::HashSet;
 use std::fs;
 use std::io::{stdin, Read};
+use std::path::Path;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::unbounded_channel;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::mpsc::UnboundedSender;
 
 use super::incremental_cache::IncrementalCache;
+use super::incremental_cache::IncrementalCacheKind;
 
 static STDIN_FILE_NAME: &str = "_stdin.ts";
 
+/// Upper bound on the number of lint/fix passes over a single file, in case
+/// fixes keep producing new fixable diagnostics.
+const MAX_FIX_PASSES: usize = 10;
+
 #[derive(Clone, Debug)]
 pub enum LintReporterKind {
   Pretty,
   Json,
+  Sarif(Vec<SarifRule>),
 }
 
 fn create_reporter(kind: LintReporterKind) -> Box<dyn LintReporter + Send> {
   match kind {
     LintReporterKind::Pretty => Box::new(PrettyLintReporter::new()),
     LintReporterKind::Json => Box::new(JsonLintReporter::new()),
+    LintReporterKind::Sarif(rules) => Box::new(SarifLintReporter::new(rules)),
   }
 }
 
@@ -60,6 +91,10 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
     files: args,
     ignore,
     json,
+    sarif,
+    fix,
+    dry_run,
+    force,
     ..
   } = lint_flags;
   // First, prepare final configuration.
@@ -100,41 +135,67 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
     include_files = [std::env::current_dir()?].to_vec();
   }
 
-  let reporter_kind = if json {
-    LintReporterKind::Json
-  } else {
-    LintReporterKind::Pretty
-  };
-
   let has_error = Arc::new(AtomicBool::new(false));
+  let fixed_files_count = Arc::new(AtomicUsize::new(0));
   // Try to get configured rules. CLI flags take precendence
   // over config file, ie. if there's `rules.include` in config file
   // and `--rules-include` CLI flag, only the flag value is taken into account.
+  let plugin_rules_exclude = maybe_rules_exclude
+    .clone()
+    .or_else(|| {
+      maybe_lint_config
+        .as_ref()
+        .and_then(|c| c.rules.exclude.clone())
+    })
+    .unwrap_or_default();
   let lint_rules = get_configured_rules(
     maybe_lint_config.as_ref(),
     maybe_rules_tags,
     maybe_rules_include,
     maybe_rules_exclude,
   )?;
+  let reporter_kind = if json {
+    LintReporterKind::Json
+  } else if sarif {
+    LintReporterKind::Sarif(lint_rules.iter().map(SarifRule::new).collect())
+  } else {
+    LintReporterKind::Pretty
+  };
+  let plugins = maybe_lint_config
+    .as_ref()
+    .map(|c| c.plugins.clone())
+    .unwrap_or_default();
+  let loaded_plugins = Mutex::new(
+    LoadedLintPlugins::load(&ps, &plugins, &plugin_rules_exclude).await?,
+  );
+  // set by the watcher when any module of the plugins changed
+  let plugins_changed = AtomicBool::new(false);
 
   let resolver = |changed: Option<Vec<PathBuf>>| {
     let files_changed = changed.is_some();
+    let plugin_paths = loaded_plugins.lock().unwrap().paths.clone();
+    let plugin_changed = changed.as_ref().map_or(false, |paths| {
+      paths.iter().any(|path| plugin_paths.contains(path))
+    });
+    if plugin_changed {
+      plugins_changed.store(true, Ordering::Relaxed);
+    }
     let result =
       collect_files(&include_files, &exclude_files, is_supported_ext).map(
-        |files| {
-          if let Some(paths) = changed {
-            files
-              .iter()
-              .any(|path| paths.contains(path))
-              .then(|| files)
-              .unwrap_or_else(|| [].to_vec())
-          } else {
-            files
-          }
+        |files| match changed {
+          // every file has to be linted again with the changed plugins
+          Some(_) if plugin_changed => files,
+          Some(paths) => files
+            .iter()
+            .any(|path| paths.contains(path))
+            .then(|| files)
+            .unwrap_or_else(|| [].to_vec()),
+          None => files,
         },
       );
 
-    let paths_to_watch = include_files.clone();
+    let mut paths_to_watch = include_files.clone();
+    paths_to_watch.extend(plugin_paths);
 
     async move {
       if files_changed && matches!(result, Ok(ref files) if files.is_empty()) {
@@ -149,25 +210,45 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
   };
 
   let operation = |paths: Vec<PathBuf>| async {
+    if plugins_changed.swap(false, Ordering::Relaxed) {
+      // a new state is needed, because the existing one has the plugins'
+      // modules cached in memory
+      let ps = ProcState::build(flags.clone()).await?;
+      let reloaded =
+        LoadedLintPlugins::load(&ps, &plugins, &plugin_rules_exclude).await?;
+      *loaded_plugins.lock().unwrap() = reloaded;
+    }
+    let (maybe_plugin_host, plugins_state) = {
+      let loaded_plugins = loaded_plugins.lock().unwrap();
+      (
+        loaded_plugins.maybe_host.clone(),
+        loaded_plugins.state.clone(),
+      )
+    };
     let incremental_cache = Arc::new(IncrementalCache::new(
-      &ps.dir.lint_incremental_cache_db_file_path(),
-      // use a hash of the rule names in order to bust the cache
+      &ps.dir.incremental_cache_db_file_path(),
+      IncrementalCacheKind::Lint,
+      // use a hash of the rule names and plugins in order to bust the cache
       &{
         // ensure this is stable by sorting it
         let mut names = lint_rules.iter().map(|r| r.code()).collect::<Vec<_>>();
         names.sort_unstable();
-        names
+        (names, &plugins_state)
       },
       &paths,
+      force,
     ));
     let target_files_len = paths.len();
     let reporter_kind = reporter_kind.clone();
     let reporter_lock = Arc::new(Mutex::new(create_reporter(reporter_kind)));
+    fixed_files_count.store(0, Ordering::Relaxed);
     run_parallelized(paths, {
       let has_error = has_error.clone();
       let lint_rules = lint_rules.clone();
       let reporter_lock = reporter_lock.clone();
       let incremental_cache = incremental_cache.clone();
+      let fixed_files_count = fixed_files_count.clone();
+      let maybe_plugin_host = maybe_plugin_host.clone();
       move |file_path| {
         let file_text = fs::read_to_string(&file_path)?;
 
@@ -176,11 +257,36 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
           return Ok(());
         }
 
-        let r = lint_file(file_path.clone(), file_text, lint_rules.clone());
-        if let Ok((file_diagnostics, file_text)) = &r {
-          if file_diagnostics.is_empty() {
+        let r = if fix {
+          fix_file(
+            file_path.clone(),
+            file_text.clone(),
+            lint_rules.clone(),
+            maybe_plugin_host.as_deref(),
+          )
+        } else {
+          lint_file(
+            file_path.clone(),
+            file_text.clone(),
+            lint_rules.clone(),
+            maybe_plugin_host.as_deref(),
+          )
+        };
+        if let Ok((file_diagnostics, fixed_text)) = &r {
+          if *fixed_text != file_text {
+            fixed_files_count.fetch_add(1, Ordering::Relaxed);
+            if dry_run {
+              let _g = reporter_lock.lock().unwrap();
+              info!("");
+              info!("{} {}:", colors::bold("from"), file_path.display());
+              info!("{}", diff(&file_text, fixed_text));
+            } else {
+              fs::write(&file_path, fixed_text)?;
+            }
+          }
+          if file_diagnostics.is_empty() && !dry_run {
             // update the incremental cache if there were no diagnostics
-            incremental_cache.update_file(&file_path, file_text)
+            incremental_cache.update_file(&file_path, fixed_text)
           }
         }
 
@@ -197,6 +303,13 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
     .await?;
     incremental_cache.wait_completion().await;
     reporter_lock.lock().unwrap().close(target_files_len);
+    if fix {
+      let verb = if dry_run { "Would fix" } else { "Fixed" };
+      match fixed_files_count.load(Ordering::Relaxed) {
+        1 => info!("{} 1 file", verb),
+        n => info!("{} {} files", verb, n),
+      }
+    }
 
     Ok(())
   };
@@ -209,17 +322,20 @@ pub async fn lint(flags: Flags, lint_flags: LintFlags) -> Result<(), AnyError> {
     file_watcher::watch_func(
       resolver,
       operation,
-      file_watcher::PrintConfig {
-        job_name: "Lint".to_string(),
-        clear_screen: !flags.no_clear_screen,
-      },
+      file_watcher::WatcherConfig::new("Lint", &flags)?,
     )
     .await?;
   } else {
     if args.len() == 1 && args[0].to_string_lossy() == "-" {
+      if fix {
+        return Err(generic_error(
+          "Fixing lint errors on standard input is not supported.",
+        ));
+      }
       let reporter_lock =
         Arc::new(Mutex::new(create_reporter(reporter_kind.clone())));
-      let r = lint_stdin(lint_rules);
+      let maybe_plugin_host = loaded_plugins.lock().unwrap().maybe_host.clone();
+      let r = lint_stdin(lint_rules, maybe_plugin_host.as_deref());
       handle_lint_result(
         STDIN_FILE_NAME,
         r,
@@ -293,15 +409,464 @@ fn lint_file(
   file_path: PathBuf,
   source_code: String,
   lint_rules: Vec<Arc<dyn LintRule>>,
+  maybe_plugin_host: Option<&LintPluginHost>,
 ) -> Result<(Vec<LintDiagnostic>, String), AnyError> {
+  let (_, file_diagnostics) =
+    lint_source(&file_path, &source_code, lint_rules, maybe_plugin_host)?;
+
+  Ok((file_diagnostics, source_code))
+}
+
+fn lint_source(
+  file_path: &Path,
+  source_code: &str,
+  lint_rules: Vec<Arc<dyn LintRule>>,
+  maybe_plugin_host: Option<&LintPluginHost>,
+) -> Result<(ParsedSource, Vec<LintDiagnostic>), AnyError> {
   let file_name = file_path.to_string_lossy().to_string();
-  let media_type = MediaType::from(&file_path);
+  let media_type = MediaType::from(file_path);
 
   let linter = create_linter(media_type, lint_rules);
 
-  let (_, file_diagnostics) = linter.lint(file_name, source_code.clone())?;
+  let (parsed_source, mut file_diagnostics) =
+    linter.lint(file_name.clone(), source_code.to_string())?;
+  if let Some(plugin_host) = maybe_plugin_host {
+    file_diagnostics.extend(plugin_host.lint(
+      &file_name,
+      source_code,
+      &parsed_source,
+    )?);
+  }
 
-  Ok((file_diagnostics, source_code))
+  Ok((parsed_source, file_diagnostics))
+}
+
+/// A text edit fixing a lint diagnostic, replacing the bytes `start..end` of
+/// the source with `new_text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LintFix {
+  start: usize,
+  end: usize,
+  new_text: String,
+}
+
+/// Returns the fix for a diagnostic if its rule has a fix that is always safe
+/// to apply. `removable_debuggers` holds the positions of the `debugger`
+/// statements that can be removed, see `find_removable_debuggers`.
+fn get_lint_fix(
+  d: &LintDiagnostic,
+  source_code: &str,
+  removable_debuggers: &HashSet<(usize, usize)>,
+) -> Option<LintFix> {
+  let start = d.range.start.byte_pos;
+  let end = d.range.end.byte_pos;
+  let text = source_code.get(start..end)?;
+  let position = (d.range.start.line_index, d.range.start.column_index);
+  match d.code.as_str() {
+    "no-extra-semi" if text == ";" => {
+      Some(removal_fix(source_code, start, end))
+    }
+    "no-debugger"
+      if (text == "debugger" || text == "debugger;")
+        && removable_debuggers.contains(&position) =>
+    {
+      Some(removal_fix(source_code, start, end))
+    }
+    // `foo!!` -> `foo!`
+    "no-extra-non-null-assertion" if text.ends_with("!!") => Some(LintFix {
+      start: end - 1,
+      end,
+      new_text: String::new(),
+    }),
+    // `module Foo {}` -> `namespace Foo {}`
+    "prefer-namespace-keyword" => {
+      let index = text.find("module ")?;
+      let is_keyword = text[..index]
+        .split_whitespace()
+        .all(|word| word == "export" || word == "declare");
+      is_keyword.then(|| LintFix {
+        start: start + index,
+        end: start + index + "module".len(),
+        new_text: "namespace".to_string(),
+      })
+    }
+    _ => None,
+  }
+}
+
+/// Collects the line and column indexes of the `debugger` statements found in
+/// a list of statements. The others are the body of an `if`, a loop or a
+/// label, and removing them would make the next statement the body instead.
+fn find_removable_debuggers(
+  parsed_source: &ParsedSource,
+) -> HashSet<(usize, usize)> {
+  let mut finder = RemovableDebuggerFinder {
+    parsed_source,
+    positions: HashSet::new(),
+  };
+  parsed_source.module().visit_with(&mut finder);
+  finder.positions
+}
+
+struct RemovableDebuggerFinder<'a> {
+  parsed_source: &'a ParsedSource,
+  positions: HashSet<(usize, usize)>,
+}
+
+impl RemovableDebuggerFinder<'_> {
+  fn add(&mut self, stmt: &ast::Stmt) {
+    if let ast::Stmt::Debugger(debugger) = stmt {
+      let position = self
+        .parsed_source
+        .source()
+        .line_and_column_index(debugger.span.lo);
+      self
+        .positions
+        .insert((position.line_index, position.column_index));
+    }
+  }
+}
+
+impl Visit for RemovableDebuggerFinder<'_> {
+  fn visit_module_items(&mut self, items: &[ast::ModuleItem]) {
+    for item in items {
+      if let ast::ModuleItem::Stmt(stmt) = item {
+        self.add(stmt);
+      }
+    }
+    items.visit_children_with(self);
+  }
+
+  fn visit_stmts(&mut self, stmts: &[ast::Stmt]) {
+    for stmt in stmts {
+      self.add(stmt);
+    }
+    stmts.visit_children_with(self);
+  }
+}
+
+/// Removes `start..end`, along with the rest of its line if nothing else would
+/// be left on it.
+fn removal_fix(source_code: &str, start: usize, end: usize) -> LintFix {
+  let line_start = source_code[..start].rfind('\n').map_or(0, |i| i + 1);
+  let line_end = source_code[end..]
+    .find('\n')
+    .map_or(source_code.len(), |i| end + i + 1);
+  let (start, end) = if source_code[line_start..start].trim().is_empty()
+    && source_code[end..line_end].trim().is_empty()
+  {
+    (line_start, line_end)
+  } else {
+    (start, end)
+  };
+  LintFix {
+    start,
+    end,
+    new_text: String::new(),
+  }
+}
+
+/// Applies the fixes that don't overlap with each other. Overlapping fixes are
+/// skipped, as their diagnostics will be reported again on the next pass.
+fn apply_lint_fixes(source_code: &str, mut fixes: Vec<LintFix>) -> String {
+  fixes.sort_by_key(|fix| (fix.start, fix.end));
+  let mut text = String::with_capacity(source_code.len());
+  let mut last_end = 0;
+  for fix in fixes {
+    if fix.start < last_end {
+      continue;
+    }
+    text.push_str(&source_code[last_end..fix.start]);
+    text.push_str(&fix.new_text);
+    last_end = fix.end;
+  }
+  text.push_str(&source_code[last_end..]);
+  text
+}
+
+/// Lints the file and applies the available fixes, repeating until there is
+/// nothing left to fix. Returns the remaining diagnostics and the fixed
+/// source.
+fn fix_file(
+  file_path: PathBuf,
+  mut source_code: String,
+  lint_rules: Vec<Arc<dyn LintRule>>,
+  maybe_plugin_host: Option<&LintPluginHost>,
+) -> Result<(Vec<LintDiagnostic>, String), AnyError> {
+  for _ in 0..MAX_FIX_PASSES {
+    let (parsed_source, file_diagnostics) = lint_source(
+      &file_path,
+      &source_code,
+      lint_rules.clone(),
+      maybe_plugin_host,
+    )?;
+    let removable_debuggers = find_removable_debuggers(&parsed_source);
+    let fixes = file_diagnostics
+      .iter()
+      .filter_map(|d| get_lint_fix(d, &source_code, &removable_debuggers))
+      .collect::<Vec<_>>();
+    if fixes.is_empty() {
+      return Ok((file_diagnostics, source_code));
+    }
+    source_code = apply_lint_fixes(&source_code, fixes);
+  }
+  lint_file(file_path, source_code, lint_rules, maybe_plugin_host)
+}
+
+/// The host running the lint plugins, along with what is needed to tell when
+/// they have to be loaded again.
+struct LoadedLintPlugins {
+  maybe_host: Option<Arc<LintPluginHost>>,
+  state: (Vec<String>, Option<String>),
+  /// The paths of the local modules of the plugins.
+  paths: Vec<PathBuf>,
+}
+
+impl LoadedLintPlugins {
+  async fn load(
+    ps: &ProcState,
+    plugins: &[ModuleSpecifier],
+    exclude: &[String],
+  ) -> Result<Self, AnyError> {
+    let maybe_host = if plugins.is_empty() {
+      None
+    } else {
+      Some(Arc::new(
+        LintPluginHost::start(ps, plugins.to_vec(), exclude.to_vec()).await?,
+      ))
+    };
+    Ok(Self {
+      maybe_host,
+      state: get_plugins_state(ps, plugins),
+      paths: ps.graph_local_paths(plugins),
+    })
+  }
+}
+
+/// Identifies the plugins by their specifiers and a checksum of the code of
+/// their whole module graph, so that changing a plugin or any module it
+/// imports invalidates the results in the incremental cache.
+fn get_plugins_state(
+  ps: &ProcState,
+  plugins: &[ModuleSpecifier],
+) -> (Vec<String>, Option<String>) {
+  let specifiers = plugins.iter().map(|s| s.to_string()).collect();
+  (specifiers, ps.graph_checksum(plugins))
+}
+
+/// Calls every rule of the loaded plugins with the file being linted,
+/// resolving to the diagnostics they reported.
+const RUN_PLUGIN_RULES: &str = r#"(async (plugins, specifiers, file) => {
+  const diagnostics = [];
+  for (let i = 0; i < plugins.length; i++) {
+    const plugin = plugins[i].default;
+    if (
+      plugin === null || typeof plugin !== "object" ||
+      typeof plugin.name !== "string" ||
+      plugin.rules === null || typeof plugin.rules !== "object"
+    ) {
+      throw new TypeError(
+        `Lint plugin "${specifiers[i]}" must default export an object with "name" and "rules"`,
+      );
+    }
+    for (const [ruleName, rule] of Object.entries(plugin.rules)) {
+      const code = `${plugin.name}/${ruleName}`;
+      await rule({
+        filename: file.filename,
+        source: file.source,
+        ast: file.ast,
+        report({ span, message, hint }) {
+          diagnostics.push({
+            code,
+            start: span.start,
+            end: span.end,
+            message: String(message),
+            hint: hint === undefined ? null : String(hint),
+          });
+        },
+      });
+    }
+  }
+  return diagnostics;
+})"#;
+
+/// A diagnostic reported by a plugin rule, spanning the bytes `start..end` of
+/// the linted source.
+#[derive(Debug, Deserialize)]
+struct PluginDiagnostic {
+  code: String,
+  start: usize,
+  end: usize,
+  message: String,
+  hint: Option<String>,
+}
+
+struct LintPluginRequest {
+  filename: String,
+  source: String,
+  ast: serde_json::Value,
+  reply: std::sync::mpsc::Sender<Result<Vec<PluginDiagnostic>, AnyError>>,
+}
+
+/// Runs the rules of the plugins configured in `lint.plugins`.
+///
+/// A plugin is a module whose default export is an object with a `name` and
+/// a `rules` map from rule name to a function. Each rule is called
+/// with a context holding the file name, source text and serialized AST of
+/// every linted file, and reports diagnostics with
+/// `context.report({ span, message, hint })`, where `span` holds byte
+/// offsets like the `span` of any AST node. Diagnostics use the code
+/// `<name>/<rule>`, which can be excluded like any built-in rule.
+///
+/// The plugins are loaded into a single worker without any permissions, on a
+/// thread of its own that lints one file at a time on behalf of the linting
+/// threads.
+pub struct LintPluginHost {
+  sender: UnboundedSender<LintPluginRequest>,
+  exclude: Vec<String>,
+}
+
+impl LintPluginHost {
+  async fn start(
+    ps: &ProcState,
+    plugins: Vec<ModuleSpecifier>,
+    exclude: Vec<String>,
+  ) -> Result<Self, AnyError> {
+    let ps = ps.clone();
+    let (sender, receiver) = unbounded_channel();
+    let (ready_sender, ready_receiver) = oneshot::channel();
+    let handle = std::thread::spawn(move || {
+      run_basic(run_lint_plugins(ps, plugins, ready_sender, receiver))
+    });
+
+    match ready_receiver.await {
+      Ok(()) => Ok(Self { sender, exclude }),
+      // The plugins failed to load, report why.
+      Err(_) => Err(handle.join().unwrap().err().unwrap_or_else(|| {
+        generic_error("Lint plugins exited before they were loaded")
+      })),
+    }
+  }
+
+  fn lint(
+    &self,
+    filename: &str,
+    source_code: &str,
+    parsed_source: &ParsedSource,
+  ) -> Result<Vec<LintDiagnostic>, AnyError> {
+    let stopped = || generic_error("Lint plugins stopped unexpectedly");
+    let (reply, receiver) = std::sync::mpsc::channel();
+    self
+      .sender
+      .send(LintPluginRequest {
+        filename: filename.to_string(),
+        source: source_code.to_string(),
+        ast: serde_json::to_value(parsed_source.module())?,
+        reply,
+      })
+      .map_err(|_| stopped())?;
+    let diagnostics = receiver.recv().map_err(|_| stopped())??;
+
+    let text_info = parsed_source.source();
+    let to_position = |byte_pos: usize| {
+      let byte_pos = byte_pos.min(source_code.len());
+      let location = text_info.line_and_column_index(BytePos(byte_pos as u32));
+      Position {
+        line_index: location.line_index,
+        column_index: location.column_index,
+        byte_pos,
+      }
+    };
+    Ok(
+      diagnostics
+        .into_iter()
+        .filter(|d| !self.exclude.contains(&d.code))
+        .map(|d| LintDiagnostic {
+          range: Range {
+            start: to_position(d.start),
+            end: to_position(d.end.max(d.start)),
+          },
+          filename: filename.to_string(),
+          message: d.message,
+          code: d.code,
+          hint: d.hint,
+        })
+        .collect(),
+    )
+  }
+}
+
+async fn run_lint_plugins(
+  ps: ProcState,
+  plugins: Vec<ModuleSpecifier>,
+  ready_sender: oneshot::Sender<()>,
+  mut receiver: UnboundedReceiver<LintPluginRequest>,
+) -> Result<(), AnyError> {
+  let mut worker = create_main_worker(
+    &ps,
+    plugins[0].clone(),
+    Permissions::from_options(&PermissionsOptions::default()),
+    vec![],
+    Default::default(),
+  );
+
+  let mut namespaces = Vec::with_capacity(plugins.len());
+  for specifier in &plugins {
+    worker.execute_side_module(specifier).await?;
+    let id = worker.preload_module(specifier, false).await?;
+    namespaces.push(worker.js_runtime.get_module_namespace(id)?);
+  }
+  let run_rules = worker
+    .js_runtime
+    .execute_script(&located_script_name!(), RUN_PLUGIN_RULES)?;
+  let specifiers = plugins.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+  let _ = ready_sender.send(());
+
+  while let Some(request) = receiver.recv().await {
+    let promise = {
+      let scope = &mut worker.js_runtime.handle_scope();
+      let run_rules = v8::Local::new(scope, &run_rules);
+      let run_rules = v8::Local::<v8::Function>::try_from(run_rules).unwrap();
+      let namespaces = namespaces
+        .iter()
+        .map(|namespace| v8::Local::new(scope, namespace).into())
+        .collect::<Vec<v8::Local<v8::Value>>>();
+      let namespaces = v8::Array::new_with_elements(scope, &namespaces).into();
+      let specifiers = serde_v8::to_v8(scope, &specifiers)?;
+      let file = serde_v8::to_v8(
+        scope,
+        serde_json::json!({
+          "filename": request.filename,
+          "source": request.source,
+          "ast": request.ast,
+        }),
+      )?;
+      let tc_scope = &mut v8::TryCatch::new(scope);
+      let undefined = v8::undefined(tc_scope).into();
+      match run_rules.call(tc_scope, undefined, &[namespaces, specifiers, file])
+      {
+        Some(value) => v8::Global::new(tc_scope, value),
+        None => {
+          let exception = tc_scope.exception().unwrap();
+          let error = JsError::from_v8_exception(tc_scope, exception);
+          let _ = request.reply.send(Err(error.into()));
+          continue;
+        }
+      }
+    };
+    let result = match worker.js_runtime.resolve_value(promise).await {
+      Ok(value) => {
+        let scope = &mut worker.js_runtime.handle_scope();
+        let value = v8::Local::new(scope, value);
+        serde_v8::from_v8::<Vec<PluginDiagnostic>>(scope, value)
+          .map_err(AnyError::from)
+      }
+      Err(err) => Err(err),
+    };
+    let _ = request.reply.send(result);
+  }
+
+  Ok(())
 }
 
 /// Lint stdin and write result to stdout.
@@ -309,6 +874,7 @@ fn lint_file(
 /// Compatible with `--json` flag.
 fn lint_stdin(
   lint_rules: Vec<Arc<dyn LintRule>>,
+  maybe_plugin_host: Option<&LintPluginHost>,
 ) -> Result<(Vec<LintDiagnostic>, String), AnyError> {
   let mut source_code = String::new();
   if stdin().read_to_string(&mut source_code).is_err() {
@@ -317,8 +883,15 @@ fn lint_stdin(
 
   let linter = create_linter(MediaType::TypeScript, lint_rules);
 
-  let (_, file_diagnostics) =
+  let (parsed_source, mut file_diagnostics) =
     linter.lint(STDIN_FILE_NAME.to_string(), source_code.clone())?;
+  if let Some(plugin_host) = maybe_plugin_host {
+    file_diagnostics.extend(plugin_host.lint(
+      STDIN_FILE_NAME,
+      &source_code,
+      &parsed_source,
+    )?);
+  }
 
   Ok((file_diagnostics, source_code))
 }
@@ -453,24 +1026,25 @@ pub fn format_diagnostic(
     }
   }
 
-  let hint = if let Some(hint) = maybe_hint {
-    format!("    {} {}\n", colors::cyan("hint:"), hint)
-  } else {
-    "".to_string()
-  };
-  let help = format!(
-    "    {} for further information visit https://lint.deno.land/#{}",
-    colors::cyan("help:"),
-    diagnostic_code
-  );
+  let mut notes = vec![];
+  if let Some(hint) = maybe_hint {
+    notes.push(format!("    {} {}", colors::cyan("hint:"), hint));
+  }
+  // rules from plugins are namespaced and not documented on lint.deno.land
+  if !diagnostic_code.contains('/') {
+    notes.push(format!(
+      "    {} for further information visit https://lint.deno.land/#{}",
+      colors::cyan("help:"),
+      diagnostic_code
+    ));
+  }
 
   format!(
-    "{message_line}\n{snippets}\n    at {formatted_location}\n\n{hint}{help}",
+    "{message_line}\n{snippets}\n    at {formatted_location}\n\n{notes}",
     message_line = message_line,
     snippets = lines.join("\n"),
     formatted_location = formatted_location,
-    hint = hint,
-    help = help
+    notes = notes.join("\n")
   )
 }
 
@@ -508,6 +1082,197 @@ impl LintReporter for JsonLintReporter {
   }
 }
 
+/// Metadata of a rule that diagnostics are reported for, in the
+/// [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html)
+/// `reportingDescriptor` format.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRule {
+  id: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  short_description: Option<SarifMessage>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  help_uri: Option<String>,
+  default_configuration: SarifRuleConfiguration,
+  properties: SarifRuleProperties,
+}
+
+impl SarifRule {
+  fn new(rule: &Arc<dyn LintRule>) -> Self {
+    let short_description = rule
+      .docs()
+      .lines()
+      .map(str::trim)
+      .find(|line| !line.is_empty())
+      .map(|text| SarifMessage {
+        text: text.to_string(),
+      });
+    Self {
+      id: rule.code().to_string(),
+      short_description,
+      help_uri: Some(format!("https://lint.deno.land/#{}", rule.code())),
+      default_configuration: SarifRuleConfiguration { level: "error" },
+      properties: SarifRuleProperties {
+        tags: rule.tags().iter().map(|t| t.to_string()).collect(),
+      },
+    }
+  }
+
+  /// Rules from plugins are only known by the diagnostics they report.
+  fn from_code(code: &str) -> Self {
+    Self {
+      id: code.to_string(),
+      short_description: None,
+      help_uri: None,
+      default_configuration: SarifRuleConfiguration { level: "error" },
+      properties: SarifRuleProperties { tags: vec![] },
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct SarifRuleConfiguration {
+  level: &'static str,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct SarifRuleProperties {
+  tags: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct SarifMessage {
+  text: String,
+}
+
+/// Reports diagnostics as a SARIF log, as consumed by code scanning services.
+struct SarifLintReporter {
+  rules: Vec<SarifRule>,
+  diagnostics: Vec<LintDiagnostic>,
+  errors: Vec<LintError>,
+  cwd: Option<ModuleSpecifier>,
+}
+
+impl SarifLintReporter {
+  fn new(rules: Vec<SarifRule>) -> Self {
+    let cwd = std::env::current_dir()
+      .ok()
+      .and_then(|cwd| ModuleSpecifier::from_directory_path(cwd).ok());
+    Self {
+      rules,
+      diagnostics: Vec::new(),
+      errors: Vec::new(),
+      cwd,
+    }
+  }
+
+  /// Paths are made relative to the current directory, so that they resolve
+  /// against the root of the checked out repository when uploaded.
+  fn artifact_uri(&self, file_path: &str) -> String {
+    match ModuleSpecifier::from_file_path(file_path) {
+      Ok(url) => self
+        .cwd
+        .as_ref()
+        .and_then(|cwd| cwd.make_relative(&url))
+        .filter(|relative| !relative.starts_with("../"))
+        .unwrap_or_else(|| url.to_string()),
+      Err(_) => file_path.to_string(),
+    }
+  }
+
+  fn to_sarif_log(&mut self) -> serde_json::Value {
+    sort_diagnostics(&mut self.diagnostics);
+    for d in &self.diagnostics {
+      if !self.rules.iter().any(|r| r.id == d.code) {
+        self.rules.push(SarifRule::from_code(&d.code));
+      }
+    }
+    let results = self
+      .diagnostics
+      .iter()
+      .map(|d| {
+        let rule_index = self.rules.iter().position(|r| r.id == d.code);
+        let message = match &d.hint {
+          Some(hint) => format!("{}\n\n{}", d.message, hint),
+          None => d.message.clone(),
+        };
+        serde_json::json!({
+          "ruleId": d.code,
+          "ruleIndex": rule_index,
+          "level": "error",
+          "message": { "text": message },
+          "locations": [{
+            "physicalLocation": {
+              "artifactLocation": { "uri": self.artifact_uri(&d.filename) },
+              // SARIF lines and columns are 1-based, the end column is
+              // exclusive
+              "region": {
+                "startLine": d.range.start.line_index + 1,
+                "startColumn": d.range.start.column_index + 1,
+                "endLine": d.range.end.line_index + 1,
+                "endColumn": d.range.end.column_index + 1,
+              },
+            },
+          }],
+        })
+      })
+      .collect::<Vec<_>>();
+    let notifications = self
+      .errors
+      .iter()
+      .map(|e| {
+        serde_json::json!({
+          "level": "error",
+          "message": { "text": e.message },
+          "locations": [{
+            "physicalLocation": {
+              "artifactLocation": { "uri": self.artifact_uri(&e.file_path) },
+            },
+          }],
+        })
+      })
+      .collect::<Vec<_>>();
+
+    serde_json::json!({
+      "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+      "version": "2.1.0",
+      "runs": [{
+        "tool": {
+          "driver": {
+            "name": "deno lint",
+            "version": crate::version::deno(),
+            "informationUri": "https://lint.deno.land/",
+            "rules": self.rules,
+          },
+        },
+        "invocations": [{
+          "executionSuccessful": self.errors.is_empty(),
+          "toolExecutionNotifications": notifications,
+        }],
+        "results": results,
+      }],
+    })
+  }
+}
+
+impl LintReporter for SarifLintReporter {
+  fn visit_diagnostic(&mut self, d: &LintDiagnostic, _source_lines: Vec<&str>) {
+    self.diagnostics.push(d.clone());
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    self.errors.push(LintError {
+      file_path: file_path.to_string(),
+      message: err.to_string(),
+    });
+  }
+
+  fn close(&mut self, _check_count: usize) {
+    let log = self.to_sarif_log();
+    println!("{}", serde_json::to_string_pretty(&log).unwrap());
+  }
+}
+
 fn sort_diagnostics(diagnostics: &mut Vec<LintDiagnostic>) {
   // Sort so that we guarantee a deterministic output which is useful for tests
   diagnostics.sort_by(|a, b| {
@@ -616,4 +1381,93 @@ mod test {
     recommended_rule_names.sort();
     assert_eq!(rule_names, recommended_rule_names);
   }
+
+  #[test]
+  fn apply_lint_fixes_skips_overlapping_fixes() {
+    let fixes = vec![
+      LintFix {
+        start: 4,
+        end: 7,
+        new_text: "qux".to_string(),
+      },
+      LintFix {
+        start: 0,
+        end: 3,
+        new_text: "baz".to_string(),
+      },
+      LintFix {
+        start: 5,
+        end: 6,
+        new_text: "x".to_string(),
+      },
+    ];
+    assert_eq!(apply_lint_fixes("foo bar", fixes), "baz qux");
+  }
+
+  #[test]
+  fn fix_file_applies_fixes_until_stable() {
+    let source_code = r#"const a = 1;;
+debugger;
+module Foo {}
+export declare module Bar {}
+const b = a!!;
+console.log(b);
+"#;
+    let (diagnostics, fixed) = fix_file(
+      PathBuf::from("test.ts"),
+      source_code.to_string(),
+      get_recommended_rules(),
+      None,
+    )
+    .unwrap();
+    assert_eq!(
+      fixed,
+      r#"const a = 1;
+namespace Foo {}
+export declare namespace Bar {}
+const b = a!;
+console.log(b);
+"#
+    );
+    let debuggers = HashSet::new();
+    assert!(diagnostics
+      .iter()
+      .all(|d| get_lint_fix(d, &fixed, &debuggers).is_none()));
+  }
+
+  #[test]
+  fn fix_file_keeps_debugger_statement_bodies() {
+    let source_code = r#"function foo(a: boolean) {
+  debugger;
+  if (a) debugger;
+  while (a)
+    debugger;
+  console.log(a);
+}
+"#;
+    let (diagnostics, fixed) = fix_file(
+      PathBuf::from("test.ts"),
+      source_code.to_string(),
+      get_recommended_rules(),
+      None,
+    )
+    .unwrap();
+    assert_eq!(
+      fixed,
+      r#"function foo(a: boolean) {
+  if (a) debugger;
+  while (a)
+    debugger;
+  console.log(a);
+}
+"#
+    );
+    assert_eq!(
+      diagnostics
+        .iter()
+        .filter(|d| d.code == "no-debugger")
+        .count(),
+      2
+    );
+  }
 }
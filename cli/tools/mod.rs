@@ -5,8 +5,11 @@ pub mod coverage;
 pub mod doc;
 pub mod fmt;
 pub mod incremental_cache;
+pub mod info;
+pub mod init;
 pub mod installer;
 pub mod lint;
+pub mod outdated;
 pub mod repl;
 pub mod standalone;
 pub mod task;
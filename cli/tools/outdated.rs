@@ -0,0 +1,688 @@
+// Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
+
+//! This module provides the `deno outdated` command, which reports the
+//! versioned remote dependencies that have newer versions published.
+
+use crate::colors;
+use crate::config_file;
+use crate::flags::Flags;
+use crate::flags::OutdatedFlags;
+use crate::flags::TypeCheckMode;
+use crate::lockfile;
+use crate::proc_state::ProcState;
+use crate::resolver::ImportMapResolver;
+use crate::resolver::JsxResolver;
+use crate::tools::fmt::format_json;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::resolve_url_or_path;
+use deno_core::serde_json;
+use deno_core::serde_json::Value;
+use deno_core::ModuleSpecifier;
+use deno_runtime::deno_fetch::reqwest::Client;
+use deno_runtime::permissions::Permissions;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use semver_parser::version::parse as semver_parse;
+use semver_parser::version::Version;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+static DENO_LAND_URL_RE: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r"^https://deno\.land/(?:x/)?([^/@]+)@([^/?#]+)").unwrap()
+});
+
+static NPM_CDN_URL_RE: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(
+    r"^https://(?:esm\.sh|cdn\.skypack\.dev|unpkg\.com|cdn\.jsdelivr\.net/npm)/(?:v\d+/)?((?:@[^/@]+/)?[^/@?#]+)@([^/?#]+)",
+  )
+  .unwrap()
+});
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Registry {
+  /// Modules of `deno.land/x` and `deno.land/std`.
+  DenoLand,
+  /// Packages of the npm registry served by a CDN.
+  Npm,
+}
+
+/// A package at a version, or semver range, referenced by a remote specifier.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct VersionedPackage {
+  registry: Registry,
+  name: String,
+  version: String,
+}
+
+fn parse_versioned_specifier(specifier: &str) -> Option<VersionedPackage> {
+  let (registry, captures) =
+    if let Some(captures) = DENO_LAND_URL_RE.captures(specifier) {
+      (Registry::DenoLand, captures)
+    } else {
+      (Registry::Npm, NPM_CDN_URL_RE.captures(specifier)?)
+    };
+  Some(VersionedPackage {
+    registry,
+    name: captures.get(1)?.as_str().to_string(),
+    version: captures.get(2)?.as_str().to_string(),
+  })
+}
+
+fn parse_version(version: &str) -> Option<Version> {
+  semver_parse(version.strip_prefix('v').unwrap_or(version)).ok()
+}
+
+/// Returns if `version` satisfies the semver range `range`. Supports caret
+/// (`^1.2.3`) and tilde (`~1.2.3`) ranges, partial versions (`1` or `1.2`)
+/// and exact versions.
+fn satisfies(range: &str, version: &Version) -> bool {
+  let (operator, range_version) = match range.chars().next() {
+    Some(c @ ('^' | '~')) => (Some(c), &range[1..]),
+    _ => (None, range),
+  };
+  let range_version = range_version.strip_prefix('v').unwrap_or(range_version);
+  let parts = range_version
+    .split('.')
+    .map(|part| part.parse::<u64>().ok())
+    .collect::<Option<Vec<_>>>();
+  if operator.is_none() {
+    if let Some(parts) = parts.filter(|parts| parts.len() < 3) {
+      // a partial version matches every version starting with it
+      return version.pre.is_empty()
+        && parts.get(0) == Some(&version.major)
+        && parts.get(1).map_or(true, |minor| *minor == version.minor);
+    }
+  }
+  let base = match semver_parse(range_version) {
+    Ok(base) => base,
+    Err(_) => return false,
+  };
+  if !version.pre.is_empty() && version != &base {
+    return false;
+  }
+  match operator {
+    Some('^') => {
+      version >= &base
+        && version.major == base.major
+        && (base.major > 0 || version.minor == base.minor)
+    }
+    Some('~') => {
+      version >= &base
+        && version.major == base.major
+        && version.minor == base.minor
+    }
+    _ => version == &base,
+  }
+}
+
+fn is_range(version: &str) -> bool {
+  version.starts_with('^')
+    || version.starts_with('~')
+    || version.trim_start_matches('v').split('.').count() < 3
+}
+
+struct PackageVersions {
+  latest: String,
+  versions: Vec<String>,
+}
+
+async fn fetch_package_versions(
+  client: &Client,
+  package: &VersionedPackage,
+) -> Result<PackageVersions, AnyError> {
+  match package.registry {
+    Registry::DenoLand => {
+      #[derive(Deserialize)]
+      struct VersionsResponse {
+        latest: String,
+        versions: Vec<String>,
+      }
+
+      let url =
+        format!("https://cdn.deno.land/{}/meta/versions.json", package.name);
+      let response: VersionsResponse =
+        client.get(&url).send().await?.json().await?;
+      Ok(PackageVersions {
+        latest: response.latest,
+        versions: response.versions,
+      })
+    }
+    Registry::Npm => {
+      #[derive(Deserialize)]
+      struct PackageResponse {
+        #[serde(rename = "dist-tags")]
+        dist_tags: HashMap<String, String>,
+        versions: HashMap<String, Value>,
+      }
+
+      let url = format!(
+        "https://registry.npmjs.org/{}",
+        package.name.replace('/', "%2F")
+      );
+      let response: PackageResponse =
+        client.get(&url).send().await?.json().await?;
+      let latest = match response.dist_tags.get("latest") {
+        Some(latest) => latest.clone(),
+        None => bail!("No latest version of {} found", package.name),
+      };
+      Ok(PackageVersions {
+        latest,
+        versions: response.versions.into_keys().collect(),
+      })
+    }
+  }
+}
+
+/// The versions of an outdated package.
+#[derive(Debug, PartialEq)]
+struct OutdatedPackage {
+  package: VersionedPackage,
+  /// The newest version within the range of the specifier.
+  wanted: String,
+  latest: String,
+}
+
+/// Checks if a package is outdated given the versions published for it.
+fn get_outdated_package(
+  package: &VersionedPackage,
+  versions: &PackageVersions,
+) -> Option<OutdatedPackage> {
+  let wanted = if is_range(&package.version) {
+    versions
+      .versions
+      .iter()
+      .filter_map(|v| parse_version(v).map(|parsed| (parsed, v)))
+      .filter(|(parsed, _)| satisfies(&package.version, parsed))
+      .max_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap())
+      .map(|(_, v)| v.clone())
+      .unwrap_or_else(|| package.version.clone())
+  } else {
+    package.version.clone()
+  };
+  let latest = parse_version(&versions.latest)?;
+  let is_outdated = match parse_version(&wanted) {
+    Some(wanted) => latest > wanted,
+    None => wanted != versions.latest,
+  };
+  if is_outdated {
+    Some(OutdatedPackage {
+      package: package.clone(),
+      wanted,
+      latest: versions.latest.clone(),
+    })
+  } else {
+    None
+  }
+}
+
+/// Returns the version to update a specifier to, keeping the operator of
+/// caret and tilde ranges.
+fn get_updated_version(outdated: &OutdatedPackage) -> String {
+  match outdated.package.version.chars().next() {
+    Some(c @ ('^' | '~')) => format!("{}{}", c, outdated.latest),
+    _ => outdated.latest.clone(),
+  }
+}
+
+/// Replaces the version of the outdated packages in a specifier.
+fn update_specifier(
+  specifier: &str,
+  outdated_packages: &[OutdatedPackage],
+) -> Option<String> {
+  let package = parse_versioned_specifier(specifier)?;
+  let outdated = outdated_packages.iter().find(|o| o.package == package)?;
+  let from = format!("{}@{}", package.name, package.version);
+  let to = format!("{}@{}", package.name, get_updated_version(outdated));
+  Some(specifier.replacen(&from, &to, 1))
+}
+
+/// Collects the specifiers that are values of the import map.
+fn get_import_map_specifiers(import_map: &Value) -> Vec<String> {
+  let mut specifiers = Vec::new();
+  let mut add_values = |map: Option<&Value>| {
+    if let Some(Value::Object(map)) = map {
+      for value in map.values() {
+        if let Value::String(specifier) = value {
+          specifiers.push(specifier.clone());
+        }
+      }
+    }
+  };
+  add_values(import_map.get("imports"));
+  if let Some(Value::Object(scopes)) = import_map.get("scopes") {
+    for scope in scopes.values() {
+      add_values(Some(scope));
+    }
+  }
+  specifiers
+}
+
+/// Rewrites the outdated specifiers of the import map, returning the number
+/// of updated specifiers.
+fn update_import_map(
+  import_map: &mut Value,
+  outdated_packages: &[OutdatedPackage],
+) -> usize {
+  let mut count = 0;
+  let mut update_values = |map: Option<&mut Value>| {
+    if let Some(Value::Object(map)) = map {
+      for value in map.values_mut() {
+        if let Value::String(specifier) = value {
+          if let Some(updated) = update_specifier(specifier, outdated_packages)
+          {
+            *specifier = updated;
+            count += 1;
+          }
+        }
+      }
+    }
+  };
+  update_values(import_map.get_mut("imports"));
+  if let Some(Value::Object(scopes)) = import_map.get_mut("scopes") {
+    for scope in scopes.values_mut() {
+      update_values(Some(scope));
+    }
+  }
+  count
+}
+
+async fn create_graph(
+  ps: &ProcState,
+  roots: Vec<ModuleSpecifier>,
+) -> Result<deno_graph::ModuleGraph, AnyError> {
+  let mut cache = crate::cache::FetchCacher::new(
+    ps.dir.gen_cache.clone(),
+    ps.file_fetcher.clone(),
+    Permissions::allow_all(),
+    Permissions::allow_all(),
+  );
+  let maybe_locker = lockfile::as_maybe_locker(ps.lockfile.clone());
+  let maybe_import_map_resolver =
+    ps.maybe_import_map.clone().map(ImportMapResolver::new);
+  let maybe_jsx_resolver = ps.maybe_config_file.as_ref().and_then(|cf| {
+    cf.to_maybe_jsx_import_source_module()
+      .map(|im| JsxResolver::new(im, maybe_import_map_resolver.clone()))
+  });
+  let maybe_resolver = if maybe_jsx_resolver.is_some() {
+    maybe_jsx_resolver.as_ref().map(|jr| jr.as_resolver())
+  } else {
+    maybe_import_map_resolver
+      .as_ref()
+      .map(|im| im.as_resolver())
+  };
+
+  let graph = deno_graph::create_graph(
+    roots
+      .into_iter()
+      .map(|root| (root, deno_graph::ModuleKind::Esm))
+      .collect(),
+    false,
+    None,
+    &mut cache,
+    maybe_resolver,
+    maybe_locker,
+    None,
+    None,
+  )
+  .await;
+  graph.valid()?;
+  Ok(graph)
+}
+
+fn print_outdated_packages(outdated_packages: &[OutdatedPackage]) {
+  let header = ["Package", "Current", "Wanted", "Latest"];
+  let rows = outdated_packages
+    .iter()
+    .map(|o| {
+      [
+        o.package.name.clone(),
+        o.package.version.clone(),
+        o.wanted.clone(),
+        o.latest.clone(),
+      ]
+    })
+    .collect::<Vec<_>>();
+  let mut widths = header.map(|h| h.len());
+  for row in &rows {
+    for (width, cell) in widths.iter_mut().zip(row) {
+      *width = (*width).max(cell.len());
+    }
+  }
+  println!(
+    "{}",
+    colors::bold(format!(
+      "{:w0$}  {:w1$}  {:w2$}  {}",
+      header[0],
+      header[1],
+      header[2],
+      header[3],
+      w0 = widths[0],
+      w1 = widths[1],
+      w2 = widths[2],
+    ))
+  );
+  for row in rows {
+    println!(
+      "{:w0$}  {:w1$}  {:w2$}  {}",
+      row[0],
+      row[1],
+      row[2],
+      colors::green(&row[3]),
+      w0 = widths[0],
+      w1 = widths[1],
+      w2 = widths[2],
+    );
+  }
+}
+
+pub async fn outdated(
+  flags: Flags,
+  outdated_flags: OutdatedFlags,
+) -> Result<(), AnyError> {
+  let ps = ProcState::build(Arc::new(flags.clone())).await?;
+  let roots = outdated_flags
+    .files
+    .iter()
+    .map(|file| resolve_url_or_path(file))
+    .collect::<Result<Vec<_>, _>>()?;
+
+  let maybe_import_map_specifier = config_file::resolve_import_map_specifier(
+    flags.import_map_path.as_deref(),
+    ps.maybe_config_file.as_ref(),
+  )?;
+  let maybe_import_map = match &maybe_import_map_specifier {
+    Some(specifier) => {
+      let file = ps
+        .file_fetcher
+        .fetch(specifier, &mut Permissions::allow_all())
+        .await?;
+      let value: Value =
+        serde_json::from_str(&file.source).with_context(|| {
+          format!("Unable to parse import map: {}", specifier)
+        })?;
+      Some((specifier.clone(), value))
+    }
+    None => None,
+  };
+  if roots.is_empty() && maybe_import_map.is_none() {
+    bail!("No modules to check. Pass the modules or use an import map.");
+  }
+
+  let mut specifiers = BTreeSet::new();
+  if let Some((_, import_map)) = &maybe_import_map {
+    specifiers.extend(get_import_map_specifiers(import_map));
+  }
+  if !roots.is_empty() {
+    let graph = create_graph(&ps, roots.clone()).await?;
+    specifiers.extend(graph.modules().iter().map(|m| m.specifier.to_string()));
+  }
+  let packages = specifiers
+    .iter()
+    .filter_map(|specifier| parse_versioned_specifier(specifier))
+    .collect::<BTreeSet<_>>();
+
+  let client = Client::builder().build()?;
+  let mut versions_cache = BTreeMap::new();
+  let mut outdated_packages = Vec::new();
+  for package in &packages {
+    let key = (package.registry, package.name.clone());
+    if !versions_cache.contains_key(&key) {
+      match fetch_package_versions(&client, package).await {
+        Ok(versions) => {
+          versions_cache.insert(key.clone(), versions);
+        }
+        Err(err) => {
+          log::warn!(
+            "{} Unable to get the versions of {}: {}",
+            colors::yellow("Warning"),
+            package.name,
+            err
+          );
+          continue;
+        }
+      }
+    }
+    if let Some(outdated) = get_outdated_package(package, &versions_cache[&key])
+    {
+      outdated_packages.push(outdated);
+    }
+  }
+
+  if outdated_packages.is_empty() {
+    println!("All dependencies are up to date.");
+    return Ok(());
+  }
+  print_outdated_packages(&outdated_packages);
+
+  if !outdated_flags.update {
+    return Ok(());
+  }
+
+  let (import_map_specifier, mut import_map) = match maybe_import_map {
+    Some(import_map) => import_map,
+    None => bail!("Updating requires an import map to rewrite."),
+  };
+  if import_map_specifier.scheme() != "file" {
+    bail!(
+      "Only local import maps can be updated: {}",
+      import_map_specifier
+    );
+  }
+  let import_map_packages = get_import_map_specifiers(&import_map)
+    .iter()
+    .filter_map(|specifier| parse_versioned_specifier(specifier))
+    .collect::<BTreeSet<_>>();
+  let count = update_import_map(&mut import_map, &outdated_packages);
+  let import_map_path = import_map_specifier.to_file_path().unwrap();
+  let text = serde_json::to_string_pretty(&import_map)?;
+  let text = format_json(&text, &Default::default())
+    .ok()
+    .flatten()
+    .unwrap_or(text);
+  std::fs::write(&import_map_path, text)?;
+  println!(
+    "Updated {} specifier(s) in {}",
+    count,
+    import_map_path.display()
+  );
+
+  for outdated in outdated_packages
+    .iter()
+    .filter(|o| !import_map_packages.contains(&o.package))
+  {
+    log::warn!(
+      "{} {}@{} is not imported through the import map and was not updated",
+      colors::yellow("Warning"),
+      outdated.package.name,
+      outdated.package.version,
+    );
+  }
+
+  if flags.lock.is_some() && !roots.is_empty() {
+    // rebuild the graph with the updated import map, writing the lockfile
+    let mut flags = flags;
+    flags.lock_write = true;
+    flags.type_check_mode = TypeCheckMode::None;
+    let ps = ProcState::build(Arc::new(flags)).await?;
+    create_graph(&ps, roots).await?;
+    if let Some(lockfile) = &ps.lockfile {
+      let lockfile = lockfile.lock();
+      lockfile.write()?;
+      println!("Updated {}", lockfile.filename.display());
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn package(
+    registry: Registry,
+    name: &str,
+    version: &str,
+  ) -> VersionedPackage {
+    VersionedPackage {
+      registry,
+      name: name.to_string(),
+      version: version.to_string(),
+    }
+  }
+
+  #[test]
+  fn versioned_specifiers() {
+    let fixtures = vec![
+      (
+        "https://deno.land/std@0.137.0/fs/mod.ts",
+        Some(package(Registry::DenoLand, "std", "0.137.0")),
+      ),
+      (
+        "https://deno.land/x/oak@v10.5.1/mod.ts",
+        Some(package(Registry::DenoLand, "oak", "v10.5.1")),
+      ),
+      (
+        "https://esm.sh/react@^17.0.0",
+        Some(package(Registry::Npm, "react", "^17.0.0")),
+      ),
+      (
+        "https://esm.sh/v86/react-dom@18.1.0/es2022/react-dom.js",
+        Some(package(Registry::Npm, "react-dom", "18.1.0")),
+      ),
+      (
+        "https://cdn.skypack.dev/@preact/signals@1.0.0?dts",
+        Some(package(Registry::Npm, "@preact/signals", "1.0.0")),
+      ),
+      (
+        "https://cdn.jsdelivr.net/npm/lodash@4/lodash.js",
+        Some(package(Registry::Npm, "lodash", "4")),
+      ),
+      ("https://deno.land/std/fs/mod.ts", None),
+      ("https://esm.sh/react", None),
+      ("https://example.com/mod@1.0.0/mod.ts", None),
+    ];
+    for (specifier, expected) in fixtures {
+      assert_eq!(
+        parse_versioned_specifier(specifier),
+        expected,
+        "{}",
+        specifier
+      );
+    }
+  }
+
+  #[test]
+  fn semver_ranges() {
+    let version = |v: &str| parse_version(v).unwrap();
+    assert!(satisfies("^1.2.0", &version("1.9.0")));
+    assert!(!satisfies("^1.2.0", &version("2.0.0")));
+    assert!(!satisfies("^1.2.0", &version("1.1.0")));
+    assert!(satisfies("^0.2.0", &version("0.2.5")));
+    assert!(!satisfies("^0.2.0", &version("0.3.0")));
+    assert!(satisfies("~1.2.0", &version("1.2.9")));
+    assert!(!satisfies("~1.2.0", &version("1.3.0")));
+    assert!(satisfies("1", &version("1.9.9")));
+    assert!(satisfies("1.2", &version("1.2.9")));
+    assert!(!satisfies("1.2", &version("1.3.0")));
+    assert!(satisfies("1.2.3", &version("1.2.3")));
+    assert!(!satisfies("1.2.3", &version("1.2.4")));
+    assert!(!satisfies("^1.2.0", &version("1.3.0-rc.1")));
+  }
+
+  #[test]
+  fn outdated_packages() {
+    let versions = PackageVersions {
+      latest: "18.1.0".to_string(),
+      versions: vec!["17.0.0", "17.0.2", "18.0.0", "18.1.0", "19.0.0-rc.0"]
+        .into_iter()
+        .map(String::from)
+        .collect(),
+    };
+    let outdated = get_outdated_package(
+      &package(Registry::Npm, "react", "^17.0.0"),
+      &versions,
+    )
+    .unwrap();
+    assert_eq!(outdated.wanted, "17.0.2");
+    assert_eq!(outdated.latest, "18.1.0");
+    assert_eq!(get_updated_version(&outdated), "^18.1.0");
+
+    let outdated = get_outdated_package(
+      &package(Registry::Npm, "react", "18.0.0"),
+      &versions,
+    )
+    .unwrap();
+    assert_eq!(outdated.wanted, "18.0.0");
+    assert_eq!(get_updated_version(&outdated), "18.1.0");
+
+    assert!(get_outdated_package(
+      &package(Registry::Npm, "react", "18.1.0"),
+      &versions
+    )
+    .is_none());
+    assert!(get_outdated_package(
+      &package(Registry::Npm, "react", "^18.0.0"),
+      &versions
+    )
+    .is_none());
+  }
+
+  #[test]
+  fn import_map_update() {
+    let mut import_map = serde_json::json!({
+      "imports": {
+        "std/": "https://deno.land/std@0.130.0/",
+        "react": "https://esm.sh/react@^17.0.0",
+        "local/": "./local/"
+      },
+      "scopes": {
+        "./vendor/": {
+          "std/": "https://deno.land/std@0.130.0/"
+        }
+      }
+    });
+    assert_eq!(
+      get_import_map_specifiers(&import_map),
+      vec![
+        "https://deno.land/std@0.130.0/",
+        "https://esm.sh/react@^17.0.0",
+        "./local/",
+        "https://deno.land/std@0.130.0/",
+      ]
+    );
+    let outdated_packages = vec![
+      OutdatedPackage {
+        package: package(Registry::DenoLand, "std", "0.130.0"),
+        wanted: "0.130.0".to_string(),
+        latest: "0.137.0".to_string(),
+      },
+      OutdatedPackage {
+        package: package(Registry::Npm, "react", "^17.0.0"),
+        wanted: "17.0.2".to_string(),
+        latest: "18.1.0".to_string(),
+      },
+    ];
+    assert_eq!(update_import_map(&mut import_map, &outdated_packages), 3);
+    assert_eq!(
+      import_map,
+      serde_json::json!({
+        "imports": {
+          "std/": "https://deno.land/std@0.137.0/",
+          "react": "https://esm.sh/react@^18.1.0",
+          "local/": "./local/"
+        },
+        "scopes": {
+          "./vendor/": {
+            "std/": "https://deno.land/std@0.137.0/"
+          }
+        }
+      })
+    );
+  }
+}
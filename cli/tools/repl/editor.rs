@@ -3,6 +3,7 @@
 use crate::cdp;
 use crate::colors;
 use deno_ast::swc::parser::error::SyntaxError;
+use deno_ast::swc::parser::token::Keyword;
 use deno_ast::swc::parser::token::Token;
 use deno_ast::swc::parser::token::Word;
 use deno_core::error::AnyError;
@@ -11,6 +12,7 @@ use deno_core::serde_json;
 use rustyline::completion::Completer;
 use rustyline::error::ReadlineError;
 use rustyline::highlight::Highlighter;
+use rustyline::history::History;
 use rustyline::validate::ValidationContext;
 use rustyline::validate::ValidationResult;
 use rustyline::validate::Validator;
@@ -20,6 +22,7 @@ use rustyline::Context;
 use rustyline::Editor;
 use rustyline_derive::{Helper, Hinter};
 use std::borrow::Cow;
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -31,6 +34,10 @@ use super::channel::RustylineSyncMessageSender;
 pub struct EditorHelper {
   pub context_id: u64,
   pub sync_sender: RustylineSyncMessageSender,
+  /// The bare specifiers of the import map.
+  pub import_map_specifiers: Vec<String>,
+  /// The specifiers imported by the lines evaluated so far.
+  pub imported_specifiers: Arc<Mutex<Vec<String>>>,
 }
 
 impl EditorHelper {
@@ -143,6 +150,183 @@ impl EditorHelper {
   }
 }
 
+/// What a string literal is passed to.
+#[derive(Debug, PartialEq)]
+enum StringLiteralKind {
+  /// An import declaration, export declaration or dynamic import.
+  Import,
+  /// A file system API, like `Deno.readTextFile`.
+  FilePath,
+}
+
+const FILE_PATH_FUNCTIONS: &[&str] = &[
+  "Deno.chdir",
+  "Deno.lstat",
+  "Deno.lstatSync",
+  "Deno.open",
+  "Deno.openSync",
+  "Deno.readDir",
+  "Deno.readDirSync",
+  "Deno.readFile",
+  "Deno.readFileSync",
+  "Deno.readTextFile",
+  "Deno.readTextFileSync",
+  "Deno.remove",
+  "Deno.removeSync",
+  "Deno.stat",
+  "Deno.statSync",
+  "Deno.writeFile",
+  "Deno.writeFileSync",
+  "Deno.writeTextFile",
+  "Deno.writeTextFileSync",
+];
+
+impl EditorHelper {
+  fn get_string_literal_completions(
+    &self,
+    kind: StringLiteralKind,
+    text: &str,
+  ) -> Vec<String> {
+    if kind == StringLiteralKind::FilePath
+      || text.starts_with('.')
+      || text.starts_with('/')
+    {
+      return get_file_path_completions(text);
+    }
+
+    let imported_specifiers = self.imported_specifiers.lock();
+    let mut candidates = self
+      .import_map_specifiers
+      .iter()
+      .chain(imported_specifiers.iter())
+      .filter(|s| s.starts_with(text))
+      .cloned()
+      .collect::<Vec<_>>();
+    candidates.sort();
+    candidates.dedup();
+    candidates
+  }
+}
+
+/// Gets the kind of the string literal the cursor is in, along with the
+/// position its text starts at.
+fn get_string_literal_at_pos(
+  line: &str,
+  cursor_pos: usize,
+) -> Option<(StringLiteralKind, usize)> {
+  let mut quote: Option<(char, usize)> = None;
+  let mut chars = line[..cursor_pos].char_indices();
+  while let Some((index, c)) = chars.next() {
+    match quote {
+      Some(_) if c == '\\' => {
+        chars.next();
+      }
+      Some((quote_char, _)) if c == quote_char => quote = None,
+      Some(_) => {}
+      None if c == '\'' || c == '"' => quote = Some((c, index + 1)),
+      None => {}
+    }
+  }
+  let (_, start) = quote?;
+  let before = line[..start - 1].trim_end();
+  let kind = match before.strip_suffix('(') {
+    Some(callee) => {
+      let callee = callee.trim_end();
+      if ends_with_word(callee, "import") {
+        StringLiteralKind::Import
+      } else if FILE_PATH_FUNCTIONS
+        .iter()
+        .any(|name| ends_with_word(callee, name))
+      {
+        StringLiteralKind::FilePath
+      } else {
+        return None;
+      }
+    }
+    None
+      if ends_with_word(before, "import") || ends_with_word(before, "from") =>
+    {
+      StringLiteralKind::Import
+    }
+    None => return None,
+  };
+  Some((kind, start))
+}
+
+fn ends_with_word(text: &str, word: &str) -> bool {
+  match text.strip_suffix(word) {
+    Some(rest) => !rest
+      .chars()
+      .last()
+      .map(|c| c.is_alphanumeric() || c == '_' || c == '$' || c == '.')
+      .unwrap_or(false),
+    None => false,
+  }
+}
+
+/// Gets the files and directories that start with the text, relative to the
+/// current directory.
+fn get_file_path_completions(text: &str) -> Vec<String> {
+  let (dir_text, file_prefix) = match text.rfind('/') {
+    Some(index) => (&text[..index + 1], &text[index + 1..]),
+    None => ("", text),
+  };
+  let dir_path = if dir_text.is_empty() {
+    PathBuf::from(".")
+  } else {
+    PathBuf::from(dir_text)
+  };
+  let entries = match std::fs::read_dir(dir_path) {
+    Ok(entries) => entries,
+    Err(_) => return Vec::new(),
+  };
+  let mut candidates = entries
+    .filter_map(|entry| {
+      let entry = entry.ok()?;
+      let name = entry.file_name().to_str()?.to_string();
+      // only show hidden files when asked for
+      if !name.starts_with(file_prefix)
+        || (file_prefix.is_empty() && name.starts_with('.'))
+      {
+        return None;
+      }
+      let suffix = if entry.file_type().ok()?.is_dir() {
+        "/"
+      } else {
+        ""
+      };
+      Some(format!("{}{}{}", dir_text, name, suffix))
+    })
+    .collect::<Vec<_>>();
+  candidates.sort();
+  candidates
+}
+
+/// Gets the specifiers of the imports and exports in the source.
+pub fn get_import_specifiers(source: &str) -> Vec<String> {
+  let mut specifiers = Vec::new();
+  let mut previous_tokens: Vec<Token> = Vec::new();
+  for item in deno_ast::lex(source, deno_ast::MediaType::TypeScript) {
+    if let deno_ast::TokenOrComment::Token(token) = item.inner {
+      if let Token::Str { value, .. } = &token {
+        let is_specifier = match previous_tokens.as_slice() {
+          [.., Token::Word(Word::Keyword(Keyword::Import))]
+          | [.., Token::Word(Word::Keyword(Keyword::Import)), Token::LParen] => {
+            true
+          }
+          [.., Token::Word(Word::Ident(ident))] => &**ident == "from",
+          _ => false,
+        };
+        if is_specifier {
+          specifiers.push(value.to_string());
+        }
+      }
+      previous_tokens.push(token);
+    }
+  }
+  specifiers
+}
+
 fn is_word_boundary(c: char) -> bool {
   if c == '.' {
     false
@@ -175,6 +359,14 @@ impl Completer for EditorHelper {
     pos: usize,
     _ctx: &Context<'_>,
   ) -> Result<(usize, Vec<String>), ReadlineError> {
+    if let Some((kind, start)) = get_string_literal_at_pos(line, pos) {
+      let candidates =
+        self.get_string_literal_completions(kind, &line[start..pos]);
+      if !candidates.is_empty() {
+        return Ok((start, candidates));
+      }
+    }
+
     let lsp_completions = self.sync_sender.lsp_completions(line, pos);
     if !lsp_completions.is_empty() {
       // assumes all lsp completions have the same start position
@@ -351,18 +543,27 @@ impl Highlighter for EditorHelper {
 #[derive(Clone)]
 pub struct ReplEditor {
   inner: Arc<Mutex<Editor<EditorHelper>>>,
-  history_file_path: PathBuf,
+  history_file_path: Option<PathBuf>,
 }
 
 impl ReplEditor {
-  pub fn new(helper: EditorHelper, history_file_path: PathBuf) -> Self {
+  pub fn new(
+    helper: EditorHelper,
+    history_file_path: Option<PathBuf>,
+    history_size: usize,
+  ) -> Self {
     let editor_config = Config::builder()
       .completion_type(CompletionType::List)
+      .max_history_size(history_size)
+      .history_ignore_dups(true)
       .build();
 
     let mut editor = Editor::with_config(editor_config);
     editor.set_helper(Some(helper));
-    editor.load_history(&history_file_path).unwrap_or(());
+    if let Some(history_file_path) = &history_file_path {
+      editor.load_history(history_file_path).unwrap_or(());
+      dedup_history(editor.history_mut());
+    }
 
     ReplEditor {
       inner: Arc::new(Mutex::new(editor)),
@@ -375,13 +576,40 @@ impl ReplEditor {
   }
 
   pub fn add_history_entry(&self, entry: String) {
-    self.inner.lock().add_history_entry(entry);
+    let mut editor = self.inner.lock();
+    editor.add_history_entry(entry);
+    dedup_history(editor.history_mut());
   }
 
   pub fn save_history(&self) -> Result<(), AnyError> {
-    std::fs::create_dir_all(self.history_file_path.parent().unwrap())?;
+    let history_file_path = match &self.history_file_path {
+      Some(history_file_path) => history_file_path,
+      None => return Ok(()),
+    };
+    if let Some(parent) = history_file_path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
 
-    self.inner.lock().save_history(&self.history_file_path)?;
+    self.inner.lock().save_history(history_file_path)?;
     Ok(())
   }
 }
+
+/// Only keeps the most recent occurrence of each entry in the history.
+fn dedup_history(history: &mut History) {
+  let mut seen = HashSet::new();
+  let mut entries = history
+    .iter()
+    .rev()
+    .filter(|entry| seen.insert(entry.as_str()))
+    .cloned()
+    .collect::<Vec<_>>();
+  if entries.len() == history.len() {
+    return;
+  }
+  entries.reverse();
+  history.clear();
+  for entry in entries {
+    history.add(entry);
+  }
+}
@@ -1,10 +1,14 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
+use crate::colors;
 use crate::proc_state::ProcState;
 use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
 use deno_runtime::permissions::Permissions;
 use deno_runtime::worker::MainWorker;
 use rustyline::error::ReadlineError;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 mod channel;
 mod editor;
@@ -19,6 +23,8 @@ use editor::ReplEditor;
 use session::EvaluationOutput;
 use session::ReplSession;
 
+const DEFAULT_HISTORY_SIZE: usize = 1000;
+
 async fn read_line_and_poll(
   repl_session: &mut ReplSession,
   message_handler: &mut RustylineSyncMessageHandler,
@@ -59,6 +65,35 @@ async fn read_line_and_poll(
   }
 }
 
+/// Gets the file to persist the history to, which is disabled when the
+/// `DENO_REPL_HISTORY` environment variable is set to an empty value.
+fn history_file_path(ps: &ProcState) -> Option<PathBuf> {
+  match std::env::var_os("DENO_REPL_HISTORY") {
+    Some(path) if path.is_empty() => None,
+    Some(path) => Some(PathBuf::from(path)),
+    None => Some(ps.dir.root.join("deno_history.txt")),
+  }
+}
+
+/// Gets the maximum number of history entries to keep from the
+/// `DENO_REPL_HISTORY_SIZE` environment variable.
+fn history_size() -> usize {
+  match std::env::var("DENO_REPL_HISTORY_SIZE") {
+    Ok(size) => match size.parse() {
+      Ok(size) => size,
+      Err(_) => {
+        log::warn!(
+          "{} Ignoring invalid DENO_REPL_HISTORY_SIZE: {}",
+          colors::yellow("Warning"),
+          size
+        );
+        DEFAULT_HISTORY_SIZE
+      }
+    },
+    Err(_) => DEFAULT_HISTORY_SIZE,
+  }
+}
+
 async fn read_eval_file(
   ps: &ProcState,
   eval_file: &str,
@@ -82,13 +117,26 @@ pub async fn run(
   let mut repl_session = ReplSession::initialize(worker).await?;
   let mut rustyline_channel = rustyline_channel();
 
+  let imported_specifiers = Arc::new(Mutex::new(Vec::new()));
   let helper = EditorHelper {
     context_id: repl_session.context_id,
     sync_sender: rustyline_channel.0,
+    import_map_specifiers: ps
+      .maybe_import_map
+      .as_ref()
+      .map(|import_map| {
+        import_map
+          .imports_keys()
+          .iter()
+          .filter(|key| !key.starts_with("file://"))
+          .map(|key| key.to_string())
+          .collect()
+      })
+      .unwrap_or_default(),
+    imported_specifiers: imported_specifiers.clone(),
   };
 
-  let history_file_path = ps.dir.root.join("deno_history.txt");
-  let editor = ReplEditor::new(helper, history_file_path);
+  let editor = ReplEditor::new(helper, history_file_path(ps), history_size());
 
   if let Some(eval_files) = maybe_eval_files {
     for eval_file in eval_files {
@@ -137,6 +185,12 @@ pub async fn run(
           break;
         }
 
+        if let EvaluationOutput::Value(_) = output {
+          imported_specifiers
+            .lock()
+            .extend(editor::get_import_specifiers(&line));
+        }
+
         println!("{}", output);
 
         editor.add_history_entry(line);
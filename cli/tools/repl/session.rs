@@ -7,10 +7,13 @@ use deno_ast::DiagnosticsError;
 use deno_ast::ImportsNotUsedAsValues;
 use deno_core::error::AnyError;
 use deno_core::futures::FutureExt;
+use deno_core::parking_lot::Mutex;
 use deno_core::serde_json;
 use deno_core::serde_json::Value;
+use deno_core::v8;
 use deno_core::LocalInspectorSession;
 use deno_runtime::worker::MainWorker;
+use std::sync::Arc;
 
 static PRELUDE: &str = r#"
 Object.defineProperty(globalThis, "_", {
@@ -59,6 +62,63 @@ impl std::fmt::Display for EvaluationOutput {
   }
 }
 
+#[derive(Default)]
+struct InterruptState {
+  is_evaluating: bool,
+  interrupted: bool,
+}
+
+/// Terminates the execution of the code being evaluated when Ctrl-C is
+/// pressed, so that long running code doesn't wedge the REPL.
+struct EvaluationInterrupter {
+  state: Arc<Mutex<InterruptState>>,
+}
+
+impl EvaluationInterrupter {
+  fn new(isolate_handle: v8::IsolateHandle) -> Self {
+    let state = Arc::new(Mutex::new(InterruptState::default()));
+    let thread_state = state.clone();
+    // The isolate blocks the main thread while evaluating, so the signal has
+    // to be received on another one.
+    std::thread::spawn(move || {
+      let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+      {
+        Ok(runtime) => runtime,
+        Err(_) => return,
+      };
+      runtime.block_on(async move {
+        while tokio::signal::ctrl_c().await.is_ok() {
+          let mut state = thread_state.lock();
+          if state.is_evaluating {
+            state.interrupted = true;
+            isolate_handle.terminate_execution();
+          } else {
+            // keep the default behavior of Ctrl-C outside of evaluations
+            std::process::exit(130);
+          }
+        }
+      });
+    });
+    Self { state }
+  }
+
+  fn start(&self) {
+    *self.state.lock() = InterruptState {
+      is_evaluating: true,
+      interrupted: false,
+    };
+  }
+
+  /// Returns if the evaluation was interrupted.
+  fn finish(&self) -> bool {
+    let mut state = self.state.lock();
+    state.is_evaluating = false;
+    state.interrupted
+  }
+}
+
 struct TsEvaluateResponse {
   ts_code: String,
   value: cdp::EvaluateResponse,
@@ -69,6 +129,7 @@ pub struct ReplSession {
   session: LocalInspectorSession,
   pub context_id: u64,
   pub language_server: ReplLanguageServer,
+  interrupter: EvaluationInterrupter,
 }
 
 impl ReplSession {
@@ -103,11 +164,15 @@ impl ReplSession {
       }
     }
 
+    let interrupter = EvaluationInterrupter::new(
+      worker.js_runtime.v8_isolate().thread_safe_handle(),
+    );
     let mut repl_session = ReplSession {
       worker,
       session,
       context_id,
       language_server,
+      interrupter,
     };
 
     // inject prelude
@@ -158,7 +223,20 @@ impl ReplSession {
       )
     }
 
-    match self.evaluate_line_with_object_wrapping(line).await {
+    self.interrupter.start();
+    let evaluate_result = self.evaluate_line_with_object_wrapping(line).await;
+    if self.interrupter.finish() {
+      // allow executing code in the isolate again, which keeps the state of
+      // the session from before the interrupted evaluation
+      self
+        .worker
+        .js_runtime
+        .v8_isolate()
+        .cancel_terminate_execution();
+      return Ok(EvaluationOutput::Error("Interrupted".to_string()));
+    }
+
+    match evaluate_result {
       Ok(evaluate_response) => {
         let cdp::EvaluateResponse {
           result,
@@ -1,6 +1,8 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
+use crate::colors;
 use crate::deno_dir::DenoDir;
+use crate::display;
 use crate::flags::CompileFlags;
 use crate::flags::DenoSubcommand;
 use crate::flags::Flags;
@@ -8,6 +10,9 @@ use crate::flags::FutureTypeCheckMode;
 use crate::flags::RunFlags;
 use crate::flags::TypeCheckMode;
 use crate::fs_util;
+use crate::ops::virtual_fs::embedded_file_key;
+use crate::standalone::embedded_files_root;
+use crate::standalone::EmbeddedFile;
 use crate::standalone::Metadata;
 use crate::standalone::MAGIC_TRAILER;
 use crate::ProcState;
@@ -18,9 +23,12 @@ use deno_core::error::AnyError;
 use deno_core::resolve_url_or_path;
 use deno_core::serde_json;
 use deno_core::url::Url;
+use deno_graph::ModuleGraph;
 use deno_graph::ModuleSpecifier;
 use deno_runtime::deno_fetch::reqwest::Client;
 use deno_runtime::permissions::Permissions;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use std::env;
 use std::fs::read;
 use std::fs::File;
@@ -28,11 +36,55 @@ use std::io::Read;
 use std::io::Seek;
 use std::io::SeekFrom;
 use std::io::Write;
+use std::iter::once;
 use std::path::Path;
 use std::path::PathBuf;
 
 use super::installer::infer_name_from_url;
 
+/// Matches workers created from a URL relative to the module, e.g.
+/// `new Worker(new URL("./worker.ts", import.meta.url), { type: "module" })`.
+static WORKER_RE: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(
+    r#"new\s+Worker\s*\(\s*new\s+URL\s*\(\s*(?:"([^"\n]+)"|'([^'\n]+)')\s*,\s*import\.meta\.url\s*\)"#,
+  )
+  .unwrap()
+});
+
+/// Returns the entry points of the workers created by the modules of the
+/// graph which are not roots of the graph yet, so they can be added to it
+/// and embedded with the rest of the program.
+pub fn find_worker_entry_points(graph: &ModuleGraph) -> Vec<ModuleSpecifier> {
+  let mut entry_points = Vec::new();
+  for module in graph.modules() {
+    let source = match &module.maybe_source {
+      Some(source) => source,
+      None => continue,
+    };
+    for specifier in worker_specifiers_in_source(&module.specifier, source) {
+      if !graph.roots.iter().any(|(root, _)| *root == specifier)
+        && !entry_points.contains(&specifier)
+      {
+        entry_points.push(specifier);
+      }
+    }
+  }
+  entry_points
+}
+
+fn worker_specifiers_in_source(
+  referrer: &ModuleSpecifier,
+  source: &str,
+) -> Vec<ModuleSpecifier> {
+  WORKER_RE
+    .captures_iter(source)
+    .filter_map(|captures| {
+      let specifier = captures.get(1).or_else(|| captures.get(2))?.as_str();
+      referrer.join(specifier).ok()
+    })
+    .collect()
+}
+
 pub async fn get_base_binary(
   deno_dir: &DenoDir,
   target: Option<String>,
@@ -93,16 +145,415 @@ async fn download_base_binary(
   Ok(())
 }
 
-/// This functions creates a standalone deno binary by appending a bundle
-/// and magic trailer to the currently executing binary.
+/// Collects the files to embed into the binary from the paths and globs
+/// passed to `--include`, sorted by path.
+pub fn collect_included_files(
+  include: &[String],
+) -> Result<Vec<PathBuf>, AnyError> {
+  let mut files = Vec::new();
+  for path in include {
+    let matched_files = if fs_util::is_glob(path) {
+      fs_util::expand_glob(path, &[])?
+    } else {
+      let path = fs_util::resolve_from_cwd(Path::new(path))?;
+      fs_util::collect_files(&[path], &[], |_| true)?
+    };
+    if matched_files.is_empty() {
+      bail!("No files to include were found for \"{}\".", path);
+    }
+    files.extend(matched_files);
+  }
+  files.sort();
+  files.dedup();
+  Ok(files)
+}
+
+#[cfg(feature = "windows_metadata")]
+const RT_VERSION: u32 = 16;
+const LANG_EN_US: u32 = 0x0409;
+const CODEPAGE_UTF16: u32 = 1200;
+
+// Values of the `VS_FIXEDFILEINFO` fields.
+const VS_FFI_SIGNATURE: u32 = 0xFEEF04BD;
+const VS_FFI_STRUCVERSION: u32 = 0x00010000;
+const VS_FFI_FILEFLAGSMASK: u32 = 0x3F;
+const VOS_NT_WINDOWS32: u32 = 0x00040004;
+const VFT_APP: u32 = 1;
+
+/// Sets the icon and version info of executables for Windows targets. These
+/// are stored as resources of the PE image, which other targets lack, so the
+/// flags are ignored with a warning for them. Writing the resources needs the
+/// `windows_metadata` feature, without which this fails for Windows targets.
+pub fn apply_binary_metadata(
+  original_bin: Vec<u8>,
+  compile_flags: &CompileFlags,
+  output_path: &Path,
+) -> Result<Vec<u8>, AnyError> {
+  let has_version_info = compile_flags.product_name.is_some()
+    || compile_flags.product_version.is_some()
+    || compile_flags.description.is_some()
+    || compile_flags.copyright.is_some();
+  if compile_flags.icon.is_none() && !has_version_info {
+    return Ok(original_bin);
+  }
+  let is_windows_target = match &compile_flags.target {
+    Some(target) => target.contains("windows"),
+    None => cfg!(windows),
+  };
+  if !is_windows_target {
+    log::warn!(
+      "{} the icon and version info are only supported for Windows targets and were ignored.",
+      crate::colors::yellow("Warning"),
+    );
+    return Ok(original_bin);
+  }
+
+  let maybe_version_info = if has_version_info {
+    Some(version_info_resource(compile_flags, output_path)?)
+  } else {
+    None
+  };
+  set_resources(
+    original_bin,
+    compile_flags.icon.as_deref(),
+    maybe_version_info,
+  )
+}
+
+/// Adds the icon and the encoded version info to the resources of a PE image.
+#[cfg(feature = "windows_metadata")]
+fn set_resources(
+  original_bin: Vec<u8>,
+  maybe_icon: Option<&Path>,
+  maybe_version_info: Option<Vec<u8>>,
+) -> Result<Vec<u8>, AnyError> {
+  let mut image = editpe::Image::parse(&original_bin).map_err(|err| {
+    generic_error(format!("Failed to parse executable: {}", err))
+  })?;
+  let mut resources = image.resource_directory().cloned().unwrap_or_default();
+  if let Some(icon) = maybe_icon {
+    let icon_data = read(icon)
+      .with_context(|| format!("Failed to read icon \"{}\"", icon.display()))?;
+    resources.set_icon(icon_data).map_err(|err| {
+      generic_error(format!(
+        "Failed to set icon \"{}\": {}",
+        icon.display(),
+        err
+      ))
+    })?;
+  }
+  if let Some(version_info) = maybe_version_info {
+    let mut data = editpe::ResourceData::default();
+    data.set_data(version_info);
+    let mut languages = editpe::ResourceTable::default();
+    languages.insert(
+      editpe::ResourceEntryName::ID(LANG_EN_US),
+      editpe::ResourceEntry::Data(data),
+    );
+    let mut names = editpe::ResourceTable::default();
+    names.insert(
+      editpe::ResourceEntryName::ID(1),
+      editpe::ResourceEntry::Table(languages),
+    );
+    resources.root_mut().insert(
+      editpe::ResourceEntryName::ID(RT_VERSION),
+      editpe::ResourceEntry::Table(names),
+    );
+  }
+  image.set_resource_directory(resources).map_err(|err| {
+    generic_error(format!("Failed to write resources: {}", err))
+  })?;
+  Ok(image.data().to_vec())
+}
+
+#[cfg(not(feature = "windows_metadata"))]
+fn set_resources(
+  _original_bin: Vec<u8>,
+  _maybe_icon: Option<&Path>,
+  _maybe_version_info: Option<Vec<u8>>,
+) -> Result<Vec<u8>, AnyError> {
+  bail!("Setting the icon and version info requires a build of deno with the \"windows_metadata\" feature.")
+}
+
+/// Parses a version of up to four numbers separated by dots, as used by the
+/// fixed version info of Windows executables.
+fn parse_binary_version(version: &str) -> Result<[u16; 4], AnyError> {
+  let mut parts = [0; 4];
+  let numbers = version.split('.').collect::<Vec<_>>();
+  if numbers.len() > parts.len() {
+    bail!(
+      "Invalid product version \"{}\": expected at most four numbers separated by dots.",
+      version
+    );
+  }
+  for (part, number) in parts.iter_mut().zip(numbers) {
+    *part = number.parse().with_context(|| {
+      format!(
+        "Invalid product version \"{}\": \"{}\" is not a number from 0 to 65535.",
+        version, number
+      )
+    })?;
+  }
+  Ok(parts)
+}
+
+/// Encodes a `VS_VERSIONINFO` structure with the fixed file info and the
+/// strings of the given flags.
+fn version_info_resource(
+  compile_flags: &CompileFlags,
+  output_path: &Path,
+) -> Result<Vec<u8>, AnyError> {
+  let version = match &compile_flags.product_version {
+    Some(version) => parse_binary_version(version)?,
+    None => [0; 4],
+  };
+  let version_ms = ((version[0] as u32) << 16) | version[1] as u32;
+  let version_ls = ((version[2] as u32) << 16) | version[3] as u32;
+  // The fields of `VS_FIXEDFILEINFO` in order: the signature and structure
+  // version, the file version then the product version (both as two halves),
+  // the flags mask and flags, the OS and type, and finally the subtype and
+  // the two halves of the date, which are left unset.
+  let mut fixed_file_info = Vec::new();
+  for value in [
+    VS_FFI_SIGNATURE,
+    VS_FFI_STRUCVERSION,
+    version_ms,
+    version_ls,
+    version_ms,
+    version_ls,
+    VS_FFI_FILEFLAGSMASK,
+    0,
+    VOS_NT_WINDOWS32,
+    VFT_APP,
+    0,
+    0,
+    0,
+  ] {
+    fixed_file_info.extend(u32::to_le_bytes(value));
+  }
+
+  let version_string = version
+    .iter()
+    .map(|part| part.to_string())
+    .collect::<Vec<_>>()
+    .join(".");
+  let original_filename = output_path
+    .file_name()
+    .map(|name| name.to_string_lossy().to_string());
+  let strings = [
+    ("FileDescription", compile_flags.description.clone()),
+    ("FileVersion", Some(version_string.clone())),
+    ("LegalCopyright", compile_flags.copyright.clone()),
+    ("OriginalFilename", original_filename),
+    ("ProductName", compile_flags.product_name.clone()),
+    ("ProductVersion", Some(version_string)),
+  ]
+  .into_iter()
+  .filter_map(|(key, value)| {
+    let value = value?;
+    let utf16 = value.encode_utf16().chain(once(0)).collect::<Vec<_>>();
+    Some(version_block(
+      key,
+      1,
+      &utf16_bytes(&utf16),
+      utf16.len() as u16,
+      &[],
+    ))
+  })
+  .collect::<Vec<_>>();
+  let string_table = version_block(
+    &format!("{:04x}{:04x}", LANG_EN_US, CODEPAGE_UTF16),
+    1,
+    &[],
+    0,
+    &strings,
+  );
+  let string_file_info =
+    version_block("StringFileInfo", 1, &[], 0, &[string_table]);
+  let translation = ((CODEPAGE_UTF16 << 16) | LANG_EN_US).to_le_bytes();
+  let var = version_block("Translation", 0, &translation, 4, &[]);
+  let var_file_info = version_block("VarFileInfo", 1, &[], 0, &[var]);
+  Ok(version_block(
+    "VS_VERSION_INFO",
+    0,
+    &fixed_file_info,
+    fixed_file_info.len() as u16,
+    &[string_file_info, var_file_info],
+  ))
+}
+
+fn utf16_bytes(utf16: &[u16]) -> Vec<u8> {
+  utf16.iter().flat_map(|unit| unit.to_le_bytes()).collect()
+}
+
+/// Encodes a block of a version resource: its length, value length, type and
+/// null terminated key, followed by the value and the children, which are
+/// aligned to 32 bits.
+fn version_block(
+  key: &str,
+  value_type: u16,
+  value: &[u8],
+  value_length: u16,
+  children: &[Vec<u8>],
+) -> Vec<u8> {
+  fn align(block: &mut Vec<u8>) {
+    while block.len() % 4 != 0 {
+      block.push(0);
+    }
+  }
+
+  let mut block = vec![0, 0];
+  block.extend(value_length.to_le_bytes());
+  block.extend(value_type.to_le_bytes());
+  let key = key.encode_utf16().chain(once(0)).collect::<Vec<_>>();
+  block.extend(utf16_bytes(&key));
+  align(&mut block);
+  block.extend(value);
+  for child in children {
+    align(&mut block);
+    block.extend(child);
+  }
+  let length = block.len() as u16;
+  block[0..2].copy_from_slice(&length.to_le_bytes());
+  block
+}
+
+/// The zstd level the eszip archive is compressed with by `--compress`.
+const ESZIP_COMPRESSION_LEVEL: i32 = 19;
+
+/// The serialized eszip archive to embed into the binary.
+pub struct EszipArchive {
+  pub data: Vec<u8>,
+  /// The size of the archive before compression.
+  pub uncompressed_len: usize,
+  pub compressed: bool,
+}
+
+pub fn encode_eszip(
+  eszip: eszip::EszipV2,
+  compress: bool,
+) -> Result<EszipArchive, AnyError> {
+  let data = eszip.into_bytes();
+  let uncompressed_len = data.len();
+  let data = if compress {
+    zstd::stream::encode_all(data.as_slice(), ESZIP_COMPRESSION_LEVEL)
+      .context("Failed to compress eszip archive")?
+  } else {
+    data
+  };
+  Ok(EszipArchive {
+    data,
+    uncompressed_len,
+    compressed: compress,
+  })
+}
+
+/// The number of modules listed by `--size-report`.
+const SIZE_REPORT_MODULES: usize = 10;
+
+/// Returns the size of the source of each module of the graph, largest first.
+pub fn get_module_sizes(graph: &ModuleGraph) -> Vec<(ModuleSpecifier, usize)> {
+  let mut sizes = graph
+    .modules()
+    .into_iter()
+    .filter_map(|module| {
+      let source = module.maybe_source.as_ref()?;
+      Some((module.specifier.clone(), source.len()))
+    })
+    .collect::<Vec<_>>();
+  sizes.sort_by(|(a_specifier, a), (b_specifier, b)| {
+    b.cmp(a).then_with(|| a_specifier.cmp(b_specifier))
+  });
+  sizes
+}
+
+/// Prints the size of what is embedded into the executable, along with the
+/// largest modules, for `--size-report`.
+pub fn print_size_report(
+  module_sizes: &[(ModuleSpecifier, usize)],
+  eszip_archive: &EszipArchive,
+  included_files: &[PathBuf],
+) -> Result<(), AnyError> {
+  let human_size = |size: usize| display::human_size(size as f64);
+  let modules_len = module_sizes.iter().map(|(_, size)| size).sum::<usize>();
+  let mut included_len = 0;
+  for path in included_files {
+    included_len += std::fs::metadata(path)?.len() as usize;
+  }
+
+  println!("{}", colors::bold("Size report"));
+  println!(
+    "  Modules ({}): {}",
+    module_sizes.len(),
+    human_size(modules_len)
+  );
+  if eszip_archive.compressed {
+    println!(
+      "  Eszip archive: {} (compressed to {})",
+      human_size(eszip_archive.uncompressed_len),
+      human_size(eszip_archive.data.len())
+    );
+  } else {
+    println!("  Eszip archive: {}", human_size(eszip_archive.data.len()));
+  }
+  if !included_files.is_empty() {
+    println!(
+      "  Included files ({}): {}",
+      included_files.len(),
+      human_size(included_len)
+    );
+  }
+  println!("{}", colors::bold("Largest modules"));
+  for (specifier, size) in module_sizes.iter().take(SIZE_REPORT_MODULES) {
+    println!("  {:>8}  {}", human_size(*size), specifier);
+  }
+  Ok(())
+}
+
+/// This functions creates a standalone deno binary by appending a bundle,
+/// the included files and magic trailer to the currently executing binary.
 pub async fn create_standalone_binary(
   mut original_bin: Vec<u8>,
-  eszip: eszip::EszipV2,
+  eszip_archive: EszipArchive,
   entrypoint: ModuleSpecifier,
+  included_files: Vec<PathBuf>,
   flags: Flags,
   ps: ProcState,
 ) -> Result<Vec<u8>, AnyError> {
-  let mut eszip_archive = eszip.into_bytes();
+  let compressed_eszip_len = if eszip_archive.compressed {
+    Some(eszip_archive.data.len() as u64)
+  } else {
+    None
+  };
+  let mut eszip_archive = eszip_archive.data;
+
+  let eszip_pos = original_bin.len();
+  let mut embedded_data = Vec::new();
+  let mut embedded_files = Vec::new();
+  if !included_files.is_empty() {
+    let root = match embedded_files_root(&entrypoint) {
+      Some(root) => root,
+      None => bail!("Including files requires a local main module."),
+    };
+    for path in included_files {
+      let relative_path = match path.strip_prefix(&root) {
+        Ok(relative_path) => relative_path,
+        Err(_) => bail!(
+          "Included file {} is not in the directory of the main module ({}).",
+          path.display(),
+          root.display()
+        ),
+      };
+      let mut data = read(&path)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+      embedded_files.push(EmbeddedFile {
+        path: embedded_file_key(relative_path),
+        offset: (eszip_pos + eszip_archive.len() + embedded_data.len()) as u64,
+        len: data.len() as u64,
+      });
+      embedded_data.append(&mut data);
+    }
+  }
 
   let ca_data = match &flags.ca_file {
     Some(ca_file) => Some(read(ca_file)?),
@@ -143,20 +594,21 @@ pub async fn create_standalone_binary(
     ca_data,
     entrypoint,
     maybe_import_map,
+    embedded_files,
+    compressed_eszip_len,
   };
   let mut metadata = serde_json::to_string(&metadata)?.as_bytes().to_vec();
 
-  let eszip_pos = original_bin.len();
-  let metadata_pos = eszip_pos + eszip_archive.len();
+  let metadata_pos = eszip_pos + eszip_archive.len() + embedded_data.len();
   let mut trailer = MAGIC_TRAILER.to_vec();
   trailer.write_all(&eszip_pos.to_be_bytes())?;
   trailer.write_all(&metadata_pos.to_be_bytes())?;
 
-  let mut final_bin = Vec::with_capacity(
-    original_bin.len() + eszip_archive.len() + trailer.len(),
-  );
+  let mut final_bin =
+    Vec::with_capacity(metadata_pos + metadata.len() + trailer.len());
   final_bin.append(&mut original_bin);
   final_bin.append(&mut eszip_archive);
+  final_bin.append(&mut embedded_data);
   final_bin.append(&mut metadata);
   final_bin.append(&mut trailer);
 
@@ -258,6 +710,7 @@ pub fn compile_to_runtime_flags(
     allow_ffi: flags.allow_ffi.clone(),
     allow_read: flags.allow_read.clone(),
     allow_run: flags.allow_run.clone(),
+    allow_run_args: flags.allow_run_args.clone(),
     allow_write: flags.allow_write.clone(),
     ca_stores: flags.ca_stores.clone(),
     ca_file: flags.ca_file.clone(),
@@ -271,6 +724,7 @@ pub fn compile_to_runtime_flags(
     import_map_path: flags.import_map_path.clone(),
     inspect_brk: None,
     inspect: None,
+    inspect_wait: None,
     location: flags.location.clone(),
     lock_write: false,
     lock: None,
@@ -284,6 +738,8 @@ pub fn compile_to_runtime_flags(
       .clone(),
     no_remote: false,
     no_prompt: flags.no_prompt,
+    permission_audit: flags.permission_audit.clone(),
+    permission_set: None,
     reload: false,
     repl: false,
     seed: flags.seed,
@@ -291,7 +747,12 @@ pub fn compile_to_runtime_flags(
     v8_flags: flags.v8_flags.clone(),
     version: false,
     watch: None,
+    watch_debounce: None,
+    watch_poll_interval: None,
     no_clear_screen: false,
+    hot: false,
+    dump_op_metrics: flags.dump_op_metrics.clone(),
+    max_heap_size: flags.max_heap_size,
   })
 }
 
@@ -341,6 +802,14 @@ mod test {
       output: Some(PathBuf::from("./file")),
       args: Vec::new(),
       target: Some("x86_64-unknown-linux-gnu".to_string()),
+      include: Vec::new(),
+      icon: None,
+      product_name: None,
+      product_version: None,
+      description: None,
+      copyright: None,
+      compress: false,
+      size_report: false,
     })
     .unwrap();
 
@@ -350,6 +819,52 @@ mod test {
     assert_eq!(path.file_name().unwrap(), "file");
   }
 
+  #[test]
+  fn test_worker_specifiers_in_source() {
+    let referrer = ModuleSpecifier::parse("file:///app/main.ts").unwrap();
+    let source = r#"
+      new Worker(new URL("./worker.ts", import.meta.url), { type: "module" });
+      new Worker(
+        new URL('../workers/other.js', import.meta.url).href,
+        { type: "module" },
+      );
+      new Worker(new URL("./ignored.ts", location.href));
+    "#;
+    assert_eq!(
+      worker_specifiers_in_source(&referrer, source),
+      vec![
+        ModuleSpecifier::parse("file:///app/worker.ts").unwrap(),
+        ModuleSpecifier::parse("file:///workers/other.js").unwrap(),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_parse_binary_version() {
+    assert_eq!(parse_binary_version("1.2.3").unwrap(), [1, 2, 3, 0]);
+    assert_eq!(parse_binary_version("1.2.3.4").unwrap(), [1, 2, 3, 4]);
+    assert!(parse_binary_version("1.2.3.4.5").is_err());
+    assert!(parse_binary_version("1.x").is_err());
+    assert!(parse_binary_version("70000").is_err());
+  }
+
+  #[test]
+  fn test_version_block() {
+    let block = version_block("A", 1, &[1, 0, 0, 0], 2, &[vec![9, 9]]);
+    assert_eq!(
+      block,
+      vec![
+        18, 0, // length
+        2, 0, // value length
+        1, 0, // type
+        b'A', 0, 0, 0, // key
+        0, 0, // padding
+        1, 0, 0, 0, // value
+        9, 9, // child
+      ]
+    );
+  }
+
   #[test]
   fn resolve_compile_executable_output_path_target_windows() {
     let path = resolve_compile_executable_output_path(&CompileFlags {
@@ -357,6 +872,14 @@ mod test {
       output: Some(PathBuf::from("./file")),
       args: Vec::new(),
       target: Some("x86_64-pc-windows-msvc".to_string()),
+      include: Vec::new(),
+      icon: None,
+      product_name: None,
+      product_version: None,
+      description: None,
+      copyright: None,
+      compress: false,
+      size_report: false,
     })
     .unwrap();
     assert_eq!(path.file_name().unwrap(), "file.exe");
@@ -2,19 +2,30 @@
 
 use crate::colors;
 use crate::config_file::ConfigFile;
+use crate::config_file::TaskDefinition;
 use crate::flags::Flags;
 use crate::flags::TaskFlags;
+use crate::fs_util;
 use crate::proc_state::ProcState;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
+use deno_core::futures;
+use deno_task_shell::parser::Command;
+use deno_task_shell::parser::CommandInner;
+use deno_task_shell::parser::PipelineInner;
+use deno_task_shell::parser::Sequence;
+use deno_task_shell::parser::SequentialList;
+use deno_task_shell::parser::Word;
+use deno_task_shell::parser::WordPart;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 
 fn get_tasks_config(
   maybe_config_file: Option<&ConfigFile>,
-) -> Result<BTreeMap<String, String>, AnyError> {
+) -> Result<BTreeMap<String, TaskDefinition>, AnyError> {
   if let Some(config_file) = maybe_config_file {
     let maybe_tasks_config = config_file.to_tasks_config()?;
     if let Some(tasks_config) = maybe_tasks_config {
@@ -30,6 +41,17 @@ fn get_tasks_config(
           bail!("Configuration file task names must start with an alphabetic character. Task: {}", key);
         }
       }
+      for (name, task) in &tasks_config {
+        for dependency in &task.depends_on {
+          if !tasks_config.contains_key(dependency) {
+            bail!(
+              "Task \"{}\" depends on unknown task \"{}\"",
+              name,
+              dependency
+            );
+          }
+        }
+      }
       Ok(tasks_config)
     } else {
       bail!("No tasks found in configuration file")
@@ -39,12 +61,238 @@ fn get_tasks_config(
   }
 }
 
-fn print_available_tasks(tasks_config: BTreeMap<String, String>) {
-  eprintln!("{}", colors::green("Available tasks:"));
+/// Expands the `${NAME:-default}` style environment variables and the
+/// unquoted glob arguments in the words of a parsed script, so that they
+/// behave the same on every platform.
+fn expand_list(
+  list: &mut SequentialList,
+  env_vars: &HashMap<String, String>,
+  cwd: &Path,
+) {
+  for item in &mut list.items {
+    expand_sequence(&mut item.sequence, env_vars, cwd);
+  }
+}
+
+fn expand_sequence(
+  sequence: &mut Sequence,
+  env_vars: &HashMap<String, String>,
+  cwd: &Path,
+) {
+  match sequence {
+    Sequence::ShellVar(var) => expand_word(&mut var.value, env_vars, cwd),
+    Sequence::Pipeline(pipeline) => {
+      expand_pipeline(&mut pipeline.inner, env_vars, cwd)
+    }
+    Sequence::BooleanList(list) => {
+      expand_sequence(&mut list.current, env_vars, cwd);
+      expand_sequence(&mut list.next, env_vars, cwd);
+    }
+  }
+}
+
+fn expand_pipeline(
+  inner: &mut PipelineInner,
+  env_vars: &HashMap<String, String>,
+  cwd: &Path,
+) {
+  match inner {
+    PipelineInner::Command(command) => expand_command(command, env_vars, cwd),
+    PipelineInner::PipeSequence(sequence) => {
+      expand_command(&mut sequence.current, env_vars, cwd);
+      expand_pipeline(&mut sequence.next, env_vars, cwd);
+    }
+  }
+}
+
+fn expand_command(
+  command: &mut Command,
+  env_vars: &HashMap<String, String>,
+  cwd: &Path,
+) {
+  match &mut command.inner {
+    CommandInner::Simple(simple) => {
+      for var in &mut simple.env_vars {
+        expand_word(&mut var.value, env_vars, cwd);
+      }
+      // only arguments are split into the paths a glob matches
+      for mut arg in std::mem::take(&mut simple.args) {
+        match expand_glob(&arg, cwd) {
+          Some(paths) => simple.args.extend(paths),
+          None => {
+            expand_word(&mut arg, env_vars, cwd);
+            simple.args.push(arg);
+          }
+        }
+      }
+    }
+    CommandInner::Subshell(list) => expand_list(list, env_vars, cwd),
+  }
+  if let Some(redirect) = &mut command.redirect {
+    expand_word(&mut redirect.io_file, env_vars, cwd);
+  }
+}
 
+fn expand_word(
+  word: &mut Word,
+  env_vars: &HashMap<String, String>,
+  cwd: &Path,
+) {
+  let parts = std::mem::replace(word, Word::new(Vec::new())).into_parts();
+  *word = Word::new(expand_word_parts(parts, env_vars, cwd));
+}
+
+fn expand_word_parts(
+  parts: Vec<WordPart>,
+  env_vars: &HashMap<String, String>,
+  cwd: &Path,
+) -> Vec<WordPart> {
+  parts
+    .into_iter()
+    .map(|part| match part {
+      // plain variables are left to the shell, which also knows the ones
+      // the script sets itself
+      WordPart::Variable(expression) if expression.contains('-') => {
+        WordPart::Text(expand_variable(&expression, env_vars))
+      }
+      WordPart::Quoted(parts) => {
+        WordPart::Quoted(expand_word_parts(parts, env_vars, cwd))
+      }
+      WordPart::Command(mut list) => {
+        expand_list(&mut list, env_vars, cwd);
+        WordPart::Command(list)
+      }
+      part => part,
+    })
+    .collect()
+}
+
+/// Expands the inside of `${...}`, supporting `NAME`, `NAME:-default` (used
+/// when the variable is unset or empty) and `NAME-default` (used when the
+/// variable is unset).
+fn expand_variable(
+  expression: &str,
+  env_vars: &HashMap<String, String>,
+) -> String {
+  if let Some((name, default)) = expression.split_once(":-") {
+    match env_vars.get(name) {
+      Some(value) if !value.is_empty() => value.clone(),
+      _ => default.to_string(),
+    }
+  } else if let Some((name, default)) = expression.split_once('-') {
+    match env_vars.get(name) {
+      Some(value) => value.clone(),
+      None => default.to_string(),
+    }
+  } else {
+    env_vars.get(expression).cloned().unwrap_or_default()
+  }
+}
+
+/// Gets the paths matching an argument that is an unquoted glob. Like in
+/// POSIX shells, a glob without any matches is left as is.
+fn expand_glob(word: &Word, cwd: &Path) -> Option<Vec<Word>> {
+  let mut pattern = String::new();
+  for part in word.parts() {
+    match part {
+      WordPart::Text(text) => pattern.push_str(text),
+      _ => return None,
+    }
+  }
+  if !pattern.contains(|c| matches!(c, '*' | '?' | '['))
+    || pattern.contains('=')
+  {
+    return None;
+  }
+  let paths =
+    fs_util::expand_glob(&cwd.join(&pattern).to_string_lossy(), &[]).ok()?;
+  if paths.is_empty() {
+    return None;
+  }
+  let cwd =
+    fs_util::canonicalize_path(cwd).unwrap_or_else(|_| cwd.to_path_buf());
+  Some(
+    paths
+      .iter()
+      .map(|path| {
+        let path = path.strip_prefix(&cwd).unwrap_or(path);
+        let text = path.to_string_lossy().replace('\\', "/");
+        Word::new(vec![WordPart::Text(text)])
+      })
+      .collect(),
+  )
+}
+
+/// Resolves the tasks that have to run for `task_name`, grouped into stages
+/// in dependency order. Every task of a stage only depends on tasks of the
+/// previous stages, so the tasks within a stage can run concurrently.
+fn resolve_task_stages(
+  tasks_config: &BTreeMap<String, TaskDefinition>,
+  task_name: &str,
+) -> Result<Vec<Vec<String>>, AnyError> {
+  fn visit(
+    name: &str,
+    tasks_config: &BTreeMap<String, TaskDefinition>,
+    depths: &mut HashMap<String, usize>,
+    path: &mut Vec<String>,
+  ) -> Result<usize, AnyError> {
+    if let Some(depth) = depths.get(name) {
+      return Ok(*depth);
+    }
+    if let Some(index) = path.iter().position(|n| n == name) {
+      let mut cycle = path[index..].to_vec();
+      cycle.push(name.to_string());
+      bail!("Task dependency cycle detected: {}", cycle.join(" -> "));
+    }
+    path.push(name.to_string());
+    let mut depth = 0;
+    for dependency in &tasks_config[name].depends_on {
+      depth = depth.max(visit(dependency, tasks_config, depths, path)? + 1);
+    }
+    path.pop();
+    depths.insert(name.to_string(), depth);
+    Ok(depth)
+  }
+
+  let mut depths = HashMap::new();
+  visit(task_name, tasks_config, &mut depths, &mut Vec::new())?;
+  let mut stages = vec![Vec::new(); depths.values().max().unwrap() + 1];
+  // iterate the config so that the order within a stage is deterministic
   for name in tasks_config.keys() {
+    if let Some(depth) = depths.get(name) {
+      stages[*depth].push(name.clone());
+    }
+  }
+  Ok(stages)
+}
+
+async fn run_task(
+  task_name: &str,
+  script: &str,
+  cwd: &Path,
+) -> Result<i32, AnyError> {
+  log::info!(
+    "{} {} {}",
+    colors::green("Task"),
+    colors::cyan(task_name),
+    script,
+  );
+  let env_vars = std::env::vars().collect::<HashMap<String, String>>();
+  let mut seq_list = deno_task_shell::parser::parse(script)
+    .with_context(|| format!("Error parsing script '{}'.", task_name))?;
+  expand_list(&mut seq_list, &env_vars, cwd);
+  Ok(deno_task_shell::execute(seq_list, env_vars, cwd).await)
+}
+
+fn print_available_tasks(tasks_config: BTreeMap<String, TaskDefinition>) {
+  eprintln!("{}", colors::green("Available tasks:"));
+
+  for (name, task) in &tasks_config {
     eprintln!("- {}", colors::cyan(name));
-    eprintln!("    {}", tasks_config[name])
+    eprintln!("    {}", task.cmd);
+    if !task.depends_on.is_empty() {
+      eprintln!("    depends on: {}", task.depends_on.join(", "));
+    }
   }
 }
 
@@ -73,9 +321,8 @@ pub async fn execute_script(
 
   let cwd = config_file_path.parent().unwrap();
   let task_name = task_flags.task;
-  let maybe_script = tasks_config.get(&task_name);
 
-  if let Some(script) = maybe_script {
+  if tasks_config.contains_key(&task_name) {
     let additional_args = flags
       .argv
       .iter()
@@ -84,19 +331,38 @@ pub async fn execute_script(
       .map(|a| format!("\"{}\"", a.replace('"', "\\\"").replace('$', "\\$")))
       .collect::<Vec<_>>()
       .join(" ");
-    let script = format!("{} {}", script, additional_args);
-    let script = script.trim();
-    log::info!(
-      "{} {} {}",
-      colors::green("Task"),
-      colors::cyan(&task_name),
-      script,
-    );
-    let seq_list = deno_task_shell::parser::parse(script)
-      .with_context(|| format!("Error parsing script '{}'.", task_name))?;
-    let env_vars = std::env::vars().collect::<HashMap<String, String>>();
-    let exit_code = deno_task_shell::execute(seq_list, env_vars, cwd).await;
-    Ok(exit_code)
+    let stages = resolve_task_stages(&tasks_config, &task_name)?;
+    for stage in stages {
+      let scripts = stage.iter().map(|name| {
+        let script = &tasks_config[name].cmd;
+        // only the requested task receives the additional arguments
+        if *name == task_name {
+          format!("{} {}", script, additional_args).trim().to_string()
+        } else {
+          script.clone()
+        }
+      });
+      let exit_codes = if task_flags.parallel {
+        futures::future::try_join_all(stage.iter().zip(scripts).map(
+          |(name, script)| async move { run_task(name, &script, cwd).await },
+        ))
+        .await?
+      } else {
+        let mut exit_codes = Vec::new();
+        for (name, script) in stage.iter().zip(scripts) {
+          let exit_code = run_task(name, &script, cwd).await?;
+          exit_codes.push(exit_code);
+          if exit_code != 0 {
+            break;
+          }
+        }
+        exit_codes
+      };
+      if let Some(exit_code) = exit_codes.into_iter().find(|code| *code != 0) {
+        return Ok(exit_code);
+      }
+    }
+    Ok(0)
   } else {
     eprintln!("Task not found: {}", task_name);
     print_available_tasks(tasks_config);
@@ -108,6 +374,7 @@ pub async fn execute_script(
 mod test {
   use deno_ast::ModuleSpecifier;
   use pretty_assertions::assert_eq;
+  use test_util::TempDir;
 
   use super::*;
 
@@ -161,6 +428,157 @@ mod test {
     );
   }
 
+  #[test]
+  fn task_unknown_dependency() {
+    run_task_error_test(
+      r#"{
+        "tasks": {
+          "build": { "cmd": "deno run build.ts", "dependsOn": ["codegen"] }
+        }
+      }"#,
+      "Task \"build\" depends on unknown task \"codegen\"",
+    );
+  }
+
+  #[test]
+  fn task_stages() {
+    let tasks_config = tasks_config_from(
+      r#"{
+        "tasks": {
+          "build": { "cmd": "deno run build.ts", "dependsOn": ["codegen", "fmt"] },
+          "codegen": { "cmd": "deno run codegen.ts", "dependsOn": ["fetch"] },
+          "fetch": "deno cache deps.ts",
+          "fmt": "deno fmt",
+          "unrelated": "echo 1"
+        }
+      }"#,
+    );
+    assert_eq!(
+      resolve_task_stages(&tasks_config, "build").unwrap(),
+      vec![
+        vec!["fetch".to_string(), "fmt".to_string()],
+        vec!["codegen".to_string()],
+        vec!["build".to_string()],
+      ]
+    );
+    assert_eq!(
+      resolve_task_stages(&tasks_config, "fmt").unwrap(),
+      vec![vec!["fmt".to_string()]]
+    );
+  }
+
+  #[test]
+  fn task_stages_cycle() {
+    let tasks_config = tasks_config_from(
+      r#"{
+        "tasks": {
+          "a": { "cmd": "echo a", "dependsOn": ["b"] },
+          "b": { "cmd": "echo b", "dependsOn": ["c"] },
+          "c": { "cmd": "echo c", "dependsOn": ["b"] }
+        }
+      }"#,
+    );
+    assert_eq!(
+      resolve_task_stages(&tasks_config, "a")
+        .err()
+        .unwrap()
+        .to_string(),
+      "Task dependency cycle detected: b -> c -> b",
+    );
+  }
+
+  #[test]
+  fn expand_script_variables() {
+    let env_vars = HashMap::from([
+      ("SET".to_string(), "value".to_string()),
+      ("EMPTY".to_string(), "".to_string()),
+      ("QUOTED".to_string(), "a \"b\" $c".to_string()),
+    ]);
+    let cwd = Path::new("/deno");
+    let expand = |script: &str| expanded_args(script, &env_vars, cwd);
+    assert_eq!(expand("echo ${SET:-default}"), ["echo", "value"]);
+    assert_eq!(expand("echo ${EMPTY:-default}"), ["echo", "default"]);
+    assert_eq!(expand("echo ${EMPTY-default}"), ["echo", ""]);
+    assert_eq!(expand("echo ${UNSET-default}"), ["echo", "default"]);
+    assert_eq!(expand("echo \"out/${SET:-x}\""), ["echo", "out/value"]);
+    // the value is a single word that is not parsed again
+    assert_eq!(expand("echo ${QUOTED:-default}"), ["echo", "a \"b\" $c"]);
+    // not expanded in single quotes
+    assert_eq!(
+      expand("echo '${SET:-default}'"),
+      ["echo", "${SET:-default}"]
+    );
+  }
+
+  #[test]
+  fn expand_script_globs() {
+    let temp_dir = TempDir::new();
+    let cwd = fs_util::canonicalize_path(temp_dir.path()).unwrap();
+    std::fs::create_dir(cwd.join("sub")).unwrap();
+    std::fs::write(cwd.join("a.ts"), "").unwrap();
+    std::fs::write(cwd.join("b.ts"), "").unwrap();
+    std::fs::write(cwd.join("c.js"), "").unwrap();
+    std::fs::write(cwd.join("sub/d.ts"), "").unwrap();
+    let env_vars = HashMap::new();
+    let expand = |script: &str| expanded_args(script, &env_vars, &cwd);
+    assert_eq!(expand("deno fmt *.ts"), ["deno", "fmt", "a.ts", "b.ts"]);
+    assert_eq!(expand("deno fmt sub/*.ts"), ["deno", "fmt", "sub/d.ts"]);
+    assert_eq!(
+      expand("deno fmt **/*.ts"),
+      ["deno", "fmt", "a.ts", "b.ts", "sub/d.ts"]
+    );
+    // quoted and unmatched globs are left as is
+    assert_eq!(expand("echo \"*.ts\" '*.ts'"), ["echo", "*.ts", "*.ts"]);
+    assert_eq!(expand("echo *.md"), ["echo", "*.md"]);
+  }
+
+  /// Parses and expands a script made of a single simple command, getting
+  /// the text of its arguments.
+  fn expanded_args(
+    script: &str,
+    env_vars: &HashMap<String, String>,
+    cwd: &Path,
+  ) -> Vec<String> {
+    fn word_text(parts: &[WordPart], text: &mut String) {
+      for part in parts {
+        match part {
+          WordPart::Text(value) => text.push_str(value),
+          WordPart::Quoted(parts) => word_text(parts, text),
+          part => panic!("unexpected word part: {:?}", part),
+        }
+      }
+    }
+
+    let mut list = deno_task_shell::parser::parse(script).unwrap();
+    expand_list(&mut list, env_vars, cwd);
+    let simple = match &list.items[0].sequence {
+      Sequence::Pipeline(pipeline) => match &pipeline.inner {
+        PipelineInner::Command(Command {
+          inner: CommandInner::Simple(simple),
+          ..
+        }) => simple,
+        _ => unreachable!(),
+      },
+      _ => unreachable!(),
+    };
+    simple
+      .args
+      .iter()
+      .map(|arg| {
+        let mut text = String::new();
+        word_text(arg.parts(), &mut text);
+        text
+      })
+      .collect()
+  }
+
+  fn tasks_config_from(config_text: &str) -> BTreeMap<String, TaskDefinition> {
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    get_tasks_config(Some(&config_file)).unwrap()
+  }
+
   fn run_task_error_test(config_text: &str, expected_error: &str) {
     let config_dir = ModuleSpecifier::parse("file:///deno/").unwrap();
     let config_specifier = config_dir.join("tsconfig.json").unwrap();
@@ -12,6 +12,9 @@ use crate::file_watcher;
 use crate::file_watcher::ResolutionResult;
 use crate::flags::Flags;
 use crate::flags::TestFlags;
+use crate::flags::TestListFormat;
+use crate::flags::TestReporterConfig;
+use crate::flags::TestShard;
 use crate::flags::TypeCheckMode;
 use crate::fmt_errors::format_js_error;
 use crate::fs_util::collect_specifiers;
@@ -21,6 +24,8 @@ use crate::graph_util::contains_specifier;
 use crate::graph_util::graph_valid;
 use crate::located_script_name;
 use crate::lockfile;
+use crate::lsp::testing::TestCollector;
+use crate::lsp::testing::TestDefinition;
 use crate::ops;
 use crate::proc_state::ProcState;
 use crate::resolver::ImportMapResolver;
@@ -28,6 +33,7 @@ use crate::resolver::JsxResolver;
 use crate::tools::coverage::CoverageCollector;
 
 use deno_ast::swc::common::comments::CommentKind;
+use deno_ast::swc::visit::VisitWith;
 use deno_ast::MediaType;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
@@ -36,37 +42,50 @@ use deno_core::futures::future;
 use deno_core::futures::stream;
 use deno_core::futures::FutureExt;
 use deno_core::futures::StreamExt;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
 use deno_core::serde_json::json;
+use deno_core::serde_v8;
 use deno_core::url::Url;
+use deno_core::v8;
 use deno_core::ModuleSpecifier;
 use deno_graph::ModuleKind;
 use deno_runtime::ops::io::Stdio;
 use deno_runtime::ops::io::StdioPipe;
 use deno_runtime::permissions::Permissions;
 use deno_runtime::tokio_util::run_basic;
+use deno_runtime::worker::MainWorker;
 use log::Level;
 use rand::rngs::SmallRng;
 use rand::seq::SliceRandom;
 use rand::SeedableRng;
 use regex::Regex;
 use serde::Deserialize;
+use serde::Serialize;
+use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::fs;
 use std::io::Read;
 use std::io::Write;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
+use std::rc::Rc;
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 use tokio::sync::mpsc::unbounded_channel;
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
 
 /// The test mode is used to determine how a specifier is to be tested.
 #[derive(Debug, Clone, PartialEq)]
 pub enum TestMode {
-  /// Test as documentation, type-checking fenced code blocks.
+  /// Test as documentation, type-checking fenced code blocks and evaluating
+  /// those followed by an `output` fence.
   Documentation,
   /// Test as an executable module, loading the module into the isolate and running each test it
   /// defines.
@@ -75,7 +94,7 @@ pub enum TestMode {
   Both,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct TestDescription {
   pub origin: String,
@@ -89,15 +108,39 @@ pub enum TestOutput {
   Bytes(Vec<u8>),
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TestResult {
   Ok,
   Ignored,
-  Failed(Box<JsError>),
+  /// Passed after failing and being retried the given number of times.
+  Flaky(usize),
+  Failed(TestFailure),
+  /// Failed, but the test is quarantined so the run doesn't fail.
+  Quarantined(TestFailure),
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestFailure {
+  pub error: Box<JsError>,
+  /// Set when the test failed because a value didn't match what was
+  /// expected, e.g. a snapshot.
+  #[serde(default)]
+  pub diff: Option<TestFailureDiff>,
+  /// How many times the test was retried before failing for good.
+  #[serde(default)]
+  pub retries: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TestFailureDiff {
+  pub expected: String,
+  pub actual: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TestStepDescription {
   pub test: TestDescription,
@@ -105,7 +148,7 @@ pub struct TestStepDescription {
   pub name: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TestStepResult {
   Ok,
@@ -124,7 +167,7 @@ impl TestStepResult {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TestPlan {
   pub origin: String,
@@ -139,6 +182,9 @@ pub enum TestEvent {
   Plan(TestPlan),
   Wait(TestDescription),
   Output(Vec<u8>),
+  /// Output of the worker testing the module with the given origin, for
+  /// `--capture-output`.
+  CapturedOutput(String, Vec<u8>),
   Result(TestDescription, TestResult, u64),
   StepWait(TestStepDescription),
   StepResult(TestStepDescription, TestStepResult, u64),
@@ -150,23 +196,101 @@ pub struct TestSummary {
   pub passed: usize,
   pub failed: usize,
   pub ignored: usize,
+  pub flaky: usize,
   pub passed_steps: usize,
   pub failed_steps: usize,
   pub pending_steps: usize,
   pub ignored_steps: usize,
   pub filtered_out: usize,
   pub measured: usize,
-  pub failures: Vec<(TestDescription, Box<JsError>)>,
+  pub failures: Vec<(TestDescription, TestFailure)>,
+  /// Seed used to shuffle the order of test modules and tests, if any.
+  pub shuffle_seed: Option<u64>,
+  /// The slowest tests and steps over `--slow-threshold`, slowest first.
+  pub slowest: Vec<SlowTest>,
+  pub quarantined_passed: usize,
+  pub quarantined_failures: Vec<(TestDescription, TestFailure)>,
+}
+
+/// Number of slow tests and steps listed after a run.
+const SLOWEST_TESTS_COUNT: usize = 10;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowTest {
+  pub origin: String,
+  pub name: String,
+  pub elapsed: u64,
 }
 
+/// Selects the tests to run from a module by name.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum TestNameFilter {
+  /// Tests whose name contains the string, or matches it if it's a
+  /// `/regex/`.
+  Pattern(String),
+  /// Tests with one of the `include` names, if given, and none of the
+  /// `exclude` names.
+  Names {
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+  },
+}
+
+/// Options of a test run, see `run_test_modules`.
 #[derive(Debug, Clone, Deserialize)]
-struct TestSpecifierOptions {
-  compat_mode: bool,
-  concurrent_jobs: NonZeroUsize,
-  fail_fast: Option<NonZeroUsize>,
-  filter: Option<String>,
-  shuffle: Option<u64>,
-  trace_ops: bool,
+pub struct TestRunOptions {
+  pub compat_mode: bool,
+  pub concurrent_jobs: NonZeroUsize,
+  pub fail_fast: Option<NonZeroUsize>,
+  pub filter: Option<TestNameFilter>,
+  /// Filters for specific modules, used instead of `filter` for them.
+  pub module_filters: HashMap<ModuleSpecifier, TestNameFilter>,
+  pub shuffle: Option<u64>,
+  pub trace_ops: bool,
+  pub profile: Option<PathBuf>,
+  pub reporter: TestReporterConfig,
+  pub timeout: Option<u64>,
+  pub retries: usize,
+  pub update_snapshots: bool,
+  pub reuse_workers: bool,
+  pub slow_threshold: Option<u64>,
+  pub no_diff: bool,
+  pub quarantine: Vec<String>,
+  pub filter_tags: Vec<String>,
+  pub skip_tags: Vec<String>,
+  pub capture_output: bool,
+  pub verbose_output: bool,
+}
+
+impl TestRunOptions {
+  /// Options matching the given `deno test` flags. The quarantine, which comes
+  /// from the config file, is left empty.
+  pub fn new(flags: &Flags, test_flags: &TestFlags) -> Self {
+    Self {
+      compat_mode: flags.compat,
+      concurrent_jobs: test_flags.concurrent_jobs,
+      fail_fast: test_flags.fail_fast,
+      filter: test_flags.filter.clone().map(TestNameFilter::Pattern),
+      module_filters: HashMap::new(),
+      shuffle: test_flags.shuffle,
+      trace_ops: test_flags.trace_ops,
+      profile: test_flags.profile.clone(),
+      reporter: test_flags.reporter.clone(),
+      timeout: test_flags.timeout,
+      retries: test_flags.retries,
+      update_snapshots: test_flags.update_snapshots,
+      reuse_workers: test_flags.reuse_workers,
+      slow_threshold: test_flags.slow_threshold,
+      no_diff: test_flags.no_diff,
+      quarantine: Vec::new(),
+      filter_tags: test_flags.filter_tags.clone(),
+      skip_tags: test_flags.skip_tags.clone(),
+      capture_output: test_flags.capture_output,
+      verbose_output: test_flags.verbose_output,
+    }
+  }
 }
 
 impl TestSummary {
@@ -176,6 +300,7 @@ impl TestSummary {
       passed: 0,
       failed: 0,
       ignored: 0,
+      flaky: 0,
       passed_steps: 0,
       failed_steps: 0,
       pending_steps: 0,
@@ -183,6 +308,10 @@ impl TestSummary {
       filtered_out: 0,
       measured: 0,
       failures: Vec::new(),
+      shuffle_seed: None,
+      slowest: Vec::new(),
+      quarantined_passed: 0,
+      quarantined_failures: Vec::new(),
     }
   }
 
@@ -191,7 +320,46 @@ impl TestSummary {
   }
 
   fn has_pending(&self) -> bool {
-    self.total - self.passed - self.failed - self.ignored > 0
+    self.total
+      - self.passed
+      - self.flaky
+      - self.failed
+      - self.ignored
+      - self.quarantined_failures.len()
+      > 0
+  }
+}
+
+/// Matches the names of quarantined tests, given either verbatim or as a
+/// `/regex/`.
+struct TestQuarantine {
+  names: HashSet<String>,
+  patterns: Vec<Regex>,
+}
+
+impl TestQuarantine {
+  fn new(entries: &[String]) -> Result<Self, AnyError> {
+    let mut names = HashSet::new();
+    let mut patterns = Vec::new();
+    for entry in entries {
+      if entry.len() > 1 && entry.starts_with('/') && entry.ends_with('/') {
+        let pattern = &entry[1..entry.len() - 1];
+        patterns.push(Regex::new(pattern).map_err(|err| {
+          generic_error(format!(
+            "Invalid quarantine pattern \"{}\": {}",
+            entry, err
+          ))
+        })?);
+      } else {
+        names.insert(entry.clone());
+      }
+    }
+    Ok(Self { names, patterns })
+  }
+
+  fn contains(&self, name: &str) -> bool {
+    self.names.contains(name)
+      || self.patterns.iter().any(|pattern| pattern.is_match(name))
   }
 }
 
@@ -199,6 +367,15 @@ pub trait TestReporter {
   fn report_plan(&mut self, plan: &TestPlan);
   fn report_wait(&mut self, description: &TestDescription);
   fn report_output(&mut self, output: &[u8]);
+  /// Reports the output `--capture-output` captured while running a test,
+  /// right before its result.
+  fn report_captured_output(
+    &mut self,
+    _description: &TestDescription,
+    output: &[u8],
+  ) {
+    self.report_output(output);
+  }
   fn report_result(
     &mut self,
     description: &TestDescription,
@@ -213,6 +390,10 @@ pub trait TestReporter {
     elapsed: u64,
   );
   fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration);
+  /// Waits until the reporter is done with the reported events.
+  fn finish(&mut self) -> Result<(), AnyError> {
+    Ok(())
+  }
 }
 
 enum DeferredStepOutput {
@@ -220,10 +401,29 @@ enum DeferredStepOutput {
   StepResult(TestStepDescription, TestStepResult, u64),
 }
 
+pub(crate) fn to_relative_path_or_remote_url(
+  cwd: &Url,
+  path_or_url: &str,
+) -> String {
+  let url = Url::parse(path_or_url).unwrap();
+  if url.scheme() == "file" {
+    if let Some(mut r) = cwd.make_relative(&url) {
+      if !r.starts_with("../") {
+        r = format!("./{}", r);
+      }
+      return r;
+    }
+  }
+  path_or_url.to_string()
+}
+
 struct PrettyTestReporter {
   concurrent: bool,
   echo_output: bool,
+  slow_threshold: Option<u64>,
+  show_diff: bool,
   deferred_step_output: HashMap<TestDescription, Vec<DeferredStepOutput>>,
+  deferred_captured_output: HashMap<TestDescription, Vec<u8>>,
   in_test_count: usize,
   last_wait_output_level: usize,
   cwd: Url,
@@ -231,12 +431,20 @@ struct PrettyTestReporter {
 }
 
 impl PrettyTestReporter {
-  fn new(concurrent: bool, echo_output: bool) -> PrettyTestReporter {
+  fn new(
+    concurrent: bool,
+    echo_output: bool,
+    slow_threshold: Option<u64>,
+    show_diff: bool,
+  ) -> PrettyTestReporter {
     PrettyTestReporter {
       concurrent,
       echo_output,
+      slow_threshold,
+      show_diff,
       in_test_count: 0,
       deferred_step_output: HashMap::new(),
+      deferred_captured_output: HashMap::new(),
       last_wait_output_level: 0,
       cwd: Url::from_directory_path(std::env::current_dir().unwrap()).unwrap(),
       did_have_user_output: false,
@@ -251,16 +459,7 @@ impl PrettyTestReporter {
   }
 
   fn to_relative_path_or_remote_url(&self, path_or_url: &str) -> String {
-    let url = Url::parse(path_or_url).unwrap();
-    if url.scheme() == "file" {
-      if let Some(mut r) = self.cwd.make_relative(&url) {
-        if !r.starts_with("../") {
-          r = format!("./{}", r);
-        }
-        return r;
-      }
-    }
-    path_or_url.to_string()
+    to_relative_path_or_remote_url(&self.cwd, path_or_url)
   }
 
   fn force_report_step_wait(&mut self, description: &TestStepDescription) {
@@ -294,11 +493,7 @@ impl PrettyTestReporter {
       print!("{}", "  ".repeat(description.level));
     }
 
-    println!(
-      "{} {}",
-      status,
-      colors::gray(format!("({})", display::human_elapsed(elapsed.into())))
-    );
+    println!("{} {}", status, self.format_elapsed(elapsed));
 
     if let Some(js_error) = result.error() {
       let err_string = format_test_error(js_error);
@@ -308,6 +503,18 @@ impl PrettyTestReporter {
     }
   }
 
+  fn format_elapsed(&self, elapsed: u64) -> String {
+    let text =
+      colors::gray(format!("({})", display::human_elapsed(elapsed.into())))
+        .to_string();
+    match self.slow_threshold {
+      Some(threshold) if elapsed > threshold => {
+        format!("{} {}", text, colors::yellow("slow"))
+      }
+      _ => text,
+    }
+  }
+
   fn write_output_end(&mut self) -> bool {
     if self.did_have_user_output {
       println!("{}", colors::gray("----- output end -----"));
@@ -356,6 +563,22 @@ impl TestReporter for PrettyTestReporter {
     std::io::stdout().write_all(output).unwrap();
   }
 
+  fn report_captured_output(
+    &mut self,
+    description: &TestDescription,
+    output: &[u8],
+  ) {
+    // Concurrent tests only get their header once they're done, the output
+    // has to follow it.
+    if self.concurrent {
+      self
+        .deferred_captured_output
+        .insert(description.clone(), output.to_vec());
+    } else {
+      self.report_output(output);
+    }
+  }
+
   fn report_result(
     &mut self,
     description: &TestDescription,
@@ -367,6 +590,10 @@ impl TestReporter for PrettyTestReporter {
     if self.concurrent {
       self.force_report_wait(description);
 
+      if let Some(output) = self.deferred_captured_output.remove(description) {
+        self.report_output(&output);
+      }
+
       if let Some(step_outputs) = self.deferred_step_output.remove(description)
       {
         for step_output in step_outputs {
@@ -396,14 +623,19 @@ impl TestReporter for PrettyTestReporter {
     let status = match result {
       TestResult::Ok => colors::green("ok").to_string(),
       TestResult::Ignored => colors::yellow("ignored").to_string(),
-      TestResult::Failed(_) => colors::red("FAILED").to_string(),
+      TestResult::Flaky(retries) => {
+        colors::yellow(format!("flaky{}", retries_text(*retries))).to_string()
+      }
+      TestResult::Failed(failure) => {
+        colors::red(format!("FAILED{}", retries_text(failure.retries)))
+          .to_string()
+      }
+      TestResult::Quarantined(_) => {
+        colors::yellow("FAILED (quarantined)").to_string()
+      }
     };
 
-    println!(
-      "{} {}",
-      status,
-      colors::gray(format!("({})", display::human_elapsed(elapsed.into())))
-    );
+    println!("{} {}", status, self.format_elapsed(elapsed));
   }
 
   fn report_step_wait(&mut self, description: &TestStepDescription) {
@@ -440,9 +672,25 @@ impl TestReporter for PrettyTestReporter {
   }
 
   fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration) {
+    if !summary.quarantined_failures.is_empty() {
+      println!("\nquarantined failures:\n");
+      for (description, failure) in &summary.quarantined_failures {
+        println!(
+          "{} {} {}",
+          colors::gray(
+            self.to_relative_path_or_remote_url(&description.origin)
+          ),
+          colors::gray(">"),
+          description.name
+        );
+        println!("{}", format_test_failure(failure, self.show_diff));
+        println!();
+      }
+    }
+
     if !summary.failures.is_empty() {
       println!("\nfailures:\n");
-      for (description, js_error) in &summary.failures {
+      for (description, failure) in &summary.failures {
         println!(
           "{} {} {}",
           colors::gray(
@@ -451,7 +699,7 @@ impl TestReporter for PrettyTestReporter {
           colors::gray(">"),
           description.name
         );
-        println!("{}", format_test_error(js_error));
+        println!("{}", format_test_failure(failure, self.show_diff));
         println!();
       }
 
@@ -491,11 +739,17 @@ impl TestReporter for PrettyTestReporter {
         format!(" ({} steps)", count)
       }
     };
+    let flaky_text = if summary.flaky == 0 {
+      String::new()
+    } else {
+      format!(" {} flaky;", summary.flaky)
+    };
     println!(
-      "\ntest result: {}. {} passed{}; {} failed{}; {} ignored{}; {} measured; {} filtered out {}\n",
+      "\ntest result: {}. {} passed{};{} {} failed{}; {} ignored{}; {} measured; {} filtered out {}\n",
       status,
       summary.passed,
       get_steps_text(summary.passed_steps),
+      flaky_text,
       summary.failed,
       get_steps_text(summary.failed_steps + summary.pending_steps),
       summary.ignored,
@@ -505,214 +759,1138 @@ impl TestReporter for PrettyTestReporter {
       colors::gray(
         format!("({})", display::human_elapsed(elapsed.as_millis()))),
     );
+
+    let quarantined_total =
+      summary.quarantined_passed + summary.quarantined_failures.len();
+    if quarantined_total > 0 {
+      println!(
+        "quarantined: {} of {} passed ({}%)\n",
+        summary.quarantined_passed,
+        quarantined_total,
+        summary.quarantined_passed * 100 / quarantined_total,
+      );
+    }
+
+    if !summary.slowest.is_empty() {
+      println!("slowest tests:\n");
+      for slow_test in &summary.slowest {
+        println!(
+          "\t{} {} {} {}",
+          colors::gray(self.to_relative_path_or_remote_url(&slow_test.origin)),
+          colors::gray(">"),
+          slow_test.name,
+          colors::gray(format!(
+            "({})",
+            display::human_elapsed(slow_test.elapsed.into())
+          )),
+        );
+      }
+      println!();
+    }
+
+    if let Some(seed) = summary.shuffle_seed {
+      println!(
+        "{}\n",
+        colors::gray(format!(
+          "shuffle seed: {} (reproduce this order with --shuffle={})",
+          seed, seed
+        ))
+      );
+    }
   }
 }
 
-fn abbreviate_test_error(js_error: &JsError) -> JsError {
-  let mut js_error = js_error.clone();
-  let frames = std::mem::take(&mut js_error.frames);
+/// Tracks the names of the steps currently running in each test, so that
+/// reporters which flatten steps can name them after their ancestors (e.g.
+/// "test > step > nested step").
+#[derive(Default)]
+struct StepNames(HashMap<TestDescription, Vec<String>>);
+
+impl StepNames {
+  fn wait(&mut self, description: &TestStepDescription) {
+    let names = self
+      .0
+      .entry(description.test.clone())
+      .or_insert_with(Vec::new);
+    names.truncate(description.level.saturating_sub(1));
+    names.push(description.name.clone());
+  }
 
-  // check if there are any stack frames coming from user code
-  let should_filter = frames.iter().any(|f| {
-    if let Some(file_name) = &f.file_name {
-      !(file_name.starts_with("[deno:") || file_name.starts_with("deno:"))
-    } else {
-      true
+  fn full_name(&mut self, description: &TestStepDescription) -> String {
+    let mut name = description.test.name.clone();
+    if let Some(names) = self.0.get_mut(&description.test) {
+      names.truncate(description.level);
+      for ancestor in names.iter().take(description.level.saturating_sub(1)) {
+        name.push_str(" > ");
+        name.push_str(ancestor);
+      }
     }
-  });
-
-  if should_filter {
-    let mut frames = frames
-      .into_iter()
-      .rev()
-      .skip_while(|f| {
-        if let Some(file_name) = &f.file_name {
-          file_name.starts_with("[deno:") || file_name.starts_with("deno:")
-        } else {
-          false
-        }
-      })
-      .into_iter()
-      .collect::<Vec<_>>();
-    frames.reverse();
-    js_error.frames = frames;
-  } else {
-    js_error.frames = frames;
+    name.push_str(" > ");
+    name.push_str(&description.name);
+    name
   }
 
-  js_error.cause = js_error
-    .cause
-    .as_ref()
-    .map(|e| Box::new(abbreviate_test_error(e)));
-  js_error.aggregated = js_error
-    .aggregated
-    .as_ref()
-    .map(|es| es.iter().map(abbreviate_test_error).collect());
-  js_error
+  fn finish(&mut self, description: &TestDescription) {
+    self.0.remove(description);
+  }
 }
 
-// This function prettifies `JsError` and applies some changes specifically for
-// test runner purposes:
-//
-// - filter out stack frames:
-//   - if stack trace consists of mixed user and internal code, the frames
-//     below the first user code frame are filtered out
-//   - if stack trace consists only of internal code it is preserved as is
-pub fn format_test_error(js_error: &JsError) -> String {
-  let mut js_error = abbreviate_test_error(js_error);
-  js_error.exception_message = js_error
-    .exception_message
-    .trim_start_matches("Uncaught ")
-    .to_string();
-  format_js_error(&js_error)
+struct JunitTestCase {
+  name: String,
+  elapsed: u64,
+  status: JunitTestStatus,
 }
 
-fn create_reporter(
-  concurrent: bool,
-  echo_output: bool,
-) -> Box<dyn TestReporter + Send> {
-  Box::new(PrettyTestReporter::new(concurrent, echo_output))
+enum JunitTestStatus {
+  Ok,
+  Skipped,
+  Failed { message: String, details: String },
 }
 
-/// Test a single specifier as documentation containing test programs, an executable test module or
-/// both.
-async fn test_specifier(
-  ps: ProcState,
-  permissions: Permissions,
-  specifier: ModuleSpecifier,
-  mode: TestMode,
-  sender: TestEventSender,
-  options: TestSpecifierOptions,
-) -> Result<(), AnyError> {
-  let mut worker = create_main_worker(
-    &ps,
-    specifier.clone(),
-    permissions,
-    vec![ops::testing::init(sender.clone())],
-    Stdio {
-      stdin: StdioPipe::Inherit,
-      stdout: StdioPipe::File(sender.stdout()),
-      stderr: StdioPipe::File(sender.stderr()),
-    },
-  );
-
-  let mut maybe_coverage_collector = if let Some(ref coverage_dir) =
-    ps.coverage_dir
-  {
-    let session = worker.create_inspector_session().await;
-    let coverage_dir = PathBuf::from(coverage_dir);
-    let mut coverage_collector = CoverageCollector::new(coverage_dir, session);
-    worker
-      .with_event_loop(coverage_collector.start_collecting().boxed_local())
-      .await?;
+impl JunitTestStatus {
+  fn from_error(js_error: &JsError) -> Self {
+    JunitTestStatus::Failed {
+      message: js_error
+        .exception_message
+        .trim_start_matches("Uncaught ")
+        .to_string(),
+      details: js_error
+        .stack
+        .clone()
+        .unwrap_or_else(|| js_error.exception_message.clone()),
+    }
+  }
+}
 
-    Some(coverage_collector)
-  } else {
-    None
-  };
+/// Collects results and writes them as JUnit XML once the run is over, with
+/// one `<testsuite>` per module and one `<testcase>` per test or step.
+struct JunitTestReporter {
+  path: Option<PathBuf>,
+  suites: Vec<(String, Vec<JunitTestCase>)>,
+  step_names: StepNames,
+}
 
-  // Enable op call tracing in core to enable better debugging of op sanitizer
-  // failures.
-  if options.trace_ops {
-    worker
-      .execute_script(
-        &located_script_name!(),
-        "Deno.core.enableOpCallTracing();",
-      )
-      .unwrap();
+impl JunitTestReporter {
+  fn new(path: Option<PathBuf>) -> JunitTestReporter {
+    JunitTestReporter {
+      path,
+      suites: Vec::new(),
+      step_names: StepNames::default(),
+    }
   }
 
-  // We only execute the specifier as a module if it is tagged with TestMode::Module or
-  // TestMode::Both.
-  if mode != TestMode::Documentation {
-    if options.compat_mode {
-      worker.execute_side_module(&compat::GLOBAL_URL).await?;
-      worker.execute_side_module(&compat::MODULE_URL).await?;
-
-      let use_esm_loader = compat::check_if_should_use_esm_loader(&specifier)?;
+  fn add_case(&mut self, origin: &str, case: JunitTestCase) {
+    match self.suites.iter_mut().find(|(o, _)| o == origin) {
+      Some((_, cases)) => cases.push(case),
+      None => self.suites.push((origin.to_string(), vec![case])),
+    }
+  }
 
-      if use_esm_loader {
-        worker.execute_side_module(&specifier).await?;
-      } else {
-        compat::load_cjs_module(
-          &mut worker.js_runtime,
-          &specifier.to_file_path().unwrap().display().to_string(),
-          false,
-        )?;
-        worker.run_event_loop(false).await?;
+  fn to_xml(&self, elapsed: &Duration) -> String {
+    let count_failures = |cases: &[JunitTestCase]| {
+      cases
+        .iter()
+        .filter(|c| matches!(c.status, JunitTestStatus::Failed { .. }))
+        .count()
+    };
+    let total_tests: usize = self.suites.iter().map(|(_, c)| c.len()).sum();
+    let total_failures: usize =
+      self.suites.iter().map(|(_, c)| count_failures(c)).sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+      "<testsuites name=\"deno test\" tests=\"{}\" failures=\"{}\" time=\"{}\">\n",
+      total_tests,
+      total_failures,
+      format_junit_time(elapsed.as_millis() as u64),
+    ));
+    for (origin, cases) in &self.suites {
+      let skipped = cases
+        .iter()
+        .filter(|c| matches!(c.status, JunitTestStatus::Skipped))
+        .count();
+      let time = cases.iter().map(|c| c.elapsed).sum();
+      xml.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+        escape_xml(origin),
+        cases.len(),
+        count_failures(cases),
+        skipped,
+        format_junit_time(time),
+      ));
+      for case in cases {
+        xml.push_str(&format!(
+          "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+          escape_xml(&case.name),
+          escape_xml(origin),
+          format_junit_time(case.elapsed),
+        ));
+        match &case.status {
+          JunitTestStatus::Ok => xml.push_str("/>\n"),
+          JunitTestStatus::Skipped => {
+            xml.push_str(">\n      <skipped/>\n    </testcase>\n")
+          }
+          JunitTestStatus::Failed { message, details } => {
+            xml.push_str(&format!(
+              ">\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+              escape_xml(message),
+              escape_xml(details),
+            ));
+          }
+        }
       }
-    } else {
-      // We execute the module module as a side module so that import.meta.main is not set.
-      worker.execute_side_module(&specifier).await?;
+      xml.push_str("  </testsuite>\n");
     }
+    xml.push_str("</testsuites>\n");
+    xml
   }
+}
 
-  worker.dispatch_load_event(&located_script_name!())?;
+impl TestReporter for JunitTestReporter {
+  fn report_plan(&mut self, _plan: &TestPlan) {}
 
-  let test_result = worker.js_runtime.execute_script(
-    &located_script_name!(),
-    &format!(
-      r#"Deno[Deno.internal].runTests({})"#,
-      json!({
-        "filter": options.filter,
-        "shuffle": options.shuffle,
-      }),
-    ),
-  )?;
+  fn report_wait(&mut self, _description: &TestDescription) {}
 
-  worker.js_runtime.resolve_value(test_result).await?;
+  // Output can't be attributed to a single test when tests run concurrently,
+  // so it is left out of the report.
+  fn report_output(&mut self, _output: &[u8]) {}
 
-  worker.dispatch_unload_event(&located_script_name!())?;
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    self.step_names.finish(description);
+    let status = match result {
+      TestResult::Ok | TestResult::Flaky(_) => JunitTestStatus::Ok,
+      TestResult::Ignored => JunitTestStatus::Skipped,
+      TestResult::Failed(failure) => {
+        JunitTestStatus::from_error(&failure.error)
+      }
+      // Reported as skipped so that CI dashboards don't fail the build on it.
+      TestResult::Quarantined(_) => JunitTestStatus::Skipped,
+    };
+    self.add_case(
+      &description.origin,
+      JunitTestCase {
+        name: description.name.clone(),
+        elapsed,
+        status,
+      },
+    );
+  }
 
-  if let Some(coverage_collector) = maybe_coverage_collector.as_mut() {
-    worker
-      .with_event_loop(coverage_collector.stop_collecting().boxed_local())
-      .await?;
+  fn report_step_wait(&mut self, description: &TestStepDescription) {
+    self.step_names.wait(description);
   }
 
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+  ) {
+    let name = self.step_names.full_name(description);
+    let status = match result {
+      TestStepResult::Ok => JunitTestStatus::Ok,
+      TestStepResult::Ignored => JunitTestStatus::Skipped,
+      TestStepResult::Failed(Some(js_error))
+      | TestStepResult::Pending(Some(js_error)) => {
+        JunitTestStatus::from_error(js_error)
+      }
+      TestStepResult::Failed(None) => JunitTestStatus::Failed {
+        message: "Step failed".to_string(),
+        details: String::new(),
+      },
+      TestStepResult::Pending(None) => JunitTestStatus::Failed {
+        message: "Step did not complete".to_string(),
+        details: String::new(),
+      },
+    };
+    self.add_case(
+      &description.test.origin,
+      JunitTestCase {
+        name,
+        elapsed,
+        status,
+      },
+    );
+  }
+
+  fn report_summary(&mut self, _summary: &TestSummary, elapsed: &Duration) {
+    let xml = self.to_xml(elapsed);
+    match &self.path {
+      Some(path) => {
+        if let Err(err) = fs::write(path, xml) {
+          eprintln!(
+            "{} Failed to write JUnit report to {}: {}",
+            colors::red_bold("error:"),
+            path.display(),
+            err
+          );
+        }
+      }
+      None => print!("{}", xml),
+    }
+  }
+}
+
+/// Streams results as TAP version 13, flattening steps into their own test
+/// points and describing failures in YAML diagnostic blocks.
+struct TapTestReporter {
+  count: usize,
+  step_names: StepNames,
+}
+
+impl TapTestReporter {
+  fn new() -> TapTestReporter {
+    println!("TAP version 13");
+    TapTestReporter {
+      count: 0,
+      step_names: StepNames::default(),
+    }
+  }
+
+  fn print_test_point(
+    &mut self,
+    name: &str,
+    ok: bool,
+    directive: Option<&str>,
+    elapsed: u64,
+  ) {
+    self.count += 1;
+    let mut line = format!(
+      "{} {} - {}",
+      if ok { "ok" } else { "not ok" },
+      self.count,
+      escape_tap_description(name)
+    );
+    if let Some(directive) = directive {
+      line.push_str(" # ");
+      line.push_str(directive);
+    }
+    println!("{}", line);
+    println!("  ---");
+    println!("  duration_ms: {}", elapsed);
+  }
+
+  fn print_failure_diagnostics(
+    &self,
+    origin: &str,
+    js_error: Option<&JsError>,
+  ) {
+    match js_error {
+      Some(js_error) => {
+        println!(
+          "  message: {}",
+          serde_json::to_string(
+            js_error.exception_message.trim_start_matches("Uncaught ")
+          )
+          .unwrap()
+        );
+        println!("  severity: fail");
+        let frame = js_error.frames.iter().find(|f| {
+          f.file_name
+            .as_ref()
+            .map(|n| !(n.starts_with("[deno:") || n.starts_with("deno:")))
+            .unwrap_or(false)
+        });
+        println!("  at:");
+        match frame {
+          Some(frame) => {
+            println!(
+              "    file: {}",
+              serde_json::to_string(frame.file_name.as_ref().unwrap()).unwrap()
+            );
+            if let Some(line_number) = frame.line_number {
+              println!("    line: {}", line_number);
+            }
+            if let Some(column_number) = frame.column_number {
+              println!("    column: {}", column_number);
+            }
+          }
+          None => {
+            println!("    file: {}", serde_json::to_string(origin).unwrap())
+          }
+        }
+        if let Some(stack) = &js_error.stack {
+          print_tap_yaml_block("stack", stack);
+        }
+      }
+      None => println!("  severity: fail"),
+    }
+  }
+}
+
+impl TestReporter for TapTestReporter {
+  fn report_plan(&mut self, plan: &TestPlan) {
+    println!(
+      "# {} {} from {}",
+      plan.total,
+      if plan.total == 1 { "test" } else { "tests" },
+      plan.origin
+    );
+  }
+
+  fn report_wait(&mut self, _description: &TestDescription) {}
+
+  fn report_output(&mut self, output: &[u8]) {
+    // Anything that isn't a TAP line must be a comment to keep the stream
+    // parseable.
+    for line in String::from_utf8_lossy(output).lines() {
+      println!("# {}", line);
+    }
+  }
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    self.step_names.finish(description);
+    match result {
+      TestResult::Ok => {
+        self.print_test_point(&description.name, true, None, elapsed)
+      }
+      TestResult::Ignored => {
+        self.print_test_point(&description.name, true, Some("SKIP"), elapsed)
+      }
+      TestResult::Flaky(retries) => {
+        self.print_test_point(&description.name, true, None, elapsed);
+        println!("  flaky: true");
+        println!("  retries: {}", retries);
+      }
+      TestResult::Failed(failure) => {
+        self.print_test_point(&description.name, false, None, elapsed);
+        self
+          .print_failure_diagnostics(&description.origin, Some(&failure.error));
+        if let Some(diff) = &failure.diff {
+          print_tap_yaml_block("expected", &diff.expected);
+          print_tap_yaml_block("actual", &diff.actual);
+        }
+      }
+      TestResult::Quarantined(failure) => {
+        // Failing TODO tests don't count as failures for TAP consumers.
+        self.print_test_point(
+          &description.name,
+          false,
+          Some("TODO quarantined"),
+          elapsed,
+        );
+        self
+          .print_failure_diagnostics(&description.origin, Some(&failure.error));
+      }
+    }
+    println!("  ...");
+  }
+
+  fn report_step_wait(&mut self, description: &TestStepDescription) {
+    self.step_names.wait(description);
+  }
+
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+  ) {
+    let name = self.step_names.full_name(description);
+    match result {
+      TestStepResult::Ok => self.print_test_point(&name, true, None, elapsed),
+      TestStepResult::Ignored => {
+        self.print_test_point(&name, true, Some("SKIP"), elapsed)
+      }
+      TestStepResult::Failed(_) | TestStepResult::Pending(_) => {
+        self.print_test_point(&name, false, None, elapsed);
+        self
+          .print_failure_diagnostics(&description.test.origin, result.error());
+      }
+    }
+    println!("  ...");
+  }
+
+  fn report_summary(&mut self, summary: &TestSummary, _elapsed: &Duration) {
+    println!("1..{}", self.count);
+    println!(
+      "# pass {}",
+      summary.passed + summary.flaky + summary.passed_steps
+    );
+    println!(
+      "# fail {}",
+      summary.failed + summary.failed_steps + summary.pending_steps
+    );
+    println!("# skip {}", summary.ignored + summary.ignored_steps);
+    if !summary.quarantined_failures.is_empty() {
+      println!("# todo {}", summary.quarantined_failures.len());
+    }
+    if let Some(seed) = summary.shuffle_seed {
+      println!("# shuffle seed {}", seed);
+    }
+  }
+}
+
+/// Emits every test event as a line of JSON on stdout, for tools that want
+/// structured results rather than parsing the pretty output, or passes the
+/// events to a reporter module.
+struct JsonTestReporter {
+  maybe_module: Option<ReporterModule>,
+}
+
+impl JsonTestReporter {
+  fn new(maybe_module: Option<ReporterModule>) -> Self {
+    Self { maybe_module }
+  }
+
+  fn emit(&self, event: serde_json::Value) {
+    if let Some(module) = &self.maybe_module {
+      module.send(event);
+      return;
+    }
+    let mut stdout = std::io::stdout();
+    serde_json::to_writer(&mut stdout, &event).unwrap();
+    stdout.write_all(b"\n").unwrap();
+    stdout.flush().unwrap();
+  }
+}
+
+impl TestReporter for JsonTestReporter {
+  fn report_plan(&mut self, plan: &TestPlan) {
+    self.emit(json!({ "type": "plan", "plan": plan }));
+  }
+
+  fn report_wait(&mut self, description: &TestDescription) {
+    self.emit(json!({ "type": "wait", "description": description }));
+  }
+
+  fn report_output(&mut self, output: &[u8]) {
+    self.emit(json!({
+      "type": "output",
+      "output": String::from_utf8_lossy(output),
+    }));
+  }
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    self.emit(json!({
+      "type": "result",
+      "description": description,
+      "result": result,
+      "elapsed": elapsed,
+    }));
+  }
+
+  fn report_step_wait(&mut self, description: &TestStepDescription) {
+    self.emit(json!({ "type": "stepWait", "description": description }));
+  }
+
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+  ) {
+    self.emit(json!({
+      "type": "stepResult",
+      "description": description,
+      "result": result,
+      "elapsed": elapsed,
+    }));
+  }
+
+  fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration) {
+    let failures = summary
+      .failures
+      .iter()
+      .map(|(description, failure)| {
+        json!({
+          "description": description,
+          "error": failure.error,
+          "diff": failure.diff,
+        })
+      })
+      .collect::<Vec<_>>();
+    self.emit(json!({
+      "type": "summary",
+      "summary": {
+        "total": summary.total,
+        "passed": summary.passed,
+        "failed": summary.failed,
+        "ignored": summary.ignored,
+        "flaky": summary.flaky,
+        "passedSteps": summary.passed_steps,
+        "failedSteps": summary.failed_steps,
+        "pendingSteps": summary.pending_steps,
+        "ignoredSteps": summary.ignored_steps,
+        "filteredOut": summary.filtered_out,
+        "measured": summary.measured,
+        "failures": failures,
+        "shuffleSeed": summary.shuffle_seed,
+        "slowest": summary.slowest,
+        "quarantinedPassed": summary.quarantined_passed,
+        "quarantinedFailed": summary.quarantined_failures.len(),
+      },
+      "elapsed": elapsed.as_millis() as u64,
+    }));
+  }
+
+  fn finish(&mut self) -> Result<(), AnyError> {
+    match self.maybe_module.take() {
+      Some(module) => module.finish(),
+      None => Ok(()),
+    }
+  }
+}
+
+/// Prints a multi-line string as a literal block of a YAML diagnostic.
+fn print_tap_yaml_block(key: &str, text: &str) {
+  println!("  {}: |-", key);
+  for line in text.lines() {
+    println!("    {}", line);
+  }
+}
+
+/// Escapes characters that have a meaning in a TAP test point description.
+fn escape_tap_description(text: &str) -> String {
+  text
+    .replace('\\', "\\\\")
+    .replace('#', "\\#")
+    .replace('\n', " ")
+}
+
+/// Describes how often a test was retried, for the status of its result.
+fn retries_text(retries: usize) -> String {
+  match retries {
+    0 => String::new(),
+    1 => " (1 retry)".to_string(),
+    n => format!(" ({} retries)", n),
+  }
+}
+
+fn format_junit_time(elapsed_ms: u64) -> String {
+  format!("{:.3}", elapsed_ms as f64 / 1000.0)
+}
+
+fn escape_xml(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&apos;"),
+      // Strip control characters that aren't allowed in XML 1.0, such as
+      // ANSI escape codes.
+      c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+fn abbreviate_test_error(js_error: &JsError) -> JsError {
+  let mut js_error = js_error.clone();
+  let frames = std::mem::take(&mut js_error.frames);
+
+  // check if there are any stack frames coming from user code
+  let should_filter = frames.iter().any(|f| {
+    if let Some(file_name) = &f.file_name {
+      !(file_name.starts_with("[deno:") || file_name.starts_with("deno:"))
+    } else {
+      true
+    }
+  });
+
+  if should_filter {
+    let mut frames = frames
+      .into_iter()
+      .rev()
+      .skip_while(|f| {
+        if let Some(file_name) = &f.file_name {
+          file_name.starts_with("[deno:") || file_name.starts_with("deno:")
+        } else {
+          false
+        }
+      })
+      .into_iter()
+      .collect::<Vec<_>>();
+    frames.reverse();
+    js_error.frames = frames;
+  } else {
+    js_error.frames = frames;
+  }
+
+  js_error.cause = js_error
+    .cause
+    .as_ref()
+    .map(|e| Box::new(abbreviate_test_error(e)));
+  js_error.aggregated = js_error
+    .aggregated
+    .as_ref()
+    .map(|es| es.iter().map(abbreviate_test_error).collect());
+  js_error
+}
+
+// This function prettifies `JsError` and applies some changes specifically for
+// test runner purposes:
+//
+// - filter out stack frames:
+//   - if stack trace consists of mixed user and internal code, the frames
+//     below the first user code frame are filtered out
+//   - if stack trace consists only of internal code it is preserved as is
+pub fn format_test_error(js_error: &JsError) -> String {
+  let mut js_error = abbreviate_test_error(js_error);
+  js_error.exception_message = js_error
+    .exception_message
+    .trim_start_matches("Uncaught ")
+    .to_string();
+  format_js_error(&js_error)
+}
+
+/// Formats a test failure. If the failure carries the expected and actual
+/// values and `show_diff` is set, only the first line of the error message is
+/// kept and a diff of the values is shown after the stack, since assertion
+/// messages usually contain their own, less readable, rendering of them.
+pub fn format_test_failure(failure: &TestFailure, show_diff: bool) -> String {
+  match &failure.diff {
+    Some(diff) if show_diff => {
+      let mut js_error = (*failure.error).clone();
+      if let Some(first_line) = js_error.exception_message.lines().next() {
+        js_error.exception_message = first_line.to_string();
+      }
+      format!(
+        "{}\n\n{}{}\n{}",
+        format_test_error(&js_error),
+        colors::red("- expected"),
+        colors::green(" + actual"),
+        crate::diff::diff(&diff.expected, &diff.actual).trim_end(),
+      )
+    }
+    _ => format_test_error(&failure.error),
+  }
+}
+
+fn create_reporter(
+  reporter: &TestReporterConfig,
+  concurrent: bool,
+  echo_output: bool,
+  slow_threshold: Option<u64>,
+  show_diff: bool,
+  maybe_reporter_module: Option<ReporterModule>,
+) -> Box<dyn TestReporter + Send> {
+  match reporter {
+    TestReporterConfig::Pretty => Box::new(PrettyTestReporter::new(
+      concurrent,
+      echo_output,
+      slow_threshold,
+      show_diff,
+    )),
+    TestReporterConfig::Junit(path) => {
+      Box::new(JunitTestReporter::new(path.clone()))
+    }
+    TestReporterConfig::Tap => Box::new(TapTestReporter::new()),
+    TestReporterConfig::Json | TestReporterConfig::Module(_) => {
+      Box::new(JsonTestReporter::new(maybe_reporter_module))
+    }
+  }
+}
+
+/// Name of the `.cpuprofile` file written for `specifier` by `--profile`.
+fn profile_file_name(specifier: &ModuleSpecifier) -> String {
+  let name: String = specifier
+    .as_str()
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+        c
+      } else {
+        '_'
+      }
+    })
+    .collect();
+  format!("{}.cpuprofile", name)
+}
+
+/// Lets `--fail-fast` stop the test modules that are still running or queued
+/// rather than waiting for them to finish.
+#[derive(Clone, Default)]
+pub struct TestCancellation {
+  token: CancellationToken,
+  isolates: Arc<Mutex<Vec<v8::IsolateHandle>>>,
+}
+
+impl TestCancellation {
+  /// Creates a cancellation that is also triggered by cancelling `token`,
+  /// though only `cancel` terminates the tests that are already running.
+  pub fn new(token: CancellationToken) -> Self {
+    Self {
+      token,
+      isolates: Default::default(),
+    }
+  }
+
+  pub fn is_cancelled(&self) -> bool {
+    self.token.is_cancelled()
+  }
+
+  /// Makes `cancel` terminate the JS running in `worker`.
+  fn register(&self, worker: &mut MainWorker) {
+    let isolate = worker.js_runtime.v8_isolate().thread_safe_handle();
+    self.isolates.lock().push(isolate.clone());
+    // The worker may have been created after `cancel` went over the isolates.
+    if self.is_cancelled() {
+      isolate.terminate_execution();
+    }
+  }
+
+  pub fn cancel(&self) {
+    self.token.cancel();
+    for isolate in self.isolates.lock().drain(..) {
+      isolate.terminate_execution();
+    }
+  }
+}
+
+/// Runs the tests registered by the modules loaded into `worker`.
+async fn run_loaded_tests(
+  worker: &mut MainWorker,
+  specifier: &ModuleSpecifier,
+  options: &TestRunOptions,
+) -> Result<(), AnyError> {
+  let filter = options
+    .module_filters
+    .get(specifier)
+    .or(options.filter.as_ref());
+  worker.dispatch_load_event(&located_script_name!())?;
+
+  let test_result = worker.js_runtime.execute_script(
+    &located_script_name!(),
+    &format!(
+      r#"Deno[Deno.internal].runTests({})"#,
+      json!({
+        "filter": filter,
+        "shuffle": options.shuffle,
+        "timeout": options.timeout,
+        "retries": options.retries,
+        "filterTags": options.filter_tags,
+        "skipTags": options.skip_tags,
+      }),
+    ),
+  )?;
+
+  worker.js_runtime.resolve_value(test_result).await?;
+
+  worker.dispatch_unload_event(&located_script_name!())?;
+
   Ok(())
 }
 
-fn extract_files_from_regex_blocks(
+/// Tests the modules taken from `queue` one after the other in a single
+/// worker, for `--reuse-workers`. Modules share the worker's globals and
+/// module instances, but only pay for creating a worker once.
+async fn test_specifiers_in_worker(
+  ps: ProcState,
+  permissions: Permissions,
+  queue: Arc<Mutex<VecDeque<(ModuleSpecifier, TestMode)>>>,
+  sender: TestEventSender,
+  cancellation: TestCancellation,
+  options: TestRunOptions,
+) -> Result<(), AnyError> {
+  let main_module = match queue.lock().front() {
+    Some((specifier, _)) if !cancellation.is_cancelled() => specifier.clone(),
+    _ => return Ok(()),
+  };
+  let mut worker = create_main_worker(
+    &ps,
+    main_module,
+    permissions,
+    vec![ops::testing::init(sender.clone(), options.update_snapshots)],
+    Stdio {
+      stdin: StdioPipe::Inherit,
+      stdout: StdioPipe::File(sender.stdout()),
+      stderr: StdioPipe::File(sender.stderr()),
+    },
+  );
+  cancellation.register(&mut worker);
+
+  if options.trace_ops {
+    worker
+      .execute_script(
+        &located_script_name!(),
+        "Deno.core.enableOpCallTracing();",
+      )
+      .unwrap();
+  }
+
+  // A module that fails to load or run must not keep the modules after it
+  // from being tested, so the first error is only returned once the queue
+  // is drained.
+  let mut first_error = None;
+  loop {
+    if cancellation.is_cancelled() {
+      break;
+    }
+    let (specifier, mode) = match queue.lock().pop_front() {
+      Some(next) => next,
+      None => break,
+    };
+
+    let result =
+      test_specifier_in_worker(&ps, &mut worker, &specifier, mode, &options)
+        .await;
+    let reset_result = worker.execute_script(
+      &located_script_name!(),
+      "Deno[Deno.internal].resetTests();",
+    );
+    if let Err(err) = result.and(reset_result) {
+      first_error.get_or_insert(err);
+    }
+  }
+
+  match first_error {
+    Some(err) => Err(err),
+    None => Ok(()),
+  }
+}
+
+/// Loads and tests a single module in a worker shared with other modules.
+async fn test_specifier_in_worker(
+  ps: &ProcState,
+  worker: &mut MainWorker,
+  specifier: &ModuleSpecifier,
+  mode: TestMode,
+  options: &TestRunOptions,
+) -> Result<(), AnyError> {
+  // The test origin and `Deno.mainModule` are read from the op state.
+  worker
+    .js_runtime
+    .op_state()
+    .borrow_mut()
+    .put::<ModuleSpecifier>(specifier.clone());
+
+  if mode != TestMode::Documentation {
+    worker.execute_side_module(specifier).await?;
+  }
+
+  if mode != TestMode::Executable {
+    for test_specifier in fetch_doc_test_specifiers(ps, specifier).await? {
+      worker.execute_side_module(&test_specifier).await?;
+    }
+  }
+
+  run_loaded_tests(worker, specifier, options).await
+}
+
+/// Test a single specifier as documentation containing test programs, an executable test module or
+/// both.
+async fn test_specifier(
+  ps: ProcState,
+  permissions: Permissions,
+  specifier: ModuleSpecifier,
+  mode: TestMode,
+  sender: TestEventSender,
+  cancellation: TestCancellation,
+  options: TestRunOptions,
+) -> Result<(), AnyError> {
+  if cancellation.is_cancelled() {
+    return Ok(());
+  }
+
+  let mut worker = create_main_worker(
+    &ps,
+    specifier.clone(),
+    permissions,
+    vec![ops::testing::init(sender.clone(), options.update_snapshots)],
+    Stdio {
+      stdin: StdioPipe::Inherit,
+      stdout: StdioPipe::File(sender.stdout()),
+      stderr: StdioPipe::File(sender.stderr()),
+    },
+  );
+  cancellation.register(&mut worker);
+
+  let mut maybe_coverage_collector =
+    if let Some(ref coverage_dir) = ps.coverage_dir {
+      let coverage_dir = PathBuf::from(coverage_dir);
+      let mut coverage_collector = CoverageCollector::new(coverage_dir);
+      coverage_collector
+        .start_collecting(&mut worker.js_runtime)
+        .await?;
+
+      Some(coverage_collector)
+    } else {
+      None
+    };
+
+  // Enable op call tracing in core to enable better debugging of op sanitizer
+  // failures.
+  if options.trace_ops {
+    worker
+      .execute_script(
+        &located_script_name!(),
+        "Deno.core.enableOpCallTracing();",
+      )
+      .unwrap();
+  }
+
+  if options.profile.is_some() {
+    let promise = worker.js_runtime.execute_script(
+      &located_script_name!(),
+      &format!(
+        "Deno.core.opAsync(\"op_profile_start\", {})",
+        json!(specifier.as_str())
+      ),
+    )?;
+    worker.js_runtime.resolve_value(promise).await?;
+  }
+
+  // We only execute the specifier as a module if it is tagged with TestMode::Module or
+  // TestMode::Both.
+  if mode != TestMode::Documentation {
+    if options.compat_mode {
+      worker.execute_side_module(&compat::GLOBAL_URL).await?;
+      worker.execute_side_module(&compat::MODULE_URL).await?;
+
+      let use_esm_loader = compat::check_if_should_use_esm_loader(&specifier)?;
+
+      if use_esm_loader {
+        worker.execute_side_module(&specifier).await?;
+      } else {
+        compat::load_cjs_module(
+          &mut worker.js_runtime,
+          &specifier.to_file_path().unwrap().display().to_string(),
+          false,
+        )?;
+        worker.run_event_loop(false).await?;
+      }
+    } else {
+      // We execute the module module as a side module so that import.meta.main is not set.
+      worker.execute_side_module(&specifier).await?;
+    }
+  }
+
+  // Examples with an expected output are evaluated by test modules of their
+  // own.
+  if mode != TestMode::Executable {
+    for test_specifier in fetch_doc_test_specifiers(&ps, &specifier).await? {
+      worker.execute_side_module(&test_specifier).await?;
+    }
+  }
+
+  run_loaded_tests(&mut worker, &specifier, &options).await?;
+
+  if let Some(profile_dir) = &options.profile {
+    let promise = worker.js_runtime.execute_script(
+      &located_script_name!(),
+      "Deno.core.opAsync(\"op_profile_stop\")",
+    )?;
+    let profile = worker.js_runtime.resolve_value(promise).await?;
+    let profile: serde_json::Value = {
+      let scope = &mut worker.js_runtime.handle_scope();
+      let profile = v8::Local::new(scope, profile);
+      serde_v8::from_v8(scope, profile)?
+    };
+    fs::create_dir_all(profile_dir)?;
+    fs::write(
+      profile_dir.join(profile_file_name(&specifier)),
+      serde_json::to_string(&profile)?,
+    )?;
+  }
+
+  if let Some(coverage_collector) = maybe_coverage_collector.as_mut() {
+    coverage_collector
+      .stop_collecting(&mut worker.js_runtime)
+      .await?;
+  }
+
+  Ok(())
+}
+
+/// A code example extracted from a fenced block of documentation.
+struct DocExample {
+  /// The example as a module of its own, which is type checked.
+  file: File,
+  /// The contents of an `output` fence directly following the example, which
+  /// the console output of the example is asserted against. Examples with the
+  /// `no-eval` attribute are never evaluated, and don't have one.
+  expected_output: Option<String>,
+}
+
+impl DocExample {
+  /// Creates a test module evaluating the example, if it has an expected
+  /// output to assert.
+  fn test_file(&self) -> Option<File> {
+    let expected_output = self.expected_output.as_ref()?;
+    let name = self
+      .file
+      .specifier
+      .path_segments()
+      .and_then(|segments| segments.last())
+      .unwrap_or_else(|| self.file.specifier.as_str());
+    let specifier =
+      ModuleSpecifier::parse(&format!("{}$deno-test.js", self.file.specifier))
+        .unwrap();
+
+    Some(File {
+      local: specifier.to_file_path().unwrap(),
+      maybe_types: None,
+      media_type: MediaType::JavaScript,
+      source: Arc::new(format!(
+        "Deno.test({}, () => Deno[Deno.internal].assertDocOutput(() => import({}), {}));\n",
+        json!(name),
+        json!(self.file.specifier.as_str()),
+        json!(expected_output),
+      )),
+      specifier,
+      maybe_headers: None,
+    })
+  }
+}
+
+fn extract_examples_from_regex_blocks(
   specifier: &ModuleSpecifier,
   source: &str,
   media_type: MediaType,
   file_line_index: usize,
   blocks_regex: &Regex,
   lines_regex: &Regex,
-) -> Result<Vec<File>, AnyError> {
-  let files = blocks_regex
-    .captures_iter(source)
-    .filter_map(|block| {
+  output_lines_regex: &Regex,
+) -> Result<Vec<DocExample>, AnyError> {
+  let blocks: Vec<_> = blocks_regex.captures_iter(source).collect();
+  let examples = blocks
+    .iter()
+    .enumerate()
+    .filter_map(|(index, block)| {
       if block.get(1) == None {
         return None;
       }
 
-      let maybe_attributes: Option<Vec<_>> = block
-        .get(1)
-        .map(|attributes| attributes.as_str().split(' ').collect());
+      let attributes: Vec<_> =
+        block.get(1).unwrap().as_str().split(' ').collect();
 
-      let file_media_type = if let Some(attributes) = maybe_attributes {
-        if attributes.contains(&"ignore") {
-          return None;
-        }
+      if attributes.contains(&"ignore") {
+        return None;
+      }
 
-        match attributes.get(0) {
-          Some(&"js") => MediaType::JavaScript,
-          Some(&"javascript") => MediaType::JavaScript,
-          Some(&"mjs") => MediaType::Mjs,
-          Some(&"cjs") => MediaType::Cjs,
-          Some(&"jsx") => MediaType::Jsx,
-          Some(&"ts") => MediaType::TypeScript,
-          Some(&"typescript") => MediaType::TypeScript,
-          Some(&"mts") => MediaType::Mts,
-          Some(&"cts") => MediaType::Cts,
-          Some(&"tsx") => MediaType::Tsx,
-          Some(&"") => media_type,
-          _ => MediaType::Unknown,
-        }
-      } else {
-        media_type
+      let file_media_type = match attributes.get(0) {
+        Some(&"js") => MediaType::JavaScript,
+        Some(&"javascript") => MediaType::JavaScript,
+        Some(&"mjs") => MediaType::Mjs,
+        Some(&"cjs") => MediaType::Cjs,
+        Some(&"jsx") => MediaType::Jsx,
+        Some(&"ts") => MediaType::TypeScript,
+        Some(&"typescript") => MediaType::TypeScript,
+        Some(&"mts") => MediaType::Mts,
+        Some(&"cts") => MediaType::Cts,
+        Some(&"tsx") => MediaType::Tsx,
+        Some(&"") => media_type,
+        _ => MediaType::Unknown,
       };
 
       if file_media_type == MediaType::Unknown {
@@ -747,25 +1925,51 @@ fn extract_files_from_regex_blocks(
       ))
       .unwrap();
 
-      Some(File {
-        local: file_specifier.to_file_path().unwrap(),
-        maybe_types: None,
-        media_type: file_media_type,
-        source: Arc::new(file_source),
-        specifier: file_specifier,
-        maybe_headers: None,
+      // The expected output is an `output` fence separated from the example
+      // by nothing but whitespace (and comment decorations).
+      let expected_output = blocks
+        .get(index + 1)
+        .filter(|_| !attributes.contains(&"no-eval"))
+        .filter(|next| {
+          next.get(1).map(|attributes| attributes.as_str().trim())
+            == Some("output")
+            && source[block.get(0).unwrap().end()..next.get(0).unwrap().start()]
+              .chars()
+              .all(|c| c.is_whitespace() || c == '*')
+        })
+        .map(|next| {
+          let mut output = String::new();
+          for line in
+            output_lines_regex.captures_iter(next.get(2).unwrap().as_str())
+          {
+            output.push_str(line.get(1).unwrap().as_str());
+            output.push('\n');
+          }
+          output
+        });
+
+      Some(DocExample {
+        file: File {
+          local: file_specifier.to_file_path().unwrap(),
+          maybe_types: None,
+          media_type: file_media_type,
+          source: Arc::new(file_source),
+          specifier: file_specifier,
+          maybe_headers: None,
+        },
+        expected_output,
       })
     })
     .collect();
 
-  Ok(files)
+  Ok(examples)
 }
 
-fn extract_files_from_source_comments(
+fn extract_examples_from_source_comments(
   specifier: &ModuleSpecifier,
   source: Arc<String>,
   media_type: MediaType,
-) -> Result<Vec<File>, AnyError> {
+) -> Result<Vec<DocExample>, AnyError> {
   let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
     specifier: specifier.as_str().to_string(),
     source: deno_ast::SourceTextInfo::new(source),
@@ -777,8 +1981,9 @@ fn extract_files_from_source_comments(
   let comments = parsed_source.comments().get_vec();
   let blocks_regex = Regex::new(r"```([^\r\n]*)\r?\n([\S\s]*?)```")?;
   let lines_regex = Regex::new(r"(?:\* ?)(?:\# ?)?(.*)")?;
+  let output_lines_regex = Regex::new(r"(?:\* ?)(.*)")?;
 
-  let files = comments
+  let examples = comments
     .iter()
     .filter(|comment| {
       if comment.kind != CommentKind::Block || !comment.text.starts_with('*') {
@@ -788,26 +1993,27 @@ fn extract_files_from_source_comments(
       true
     })
     .flat_map(|comment| {
-      extract_files_from_regex_blocks(
+      extract_examples_from_regex_blocks(
         specifier,
         &comment.text,
         media_type,
         parsed_source.source().line_index(comment.span.lo),
         &blocks_regex,
         &lines_regex,
+        &output_lines_regex,
       )
     })
     .flatten()
     .collect();
 
-  Ok(files)
+  Ok(examples)
 }
 
-fn extract_files_from_fenced_blocks(
+fn extract_examples_from_fenced_blocks(
   specifier: &ModuleSpecifier,
   source: &str,
   media_type: MediaType,
-) -> Result<Vec<File>, AnyError> {
+) -> Result<Vec<DocExample>, AnyError> {
   // The pattern matches code blocks as well as anything in HTML comment syntax,
   // but it stores the latter without any capturing groups. This way, a simple
   // check can be done to see if a block is inside a comment (and skip typechecking)
@@ -815,22 +2021,24 @@ fn extract_files_from_fenced_blocks(
   let blocks_regex =
     Regex::new(r"(?s)<!--.*?-->|```([^\r\n]*)\r?\n([\S\s]*?)```")?;
   let lines_regex = Regex::new(r"(?:\# ?)?(.*)")?;
+  let output_lines_regex = Regex::new(r"(.*)")?;
 
-  extract_files_from_regex_blocks(
+  extract_examples_from_regex_blocks(
     specifier,
     source,
     media_type,
     /* file line index */ 0,
     &blocks_regex,
     &lines_regex,
+    &output_lines_regex,
   )
 }
 
-async fn fetch_inline_files(
+async fn fetch_doc_examples(
   ps: ProcState,
   specifiers: Vec<ModuleSpecifier>,
-) -> Result<Vec<File>, AnyError> {
-  let mut files = Vec::new();
+) -> Result<Vec<DocExample>, AnyError> {
+  let mut examples = Vec::new();
   for specifier in specifiers {
     let mut fetch_permissions = Permissions::allow_all();
     let file = ps
@@ -838,24 +2046,41 @@ async fn fetch_inline_files(
       .fetch(&specifier, &mut fetch_permissions)
       .await?;
 
-    let inline_files = if file.media_type == MediaType::Unknown {
-      extract_files_from_fenced_blocks(
+    let file_examples = if file.media_type == MediaType::Unknown {
+      extract_examples_from_fenced_blocks(
         &file.specifier,
         &file.source,
         file.media_type,
       )
     } else {
-      extract_files_from_source_comments(
+      extract_examples_from_source_comments(
         &file.specifier,
         file.source.clone(),
         file.media_type,
       )
     };
 
-    files.extend(inline_files?);
+    examples.extend(file_examples?);
   }
 
-  Ok(files)
+  Ok(examples)
+}
+
+/// Specifiers of the test modules evaluating the examples of `specifier` that
+/// have an expected output. They are prepared by `check_specifiers`.
+async fn fetch_doc_test_specifiers(
+  ps: &ProcState,
+  specifier: &ModuleSpecifier,
+) -> Result<Vec<ModuleSpecifier>, AnyError> {
+  let examples =
+    fetch_doc_examples(ps.clone(), vec![specifier.clone()]).await?;
+  Ok(
+    examples
+      .iter()
+      .filter_map(|example| example.test_file())
+      .map(|file| file.specifier)
+      .collect(),
+  )
 }
 
 /// Type check a collection of module and document specifiers.
@@ -865,7 +2090,7 @@ pub async fn check_specifiers(
   specifiers: Vec<(ModuleSpecifier, TestMode)>,
   lib: emit::TypeLib,
 ) -> Result<(), AnyError> {
-  let inline_files = fetch_inline_files(
+  let examples = fetch_doc_examples(
     ps.clone(),
     specifiers
       .iter()
@@ -880,6 +2105,12 @@ pub async fn check_specifiers(
   )
   .await?;
 
+  let mut inline_files = Vec::new();
+  for example in examples {
+    inline_files.extend(example.test_file());
+    inline_files.push(example.file);
+  }
+
   if !inline_files.is_empty() {
     let specifiers = inline_files
       .iter()
@@ -925,14 +2156,18 @@ pub async fn check_specifiers(
   Ok(())
 }
 
-/// Test a collection of specifiers with test modes concurrently.
-async fn test_specifiers(
+/// Runs the tests of the given modules concurrently, sending their events to
+/// `sender` as they happen. This is `deno test` without the reporting, for
+/// embedders and the LSP to drive test runs with. Returns once all modules are
+/// done, or have been stopped by `cancellation`.
+pub async fn run_test_modules(
   ps: ProcState,
   permissions: Permissions,
   specifiers_with_mode: Vec<(ModuleSpecifier, TestMode)>,
-  options: TestSpecifierOptions,
+  options: TestRunOptions,
+  sender: UnboundedSender<TestEvent>,
+  cancellation: TestCancellation,
 ) -> Result<(), AnyError> {
-  let log_level = ps.flags.log_level;
   let specifiers_with_mode = if let Some(seed) = options.shuffle {
     let mut rng = SmallRng::seed_from_u64(seed);
     let mut specifiers_with_mode = specifiers_with_mode.clone();
@@ -943,40 +2178,150 @@ async fn test_specifiers(
     specifiers_with_mode
   };
 
-  let (sender, mut receiver) = unbounded_channel::<TestEvent>();
   let sender = TestEventSender::new(sender);
   let concurrent_jobs = options.concurrent_jobs;
-  let fail_fast = options.fail_fast;
 
-  let join_handles =
-    specifiers_with_mode.iter().map(move |(specifier, mode)| {
-      let ps = ps.clone();
-      let permissions = permissions.clone();
-      let specifier = specifier.clone();
-      let mode = mode.clone();
-      let sender = sender.clone();
-      let options = options.clone();
+  // Compat mode loads CommonJS modules in a way that can't be undone, so it
+  // always gets a worker per module.
+  let reuse_workers = options.reuse_workers && !options.compat_mode;
+  let join_stream = if reuse_workers {
+    let queue = Arc::new(Mutex::new(
+      specifiers_with_mode
+        .iter()
+        .cloned()
+        .collect::<VecDeque<_>>(),
+    ));
+    let worker_handles = (0..concurrent_jobs.get())
+      .map(|_| {
+        let ps = ps.clone();
+        let permissions = permissions.clone();
+        let queue = queue.clone();
+        let sender = sender.clone();
+        let cancellation = cancellation.clone();
+        let options = options.clone();
+        tokio::task::spawn_blocking(move || {
+          run_basic(test_specifiers_in_worker(
+            ps,
+            permissions,
+            queue,
+            sender,
+            cancellation,
+            options,
+          ))
+        })
+      })
+      .collect::<Vec<_>>();
+    // The workers hold the only senders left, so the event stream ends once
+    // they are done.
+    drop(sender);
+    future::join_all(worker_handles).boxed_local()
+  } else {
+    let cancellation = cancellation.clone();
+    let join_handles =
+      specifiers_with_mode.iter().map(move |(specifier, mode)| {
+        let ps = ps.clone();
+        let permissions = permissions.clone();
+        let specifier = specifier.clone();
+        let mode = mode.clone();
+        let sender = if options.capture_output {
+          TestEventSender::with_output_origin(
+            sender.sender.clone(),
+            Some(specifier.to_string()),
+          )
+        } else {
+          sender.clone()
+        };
+        let cancellation = cancellation.clone();
+        let options = options.clone();
+
+        tokio::task::spawn_blocking(move || {
+          let future = test_specifier(
+            ps,
+            permissions,
+            specifier,
+            mode,
+            sender,
+            cancellation,
+            options,
+          );
+
+          run_basic(future)
+        })
+      });
+
+    stream::iter(join_handles)
+      .buffer_unordered(concurrent_jobs.get())
+      .collect::<Vec<Result<Result<(), AnyError>, tokio::task::JoinError>>>()
+      .boxed_local()
+  };
 
-      tokio::task::spawn_blocking(move || {
-        let future =
-          test_specifier(ps, permissions, specifier, mode, sender, options);
+  let join_results = join_stream.await;
 
-        run_basic(future)
-      })
-    });
+  // propagate any errors, except for those of the workers that were
+  // terminated by a cancellation
+  for join_result in join_results {
+    let worker_result = join_result?;
+    if !cancellation.is_cancelled() {
+      worker_result?;
+    }
+  }
 
-  let join_stream = stream::iter(join_handles)
-    .buffer_unordered(concurrent_jobs.get())
-    .collect::<Vec<Result<Result<(), AnyError>, tokio::task::JoinError>>>();
+  Ok(())
+}
 
-  let mut reporter =
-    create_reporter(concurrent_jobs.get() > 1, log_level != Some(Level::Error));
+/// Test a collection of specifiers with test modes concurrently.
+async fn test_specifiers(
+  ps: ProcState,
+  permissions: Permissions,
+  specifiers_with_mode: Vec<(ModuleSpecifier, TestMode)>,
+  options: TestRunOptions,
+) -> Result<(), AnyError> {
+  let log_level = ps.flags.log_level;
+  let (sender, mut receiver) = unbounded_channel::<TestEvent>();
+  let concurrent_jobs = options.concurrent_jobs;
+  let fail_fast = options.fail_fast;
+  let reporter_config = options.reporter.clone();
+  let shuffle_seed = options.shuffle;
+  let slow_threshold = options.slow_threshold;
+  let show_diff = !options.no_diff;
+  let quarantine = TestQuarantine::new(&options.quarantine)?;
+  let cancellation = TestCancellation::default();
+  let verbose_output = options.verbose_output;
+
+  let maybe_reporter_module = match &reporter_config {
+    TestReporterConfig::Module(specifier) => Some(
+      ReporterModule::start(ps.clone(), permissions.clone(), specifier).await?,
+    ),
+    _ => None,
+  };
+
+  let run = run_test_modules(
+    ps,
+    permissions,
+    specifiers_with_mode,
+    options,
+    sender,
+    cancellation.clone(),
+  );
+
+  let mut reporter = create_reporter(
+    &reporter_config,
+    concurrent_jobs.get() > 1,
+    log_level != Some(Level::Error),
+    slow_threshold,
+    show_diff,
+    maybe_reporter_module,
+  );
 
   let handler = {
+    let cancellation = cancellation.clone();
     tokio::task::spawn(async move {
       let earlier = Instant::now();
       let mut summary = TestSummary::new();
+      summary.shuffle_seed = shuffle_seed;
       let mut used_only = false;
+      let mut captured_output: HashMap<String, Vec<u8>> = HashMap::new();
+      let mut step_names = StepNames::default();
 
       while let Some(event) = receiver.recv().await {
         match event {
@@ -999,7 +2344,24 @@ async fn test_specifiers(
             reporter.report_output(&output);
           }
 
+          TestEvent::CapturedOutput(origin, output) => {
+            captured_output.entry(origin).or_default().extend(output);
+          }
+
           TestEvent::Result(description, result, elapsed) => {
+            let quarantined = quarantine.contains(&description.name);
+            let result = match result {
+              TestResult::Failed(failure) if quarantined => {
+                TestResult::Quarantined(failure)
+              }
+              result => result,
+            };
+            if quarantined
+              && matches!(result, TestResult::Ok | TestResult::Flaky(_))
+            {
+              summary.quarantined_passed += 1;
+            }
+
             match &result {
               TestResult::Ok => {
                 summary.passed += 1;
@@ -1007,16 +2369,49 @@ async fn test_specifiers(
               TestResult::Ignored => {
                 summary.ignored += 1;
               }
-              TestResult::Failed(error) => {
+              TestResult::Flaky(_) => {
+                summary.flaky += 1;
+              }
+              TestResult::Failed(failure) => {
                 summary.failed += 1;
-                summary.failures.push((description.clone(), error.clone()));
+                summary
+                  .failures
+                  .push((description.clone(), failure.clone()));
               }
+              TestResult::Quarantined(failure) => {
+                summary
+                  .quarantined_failures
+                  .push((description.clone(), failure.clone()));
+              }
+            }
+
+            if matches!(slow_threshold, Some(threshold) if elapsed > threshold)
+            {
+              summary.slowest.push(SlowTest {
+                origin: description.origin.clone(),
+                name: description.name.clone(),
+                elapsed,
+              });
             }
 
+            // Output printed by the module before the test started counts
+            // as part of the test.
+            if let Some(output) = captured_output.remove(&description.origin) {
+              let failed = matches!(
+                result,
+                TestResult::Failed(_) | TestResult::Quarantined(_)
+              );
+              if failed || verbose_output {
+                reporter.report_captured_output(&description, &output);
+              }
+            }
+
+            step_names.finish(&description);
             reporter.report_result(&description, &result, elapsed);
           }
 
           TestEvent::StepWait(description) => {
+            step_names.wait(&description);
             reporter.report_step_wait(&description);
           }
 
@@ -1036,19 +2431,37 @@ async fn test_specifiers(
               }
             }
 
+            if matches!(slow_threshold, Some(threshold) if duration > threshold)
+            {
+              summary.slowest.push(SlowTest {
+                origin: description.test.origin.clone(),
+                name: step_names.full_name(&description),
+                elapsed: duration,
+              });
+            }
+
             reporter.report_step_result(&description, &result, duration);
           }
         }
 
         if let Some(x) = fail_fast {
           if summary.failed >= x.get() {
+            cancellation.cancel();
+            // Wait for the terminated tests to actually stop, which closes
+            // the channel, so that nothing runs past the summary. Whatever
+            // they report in the meantime was cut short and is dropped.
+            while receiver.recv().await.is_some() {}
             break;
           }
         }
       }
 
+      summary.slowest.sort_by(|a, b| b.elapsed.cmp(&a.elapsed));
+      summary.slowest.truncate(SLOWEST_TESTS_COUNT);
+
       let elapsed = Instant::now().duration_since(earlier);
       reporter.report_summary(&summary, &elapsed);
+      reporter.finish()?;
 
       if used_only {
         return Err(generic_error(
@@ -1064,13 +2477,9 @@ async fn test_specifiers(
     })
   };
 
-  let (join_results, result) = future::join(join_stream, handler).await;
-
-  // propagate any errors
-  for join_result in join_results {
-    join_result??;
-  }
+  let (run_result, result) = future::join(run, handler).await;
 
+  run_result?;
   result??;
 
   Ok(())
@@ -1126,7 +2535,7 @@ fn collect_specifiers_with_test_mode(
 /// module are marked as `TestMode::Documentation`. Type definition files
 /// cannot be run, and therefore need to be marked as `TestMode::Documentation`
 /// as well.
-async fn fetch_specifiers_with_test_mode(
+pub async fn fetch_specifiers_with_test_mode(
   ps: &ProcState,
   include: Vec<String>,
   ignore: Vec<PathBuf>,
@@ -1150,6 +2559,412 @@ async fn fetch_specifiers_with_test_mode(
   Ok(specifiers_with_mode)
 }
 
+/// Keeps the specifiers that belong to `shard`. Specifiers are assigned to
+/// shards by an FNV-1a hash of their path relative to the current directory,
+/// so that every machine of a CI job agrees on the split regardless of where
+/// the repository is checked out.
+fn shard_specifiers(
+  specifiers_with_mode: Vec<(ModuleSpecifier, TestMode)>,
+  shard: &TestShard,
+) -> Vec<(ModuleSpecifier, TestMode)> {
+  let cwd = std::env::current_dir()
+    .ok()
+    .and_then(|cwd| Url::from_directory_path(cwd).ok());
+  specifiers_with_mode
+    .into_iter()
+    .filter(|(specifier, _)| {
+      let key = cwd
+        .as_ref()
+        .and_then(|cwd| cwd.make_relative(specifier))
+        .unwrap_or_else(|| specifier.to_string());
+      let hash = key.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+      });
+      (hash % shard.count as u64) as usize == shard.index - 1
+    })
+    .collect()
+}
+
+/// A running global setup module (`deno test --setup` or `"test.setup"` in the
+/// config file). Its default export is called once before any test module
+/// runs, in a worker of its own that is kept alive, with its event loop
+/// running, until the whole run is over. A function returned by it is then
+/// called to tear down whatever it started.
+struct GlobalSetup {
+  teardown_sender: oneshot::Sender<()>,
+  handle: tokio::task::JoinHandle<Result<(), AnyError>>,
+}
+
+impl GlobalSetup {
+  async fn start(
+    ps: ProcState,
+    permissions: Permissions,
+    specifier: ModuleSpecifier,
+  ) -> Result<Self, AnyError> {
+    let (ready_sender, ready_receiver) = oneshot::channel();
+    let (teardown_sender, teardown_receiver) = oneshot::channel();
+    let handle = tokio::task::spawn_blocking(move || {
+      run_basic(run_global_setup(
+        ps,
+        permissions,
+        specifier,
+        ready_sender,
+        teardown_receiver,
+      ))
+    });
+
+    match ready_receiver.await {
+      Ok(()) => Ok(Self {
+        teardown_sender,
+        handle,
+      }),
+      // The setup failed before it was ready, report why.
+      Err(_) => Err(handle.await?.err().unwrap_or_else(|| {
+        generic_error("Global setup module exited before it was ready")
+      })),
+    }
+  }
+
+  /// Runs the teardown function returned by the setup module, if any.
+  async fn teardown(self) -> Result<(), AnyError> {
+    let _ = self.teardown_sender.send(());
+    self.handle.await?
+  }
+}
+
+async fn run_global_setup(
+  ps: ProcState,
+  permissions: Permissions,
+  specifier: ModuleSpecifier,
+  ready_sender: oneshot::Sender<()>,
+  mut teardown_receiver: oneshot::Receiver<()>,
+) -> Result<(), AnyError> {
+  let mut worker = create_main_worker(
+    &ps,
+    specifier.clone(),
+    permissions,
+    vec![],
+    Default::default(),
+  );
+
+  let promise = worker.js_runtime.execute_script(
+    &located_script_name!(),
+    &format!(
+      r#"(async () => {{
+        const mod = await import({});
+        if (typeof mod.default !== "function") {{
+          throw new TypeError("The setup module must export a function as default");
+        }}
+        return await mod.default();
+      }})()"#,
+      json!(specifier.as_str()),
+    ),
+  )?;
+  let teardown = worker.js_runtime.resolve_value(promise).await?;
+  let _ = ready_sender.send(());
+
+  // Keep anything the setup started (servers, timers, ...) running while the
+  // tests run.
+  tokio::select! {
+    result = worker.run_event_loop(false) => {
+      result?;
+      let _ = (&mut teardown_receiver).await;
+    }
+    _ = &mut teardown_receiver => {}
+  }
+
+  let promise = {
+    let scope = &mut worker.js_runtime.handle_scope();
+    let teardown = v8::Local::new(scope, &teardown);
+    let teardown = match v8::Local::<v8::Function>::try_from(teardown) {
+      Ok(teardown) => teardown,
+      Err(_) => return Ok(()),
+    };
+    let tc_scope = &mut v8::TryCatch::new(scope);
+    let undefined = v8::undefined(tc_scope).into();
+    match teardown.call(tc_scope, undefined, &[]) {
+      Some(value) => v8::Global::new(tc_scope, value),
+      None => {
+        let exception = tc_scope.exception().unwrap();
+        return Err(JsError::from_v8_exception(tc_scope, exception).into());
+      }
+    }
+  };
+  worker.js_runtime.resolve_value(promise).await?;
+
+  Ok(())
+}
+
+/// A user module reporting the test events, for `--reporter=./reporter.ts`.
+/// It runs in a worker on a thread of its own, which calls the function the
+/// module exports for each JSON event, e.g. `onPlan` for the `plan` event or
+/// `onStepResult` for the `stepResult` event, and awaits it before passing on
+/// the next one. Events without such an export are skipped.
+struct ReporterModule {
+  sender: UnboundedSender<serde_json::Value>,
+  handle: std::thread::JoinHandle<Result<(), AnyError>>,
+}
+
+impl ReporterModule {
+  async fn start(
+    ps: ProcState,
+    permissions: Permissions,
+    specifier: &str,
+  ) -> Result<Self, AnyError> {
+    let specifier = deno_core::resolve_url_or_path(specifier)?;
+    let (sender, receiver) = unbounded_channel();
+    let (ready_sender, ready_receiver) = oneshot::channel();
+    let handle = std::thread::spawn(move || {
+      run_basic(run_reporter_module(
+        ps,
+        permissions,
+        specifier,
+        ready_sender,
+        receiver,
+      ))
+    });
+
+    match ready_receiver.await {
+      Ok(()) => Ok(Self { sender, handle }),
+      // The module failed to load, report why.
+      Err(_) => Err(handle.join().unwrap().err().unwrap_or_else(|| {
+        generic_error("Reporter module exited before it was ready")
+      })),
+    }
+  }
+
+  fn send(&self, event: serde_json::Value) {
+    // The module stopped because of an error, which `finish` reports.
+    let _ = self.sender.send(event);
+  }
+
+  /// Waits until the module has handled all events.
+  fn finish(self) -> Result<(), AnyError> {
+    drop(self.sender);
+    self.handle.join().unwrap()
+  }
+}
+
+async fn run_reporter_module(
+  ps: ProcState,
+  permissions: Permissions,
+  specifier: ModuleSpecifier,
+  ready_sender: oneshot::Sender<()>,
+  mut receiver: tokio::sync::mpsc::UnboundedReceiver<serde_json::Value>,
+) -> Result<(), AnyError> {
+  let mut worker = create_main_worker(
+    &ps,
+    specifier.clone(),
+    permissions,
+    vec![],
+    Default::default(),
+  );
+
+  let promise = worker.js_runtime.execute_script(
+    &located_script_name!(),
+    &format!("import({})", json!(specifier.as_str())),
+  )?;
+  let module = worker.js_runtime.resolve_value(promise).await?;
+  let _ = ready_sender.send(());
+
+  while let Some(event) = receiver.recv().await {
+    let event_type = event["type"].as_str().unwrap_or_default();
+    let handler_name = format!(
+      "on{}{}",
+      event_type.get(..1).unwrap_or_default().to_uppercase(),
+      event_type.get(1..).unwrap_or_default()
+    );
+    let promise = {
+      let scope = &mut worker.js_runtime.handle_scope();
+      let module = v8::Local::new(scope, &module);
+      let module = v8::Local::<v8::Object>::try_from(module).unwrap();
+      let key = v8::String::new(scope, &handler_name).unwrap();
+      let handler = match module
+        .get(scope, key.into())
+        .and_then(|handler| v8::Local::<v8::Function>::try_from(handler).ok())
+      {
+        Some(handler) => handler,
+        None => continue,
+      };
+      let event = serde_v8::to_v8(scope, &event)?;
+      let tc_scope = &mut v8::TryCatch::new(scope);
+      let undefined = v8::undefined(tc_scope).into();
+      match handler.call(tc_scope, undefined, &[event]) {
+        Some(value) => v8::Global::new(tc_scope, value),
+        None => {
+          let exception = tc_scope.exception().unwrap();
+          return Err(JsError::from_v8_exception(tc_scope, exception).into());
+        }
+      }
+    };
+    worker.js_runtime.resolve_value(promise).await?;
+  }
+
+  // Let anything the module started, like sending the results somewhere,
+  // finish.
+  worker.run_event_loop(false).await?;
+
+  Ok(())
+}
+
+/// A test declaration found by `deno test --list`.
+#[derive(Debug, Serialize)]
+struct ListedTest {
+  name: String,
+  line: usize,
+  column: usize,
+  steps: Vec<ListedTest>,
+}
+
+impl ListedTest {
+  fn new(
+    definition: TestDefinition,
+    source: &deno_ast::SourceTextInfo,
+  ) -> Self {
+    let position = source.line_and_column_index(definition.span.lo);
+    Self {
+      name: definition.name,
+      line: position.line_index + 1,
+      column: position.column_index + 1,
+      steps: definition
+        .steps
+        .unwrap_or_default()
+        .into_iter()
+        .map(|step| Self::new(step, source))
+        .collect(),
+    }
+  }
+
+  fn print(&self, level: usize) {
+    println!("{}{}", "  ".repeat(level), self.name);
+    for step in &self.steps {
+      step.print(level + 1);
+    }
+  }
+}
+
+#[derive(Debug, Serialize)]
+struct ListedModule {
+  specifier: ModuleSpecifier,
+  tests: Vec<ListedTest>,
+}
+
+/// Prints the tests declared by the executable modules, as found by the
+/// collector the LSP uses for its test explorer. Modules are parsed, but
+/// neither type checked nor run.
+async fn list_tests(
+  ps: &ProcState,
+  specifiers_with_mode: Vec<(ModuleSpecifier, TestMode)>,
+  format: &TestListFormat,
+) -> Result<(), AnyError> {
+  let mut modules = Vec::new();
+  for (specifier, mode) in specifiers_with_mode {
+    if mode == TestMode::Documentation {
+      continue;
+    }
+    let file = ps
+      .file_fetcher
+      .fetch(&specifier, &mut Permissions::allow_all())
+      .await?;
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: specifier.to_string(),
+      source: deno_ast::SourceTextInfo::new(file.source),
+      media_type: file.media_type,
+      capture_tokens: false,
+      maybe_syntax: None,
+      scope_analysis: false,
+    })?;
+    let mut collector = TestCollector::new(specifier.clone());
+    parsed_source.module().visit_with(&mut collector);
+    let tests = collector
+      .take()
+      .into_iter()
+      .map(|definition| ListedTest::new(definition, parsed_source.source()))
+      .collect();
+    modules.push(ListedModule { specifier, tests });
+  }
+
+  match format {
+    TestListFormat::Pretty => {
+      let cwd = Url::from_directory_path(std::env::current_dir()?).unwrap();
+      for module in &modules {
+        println!(
+          "{}",
+          to_relative_path_or_remote_url(&cwd, module.specifier.as_str())
+        );
+        for test in &module.tests {
+          test.print(1);
+        }
+      }
+    }
+    TestListFormat::Json => {
+      println!("{}", serde_json::to_string_pretty(&modules)?);
+    }
+  }
+
+  Ok(())
+}
+
+/// Resolves the global setup module, preferring `--setup` over the config
+/// file.
+fn resolve_setup_specifier(
+  ps: &ProcState,
+  test_flags: &TestFlags,
+) -> Result<Option<ModuleSpecifier>, AnyError> {
+  if let Some(setup) = &test_flags.setup {
+    return Ok(Some(deno_core::resolve_url_or_path(setup)?));
+  }
+  match &ps.maybe_config_file {
+    Some(config_file) => Ok(
+      config_file
+        .to_test_config()?
+        .and_then(|test_config| test_config.setup),
+    ),
+    None => Ok(None),
+  }
+}
+
+/// Adds the documents listed in the config file, which are tested as
+/// documentation even without `--doc`.
+fn with_config_docs(
+  ps: &ProcState,
+  mut specifiers_with_mode: Vec<(ModuleSpecifier, TestMode)>,
+) -> Result<Vec<(ModuleSpecifier, TestMode)>, AnyError> {
+  let docs = match &ps.maybe_config_file {
+    Some(config_file) => config_file
+      .to_test_config()?
+      .map(|test_config| test_config.docs)
+      .unwrap_or_default(),
+    None => return Ok(specifiers_with_mode),
+  };
+  for doc in docs {
+    match specifiers_with_mode
+      .iter_mut()
+      .find(|(specifier, _)| *specifier == doc)
+    {
+      Some((_, mode)) if *mode == TestMode::Executable => {
+        *mode = TestMode::Both
+      }
+      Some(_) => {}
+      None => specifiers_with_mode.push((doc, TestMode::Documentation)),
+    }
+  }
+  Ok(specifiers_with_mode)
+}
+
+/// Resolves the names of quarantined tests from the config file.
+fn resolve_quarantine(ps: &ProcState) -> Result<Vec<String>, AnyError> {
+  match &ps.maybe_config_file {
+    Some(config_file) => Ok(
+      config_file
+        .to_test_config()?
+        .map(|test_config| test_config.quarantine)
+        .unwrap_or_default(),
+    ),
+    None => Ok(Vec::new()),
+  }
+}
+
 pub async fn run_tests(
   flags: Flags,
   test_flags: TestFlags,
@@ -1158,46 +2973,175 @@ pub async fn run_tests(
   let permissions = Permissions::from_options(&ps.flags.permissions_options());
   let specifiers_with_mode = fetch_specifiers_with_test_mode(
     &ps,
-    test_flags.include.unwrap_or_else(|| vec![".".to_string()]),
+    test_flags
+      .include
+      .clone()
+      .unwrap_or_else(|| vec![".".to_string()]),
     test_flags.ignore.clone(),
     test_flags.doc,
   )
   .await?;
+  let specifiers_with_mode = with_config_docs(&ps, specifiers_with_mode)?;
 
   if !test_flags.allow_none && specifiers_with_mode.is_empty() {
     return Err(generic_error("No test modules found"));
   }
 
+  let specifiers_with_mode = match &test_flags.shard {
+    Some(shard) => shard_specifiers(specifiers_with_mode, shard),
+    None => specifiers_with_mode,
+  };
+
+  if let Some(format) = &test_flags.list {
+    return list_tests(&ps, specifiers_with_mode, format).await;
+  }
+
   let lib = if ps.flags.unstable {
     emit::TypeLib::UnstableDenoWindow
   } else {
     emit::TypeLib::DenoWindow
   };
 
-  check_specifiers(&ps, permissions.clone(), specifiers_with_mode.clone(), lib)
-    .await?;
+  let setup_specifier = resolve_setup_specifier(&ps, &test_flags)?;
+  let mut specifiers_to_check = specifiers_with_mode.clone();
+  if let Some(specifier) = &setup_specifier {
+    specifiers_to_check.push((specifier.clone(), TestMode::Executable));
+  }
+  check_specifiers(&ps, permissions.clone(), specifiers_to_check, lib).await?;
 
   if test_flags.no_run {
     return Ok(());
   }
 
-  let compat = ps.flags.compat;
-  test_specifiers(
-    ps,
-    permissions,
-    specifiers_with_mode,
-    TestSpecifierOptions {
-      compat_mode: compat,
-      concurrent_jobs: test_flags.concurrent_jobs,
-      fail_fast: test_flags.fail_fast,
-      filter: test_flags.filter,
-      shuffle: test_flags.shuffle,
-      trace_ops: test_flags.trace_ops,
-    },
-  )
-  .await?;
+  let setup = match setup_specifier {
+    Some(specifier) => Some(
+      GlobalSetup::start(ps.clone(), permissions.clone(), specifier).await?,
+    ),
+    None => None,
+  };
 
-  Ok(())
+  let options = TestRunOptions {
+    quarantine: resolve_quarantine(&ps)?,
+    ..TestRunOptions::new(&ps.flags, &test_flags)
+  };
+  let result =
+    test_specifiers(ps, permissions, specifiers_with_mode, options).await;
+
+  if let Some(setup) = setup {
+    setup.teardown().await?;
+  }
+
+  result
+}
+
+/// Collects the modules `maybe_module` (transitively) depends on.
+fn get_dependencies<'a>(
+  graph: &'a deno_graph::ModuleGraph,
+  maybe_module: Option<&'a deno_graph::Module>,
+  // This needs to be accessible to skip getting dependencies if they're already there,
+  // otherwise this will cause a stack overflow with circular dependencies
+  output: &mut HashSet<&'a ModuleSpecifier>,
+  no_check: bool,
+) {
+  if let Some(module) = maybe_module {
+    for dep in module.dependencies.values() {
+      if let Some(specifier) = &dep.get_code() {
+        if !output.contains(specifier) {
+          output.insert(specifier);
+          get_dependencies(graph, graph.get(specifier), output, no_check);
+        }
+      }
+      if !no_check {
+        if let Some(specifier) = &dep.get_type() {
+          if !output.contains(specifier) {
+            output.insert(specifier);
+            get_dependencies(graph, graph.get(specifier), output, no_check);
+          }
+        }
+      }
+    }
+  }
+}
+
+/// Maps every test module to the modules it (transitively) depends on, and
+/// back. It is kept across `--watch` iterations so that a change only requires
+/// rebuilding the module graphs of the test modules it affects.
+#[derive(Default)]
+struct TestDependencyIndex {
+  /// Dependencies of each test module, including the module itself.
+  dependencies: HashMap<ModuleSpecifier, HashSet<ModuleSpecifier>>,
+  /// Test modules depending on each module.
+  dependents: HashMap<ModuleSpecifier, HashSet<ModuleSpecifier>>,
+}
+
+impl TestDependencyIndex {
+  fn contains(&self, test_module: &ModuleSpecifier) -> bool {
+    self.dependencies.contains_key(test_module)
+  }
+
+  /// Returns the test modules depending on any of `changed`.
+  fn affected_by(
+    &self,
+    changed: &[ModuleSpecifier],
+  ) -> HashSet<ModuleSpecifier> {
+    changed
+      .iter()
+      .filter_map(|specifier| self.dependents.get(specifier))
+      .flatten()
+      .cloned()
+      .collect()
+  }
+
+  fn update(
+    &mut self,
+    test_module: ModuleSpecifier,
+    dependencies: HashSet<ModuleSpecifier>,
+  ) {
+    self.remove(&test_module);
+    for dependency in &dependencies {
+      self
+        .dependents
+        .entry(dependency.clone())
+        .or_default()
+        .insert(test_module.clone());
+    }
+    self.dependencies.insert(test_module, dependencies);
+  }
+
+  fn remove(&mut self, test_module: &ModuleSpecifier) {
+    if let Some(dependencies) = self.dependencies.remove(test_module) {
+      for dependency in dependencies {
+        if let Some(dependents) = self.dependents.get_mut(&dependency) {
+          dependents.remove(test_module);
+          if dependents.is_empty() {
+            self.dependents.remove(&dependency);
+          }
+        }
+      }
+    }
+  }
+
+  /// Drops the test modules that are no longer part of `test_modules`.
+  fn retain(&mut self, test_modules: &[ModuleSpecifier]) {
+    let removed = self
+      .dependencies
+      .keys()
+      .filter(|s| !test_modules.contains(s))
+      .cloned()
+      .collect::<Vec<_>>();
+    for test_module in removed {
+      self.remove(&test_module);
+    }
+  }
+
+  /// Returns the local files of all indexed modules.
+  fn paths(&self) -> Vec<PathBuf> {
+    self
+      .dependents
+      .keys()
+      .filter_map(|specifier| specifier.to_file_path().ok())
+      .collect()
+  }
 }
 
 pub async fn run_tests_with_watch(
@@ -1218,6 +3162,8 @@ pub async fn run_tests_with_watch(
   let ignore = test_flags.ignore.clone();
   let paths_to_watch: Vec<_> = include.iter().map(PathBuf::from).collect();
   let no_check = ps.flags.type_check_mode == TypeCheckMode::None;
+  let watch_full = test_flags.watch_full;
+  let index = Rc::new(RefCell::new(TestDependencyIndex::default()));
 
   let resolver = |changed: Option<Vec<PathBuf>>| {
     let mut cache = cache::FetchCacher::new(
@@ -1244,6 +3190,7 @@ pub async fn run_tests_with_watch(
     let files_changed = changed.is_some();
     let include = include.clone();
     let ignore = ignore.clone();
+    let index = index.clone();
     let check_js = ps
       .maybe_config_file
       .as_ref()
@@ -1258,105 +3205,87 @@ pub async fn run_tests_with_watch(
       }?;
 
       let mut paths_to_watch = paths_to_watch_clone;
-      let mut modules_to_reload = if files_changed {
-        Vec::new()
-      } else {
+      let changed_specifiers = changed
+        .iter()
+        .flatten()
+        .filter_map(|path| {
+          deno_core::resolve_url_or_path(&path.to_string_lossy()).ok()
+        })
+        .collect::<Vec<_>>();
+
+      // Only the graphs of new test modules and of those affected by the
+      // change need to be built; the index remembers the rest.
+      let modules_to_index = {
+        let mut index = index.borrow_mut();
+        index.retain(&test_modules);
+        let affected = index.affected_by(&changed_specifiers);
         test_modules
           .iter()
-          .map(|url| (url.clone(), ModuleKind::Esm))
-          .collect()
-      };
-      let maybe_imports = if let Some(result) = maybe_imports {
-        result?
-      } else {
-        None
-      };
-      let maybe_resolver = if maybe_jsx_resolver.is_some() {
-        maybe_jsx_resolver.as_ref().map(|jr| jr.as_resolver())
-      } else {
-        maybe_import_map_resolver
-          .as_ref()
-          .map(|im| im.as_resolver())
+          .filter(|s| {
+            !files_changed || !index.contains(s) || affected.contains(*s)
+          })
+          .cloned()
+          .collect::<Vec<_>>()
       };
-      let graph = deno_graph::create_graph(
-        test_modules
-          .iter()
-          .map(|s| (s.clone(), ModuleKind::Esm))
-          .collect(),
-        false,
-        maybe_imports,
-        cache.as_mut_loader(),
-        maybe_resolver,
-        maybe_locker,
-        None,
-        None,
-      )
-      .await;
-      graph_valid(&graph, !no_check, check_js)?;
-
-      // TODO(@kitsonk) - This should be totally derivable from the graph.
-      for specifier in test_modules {
-        fn get_dependencies<'a>(
-          graph: &'a deno_graph::ModuleGraph,
-          maybe_module: Option<&'a deno_graph::Module>,
-          // This needs to be accessible to skip getting dependencies if they're already there,
-          // otherwise this will cause a stack overflow with circular dependencies
-          output: &mut HashSet<&'a ModuleSpecifier>,
-          no_check: bool,
-        ) {
-          if let Some(module) = maybe_module {
-            for dep in module.dependencies.values() {
-              if let Some(specifier) = &dep.get_code() {
-                if !output.contains(specifier) {
-                  output.insert(specifier);
-                  get_dependencies(
-                    graph,
-                    graph.get(specifier),
-                    output,
-                    no_check,
-                  );
-                }
-              }
-              if !no_check {
-                if let Some(specifier) = &dep.get_type() {
-                  if !output.contains(specifier) {
-                    output.insert(specifier);
-                    get_dependencies(
-                      graph,
-                      graph.get(specifier),
-                      output,
-                      no_check,
-                    );
-                  }
-                }
-              }
-            }
-          }
-        }
-
-        // This test module and all it's dependencies
-        let mut modules = HashSet::new();
-        modules.insert(&specifier);
-        get_dependencies(&graph, graph.get(&specifier), &mut modules, no_check);
 
-        paths_to_watch.extend(
-          modules
+      if !modules_to_index.is_empty() {
+        let maybe_imports = if let Some(result) = maybe_imports {
+          result?
+        } else {
+          None
+        };
+        let maybe_resolver = if maybe_jsx_resolver.is_some() {
+          maybe_jsx_resolver.as_ref().map(|jr| jr.as_resolver())
+        } else {
+          maybe_import_map_resolver
+            .as_ref()
+            .map(|im| im.as_resolver())
+        };
+        let graph = deno_graph::create_graph(
+          modules_to_index
             .iter()
-            .filter_map(|specifier| specifier.to_file_path().ok()),
-        );
-
-        if let Some(changed) = &changed {
-          for path in changed.iter().filter_map(|path| {
-            deno_core::resolve_url_or_path(&path.to_string_lossy()).ok()
-          }) {
-            if modules.contains(&&path) {
-              modules_to_reload.push((specifier, ModuleKind::Esm));
-              break;
-            }
-          }
+            .map(|s| (s.clone(), ModuleKind::Esm))
+            .collect(),
+          false,
+          maybe_imports,
+          cache.as_mut_loader(),
+          maybe_resolver,
+          maybe_locker,
+          None,
+          None,
+        )
+        .await;
+        graph_valid(&graph, !no_check, check_js)?;
+
+        let mut index = index.borrow_mut();
+        for specifier in &modules_to_index {
+          let mut modules = HashSet::new();
+          modules.insert(specifier);
+          get_dependencies(
+            &graph,
+            graph.get(specifier),
+            &mut modules,
+            no_check,
+          );
+          index
+            .update(specifier.clone(), modules.into_iter().cloned().collect());
         }
       }
 
+      paths_to_watch.extend(index.borrow().paths());
+
+      let modules_to_reload = if files_changed && !watch_full {
+        modules_to_index
+      } else if files_changed && modules_to_index.is_empty() {
+        Vec::new()
+      } else {
+        test_modules
+      };
+      let modules_to_reload = modules_to_reload
+        .into_iter()
+        .map(|specifier| (specifier, ModuleKind::Esm))
+        .collect::<Vec<_>>();
+
       Ok((paths_to_watch, modules_to_reload))
     }
     .map(move |result| {
@@ -1383,7 +3312,6 @@ pub async fn run_tests_with_watch(
 
   let operation = |modules_to_reload: Vec<(ModuleSpecifier, ModuleKind)>| {
     let flags = flags.clone();
-    let filter = test_flags.filter.clone();
     let include = include.clone();
     let ignore = ignore.clone();
     let lib = lib.clone();
@@ -1404,45 +3332,51 @@ pub async fn run_tests_with_watch(
       })
       .cloned()
       .collect::<Vec<(ModuleSpecifier, TestMode)>>();
+      let specifiers_with_mode = match &test_flags.shard {
+        Some(shard) => shard_specifiers(specifiers_with_mode, shard),
+        None => specifiers_with_mode,
+      };
 
-      check_specifiers(
-        &ps,
-        permissions.clone(),
-        specifiers_with_mode.clone(),
-        lib,
-      )
-      .await?;
+      let setup_specifier = resolve_setup_specifier(&ps, &test_flags)?;
+      let mut specifiers_to_check = specifiers_with_mode.clone();
+      if let Some(specifier) = &setup_specifier {
+        specifiers_to_check.push((specifier.clone(), TestMode::Executable));
+      }
+      check_specifiers(&ps, permissions.clone(), specifiers_to_check, lib)
+        .await?;
 
       if test_flags.no_run {
         return Ok(());
       }
 
-      test_specifiers(
-        ps,
-        permissions.clone(),
-        specifiers_with_mode,
-        TestSpecifierOptions {
-          compat_mode: flags.compat,
-          concurrent_jobs: test_flags.concurrent_jobs,
-          fail_fast: test_flags.fail_fast,
-          filter: filter.clone(),
-          shuffle: test_flags.shuffle,
-          trace_ops: test_flags.trace_ops,
-        },
-      )
-      .await?;
+      let setup = match setup_specifier {
+        Some(specifier) => Some(
+          GlobalSetup::start(ps.clone(), permissions.clone(), specifier)
+            .await?,
+        ),
+        None => None,
+      };
 
-      Ok(())
+      let options = TestRunOptions {
+        quarantine: resolve_quarantine(&ps)?,
+        ..TestRunOptions::new(&flags, &test_flags)
+      };
+      let result =
+        test_specifiers(ps, permissions.clone(), specifiers_with_mode, options)
+          .await;
+
+      if let Some(setup) = setup {
+        setup.teardown().await?;
+      }
+
+      result
     }
   };
 
   file_watcher::watch_func(
     resolver,
     operation,
-    file_watcher::PrintConfig {
-      job_name: "Test".to_string(),
-      clear_screen: !flags.no_clear_screen,
-    },
+    file_watcher::WatcherConfig::new("Test", &flags)?,
   )
   .await?;
 
@@ -1467,11 +3401,20 @@ impl Clone for TestEventSender {
 
 impl TestEventSender {
   pub fn new(sender: UnboundedSender<TestEvent>) -> Self {
+    Self::with_output_origin(sender, None)
+  }
+
+  /// Creates a sender with its own output pipes, whose output is sent as
+  /// `TestEvent::CapturedOutput` of `origin` if given.
+  fn with_output_origin(
+    sender: UnboundedSender<TestEvent>,
+    origin: Option<String>,
+  ) -> Self {
     let (stdout_reader, stdout_writer) = os_pipe::pipe().unwrap();
     let (stderr_reader, stderr_writer) = os_pipe::pipe().unwrap();
 
-    start_output_redirect_thread(stdout_reader, sender.clone());
-    start_output_redirect_thread(stderr_reader, sender.clone());
+    start_output_redirect_thread(stdout_reader, sender.clone(), origin.clone());
+    start_output_redirect_thread(stderr_reader, sender.clone(), origin);
 
     Self {
       sender,
@@ -1525,6 +3468,7 @@ fn pipe_writer_to_file(writer: os_pipe::PipeWriter) -> std::fs::File {
 fn start_output_redirect_thread(
   mut pipe_reader: os_pipe::PipeReader,
   sender: UnboundedSender<TestEvent>,
+  origin: Option<String>,
 ) {
   tokio::task::spawn_blocking(move || loop {
     let mut buffer = [0; 512];
@@ -1533,10 +3477,12 @@ fn start_output_redirect_thread(
       Ok(size) => size,
     };
 
-    if sender
-      .send(TestEvent::Output(buffer[0..size].to_vec()))
-      .is_err()
-    {
+    let output = buffer[0..size].to_vec();
+    let event = match &origin {
+      Some(origin) => TestEvent::CapturedOutput(origin.clone(), output),
+      None => TestEvent::Output(output),
+    };
+    if sender.send(event).is_err() {
       break;
     }
   });
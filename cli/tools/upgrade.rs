@@ -2,13 +2,18 @@
 
 //! This module provides feature to upgrade deno executable
 
+use crate::colors;
+use crate::flags::UpgradeChannel;
 use crate::flags::UpgradeFlags;
+use deno_core::anyhow::anyhow;
 use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
 use deno_core::futures::StreamExt;
 use deno_runtime::deno_fetch::reqwest;
 use deno_runtime::deno_fetch::reqwest::Client;
 use once_cell::sync::Lazy;
+use ring::signature;
 use semver_parser::version::parse as semver_parse;
 use std::env;
 use std::fs;
@@ -21,6 +26,12 @@ static ARCHIVE_NAME: Lazy<String> =
   Lazy::new(|| format!("deno-{}.zip", env!("TARGET")));
 
 const RELEASE_URL: &str = "https://github.com/denoland/deno/releases";
+const DL_URL: &str = "https://dl.deno.land";
+
+/// The base64 encoded Ed25519 public key that release archives are signed
+/// with. It is pinned when the executable is built, so that a signature
+/// served next to the archive can't vouch for a tampered download.
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("DENO_RELEASE_PUBLIC_KEY");
 
 pub async fn upgrade(upgrade_flags: UpgradeFlags) -> Result<(), AnyError> {
   let old_exe_path = std::env::current_exe()?;
@@ -41,19 +52,13 @@ pub async fn upgrade(upgrade_flags: UpgradeFlags) -> Result<(), AnyError> {
   }
 
   let client = client_builder.build()?;
+  let channel = upgrade_flags.channel;
 
   let install_version = match upgrade_flags.version {
     Some(passed_version) => {
-      if upgrade_flags.canary
-        && !regex::Regex::new("^[0-9a-f]{40}$")?.is_match(&passed_version)
-      {
-        bail!("Invalid commit hash passed");
-      } else if !upgrade_flags.canary && semver_parse(&passed_version).is_err()
-      {
-        bail!("Invalid semver passed");
-      }
+      validate_version(channel, &passed_version)?;
 
-      let current_is_passed = if upgrade_flags.canary {
+      let current_is_passed = if channel == UpgradeChannel::Canary {
         crate::version::GIT_COMMIT_HASH == passed_version
       } else if !crate::version::is_canary() {
         crate::version::deno() == passed_version
@@ -72,13 +77,13 @@ pub async fn upgrade(upgrade_flags: UpgradeFlags) -> Result<(), AnyError> {
       }
     }
     None => {
-      let latest_version = if upgrade_flags.canary {
-        get_latest_canary_version(&client).await?
-      } else {
-        get_latest_release_version(&client).await?
+      let latest_version = match channel {
+        UpgradeChannel::Stable => get_latest_release_version(&client).await?,
+        UpgradeChannel::Canary => get_latest_canary_version(&client).await?,
+        UpgradeChannel::Rc => get_latest_rc_version(&client).await?,
       };
 
-      let current_is_most_recent = if upgrade_flags.canary {
+      let current_is_most_recent = if channel == UpgradeChannel::Canary {
         let mut latest_hash = latest_version.clone();
         latest_hash.truncate(7);
         crate::version::GIT_COMMIT_HASH == latest_hash
@@ -106,19 +111,26 @@ pub async fn upgrade(upgrade_flags: UpgradeFlags) -> Result<(), AnyError> {
     }
   };
 
-  let download_url = if upgrade_flags.canary {
-    format!(
-      "https://dl.deno.land/canary/{}/{}",
-      install_version, *ARCHIVE_NAME
-    )
-  } else {
-    format!(
-      "{}/download/v{}/{}",
-      RELEASE_URL, install_version, *ARCHIVE_NAME
-    )
-  };
+  let download_url = get_download_url(channel, &install_version);
 
-  let archive_data = download_package(client, &download_url).await?;
+  let archive_data = download_package(&client, &download_url).await?;
+  if upgrade_flags.no_verify {
+    eprintln!(
+      "{} Skipping signature verification of the downloaded archive",
+      colors::yellow("Warning"),
+    );
+  } else {
+    let public_key = match RELEASE_PUBLIC_KEY {
+      Some(public_key) => public_key,
+      None => bail!(
+        "This executable was built without a release signing key, so the download can't be verified. Pass --no-verify to install it anyway"
+      ),
+    };
+    let signature_url = format!("{}.sig", download_url);
+    let signature_text = download_signature(&client, &signature_url).await?;
+    verify_signature(public_key, &archive_data, &signature_text)?;
+    println!("Verified archive signature");
+  }
 
   println!("Deno is upgrading to version {}", &install_version);
 
@@ -126,14 +138,24 @@ pub async fn upgrade(upgrade_flags: UpgradeFlags) -> Result<(), AnyError> {
   fs::set_permissions(&new_exe_path, permissions)?;
   check_exe(&new_exe_path)?;
 
-  if !upgrade_flags.dry_run {
-    match upgrade_flags.output {
-      Some(path) => {
-        fs::rename(&new_exe_path, &path)
-          .or_else(|_| fs::copy(&new_exe_path, &path).map(|_| ()))?;
-      }
-      None => replace_exe(&new_exe_path, &old_exe_path)?,
+  if upgrade_flags.dry_run {
+    let target_path = upgrade_flags.output.as_ref().unwrap_or(&old_exe_path);
+    println!(
+      "Would install {} version {} from {} to {}",
+      channel_name(channel),
+      install_version,
+      download_url,
+      target_path.display()
+    );
+    return Ok(());
+  }
+
+  match upgrade_flags.output {
+    Some(path) => {
+      fs::rename(&new_exe_path, &path)
+        .or_else(|_| fs::copy(&new_exe_path, &path).map(|_| ()))?;
     }
+    None => replace_exe(&new_exe_path, &old_exe_path)?,
   }
 
   println!("Upgraded successfully");
@@ -141,6 +163,51 @@ pub async fn upgrade(upgrade_flags: UpgradeFlags) -> Result<(), AnyError> {
   Ok(())
 }
 
+fn channel_name(channel: UpgradeChannel) -> &'static str {
+  match channel {
+    UpgradeChannel::Stable => "stable",
+    UpgradeChannel::Canary => "canary",
+    UpgradeChannel::Rc => "rc",
+  }
+}
+
+fn validate_version(
+  channel: UpgradeChannel,
+  version: &str,
+) -> Result<(), AnyError> {
+  match channel {
+    UpgradeChannel::Canary => {
+      if !regex::Regex::new("^[0-9a-f]{40}$")?.is_match(version) {
+        bail!("Invalid commit hash passed");
+      }
+    }
+    UpgradeChannel::Stable => {
+      if semver_parse(version).is_err() {
+        bail!("Invalid semver passed");
+      }
+    }
+    UpgradeChannel::Rc => match semver_parse(version) {
+      Ok(version) if !version.pre.is_empty() => {}
+      _ => bail!("Invalid release candidate version passed"),
+    },
+  }
+  Ok(())
+}
+
+fn get_download_url(channel: UpgradeChannel, version: &str) -> String {
+  match channel {
+    UpgradeChannel::Stable => {
+      format!("{}/download/v{}/{}", RELEASE_URL, version, *ARCHIVE_NAME)
+    }
+    UpgradeChannel::Canary => {
+      format!("{}/canary/{}/{}", DL_URL, version, *ARCHIVE_NAME)
+    }
+    UpgradeChannel::Rc => {
+      format!("{}/release/v{}/{}", DL_URL, version, *ARCHIVE_NAME)
+    }
+  }
+}
+
 async fn get_latest_release_version(
   client: &Client,
 ) -> Result<String, AnyError> {
@@ -161,7 +228,7 @@ async fn get_latest_canary_version(
   println!("Looking up latest version");
 
   let res = client
-    .get("https://dl.deno.land/canary-latest.txt")
+    .get(&format!("{}/canary-latest.txt", DL_URL))
     .send()
     .await?;
   let version = res.text().await?.trim().to_string();
@@ -169,8 +236,57 @@ async fn get_latest_canary_version(
   Ok(version)
 }
 
+async fn get_latest_rc_version(client: &Client) -> Result<String, AnyError> {
+  println!("Looking up latest version");
+
+  let res = client
+    .get(&format!("{}/release-rc-latest.txt", DL_URL))
+    .send()
+    .await?;
+  let version = res.text().await?.trim().trim_start_matches('v').to_string();
+
+  Ok(version)
+}
+
+/// Downloads the detached signature of an archive. A missing signature is an
+/// error, since it can't be told apart from one that was withheld.
+async fn download_signature(
+  client: &Client,
+  signature_url: &str,
+) -> Result<String, AnyError> {
+  let res = client.get(signature_url).send().await?;
+  if !res.status().is_success() {
+    bail!(
+      "No signature was published at {} ({}), aborting. Pass --no-verify to install the download without verifying it",
+      signature_url,
+      res.status()
+    );
+  }
+  Ok(res.text().await?)
+}
+
+/// Checks the archive against a base64 encoded Ed25519 signature made with
+/// the release key, whose base64 encoded public half is `public_key`.
+fn verify_signature(
+  public_key: &str,
+  archive_data: &[u8],
+  signature_text: &str,
+) -> Result<(), AnyError> {
+  let public_key = base64::decode(public_key.trim())
+    .context("The pinned release public key is not valid base64")?;
+  let signature = base64::decode(signature_text.trim())
+    .context("The published signature is not valid base64")?;
+  signature::UnparsedPublicKey::new(&signature::ED25519, public_key)
+    .verify(archive_data, &signature)
+    .map_err(|_| {
+      anyhow!(
+        "Signature verification of the downloaded archive failed, aborting"
+      )
+    })
+}
+
 async fn download_package(
-  client: Client,
+  client: &Client,
   download_url: &str,
 ) -> Result<Vec<u8>, AnyError> {
   println!("Checking {}", &download_url);
@@ -312,3 +428,40 @@ fn check_exe(exe_path: &Path) -> Result<(), AnyError> {
   assert!(output.status.success());
   Ok(())
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_validate_version() {
+    let hash = "e6685f0f01b8a11a5eaff020f5babcfde76b3038";
+    assert!(validate_version(UpgradeChannel::Stable, "1.11.5").is_ok());
+    assert!(validate_version(UpgradeChannel::Stable, hash).is_err());
+    assert!(validate_version(UpgradeChannel::Canary, hash).is_ok());
+    assert!(validate_version(UpgradeChannel::Canary, "1.11.5").is_err());
+    assert!(validate_version(UpgradeChannel::Rc, "1.23.0-rc.1").is_ok());
+    assert!(validate_version(UpgradeChannel::Rc, "1.23.0").is_err());
+  }
+
+  #[test]
+  fn test_verify_signature() {
+    use ring::signature::KeyPair;
+
+    let key_pair =
+      signature::Ed25519KeyPair::from_seed_unchecked(&[7; 32]).unwrap();
+    let public_key = base64::encode(key_pair.public_key());
+    let data = b"hello world";
+    let sig = base64::encode(key_pair.sign(data));
+    assert!(verify_signature(&public_key, data, &sig).is_ok());
+    assert!(verify_signature(&public_key, data, &format!("{}\n", sig)).is_ok());
+    assert!(verify_signature(&public_key, b"hello deno", &sig).is_err());
+    assert!(verify_signature(&public_key, data, "").is_err());
+    assert!(verify_signature(&public_key, data, "not base64!").is_err());
+
+    let other =
+      signature::Ed25519KeyPair::from_seed_unchecked(&[8; 32]).unwrap();
+    let other_sig = base64::encode(other.sign(data));
+    assert!(verify_signature(&public_key, data, &other_sig).is_err());
+  }
+}
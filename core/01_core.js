@@ -13,15 +13,19 @@
     Array,
     ArrayPrototypeFill,
     ArrayPrototypeMap,
+    ArrayPrototypePush,
     ErrorCaptureStackTrace,
     Promise,
     ObjectFromEntries,
+    ObjectKeys,
     MapPrototypeGet,
     MapPrototypeDelete,
     MapPrototypeSet,
     PromisePrototypeThen,
     PromisePrototypeFinally,
+    StringPrototypeEndsWith,
     StringPrototypeSlice,
+    SafeArrayIterator,
     ObjectAssign,
     SymbolFor,
   } = window.__bootstrap.primordials;
@@ -50,6 +54,27 @@
 
   let opCallTracingEnabled = false;
   const opCallTraces = new Map();
+  const resourceCreationTraces = new Map();
+  // Only the traces of the most recently created resources are kept, so that
+  // long running programs don't accumulate them. The ring holds their ids in
+  // the order they were traced.
+  const RESOURCE_TRACE_RING_SIZE = 4 * 1024;
+  const resourceTraceRing = ArrayPrototypeFill(
+    new Array(RESOURCE_TRACE_RING_SIZE),
+    null,
+  );
+  let nextResourceTraceIndex = 0;
+
+  function setResourceCreationTrace(rid, trace) {
+    const oldRid = resourceTraceRing[nextResourceTraceIndex];
+    if (oldRid !== null) {
+      MapPrototypeDelete(resourceCreationTraces, oldRid);
+    }
+    resourceTraceRing[nextResourceTraceIndex] = rid;
+    nextResourceTraceIndex = (nextResourceTraceIndex + 1) %
+      RESOURCE_TRACE_RING_SIZE;
+    MapPrototypeSet(resourceCreationTraces, rid, trace);
+  }
 
   function enableOpCallTracing() {
     opCallTracingEnabled = true;
@@ -108,12 +133,12 @@
     return promiseRing[idx] != NO_PROMISE;
   }
 
-  function opresolve() {
-    for (let i = 0; i < arguments.length; i += 2) {
-      const promiseId = arguments[i];
-      const res = arguments[i + 1];
-      const promise = getPromise(promiseId);
-      promise.resolve(res);
+  // Called once per event loop turn with the ids of the promises of all ops
+  // that completed (an Int32Array) and their results.
+  function opresolve(promiseIds, results) {
+    for (let i = 0; i < promiseIds.length; i++) {
+      const promise = getPromise(promiseIds[i]);
+      promise.resolve(results[i]);
     }
   }
 
@@ -149,6 +174,7 @@
 
   function opAsync(opName, ...args) {
     const promiseId = nextPromiseId++;
+    const firstRid = opCallTracingEnabled ? ops.op_next_rid() : 0;
     const maybeError = ops[opName](promiseId, ...args);
     // Handle sync error (e.g: error parsing args)
     if (maybeError) return unwrapOpResult(maybeError);
@@ -158,6 +184,17 @@
       // first 6 characters (the `Error\n` prefix) to get just the stack trace.
       const stack = StringPrototypeSlice(new Error().stack, 6);
       MapPrototypeSet(opCallTraces, promiseId, { opName, stack });
+      p = PromisePrototypeThen(p, (res) => {
+        // Other ops may have created resources while this one was pending, so
+        // only the resource ids returned by this op are attributed to it.
+        const lastRid = ops.op_next_rid();
+        for (const rid of new SafeArrayIterator(resultRids(res))) {
+          if (rid >= firstRid && rid < lastRid) {
+            setResourceCreationTrace(rid, { opName, stack });
+          }
+        }
+        return res;
+      });
       p = PromisePrototypeFinally(
         p,
         () => MapPrototypeDelete(opCallTraces, promiseId),
@@ -169,7 +206,41 @@
   }
 
   function opSync(opName, ...args) {
-    return unwrapOpResult(ops[opName](...args));
+    if (!opCallTracingEnabled) {
+      return unwrapOpResult(ops[opName](...args));
+    }
+    const firstRid = ops.op_next_rid();
+    const res = ops[opName](...args);
+    const lastRid = ops.op_next_rid();
+    if (lastRid > firstRid) {
+      // Sync ops run to completion, so all resources added in the meantime
+      // were created by this op.
+      const stack = StringPrototypeSlice(new Error().stack, 6);
+      for (let rid = firstRid; rid < lastRid; rid++) {
+        setResourceCreationTrace(rid, { opName, stack });
+      }
+    }
+    return unwrapOpResult(res);
+  }
+
+  // Returns the resource ids found in the result of an op: either the result
+  // itself or its own `rid`, `fooRid` properties.
+  function resultRids(res) {
+    if (typeof res === "number") {
+      return [res];
+    }
+    const rids = [];
+    if (res !== null && typeof res === "object") {
+      for (const key of new SafeArrayIterator(ObjectKeys(res))) {
+        if (
+          (key === "rid" || StringPrototypeEndsWith(key, "Rid")) &&
+          typeof res[key] === "number"
+        ) {
+          ArrayPrototypePush(rids, res[key]);
+        }
+      }
+    }
+    return rids;
   }
 
   function refOp(promiseId) {
@@ -214,6 +285,10 @@
     opSync("op_print", str, isErr);
   }
 
+  function heapSnapshot(path) {
+    return opAsync("op_heap_snapshot", path);
+  }
+
   function metrics() {
     const [aggregate, perOps] = opSync("op_metrics");
     aggregate.ops = ObjectFromEntries(ArrayPrototypeMap(
@@ -254,6 +329,7 @@
     print,
     resources,
     metrics,
+    heapSnapshot,
     registerErrorBuilder,
     registerErrorClass,
     opresolve,
@@ -264,6 +340,7 @@
     enableOpCallTracing,
     isOpCallTracingEnabled,
     opCallTraces,
+    resourceCreationTraces,
     refOp,
     unrefOp,
   });
@@ -334,15 +334,18 @@ pub extern "C" fn host_import_module_dynamically_callback(
     ImportAssertionsKind::DynamicImport,
   );
 
-  {
+  let module_type = {
+    let module_map_rc = JsRuntime::module_map(scope);
+    let module_map = module_map_rc.borrow();
     let tc_scope = &mut v8::TryCatch::new(scope);
-    validate_import_assertions(tc_scope, &assertions);
+    validate_import_assertions(tc_scope, &assertions, &module_map);
     if tc_scope.has_caught() {
       let e = tc_scope.exception().unwrap();
       resolver.reject(tc_scope, e);
+      return &*promise as *const _ as *mut _;
     }
-  }
-  let module_type = get_module_type_from_assertions(&assertions);
+    get_module_type_from_assertions(&assertions, &module_map)
+  };
 
   let resolver_handle = v8::Global::new(scope, resolver);
   {
@@ -434,6 +437,14 @@ pub extern "C" fn host_initialize_import_meta_object_callback(
   let main_key = v8::String::new(scope, "main").unwrap();
   let main_val = v8::Boolean::new(scope, info.main);
   meta.create_data_property(scope, main_key.into(), main_val.into());
+
+  for import_meta_cb in &module_map.import_meta_cbs {
+    for (key, value) in import_meta_cb(scope, &info.name, info.main) {
+      let key = v8::String::new(scope, &key).unwrap();
+      let value = v8::Local::new(scope, value);
+      meta.create_data_property(scope, key.into(), value);
+    }
+  }
 }
 
 pub extern "C" fn promise_reject_callback(message: v8::PromiseRejectMessage) {
@@ -1513,6 +1524,24 @@ fn is_proxy(
   rv.set(v8::Boolean::new(scope, args.get(0).is_proxy()).into())
 }
 
+/// Stub installed in place of ops listed in `RuntimeOptions::disabled_ops`.
+pub fn op_disabled(
+  scope: &mut v8::HandleScope,
+  args: v8::FunctionCallbackArguments,
+  _rv: v8::ReturnValue,
+) {
+  // SAFETY: ops are always registered with an External pointing to their
+  // OpCtx, which lives as long as the isolate.
+  let ctx = unsafe {
+    &*(v8::Local::<v8::External>::cast(args.data().unwrap()).value()
+      as *const OpCtx)
+  };
+  throw_type_error(
+    scope,
+    format!("Op '{}' is disabled in this runtime", ctx.decl.name),
+  );
+}
+
 pub fn throw_type_error(scope: &mut v8::HandleScope, message: impl AsRef<str>) {
   let message = v8::String::new(scope, message.as_ref()).unwrap();
   let exception = v8::Exception::type_error(scope, message);
@@ -0,0 +1,107 @@
+// Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
+
+//! Block-level precise coverage collection.
+//!
+//! The `v8` crate doesn't bind `v8::debug::Coverage` yet, so collection is
+//! driven through an in-process inspector session that is owned and polled
+//! by the runtime itself. Callers only deal with the typed results below.
+
+use crate::inspector::LocalInspectorSession;
+use crate::serde_json;
+use crate::serde_json::json;
+use anyhow::Error;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageRange {
+  /// Start byte index.
+  pub start_offset: usize,
+  /// End byte index.
+  pub end_offset: usize,
+  pub count: i64,
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionCoverage {
+  pub function_name: String,
+  pub ranges: Vec<CoverageRange>,
+  pub is_block_coverage: bool,
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptCoverage {
+  pub script_id: String,
+  pub url: String,
+  pub functions: Vec<FunctionCoverage>,
+}
+
+#[derive(Deserialize)]
+struct TakePreciseCoverageResult {
+  result: Vec<ScriptCoverage>,
+}
+
+/// A precise coverage collection in progress.
+pub(crate) struct PreciseCoverageSession {
+  session: LocalInspectorSession,
+}
+
+impl PreciseCoverageSession {
+  pub fn new(session: LocalInspectorSession) -> Self {
+    Self { session }
+  }
+
+  /// Enables block coverage with call counts.
+  pub async fn start(&mut self) -> Result<(), Error> {
+    self
+      .session
+      .post_message::<()>("Debugger.enable", None)
+      .await?;
+    self
+      .session
+      .post_message::<()>("Profiler.enable", None)
+      .await?;
+    self
+      .session
+      .post_message(
+        "Profiler.startPreciseCoverage",
+        Some(json!({
+          "callCount": true,
+          "detailed": true,
+          "allowTriggeredUpdates": false,
+        })),
+      )
+      .await?;
+    Ok(())
+  }
+
+  /// Returns the coverage collected since the last call and resets the
+  /// counters.
+  pub async fn take(&mut self) -> Result<Vec<ScriptCoverage>, Error> {
+    let value = self
+      .session
+      .post_message::<()>("Profiler.takePreciseCoverage", None)
+      .await?;
+    let result: TakePreciseCoverageResult = serde_json::from_value(value)?;
+    Ok(result.result)
+  }
+
+  pub async fn stop(&mut self) -> Result<(), Error> {
+    self
+      .session
+      .post_message::<()>("Profiler.stopPreciseCoverage", None)
+      .await?;
+    self
+      .session
+      .post_message::<()>("Profiler.disable", None)
+      .await?;
+    self
+      .session
+      .post_message::<()>("Debugger.disable", None)
+      .await?;
+    Ok(())
+  }
+}
@@ -0,0 +1,137 @@
+// Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
+
+//! Support for running the event loop deterministically, see
+//! `RuntimeOptions::deterministic_seed`.
+
+use futures::channel::oneshot;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Virtual time used by timers and `Date` when the runtime runs
+/// deterministically.
+///
+/// Time only moves forward when the event loop has nothing else to do, and
+/// then jumps straight to the earliest pending timer, or when the runtime
+/// sleeps synchronously.
+#[derive(Default)]
+pub struct VirtualClock {
+  now: u64,
+  next_seq: u64,
+  timers: BTreeMap<(u64, u64), oneshot::Sender<()>>,
+}
+
+impl VirtualClock {
+  /// Virtual time elapsed since the runtime was created.
+  pub fn now(&self) -> Duration {
+    Duration::from_millis(self.now)
+  }
+
+  /// Returns a receiver that resolves once virtual time has advanced by
+  /// `millis`. Timers with the same deadline fire in the order they were
+  /// created.
+  pub(crate) fn sleep(&mut self, millis: u64) -> oneshot::Receiver<()> {
+    let (sender, receiver) = oneshot::channel();
+    let seq = self.next_seq;
+    self.next_seq += 1;
+    self
+      .timers
+      .insert((self.now.saturating_add(millis), seq), sender);
+    receiver
+  }
+
+  /// Moves virtual time forward by `millis`, as a blocking sleep would, and
+  /// fires every timer that became due.
+  pub fn advance_by(&mut self, millis: u64) {
+    self.advance_to(self.now.saturating_add(millis));
+  }
+
+  /// Advances to the earliest deadline and fires every timer due at it.
+  /// Timers that were canceled in the meantime are skipped. Returns `false`
+  /// if there are no timers left.
+  pub(crate) fn advance(&mut self) -> bool {
+    self.timers.retain(|_, sender| !sender.is_canceled());
+    let deadline = match self.timers.keys().next() {
+      Some((deadline, _)) => *deadline,
+      None => return false,
+    };
+    self.advance_to(deadline);
+    true
+  }
+
+  fn advance_to(&mut self, time: u64) {
+    self.now = time;
+    let later = match time.checked_add(1) {
+      Some(after) => self.timers.split_off(&(after, 0)),
+      None => BTreeMap::new(),
+    };
+    for (_, sender) in std::mem::replace(&mut self.timers, later) {
+      let _ = sender.send(());
+    }
+  }
+}
+
+/// Replaces the global `Date` so that `Date.now()`, `Date()` and `new Date()`
+/// read the [`VirtualClock`], counting from the Unix epoch. Code that
+/// captured the original `Date.now` before, like the primordials, keeps
+/// reading real time.
+pub(crate) const VIRTUAL_DATE_JS: &str = r#"
+((globalThis) => {
+  const OriginalDate = globalThis.Date;
+  const now = () => Deno.core.opSync("op_virtual_time");
+  function Date(...args) {
+    if (new.target === undefined) {
+      return new OriginalDate(now()).toString();
+    }
+    return Reflect.construct(
+      OriginalDate,
+      args.length === 0 ? [now()] : args,
+      new.target,
+    );
+  }
+  Object.setPrototypeOf(Date, OriginalDate);
+  Object.defineProperty(Date, "prototype", {
+    value: OriginalDate.prototype,
+    writable: false,
+  });
+  Object.defineProperty(OriginalDate.prototype, "constructor", {
+    value: Date,
+    writable: true,
+    enumerable: false,
+    configurable: true,
+  });
+  Object.defineProperty(Date, "now", {
+    value: now,
+    writable: true,
+    enumerable: false,
+    configurable: true,
+  });
+  globalThis.Date = Date;
+})(globalThis);
+"#;
+
+/// Shuffles batches of op completions with a seeded PRNG (splitmix64), so
+/// every seed explores one reproducible interleaving.
+pub(crate) struct OpShuffler {
+  state: u64,
+}
+
+impl OpShuffler {
+  pub fn new(seed: u64) -> Self {
+    Self { state: seed }
+  }
+
+  fn next(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+  }
+
+  pub fn shuffle<T>(&mut self, items: &mut [T]) {
+    for i in (1..items.len()).rev() {
+      let j = (self.next() % (i as u64 + 1)) as usize;
+      items.swap(i, j);
+    }
+  }
+}
@@ -24,4 +24,23 @@ fn main() {
   runtime
     .execute_script("<usage>", r#"Deno.core.print("I'm broken")"#)
     .unwrap();
+
+  // Alternatively, ops can be disabled centrally when building the runtime,
+  // in which case calling them throws a descriptive `TypeError`.
+  let mut runtime = JsRuntime::new(RuntimeOptions {
+    disabled_ops: vec!["op_resources"],
+    ..Default::default()
+  });
+  runtime
+    .execute_script(
+      "<usage>",
+      r#"
+      try {
+        Deno.core.resources();
+      } catch (e) {
+        Deno.core.print(`${e.message}\n`, true);
+      }
+      "#,
+    )
+    .unwrap();
 }
@@ -9,6 +9,7 @@ pub type OpFnRef = v8::FunctionCallback;
 pub type OpMiddlewareFn = dyn Fn(OpDecl) -> OpDecl;
 pub type OpStateFn = dyn Fn(&mut OpState) -> Result<(), Error>;
 pub type OpEventLoopFn = dyn Fn(&mut OpState, &mut Context) -> bool;
+pub type OpTeardownFn = dyn FnOnce(&mut OpState);
 
 #[derive(Clone, Copy)]
 pub struct OpDecl {
@@ -36,6 +37,7 @@ pub struct Extension {
   opstate_fn: Option<Box<OpStateFn>>,
   middleware_fn: Option<Box<OpMiddlewareFn>>,
   event_loop_middleware: Option<Box<OpEventLoopFn>>,
+  teardown_fn: Option<Box<OpTeardownFn>>,
   initialized: bool,
   enabled: bool,
 }
@@ -100,6 +102,12 @@ impl Extension {
       .unwrap_or(false)
   }
 
+  /// Called when the owning JsRuntime is dropped, before the isolate and its
+  /// op-state are torn down.
+  pub fn take_teardown(&mut self) -> Option<Box<OpTeardownFn>> {
+    self.teardown_fn.take()
+  }
+
   pub fn enabled(self, enabled: bool) -> Self {
     Self { enabled, ..self }
   }
@@ -117,6 +125,7 @@ pub struct ExtensionBuilder {
   state: Option<Box<OpStateFn>>,
   middleware: Option<Box<OpMiddlewareFn>>,
   event_loop_middleware: Option<Box<OpEventLoopFn>>,
+  teardown: Option<Box<OpTeardownFn>>,
 }
 
 impl ExtensionBuilder {
@@ -154,6 +163,17 @@ impl ExtensionBuilder {
     self
   }
 
+  /// Registers a hook that runs once when the `JsRuntime` is dropped. Use it
+  /// to close native handles, join background threads or flush buffers held
+  /// in the op-state, rather than relying on the drop order of its contents.
+  pub fn teardown<F>(&mut self, teardown_fn: F) -> &mut Self
+  where
+    F: FnOnce(&mut OpState) + 'static,
+  {
+    self.teardown = Some(Box::new(teardown_fn));
+    self
+  }
+
   pub fn build(&mut self) -> Extension {
     let js_files = Some(std::mem::take(&mut self.js));
     let ops = Some(std::mem::take(&mut self.ops));
@@ -163,6 +183,7 @@ impl ExtensionBuilder {
       opstate_fn: self.state.take(),
       middleware_fn: self.middleware.take(),
       event_loop_middleware: self.event_loop_middleware.take(),
+      teardown_fn: self.teardown.take(),
       initialized: false,
       enabled: true,
     }
@@ -326,6 +326,18 @@ impl JsRuntimeInspector {
     }
   }
 
+  /// This function blocks the thread until at least one inspector client has
+  /// established a websocket connection and sent the
+  /// "Runtime.runIfWaitingForDebugger" message. Unlike
+  /// `wait_for_session_and_break_on_next_statement()`, execution is not paused
+  /// afterwards.
+  pub fn wait_for_session(&mut self) {
+    self.flags.get_mut().waiting_for_session = true;
+    while self.flags.get_mut().waiting_for_session {
+      let _ = self.poll_sessions(None).unwrap();
+    }
+  }
+
   /// Obtain a sender for proxy channels.
   pub fn get_session_sender(&self) -> UnboundedSender<InspectorSessionProxy> {
     self.new_session_tx.clone()
@@ -645,10 +657,53 @@ impl LocalInspectorSession {
     }
   }
 
+  /// Create a local session by handing a proxy to the inspector's session
+  /// sender. Unlike `JsRuntimeInspector::create_local_session` this doesn't
+  /// need a reference to the inspector, which makes it usable from ops. The
+  /// session is established the next time the inspector is polled.
+  pub fn connect(
+    session_sender: &UnboundedSender<InspectorSessionProxy>,
+  ) -> Result<Self, Error> {
+    let (outbound_tx, outbound_rx) = mpsc::unbounded();
+    let (inbound_tx, inbound_rx) = mpsc::unbounded();
+    let proxy = InspectorSessionProxy {
+      tx: outbound_tx,
+      rx: inbound_rx,
+    };
+    session_sender
+      .unbounded_send(proxy)
+      .map_err(|_| generic_error("Inspector is not available"))?;
+    Ok(Self::new(inbound_tx, outbound_rx))
+  }
+
   pub fn notifications(&mut self) -> Vec<Value> {
     self.notification_queue.split_off(0)
   }
 
+  /// Takes a V8 heap snapshot and returns it in the `.heapsnapshot` format
+  /// understood by Chrome DevTools.
+  pub async fn take_heap_snapshot(&mut self) -> Result<String, Error> {
+    self.post_message::<()>("HeapProfiler.enable", None).await?;
+    let result = self
+      .post_message(
+        "HeapProfiler.takeHeapSnapshot",
+        Some(json!({ "reportProgress": false })),
+      )
+      .await;
+    // Chunks arrive as notifications while the snapshot is being taken.
+    let snapshot = self
+      .notifications()
+      .into_iter()
+      .filter(|n| n["method"] == "HeapProfiler.addHeapSnapshotChunk")
+      .filter_map(|n| n["params"]["chunk"].as_str().map(ToOwned::to_owned))
+      .collect::<String>();
+    self
+      .post_message::<()>("HeapProfiler.disable", None)
+      .await?;
+    result?;
+    Ok(snapshot)
+  }
+
   pub async fn post_message<T: serde::Serialize>(
     &mut self,
     method: &str,
@@ -71,6 +71,10 @@ declare namespace Deno {
     /** Get heap stats for current isolate/worker */
     function heapStats(): Record<string, number>;
 
+    /** Write a V8 heap snapshot of the current isolate/worker to `path`, in
+     * the `.heapsnapshot` format understood by Chrome DevTools. */
+    function heapSnapshot(path: string): Promise<void>;
+
     /** Encode a string to its Uint8Array representation. */
     function encode(input: string): Uint8Array;
 
@@ -164,5 +168,13 @@ declare namespace Deno {
      * enabled.
      */
     const opCallTraces: Map<number, OpCallTrace>;
+
+    /**
+     * A map containing traces of where resources were created, keyed by
+     * resource id. The trace is that of the op call that returned the resource.
+     * Only the traces of the last 4096 resources created are kept. Tracing only
+     * occurs when `Deno.core.enableOpCallTracing()` was previously enabled.
+     */
+    const resourceCreationTraces: Map<number, OpCallTrace>;
   }
 }
@@ -2,12 +2,15 @@
 mod async_cancel;
 mod async_cell;
 mod bindings;
+mod coverage;
+mod deterministic;
 pub mod error;
 mod error_codes;
 mod extensions;
 mod flags;
 mod gotham_state;
 mod inspector;
+mod message_channel;
 mod module_specifier;
 mod modules;
 mod normalize_path;
@@ -16,6 +19,7 @@ mod ops_builtin;
 mod ops_metrics;
 mod resources;
 mod runtime;
+mod runtime_pool;
 mod source_map;
 
 // Re-exports
@@ -46,6 +50,10 @@ pub use crate::async_cell::AsyncRefCell;
 pub use crate::async_cell::AsyncRefFuture;
 pub use crate::async_cell::RcLike;
 pub use crate::async_cell::RcRef;
+pub use crate::coverage::CoverageRange;
+pub use crate::coverage::FunctionCoverage;
+pub use crate::coverage::ScriptCoverage;
+pub use crate::deterministic::VirtualClock;
 pub use crate::extensions::Extension;
 pub use crate::extensions::ExtensionBuilder;
 pub use crate::extensions::OpDecl;
@@ -56,6 +64,13 @@ pub use crate::inspector::InspectorMsgKind;
 pub use crate::inspector::InspectorSessionProxy;
 pub use crate::inspector::JsRuntimeInspector;
 pub use crate::inspector::LocalInspectorSession;
+pub use crate::message_channel::message_channel;
+pub use crate::message_channel::op_message_channel_create;
+pub use crate::message_channel::op_message_channel_read;
+pub use crate::message_channel::op_message_channel_write;
+pub use crate::message_channel::ChannelMessage;
+pub use crate::message_channel::MessageChannelEnd;
+pub use crate::message_channel::MessageChannelResource;
 pub use crate::module_specifier::resolve_import;
 pub use crate::module_specifier::resolve_path;
 pub use crate::module_specifier::resolve_url;
@@ -63,9 +78,13 @@ pub use crate::module_specifier::resolve_url_or_path;
 pub use crate::module_specifier::ModuleResolutionError;
 pub use crate::module_specifier::ModuleSpecifier;
 pub use crate::module_specifier::DUMMY_SPECIFIER;
+pub use crate::modules::CustomModuleEvaluationCb;
 pub use crate::modules::FsModuleLoader;
+pub use crate::modules::ImportMetaCb;
 pub use crate::modules::ModuleId;
 pub use crate::modules::ModuleLoader;
+pub use crate::modules::ModuleMapData;
+pub use crate::modules::ModuleRecord;
 pub use crate::modules::ModuleSource;
 pub use crate::modules::ModuleSourceFuture;
 pub use crate::modules::ModuleType;
@@ -81,10 +100,18 @@ pub use crate::ops::OpResult;
 pub use crate::ops::OpState;
 pub use crate::ops::PromiseId;
 pub use crate::ops_builtin::op_close;
+pub use crate::ops_builtin::op_coverage_start;
+pub use crate::ops_builtin::op_coverage_stop;
+pub use crate::ops_builtin::op_coverage_take;
 pub use crate::ops_builtin::op_print;
+pub use crate::ops_builtin::op_profile_start;
+pub use crate::ops_builtin::op_profile_stop;
 pub use crate::ops_builtin::op_resources;
+pub use crate::ops_builtin::op_sleep;
+pub use crate::ops_builtin::op_timer_handle;
 pub use crate::ops_builtin::op_void_async;
 pub use crate::ops_builtin::op_void_sync;
+pub use crate::ops_builtin::TimerHandle;
 pub use crate::ops_metrics::OpsTracker;
 pub use crate::resources::AsyncResult;
 pub use crate::resources::Resource;
@@ -93,12 +120,19 @@ pub use crate::resources::ResourceTable;
 pub use crate::runtime::CompiledWasmModuleStore;
 pub use crate::runtime::CrossIsolateStore;
 pub use crate::runtime::GetErrorClassFn;
+pub use crate::runtime::HeapLimits;
 pub use crate::runtime::JsErrorCreateFn;
 pub use crate::runtime::JsRealm;
 pub use crate::runtime::JsRuntime;
+pub use crate::runtime::MicrotaskPolicy;
+pub use crate::runtime::NearHeapLimitAction;
+pub use crate::runtime::NearHeapLimitFn;
 pub use crate::runtime::RuntimeOptions;
 pub use crate::runtime::SharedArrayBufferStore;
 pub use crate::runtime::Snapshot;
+pub use crate::runtime::UncaughtExceptionCb;
+pub use crate::runtime::UnhandledRejectionCb;
+pub use crate::runtime_pool::JsRuntimePool;
 pub use crate::source_map::SourceMapGetter;
 pub use deno_ops::op;
 
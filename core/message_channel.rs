@@ -0,0 +1,142 @@
+// Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
+
+//! Entangled pairs of channel ends that carry serialized values between
+//! isolates, which may live on different threads.
+//!
+//! Values are serialized with `Deno.core.serialize()`. Transferred
+//! `ArrayBuffer`s travel through the `SharedArrayBufferStore`, so both
+//! runtimes must be created with the same store.
+
+use crate::futures::channel::mpsc;
+use crate::futures::StreamExt;
+use crate::AsyncRefCell;
+use crate::CancelFuture;
+use crate::CancelHandle;
+use crate::OpState;
+use crate::RcRef;
+use crate::Resource;
+use crate::ResourceId;
+use crate::ZeroCopyBuf;
+use anyhow::Error;
+use deno_ops::op;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A serialized value along with the `SharedArrayBufferStore` ids of the
+/// `ArrayBuffer`s transferred with it.
+#[derive(Debug)]
+pub struct ChannelMessage {
+  pub data: Vec<u8>,
+  pub transferred_array_buffers: Vec<u32>,
+}
+
+/// One end of a message channel. Can be sent to another thread and turned
+/// into a resource of the runtime living there with
+/// [`MessageChannelResource::new`].
+pub struct MessageChannelEnd {
+  tx: mpsc::UnboundedSender<ChannelMessage>,
+  rx: mpsc::UnboundedReceiver<ChannelMessage>,
+}
+
+/// Creates a pair of entangled channel ends. Messages written to one end are
+/// read from the other.
+pub fn message_channel() -> (MessageChannelEnd, MessageChannelEnd) {
+  let (tx1, rx2) = mpsc::unbounded();
+  let (tx2, rx1) = mpsc::unbounded();
+  (
+    MessageChannelEnd { tx: tx1, rx: rx1 },
+    MessageChannelEnd { tx: tx2, rx: rx2 },
+  )
+}
+
+pub struct MessageChannelResource {
+  tx: mpsc::UnboundedSender<ChannelMessage>,
+  rx: AsyncRefCell<mpsc::UnboundedReceiver<ChannelMessage>>,
+  cancel: CancelHandle,
+}
+
+impl MessageChannelResource {
+  pub fn new(end: MessageChannelEnd) -> Self {
+    Self {
+      tx: end.tx,
+      rx: AsyncRefCell::new(end.rx),
+      cancel: CancelHandle::new(),
+    }
+  }
+
+  /// Returns `false` if the other end has been closed.
+  pub fn write(&self, message: ChannelMessage) -> bool {
+    self.tx.unbounded_send(message).is_ok()
+  }
+
+  /// Resolves to `None` once the other end has been closed and all messages
+  /// sent before that have been read.
+  pub async fn read(self: Rc<Self>) -> Result<Option<ChannelMessage>, Error> {
+    let mut rx = RcRef::map(&self, |r| &r.rx).borrow_mut().await;
+    let cancel = RcRef::map(&self, |r| &r.cancel);
+    Ok(rx.next().or_cancel(cancel).await?)
+  }
+}
+
+impl Resource for MessageChannelResource {
+  fn name(&self) -> Cow<str> {
+    "messageChannel".into()
+  }
+
+  fn close(self: Rc<Self>) {
+    self.cancel.cancel();
+  }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsChannelMessage {
+  data: ZeroCopyBuf,
+  transferred_array_buffers: Vec<u32>,
+}
+
+/// Creates an entangled pair of channel resources.
+#[op]
+pub fn op_message_channel_create(
+  state: &mut OpState,
+) -> Result<(ResourceId, ResourceId), Error> {
+  let (end1, end2) = message_channel();
+  let rid1 = state.resource_table.add(MessageChannelResource::new(end1));
+  let rid2 = state.resource_table.add(MessageChannelResource::new(end2));
+  Ok((rid1, rid2))
+}
+
+/// Writes a value serialized with `Deno.core.serialize()`. Returns `false`
+/// if the other end has been closed.
+#[op]
+pub fn op_message_channel_write(
+  state: &mut OpState,
+  rid: ResourceId,
+  data: ZeroCopyBuf,
+  transferred_array_buffers: Vec<u32>,
+) -> Result<bool, Error> {
+  let resource = state.resource_table.get::<MessageChannelResource>(rid)?;
+  Ok(resource.write(ChannelMessage {
+    data: data.to_vec(),
+    transferred_array_buffers,
+  }))
+}
+
+/// Reads the next message, or `null` once the other end has been closed.
+#[op]
+pub async fn op_message_channel_read(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+) -> Result<Option<JsChannelMessage>, Error> {
+  let resource = state
+    .borrow()
+    .resource_table
+    .get::<MessageChannelResource>(rid)?;
+  let message = resource.read().await?;
+  Ok(message.map(|message| JsChannelMessage {
+    data: message.data.into(),
+    transferred_array_buffers: message.transferred_array_buffers,
+  }))
+}
@@ -13,11 +13,16 @@ use futures::stream::Stream;
 use futures::stream::StreamFuture;
 use futures::stream::TryStreamExt;
 use log::debug;
+use serde::Deserialize;
+use serde::Serialize;
 use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::future::Future;
+use std::hash::Hasher;
 use std::pin::Pin;
 use std::rc::Rc;
 use std::task::Context;
@@ -37,15 +42,41 @@ fn strip_bom(text: &str) -> &str {
   }
 }
 
-const SUPPORTED_TYPE_ASSERTIONS: &[&str] = &["json"];
+/// Creates the exports of a module of a type that core doesn't handle itself,
+/// eg. one imported with `assert { type: "webassembly" }`. Receives the
+/// module specifier and the code returned by the `ModuleLoader`, and returns
+/// the module's exports by name.
+///
+/// The module map is borrowed while the callback runs, so it must not load
+/// or evaluate modules.
+pub type CustomModuleEvaluationCb =
+  dyn Fn(
+    &mut v8::HandleScope,
+    &str,
+    &str,
+  ) -> Result<Vec<(String, v8::Global<v8::Value>)>, Error>;
+
+/// Adds properties to the `import.meta` object of a module besides the
+/// built-in `url` and `main`, eg. `import.meta.env`. Receives the module
+/// specifier and whether it is the main module, and returns the properties
+/// by name. Called when the module accesses `import.meta` for the first time.
+///
+/// The module map is borrowed while the callback runs, so it must not load
+/// or evaluate modules.
+pub type ImportMetaCb = dyn Fn(
+  &mut v8::HandleScope,
+  &str,
+  bool,
+) -> Vec<(String, v8::Global<v8::Value>)>;
 
 /// Throws V8 exception if assertions are invalid
 pub(crate) fn validate_import_assertions(
   scope: &mut v8::HandleScope,
   assertions: &HashMap<String, String>,
+  module_map: &ModuleMap,
 ) {
   for (key, value) in assertions {
-    if key == "type" && !SUPPORTED_TYPE_ASSERTIONS.contains(&value.as_str()) {
+    if key == "type" && !module_map.is_supported_module_type(value) {
       let message = v8::String::new(
         scope,
         &format!("\"{}\" is not a valid module type.", value),
@@ -100,25 +131,27 @@ pub(crate) fn parse_import_assertions(
   assertions
 }
 
+/// Assertions must have been checked with `validate_import_assertions`, an
+/// unsupported type is treated as JavaScript.
 pub(crate) fn get_module_type_from_assertions(
   assertions: &HashMap<String, String>,
+  module_map: &ModuleMap,
 ) -> ModuleType {
-  assertions
-    .get("type")
-    .map(|ty| {
-      if ty == "json" {
-        ModuleType::Json
-      } else {
-        ModuleType::JavaScript
-      }
-    })
-    .unwrap_or(ModuleType::JavaScript)
+  match assertions.get("type").map(String::as_str) {
+    None => ModuleType::JavaScript,
+    Some("json") => ModuleType::Json,
+    Some(ty) => module_map
+      .custom_module_types
+      .get_key_value(ty)
+      .map(|(ty, _)| ModuleType::Other(*ty))
+      .unwrap_or(ModuleType::JavaScript),
+  }
 }
 
 // Clippy thinks the return value doesn't need to be an Option, it's unaware
 // of the mapping that MapFnFrom<F> does for ResolveModuleCallback.
 #[allow(clippy::unnecessary_wraps)]
-fn json_module_evaluation_steps<'a>(
+fn synthetic_module_evaluation_steps<'a>(
   context: v8::Local<'a, v8::Context>,
   module: v8::Local<v8::Module>,
 ) -> Option<v8::Local<'a, v8::Value>> {
@@ -130,20 +163,20 @@ fn json_module_evaluation_steps<'a>(
     .clone();
 
   let handle = v8::Global::<v8::Module>::new(tc_scope, module);
-  let value_handle = module_map
+  let exports = module_map
     .borrow_mut()
-    .json_value_store
+    .synthetic_module_exports
     .remove(&handle)
     .unwrap();
-  let value_local = v8::Local::new(tc_scope, value_handle);
-
-  let name = v8::String::new(tc_scope, "default").unwrap();
-  // This should never fail
-  assert!(
-    module.set_synthetic_module_export(tc_scope, name, value_local)
-      == Some(true)
-  );
-  assert!(!tc_scope.has_caught());
+  for (name, value) in exports {
+    let name = v8::Local::new(tc_scope, name);
+    let value = v8::Local::new(tc_scope, value);
+    // This should never fail
+    assert!(
+      module.set_synthetic_module_export(tc_scope, name, value) == Some(true)
+    );
+    assert!(!tc_scope.has_caught());
+  }
 
   // Since TLA is active we need to return a promise.
   let resolver = v8::PromiseResolver::new(tc_scope).unwrap();
@@ -162,6 +195,9 @@ fn json_module_evaluation_steps<'a>(
 pub enum ModuleType {
   JavaScript,
   Json,
+  /// A module of a type requested with a `type` import assertion that is
+  /// evaluated by a callback from `RuntimeOptions::custom_module_types`.
+  Other(&'static str),
 }
 
 impl std::fmt::Display for ModuleType {
@@ -169,6 +205,7 @@ impl std::fmt::Display for ModuleType {
     match self {
       Self::JavaScript => write!(f, "JavaScript"),
       Self::Json => write!(f, "JSON"),
+      Self::Other(module_type) => write!(f, "{}", module_type),
     }
   }
 }
@@ -246,6 +283,46 @@ pub trait ModuleLoader {
   ) -> Pin<Box<dyn Future<Output = Result<(), Error>>>> {
     async { Ok(()) }.boxed_local()
   }
+
+  /// Returns V8 code cache data previously passed to `set_code_cache` for
+  /// this module, provided it was created for a source with the same
+  /// `source_hash`. Compiling with it skips most of V8's parsing work.
+  ///
+  /// It's not required to implement this method.
+  fn get_code_cache(
+    &self,
+    _module_specifier: &ModuleSpecifier,
+    _source_hash: u64,
+  ) -> Option<Vec<u8>> {
+    None
+  }
+
+  /// Called when V8 rejected the data returned by `get_code_cache`, for
+  /// example because it was created by a different V8 version or with
+  /// different flags. Implementors should drop the stored data; fresh data
+  /// is passed to `set_code_cache` once the module has been evaluated.
+  ///
+  /// It's not required to implement this method.
+  fn discard_code_cache(
+    &self,
+    _module_specifier: &ModuleSpecifier,
+    _source_hash: u64,
+  ) {
+  }
+
+  /// Called with fresh code cache data once a module that was compiled
+  /// without cached data, or whose cached data was rejected, has been
+  /// evaluated. Implementors can persist it and hand it back from
+  /// `get_code_cache` on subsequent loads.
+  ///
+  /// It's not required to implement this method.
+  fn set_code_cache(
+    &self,
+    _module_specifier: &ModuleSpecifier,
+    _source_hash: u64,
+    _data: &[u8],
+  ) {
+  }
 }
 
 /// Placeholder structure used when creating
@@ -535,6 +612,14 @@ impl RecursiveModuleLoad {
           &module_source.module_url_found,
           &module_source.code,
         )?,
+        ModuleType::Other(module_type) => {
+          self.module_map_rc.borrow_mut().new_custom_module(
+            scope,
+            &module_source.module_url_found,
+            module_type,
+            &module_source.code,
+          )?
+        }
       },
     };
 
@@ -697,6 +782,38 @@ pub(crate) struct ModuleInfo {
   pub name: String,
   pub requests: Vec<ModuleRequest>,
   pub module_type: ModuleType,
+  pub source_hash: u64,
+}
+
+/// A module graph as seen by a `ModuleMap`, in a form that can be written to
+/// disk and handed back through `RuntimeOptions::module_map_data` on the next
+/// run. Import specifiers of modules whose source hasn't changed are then
+/// resolved from this data instead of calling `ModuleLoader::resolve`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleMapData {
+  pub modules: Vec<ModuleRecord>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleRecord {
+  pub name: String,
+  /// The module's `ModuleType`, as displayed.
+  pub module_type: String,
+  /// Hash of the module source the resolutions below were computed for.
+  pub source_hash: u64,
+  /// Import specifiers as written in the source, mapped to the specifiers
+  /// they resolved to.
+  pub resolutions: BTreeMap<String, String>,
+}
+
+/// Hash used to detect whether a module changed since its `ModuleRecord` was
+/// written. Only needs to be stable across runs of the same binary.
+fn hash_source(source: &str) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  hasher.write(source.as_bytes());
+  hasher.finish()
 }
 
 /// A symbolic module entity.
@@ -735,9 +852,25 @@ pub(crate) struct ModuleMap {
   pub(crate) pending_dynamic_imports:
     FuturesUnordered<StreamFuture<RecursiveModuleLoad>>,
 
-  // This store is used temporarly, to forward parsed JSON
-  // value from `new_json_module` to `json_module_evaluation_steps`
-  json_value_store: HashMap<v8::Global<v8::Module>, v8::Global<v8::Value>>,
+  // This store is used temporarly, to forward the exports of JSON and custom
+  // modules from their creation to `synthetic_module_evaluation_steps`
+  synthetic_module_exports: HashMap<
+    v8::Global<v8::Module>,
+    Vec<(v8::Global<v8::String>, v8::Global<v8::Value>)>,
+  >,
+  pub(crate) custom_module_types:
+    HashMap<&'static str, Box<CustomModuleEvaluationCb>>,
+  pub(crate) import_meta_cbs: Vec<Box<ImportMetaCb>>,
+
+  // Resolved import specifiers keyed by (referrer, specifier), and the ones
+  // restored from a previous run keyed by module name.
+  resolutions: HashMap<(String, String), ModuleSpecifier>,
+  restored: HashMap<String, ModuleRecord>,
+
+  // Modules compiled without code cache, waiting to be evaluated so their
+  // code cache can be handed to `ModuleLoader::set_code_cache`.
+  pending_code_caches:
+    Vec<(ModuleSpecifier, u64, v8::Global<v8::UnboundModuleScript>)>,
 }
 
 impl ModuleMap {
@@ -757,10 +890,52 @@ impl ModuleMap {
       dynamic_import_map: HashMap::new(),
       preparing_dynamic_imports: FuturesUnordered::new(),
       pending_dynamic_imports: FuturesUnordered::new(),
-      json_value_store: HashMap::new(),
+      synthetic_module_exports: HashMap::new(),
+      custom_module_types: HashMap::new(),
+      import_meta_cbs: vec![],
+      resolutions: HashMap::new(),
+      restored: HashMap::new(),
+      pending_code_caches: vec![],
     }
   }
 
+  /// Seeds the resolution cache with data from `serialize()` of a previous
+  /// run. Records for modules whose source has since changed are ignored.
+  pub(crate) fn restore(&mut self, data: ModuleMapData) {
+    self.restored = data
+      .modules
+      .into_iter()
+      .map(|record| (record.name.clone(), record))
+      .collect();
+  }
+
+  pub(crate) fn serialize(&self) -> ModuleMapData {
+    let mut resolutions_by_referrer =
+      HashMap::<&str, BTreeMap<String, String>>::new();
+    for ((referrer, specifier), resolved) in &self.resolutions {
+      resolutions_by_referrer
+        .entry(referrer.as_str())
+        .or_default()
+        .insert(specifier.clone(), resolved.to_string());
+    }
+    let mut modules = self
+      .info
+      .values()
+      .filter(|info| self.is_current(info))
+      .map(|info| ModuleRecord {
+        name: info.name.clone(),
+        module_type: info.module_type.to_string(),
+        source_hash: info.source_hash,
+        resolutions: resolutions_by_referrer
+          .get(info.name.as_str())
+          .cloned()
+          .unwrap_or_default(),
+      })
+      .collect::<Vec<_>>();
+    modules.sort_by(|a, b| a.name.cmp(&b.name));
+    ModuleMapData { modules }
+  }
+
   /// Get module id, following all aliases in case of module specifier
   /// that had been redirected.
   fn get_id(&self, name: &str, module_type: ModuleType) -> Option<ModuleId> {
@@ -783,7 +958,6 @@ impl ModuleMap {
     name: &str,
     source: &str,
   ) -> Result<ModuleId, ModuleError> {
-    let name_str = v8::String::new(scope, name).unwrap();
     let source_str = v8::String::new(scope, strip_bom(source)).unwrap();
 
     let tc_scope = &mut v8::TryCatch::new(scope);
@@ -798,22 +972,88 @@ impl ModuleMap {
       }
     };
 
-    let export_names = [v8::String::new(tc_scope, "default").unwrap()];
-    let module = v8::Module::create_synthetic_module(
+    let exports = vec![(
+      "default".to_string(),
+      v8::Global::<v8::Value>::new(tc_scope, parsed_json),
+    )];
+    let id = self.new_synthetic_module(
       tc_scope,
+      name,
+      ModuleType::Json,
+      exports,
+      hash_source(source),
+    );
+
+    Ok(id)
+  }
+
+  fn new_custom_module(
+    &mut self,
+    scope: &mut v8::HandleScope,
+    name: &str,
+    module_type: &'static str,
+    source: &str,
+  ) -> Result<ModuleId, ModuleError> {
+    let evaluate =
+      self.custom_module_types.get(module_type).ok_or_else(|| {
+        ModuleError::Other(generic_error(format!(
+          "\"{}\" is not a valid module type.",
+          module_type
+        )))
+      })?;
+    let exports = evaluate(scope, name, source).map_err(ModuleError::Other)?;
+    let id = self.new_synthetic_module(
+      scope,
+      name,
+      ModuleType::Other(module_type),
+      exports,
+      hash_source(source),
+    );
+    Ok(id)
+  }
+
+  fn new_synthetic_module(
+    &mut self,
+    scope: &mut v8::HandleScope,
+    name: &str,
+    module_type: ModuleType,
+    exports: Vec<(String, v8::Global<v8::Value>)>,
+    source_hash: u64,
+  ) -> ModuleId {
+    let name_str = v8::String::new(scope, name).unwrap();
+    let export_names = exports
+      .iter()
+      .map(|(export_name, _)| v8::String::new(scope, export_name).unwrap())
+      .collect::<Vec<_>>();
+    let module = v8::Module::create_synthetic_module(
+      scope,
       name_str,
       &export_names,
-      json_module_evaluation_steps,
+      synthetic_module_evaluation_steps,
     );
 
-    let handle = v8::Global::<v8::Module>::new(tc_scope, module);
-    let value_handle = v8::Global::<v8::Value>::new(tc_scope, parsed_json);
-    self.json_value_store.insert(handle.clone(), value_handle);
+    let handle = v8::Global::<v8::Module>::new(scope, module);
+    let exports = export_names
+      .into_iter()
+      .zip(exports.into_iter().map(|(_, value)| value))
+      .map(|(export_name, value)| (v8::Global::new(scope, export_name), value))
+      .collect();
+    self
+      .synthetic_module_exports
+      .insert(handle.clone(), exports);
 
-    let id =
-      self.create_module_info(name, ModuleType::Json, handle, false, vec![]);
+    self.create_module_info(
+      name,
+      module_type,
+      handle,
+      false,
+      vec![],
+      source_hash,
+    )
+  }
 
-    Ok(id)
+  pub(crate) fn is_supported_module_type(&self, module_type: &str) -> bool {
+    module_type == "json" || self.custom_module_types.contains_key(module_type)
   }
 
   // Create and compile an ES module.
@@ -824,15 +1064,43 @@ impl ModuleMap {
     name: &str,
     source: &str,
   ) -> Result<ModuleId, ModuleError> {
+    let source_hash = hash_source(source);
+    let maybe_specifier = ModuleSpecifier::parse(name).ok();
+    let maybe_code_cache = maybe_specifier
+      .as_ref()
+      .and_then(|specifier| self.loader.get_code_cache(specifier, source_hash));
+
     let name_str = v8::String::new(scope, name).unwrap();
     let source_str = v8::String::new(scope, source).unwrap();
 
     let origin = bindings::module_origin(scope, name_str);
-    let source = v8::script_compiler::Source::new(source_str, Some(&origin));
 
     let tc_scope = &mut v8::TryCatch::new(scope);
 
-    let maybe_module = v8::script_compiler::compile_module(tc_scope, source);
+    let (maybe_module, code_cache_rejected) = match &maybe_code_cache {
+      Some(code_cache) => {
+        let mut source = v8::script_compiler::Source::new_with_cached_data(
+          source_str,
+          Some(&origin),
+          v8::script_compiler::CachedData::new(code_cache),
+        );
+        let maybe_module = v8::script_compiler::compile_module2(
+          tc_scope,
+          &mut source,
+          v8::script_compiler::CompileOptions::ConsumeCodeCache,
+          v8::script_compiler::NoCacheReason::NoReason,
+        );
+        let rejected = source
+          .get_cached_data()
+          .map_or(false, |cached_data| cached_data.rejected());
+        (maybe_module, rejected)
+      }
+      None => {
+        let source =
+          v8::script_compiler::Source::new(source_str, Some(&origin));
+        (v8::script_compiler::compile_module(tc_scope, source), false)
+      }
+    };
 
     if tc_scope.has_caught() {
       assert!(maybe_module.is_none());
@@ -843,6 +1111,11 @@ impl ModuleMap {
 
     let module = maybe_module.unwrap();
 
+    let restored = self
+      .restored
+      .get(name)
+      .filter(|record| record.source_hash == source_hash);
+
     let mut requests: Vec<ModuleRequest> = vec![];
     let module_requests = module.get_module_requests();
     for i in 0..module_requests.length() {
@@ -864,19 +1137,29 @@ impl ModuleMap {
 
       // FIXME(bartomieju): there are no stack frames if exception
       // is thrown here
-      validate_import_assertions(tc_scope, &assertions);
+      validate_import_assertions(tc_scope, &assertions, self);
       if tc_scope.has_caught() {
         let exception = tc_scope.exception().unwrap();
         let exception = v8::Global::new(tc_scope, exception);
         return Err(ModuleError::Exception(exception));
       }
 
-      let module_specifier =
-        match self.loader.resolve(&import_specifier, name, false) {
+      let maybe_restored = restored
+        .and_then(|record| record.resolutions.get(&import_specifier))
+        .and_then(|resolved| ModuleSpecifier::parse(resolved).ok());
+      let module_specifier = match maybe_restored {
+        Some(s) => s,
+        None => match self.loader.resolve(&import_specifier, name, false) {
           Ok(s) => s,
           Err(e) => return Err(ModuleError::Other(e)),
-        };
-      let expected_module_type = get_module_type_from_assertions(&assertions);
+        },
+      };
+      self.resolutions.insert(
+        (name.to_string(), import_specifier),
+        module_specifier.clone(),
+      );
+      let expected_module_type =
+        get_module_type_from_assertions(&assertions, self);
       let request = ModuleRequest {
         specifier: module_specifier,
         expected_module_type,
@@ -895,6 +1178,21 @@ impl ModuleMap {
       }
     }
 
+    if let Some(specifier) = maybe_specifier {
+      if code_cache_rejected {
+        self.loader.discard_code_cache(&specifier, source_hash);
+      }
+      if maybe_code_cache.is_none() || code_cache_rejected {
+        // The unbound script has to be grabbed before the module is evaluated.
+        let unbound_module_script = module.get_unbound_module_script(tc_scope);
+        self.pending_code_caches.push((
+          specifier,
+          source_hash,
+          v8::Global::new(tc_scope, unbound_module_script),
+        ));
+      }
+    }
+
     let handle = v8::Global::<v8::Module>::new(tc_scope, module);
     let id = self.create_module_info(
       name,
@@ -902,6 +1200,7 @@ impl ModuleMap {
       handle,
       main,
       requests,
+      source_hash,
     );
 
     Ok(id)
@@ -914,6 +1213,7 @@ impl ModuleMap {
     handle: v8::Global<v8::Module>,
     main: bool,
     requests: Vec<ModuleRequest>,
+    source_hash: u64,
   ) -> ModuleId {
     let id = self.next_module_id;
     self.next_module_id += 1;
@@ -930,6 +1230,7 @@ impl ModuleMap {
         name: name.to_string(),
         requests,
         module_type,
+        source_hash,
       },
     );
 
@@ -960,6 +1261,83 @@ impl ModuleMap {
     );
   }
 
+  /// Whether `info` is the module currently registered under its name, as
+  /// opposed to a stale instance left behind by `invalidate`.
+  fn is_current(&self, info: &ModuleInfo) -> bool {
+    self.get_id(&info.name, info.module_type) == Some(info.id)
+  }
+
+  fn imports(&self, importer: &ModuleInfo, id: ModuleId) -> bool {
+    importer.requests.iter().any(|request| {
+      self.get_id(request.specifier.as_str(), request.expected_module_type)
+        == Some(id)
+    })
+  }
+
+  /// Unregisters the modules in `names` along with every module that
+  /// transitively imports them, so the next load fetches and compiles them
+  /// again. Already linked instances keep working with their old bindings.
+  ///
+  /// Returns the invalidated modules that aren't imported by any other
+  /// module; these are the ones that have to be loaded again to pick up the
+  /// changes.
+  pub(crate) fn invalidate(&mut self, names: &[&str]) -> Vec<ModuleSpecifier> {
+    let mut invalidated = HashSet::new();
+    let mut queue = self
+      .by_name
+      .keys()
+      .filter(|(name, _)| names.contains(&name.as_str()))
+      .filter_map(|(name, module_type)| self.get_id(name, module_type))
+      .collect::<Vec<_>>();
+    while let Some(id) = queue.pop() {
+      if !invalidated.insert(id) {
+        continue;
+      }
+      for info in self.info.values() {
+        if self.is_current(info) && self.imports(info, id) {
+          queue.push(info.id);
+        }
+      }
+    }
+
+    let mut roots = invalidated
+      .iter()
+      .filter(|id| {
+        !self
+          .info
+          .values()
+          .any(|info| self.is_current(info) && self.imports(info, **id))
+      })
+      .copied()
+      .collect::<Vec<_>>();
+    roots.sort_unstable();
+    let roots = roots
+      .into_iter()
+      .filter_map(|id| resolve_url(&self.info[&id].name).ok())
+      .collect();
+
+    let stale_keys = self
+      .by_name
+      .keys()
+      .filter(|(name, module_type)| {
+        self
+          .get_id(name, module_type)
+          .map_or(false, |id| invalidated.contains(&id))
+      })
+      .cloned()
+      .collect::<Vec<_>>();
+    for key in stale_keys {
+      self.by_name.remove(&key);
+    }
+    for id in &invalidated {
+      let name = &self.info[id].name;
+      self.restored.remove(name);
+      self.resolutions.retain(|(referrer, _), _| referrer != name);
+    }
+
+    roots
+  }
+
   #[cfg(test)]
   fn is_alias(&self, name: &str, module_type: ModuleType) -> bool {
     let cond = self.by_name.get(&(name.to_string(), module_type));
@@ -1048,6 +1426,22 @@ impl ModuleMap {
       .push(fut);
   }
 
+  /// Creates code cache for modules that were compiled without it and hands
+  /// it to the loader. Called right after module evaluation, so the cache
+  /// also covers functions that were compiled lazily by top-level code.
+  pub(crate) fn store_code_caches(&mut self, scope: &mut v8::HandleScope) {
+    for (specifier, source_hash, unbound_module_script) in
+      std::mem::take(&mut self.pending_code_caches)
+    {
+      let unbound_module_script = v8::Local::new(scope, unbound_module_script);
+      if let Some(code_cache) = unbound_module_script.create_code_cache() {
+        self
+          .loader
+          .set_code_cache(&specifier, source_hash, &code_cache);
+      }
+    }
+  }
+
   pub(crate) fn has_pending_dynamic_imports(&self) -> bool {
     !(self.preparing_dynamic_imports.is_empty()
       && self.pending_dynamic_imports.is_empty())
@@ -1061,12 +1455,18 @@ impl ModuleMap {
     referrer: &str,
     import_assertions: HashMap<String, String>,
   ) -> Option<v8::Local<'s, v8::Module>> {
-    let resolved_specifier = self
-      .loader
-      .resolve(specifier, referrer, false)
-      .expect("Module should have been already resolved");
+    let resolved_specifier = match self
+      .resolutions
+      .get(&(referrer.to_string(), specifier.to_string()))
+    {
+      Some(resolved) => resolved.clone(),
+      None => self
+        .loader
+        .resolve(specifier, referrer, false)
+        .expect("Module should have been already resolved"),
+    };
 
-    let module_type = get_module_type_from_assertions(&import_assertions);
+    let module_type = get_module_type_from_assertions(&import_assertions, self);
 
     if let Some(id) = self.get_id(resolved_specifier.as_str(), module_type) {
       if let Some(handle) = self.get_handle(id) {
@@ -1600,6 +2000,165 @@ import "/a.js";
     futures::executor::block_on(receiver).unwrap().unwrap();
   }
 
+  #[test]
+  fn import_assertions_static_and_dynamic() {
+    struct AssertionsLoader;
+
+    impl ModuleLoader for AssertionsLoader {
+      fn resolve(
+        &self,
+        specifier: &str,
+        referrer: &str,
+        _is_main: bool,
+      ) -> Result<ModuleSpecifier, Error> {
+        Ok(resolve_import(specifier, referrer)?)
+      }
+
+      fn load(
+        &self,
+        module_specifier: &ModuleSpecifier,
+        _maybe_referrer: Option<ModuleSpecifier>,
+        _is_dyn_import: bool,
+      ) -> Pin<Box<ModuleSourceFuture>> {
+        let (code, module_type) = match module_specifier.as_str() {
+          "file:///main.js" => (
+            r#"
+              import data from "./data.json" assert { type: "json" };
+              import { answer } from "./answer.txt" assert { type: "text" };
+              if (data.a !== 1) throw new Error("static json");
+              if (answer !== "42") throw new Error("static text");
+              const mod = await import("./data.json", {
+                assert: { type: "json" },
+              });
+              if (mod.default !== data) throw new Error("dynamic json");
+              let error;
+              try {
+                await import("./data.json", { assert: { type: "yaml" } });
+              } catch (e) {
+                error = e;
+              }
+              if (!(error instanceof TypeError)) {
+                throw new Error("unsupported type");
+              }
+            "#,
+            ModuleType::JavaScript,
+          ),
+          "file:///data.json" => (r#"{ "a": 1 }"#, ModuleType::Json),
+          "file:///answer.txt" => ("42\n", ModuleType::Other("text")),
+          _ => unreachable!(),
+        };
+        let module_source = ModuleSource {
+          module_url_specified: module_specifier.to_string(),
+          module_url_found: module_specifier.to_string(),
+          code: code.to_owned(),
+          module_type,
+        };
+        async move { Ok(module_source) }.boxed()
+      }
+    }
+
+    let mut custom_module_types: HashMap<
+      &'static str,
+      Box<CustomModuleEvaluationCb>,
+    > = HashMap::new();
+    custom_module_types.insert(
+      "text",
+      Box::new(
+        |scope: &mut v8::HandleScope, _specifier: &str, code: &str| {
+          let value: v8::Local<v8::Value> =
+            v8::String::new(scope, code.trim()).unwrap().into();
+          Ok(vec![("answer".to_string(), v8::Global::new(scope, value))])
+        },
+      ),
+    );
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      module_loader: Some(Rc::new(AssertionsLoader)),
+      custom_module_types,
+      ..Default::default()
+    });
+
+    let main_specifier = resolve_url("file:///main.js").unwrap();
+    let main_id_fut = runtime
+      .load_main_module(&main_specifier, None)
+      .boxed_local();
+    let main_id = futures::executor::block_on(main_id_fut).unwrap();
+    let receiver = runtime.mod_evaluate(main_id);
+    futures::executor::block_on(runtime.run_event_loop(false)).unwrap();
+    futures::executor::block_on(receiver).unwrap().unwrap();
+  }
+
+  #[test]
+  fn import_meta_cbs() {
+    struct MetaLoader;
+
+    impl ModuleLoader for MetaLoader {
+      fn resolve(
+        &self,
+        specifier: &str,
+        referrer: &str,
+        _is_main: bool,
+      ) -> Result<ModuleSpecifier, Error> {
+        Ok(resolve_import(specifier, referrer)?)
+      }
+
+      fn load(
+        &self,
+        module_specifier: &ModuleSpecifier,
+        _maybe_referrer: Option<ModuleSpecifier>,
+        _is_dyn_import: bool,
+      ) -> Pin<Box<ModuleSourceFuture>> {
+        let code = match module_specifier.as_str() {
+          "file:///main.js" => {
+            r#"
+              import { meta } from "./dep.js";
+              if (import.meta.env !== "test") throw new Error("env");
+              if (import.meta.isMain !== true) throw new Error("main");
+              if (import.meta.url !== "file:///main.js") throw new Error("url");
+              if (meta.isMain !== false) throw new Error("dep");
+            "#
+          }
+          "file:///dep.js" => "export const meta = import.meta;",
+          _ => unreachable!(),
+        };
+        let module_source = ModuleSource {
+          module_url_specified: module_specifier.to_string(),
+          module_url_found: module_specifier.to_string(),
+          code: code.to_owned(),
+          module_type: ModuleType::JavaScript,
+        };
+        async move { Ok(module_source) }.boxed()
+      }
+    }
+
+    let env: Box<ImportMetaCb> = Box::new(
+      |scope: &mut v8::HandleScope, _specifier: &str, _main: bool| {
+        let value: v8::Local<v8::Value> =
+          v8::String::new(scope, "test").unwrap().into();
+        vec![("env".to_string(), v8::Global::new(scope, value))]
+      },
+    );
+    let is_main: Box<ImportMetaCb> = Box::new(
+      |scope: &mut v8::HandleScope, _specifier: &str, main: bool| {
+        let value: v8::Local<v8::Value> = v8::Boolean::new(scope, main).into();
+        vec![("isMain".to_string(), v8::Global::new(scope, value))]
+      },
+    );
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      module_loader: Some(Rc::new(MetaLoader)),
+      import_meta_cbs: vec![env, is_main],
+      ..Default::default()
+    });
+
+    let main_specifier = resolve_url("file:///main.js").unwrap();
+    let main_id_fut = runtime
+      .load_main_module(&main_specifier, None)
+      .boxed_local();
+    let main_id = futures::executor::block_on(main_id_fut).unwrap();
+    let receiver = runtime.mod_evaluate(main_id);
+    futures::executor::block_on(runtime.run_event_loop(false)).unwrap();
+    futures::executor::block_on(receiver).unwrap().unwrap();
+  }
+
   #[test]
   fn dyn_import_err() {
     #[derive(Clone, Default)]
@@ -2228,4 +2787,275 @@ if (import.meta.url != 'file:///main_with_code.js') throw Error();
     let _ = runtime.mod_evaluate(side_id);
     futures::executor::block_on(runtime.run_event_loop(false)).unwrap();
   }
+
+  #[test]
+  fn module_map_data_skips_resolution() {
+    struct CountingLoader {
+      resolve_count: Arc<AtomicUsize>,
+    }
+
+    impl ModuleLoader for CountingLoader {
+      fn resolve(
+        &self,
+        specifier: &str,
+        referrer: &str,
+        _is_main: bool,
+      ) -> Result<ModuleSpecifier, Error> {
+        self.resolve_count.fetch_add(1, Ordering::Relaxed);
+        let s = resolve_import(specifier, referrer).unwrap();
+        Ok(s)
+      }
+
+      fn load(
+        &self,
+        module_specifier: &ModuleSpecifier,
+        _maybe_referrer: Option<ModuleSpecifier>,
+        _is_dyn_import: bool,
+      ) -> Pin<Box<ModuleSourceFuture>> {
+        let code = match module_specifier.as_str() {
+          "file:///main.js" => "import './dep.js';",
+          "file:///dep.js" => "export const a = 1;",
+          _ => unreachable!(),
+        };
+        let module_source = ModuleSource {
+          module_url_specified: module_specifier.to_string(),
+          module_url_found: module_specifier.to_string(),
+          code: code.to_owned(),
+          module_type: ModuleType::JavaScript,
+        };
+        async move { Ok(module_source) }.boxed()
+      }
+    }
+
+    let main_specifier = resolve_url("file:///main.js").unwrap();
+    let run = |module_map_data: Option<ModuleMapData>| {
+      let resolve_count = Arc::new(AtomicUsize::new(0));
+      let loader = Rc::new(CountingLoader {
+        resolve_count: resolve_count.clone(),
+      });
+      let mut runtime = JsRuntime::new(RuntimeOptions {
+        module_loader: Some(loader),
+        module_map_data,
+        ..Default::default()
+      });
+      let main_id_fut = runtime
+        .load_main_module(&main_specifier, None)
+        .boxed_local();
+      let main_id = futures::executor::block_on(main_id_fut).unwrap();
+      let _ = runtime.mod_evaluate(main_id);
+      futures::executor::block_on(runtime.run_event_loop(false)).unwrap();
+      (
+        runtime.serialize_module_map(),
+        resolve_count.load(Ordering::Relaxed),
+      )
+    };
+
+    let (data, first_count) = run(None);
+    assert_eq!(data.modules.len(), 2);
+    assert_eq!(
+      data.modules[1]
+        .resolutions
+        .get("./dep.js")
+        .map(String::as_str),
+      Some("file:///dep.js")
+    );
+
+    let (second_data, second_count) = run(Some(data.clone()));
+    assert_eq!(second_data, data);
+    assert!(second_count < first_count);
+  }
+
+  #[test]
+  fn code_cache_round_trip() {
+    #[derive(Default)]
+    struct CodeCacheLoader {
+      code_caches: RefCell<HashMap<(ModuleSpecifier, u64), Vec<u8>>>,
+      set_count: AtomicUsize,
+      discard_count: AtomicUsize,
+    }
+
+    impl ModuleLoader for CodeCacheLoader {
+      fn resolve(
+        &self,
+        specifier: &str,
+        referrer: &str,
+        _is_main: bool,
+      ) -> Result<ModuleSpecifier, Error> {
+        Ok(resolve_import(specifier, referrer)?)
+      }
+
+      fn load(
+        &self,
+        module_specifier: &ModuleSpecifier,
+        _maybe_referrer: Option<ModuleSpecifier>,
+        _is_dyn_import: bool,
+      ) -> Pin<Box<ModuleSourceFuture>> {
+        let module_source = ModuleSource {
+          module_url_specified: module_specifier.to_string(),
+          module_url_found: module_specifier.to_string(),
+          code: "function add(a, b) { return a + b; }\n\
+                 if (add(1, 2) !== 3) throw new Error();"
+            .to_owned(),
+          module_type: ModuleType::JavaScript,
+        };
+        async move { Ok(module_source) }.boxed()
+      }
+
+      fn get_code_cache(
+        &self,
+        module_specifier: &ModuleSpecifier,
+        source_hash: u64,
+      ) -> Option<Vec<u8>> {
+        self
+          .code_caches
+          .borrow()
+          .get(&(module_specifier.clone(), source_hash))
+          .cloned()
+      }
+
+      fn discard_code_cache(
+        &self,
+        module_specifier: &ModuleSpecifier,
+        source_hash: u64,
+      ) {
+        self.discard_count.fetch_add(1, Ordering::Relaxed);
+        self
+          .code_caches
+          .borrow_mut()
+          .remove(&(module_specifier.clone(), source_hash));
+      }
+
+      fn set_code_cache(
+        &self,
+        module_specifier: &ModuleSpecifier,
+        source_hash: u64,
+        data: &[u8],
+      ) {
+        self.set_count.fetch_add(1, Ordering::Relaxed);
+        self
+          .code_caches
+          .borrow_mut()
+          .insert((module_specifier.clone(), source_hash), data.to_vec());
+      }
+    }
+
+    let loader = Rc::new(CodeCacheLoader::default());
+    let main_specifier = resolve_url("file:///main.js").unwrap();
+    let run = || {
+      let mut runtime = JsRuntime::new(RuntimeOptions {
+        module_loader: Some(loader.clone()),
+        ..Default::default()
+      });
+      let main_id_fut = runtime
+        .load_main_module(&main_specifier, None)
+        .boxed_local();
+      let main_id = futures::executor::block_on(main_id_fut).unwrap();
+      let receiver = runtime.mod_evaluate(main_id);
+      futures::executor::block_on(runtime.run_event_loop(false)).unwrap();
+      futures::executor::block_on(receiver).unwrap().unwrap();
+    };
+    run();
+    run();
+    // The second runtime consumed the cache instead of creating a new one.
+    assert_eq!(loader.set_count.load(Ordering::Relaxed), 1);
+    assert_eq!(loader.discard_count.load(Ordering::Relaxed), 0);
+    assert_eq!(loader.code_caches.borrow().len(), 1);
+
+    // Data V8 rejects is discarded and replaced by fresh code cache.
+    for data in loader.code_caches.borrow_mut().values_mut() {
+      *data = b"not a code cache".to_vec();
+    }
+    run();
+    assert_eq!(loader.discard_count.load(Ordering::Relaxed), 1);
+    assert_eq!(loader.set_count.load(Ordering::Relaxed), 2);
+    let code_caches = loader.code_caches.borrow();
+    assert_eq!(code_caches.len(), 1);
+    assert_ne!(
+      code_caches.values().next().unwrap().as_slice(),
+      b"not a code cache"
+    );
+  }
+
+  #[test]
+  fn reload_modules_reevaluates_importers() {
+    struct MutableLoader {
+      sources: Rc<RefCell<HashMap<&'static str, &'static str>>>,
+    }
+
+    impl ModuleLoader for MutableLoader {
+      fn resolve(
+        &self,
+        specifier: &str,
+        referrer: &str,
+        _is_main: bool,
+      ) -> Result<ModuleSpecifier, Error> {
+        Ok(resolve_import(specifier, referrer)?)
+      }
+
+      fn load(
+        &self,
+        module_specifier: &ModuleSpecifier,
+        _maybe_referrer: Option<ModuleSpecifier>,
+        _is_dyn_import: bool,
+      ) -> Pin<Box<ModuleSourceFuture>> {
+        let code = self.sources.borrow()[module_specifier.as_str()];
+        let module_source = ModuleSource {
+          module_url_specified: module_specifier.to_string(),
+          module_url_found: module_specifier.to_string(),
+          code: code.to_owned(),
+          module_type: ModuleType::JavaScript,
+        };
+        async move { Ok(module_source) }.boxed()
+      }
+    }
+
+    let sources = Rc::new(RefCell::new(HashMap::from([
+      (
+        "file:///main.js",
+        "import { a } from './dep.js';\n\
+         import { b } from './other.js';\n\
+         globalThis.result = a + b;",
+      ),
+      ("file:///dep.js", "export const a = 1;"),
+      (
+        "file:///other.js",
+        "globalThis.otherEvaluations = (globalThis.otherEvaluations ?? 0) + 1;\n\
+         export const b = 10;",
+      ),
+    ])));
+    let loader = Rc::new(MutableLoader {
+      sources: sources.clone(),
+    });
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      module_loader: Some(loader),
+      ..Default::default()
+    });
+
+    let main_specifier = resolve_url("file:///main.js").unwrap();
+    let main_id_fut = runtime
+      .load_main_module(&main_specifier, None)
+      .boxed_local();
+    let main_id = futures::executor::block_on(main_id_fut).unwrap();
+    let receiver = runtime.mod_evaluate(main_id);
+    futures::executor::block_on(runtime.run_event_loop(false)).unwrap();
+    futures::executor::block_on(receiver).unwrap().unwrap();
+
+    sources
+      .borrow_mut()
+      .insert("file:///dep.js", "export const a = 2;");
+    let dep_specifier = resolve_url("file:///dep.js").unwrap();
+    let reloaded =
+      futures::executor::block_on(runtime.reload_modules(&[dep_specifier]))
+        .unwrap();
+    assert_eq!(reloaded, vec![main_specifier]);
+
+    runtime
+      .execute_script(
+        "check.js",
+        "if (globalThis.result !== 12) throw new Error();\n\
+         if (globalThis.otherEvaluations !== 1) throw new Error();",
+      )
+      .unwrap();
+    assert_eq!(runtime.serialize_module_map().modules.len(), 3);
+  }
 }
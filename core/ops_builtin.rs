@@ -1,16 +1,31 @@
+use crate::coverage::PreciseCoverageSession;
+use crate::coverage::ScriptCoverage;
+use crate::deterministic::VirtualClock;
+use crate::error::generic_error;
 use crate::error::type_error;
+use crate::futures::channel::mpsc::UnboundedSender;
 use crate::include_js_files;
+use crate::inspector::InspectorSessionProxy;
+use crate::inspector::LocalInspectorSession;
+use crate::message_channel::op_message_channel_create;
+use crate::message_channel::op_message_channel_read;
+use crate::message_channel::op_message_channel_write;
 use crate::ops_metrics::OpMetrics;
 use crate::resources::ResourceId;
+use crate::serde_json;
+use crate::CancelFuture;
+use crate::CancelHandle;
 use crate::Extension;
 use crate::OpState;
 use crate::Resource;
 use crate::ZeroCopyBuf;
 use anyhow::Error;
 use deno_ops::op;
+use std::borrow::Cow;
 use std::cell::RefCell;
 use std::io::{stderr, stdout, Write};
 use std::rc::Rc;
+use std::time::Duration;
 
 pub(crate) fn init_builtins() -> Extension {
   Extension::builder()
@@ -25,6 +40,7 @@ pub(crate) fn init_builtins() -> Extension {
       op_try_close::decl(),
       op_print::decl(),
       op_resources::decl(),
+      op_next_rid::decl(),
       op_wasm_streaming_feed::decl(),
       op_wasm_streaming_set_url::decl(),
       op_void_sync::decl(),
@@ -34,6 +50,18 @@ pub(crate) fn init_builtins() -> Extension {
       op_write::decl(),
       op_shutdown::decl(),
       op_metrics::decl(),
+      op_timer_handle::decl(),
+      op_sleep::decl(),
+      op_virtual_time::decl(),
+      op_profile_start::decl(),
+      op_profile_stop::decl(),
+      op_coverage_start::decl(),
+      op_coverage_take::decl(),
+      op_coverage_stop::decl(),
+      op_heap_snapshot::decl(),
+      op_message_channel_create::decl(),
+      op_message_channel_write::decl(),
+      op_message_channel_read::decl(),
     ])
     .build()
 }
@@ -52,6 +80,14 @@ pub fn op_resources(
   Ok(serialized_resources)
 }
 
+/// Return the id the next resource added to the resource table will get.
+/// Used to find out which resources were created by an op when op call
+/// tracing is enabled.
+#[op]
+pub fn op_next_rid(state: &mut OpState) -> Result<ResourceId, Error> {
+  Ok(state.resource_table.next_rid())
+}
+
 #[op]
 pub fn op_void_sync() -> Result<(), Error> {
   Ok(())
@@ -98,6 +134,195 @@ pub fn op_metrics(
   Ok((aggregate, per_op))
 }
 
+pub struct TimerHandle(Rc<CancelHandle>);
+
+impl Resource for TimerHandle {
+  fn name(&self) -> Cow<str> {
+    "timer".into()
+  }
+
+  fn close(self: Rc<Self>) {
+    self.0.cancel();
+  }
+}
+
+/// Creates a [`TimerHandle`] resource that can be used to cancel invocations of
+/// [`op_sleep`].
+#[op]
+pub fn op_timer_handle(state: &mut OpState) -> Result<ResourceId, Error> {
+  let rid = state
+    .resource_table
+    .add(TimerHandle(CancelHandle::new_rc()));
+  Ok(rid)
+}
+
+/// Waits asynchronously until either `millis` milliseconds have passed or the
+/// [`TimerHandle`] resource given by `rid` has been canceled. Uses the
+/// [`VirtualClock`] instead of real time when the runtime is deterministic.
+#[op]
+pub async fn op_sleep(
+  state: Rc<RefCell<OpState>>,
+  millis: u64,
+  rid: ResourceId,
+) -> Result<(), Error> {
+  let handle = state.borrow().resource_table.get::<TimerHandle>(rid)?;
+  let maybe_virtual_timer = state
+    .borrow_mut()
+    .try_borrow_mut::<VirtualClock>()
+    .map(|clock| clock.sleep(millis));
+  if let Some(virtual_timer) = maybe_virtual_timer {
+    // The sender is only dropped without firing if the runtime goes away.
+    let _ = virtual_timer.or_cancel(handle.0.clone()).await?;
+  } else {
+    tokio::time::sleep(Duration::from_millis(millis))
+      .or_cancel(handle.0.clone())
+      .await?;
+  }
+  Ok(())
+}
+
+/// Returns the milliseconds elapsed on the [`VirtualClock`], which `Date`
+/// reads when the runtime is deterministic.
+#[op]
+pub fn op_virtual_time(state: &mut OpState) -> Result<f64, Error> {
+  let clock = state
+    .try_borrow::<VirtualClock>()
+    .ok_or_else(|| generic_error("The runtime is not deterministic"))?;
+  Ok(clock.now().as_millis() as f64)
+}
+
+/// Lets ops open sessions on the runtime's own inspector. Put into `OpState`
+/// by `JsRuntime::new`.
+pub(crate) struct InspectorSessionSender(
+  pub UnboundedSender<InspectorSessionProxy>,
+);
+
+/// A CPU profile started by [`op_profile_start`].
+struct CpuProfile {
+  name: String,
+  session: LocalInspectorSession,
+}
+
+/// Starts V8's CPU profiler through a local inspector session. Only one
+/// profile can be running at a time.
+#[op]
+pub async fn op_profile_start(
+  state: Rc<RefCell<OpState>>,
+  name: String,
+) -> Result<(), Error> {
+  let mut session = {
+    let state = state.borrow();
+    if let Some(profile) = state.try_borrow::<CpuProfile>() {
+      return Err(generic_error(format!(
+        "CPU profile \"{}\" is already running",
+        profile.name
+      )));
+    }
+    LocalInspectorSession::connect(&state.borrow::<InspectorSessionSender>().0)?
+  };
+  session.post_message::<()>("Profiler.enable", None).await?;
+  session.post_message::<()>("Profiler.start", None).await?;
+
+  let mut state = state.borrow_mut();
+  if let Some(profile) = state.try_borrow::<CpuProfile>() {
+    return Err(generic_error(format!(
+      "CPU profile \"{}\" is already running",
+      profile.name
+    )));
+  }
+  state.put(CpuProfile { name, session });
+  Ok(())
+}
+
+/// Stops the profile started by [`op_profile_start`] and returns it in the
+/// `.cpuprofile` format understood by Chrome DevTools.
+#[op]
+pub async fn op_profile_stop(
+  state: Rc<RefCell<OpState>>,
+) -> Result<serde_json::Value, Error> {
+  let CpuProfile { mut session, .. } = state
+    .borrow_mut()
+    .try_take::<CpuProfile>()
+    .ok_or_else(|| generic_error("No CPU profile is running"))?;
+  let mut result = session.post_message::<()>("Profiler.stop", None).await?;
+  session.post_message::<()>("Profiler.disable", None).await?;
+  Ok(result["profile"].take())
+}
+
+/// Marks precise coverage as being started, so that a concurrent
+/// `op_coverage_start` fails instead of starting a second session.
+struct PreciseCoverageStarting;
+
+/// Starts collecting block-level precise coverage, see
+/// `JsRuntime::start_precise_coverage`.
+#[op]
+pub async fn op_coverage_start(
+  state: Rc<RefCell<OpState>>,
+) -> Result<(), Error> {
+  let mut session = {
+    let mut state = state.borrow_mut();
+    if state.has::<PreciseCoverageSession>()
+      || state.has::<PreciseCoverageStarting>()
+    {
+      return Err(generic_error("Precise coverage is already being collected"));
+    }
+    let session = PreciseCoverageSession::new(LocalInspectorSession::connect(
+      &state.borrow::<InspectorSessionSender>().0,
+    )?);
+    state.put(PreciseCoverageStarting);
+    session
+  };
+  let result = session.start().await;
+  let mut state = state.borrow_mut();
+  state.take::<PreciseCoverageStarting>();
+  result?;
+  state.put(session);
+  Ok(())
+}
+
+/// Returns the coverage collected since `op_coverage_start` or the previous
+/// call, and resets the counters.
+#[op]
+pub async fn op_coverage_take(
+  state: Rc<RefCell<OpState>>,
+) -> Result<Vec<ScriptCoverage>, Error> {
+  let mut session = take_coverage_session(&state)?;
+  let result = session.take().await;
+  state.borrow_mut().put(session);
+  result
+}
+
+#[op]
+pub async fn op_coverage_stop(
+  state: Rc<RefCell<OpState>>,
+) -> Result<(), Error> {
+  take_coverage_session(&state)?.stop().await
+}
+
+fn take_coverage_session(
+  state: &Rc<RefCell<OpState>>,
+) -> Result<PreciseCoverageSession, Error> {
+  state
+    .borrow_mut()
+    .try_take::<PreciseCoverageSession>()
+    .ok_or_else(|| generic_error("Precise coverage is not being collected"))
+}
+
+/// Writes a V8 heap snapshot to `path`, see
+/// `LocalInspectorSession::take_heap_snapshot`.
+#[op]
+pub async fn op_heap_snapshot(
+  state: Rc<RefCell<OpState>>,
+  path: String,
+) -> Result<(), Error> {
+  let mut session = LocalInspectorSession::connect(
+    &state.borrow().borrow::<InspectorSessionSender>().0,
+  )?;
+  let snapshot = session.take_heap_snapshot().await?;
+  std::fs::write(path, snapshot)?;
+  Ok(())
+}
+
 /// Builtin utility to print to stdout/stderr
 #[op]
 pub fn op_print(msg: String, is_err: bool) -> Result<(), Error> {
@@ -64,6 +64,15 @@ pub trait Resource: Any + 'static {
   /// resource specific clean-ups, such as cancelling pending futures, after a
   /// resource has been removed from the resource table.
   fn close(self: Rc<Self>) {}
+
+  /// Returns the number of bytes of native memory kept alive by this resource
+  /// that V8 doesn't know about, e.g. large buffers owned by files, sockets or
+  /// FFI allocations. The resource table reports it to V8 so that it is taken
+  /// into account when deciding to garbage collect. The value is sampled when
+  /// the resource is added to the table.
+  fn external_memory(&self) -> usize {
+    0
+  }
 }
 
 impl dyn Resource {
@@ -104,6 +113,8 @@ pub type ResourceId = u32;
 pub struct ResourceTable {
   index: BTreeMap<ResourceId, Rc<dyn Resource>>,
   next_rid: ResourceId,
+  external_memory: BTreeMap<ResourceId, usize>,
+  external_memory_delta: i64,
 }
 
 impl ResourceTable {
@@ -126,6 +137,7 @@ impl ResourceTable {
   pub fn add_rc<T: Resource>(&mut self, resource: Rc<T>) -> ResourceId {
     let resource = resource as Rc<dyn Resource>;
     let rid = self.next_rid;
+    self.track_external_memory(rid, resource.external_memory());
     let removed_resource = self.index.insert(rid, resource);
     assert!(removed_resource.is_none());
     self.next_rid += 1;
@@ -161,9 +173,10 @@ impl ResourceTable {
   ///
   /// Panics if the resource does not exist.
   pub fn replace<T: Resource>(&mut self, rid: ResourceId, resource: T) {
-    let result = self
-      .index
-      .insert(rid, Rc::new(resource) as Rc<dyn Resource>);
+    let resource = Rc::new(resource) as Rc<dyn Resource>;
+    self.untrack_external_memory(rid);
+    self.track_external_memory(rid, resource.external_memory());
+    let result = self.index.insert(rid, resource);
     assert!(result.is_some());
   }
 
@@ -174,6 +187,7 @@ impl ResourceTable {
   pub fn take<T: Resource>(&mut self, rid: ResourceId) -> Result<Rc<T>, Error> {
     let resource = self.get::<T>(rid)?;
     self.index.remove(&rid);
+    self.untrack_external_memory(rid);
     Ok(resource)
   }
 
@@ -183,7 +197,9 @@ impl ResourceTable {
     &mut self,
     rid: ResourceId,
   ) -> Result<Rc<dyn Resource>, Error> {
-    self.index.remove(&rid).ok_or_else(bad_resource_id)
+    let resource = self.index.remove(&rid).ok_or_else(bad_resource_id)?;
+    self.untrack_external_memory(rid);
+    Ok(resource)
   }
 
   /// Removes the resource with the given `rid` from the resource table. If the
@@ -193,11 +209,52 @@ impl ResourceTable {
   /// may implement the `close()` method to perform clean-ups such as canceling
   /// ops.
   pub fn close(&mut self, rid: ResourceId) -> Result<(), Error> {
-    self
+    let resource = self.index.remove(&rid).ok_or_else(bad_resource_id)?;
+    self.untrack_external_memory(rid);
+    resource.close();
+    Ok(())
+  }
+
+  /// Returns the id the next added resource will get.
+  pub(crate) fn next_rid(&self) -> ResourceId {
+    self.next_rid
+  }
+
+  /// Closes all resources with an id greater than or equal to `first_rid`.
+  pub(crate) fn close_since(&mut self, first_rid: ResourceId) {
+    let rids = self
       .index
-      .remove(&rid)
-      .ok_or_else(bad_resource_id)
-      .map(|resource| resource.close())
+      .range(first_rid..)
+      .map(|(rid, _)| *rid)
+      .collect::<Vec<_>>();
+    for rid in rids {
+      self.close(rid).unwrap();
+    }
+  }
+
+  /// Returns the total external memory, in bytes, reported by the resources
+  /// currently in the table. See `Resource::external_memory()`.
+  pub fn external_memory(&self) -> usize {
+    self.external_memory.values().sum()
+  }
+
+  /// Returns the change in external memory since the last call, to be passed
+  /// on to `v8::Isolate::adjust_amount_of_external_allocated_memory()`.
+  pub(crate) fn take_external_memory_delta(&mut self) -> i64 {
+    std::mem::take(&mut self.external_memory_delta)
+  }
+
+  fn track_external_memory(&mut self, rid: ResourceId, bytes: usize) {
+    if bytes > 0 {
+      self.external_memory.insert(rid, bytes);
+      self.external_memory_delta += bytes as i64;
+    }
+  }
+
+  fn untrack_external_memory(&mut self, rid: ResourceId) {
+    if let Some(bytes) = self.external_memory.remove(&rid) {
+      self.external_memory_delta -= bytes as i64;
+    }
   }
 
   /// Returns an iterator that yields a `(id, name)` pair for every resource
@@ -1,6 +1,11 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
 use crate::bindings;
+use crate::coverage::PreciseCoverageSession;
+use crate::coverage::ScriptCoverage;
+use crate::deterministic::OpShuffler;
+use crate::deterministic::VirtualClock;
+use crate::deterministic::VIRTUAL_DATE_JS;
 use crate::error::generic_error;
 use crate::error::to_v8_type_error;
 use crate::error::JsError;
@@ -8,11 +13,14 @@ use crate::extensions::OpDecl;
 use crate::extensions::OpEventLoopFn;
 use crate::inspector::JsRuntimeInspector;
 use crate::module_specifier::ModuleSpecifier;
+use crate::modules::CustomModuleEvaluationCb;
+use crate::modules::ImportMetaCb;
 use crate::modules::ModuleError;
 use crate::modules::ModuleId;
 use crate::modules::ModuleLoadId;
 use crate::modules::ModuleLoader;
 use crate::modules::ModuleMap;
+use crate::modules::ModuleMapData;
 use crate::modules::NoopModuleLoader;
 use crate::op_void_async;
 use crate::op_void_sync;
@@ -23,6 +31,7 @@ use crate::OpMiddlewareFn;
 use crate::OpResult;
 use crate::OpState;
 use crate::PromiseId;
+use crate::ResourceId;
 use anyhow::Error;
 use futures::channel::oneshot;
 use futures::future::poll_fn;
@@ -33,19 +42,28 @@ use futures::stream::StreamExt;
 use futures::task::AtomicWaker;
 use std::any::Any;
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::ffi::c_void;
 use std::mem::forget;
 use std::option::Option;
+use std::path::Path;
+use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::Once;
 use std::task::Context;
 use std::task::Poll;
+use v8::MapFnTo;
 
-type PendingOpFuture = OpCall<(PromiseId, OpId, OpResult)>;
+type PendingOpFuture = OpCall<(RealmId, PromiseId, OpId, OpResult)>;
+
+/// Identifies the realm an async op was dispatched from, so that its result
+/// can be delivered to the promise table of that realm.
+pub(crate) type RealmId = u32;
+const GLOBAL_REALM_ID: RealmId = 0;
 
 pub enum Snapshot {
   Static(&'static [u8]),
@@ -57,6 +75,70 @@ pub type JsErrorCreateFn = dyn Fn(JsError) -> Error;
 
 pub type GetErrorClassFn = &'static dyn for<'e> Fn(&'e Error) -> &'static str;
 
+/// What the runtime should do when V8 reports that the heap is approaching
+/// its configured limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NearHeapLimitAction {
+  /// Raise the heap limit to the given number of bytes and keep running.
+  Extend(usize),
+  /// Terminate JavaScript execution. The limit is raised just enough to let
+  /// the termination unwind, so the embedder sees a regular "execution
+  /// terminated" error instead of a fatal OOM.
+  Terminate,
+  /// Write a heap snapshot to the given path, then terminate execution.
+  HeapSnapshot(PathBuf),
+}
+
+/// Called with the current and the initial heap limit whenever V8 approaches
+/// the limit.
+pub type NearHeapLimitFn = dyn FnMut(usize, usize) -> NearHeapLimitAction;
+
+/// Called with a promise that was rejected without a handler and the
+/// rejection reason, before the event loop turns the rejection into an error.
+/// Returning `true` marks the rejection as handled and the event loop carries
+/// on, like `preventDefault()` on an `unhandledrejection` event.
+pub type UnhandledRejectionCb = dyn Fn(
+  &mut v8::HandleScope,
+  v8::Local<v8::Promise>,
+  v8::Local<v8::Value>,
+) -> bool;
+
+/// Called with the thrown value whenever an uncaught exception is about to be
+/// returned to Rust as an error, eg. to report it.
+pub type UncaughtExceptionCb =
+  dyn Fn(&mut v8::HandleScope, v8::Local<v8::Value>);
+
+/// When microtasks (promise reactions, `queueMicrotask` callbacks) run.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MicrotaskPolicy {
+  /// Microtasks run whenever the JavaScript call stack empties, and the event
+  /// loop additionally runs them at the points it needs to make progress.
+  Auto,
+  /// Microtasks only run when the embedder calls
+  /// `JsRuntime::perform_microtask_checkpoint`, or JavaScript calls
+  /// `Deno.core.runMicrotasks()`. The event loop doesn't run them on its own,
+  /// so promise-based code only makes progress at those checkpoints.
+  Explicit,
+}
+
+impl Default for MicrotaskPolicy {
+  fn default() -> Self {
+    Self::Auto
+  }
+}
+
+/// Heap size configuration for a `JsRuntime`.
+#[derive(Default)]
+pub struct HeapLimits {
+  /// Initial heap size in bytes.
+  pub initial: usize,
+  /// Maximum heap size in bytes.
+  pub max: usize,
+  /// Decides what happens when the heap approaches `max`. If `None`,
+  /// execution is terminated.
+  pub near_heap_limit_callback: Option<Box<NearHeapLimitFn>>,
+}
+
 /// Objects that need to live as long as the isolate
 #[derive(Default)]
 struct IsolateAllocations {
@@ -87,6 +169,11 @@ pub struct JsRuntime {
   allocations: IsolateAllocations,
   extensions: Vec<Extension>,
   event_loop_middlewares: Vec<Box<OpEventLoopFn>>,
+  dump_op_metrics: Option<PathBuf>,
+  coverage_session: Option<PreciseCoverageSession>,
+  // Resources with a lower id were added while initializing extensions and
+  // are kept by `JsRuntime::reset`.
+  first_request_rid: ResourceId,
 }
 
 struct DynImportModEvaluate {
@@ -138,15 +225,93 @@ impl<T> Clone for CrossIsolateStore<T> {
   }
 }
 
-pub type SharedArrayBufferStore =
-  CrossIsolateStore<v8::SharedRef<v8::BackingStore>>;
+/// Keeps the backing stores of `SharedArrayBuffer`s and transferred
+/// `ArrayBuffer`s alive while they travel between isolates.
+///
+/// Serializing a buffer pins its backing store under a new id, and
+/// deserializing that id removes the pin again. Embedders that deliver a
+/// message to several isolates, or drop it undelivered, balance the count
+/// with [`SharedArrayBufferStore::pin`] and [`SharedArrayBufferStore::unpin`]
+/// so the memory is released as soon as no pending message refers to it.
+#[derive(Clone, Default)]
+pub struct SharedArrayBufferStore(Arc<Mutex<SharedArrayBufferStoreInner>>);
+
+#[derive(Default)]
+struct SharedArrayBufferStoreInner {
+  map: HashMap<u32, (v8::SharedRef<v8::BackingStore>, usize)>,
+  last_id: u32,
+}
+
+impl SharedArrayBufferStore {
+  /// Pins `backing_store` once and returns the id it is stored under.
+  pub fn insert(&self, backing_store: v8::SharedRef<v8::BackingStore>) -> u32 {
+    let mut store = self.0.lock().unwrap();
+    let id = store.last_id;
+    store.map.insert(id, (backing_store, 1));
+    store.last_id += 1;
+    id
+  }
+
+  /// Adds a pin to the backing store stored under `id`. Returns `false` if
+  /// there is no such backing store.
+  pub fn pin(&self, id: u32) -> bool {
+    let mut store = self.0.lock().unwrap();
+    match store.map.get_mut(&id) {
+      Some((_, pins)) => {
+        *pins += 1;
+        true
+      }
+      None => false,
+    }
+  }
+
+  /// Removes a pin from the backing store stored under `id`, releasing it
+  /// after the last one. Returns `false` if there is no such backing store.
+  pub fn unpin(&self, id: u32) -> bool {
+    self.take(id).is_some()
+  }
+
+  /// Returns the backing store stored under `id` and removes a pin from it.
+  pub(crate) fn take(
+    &self,
+    id: u32,
+  ) -> Option<v8::SharedRef<v8::BackingStore>> {
+    let mut store = self.0.lock().unwrap();
+    let (backing_store, pins) = store.map.get_mut(&id)?;
+    let backing_store = backing_store.clone();
+    *pins -= 1;
+    if *pins == 0 {
+      store.map.remove(&id);
+    }
+    Some(backing_store)
+  }
+
+  /// Number of backing stores that are currently pinned.
+  pub fn len(&self) -> usize {
+    self.0.lock().unwrap().map.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
 
 pub type CompiledWasmModuleStore = CrossIsolateStore<v8::CompiledWasmModule>;
 
+/// A realm created through `JsRuntime::create_realm`.
+pub(crate) struct KnownRealm {
+  context: v8::Global<v8::Context>,
+  js_recv_cb: v8::Global<v8::Function>,
+}
+
 /// Internal state for JsRuntime which is stored in one of v8::Isolate's
 /// embedder slots.
 pub(crate) struct JsRuntimeState {
   global_realm: Option<JsRealm>,
+  /// Realms other than the global one, keyed by the id async ops dispatched
+  /// from them are tagged with.
+  known_realms: HashMap<RealmId, KnownRealm>,
+  next_realm_id: RealmId,
   pub(crate) js_recv_cb: Option<v8::Global<v8::Function>>,
   pub(crate) js_macrotask_cbs: Vec<v8::Global<v8::Function>>,
   pub(crate) js_nexttick_cbs: Vec<v8::Global<v8::Function>>,
@@ -175,11 +340,39 @@ pub(crate) struct JsRuntimeState {
   /// It will be retrieved by `exception_to_err_result` and used as an error
   /// instead of any other exceptions.
   pub(crate) explicit_terminate_exception: Option<v8::Global<v8::Value>>,
+  microtask_policy: MicrotaskPolicy,
+  unhandled_rejection_cb: Option<Rc<UnhandledRejectionCb>>,
+  uncaught_exception_cb: Option<Rc<UncaughtExceptionCb>>,
+  /// Set in deterministic mode, see `RuntimeOptions::deterministic_seed`.
+  op_shuffler: Option<OpShuffler>,
   waker: AtomicWaker,
 }
 
 impl Drop for JsRuntime {
   fn drop(&mut self) {
+    if let Some(path) = self.dump_op_metrics.take() {
+      if self.v8_isolate.is_some() {
+        if let Err(err) = self.write_op_metrics(&path) {
+          log::error!(
+            "Failed to write op metrics to {}: {}",
+            path.display(),
+            err
+          );
+        }
+      }
+    }
+
+    // Extensions are torn down in reverse registration order, while the
+    // isolate and op-state are still alive.
+    if let Some(isolate) = self.v8_isolate.as_ref() {
+      let op_state = Self::state(isolate).borrow().op_state.clone();
+      for e in self.extensions.iter_mut().rev() {
+        if let Some(teardown) = e.take_teardown() {
+          teardown(&mut op_state.borrow_mut());
+        }
+      }
+    }
+
     // The Isolate object must outlive the Inspector object, but this is
     // currently not enforced by the type system.
     self.inspector.take();
@@ -228,6 +421,10 @@ fn v8_init(v8_platform: Option<v8::SharedRef<v8::Platform>>) {
 #[derive(Default)]
 pub struct RuntimeOptions {
   /// Source map reference for errors.
+  ///
+  /// Consulted for every stack frame and error location, so embedders can
+  /// map code back to its original source however it was loaded. Without a
+  /// getter, positions refer to the code as it was handed to V8.
   pub source_map_getter: Option<Box<dyn SourceMapGetter>>,
 
   /// Allows to map error type to a string "class" used to represent
@@ -258,6 +455,10 @@ pub struct RuntimeOptions {
   /// Isolate creation parameters.
   pub create_params: Option<v8::CreateParams>,
 
+  /// Heap limits applied on top of `create_params`, along with the action to
+  /// take when they are approached. Ignored when `will_snapshot` is set.
+  pub heap_limits: Option<HeapLimits>,
+
   /// V8 platform instance to use. Used when Deno initializes V8
   /// (which it only does once), otherwise it's silenty dropped.
   pub v8_platform: Option<v8::SharedRef<v8::Platform>>,
@@ -276,6 +477,45 @@ pub struct RuntimeOptions {
   /// [CompiledWasmModuleStore]. If no [CompiledWasmModuleStore] is specified,
   /// `WebAssembly.Module` objects cannot be serialized.
   pub compiled_wasm_module_store: Option<CompiledWasmModuleStore>,
+
+  /// Names of ops that should be replaced with a stub throwing a descriptive
+  /// `TypeError` when called, regardless of which extension registers them.
+  /// Unlike `OpDecl::disable()`, this lets embedders harden a runtime
+  /// centrally without modifying each extension. `JsRuntime::new` panics if
+  /// one of them isn't registered by any extension.
+  pub disabled_ops: Vec<&'static str>,
+
+  /// If set, the aggregate and per-op `OpMetrics` are serialized as JSON to
+  /// this path when the runtime is dropped.
+  pub dump_op_metrics: Option<PathBuf>,
+
+  /// Module graph data from `JsRuntime::serialize_module_map` of a previous
+  /// run, used to skip resolving imports of unchanged modules.
+  pub module_map_data: Option<ModuleMapData>,
+
+  /// Evaluators for module types that can be requested with a `type` import
+  /// assertion besides "json", keyed by the asserted type. The
+  /// `ModuleLoader` must return such modules with a matching
+  /// `ModuleType::Other`.
+  pub custom_module_types: HashMap<&'static str, Box<CustomModuleEvaluationCb>>,
+
+  /// Controls when microtasks run, see `MicrotaskPolicy`.
+  pub microtask_policy: MicrotaskPolicy,
+
+  /// Callbacks adding properties to `import.meta`, called in order. They may
+  /// override the built-in `url` and `main` properties.
+  pub import_meta_cbs: Vec<Box<ImportMetaCb>>,
+
+  /// Runs the event loop deterministically, for reproducible tests of
+  /// race-prone async code:
+  /// - timers and `Date` run on a `VirtualClock` that only advances when the
+  ///   event loop is otherwise idle, jumping straight to the next deadline,
+  /// - async op completions of an event loop tick are delivered to JS in an
+  ///   order derived from this seed, one at a time, with a microtask
+  ///   checkpoint after each.
+  ///
+  /// Ops that wait on real I/O still complete whenever the I/O does.
+  pub deterministic_seed: Option<u64>,
 }
 
 impl JsRuntime {
@@ -293,12 +533,16 @@ impl JsRuntime {
       .extensions
       .insert(0, crate::ops_builtin::init_builtins());
 
-    let ops = Self::collect_ops(&mut options.extensions);
+    let ops = Self::collect_ops(&mut options.extensions, &options.disabled_ops)
+      .unwrap();
     let mut op_state = OpState::new(ops.len());
 
     if let Some(get_error_class_fn) = options.get_error_class_fn {
       op_state.get_error_class_fn = get_error_class_fn;
     }
+    if options.deterministic_seed.is_some() {
+      op_state.put(VirtualClock::default());
+    }
 
     let op_state = Rc::new(RefCell::new(op_state));
     let op_ctxs = ops
@@ -333,6 +577,9 @@ impl JsRuntime {
         .take()
         .unwrap_or_else(v8::Isolate::create_params)
         .external_references(&**bindings::EXTERNAL_REFERENCES);
+      if let Some(heap_limits) = &options.heap_limits {
+        params = params.heap_limits(heap_limits.initial, heap_limits.max);
+      }
       let snapshot_loaded = if let Some(snapshot) = options.startup_snapshot {
         params = match snapshot {
           Snapshot::Static(data) => params.snapshot_blob(data),
@@ -356,8 +603,17 @@ impl JsRuntime {
       (isolate, None)
     };
 
+    if options.microtask_policy == MicrotaskPolicy::Explicit {
+      isolate.set_microtasks_policy(v8::MicrotasksPolicy::Explicit);
+    }
+
     let inspector =
       JsRuntimeInspector::new(&mut isolate, global_context.clone());
+    op_state
+      .borrow_mut()
+      .put(crate::ops_builtin::InspectorSessionSender(
+        inspector.get_session_sender(),
+      ));
 
     let loader = options
       .module_loader
@@ -365,6 +621,8 @@ impl JsRuntime {
 
     isolate.set_slot(Rc::new(RefCell::new(JsRuntimeState {
       global_realm: Some(JsRealm(global_context)),
+      known_realms: HashMap::new(),
+      next_realm_id: GLOBAL_REALM_ID + 1,
       pending_promise_exceptions: HashMap::new(),
       pending_dyn_mod_evaluate: vec![],
       pending_mod_evaluate: None,
@@ -385,10 +643,19 @@ impl JsRuntime {
       op_ctxs,
       have_unpolled_ops: false,
       explicit_terminate_exception: None,
+      microtask_policy: options.microtask_policy,
+      unhandled_rejection_cb: None,
+      uncaught_exception_cb: None,
+      op_shuffler: options.deterministic_seed.map(OpShuffler::new),
       waker: AtomicWaker::new(),
     })));
 
-    let module_map = ModuleMap::new(loader, op_state);
+    let mut module_map = ModuleMap::new(loader, op_state);
+    if let Some(module_map_data) = options.module_map_data {
+      module_map.restore(module_map_data);
+    }
+    module_map.custom_module_types = options.custom_module_types;
+    module_map.import_meta_cbs = options.import_meta_cbs;
     isolate.set_slot(Rc::new(RefCell::new(module_map)));
 
     let mut js_runtime = Self {
@@ -400,6 +667,9 @@ impl JsRuntime {
       allocations: IsolateAllocations::default(),
       event_loop_middlewares: Vec::with_capacity(options.extensions.len()),
       extensions: options.extensions,
+      dump_op_metrics: options.dump_op_metrics,
+      coverage_session: None,
+      first_request_rid: 0,
     };
 
     // TODO(@AaronO): diff extensions inited in snapshot and those provided
@@ -412,6 +682,23 @@ impl JsRuntime {
     js_runtime.init_extension_ops().unwrap();
     // Init callbacks (opresolve)
     js_runtime.init_cbs();
+    if options.deterministic_seed.is_some() {
+      js_runtime
+        .execute_script("deno:core/virtual_date.js", VIRTUAL_DATE_JS)
+        .unwrap();
+    }
+    js_runtime.first_request_rid =
+      js_runtime.op_state().borrow().resource_table.next_rid();
+
+    if let Some(heap_limits) = options.heap_limits {
+      if !options.will_snapshot {
+        js_runtime.set_heap_limit_action(
+          heap_limits
+            .near_heap_limit_callback
+            .unwrap_or_else(|| Box::new(|_, _| NearHeapLimitAction::Terminate)),
+        );
+      }
+    }
 
     js_runtime
   }
@@ -434,6 +721,12 @@ impl JsRuntime {
     state.global_realm.clone().unwrap()
   }
 
+  /// Creates a new realm (V8 context) in this runtime, with its own global
+  /// object. Realms share the runtime's ops, op-state and resource table, and
+  /// async ops dispatched from a realm are resolved in that realm.
+  ///
+  /// The realm is kept alive until it is passed to
+  /// [`JsRuntime::dispose_realm`] or the runtime is dropped.
   pub fn create_realm(&mut self) -> Result<JsRealm, Error> {
     let realm = {
       // SAFETY: Having the scope tied to self's lifetime makes it impossible to
@@ -455,9 +748,138 @@ impl JsRuntime {
     if !self.built_from_snapshot {
       self.init_extension_js(&realm)?;
     }
+
+    let js_recv_cb = {
+      let scope = &mut realm.handle_scope(self);
+      let recv_cb =
+        Self::grab_global::<v8::Function>(scope, "Deno.core.opresolve")
+          .ok_or_else(|| generic_error("Deno.core.opresolve is not defined"))?;
+      v8::Global::new(scope, recv_cb)
+    };
+    let state_rc = Self::state(self.v8_isolate());
+    let mut state = state_rc.borrow_mut();
+    let realm_id = state.next_realm_id;
+    state.next_realm_id += 1;
+    state.known_realms.insert(
+      realm_id,
+      KnownRealm {
+        context: realm.context().clone(),
+        js_recv_cb,
+      },
+    );
+
     Ok(realm)
   }
 
+  /// Releases a realm previously created with [`JsRuntime::create_realm`].
+  /// Results of async ops still in flight for that realm are discarded.
+  ///
+  /// Returns `false` if the realm is not known to this runtime, which is also
+  /// the case for the global realm, as it can't be disposed.
+  pub fn dispose_realm(&mut self, realm: &JsRealm) -> bool {
+    let state_rc = Self::state(self.v8_isolate());
+    let mut state = state_rc.borrow_mut();
+    let maybe_id = state
+      .known_realms
+      .iter()
+      .find(|(_, known)| &known.context == realm.context())
+      .map(|(id, _)| *id);
+    match maybe_id {
+      Some(id) => state.known_realms.remove(&id).is_some(),
+      None => false,
+    }
+  }
+
+  /// Discards all JavaScript state of the runtime, so that it can be reused
+  /// as if it had just been created: the global realm is replaced by a fresh
+  /// context (from the startup snapshot, if any), other realms and loaded
+  /// modules are dropped, pending ops are canceled and all resources are
+  /// closed. The isolate, its heap configuration and the op state of
+  /// extensions are kept.
+  pub fn reset(&mut self) -> Result<(), Error> {
+    if self.snapshot_creator.is_some() {
+      return Err(generic_error(
+        "A runtime created with will_snapshot can't be reset",
+      ));
+    }
+
+    let state_rc = Self::state(self.v8_isolate());
+    let op_state = state_rc.borrow().op_state.clone();
+    op_state
+      .borrow_mut()
+      .resource_table
+      .close_since(self.first_request_rid);
+
+    {
+      let mut state = state_rc.borrow_mut();
+      state.known_realms.clear();
+      state.js_recv_cb = None;
+      state.js_macrotask_cbs.clear();
+      state.js_nexttick_cbs.clear();
+      state.js_promise_reject_cb = None;
+      state.js_uncaught_exception_cb = None;
+      state.has_tick_scheduled = false;
+      state.js_wasm_streaming_cb = None;
+      state.pending_promise_exceptions.clear();
+      state.pending_dyn_mod_evaluate.clear();
+      state.pending_mod_evaluate = None;
+      state.dyn_module_evaluate_idle_counter = 0;
+      state.pending_ops = FuturesUnordered::new();
+      state.unrefed_ops.clear();
+      state.have_unpolled_ops = false;
+      state.explicit_terminate_exception = None;
+    }
+
+    let module_map_rc = Self::module_map(self.v8_isolate());
+    let module_map = {
+      let mut old_module_map = module_map_rc.borrow_mut();
+      let mut module_map =
+        ModuleMap::new(old_module_map.loader.clone(), op_state.clone());
+      module_map.custom_module_types =
+        std::mem::take(&mut old_module_map.custom_module_types);
+      module_map.import_meta_cbs =
+        std::mem::take(&mut old_module_map.import_meta_cbs);
+      module_map
+    };
+    self
+      .v8_isolate()
+      .set_slot(Rc::new(RefCell::new(module_map)));
+
+    // The inspector is bound to the global context, so it has to go first.
+    self.coverage_session.take();
+    self.inspector.take();
+    let global_context = {
+      // SAFETY: see `JsRuntime::create_realm`.
+      let scope = &mut v8::HandleScope::new(unsafe {
+        &mut *(self.v8_isolate() as *mut v8::OwnedIsolate)
+      });
+      let context = bindings::initialize_context(
+        scope,
+        &state_rc.borrow().op_ctxs,
+        self.built_from_snapshot,
+      );
+      v8::Global::new(scope, context)
+    };
+    state_rc.borrow_mut().global_realm = Some(JsRealm(global_context.clone()));
+    let inspector = JsRuntimeInspector::new(self.v8_isolate(), global_context);
+    op_state
+      .borrow_mut()
+      .put(crate::ops_builtin::InspectorSessionSender(
+        inspector.get_session_sender(),
+      ));
+    self.inspector = Some(inspector);
+
+    if !self.built_from_snapshot {
+      let realm = self.global_realm();
+      self.init_extension_js(&realm)?;
+    }
+    self.init_cbs();
+    if op_state.borrow().has::<VirtualClock>() {
+      self.execute_script("deno:core/virtual_date.js", VIRTUAL_DATE_JS)?;
+    }
+    Ok(())
+  }
+
   pub fn handle_scope(&mut self) -> v8::HandleScope {
     self.global_realm().handle_scope(self)
   }
@@ -502,8 +924,13 @@ impl JsRuntime {
     Ok(())
   }
 
-  /// Collects ops from extensions & applies middleware
-  fn collect_ops(extensions: &mut [Extension]) -> Vec<OpDecl> {
+  /// Collects ops from extensions & applies middleware. Fails if an op in
+  /// `disabled_ops` isn't registered by any extension, e.g. because of a
+  /// typo, which would otherwise leave the op enabled.
+  fn collect_ops(
+    extensions: &mut [Extension],
+    disabled_ops: &[&'static str],
+  ) -> Result<Vec<OpDecl>, Error> {
     // Middleware
     let middleware: Vec<Box<OpMiddlewareFn>> = extensions
       .iter_mut()
@@ -514,7 +941,7 @@ impl JsRuntime {
     let macroware = move |d| middleware.iter().fold(d, |d, m| m(d));
 
     // Flatten ops, apply middlware & override disabled ops
-    extensions
+    let ops: Vec<OpDecl> = extensions
       .iter_mut()
       .filter_map(|e| e.init_ops())
       .flatten()
@@ -532,7 +959,26 @@ impl JsRuntime {
           ..op
         },
       })
-      .collect()
+      .map(|op| match disabled_ops.contains(&op.name) {
+        true => OpDecl {
+          v8_fn_ptr: bindings::op_disabled.map_fn_to(),
+          enabled: false,
+          ..op
+        },
+        false => op,
+      })
+      .collect();
+
+    if let Some(unknown) = disabled_ops
+      .iter()
+      .find(|name| !ops.iter().any(|op| op.name == **name))
+    {
+      return Err(generic_error(format!(
+        "Unknown op in disabled_ops: {}",
+        unknown
+      )));
+    }
+    Ok(ops)
   }
 
   /// Initializes ops of provided Extensions
@@ -628,6 +1074,34 @@ impl JsRuntime {
     state.op_state.clone()
   }
 
+  /// Returns the aggregate op metrics along with the metrics of every
+  /// registered op, keyed by op name, as a JSON value.
+  pub fn op_metrics_json(&mut self) -> serde_json::Value {
+    let state_rc = Self::state(self.v8_isolate());
+    let state = state_rc.borrow();
+    let op_state = state.op_state.borrow();
+    let aggregate = op_state.tracker.aggregate();
+    let per_op = op_state.tracker.per_op();
+    let ops = state
+      .op_ctxs
+      .iter()
+      .zip(per_op.into_iter())
+      .map(|(ctx, metrics)| {
+        (ctx.decl.name.to_string(), serde_json::json!(metrics))
+      })
+      .collect::<serde_json::Map<_, _>>();
+    serde_json::json!({
+      "aggregate": aggregate,
+      "ops": ops,
+    })
+  }
+
+  fn write_op_metrics(&mut self, path: &Path) -> Result<(), Error> {
+    let metrics = self.op_metrics_json();
+    std::fs::write(path, serde_json::to_string_pretty(&metrics)?)?;
+    Ok(())
+  }
+
   /// Executes traditional JavaScript code (traditional = not ES modules).
   ///
   /// The execution takes place on the current global context, so it is possible
@@ -653,9 +1127,46 @@ impl JsRuntime {
   /// Takes a snapshot. The isolate should have been created with will_snapshot
   /// set to true.
   ///
-  /// `Error` can usually be downcast to `JsError`.
+  /// Panics if the snapshot can't be taken, see [`JsRuntime::try_snapshot`].
   pub fn snapshot(&mut self) -> v8::StartupData {
-    assert!(self.snapshot_creator.is_some());
+    self.try_snapshot().unwrap()
+  }
+
+  /// Takes a snapshot of the current state of the isolate, including the side
+  /// effects of any scripts and ES modules that were executed or evaluated at
+  /// runtime. The returned blob can be passed back as
+  /// `RuntimeOptions::startup_snapshot` to start a pre-warmed runtime.
+  ///
+  /// The isolate must have been created with `will_snapshot` set to true, and
+  /// the event loop must be idle, ie. there can be no pending ops, dynamic
+  /// imports or module evaluations. Module records themselves are not part of
+  /// the snapshot, only the heap they left behind.
+  ///
+  /// The runtime can't be used to execute code after it has been snapshotted.
+  pub fn try_snapshot(&mut self) -> Result<v8::StartupData, Error> {
+    if self.snapshot_creator.is_none() {
+      return Err(generic_error(
+        "Cannot take a snapshot of a runtime that was not created with `will_snapshot: true`",
+      ));
+    }
+    if self.has_snapshotted {
+      return Err(generic_error("The runtime has already been snapshotted"));
+    }
+
+    let state = Self::state(self.v8_isolate());
+    {
+      let module_map_rc = Self::module_map(self.v8_isolate());
+      let state = state.borrow();
+      if !state.pending_ops.is_empty()
+        || state.pending_mod_evaluate.is_some()
+        || !state.pending_dyn_mod_evaluate.is_empty()
+        || module_map_rc.borrow().has_pending_dynamic_imports()
+      {
+        return Err(generic_error(
+          "Cannot take a snapshot while there are pending ops, dynamic imports or module evaluations. Run the event loop to completion first.",
+        ));
+      }
+    }
 
     // Nuke Deno.core.ops.* to avoid ExternalReference snapshotting issues
     // TODO(@AaronO): make ops stable across snapshots
@@ -669,8 +1180,6 @@ impl JsRuntime {
       }
     }
 
-    let state = Self::state(self.v8_isolate());
-
     state.borrow_mut().global_realm.take();
 
     self.inspector.take();
@@ -682,16 +1191,28 @@ impl JsRuntime {
         Rc::new(NoopModuleLoader),
         state.borrow().op_state.clone(),
       ))));
-    // Drop other v8::Global handles before snapshotting
-    std::mem::take(&mut state.borrow_mut().js_recv_cb);
+    // Drop other v8::Global handles before snapshotting. User code may have
+    // registered any of these callbacks before the snapshot was requested.
+    {
+      let mut state = state.borrow_mut();
+      std::mem::take(&mut state.js_recv_cb);
+      std::mem::take(&mut state.known_realms);
+      std::mem::take(&mut state.js_macrotask_cbs);
+      std::mem::take(&mut state.js_nexttick_cbs);
+      std::mem::take(&mut state.js_promise_reject_cb);
+      std::mem::take(&mut state.js_uncaught_exception_cb);
+      std::mem::take(&mut state.js_wasm_streaming_cb);
+      std::mem::take(&mut state.pending_promise_exceptions);
+      std::mem::take(&mut state.explicit_terminate_exception);
+    }
 
     let snapshot_creator = self.snapshot_creator.as_mut().unwrap();
     let snapshot = snapshot_creator
       .create_blob(v8::FunctionCodeHandling::Keep)
-      .unwrap();
+      .ok_or_else(|| generic_error("Failed to create snapshot blob"))?;
     self.has_snapshotted = true;
 
-    snapshot
+    Ok(snapshot)
   }
 
   /// Returns the namespace object of a module.
@@ -730,6 +1251,79 @@ impl JsRuntime {
     Ok(v8::Global::new(scope, module_namespace))
   }
 
+  /// Returns the modules loaded so far together with how their imports were
+  /// resolved. Pass the result to `RuntimeOptions::module_map_data` of a
+  /// later runtime to skip resolution of modules that haven't changed.
+  pub fn serialize_module_map(&mut self) -> ModuleMapData {
+    let module_map_rc = Self::module_map(self.v8_isolate());
+    let module_map = module_map_rc.borrow();
+    module_map.serialize()
+  }
+
+  /// Starts collecting block-level precise coverage with call counts.
+  pub async fn start_precise_coverage(&mut self) -> Result<(), Error> {
+    if self.coverage_session.is_some() {
+      return Err(generic_error("Precise coverage is already being collected"));
+    }
+    let mut session =
+      PreciseCoverageSession::new(self.inspector().create_local_session());
+    self.poll_inspector_until(session.start()).await?;
+    self.coverage_session = Some(session);
+    Ok(())
+  }
+
+  /// Returns the coverage collected since `start_precise_coverage` or the
+  /// previous call, and resets the counters.
+  pub async fn take_precise_coverage(
+    &mut self,
+  ) -> Result<Vec<ScriptCoverage>, Error> {
+    let mut session = self.coverage_session.take().ok_or_else(|| {
+      generic_error("Precise coverage is not being collected")
+    })?;
+    let result = self.poll_inspector_until(session.take()).await;
+    self.coverage_session = Some(session);
+    result
+  }
+
+  pub async fn stop_precise_coverage(&mut self) -> Result<(), Error> {
+    let mut session = self.coverage_session.take().ok_or_else(|| {
+      generic_error("Precise coverage is not being collected")
+    })?;
+    self.poll_inspector_until(session.stop()).await
+  }
+
+  /// Runs all pending microtasks. Only needed with
+  /// `MicrotaskPolicy::Explicit`, otherwise they run on their own.
+  pub fn perform_microtask_checkpoint(&mut self) {
+    self.handle_scope().perform_microtask_checkpoint();
+  }
+
+  /// Takes a V8 heap snapshot in the `.heapsnapshot` format understood by
+  /// Chrome DevTools.
+  pub async fn heap_snapshot(&mut self) -> Result<String, Error> {
+    let mut session = self.inspector().create_local_session();
+    self
+      .poll_inspector_until(session.take_heap_snapshot())
+      .await
+  }
+
+  /// Drives `fut` to completion while polling the inspector, so messages
+  /// sent by local sessions are dispatched without running the event loop.
+  async fn poll_inspector_until<T>(
+    &mut self,
+    fut: impl Future<Output = T>,
+  ) -> T {
+    let mut fut = Box::pin(fut);
+    poll_fn(|cx| {
+      if let Poll::Ready(value) = fut.poll_unpin(cx) {
+        return Poll::Ready(value);
+      }
+      let _ = self.inspector().poll_unpin(cx);
+      fut.poll_unpin(cx)
+    })
+    .await
+  }
+
   /// Registers a callback on the isolate when the memory limits are approached.
   /// Use this to prevent V8 from crashing the process when reaching the limit.
   ///
@@ -757,6 +1351,62 @@ impl JsRuntime {
       .add_near_heap_limit_callback(near_heap_limit_callback::<C>, data);
   }
 
+  /// Registers a callback that is notified of unhandled promise rejections
+  /// and can mark them as handled. See `UnhandledRejectionCb`.
+  pub fn set_unhandled_rejection_cb(
+    &mut self,
+    cb: impl Fn(
+        &mut v8::HandleScope,
+        v8::Local<v8::Promise>,
+        v8::Local<v8::Value>,
+      ) -> bool
+      + 'static,
+  ) {
+    let state_rc = Self::state(self.v8_isolate());
+    state_rc.borrow_mut().unhandled_rejection_cb = Some(Rc::new(cb));
+  }
+
+  /// Registers a callback that is notified of uncaught exceptions. See
+  /// `UncaughtExceptionCb`.
+  pub fn set_uncaught_exception_cb(
+    &mut self,
+    cb: impl Fn(&mut v8::HandleScope, v8::Local<v8::Value>) + 'static,
+  ) {
+    let state_rc = Self::state(self.v8_isolate());
+    state_rc.borrow_mut().uncaught_exception_cb = Some(Rc::new(cb));
+  }
+
+  /// Like `add_near_heap_limit_callback`, but lets `cb` pick a
+  /// `NearHeapLimitAction` instead of returning a raw limit.
+  pub fn set_heap_limit_action(&mut self, mut cb: Box<NearHeapLimitFn>) {
+    let handle = self.v8_isolate().thread_safe_handle();
+    // The callback is only ever invoked by this isolate, so the pointer is
+    // valid whenever it runs.
+    let isolate_ptr: *mut v8::Isolate = &mut **self.v8_isolate();
+    self.add_near_heap_limit_callback(move |current_limit, initial_limit| {
+      match cb(current_limit, initial_limit) {
+        NearHeapLimitAction::Extend(limit) => limit.max(current_limit),
+        NearHeapLimitAction::Terminate => {
+          handle.terminate_execution();
+          current_limit.saturating_mul(2)
+        }
+        NearHeapLimitAction::HeapSnapshot(path) => {
+          // SAFETY: V8 invokes this callback on the isolate's own thread.
+          let isolate = unsafe { &mut *isolate_ptr };
+          if let Err(err) = write_heap_snapshot(isolate, &path) {
+            log::error!(
+              "Failed to write heap snapshot to {}: {}",
+              path.display(),
+              err
+            );
+          }
+          handle.terminate_execution();
+          current_limit.saturating_mul(2)
+        }
+      }
+    });
+  }
+
   pub fn remove_near_heap_limit_callback(&mut self, heap_limit: usize) {
     if let Some((_, cb)) = self.allocations.near_heap_limit_callback_data.take()
     {
@@ -776,7 +1426,7 @@ impl JsRuntime {
       // do nothing
     }
 
-    scope.perform_microtask_checkpoint();
+    run_microtasks_if_auto(scope);
   }
 
   pub fn poll_value(
@@ -862,10 +1512,14 @@ impl JsRuntime {
 
     // Ops
     {
-      self.resolve_async_ops(cx)?;
+      let resolved_ops = self.resolve_async_ops(cx)?;
       self.drain_nexttick()?;
       self.drain_macrotasks()?;
       self.check_promise_exceptions()?;
+      if resolved_ops == 0 {
+        self.advance_virtual_clock();
+      }
+      self.report_external_memory();
     }
 
     // Dynamic module loading - ie. modules loaded using "import()"
@@ -989,6 +1643,20 @@ Pending dynamic modules:\n".to_string();
   }
 }
 
+fn write_heap_snapshot(
+  isolate: &mut v8::Isolate,
+  path: &Path,
+) -> std::io::Result<()> {
+  use std::io::Write;
+  let mut file = std::fs::File::create(path)?;
+  let mut result = Ok(());
+  isolate.take_heap_snapshot(|chunk| {
+    result = file.write_all(chunk);
+    result.is_ok()
+  });
+  result
+}
+
 extern "C" fn near_heap_limit_callback<F>(
   data: *mut c_void,
   current_heap_limit: usize,
@@ -1002,6 +1670,24 @@ where
 }
 
 impl JsRuntimeState {
+  /// Returns the id of the realm whose context is currently entered in
+  /// `scope`.
+  pub(crate) fn current_realm_id(
+    &self,
+    scope: &mut v8::HandleScope,
+  ) -> RealmId {
+    if self.known_realms.is_empty() {
+      return GLOBAL_REALM_ID;
+    }
+    let context = scope.get_current_context();
+    self
+      .known_realms
+      .iter()
+      .find(|(_, known)| known.context == context)
+      .map(|(id, _)| *id)
+      .unwrap_or(GLOBAL_REALM_ID)
+  }
+
   /// Called by `bindings::host_import_module_dynamically_callback`
   /// after initiating new dynamic import load.
   pub fn notify_new_dynamic_import(&mut self) {
@@ -1010,6 +1696,16 @@ impl JsRuntimeState {
   }
 }
 
+/// Runs pending microtasks, unless the runtime was created with
+/// `MicrotaskPolicy::Explicit`.
+fn run_microtasks_if_auto(scope: &mut v8::HandleScope) {
+  let state_rc = JsRuntime::state(scope);
+  let policy = state_rc.borrow().microtask_policy;
+  if policy == MicrotaskPolicy::Auto {
+    scope.perform_microtask_checkpoint();
+  }
+}
+
 pub(crate) fn exception_to_err_result<'s, T>(
   scope: &mut v8::HandleScope<'s>,
   exception: v8::Local<v8::Value>,
@@ -1044,6 +1740,11 @@ pub(crate) fn exception_to_err_result<'s, T>(
       });
   }
 
+  let uncaught_exception_cb = state_rc.borrow().uncaught_exception_cb.clone();
+  if let Some(uncaught_exception_cb) = uncaught_exception_cb {
+    uncaught_exception_cb(scope, exception);
+  }
+
   let mut js_error = JsError::from_v8_exception(scope, exception);
   if in_promise {
     js_error.exception_message = format!(
@@ -1133,6 +1834,9 @@ impl JsRuntime {
     let tc_scope = &mut v8::TryCatch::new(scope);
     let module = v8::Local::new(tc_scope, &module_handle);
     let maybe_value = module.evaluate(tc_scope);
+    if maybe_value.is_some() {
+      module_map_rc.borrow_mut().store_code_caches(tc_scope);
+    }
 
     // Update status after evaluating.
     let status = module.get_status();
@@ -1219,6 +1923,9 @@ impl JsRuntime {
     // https://github.com/denoland/deno/issues/4908
     // https://v8.dev/features/top-level-await#module-execution-order
     let maybe_value = module.evaluate(tc_scope);
+    if maybe_value.is_some() {
+      module_map_rc.borrow_mut().store_code_caches(tc_scope);
+    }
 
     // Update status after evaluating.
     status = module.get_status();
@@ -1250,7 +1957,8 @@ impl JsRuntime {
         promise: promise_global,
         sender,
       });
-      tc_scope.perform_microtask_checkpoint();
+      drop(state);
+      run_microtasks_if_auto(tc_scope);
     } else if tc_scope.has_terminated() || tc_scope.is_execution_terminating() {
       sender.send(Err(
         generic_error("Cannot evaluate module, because JavaScript execution has been terminated.")
@@ -1283,7 +1991,7 @@ impl JsRuntime {
     // will reach into `ModuleMap` from within the isolate.
     let exception = v8::Local::new(scope, exception);
     resolver.reject(scope, exception).unwrap();
-    scope.perform_microtask_checkpoint();
+    run_microtasks_if_auto(scope);
   }
 
   fn dynamic_import_resolve(&mut self, id: ModuleLoadId, mod_id: ModuleId) {
@@ -1315,7 +2023,7 @@ impl JsRuntime {
     let module_namespace = module.get_module_namespace();
     resolver.resolve(scope, module_namespace).unwrap();
     state_rc.borrow_mut().dyn_module_evaluate_idle_counter = 0;
-    scope.perform_microtask_checkpoint();
+    run_microtasks_if_auto(scope);
   }
 
   fn prepare_dyn_imports(
@@ -1473,13 +2181,13 @@ impl JsRuntime {
         state_rc.borrow_mut().pending_mod_evaluate = Some(module_evaluation);
       }
       v8::PromiseState::Fulfilled => {
-        scope.perform_microtask_checkpoint();
+        run_microtasks_if_auto(scope);
         // Receiver end might have been already dropped, ignore the result
         let _ = module_evaluation.sender.send(Ok(()));
       }
       v8::PromiseState::Rejected => {
         let exception = promise.result(scope);
-        scope.perform_microtask_checkpoint();
+        run_microtasks_if_auto(scope);
         // Receiver end might have been already dropped, ignore the result
         let _ = module_evaluation
           .sender
@@ -1650,45 +2358,114 @@ impl JsRuntime {
     Ok(root_id)
   }
 
+  /// Replaces the modules in `changed` with their current sources.
+  ///
+  /// V8 can't re-link a module that has already been evaluated, so the live
+  /// bindings of existing importers can't be patched in place. Instead the
+  /// changed modules and every module that transitively imports them are
+  /// invalidated, and the topmost invalidated modules are loaded and
+  /// evaluated again as side modules. Unchanged dependencies are shared with
+  /// the previous module graph and are not evaluated again.
+  ///
+  /// Returns the specifiers of the modules that were evaluated again.
+  pub async fn reload_modules(
+    &mut self,
+    changed: &[ModuleSpecifier],
+  ) -> Result<Vec<ModuleSpecifier>, Error> {
+    let module_map_rc = Self::module_map(self.v8_isolate());
+    let names = changed.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+    let roots = module_map_rc.borrow_mut().invalidate(&names);
+    for root in &roots {
+      let id = self.load_side_module(root, None).await?;
+      let mut receiver = self.mod_evaluate(id);
+      poll_fn(|cx| {
+        if let Poll::Ready(Err(err)) = self.poll_event_loop(cx, false) {
+          return Poll::Ready(Err(err));
+        }
+        receiver
+          .poll_unpin(cx)
+          .map(|result| result.expect("Module evaluation result not provided."))
+      })
+      .await?;
+    }
+    Ok(roots)
+  }
+
   fn check_promise_exceptions(&mut self) -> Result<(), Error> {
-    let state_rc = Self::state(self.v8_isolate());
-    let mut state = state_rc.borrow_mut();
+    loop {
+      let state_rc = Self::state(self.v8_isolate());
+      let mut state = state_rc.borrow_mut();
 
-    if state.pending_promise_exceptions.is_empty() {
-      return Ok(());
+      let key = match state.pending_promise_exceptions.keys().next() {
+        Some(key) => key.clone(),
+        None => return Ok(()),
+      };
+      let handle = state.pending_promise_exceptions.remove(&key).unwrap();
+      let unhandled_rejection_cb = state.unhandled_rejection_cb.clone();
+      drop(state);
+
+      let scope = &mut self.handle_scope();
+      let exception = v8::Local::new(scope, handle);
+      if let Some(unhandled_rejection_cb) = unhandled_rejection_cb {
+        let promise = v8::Local::new(scope, key);
+        if unhandled_rejection_cb(scope, promise, exception) {
+          continue;
+        }
+      }
+      return exception_to_err_result(scope, exception, true);
     }
+  }
 
-    let key = {
-      state
-        .pending_promise_exceptions
-        .keys()
-        .next()
-        .unwrap()
-        .clone()
-    };
-    let handle = state.pending_promise_exceptions.remove(&key).unwrap();
-    drop(state);
+  /// In deterministic mode, fires the next virtual timers once the event
+  /// loop has nothing else to do.
+  fn advance_virtual_clock(&mut self) {
+    let state_rc = Self::state(self.v8_isolate());
+    let state = state_rc.borrow();
+    if state.have_unpolled_ops || state.has_tick_scheduled {
+      return;
+    }
+    let mut op_state = state.op_state.borrow_mut();
+    if let Some(clock) = op_state.try_borrow_mut::<VirtualClock>() {
+      if clock.advance() {
+        state.waker.wake();
+      }
+    }
+  }
 
-    let scope = &mut self.handle_scope();
-    let exception = v8::Local::new(scope, handle);
-    exception_to_err_result(scope, exception, true)
+  /// Tells V8 about changes in the external memory held by resources, so
+  /// that it can factor it in when scheduling garbage collection.
+  fn report_external_memory(&mut self) {
+    let state_rc = Self::state(self.v8_isolate());
+    let delta = {
+      let state = state_rc.borrow();
+      let mut op_state = state.op_state.borrow_mut();
+      op_state.resource_table.take_external_memory_delta()
+    };
+    if delta != 0 {
+      self
+        .v8_isolate()
+        .adjust_amount_of_external_allocated_memory(delta);
+    }
   }
 
   // Send finished responses to JS
-  fn resolve_async_ops(&mut self, cx: &mut Context) -> Result<(), Error> {
+  /// Delivers completed async ops to JS and returns how many there were.
+  fn resolve_async_ops(&mut self, cx: &mut Context) -> Result<usize, Error> {
     let state_rc = Self::state(self.v8_isolate());
 
-    let js_recv_cb_handle = state_rc.borrow().js_recv_cb.clone().unwrap();
-    let scope = &mut self.handle_scope();
-
     // We return async responses to JS in unbounded batches (may change),
-    // each batch is a flat vector of tuples:
-    // `[promise_id1, op_result1, promise_id2, op_result2, ...]`
-    // promise_id is a simple integer, op_result is an ops::OpResult
-    // which contains a value OR an error, encoded as a tuple.
-    // This batch is received in JS via the special `arguments` variable
-    // and then each tuple is used to resolve or reject promises
-    let mut args: Vec<v8::Local<v8::Value>> = vec![];
+    // one per event loop turn. Each batch is a pair of
+    // `(promise_ids: Int32Array, results: Array)` of the same length, where
+    // `results[i]` is the ops::OpResult (a value OR an error) of the op that
+    // `promise_ids[i]` belongs to. JS then resolves or rejects the promises.
+    //
+    // Every realm keeps its own promise table, so responses are batched per
+    // realm the op was dispatched from.
+    let mut responses: BTreeMap<RealmId, Vec<(PromiseId, OpResult)>> =
+      BTreeMap::new();
+
+    let mut resolved_ops = 0;
+    let deterministic;
 
     // Now handle actual ops.
     {
@@ -1697,27 +2474,82 @@ impl JsRuntime {
 
       while let Poll::Ready(Some(item)) = state.pending_ops.poll_next_unpin(cx)
       {
-        let (promise_id, op_id, resp) = item;
+        let (realm_id, promise_id, op_id, resp) = item;
         state.unrefed_ops.remove(&promise_id);
         state.op_state.borrow().tracker.track_async_completed(op_id);
-        args.push(v8::Integer::new(scope, promise_id as i32).into());
-        args.push(resp.to_v8(scope).unwrap());
+        responses
+          .entry(realm_id)
+          .or_default()
+          .push((promise_id, resp));
+        resolved_ops += 1;
       }
-    }
 
-    if args.is_empty() {
-      return Ok(());
+      // Completion order within a tick depends on how the futures were
+      // polled, so normalize it before applying the seeded shuffle.
+      deterministic = state.op_shuffler.is_some();
+      if let Some(shuffler) = &mut state.op_shuffler {
+        for responses in responses.values_mut() {
+          responses.sort_by_key(|(promise_id, _)| *promise_id);
+          shuffler.shuffle(responses);
+        }
+      }
     }
 
-    let tc_scope = &mut v8::TryCatch::new(scope);
-    let js_recv_cb = js_recv_cb_handle.open(tc_scope);
-    let this = v8::undefined(tc_scope).into();
-    js_recv_cb.call(tc_scope, this, args.as_slice());
+    for (realm_id, responses) in responses {
+      let (context, js_recv_cb_handle) = {
+        let state = state_rc.borrow();
+        if realm_id == GLOBAL_REALM_ID {
+          (
+            state.global_realm.clone().unwrap().0,
+            state.js_recv_cb.clone().unwrap(),
+          )
+        } else {
+          match state.known_realms.get(&realm_id) {
+            Some(known) => (known.context.clone(), known.js_recv_cb.clone()),
+            // The realm was disposed while the ops were in flight.
+            None => continue,
+          }
+        }
+      };
 
-    match tc_scope.exception() {
-      None => Ok(()),
-      Some(exception) => exception_to_err_result(tc_scope, exception, false),
+      let scope =
+        &mut v8::HandleScope::with_context(self.v8_isolate(), &context);
+      // In deterministic mode every completion is delivered by its own call,
+      // so the microtasks it queues run before the next one is delivered.
+      let batch_size = if deterministic { 1 } else { responses.len() };
+      let mut responses = responses.into_iter().peekable();
+      while responses.peek().is_some() {
+        let mut promise_ids: Vec<u8> = Vec::with_capacity(batch_size * 4);
+        let mut results: Vec<v8::Local<v8::Value>> =
+          Vec::with_capacity(batch_size);
+        for (promise_id, resp) in responses.by_ref().take(batch_size) {
+          promise_ids.extend_from_slice(&promise_id.to_ne_bytes());
+          results.push(resp.to_v8(scope).unwrap());
+        }
+
+        let backing_store = v8::ArrayBuffer::new_backing_store_from_boxed_slice(
+          promise_ids.into_boxed_slice(),
+        );
+        let buffer = v8::ArrayBuffer::with_backing_store(
+          scope,
+          &backing_store.make_shared(),
+        );
+        let promise_ids =
+          v8::Int32Array::new(scope, buffer, 0, results.len()).unwrap();
+        let results = v8::Array::new_with_elements(scope, &results);
+
+        let tc_scope = &mut v8::TryCatch::new(scope);
+        let js_recv_cb = js_recv_cb_handle.open(tc_scope);
+        let this = v8::undefined(tc_scope).into();
+        js_recv_cb.call(tc_scope, this, &[promise_ids.into(), results.into()]);
+
+        if let Some(exception) = tc_scope.exception() {
+          return exception_to_err_result(tc_scope, exception, false);
+        }
+      }
     }
+
+    Ok(resolved_ops)
   }
 
   fn drain_macrotasks(&mut self) -> Result<(), Error> {
@@ -1768,7 +2600,7 @@ impl JsRuntime {
 
     if !state.borrow().has_tick_scheduled {
       let scope = &mut self.handle_scope();
-      scope.perform_microtask_checkpoint();
+      run_microtasks_if_auto(scope);
     }
 
     // TODO(bartlomieju): Node also checks for absence of "rejection_to_warn"
@@ -1890,12 +2722,16 @@ impl JsRealm {
 
 #[inline]
 pub fn queue_async_op(
-  scope: &v8::Isolate,
+  scope: &mut v8::HandleScope,
   op: impl Future<Output = (PromiseId, OpId, OpResult)> + 'static,
 ) {
   let state_rc = JsRuntime::state(scope);
   let mut state = state_rc.borrow_mut();
-  state.pending_ops.push(OpCall::eager(op));
+  let realm_id = state.current_realm_id(scope);
+  state.pending_ops.push(OpCall::eager(async move {
+    let (promise_id, op_id, result) = op.await;
+    (realm_id, promise_id, op_id, result)
+  }));
   state.have_unpolled_ops = true;
 }
 
@@ -1907,6 +2743,8 @@ pub mod tests {
   use crate::modules::ModuleSource;
   use crate::modules::ModuleSourceFuture;
   use crate::modules::ModuleType;
+  use crate::JsRuntimePool;
+  use crate::Resource;
   use crate::ZeroCopyBuf;
   use deno_ops::op;
   use futures::future::lazy;
@@ -2229,6 +3067,420 @@ pub mod tests {
     );
   }
 
+  #[tokio::test]
+  async fn test_op_sleep() {
+    let mut runtime = JsRuntime::new(Default::default());
+    runtime
+      .execute_script(
+        "op_sleep.js",
+        r#"
+        const rid = Deno.core.opSync("op_timer_handle");
+        Deno.core.opAsync("op_sleep", 10, rid).then(() => {
+          globalThis.fired = true;
+        });
+        const cancelRid = Deno.core.opSync("op_timer_handle");
+        Deno.core.opAsync("op_sleep", 60000, cancelRid).then(
+          () => { throw new Error("timer should have been canceled"); },
+          () => { globalThis.canceled = true; },
+        );
+        Deno.core.close(cancelRid);
+        "#,
+      )
+      .unwrap();
+    runtime.run_event_loop(false).await.unwrap();
+    runtime
+      .execute_script(
+        "op_sleep_check.js",
+        r#"
+        if (!globalThis.fired || !globalThis.canceled) {
+          throw new Error("expected one timer to fire and one to cancel");
+        }
+        "#,
+      )
+      .unwrap();
+  }
+
+  #[tokio::test]
+  async fn test_precise_coverage() {
+    let mut runtime = JsRuntime::new(Default::default());
+    runtime.start_precise_coverage().await.unwrap();
+    assert!(runtime.start_precise_coverage().await.is_err());
+    runtime
+      .execute_script(
+        "coverage.js",
+        r#"
+        function covered(n) {
+          if (n > 0) return n;
+          return -n;
+        }
+        covered(1);
+        covered(2);
+        "#,
+      )
+      .unwrap();
+    let coverage = runtime.take_precise_coverage().await.unwrap();
+    let script = coverage
+      .iter()
+      .find(|script| script.url == "coverage.js")
+      .expect("coverage for coverage.js");
+    let function = script
+      .functions
+      .iter()
+      .find(|function| function.function_name == "covered")
+      .unwrap();
+    assert!(function.is_block_coverage);
+    assert_eq!(function.ranges[0].count, 2);
+    runtime.stop_precise_coverage().await.unwrap();
+    assert!(runtime.take_precise_coverage().await.is_err());
+  }
+
+  #[tokio::test]
+  async fn test_op_profile() {
+    let mut runtime = JsRuntime::new(Default::default());
+    runtime
+      .execute_script(
+        "op_profile.js",
+        r#"
+        (async () => {
+          await Deno.core.opAsync("op_profile_start", "test");
+          let threw = false;
+          try {
+            await Deno.core.opAsync("op_profile_start", "again");
+          } catch {
+            threw = true;
+          }
+          if (!threw) throw new Error("expected a second start to fail");
+          for (let i = 0; i < 1000; i++) Math.sqrt(i);
+          globalThis.profile = await Deno.core.opAsync("op_profile_stop");
+        })();
+        "#,
+      )
+      .unwrap();
+    runtime.run_event_loop(false).await.unwrap();
+    runtime
+      .execute_script(
+        "op_profile_check.js",
+        r#"
+        if (!Array.isArray(globalThis.profile.nodes)) {
+          throw new Error("expected a .cpuprofile object");
+        }
+        "#,
+      )
+      .unwrap();
+  }
+
+  #[test]
+  fn test_explicit_microtask_policy() {
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      microtask_policy: MicrotaskPolicy::Explicit,
+      ..Default::default()
+    });
+    runtime
+      .execute_script(
+        "queue.js",
+        "globalThis.ran = false; Promise.resolve().then(() => { ran = true; });",
+      )
+      .unwrap();
+    runtime
+      .execute_script("check1.js", "if (ran) throw new Error('ran early');")
+      .unwrap();
+    runtime.perform_microtask_checkpoint();
+    runtime
+      .execute_script("check2.js", "if (!ran) throw new Error('not run');")
+      .unwrap();
+  }
+
+  #[tokio::test]
+  async fn test_heap_snapshot() {
+    let mut runtime = JsRuntime::new(Default::default());
+    runtime
+      .execute_script("heap.js", "globalThis.retained = { answer: 42 };")
+      .unwrap();
+    let snapshot = runtime.heap_snapshot().await.unwrap();
+    let snapshot: serde_json::Value = serde_json::from_str(&snapshot).unwrap();
+    assert!(snapshot["snapshot"]["node_count"].as_u64().unwrap() > 0);
+    assert!(snapshot["nodes"].is_array());
+
+    let path = std::env::temp_dir().join(format!(
+      "deno_core_test_{}.heapsnapshot",
+      std::process::id()
+    ));
+    runtime
+      .execute_script(
+        "heap_op.js",
+        &format!(
+          "Deno.core.heapSnapshot({});",
+          serde_json::to_string(&path).unwrap()
+        ),
+      )
+      .unwrap();
+    runtime.run_event_loop(false).await.unwrap();
+    let written = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(written.starts_with('{'));
+  }
+
+  #[test]
+  fn test_resource_external_memory() {
+    struct BigBuffer(Vec<u8>);
+
+    impl Resource for BigBuffer {
+      fn external_memory(&self) -> usize {
+        self.0.len()
+      }
+    }
+
+    let mut runtime = JsRuntime::new(Default::default());
+    let op_state = runtime.op_state();
+    let (rid1, rid2) = {
+      let resource_table = &mut op_state.borrow_mut().resource_table;
+      let rid1 = resource_table.add(BigBuffer(vec![0; 1024]));
+      let rid2 = resource_table.add(BigBuffer(vec![0; 4096]));
+      assert_eq!(resource_table.external_memory(), 5120);
+      (rid1, rid2)
+    };
+    runtime.report_external_memory();
+    {
+      let resource_table = &mut op_state.borrow_mut().resource_table;
+      assert_eq!(resource_table.take_external_memory_delta(), 0);
+      resource_table.close(rid1).unwrap();
+      resource_table.replace(rid2, BigBuffer(vec![0; 2048]));
+      assert_eq!(resource_table.external_memory(), 2048);
+      assert_eq!(resource_table.take_external_memory_delta(), -3072);
+      resource_table.take::<BigBuffer>(rid2).unwrap();
+      assert_eq!(resource_table.external_memory(), 0);
+    }
+    runtime.report_external_memory();
+  }
+
+  #[tokio::test]
+  async fn test_message_channel_ops() {
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      shared_array_buffer_store: Some(Default::default()),
+      ..Default::default()
+    });
+    runtime
+      .execute_script(
+        "message_channel.js",
+        r#"
+        (async () => {
+          const [a, b] = Deno.core.opSync("op_message_channel_create");
+          const buf = new Uint8Array([1, 2, 3]).buffer;
+          const transfer = [buf];
+          const data = Deno.core.serialize(
+            { buf, n: 1 },
+            { transferedArrayBuffers: transfer },
+          );
+          if (!Deno.core.opSync("op_message_channel_write", a, data, transfer)) {
+            throw new Error("write failed");
+          }
+          if (buf.byteLength !== 0) throw new Error("buffer not detached");
+
+          const msg = await Deno.core.opAsync("op_message_channel_read", b);
+          const value = Deno.core.deserialize(msg.data, {
+            transferedArrayBuffers: msg.transferredArrayBuffers,
+          });
+          if (value.n !== 1) throw new Error("wrong value");
+          if (new Uint8Array(value.buf)[2] !== 3) throw new Error("wrong buf");
+
+          Deno.core.close(a);
+          if (await Deno.core.opAsync("op_message_channel_read", b) !== null) {
+            throw new Error("expected end of channel");
+          }
+          Deno.core.close(b);
+        })();
+        "#,
+      )
+      .unwrap();
+    runtime.run_event_loop(false).await.unwrap();
+  }
+
+  #[test]
+  fn test_message_channel_across_threads() {
+    let shared_array_buffer_store = SharedArrayBufferStore::default();
+    let (end1, end2) = crate::message_channel();
+
+    let store = shared_array_buffer_store.clone();
+    let reader = std::thread::spawn(move || {
+      let mut runtime = JsRuntime::new(RuntimeOptions {
+        shared_array_buffer_store: Some(store),
+        ..Default::default()
+      });
+      let rid = runtime
+        .op_state()
+        .borrow_mut()
+        .resource_table
+        .add(crate::MessageChannelResource::new(end2));
+      runtime
+        .execute_script(
+          "reader.js",
+          &format!(
+            r#"
+            (async () => {{
+              const msg = await Deno.core.opAsync("op_message_channel_read", {});
+              const value = Deno.core.deserialize(msg.data, {{
+                transferedArrayBuffers: msg.transferredArrayBuffers,
+              }});
+              if (new Uint8Array(value)[0] !== 42) throw new Error();
+            }})();
+            "#,
+            rid
+          ),
+        )
+        .unwrap();
+      futures::executor::block_on(runtime.run_event_loop(false)).unwrap();
+    });
+
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      shared_array_buffer_store: Some(shared_array_buffer_store),
+      ..Default::default()
+    });
+    let rid = runtime
+      .op_state()
+      .borrow_mut()
+      .resource_table
+      .add(crate::MessageChannelResource::new(end1));
+    runtime
+      .execute_script(
+        "writer.js",
+        &format!(
+          r#"
+          const buf = new Uint8Array([42]).buffer;
+          const transfer = [buf];
+          const data = Deno.core.serialize(buf, {{
+            transferedArrayBuffers: transfer,
+          }});
+          Deno.core.opSync("op_message_channel_write", {}, data, transfer);
+          "#,
+          rid
+        ),
+      )
+      .unwrap();
+    reader.join().unwrap();
+  }
+
+  #[test]
+  fn test_shared_array_buffer_store_pins() {
+    let store = SharedArrayBufferStore::default();
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      shared_array_buffer_store: Some(store.clone()),
+      ..Default::default()
+    });
+    runtime
+      .execute_script(
+        "serialize.js",
+        r#"
+        {
+          const sab = new SharedArrayBuffer(4);
+          new Int32Array(sab)[0] = 7;
+          globalThis.data = Deno.core.serialize(sab);
+        }
+        "#,
+      )
+      .unwrap();
+    assert_eq!(store.len(), 1);
+
+    // Deliver the message twice; the extra pin keeps the memory alive until
+    // the second copy has been deserialized.
+    assert!(store.pin(0));
+    for _ in 0..2 {
+      runtime
+        .execute_script(
+          "deserialize.js",
+          r#"
+          {
+            const sab = Deno.core.deserialize(globalThis.data);
+            if (new Int32Array(sab)[0] !== 7) throw new Error();
+          }
+          "#,
+        )
+        .unwrap();
+    }
+    assert!(store.is_empty());
+    assert!(!store.unpin(0));
+  }
+
+  #[tokio::test]
+  async fn test_deterministic_event_loop() {
+    async fn run(seed: u64) -> String {
+      let mut runtime = JsRuntime::new(RuntimeOptions {
+        deterministic_seed: Some(seed),
+        ..Default::default()
+      });
+      runtime
+        .execute_script(
+          "deterministic.js",
+          r#"
+          globalThis.log = [];
+          const sleep = (ms, name) =>
+            Deno.core
+              .opAsync("op_sleep", ms, Deno.core.opSync("op_timer_handle"))
+              .then(() => log.push(name));
+          sleep(60_000, "late");
+          sleep(10, "early");
+          for (let i = 0; i < 8; i++) {
+            Deno.core.opAsync("op_void_async").then(() => log.push(i));
+          }
+          "#,
+        )
+        .unwrap();
+      let start = std::time::Instant::now();
+      runtime.run_event_loop(false).await.unwrap();
+      assert!(start.elapsed() < std::time::Duration::from_secs(60));
+      assert_eq!(
+        runtime
+          .op_state()
+          .borrow()
+          .borrow::<VirtualClock>()
+          .now()
+          .as_millis(),
+        60_000
+      );
+      let log = runtime
+        .execute_script("log.js", "globalThis.log.join(',')")
+        .unwrap();
+      let scope = &mut runtime.handle_scope();
+      let log = v8::Local::new(scope, log);
+      serde_v8::from_v8(scope, log).unwrap()
+    }
+
+    let log = run(1).await;
+    assert!(log.ends_with("early,late"));
+    assert_eq!(log, run(1).await);
+  }
+
+  #[tokio::test]
+  async fn test_deterministic_date() {
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      deterministic_seed: Some(0),
+      ..Default::default()
+    });
+    runtime
+      .execute_script(
+        "deterministic_date.js",
+        r#"
+        if (Date.now() !== 0) throw new Error("start");
+        globalThis.times = [];
+        Deno.core
+          .opAsync("op_sleep", 1500, Deno.core.opSync("op_timer_handle"))
+          .then(() => {
+            const date = new Date();
+            if (!(date instanceof Date)) throw new Error("instanceof");
+            times.push(Date.now(), date.getTime());
+          });
+        "#,
+      )
+      .unwrap();
+    runtime.run_event_loop(false).await.unwrap();
+    let times = runtime
+      .execute_script("times.js", "globalThis.times.join(',')")
+      .unwrap();
+    let scope = &mut runtime.handle_scope();
+    let times = v8::Local::new(scope, times);
+    let times: String = serde_v8::from_v8(scope, times).unwrap();
+    assert_eq!(times, "1500,1500");
+  }
+
   #[test]
   fn terminate_execution() {
     let (mut isolate, _dispatch_count) = setup(Mode::Async);
@@ -2370,6 +3622,97 @@ pub mod tests {
       .unwrap();
   }
 
+  #[tokio::test]
+  async fn test_unhandled_rejection_and_uncaught_exception_cbs() {
+    let rejections = Rc::new(RefCell::new(vec![]));
+    let exceptions = Rc::new(RefCell::new(vec![]));
+    let mut runtime = JsRuntime::new(Default::default());
+    {
+      let rejections = rejections.clone();
+      runtime.set_unhandled_rejection_cb(move |scope, _promise, reason| {
+        let reason = reason.to_rust_string_lossy(scope);
+        let handled = reason == "handled";
+        rejections.borrow_mut().push(reason);
+        handled
+      });
+    }
+    {
+      let exceptions = exceptions.clone();
+      runtime.set_uncaught_exception_cb(move |scope, exception| {
+        exceptions
+          .borrow_mut()
+          .push(exception.to_rust_string_lossy(scope));
+      });
+    }
+
+    runtime
+      .execute_script("handled.js", "Promise.reject('handled');")
+      .unwrap();
+    runtime.run_event_loop(false).await.unwrap();
+    assert_eq!(*rejections.borrow(), vec!["handled"]);
+    assert!(exceptions.borrow().is_empty());
+
+    runtime
+      .execute_script("unhandled.js", "Promise.reject('unhandled');")
+      .unwrap();
+    assert!(runtime.run_event_loop(false).await.is_err());
+    assert_eq!(*rejections.borrow(), vec!["handled", "unhandled"]);
+    assert_eq!(*exceptions.borrow(), vec!["unhandled"]);
+
+    assert!(runtime
+      .execute_script("throw.js", "throw new Error('oops')")
+      .is_err());
+    assert_eq!(exceptions.borrow().len(), 2);
+    assert_eq!(exceptions.borrow()[1], "Error: oops");
+  }
+
+  #[test]
+  fn test_runtime_pool_resets_runtimes() {
+    struct Leftover;
+    impl Resource for Leftover {}
+
+    let snapshot = {
+      let mut runtime = JsRuntime::new(RuntimeOptions {
+        will_snapshot: true,
+        ..Default::default()
+      });
+      runtime.execute_script("a.js", "a = 1 + 2").unwrap();
+      assert!(runtime.reset().is_err());
+      runtime.snapshot()
+    };
+    let snapshot: &'static [u8] =
+      Box::leak(snapshot.to_vec().into_boxed_slice());
+
+    let mut pool = JsRuntimePool::new(2, move || RuntimeOptions {
+      startup_snapshot: Some(Snapshot::Static(snapshot)),
+      ..Default::default()
+    });
+    assert_eq!(pool.idle(), 2);
+
+    let mut runtime = pool.take();
+    assert_eq!(pool.idle(), 1);
+    runtime
+      .execute_script("request1.js", "if (a != 3) throw Error('x'); b = 1;")
+      .unwrap();
+    let rid = runtime.op_state().borrow_mut().resource_table.add(Leftover);
+    pool.give_back(runtime).unwrap();
+    assert_eq!(pool.idle(), 2);
+
+    let mut runtime = pool.take();
+    assert!(!runtime.op_state().borrow().resource_table.has(rid));
+    runtime
+      .execute_script(
+        "request2.js",
+        r#"
+        if (a != 3) throw Error('snapshot state is gone');
+        if (typeof b !== "undefined") throw Error('state leaked');
+        Deno.core.opSync("op_resources");
+        "#,
+      )
+      .unwrap();
+    pool.give_back(runtime).unwrap();
+  }
+
   #[test]
   fn test_from_boxed_snapshot() {
     let snapshot = {
@@ -2562,6 +3905,61 @@ pub mod tests {
     assert!(callback_invoke_count_second.load(Ordering::SeqCst) > 0);
   }
 
+  #[test]
+  fn test_heap_limits_option_terminates() {
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      heap_limits: Some(HeapLimits {
+        initial: 0,
+        max: 3 * 1024 * 1024,
+        near_heap_limit_callback: None,
+      }),
+      ..Default::default()
+    });
+    let err = runtime
+      .execute_script(
+        "script name",
+        r#"let s = ""; while(true) { s += "Hello"; }"#,
+      )
+      .expect_err("script should fail");
+    assert_eq!(
+      "Uncaught Error: execution terminated",
+      err.downcast::<JsError>().unwrap().exception_message
+    );
+  }
+
+  #[test]
+  fn test_heap_limits_option_extend() {
+    let callback_invoke_count = Rc::new(AtomicUsize::new(0));
+    let inner_invoke_count = Rc::clone(&callback_invoke_count);
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      heap_limits: Some(HeapLimits {
+        initial: 0,
+        max: 3 * 1024 * 1024,
+        near_heap_limit_callback: Some(Box::new(
+          move |current_limit, _initial_limit| {
+            if inner_invoke_count.fetch_add(1, Ordering::SeqCst) == 0 {
+              NearHeapLimitAction::Extend(current_limit * 2)
+            } else {
+              NearHeapLimitAction::Terminate
+            }
+          },
+        )),
+      }),
+      ..Default::default()
+    });
+    let err = runtime
+      .execute_script(
+        "script name",
+        r#"let s = ""; while(true) { s += "Hello"; }"#,
+      )
+      .expect_err("script should fail");
+    assert_eq!(
+      "Uncaught Error: execution terminated",
+      err.downcast::<JsError>().unwrap().exception_message
+    );
+    assert!(callback_invoke_count.load(Ordering::SeqCst) > 1);
+  }
+
   #[test]
   fn es_snapshot() {
     #[derive(Default)]
@@ -2611,6 +4009,50 @@ pub mod tests {
     let _snapshot = runtime.snapshot();
   }
 
+  #[test]
+  fn es_snapshot_after_evaluation() {
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      module_loader: Some(Rc::new(crate::FsModuleLoader)),
+      will_snapshot: true,
+      ..Default::default()
+    });
+
+    let specifier = crate::resolve_url("file:///main.js").unwrap();
+    let source_code = r#"
+      Deno.core.setMacrotaskCallback(() => true);
+      globalThis.warm = await Promise.resolve(40 + 2);
+    "#
+    .to_string();
+
+    let module_id = futures::executor::block_on(
+      runtime.load_main_module(&specifier, Some(source_code)),
+    )
+    .unwrap();
+    let receiver = runtime.mod_evaluate(module_id);
+    futures::executor::block_on(runtime.run_event_loop(false)).unwrap();
+    futures::executor::block_on(receiver).unwrap().unwrap();
+
+    let snapshot = runtime.try_snapshot().unwrap();
+    assert!(runtime.try_snapshot().is_err());
+
+    let mut runtime2 = JsRuntime::new(RuntimeOptions {
+      startup_snapshot: Some(Snapshot::JustCreated(snapshot)),
+      ..Default::default()
+    });
+    runtime2
+      .execute_script(
+        "check.js",
+        "if (globalThis.warm !== 42) throw Error('x')",
+      )
+      .unwrap();
+  }
+
+  #[test]
+  fn try_snapshot_without_will_snapshot() {
+    let mut runtime = JsRuntime::new(Default::default());
+    assert!(runtime.try_snapshot().is_err());
+  }
+
   #[test]
   fn test_error_without_stack() {
     let mut runtime = JsRuntime::new(RuntimeOptions::default());
@@ -3209,6 +4651,65 @@ assertEquals(1, notify_return_value);
     assert!(r.open(scope).is_undefined());
   }
 
+  #[test]
+  fn test_runtime_disabled_ops() {
+    #[op]
+    fn op_foo() -> Result<i64, anyhow::Error> {
+      Ok(42)
+    }
+
+    #[op]
+    fn op_bar() -> Result<i64, anyhow::Error> {
+      Ok(42)
+    }
+
+    let ext = Extension::builder()
+      .ops(vec![op_foo::decl(), op_bar::decl()])
+      .build();
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      extensions: vec![ext],
+      disabled_ops: vec!["op_bar"],
+      ..Default::default()
+    });
+    runtime
+      .execute_script(
+        "test.js",
+        r#"
+        if (Deno.core.opSync('op_foo') !== 42) {
+          throw new Error("Expected op_foo() === 42");
+        }
+        let err;
+        try {
+          Deno.core.opSync('op_bar');
+        } catch (e) {
+          err = e;
+        }
+        if (!(err instanceof TypeError)) {
+          throw new Error("Expected op_bar to throw a TypeError");
+        }
+        if (err.message !== "Op 'op_bar' is disabled in this runtime") {
+          throw new Error(`Unexpected error message: ${err.message}`);
+        }
+      "#,
+      )
+      .unwrap();
+  }
+
+  #[test]
+  fn test_runtime_disabled_ops_unknown() {
+    #[op]
+    fn op_foo() -> Result<i64, anyhow::Error> {
+      Ok(42)
+    }
+
+    let mut extensions =
+      vec![Extension::builder().ops(vec![op_foo::decl()]).build()];
+    let err = JsRuntime::collect_ops(&mut extensions, &["op_fooo"])
+      .err()
+      .unwrap();
+    assert_eq!(err.to_string(), "Unknown op in disabled_ops: op_fooo");
+  }
+
   #[test]
   fn test_op_detached_buffer() {
     use serde_v8::DetachedBuffer;
@@ -3320,6 +4821,52 @@ assertEquals(1, notify_return_value);
       .unwrap();
   }
 
+  #[test]
+  fn test_extension_teardown() {
+    struct TestResource(Arc<AtomicUsize>);
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let counter_ = counter.clone();
+    let ext = Extension::builder()
+      .state(move |state| {
+        state.put(TestResource(counter_.clone()));
+        Ok(())
+      })
+      .teardown(|state| {
+        let resource = state.take::<TestResource>();
+        resource.0.fetch_add(1, Ordering::Relaxed);
+      })
+      .build();
+    let runtime = JsRuntime::new(RuntimeOptions {
+      extensions: vec![ext],
+      ..Default::default()
+    });
+    assert_eq!(counter.load(Ordering::Relaxed), 0);
+    drop(runtime);
+    assert_eq!(counter.load(Ordering::Relaxed), 1);
+  }
+
+  #[test]
+  fn test_dump_op_metrics() {
+    let dir = std::env::temp_dir().join("deno_core_test_dump_op_metrics");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("metrics.json");
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      dump_op_metrics: Some(path.clone()),
+      ..Default::default()
+    });
+    runtime
+      .execute_script("a.js", "Deno.core.opSync('op_void_sync')")
+      .unwrap();
+    drop(runtime);
+
+    let metrics: serde_json::Value =
+      serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(metrics["ops"]["op_void_sync"]["opsDispatchedSync"], 1);
+    assert!(metrics["aggregate"]["opsDispatched"].as_u64().unwrap() >= 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
   #[test]
   fn js_realm_simple() {
     let mut runtime = JsRuntime::new(Default::default());
@@ -3360,6 +4907,51 @@ assertEquals(1, notify_return_value);
     assert_eq!(ret, serde_v8::to_v8(scope, "Test").unwrap());
   }
 
+  #[tokio::test]
+  async fn js_realm_async_ops() {
+    let mut runtime = JsRuntime::new(Default::default());
+    let realm = runtime.create_realm().unwrap();
+    realm
+      .execute_script(
+        &mut runtime,
+        "",
+        r#"
+        Deno.core.opAsync("op_void_async").then(() => {
+          globalThis.resolved = true;
+        });
+        "#,
+      )
+      .unwrap();
+    runtime
+      .execute_script(
+        "",
+        r#"Deno.core.opAsync("op_void_async").then(() => {
+          globalThis.mainResolved = true;
+        });"#,
+      )
+      .unwrap();
+    runtime.run_event_loop(false).await.unwrap();
+
+    realm
+      .execute_script(
+        &mut runtime,
+        "",
+        "if (!globalThis.resolved || globalThis.mainResolved) throw Error('x')",
+      )
+      .unwrap();
+    runtime
+      .execute_script(
+        "",
+        "if (!globalThis.mainResolved || globalThis.resolved) throw Error('x')",
+      )
+      .unwrap();
+
+    assert!(runtime.dispose_realm(&realm));
+    assert!(!runtime.dispose_realm(&realm));
+    let global_realm = runtime.global_realm();
+    assert!(!runtime.dispose_realm(&global_realm));
+  }
+
   #[test]
   fn js_realm_init_snapshot() {
     let snapshot = {
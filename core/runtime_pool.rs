@@ -0,0 +1,82 @@
+// Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
+
+use crate::JsRuntime;
+use crate::RuntimeOptions;
+use anyhow::Error;
+
+/// A pool of pre-warmed runtimes, for embedders that run many short-lived
+/// scripts (e.g. one per request) and don't want to pay the cost of creating
+/// an isolate for each of them.
+///
+/// Every runtime is created with the options returned by the factory passed
+/// to [`JsRuntimePool::new`], which would typically point `startup_snapshot`
+/// to a snapshot shared by all of them. Runtimes given back to the pool are
+/// [reset](JsRuntime::reset) before being handed out again, so no state leaks
+/// from one user to the next.
+///
+/// The pool is not `Send`, like `JsRuntime` itself; use one pool per thread.
+/// V8 enters an isolate when it is created and exits it when it is dropped,
+/// so runtimes should be given back in the reverse order they were taken.
+pub struct JsRuntimePool {
+  create_options: Box<dyn Fn() -> RuntimeOptions>,
+  idle: Vec<JsRuntime>,
+  capacity: usize,
+}
+
+impl JsRuntimePool {
+  /// Creates a pool that keeps up to `capacity` idle runtimes and pre-warms
+  /// all of them.
+  pub fn new(
+    capacity: usize,
+    create_options: impl Fn() -> RuntimeOptions + 'static,
+  ) -> Self {
+    let mut pool = Self {
+      create_options: Box::new(create_options),
+      idle: Vec::with_capacity(capacity),
+      capacity,
+    };
+    for _ in 0..capacity {
+      let runtime = pool.create();
+      pool.idle.push(runtime);
+    }
+    pool
+  }
+
+  fn create(&self) -> JsRuntime {
+    JsRuntime::new((self.create_options)())
+  }
+
+  /// Takes a runtime out of the pool, creating a new one if the pool is
+  /// empty.
+  pub fn take(&mut self) -> JsRuntime {
+    match self.idle.pop() {
+      Some(runtime) => runtime,
+      None => self.create(),
+    }
+  }
+
+  /// Resets `runtime` and puts it back into the pool. If the pool is already
+  /// full, or the runtime can't be reset, it is dropped instead.
+  pub fn give_back(&mut self, mut runtime: JsRuntime) -> Result<(), Error> {
+    if self.idle.len() >= self.capacity {
+      return Ok(());
+    }
+    runtime.reset()?;
+    self.idle.push(runtime);
+    Ok(())
+  }
+
+  /// Returns the number of runtimes ready to be taken.
+  pub fn idle(&self) -> usize {
+    self.idle.len()
+  }
+}
+
+impl Drop for JsRuntimePool {
+  fn drop(&mut self) {
+    // Drop runtimes in the reverse order they were created in.
+    while let Some(runtime) = self.idle.pop() {
+      drop(runtime);
+    }
+  }
+}
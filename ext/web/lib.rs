@@ -51,9 +51,7 @@ pub use crate::message_port::JsMessageData;
 pub use crate::message_port::MessagePort;
 
 use crate::timers::op_now;
-use crate::timers::op_sleep;
 use crate::timers::op_sleep_sync;
-use crate::timers::op_timer_handle;
 use crate::timers::StartTime;
 pub use crate::timers::TimersPermission;
 
@@ -107,9 +105,7 @@ pub fn init<P: TimersPermission + 'static>(
       compression::op_compression_write::decl(),
       compression::op_compression_finish::decl(),
       op_now::decl::<P>(),
-      op_timer_handle::decl(),
       op_cancel_handle::decl(),
-      op_sleep::decl(),
       op_sleep_sync::decl::<P>(),
     ])
     .state(move |state| {
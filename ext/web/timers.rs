@@ -4,15 +4,8 @@
 
 use deno_core::error::AnyError;
 use deno_core::op;
-
-use deno_core::CancelFuture;
-use deno_core::CancelHandle;
 use deno_core::OpState;
-use deno_core::Resource;
-use deno_core::ResourceId;
-use std::borrow::Cow;
-use std::cell::RefCell;
-use std::rc::Rc;
+use deno_core::VirtualClock;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -32,9 +25,12 @@ pub fn op_now<TP>(state: &mut OpState, _argument: ()) -> Result<f64, AnyError>
 where
   TP: TimersPermission + 'static,
 {
-  let start_time = state.borrow::<StartTime>();
-  let seconds = start_time.elapsed().as_secs();
-  let mut subsec_nanos = start_time.elapsed().subsec_nanos() as f64;
+  let elapsed = match state.try_borrow::<VirtualClock>() {
+    Some(clock) => clock.now(),
+    None => state.borrow::<StartTime>().elapsed(),
+  };
+  let seconds = elapsed.as_secs();
+  let mut subsec_nanos = elapsed.subsec_nanos() as f64;
   let reduced_time_precision = 2_000_000.0; // 2ms in nanoseconds
 
   // If the permission is not enabled
@@ -49,43 +45,6 @@ where
   Ok(result)
 }
 
-pub struct TimerHandle(Rc<CancelHandle>);
-
-impl Resource for TimerHandle {
-  fn name(&self) -> Cow<str> {
-    "timer".into()
-  }
-
-  fn close(self: Rc<Self>) {
-    self.0.cancel();
-  }
-}
-
-/// Creates a [`TimerHandle`] resource that can be used to cancel invocations of
-/// [`op_sleep`].
-#[op]
-pub fn op_timer_handle(state: &mut OpState) -> Result<ResourceId, AnyError> {
-  let rid = state
-    .resource_table
-    .add(TimerHandle(CancelHandle::new_rc()));
-  Ok(rid)
-}
-
-/// Waits asynchronously until either `millis` milliseconds have passed or the
-/// [`TimerHandle`] resource given by `rid` has been canceled.
-#[op]
-pub async fn op_sleep(
-  state: Rc<RefCell<OpState>>,
-  millis: u64,
-  rid: ResourceId,
-) -> Result<(), AnyError> {
-  let handle = state.borrow().resource_table.get::<TimerHandle>(rid)?;
-  tokio::time::sleep(Duration::from_millis(millis))
-    .or_cancel(handle.0.clone())
-    .await?;
-  Ok(())
-}
-
 #[op]
 pub fn op_sleep_sync<TP>(
   state: &mut OpState,
@@ -95,6 +54,9 @@ where
   TP: TimersPermission + 'static,
 {
   state.borrow::<TP>().check_unstable(state, "Deno.sleepSync");
-  std::thread::sleep(Duration::from_millis(millis));
+  match state.try_borrow_mut::<VirtualClock>() {
+    Some(clock) => clock.advance_by(millis),
+    None => std::thread::sleep(Duration::from_millis(millis)),
+  }
   Ok(())
 }
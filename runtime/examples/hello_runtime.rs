@@ -50,6 +50,7 @@ async fn main() -> Result<(), AnyError> {
     create_web_worker_cb,
     maybe_inspector_server: None,
     should_break_on_first_statement: false,
+    should_wait_for_inspector_session: false,
     module_loader,
     get_error_class_fn: Some(&get_error_class_name),
     origin_storage_dir: None,
@@ -58,6 +59,9 @@ async fn main() -> Result<(), AnyError> {
     shared_array_buffer_store: None,
     compiled_wasm_module_store: None,
     stdio: Default::default(),
+    dump_op_metrics: None,
+    max_heap_size: None,
+    module_map_data: None,
   };
 
   let js_path =
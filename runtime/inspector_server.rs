@@ -12,6 +12,7 @@ use deno_core::futures::pin_mut;
 use deno_core::futures::prelude::*;
 use deno_core::futures::select;
 use deno_core::futures::stream::StreamExt;
+use deno_core::futures::task::Context;
 use deno_core::futures::task::Poll;
 use deno_core::serde_json;
 use deno_core::serde_json::json;
@@ -24,34 +25,82 @@ use deno_websocket::tokio_tungstenite::WebSocketStream;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::convert::Infallible;
+use std::fmt;
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::pin::Pin;
 use std::process;
 use std::rc::Rc;
+use std::str::FromStr;
 use std::thread;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::ReadBuf;
 use uuid::Uuid;
 
+/// Address the inspector server listens on: either a TCP socket address like
+/// `127.0.0.1:9229`, or a Unix domain socket path written as `unix:PATH`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InspectorAddress {
+  Tcp(SocketAddr),
+  Unix(PathBuf),
+}
+
+impl FromStr for InspectorAddress {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.strip_prefix("unix:") {
+      Some("") => Err("missing Unix socket path".to_string()),
+      #[cfg(unix)]
+      Some(path) => Ok(Self::Unix(PathBuf::from(path))),
+      #[cfg(not(unix))]
+      Some(_) => {
+        Err("Unix sockets are not supported on this platform".to_string())
+      }
+      None => s.parse().map(Self::Tcp).map_err(|e| format!("{}", e)),
+    }
+  }
+}
+
+impl fmt::Display for InspectorAddress {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Tcp(addr) => write!(f, "{}", addr),
+      Self::Unix(path) => write!(f, "unix:{}", path.display()),
+    }
+  }
+}
+
+impl From<SocketAddr> for InspectorAddress {
+  fn from(addr: SocketAddr) -> Self {
+    Self::Tcp(addr)
+  }
+}
+
 /// Websocket server that is used to proxy connections from
 /// devtools to the inspector.
 pub struct InspectorServer {
-  pub host: SocketAddr,
+  pub host: InspectorAddress,
   register_inspector_tx: UnboundedSender<InspectorInfo>,
   shutdown_server_tx: Option<oneshot::Sender<()>>,
   thread_handle: Option<thread::JoinHandle<()>>,
 }
 
 impl InspectorServer {
-  pub fn new(host: SocketAddr, name: String) -> Self {
+  pub fn new(host: InspectorAddress, name: String) -> Self {
     let (register_inspector_tx, register_inspector_rx) =
       mpsc::unbounded::<InspectorInfo>();
 
     let (shutdown_server_tx, shutdown_server_rx) = oneshot::channel();
 
+    let server_host = host.clone();
     let thread_handle = thread::spawn(move || {
       let rt = crate::tokio_util::create_basic_runtime();
       let local = tokio::task::LocalSet::new();
       local.block_on(
         &rt,
-        server(host, register_inspector_rx, shutdown_server_rx, name),
+        server(server_host, register_inspector_rx, shutdown_server_rx, name),
       )
     });
 
@@ -73,7 +122,7 @@ impl InspectorServer {
     let session_sender = inspector.get_session_sender();
     let deregister_rx = inspector.add_deregister_handler();
     let info = InspectorInfo::new(
-      self.host,
+      self.host.clone(),
       session_sender,
       deregister_rx,
       module_url,
@@ -94,6 +143,103 @@ impl Drop for InspectorServer {
     if let Some(thread_handle) = self.thread_handle.take() {
       thread_handle.join().expect("unable to join thread");
     }
+
+    if let InspectorAddress::Unix(path) = &self.host {
+      let _ = std::fs::remove_file(path);
+    }
+  }
+}
+
+enum InspectorListener {
+  Tcp(tokio::net::TcpListener),
+  #[cfg(unix)]
+  Unix(tokio::net::UnixListener),
+}
+
+impl InspectorListener {
+  async fn bind(host: &InspectorAddress) -> std::io::Result<Self> {
+    match host {
+      InspectorAddress::Tcp(addr) => {
+        Ok(Self::Tcp(tokio::net::TcpListener::bind(addr).await?))
+      }
+      #[cfg(unix)]
+      InspectorAddress::Unix(path) => {
+        Ok(Self::Unix(tokio::net::UnixListener::bind(path)?))
+      }
+      #[cfg(not(unix))]
+      InspectorAddress::Unix(_) => unreachable!(),
+    }
+  }
+
+  fn poll_accept(
+    &self,
+    cx: &mut Context,
+  ) -> Poll<std::io::Result<InspectorStream>> {
+    match self {
+      Self::Tcp(listener) => listener
+        .poll_accept(cx)
+        .map_ok(|(stream, _)| InspectorStream::Tcp(stream)),
+      #[cfg(unix)]
+      Self::Unix(listener) => listener
+        .poll_accept(cx)
+        .map_ok(|(stream, _)| InspectorStream::Unix(stream)),
+    }
+  }
+}
+
+enum InspectorStream {
+  Tcp(tokio::net::TcpStream),
+  #[cfg(unix)]
+  Unix(tokio::net::UnixStream),
+}
+
+impl AsyncRead for InspectorStream {
+  fn poll_read(
+    self: Pin<&mut Self>,
+    cx: &mut Context,
+    buf: &mut ReadBuf,
+  ) -> Poll<std::io::Result<()>> {
+    match self.get_mut() {
+      Self::Tcp(stream) => Pin::new(stream).poll_read(cx, buf),
+      #[cfg(unix)]
+      Self::Unix(stream) => Pin::new(stream).poll_read(cx, buf),
+    }
+  }
+}
+
+impl AsyncWrite for InspectorStream {
+  fn poll_write(
+    self: Pin<&mut Self>,
+    cx: &mut Context,
+    buf: &[u8],
+  ) -> Poll<std::io::Result<usize>> {
+    match self.get_mut() {
+      Self::Tcp(stream) => Pin::new(stream).poll_write(cx, buf),
+      #[cfg(unix)]
+      Self::Unix(stream) => Pin::new(stream).poll_write(cx, buf),
+    }
+  }
+
+  fn poll_flush(
+    self: Pin<&mut Self>,
+    cx: &mut Context,
+  ) -> Poll<std::io::Result<()>> {
+    match self.get_mut() {
+      Self::Tcp(stream) => Pin::new(stream).poll_flush(cx),
+      #[cfg(unix)]
+      Self::Unix(stream) => Pin::new(stream).poll_flush(cx),
+    }
+  }
+
+  fn poll_shutdown(
+    self: Pin<&mut Self>,
+    cx: &mut Context,
+  ) -> Poll<std::io::Result<()>> {
+    match self.get_mut() {
+      Self::Tcp(stream) => Pin::new(stream).poll_shutdown(cx),
+      #[cfg(unix)]
+      Self::Unix(stream) => Pin::new(stream).poll_shutdown(cx),
+    }
   }
 }
 
@@ -216,7 +362,7 @@ fn handle_json_version_request(
 }
 
 async fn server(
-  host: SocketAddr,
+  host: InspectorAddress,
   register_inspector_rx: UnboundedReceiver<InspectorInfo>,
   shutdown_server_rx: oneshot::Receiver<()>,
   name: String,
@@ -285,12 +431,17 @@ async fn server(
     ))
   });
 
+  let listener = InspectorListener::bind(&host).await.unwrap_or_else(|e| {
+    eprintln!("Cannot start inspector server: {}.", e);
+    process::exit(1);
+  });
+  let incoming = hyper::server::accept::poll_fn(move |cx| {
+    listener.poll_accept(cx).map(Some)
+  });
+
   // Create the server manually so it can use the Local Executor
   let server_handler = hyper::server::Builder::new(
-    hyper::server::conn::AddrIncoming::bind(&host).unwrap_or_else(|e| {
-      eprintln!("Cannot start inspector server: {}.", e);
-      process::exit(1);
-    }),
+    incoming,
     hyper::server::conn::Http::new().with_executor(LocalExecutor),
   )
   .serve(make_svc)
@@ -363,7 +514,7 @@ async fn pump_websocket_messages(
 /// Inspector information that is sent from the isolate thread to the server
 /// thread when a new inspector is created.
 pub struct InspectorInfo {
-  pub host: SocketAddr,
+  pub host: InspectorAddress,
   pub uuid: Uuid,
   pub thread_name: Option<String>,
   pub new_session_tx: UnboundedSender<InspectorSessionProxy>,
@@ -374,7 +525,7 @@ pub struct InspectorInfo {
 
 impl InspectorInfo {
   pub fn new(
-    host: SocketAddr,
+    host: InspectorAddress,
     new_session_tx: mpsc::UnboundedSender<InspectorSessionProxy>,
     deregister_rx: oneshot::Receiver<()>,
     url: String,
@@ -404,15 +555,34 @@ impl InspectorInfo {
     })
   }
 
+  /// There is no standard URL for Unix sockets; like httpie and the HTTP
+  /// server, the socket path is percent-encoded into the authority of a
+  /// `ws+unix://` URL.
   pub fn get_websocket_debugger_url(&self) -> String {
-    format!("ws://{}/ws/{}", &self.host, &self.uuid)
+    match &self.host {
+      InspectorAddress::Tcp(addr) => {
+        format!("ws://{}/ws/{}", addr, &self.uuid)
+      }
+      InspectorAddress::Unix(path) => format!(
+        "ws+unix://{}/ws/{}",
+        percent_encoding::percent_encode(
+          path.to_string_lossy().as_bytes(),
+          percent_encoding::NON_ALPHANUMERIC,
+        ),
+        &self.uuid
+      ),
+    }
   }
 
-  fn get_frontend_url(&self) -> String {
-    format!(
+  /// DevTools can only connect over TCP.
+  fn get_frontend_url(&self) -> Option<String> {
+    match &self.host {
+      InspectorAddress::Tcp(addr) => Some(format!(
         "devtools://devtools/bundled/js_app.html?ws={}/ws/{}&experiments=true&v8only=true",
-        &self.host, &self.uuid
-      )
+        addr, &self.uuid
+      )),
+      InspectorAddress::Unix(_) => None,
+    }
   }
 
   fn get_title(&self) -> String {
@@ -29,11 +29,13 @@
     sourceCode,
     useDenoNamespace,
     permissions,
+    limits,
     name,
     workerType,
   ) {
     return core.opSync("op_create_worker", {
       hasSourceCode,
+      limits,
       name,
       permissions: serializePermissions(permissions),
       sourceCode,
@@ -81,9 +83,11 @@
 
       let namespace;
       let permissions;
+      let limits;
       if (typeof deno == "object") {
         namespace = deno.namespace ?? false;
         permissions = deno.permissions ?? undefined;
+        limits = deno.limits ?? undefined;
       } else {
         // Assume `deno: boolean | undefined`.
         // TODO(Soremwar)
@@ -122,6 +126,7 @@
         sourceCode,
         namespace,
         permissions,
+        limits,
         name,
         workerType,
       );
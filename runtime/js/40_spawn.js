@@ -9,6 +9,7 @@
     ArrayPrototypeMap,
     ObjectEntries,
     String,
+    SymbolFor,
     TypeError,
     Uint8Array,
     PromiseAll,
@@ -16,6 +17,8 @@
   const { readableStreamForRid, writableStreamForRid } =
     window.__bootstrap.streamUtils;
 
+  const promiseIdSymbol = SymbolFor("Deno.core.internalPromiseId");
+
   function spawnChild(command, {
     args = [],
     cwd = undefined,
@@ -23,9 +26,12 @@
     env = {},
     uid = undefined,
     gid = undefined,
+    detached = false,
+    newProcessGroup = false,
     stdin = "null",
     stdout = "piped",
     stderr = "piped",
+    pty = false,
   } = {}) {
     const child = core.opSync("op_spawn_child", {
       cmd: pathFromURL(command),
@@ -35,9 +41,14 @@
       env: ObjectEntries(env),
       uid,
       gid,
+      detached,
+      newProcessGroup,
       stdin,
       stdout,
       stderr,
+      pty: pty
+        ? { columns: pty.columns ?? 80, rows: pty.rows ?? 24 }
+        : undefined,
     });
     return new Child(illegalConstructorKey, child);
   }
@@ -90,12 +101,15 @@
       return this.#stderr;
     }
 
+    #ptyRid;
+
     constructor(key = null, {
       rid,
       pid,
       stdinRid,
       stdoutRid,
       stderrRid,
+      ptyRid,
     } = null) {
       if (key !== illegalConstructorKey) {
         throw new TypeError("Illegal constructor.");
@@ -119,12 +133,21 @@
         this.#stderr = readableStreamForRid(stderrRid);
       }
 
-      this.#status = core.opAsync("op_spawn_wait", this.#rid).then((res) => {
+      this.#ptyRid = ptyRid;
+
+      const waitPromise = core.opAsync("op_spawn_wait", this.#rid);
+      this.#waitPromiseId = waitPromise[promiseIdSymbol];
+      this.#status = waitPromise.then((res) => {
         this.#rid = null;
+        if (this.#ptyRid !== null) {
+          core.tryClose(this.#ptyRid);
+          this.#ptyRid = null;
+        }
         return res;
       });
     }
 
+    #waitPromiseId;
     #status;
     get status() {
       return this.#status;
@@ -164,6 +187,24 @@
       }
       core.opSync("op_kill", this.#pid, signo);
     }
+
+    ref() {
+      core.refOp(this.#waitPromiseId);
+    }
+
+    unref() {
+      core.unrefOp(this.#waitPromiseId);
+    }
+
+    resizePty({ columns, rows }) {
+      if (this.#rid === null) {
+        throw new TypeError("Child process has already terminated.");
+      }
+      if (this.#ptyRid === null) {
+        throw new TypeError("Child process wasn't spawned with a pty.");
+      }
+      core.opSync("op_spawn_pty_resize", this.#ptyRid, { columns, rows });
+    }
   }
 
   function spawn(command, options) {
@@ -182,6 +223,8 @@
     env = {},
     uid = undefined,
     gid = undefined,
+    detached = false,
+    newProcessGroup = false,
     stdin = "null",
     stdout = "piped",
     stderr = "piped",
@@ -199,6 +242,8 @@
       env: ObjectEntries(env),
       uid,
       gid,
+      detached,
+      newProcessGroup,
       stdin,
       stdout,
       stderr,
@@ -4,16 +4,19 @@
 ((window) => {
   const core = window.Deno.core;
   const { setExitHandler } = window.__bootstrap.os;
-  const { Console, inspectArgs } = window.__bootstrap.console;
+  const { Console, inspect, inspectArgs } = window.__bootstrap.console;
   const { serializePermissions } = window.__bootstrap.permissions;
   const { assert } = window.__bootstrap.infra;
   const {
     AggregateErrorPrototype,
     ArrayFrom,
+    ArrayIsArray,
     ArrayPrototypeFilter,
+    ArrayPrototypeIncludes,
     ArrayPrototypeJoin,
     ArrayPrototypeMap,
     ArrayPrototypePush,
+    ArrayPrototypeReverse,
     ArrayPrototypeShift,
     ArrayPrototypeSome,
     ArrayPrototypeSort,
@@ -21,19 +24,25 @@
     Error,
     FunctionPrototype,
     Map,
+    MapPrototypeGet,
     MapPrototypeHas,
     MathCeil,
+    NumberIsInteger,
+    NumberParseInt,
     ObjectKeys,
+    ObjectPrototypeHasOwnProperty,
     ObjectPrototypeIsPrototypeOf,
     Promise,
+    PromisePrototypeThen,
     RegExp,
+    RegExpPrototypeExec,
     RegExpPrototypeTest,
     SafeArrayIterator,
     Set,
-    StringPrototypeEndsWith,
     StringPrototypeIncludes,
-    StringPrototypeSlice,
+    StringPrototypeSplit,
     StringPrototypeStartsWith,
+    StringPrototypeTrimEnd,
     SymbolToStringTag,
     TypeError,
   } = window.__bootstrap.primordials;
@@ -145,8 +154,14 @@
     return async function asyncOpSanitizer(step) {
       const pre = core.metrics();
       const preTraces = new Map(core.opCallTraces);
+      let timeoutError = null;
       try {
         await fn(step);
+      } catch (error) {
+        if (!ObjectPrototypeIsPrototypeOf(TestTimeoutError.prototype, error)) {
+          throw error;
+        }
+        timeoutError = error;
       } finally {
         // Defer until next event loop turn - that way timeouts and intervals
         // cleared can actually be removed from resource table, otherwise
@@ -154,8 +169,6 @@
         await opSanitizerDelay();
       }
 
-      if (step.shouldSkipSanitizers) return;
-
       const post = core.metrics();
       const postTraces = new Map(core.opCallTraces);
 
@@ -164,7 +177,10 @@
       const dispatchedDiff = post.opsDispatchedAsync - pre.opsDispatchedAsync;
       const completedDiff = post.opsCompletedAsync - pre.opsCompletedAsync;
 
-      if (dispatchedDiff === completedDiff) return;
+      if (step.shouldSkipSanitizers || dispatchedDiff === completedDiff) {
+        if (timeoutError) throw timeoutError;
+        return;
+      }
 
       const details = [];
       for (const key in post.ops) {
@@ -225,6 +241,9 @@
           `\n\nTo get more details where ops were leaked, run again with --trace-ops flag.`;
       }
 
+      if (timeoutError) {
+        throw new TestTimeoutError(`${timeoutError.message}\n\n${msg}`);
+      }
       throw msg;
     };
   }
@@ -372,9 +391,18 @@
     /** @param step {TestStep} */
     return async function resourceSanitizer(step) {
       const pre = core.resources();
-      await fn(step);
+      let timeoutError = null;
+      try {
+        await fn(step);
+      } catch (error) {
+        if (!ObjectPrototypeIsPrototypeOf(TestTimeoutError.prototype, error)) {
+          throw error;
+        }
+        timeoutError = error;
+      }
 
       if (step.shouldSkipSanitizers) {
+        if (timeoutError) throw timeoutError;
         return;
       }
 
@@ -386,6 +414,7 @@
       ]);
 
       const details = [];
+      let missingTraces = false;
       for (const resource of allResources) {
         const preResource = pre[resource];
         const postResource = post[resource];
@@ -394,8 +423,17 @@
         if (preResource === undefined) {
           const [name, action1, action2] = prettyResourceNames(postResource);
           const hint = resourceCloseHint(postResource);
-          const detail =
+          let detail =
             `${name} (rid ${resource}) was ${action1} during the test, but not ${action2} during the test. ${hint}`;
+          const trace = MapPrototypeGet(
+            core.resourceCreationTraces,
+            NumberParseInt(resource),
+          );
+          if (trace) {
+            detail += ` The resource was ${action1} here:\n${trace.stack}`;
+          } else if (!core.isOpCallTracingEnabled()) {
+            missingTraces = true;
+          }
           details.push(detail);
         } else {
           const [name, action1, action2] = prettyResourceNames(preResource);
@@ -405,12 +443,20 @@
         }
       }
 
-      const message = `Test case is leaking ${details.length} resource${
+      let message = `Test case is leaking ${details.length} resource${
         details.length === 1 ? "" : "s"
       }:
 
  - ${details.join("\n - ")}
 `;
+      if (missingTraces) {
+        message +=
+          `\nTo get more details where resources were created, run again with --trace-ops flag.\n`;
+      }
+      if (timeoutError) {
+        if (details.length === 0) throw timeoutError;
+        throw new TestTimeoutError(`${timeoutError.message}\n\n${message}`);
+      }
       assert(details.length === 0, message);
     };
   }
@@ -514,6 +560,45 @@
     };
   }
 
+  // Timeout in milliseconds for tests that don't specify their own, set from
+  // `deno test --timeout`.
+  let defaultTestTimeout = null;
+
+  // Thrown when a test times out. The op and resource sanitizers catch it,
+  // check for leaks anyway and rethrow it with the leaks added.
+  class TestTimeoutError extends Error {
+    constructor(message) {
+      super(message);
+      this.name = "TestTimeoutError";
+    }
+  }
+
+  // Wrap test function so that it fails if it hasn't settled after `timeout`
+  // milliseconds. This is applied before the sanitizers, so they still run
+  // once the test has timed out. The test function itself can't be cancelled
+  // and keeps running in the background, so whatever it still has pending at
+  // that point is reported as leaked.
+  function withTimeout(fn, timeout) {
+    return async function timeoutGuard(step) {
+      const ms = timeout ?? defaultTestTimeout;
+      if (!ms) {
+        return await fn(step);
+      }
+      let timerId;
+      try {
+        return await new Promise((resolve, reject) => {
+          timerId = setTimeout(
+            () => reject(new TestTimeoutError(`Test timed out after ${ms}ms`)),
+            ms,
+          );
+          PromisePrototypeThen(fn(step), resolve, reject);
+        });
+      } finally {
+        clearTimeout(timerId);
+      }
+    };
+  }
+
   function pledgePermissions(permissions) {
     return core.opSync(
       "op_pledge_test_permissions",
@@ -554,6 +639,7 @@
       sanitizeResources: true,
       sanitizeExit: true,
       permissions: null,
+      tags: [],
     };
 
     if (typeof nameOrFnOrOptions === "string") {
@@ -626,6 +712,28 @@
       testDef = { ...defaults, ...nameOrFnOrOptions, fn, name };
     }
 
+    if (
+      testDef.retries != null &&
+      !(NumberIsInteger(testDef.retries) && testDef.retries >= 0)
+    ) {
+      throw new TypeError("The test retries must be a non-negative integer");
+    }
+
+    if (
+      !ArrayIsArray(testDef.tags) ||
+      ArrayPrototypeSome(testDef.tags, (tag) => typeof tag !== "string")
+    ) {
+      throw new TypeError("The test tags must be an array of strings");
+    }
+
+    if (
+      testDef.timeout != null &&
+      !(typeof testDef.timeout === "number" && testDef.timeout > 0)
+    ) {
+      throw new TypeError("The test timeout must be a positive number");
+    }
+
+    testDef.fn = withTimeout(testDef.fn, testDef.timeout);
     testDef.fn = wrapTestFnWithSanitizers(testDef.fn, testDef);
 
     if (testDef.permissions) {
@@ -755,11 +863,15 @@
       return () => true;
     }
 
-    const regex =
-      typeof filter === "string" && StringPrototypeStartsWith(filter, "/") &&
-        StringPrototypeEndsWith(filter, "/")
-        ? new RegExp(StringPrototypeSlice(filter, 1, filter.length - 1))
-        : undefined;
+    // A filter of the form `/pattern/flags` is a regular expression (the
+    // stateful `g` and `y` flags aren't allowed), anything else matches as a
+    // substring.
+    const regexMatch = typeof filter === "string"
+      ? RegExpPrototypeExec(/^\/(.+)\/([dimsu]*)$/, filter)
+      : null;
+    const regex = regexMatch
+      ? new RegExp(regexMatch[1], regexMatch[2])
+      : undefined;
 
     const filterIsObject = filter != null && typeof filter === "object";
 
@@ -780,6 +892,23 @@
     };
   }
 
+  /**
+   * Selects the tests having one of `filterTags`, if any are given, and none
+   * of `skipTags`.
+   * @param {string[]} filterTags
+   * @param {string[]} skipTags
+   * @returns {(def: { tags: string[] }) => boolean}
+   */
+  function createTagFilter(filterTags, skipTags) {
+    return (def) => {
+      const hasTag = (tag) => ArrayPrototypeIncludes(def.tags, tag);
+      if (filterTags.length > 0 && !ArrayPrototypeSome(filterTags, hasTag)) {
+        return false;
+      }
+      return !ArrayPrototypeSome(skipTags, hasTag);
+    };
+  }
+
   async function runTest(test, description) {
     if (test.ignore) {
       return "ignored";
@@ -799,15 +928,20 @@
       await test.fn(step);
       const failCount = step.failedChildStepsCount();
       return failCount === 0 ? "ok" : {
-        "failed": core.destructureError(
-          new Error(
-            `${failCount} test step${failCount === 1 ? "" : "s"} failed.`,
+        "failed": {
+          error: core.destructureError(
+            new Error(
+              `${failCount} test step${failCount === 1 ? "" : "s"} failed.`,
+            ),
           ),
-        ),
+        },
       };
     } catch (error) {
       return {
-        "failed": core.destructureError(error),
+        "failed": {
+          error: core.destructureError(error),
+          diff: getFailureDiff(error),
+        },
       };
     } finally {
       step.finalized = true;
@@ -1014,14 +1148,26 @@
     });
   }
 
+  // Step events of a test attempt that may still be retried, so that only
+  // the steps of the attempt whose result is reported get reported.
+  let bufferedStepEvents = null;
+
+  function dispatchTestStepEvent(event) {
+    if (bufferedStepEvents) {
+      ArrayPrototypePush(bufferedStepEvents, event);
+    } else {
+      core.opSync("op_dispatch_test_event", event);
+    }
+  }
+
   function reportTestStepWait(testDescription) {
-    core.opSync("op_dispatch_test_event", {
+    dispatchTestStepEvent({
       stepWait: testDescription,
     });
   }
 
   function reportTestStepResult(testDescription, result, elapsed) {
-    core.opSync("op_dispatch_test_event", {
+    dispatchTestStepEvent({
       stepResult: [testDescription, result, elapsed],
     });
   }
@@ -1057,15 +1203,22 @@
   async function runTests({
     filter = null,
     shuffle = null,
+    timeout = null,
+    retries = 0,
+    filterTags = [],
+    skipTags = [],
   } = {}) {
     core.setMacrotaskCallback(handleOpSanitizerDelayMacrotask);
+    defaultTestTimeout = timeout;
 
     const origin = getTestOrigin();
 
     const only = ArrayPrototypeFilter(tests, (test) => test.only);
+    const nameFilter = createTestFilter(filter);
+    const tagFilter = createTagFilter(filterTags, skipTags);
     const filtered = ArrayPrototypeFilter(
       only.length > 0 ? only : tests,
-      createTestFilter(filter),
+      (test) => nameFilter(test) && tagFilter(test),
     );
 
     reportTestPlan({
@@ -1102,7 +1255,29 @@
 
       reportTestWait(description);
 
-      const result = await runTest(test, description);
+      const maxRetries = test.retries ?? retries;
+      let attempt = 0;
+      let result;
+      while (true) {
+        bufferedStepEvents = attempt < maxRetries ? [] : null;
+        result = await runTest(test, description);
+        const stepEvents = bufferedStepEvents ?? [];
+        bufferedStepEvents = null;
+        if (!result.failed || attempt === maxRetries) {
+          for (const event of new SafeArrayIterator(stepEvents)) {
+            core.opSync("op_dispatch_test_event", event);
+          }
+          break;
+        }
+        attempt++;
+      }
+      if (attempt > 0) {
+        if (result.failed) {
+          result.failed.retries = attempt;
+        } else if (result === "ok") {
+          result = { flaky: attempt };
+        }
+      }
       const elapsed = DateNow() - earlier;
 
       reportTestResult(description, result, elapsed);
@@ -1189,6 +1364,8 @@
     /** @type "ok" | "ignored" | "pending" | "failed" */
     status = "pending";
     error = undefined;
+    /** Number of `assertSnapshot` calls made by this step so far. */
+    snapshotCount = 0;
     /** @type {TestStep[]} */
     children = [];
 
@@ -1374,6 +1551,121 @@
     }
   }
 
+  class DocOutputAssertionError extends Error {
+    constructor(expected, actual) {
+      super("Output of the example does not match.");
+      this.name = "DocOutputAssertionError";
+      this.expected = expected;
+      this.actual = actual;
+    }
+  }
+
+  /**
+   * Serializes a value for a snapshot. Unlike `Deno.inspect`'s defaults, the
+   * output is never truncated and object keys are sorted, so that it only
+   * changes when the value does.
+   */
+  function serializeSnapshot(value) {
+    return inspect(value, {
+      colors: false,
+      compact: false,
+      depth: Infinity,
+      iterableLimit: Infinity,
+      sorted: true,
+      strAbbreviateSize: Infinity,
+      trailingComma: true,
+    });
+  }
+
+  /**
+   * Returns the expected and actual values of a failed assertion, for errors
+   * that carry them (like snapshot errors or Node's `AssertionError`), so that
+   * reporters can show a diff.
+   * @returns {{ expected: string, actual: string } | null}
+   */
+  function getFailureDiff(error) {
+    if (
+      ObjectPrototypeIsPrototypeOf(SnapshotAssertionError.prototype, error) ||
+      ObjectPrototypeIsPrototypeOf(DocOutputAssertionError.prototype, error)
+    ) {
+      return { expected: error.expected, actual: error.actual };
+    }
+    if (
+      error !== null && typeof error === "object" &&
+      ObjectPrototypeHasOwnProperty(error, "expected") &&
+      ObjectPrototypeHasOwnProperty(error, "actual")
+    ) {
+      return {
+        expected: serializeSnapshot(error.expected),
+        actual: serializeSnapshot(error.actual),
+      };
+    }
+    return null;
+  }
+
+  class SnapshotAssertionError extends Error {
+    constructor(name, expected, actual) {
+      super(`Snapshot "${name}" does not match.`);
+      this.name = "SnapshotAssertionError";
+      this.expected = expected;
+      this.actual = actual;
+    }
+  }
+
+  /**
+   * @param step {TestStep}
+   * @param actual {unknown}
+   */
+  function assertSnapshot(step, actual) {
+    const names = [];
+    for (let s = step; s; s = s.parent) {
+      ArrayPrototypePush(names, s.name);
+    }
+    ArrayPrototypeReverse(names);
+    const name = `${ArrayPrototypeJoin(names, " > ")} ${++step.snapshotCount}`;
+    const serialized = serializeSnapshot(actual);
+    const expected = core.opSync("op_assert_snapshot", {
+      origin: step.rootTestDescription.origin,
+      name,
+      actual: serialized,
+    });
+    if (expected !== null) {
+      throw new SnapshotAssertionError(name, expected, serialized);
+    }
+  }
+
+  /** Trims trailing whitespace of every line, and trailing empty lines. */
+  function normalizeDocOutput(output) {
+    const lines = ArrayPrototypeMap(
+      StringPrototypeSplit(output, "\n"),
+      (line) => StringPrototypeTrimEnd(line),
+    );
+    return StringPrototypeTrimEnd(ArrayPrototypeJoin(lines, "\n"));
+  }
+
+  /**
+   * Evaluates a documentation example by calling `load`, which imports it, and
+   * asserts that what it printed to the console matches the `output` fence
+   * following it.
+   * @param load {() => Promise<unknown>}
+   * @param expected {string}
+   */
+  async function assertDocOutput(load, expected) {
+    const output = [];
+    const originalConsole = globalThis.console;
+    globalThis.console = new Console((msg) => ArrayPrototypePush(output, msg));
+    try {
+      await load();
+    } finally {
+      globalThis.console = originalConsole;
+    }
+    const actual = normalizeDocOutput(ArrayPrototypeJoin(output, ""));
+    expected = normalizeDocOutput(expected);
+    if (actual !== expected) {
+      throw new DocOutputAssertionError(expected, actual);
+    }
+  }
+
   /** @param parentStep {TestStep} */
   function createTestContext(parentStep) {
     return {
@@ -1390,6 +1682,12 @@
        * File Uri of the test code.
        */
       origin: parentStep.rootTestDescription.origin,
+      /**
+       * @param actual {unknown}
+       */
+      assertSnapshot(actual) {
+        assertSnapshot(parentStep, actual);
+      },
       /**
        * @param nameOrTestDefinition {string | TestStepDefinition}
        * @param fn {(t: TestContext) => void | Promise<void>}
@@ -1534,10 +1832,18 @@
     return value == null ? defaultValue : value;
   }
 
+  // Forget the tests registered by the previous module when a worker is reused
+  // to run several test modules.
+  function resetTests() {
+    tests.length = 0;
+  }
+
   window.__bootstrap.internals = {
     ...window.__bootstrap.internals ?? {},
     runTests,
+    resetTests,
     runBenchmarks,
+    assertDocOutput,
   };
 
   window.__bootstrap.testing = {
@@ -3,6 +3,12 @@
 
 ((window) => {
   const core = window.Deno.core;
+  const { BadResourcePrototype } = core;
+  const {
+    ObjectPrototypeIsPrototypeOf,
+    PromiseResolve,
+    SymbolAsyncIterator,
+  } = window.__bootstrap.primordials;
 
   function consoleSize(rid) {
     return core.opSync("op_console_size", rid);
@@ -21,9 +27,56 @@
     core.opSync("op_set_raw", { rid, mode, options: rOptions });
   }
 
+  class ConsoleSizeWatcher {
+    #rid = 0;
+
+    constructor(rid) {
+      this.#rid = core.opSync("op_console_size_watch_open", rid);
+    }
+
+    get rid() {
+      return this.#rid;
+    }
+
+    async next() {
+      try {
+        const value = await core.opAsync(
+          "op_console_size_watch_poll",
+          this.rid,
+        );
+        return value
+          ? { value, done: false }
+          : { value: undefined, done: true };
+      } catch (error) {
+        if (ObjectPrototypeIsPrototypeOf(BadResourcePrototype, error)) {
+          return { value: undefined, done: true };
+        }
+        throw error;
+      }
+    }
+
+    return(value) {
+      core.close(this.rid);
+      return PromiseResolve({ value, done: true });
+    }
+
+    close() {
+      core.close(this.rid);
+    }
+
+    [SymbolAsyncIterator]() {
+      return this;
+    }
+  }
+
+  function watchConsoleSize(rid = 1) {
+    return new ConsoleSizeWatcher(rid);
+  }
+
   window.__bootstrap.tty = {
     consoleSize,
     isatty,
     setRaw,
+    watchConsoleSize,
   };
 })(this);
@@ -119,6 +119,7 @@
     emit: __bootstrap.compilerApi.emit,
     setRaw: __bootstrap.tty.setRaw,
     consoleSize: __bootstrap.tty.consoleSize,
+    watchConsoleSize: __bootstrap.tty.watchConsoleSize,
     DiagnosticCategory: __bootstrap.diagnostics.DiagnosticCategory,
     loadavg: __bootstrap.os.loadavg,
     hostname: __bootstrap.os.hostname,
@@ -148,11 +148,11 @@ fn open_helper(
   let options = &args.options;
 
   if options.read {
-    permissions.read.check(&path)?;
+    permissions.check_read(&path)?;
   }
 
   if options.write || options.append {
-    permissions.write.check(&path)?;
+    permissions.check_write(&path)?;
   }
 
   open_options
@@ -595,7 +595,7 @@ fn op_umask(state: &mut OpState, mask: Option<u32>) -> Result<u32, AnyError> {
 #[op]
 fn op_chdir(state: &mut OpState, directory: String) -> Result<(), AnyError> {
   let d = PathBuf::from(&directory);
-  state.borrow_mut::<Permissions>().read.check(&d)?;
+  state.borrow_mut::<Permissions>().check_read(&d)?;
   set_current_dir(&d).map_err(|err| {
     Error::new(err.kind(), format!("{}, chdir '{}'", err, directory))
   })?;
@@ -614,7 +614,7 @@ pub struct MkdirArgs {
 fn op_mkdir_sync(state: &mut OpState, args: MkdirArgs) -> Result<(), AnyError> {
   let path = Path::new(&args.path).to_path_buf();
   let mode = args.mode.unwrap_or(0o777) & 0o777;
-  state.borrow_mut::<Permissions>().write.check(&path)?;
+  state.borrow_mut::<Permissions>().check_write(&path)?;
   debug!("op_mkdir {} {:o} {}", path.display(), mode, args.recursive);
   let mut builder = std::fs::DirBuilder::new();
   builder.recursive(args.recursive);
@@ -639,7 +639,7 @@ async fn op_mkdir_async(
 
   {
     let mut state = state.borrow_mut();
-    state.borrow_mut::<Permissions>().write.check(&path)?;
+    state.borrow_mut::<Permissions>().check_write(&path)?;
   }
 
   tokio::task::spawn_blocking(move || {
@@ -672,7 +672,7 @@ fn op_chmod_sync(state: &mut OpState, args: ChmodArgs) -> Result<(), AnyError> {
   let path = Path::new(&args.path);
   let mode = args.mode & 0o777;
 
-  state.borrow_mut::<Permissions>().write.check(path)?;
+  state.borrow_mut::<Permissions>().check_write(path)?;
   debug!("op_chmod_sync {} {:o}", path.display(), mode);
   raw_chmod(path, mode)
 }
@@ -687,7 +687,7 @@ async fn op_chmod_async(
 
   {
     let mut state = state.borrow_mut();
-    state.borrow_mut::<Permissions>().write.check(&path)?;
+    state.borrow_mut::<Permissions>().check_write(&path)?;
   }
 
   tokio::task::spawn_blocking(move || {
@@ -729,7 +729,7 @@ pub struct ChownArgs {
 #[op]
 fn op_chown_sync(state: &mut OpState, args: ChownArgs) -> Result<(), AnyError> {
   let path = Path::new(&args.path).to_path_buf();
-  state.borrow_mut::<Permissions>().write.check(&path)?;
+  state.borrow_mut::<Permissions>().check_write(&path)?;
   debug!(
     "op_chown_sync {} {:?} {:?}",
     path.display(),
@@ -766,7 +766,7 @@ async fn op_chown_async(
 
   {
     let mut state = state.borrow_mut();
-    state.borrow_mut::<Permissions>().write.check(&path)?;
+    state.borrow_mut::<Permissions>().check_write(&path)?;
   }
 
   tokio::task::spawn_blocking(move || {
@@ -813,7 +813,7 @@ fn op_remove_sync(
   let path = PathBuf::from(&args.path);
   let recursive = args.recursive;
 
-  state.borrow_mut::<Permissions>().write.check(&path)?;
+  state.borrow_mut::<Permissions>().check_write(&path)?;
 
   #[cfg(not(unix))]
   use std::os::windows::prelude::MetadataExt;
@@ -860,7 +860,7 @@ async fn op_remove_async(
 
   {
     let mut state = state.borrow_mut();
-    state.borrow_mut::<Permissions>().write.check(&path)?;
+    state.borrow_mut::<Permissions>().check_write(&path)?;
   }
 
   tokio::task::spawn_blocking(move || {
@@ -917,8 +917,8 @@ fn op_copy_file_sync(
   let to = PathBuf::from(&args.to);
 
   let permissions = state.borrow_mut::<Permissions>();
-  permissions.read.check(&from)?;
-  permissions.write.check(&to)?;
+  permissions.check_read(&from)?;
+  permissions.check_write(&to)?;
 
   debug!("op_copy_file_sync {} {}", from.display(), to.display());
   // On *nix, Rust reports non-existent `from` as ErrorKind::InvalidInput
@@ -957,8 +957,8 @@ async fn op_copy_file_async(
   {
     let mut state = state.borrow_mut();
     let permissions = state.borrow_mut::<Permissions>();
-    permissions.read.check(&from)?;
-    permissions.write.check(&to)?;
+    permissions.check_read(&from)?;
+    permissions.check_write(&to)?;
   }
 
   debug!("op_copy_file_async {} {}", from.display(), to.display());
@@ -1081,7 +1081,7 @@ fn op_stat_sync(
 ) -> Result<FsStat, AnyError> {
   let path = PathBuf::from(&args.path);
   let lstat = args.lstat;
-  state.borrow_mut::<Permissions>().read.check(&path)?;
+  state.borrow_mut::<Permissions>().check_read(&path)?;
   debug!("op_stat_sync {} {}", path.display(), lstat);
   let err_mapper = |err: Error| {
     Error::new(err.kind(), format!("{}, stat '{}'", err, path.display()))
@@ -1104,7 +1104,7 @@ async fn op_stat_async(
 
   {
     let mut state = state.borrow_mut();
-    state.borrow_mut::<Permissions>().read.check(&path)?;
+    state.borrow_mut::<Permissions>().check_read(&path)?;
   }
 
   tokio::task::spawn_blocking(move || {
@@ -1131,9 +1131,9 @@ fn op_realpath_sync(
   let path = PathBuf::from(&path);
 
   let permissions = state.borrow_mut::<Permissions>();
-  permissions.read.check(&path)?;
+  permissions.check_read(&path)?;
   if path.is_relative() {
-    permissions.read.check_blind(&current_dir()?, "CWD")?;
+    permissions.check_read_blind(&current_dir()?, "CWD")?;
   }
 
   debug!("op_realpath_sync {}", path.display());
@@ -1154,9 +1154,9 @@ async fn op_realpath_async(
   {
     let mut state = state.borrow_mut();
     let permissions = state.borrow_mut::<Permissions>();
-    permissions.read.check(&path)?;
+    permissions.check_read(&path)?;
     if path.is_relative() {
-      permissions.read.check_blind(&current_dir()?, "CWD")?;
+      permissions.check_read_blind(&current_dir()?, "CWD")?;
     }
   }
 
@@ -1188,7 +1188,7 @@ fn op_read_dir_sync(
 ) -> Result<Vec<DirEntry>, AnyError> {
   let path = PathBuf::from(&path);
 
-  state.borrow_mut::<Permissions>().read.check(&path)?;
+  state.borrow_mut::<Permissions>().check_read(&path)?;
 
   debug!("op_read_dir_sync {}", path.display());
   let err_mapper = |err: Error| {
@@ -1229,7 +1229,7 @@ async fn op_read_dir_async(
   let path = PathBuf::from(&path);
   {
     let mut state = state.borrow_mut();
-    state.borrow_mut::<Permissions>().read.check(&path)?;
+    state.borrow_mut::<Permissions>().check_read(&path)?;
   }
   tokio::task::spawn_blocking(move || {
     debug!("op_read_dir_async {}", path.display());
@@ -1282,9 +1282,9 @@ fn op_rename_sync(
   let newpath = PathBuf::from(&args.newpath);
 
   let permissions = state.borrow_mut::<Permissions>();
-  permissions.read.check(&oldpath)?;
-  permissions.write.check(&oldpath)?;
-  permissions.write.check(&newpath)?;
+  permissions.check_read(&oldpath)?;
+  permissions.check_write(&oldpath)?;
+  permissions.check_write(&newpath)?;
   debug!("op_rename_sync {} {}", oldpath.display(), newpath.display());
   let err_mapper = |err: Error| {
     Error::new(
@@ -1311,9 +1311,9 @@ async fn op_rename_async(
   {
     let mut state = state.borrow_mut();
     let permissions = state.borrow_mut::<Permissions>();
-    permissions.read.check(&oldpath)?;
-    permissions.write.check(&oldpath)?;
-    permissions.write.check(&newpath)?;
+    permissions.check_read(&oldpath)?;
+    permissions.check_write(&oldpath)?;
+    permissions.check_write(&newpath)?;
   }
   tokio::task::spawn_blocking(move || {
     debug!(
@@ -1352,10 +1352,10 @@ fn op_link_sync(state: &mut OpState, args: LinkArgs) -> Result<(), AnyError> {
   let newpath = PathBuf::from(&args.newpath);
 
   let permissions = state.borrow_mut::<Permissions>();
-  permissions.read.check(&oldpath)?;
-  permissions.write.check(&oldpath)?;
-  permissions.read.check(&newpath)?;
-  permissions.write.check(&newpath)?;
+  permissions.check_read(&oldpath)?;
+  permissions.check_write(&oldpath)?;
+  permissions.check_read(&newpath)?;
+  permissions.check_write(&newpath)?;
 
   debug!("op_link_sync {} {}", oldpath.display(), newpath.display());
   let err_mapper = |err: Error| {
@@ -1384,10 +1384,10 @@ async fn op_link_async(
   {
     let mut state = state.borrow_mut();
     let permissions = state.borrow_mut::<Permissions>();
-    permissions.read.check(&oldpath)?;
-    permissions.write.check(&oldpath)?;
-    permissions.read.check(&newpath)?;
-    permissions.write.check(&newpath)?;
+    permissions.check_read(&oldpath)?;
+    permissions.check_write(&oldpath)?;
+    permissions.check_read(&newpath)?;
+    permissions.check_write(&newpath)?;
   }
 
   tokio::task::spawn_blocking(move || {
@@ -1434,8 +1434,8 @@ fn op_symlink_sync(
   let oldpath = PathBuf::from(&args.oldpath);
   let newpath = PathBuf::from(&args.newpath);
 
-  state.borrow_mut::<Permissions>().write.check_all()?;
-  state.borrow_mut::<Permissions>().read.check_all()?;
+  state.borrow_mut::<Permissions>().check_write_all()?;
+  state.borrow_mut::<Permissions>().check_read_all()?;
 
   debug!(
     "op_symlink_sync {} {}",
@@ -1497,8 +1497,8 @@ async fn op_symlink_async(
 
   {
     let mut state = state.borrow_mut();
-    state.borrow_mut::<Permissions>().write.check_all()?;
-    state.borrow_mut::<Permissions>().read.check_all()?;
+    state.borrow_mut::<Permissions>().check_write_all()?;
+    state.borrow_mut::<Permissions>().check_read_all()?;
   }
 
   tokio::task::spawn_blocking(move || {
@@ -1558,7 +1558,7 @@ fn op_read_link_sync(
 ) -> Result<String, AnyError> {
   let path = PathBuf::from(&path);
 
-  state.borrow_mut::<Permissions>().read.check(&path)?;
+  state.borrow_mut::<Permissions>().check_read(&path)?;
 
   debug!("op_read_link_value {}", path.display());
   let err_mapper = |err: Error| {
@@ -1582,7 +1582,7 @@ async fn op_read_link_async(
   let path = PathBuf::from(&path);
   {
     let mut state = state.borrow_mut();
-    state.borrow_mut::<Permissions>().read.check(&path)?;
+    state.borrow_mut::<Permissions>().check_read(&path)?;
   }
   tokio::task::spawn_blocking(move || {
     debug!("op_read_link_async {}", path.display());
@@ -1666,7 +1666,7 @@ fn op_truncate_sync(
   let path = PathBuf::from(&args.path);
   let len = args.len;
 
-  state.borrow_mut::<Permissions>().write.check(&path)?;
+  state.borrow_mut::<Permissions>().check_write(&path)?;
 
   debug!("op_truncate_sync {} {}", path.display(), len);
   let err_mapper = |err: Error| {
@@ -1692,7 +1692,7 @@ async fn op_truncate_async(
   let len = args.len;
   {
     let mut state = state.borrow_mut();
-    state.borrow_mut::<Permissions>().write.check(&path)?;
+    state.borrow_mut::<Permissions>().check_write(&path)?;
   }
   tokio::task::spawn_blocking(move || {
     debug!("op_truncate_async {} {}", path.display(), len);
@@ -1777,8 +1777,7 @@ fn op_make_temp_dir_sync(
 
   state
     .borrow_mut::<Permissions>()
-    .write
-    .check(dir.clone().unwrap_or_else(temp_dir).as_path())?;
+    .check_write(dir.clone().unwrap_or_else(temp_dir).as_path())?;
 
   // TODO(piscisaureus): use byte vector for paths, not a string.
   // See https://github.com/denoland/deno/issues/627.
@@ -1807,8 +1806,7 @@ async fn op_make_temp_dir_async(
     let mut state = state.borrow_mut();
     state
       .borrow_mut::<Permissions>()
-      .write
-      .check(dir.clone().unwrap_or_else(temp_dir).as_path())?;
+      .check_write(dir.clone().unwrap_or_else(temp_dir).as_path())?;
   }
   tokio::task::spawn_blocking(move || {
     // TODO(piscisaureus): use byte vector for paths, not a string.
@@ -1840,8 +1838,7 @@ fn op_make_temp_file_sync(
 
   state
     .borrow_mut::<Permissions>()
-    .write
-    .check(dir.clone().unwrap_or_else(temp_dir).as_path())?;
+    .check_write(dir.clone().unwrap_or_else(temp_dir).as_path())?;
 
   // TODO(piscisaureus): use byte vector for paths, not a string.
   // See https://github.com/denoland/deno/issues/627.
@@ -1870,8 +1867,7 @@ async fn op_make_temp_file_async(
     let mut state = state.borrow_mut();
     state
       .borrow_mut::<Permissions>()
-      .write
-      .check(dir.clone().unwrap_or_else(temp_dir).as_path())?;
+      .check_write(dir.clone().unwrap_or_else(temp_dir).as_path())?;
   }
   tokio::task::spawn_blocking(move || {
     // TODO(piscisaureus): use byte vector for paths, not a string.
@@ -1969,7 +1965,7 @@ fn op_utime_sync(state: &mut OpState, args: UtimeArgs) -> Result<(), AnyError> {
   let atime = filetime::FileTime::from_unix_time(args.atime.0, args.atime.1);
   let mtime = filetime::FileTime::from_unix_time(args.mtime.0, args.mtime.1);
 
-  state.borrow_mut::<Permissions>().write.check(&path)?;
+  state.borrow_mut::<Permissions>().check_write(&path)?;
   filetime::set_file_times(&path, atime, mtime).map_err(|err| {
     Error::new(err.kind(), format!("{}, utime '{}'", err, path.display()))
   })?;
@@ -1990,8 +1986,7 @@ async fn op_utime_async(
   state
     .borrow_mut()
     .borrow_mut::<Permissions>()
-    .write
-    .check(&path)?;
+    .check_write(&path)?;
 
   tokio::task::spawn_blocking(move || {
     filetime::set_file_times(&path, atime, mtime).map_err(|err| {
@@ -2008,8 +2003,7 @@ fn op_cwd(state: &mut OpState) -> Result<String, AnyError> {
   let path = current_dir()?;
   state
     .borrow_mut::<Permissions>()
-    .read
-    .check_blind(&path, "CWD")?;
+    .check_read_blind(&path, "CWD")?;
   let path_str = into_string(path.into_os_string())?;
   Ok(path_str)
 }
@@ -2021,7 +2015,7 @@ fn op_readfile_sync(
 ) -> Result<ZeroCopyBuf, AnyError> {
   let permissions = state.borrow_mut::<Permissions>();
   let path = Path::new(&path);
-  permissions.read.check(path)?;
+  permissions.check_read(path)?;
   Ok(std::fs::read(path)?.into())
 }
 
@@ -2032,7 +2026,7 @@ fn op_readfile_text_sync(
 ) -> Result<String, AnyError> {
   let permissions = state.borrow_mut::<Permissions>();
   let path = Path::new(&path);
-  permissions.read.check(path)?;
+  permissions.check_read(path)?;
   Ok(string_from_utf8_lossy(std::fs::read(path)?))
 }
 
@@ -2045,7 +2039,7 @@ async fn op_readfile_async(
   {
     let path = Path::new(&path);
     let mut state = state.borrow_mut();
-    state.borrow_mut::<Permissions>().read.check(path)?;
+    state.borrow_mut::<Permissions>().check_read(path)?;
   }
   let fut = tokio::task::spawn_blocking(move || {
     let path = Path::new(&path);
@@ -2072,7 +2066,7 @@ async fn op_readfile_text_async(
   {
     let path = Path::new(&path);
     let mut state = state.borrow_mut();
-    state.borrow_mut::<Permissions>().read.check(path)?;
+    state.borrow_mut::<Permissions>().check_read(path)?;
   }
   let fut = tokio::task::spawn_blocking(move || {
     let path = Path::new(&path);
@@ -116,7 +116,7 @@ fn op_fs_events_open(
   };
   for path in &args.paths {
     let path = PathBuf::from(path);
-    state.borrow_mut::<Permissions>().read.check(&path)?;
+    state.borrow_mut::<Permissions>().check_read(&path)?;
     watcher.watch(&path, recursive_mode)?;
   }
   let resource = FsEventsResource {
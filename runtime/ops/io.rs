@@ -31,8 +31,14 @@ use tokio::io::AsyncWrite;
 use tokio::io::AsyncWriteExt;
 use tokio::process;
 
+#[cfg(unix)]
+use deno_core::CancelFuture;
+#[cfg(unix)]
+use std::os::unix::io::AsRawFd;
 #[cfg(unix)]
 use std::os::unix::io::FromRawFd;
+#[cfg(unix)]
+use std::os::unix::io::RawFd;
 
 #[cfg(windows)]
 use {
@@ -182,13 +188,16 @@ where
     RcRef::map(self, |r| &r.stream).borrow_mut()
   }
 
-  async fn write(self: Rc<Self>, buf: ZeroCopyBuf) -> Result<usize, AnyError> {
+  pub async fn write(
+    self: Rc<Self>,
+    buf: ZeroCopyBuf,
+  ) -> Result<usize, AnyError> {
     let mut stream = self.borrow_mut().await;
     let nwritten = stream.write(&buf).await?;
     Ok(nwritten)
   }
 
-  async fn shutdown(self: Rc<Self>) -> Result<(), AnyError> {
+  pub async fn shutdown(self: Rc<Self>) -> Result<(), AnyError> {
     let mut stream = self.borrow_mut().await;
     stream.shutdown().await?;
     Ok(())
@@ -230,7 +239,7 @@ where
     self.cancel_handle.cancel()
   }
 
-  async fn read(
+  pub async fn read(
     self: Rc<Self>,
     mut buf: ZeroCopyBuf,
   ) -> Result<(usize, ZeroCopyBuf), AnyError> {
@@ -340,6 +349,10 @@ impl StdFileResource {
     if self.fs_file.is_some() {
       let fs_file = self.fs_file.as_ref().unwrap();
       let std_file = fs_file.0.as_ref().unwrap().clone();
+      #[cfg(unix)]
+      wait_for_tty_input(&std_file)
+        .or_cancel(RcRef::map(&self, |r| &r.cancel))
+        .await??;
       tokio::task::spawn_blocking(
         move || -> Result<(usize, ZeroCopyBuf), AnyError> {
           let mut std_file = std_file.lock().unwrap();
@@ -401,6 +414,46 @@ impl StdFileResource {
   }
 }
 
+/// Waits until a terminal has input to read. Reading it right away would
+/// occupy a blocking thread, and the lock of the file, until the user types
+/// something, which e.g. keeps raw mode from being toggled in the meantime.
+/// Returns immediately for other files.
+#[cfg(unix)]
+async fn wait_for_tty_input(std_file: &Mutex<StdFile>) -> Result<(), AnyError> {
+  use tokio::io::unix::AsyncFd;
+  use tokio::io::Interest;
+
+  let file = match std_file.try_lock() {
+    Ok(std_file) if unsafe { libc::isatty(std_file.as_raw_fd()) } == 1 => {
+      std_file.try_clone()?
+    }
+    // another read is in progress or the file isn't a terminal
+    _ => return Ok(()),
+  };
+  let async_fd = match AsyncFd::with_interest(file, Interest::READABLE) {
+    Ok(async_fd) => async_fd,
+    // the terminal can't be polled, fall back to a blocking read
+    Err(_) => return Ok(()),
+  };
+  loop {
+    let mut guard = async_fd.readable().await?;
+    if has_input(async_fd.as_raw_fd()) {
+      return Ok(());
+    }
+    guard.clear_ready();
+  }
+}
+
+#[cfg(unix)]
+fn has_input(fd: RawFd) -> bool {
+  let mut pollfd = libc::pollfd {
+    fd,
+    events: libc::POLLIN,
+    revents: 0,
+  };
+  unsafe { libc::poll(&mut pollfd, 1, 0) == 1 }
+}
+
 impl Resource for StdFileResource {
   fn name(&self) -> Cow<str> {
     self.name.as_str().into()
@@ -7,6 +7,8 @@ pub mod io;
 pub mod os;
 pub mod permissions;
 pub mod process;
+#[cfg(unix)]
+mod pty;
 pub mod runtime;
 pub mod signal;
 pub mod spawn;
@@ -1,15 +1,19 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
+use crate::permissions::escalation_error;
+use crate::permissions::NetDescriptor;
+use crate::permissions::PermissionState;
 use crate::permissions::Permissions;
+use crate::permissions::PublishedPermissions;
 use deno_core::error::custom_error;
 use deno_core::error::uri_error;
 use deno_core::error::AnyError;
 use deno_core::op;
-use deno_core::url;
 use deno_core::Extension;
 use deno_core::OpState;
 use serde::Deserialize;
 use std::path::Path;
+use std::sync::Arc;
 
 pub fn init() -> Extension {
   Extension::builder()
@@ -30,14 +34,19 @@ pub struct PermissionArgs {
   command: Option<String>,
 }
 
-#[op]
-pub fn op_query_permission(
-  state: &mut OpState,
-  args: PermissionArgs,
-) -> Result<String, AnyError> {
-  let permissions = state.borrow::<Permissions>();
+fn no_such_permission_error(name: &str) -> AnyError {
+  custom_error(
+    "ReferenceError",
+    format!("No such permission name: {}", name),
+  )
+}
+
+fn query_permission(
+  permissions: &Permissions,
+  args: &PermissionArgs,
+) -> Result<PermissionState, AnyError> {
   let path = args.path.as_deref();
-  let perm = match args.name.as_ref() {
+  Ok(match args.name.as_ref() {
     "read" => permissions.read.query(path.map(Path::new)),
     "write" => permissions.write.query(path.map(Path::new)),
     "net" => permissions.net.query(
@@ -51,24 +60,16 @@ pub fn op_query_permission(
     "run" => permissions.run.query(args.command.as_deref()),
     "ffi" => permissions.ffi.query(args.path.as_deref().map(Path::new)),
     "hrtime" => permissions.hrtime.query(),
-    n => {
-      return Err(custom_error(
-        "ReferenceError",
-        format!("No such permission name: {}", n),
-      ))
-    }
-  };
-  Ok(perm.to_string())
+    n => return Err(no_such_permission_error(n)),
+  })
 }
 
-#[op]
-pub fn op_revoke_permission(
-  state: &mut OpState,
-  args: PermissionArgs,
-) -> Result<String, AnyError> {
-  let permissions = state.borrow_mut::<Permissions>();
+fn revoke_permission(
+  permissions: &mut Permissions,
+  args: &PermissionArgs,
+) -> Result<PermissionState, AnyError> {
   let path = args.path.as_deref();
-  let perm = match args.name.as_ref() {
+  Ok(match args.name.as_ref() {
     "read" => permissions.read.revoke(path.map(Path::new)),
     "write" => permissions.write.revoke(path.map(Path::new)),
     "net" => permissions.net.revoke(
@@ -82,13 +83,40 @@ pub fn op_revoke_permission(
     "run" => permissions.run.revoke(args.command.as_deref()),
     "ffi" => permissions.ffi.revoke(args.path.as_deref().map(Path::new)),
     "hrtime" => permissions.hrtime.revoke(),
-    n => {
-      return Err(custom_error(
-        "ReferenceError",
-        format!("No such permission name: {}", n),
-      ))
-    }
-  };
+    n => return Err(no_such_permission_error(n)),
+  })
+}
+
+/// The permissions of the ancestors of a web worker as they last published
+/// them, starting with the main worker. The worker can't request permissions
+/// that any of them was denied, and prompts for the ones they would prompt
+/// for.
+pub struct ParentPermissions(pub Vec<PublishedPermissions>);
+
+#[op]
+pub fn op_query_permission(
+  state: &mut OpState,
+  args: PermissionArgs,
+) -> Result<String, AnyError> {
+  let permissions = state.borrow_mut::<Permissions>();
+  permissions.apply_revocations();
+  let perm = query_permission(permissions, &args)?;
+  Ok(perm.to_string())
+}
+
+#[op]
+pub fn op_revoke_permission(
+  state: &mut OpState,
+  args: PermissionArgs,
+) -> Result<String, AnyError> {
+  let permissions = state.borrow_mut::<Permissions>();
+  permissions.apply_revocations();
+  let perm = revoke_permission(permissions, &args)?;
+  permissions.revoke_in_children(Arc::new(move |permissions| {
+    // the arguments were validated by the revocation above
+    let _ = revoke_permission(permissions, &args);
+  }));
+  permissions.publish();
   Ok(perm.to_string())
 }
 
@@ -97,6 +125,20 @@ pub fn op_request_permission(
   state: &mut OpState,
   args: PermissionArgs,
 ) -> Result<String, AnyError> {
+  state.borrow_mut::<Permissions>().apply_revocations();
+  if let Some(parent_permissions) = state.try_borrow::<ParentPermissions>() {
+    for ancestor_permissions in
+      parent_permissions.0.iter().filter_map(|p| p.get())
+    {
+      if query_permission(&ancestor_permissions, &args)?
+        == PermissionState::Denied
+        && query_permission(state.borrow::<Permissions>(), &args)?
+          != PermissionState::Granted
+      {
+        return Err(escalation_error());
+      }
+    }
+  }
   let permissions = state.borrow_mut::<Permissions>();
   let path = args.path.as_deref();
   let perm = match args.name.as_ref() {
@@ -113,22 +155,14 @@ pub fn op_request_permission(
     "run" => permissions.run.request(args.command.as_deref()),
     "ffi" => permissions.ffi.request(args.path.as_deref().map(Path::new)),
     "hrtime" => permissions.hrtime.request(),
-    n => {
-      return Err(custom_error(
-        "ReferenceError",
-        format!("No such permission name: {}", n),
-      ))
-    }
+    n => return Err(no_such_permission_error(n)),
   };
+  permissions.publish();
   Ok(perm.to_string())
 }
 
 fn parse_host(host_str: &str) -> Result<(String, Option<u16>), AnyError> {
-  let url = url::Url::parse(&format!("http://{}/", host_str))
-    .map_err(|_| uri_error("Invalid host"))?;
-  if url.path() != "/" {
-    return Err(uri_error("Invalid host"));
-  }
-  let hostname = url.host_str().unwrap();
-  Ok((hostname.to_string(), url.port()))
+  let NetDescriptor(hostname, port) =
+    NetDescriptor::parse(host_str).ok_or_else(|| uri_error("Invalid host"))?;
+  Ok((hostname, port))
 }
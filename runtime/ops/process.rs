@@ -147,7 +147,9 @@ struct RunInfo {
 #[op]
 fn op_run(state: &mut OpState, run_args: RunArgs) -> Result<RunInfo, AnyError> {
   let args = run_args.cmd;
-  state.borrow_mut::<Permissions>().run.check(&args[0])?;
+  state
+    .borrow_mut::<Permissions>()
+    .check_run(&args[0], &args[1..])?;
   let env = run_args.env;
   let cwd = run_args.cwd;
 
@@ -302,9 +304,51 @@ pub fn kill(pid: i32, signal: &str) -> Result<(), AnyError> {
 #[cfg(not(unix))]
 pub fn kill(pid: i32, signal: &str) -> Result<(), AnyError> {
   use deno_core::error::type_error;
+  use winapi::shared::minwindef::DWORD;
+
+  if pid == 0 || pid == i32::MIN {
+    Err(type_error("Invalid pid"))
+  } else if pid < 0 {
+    // A process group can only be signalled with a ctrl-break event, which
+    // reaches the processes of the group sharing our console.
+    if signal != "SIGBREAK" {
+      return Err(type_error(format!(
+        "Windows only supports ctrl-break (SIGBREAK) for process groups, but got {}",
+        signal
+      )));
+    }
+    ctrl_break_process_group(-pid as DWORD)
+  } else if !matches!(signal, "SIGKILL" | "SIGTERM") {
+    Err(type_error(format!("Invalid signal: {}", signal)))
+  } else {
+    terminate_process(pid as DWORD)
+  }
+}
+
+/// Sends a ctrl-break event to a console process group, which is identified
+/// by the pid of the process created with `CREATE_NEW_PROCESS_GROUP`. Unlike
+/// a pid, the group id can't be taken by an unrelated process while the group
+/// has members.
+#[cfg(not(unix))]
+fn ctrl_break_process_group(
+  pgid: winapi::shared::minwindef::DWORD,
+) -> Result<(), AnyError> {
+  use winapi::shared::minwindef::FALSE;
+  use winapi::um::wincon::GenerateConsoleCtrlEvent;
+  use winapi::um::wincon::CTRL_BREAK_EVENT;
+
+  if unsafe { GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pgid) } == FALSE {
+    return Err(std::io::Error::last_os_error().into());
+  }
+  Ok(())
+}
+
+#[cfg(not(unix))]
+fn terminate_process(
+  pid: winapi::shared::minwindef::DWORD,
+) -> Result<(), AnyError> {
   use std::io::Error;
   use std::io::ErrorKind::NotFound;
-  use winapi::shared::minwindef::DWORD;
   use winapi::shared::minwindef::FALSE;
   use winapi::shared::minwindef::TRUE;
   use winapi::shared::winerror::ERROR_INVALID_PARAMETER;
@@ -314,26 +358,20 @@ pub fn kill(pid: i32, signal: &str) -> Result<(), AnyError> {
   use winapi::um::processthreadsapi::TerminateProcess;
   use winapi::um::winnt::PROCESS_TERMINATE;
 
-  if !matches!(signal, "SIGKILL" | "SIGTERM") {
-    Err(type_error(format!("Invalid signal: {}", signal)))
-  } else if pid <= 0 {
-    Err(type_error("Invalid pid"))
+  let handle = unsafe { OpenProcess(PROCESS_TERMINATE, FALSE, pid) };
+  if handle.is_null() {
+    let err = match unsafe { GetLastError() } {
+      ERROR_INVALID_PARAMETER => Error::from(NotFound), // Invalid `pid`.
+      errno => Error::from_raw_os_error(errno as i32),
+    };
+    Err(err.into())
   } else {
-    let handle = unsafe { OpenProcess(PROCESS_TERMINATE, FALSE, pid as DWORD) };
-    if handle.is_null() {
-      let err = match unsafe { GetLastError() } {
-        ERROR_INVALID_PARAMETER => Error::from(NotFound), // Invalid `pid`.
-        errno => Error::from_raw_os_error(errno as i32),
-      };
-      Err(err.into())
-    } else {
-      let r = unsafe { TerminateProcess(handle, 1) };
-      unsafe { CloseHandle(handle) };
-      match r {
-        FALSE => Err(Error::last_os_error().into()),
-        TRUE => Ok(()),
-        _ => unreachable!(),
-      }
+    let r = unsafe { TerminateProcess(handle, 1) };
+    unsafe { CloseHandle(handle) };
+    match r {
+      FALSE => Err(Error::last_os_error().into()),
+      TRUE => Ok(()),
+      _ => unreachable!(),
     }
   }
 }
@@ -344,7 +382,7 @@ fn op_kill(
   pid: i32,
   signal: String,
 ) -> Result<(), AnyError> {
-  state.borrow_mut::<Permissions>().run.check_all()?;
+  state.borrow_mut::<Permissions>().check_run_all()?;
   kill(pid, &signal)?;
   Ok(())
 }
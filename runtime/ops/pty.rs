@@ -0,0 +1,226 @@
+// Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
+
+//! Pseudo-terminals for the subprocesses spawned with the `pty` option of
+//! `Deno.spawnChild()`, so that they behave as if run in a terminal.
+
+use super::io::ReadOnlyResource;
+use super::io::WriteOnlyResource;
+use super::spawn::PtySize;
+use deno_core::error::AnyError;
+use deno_core::futures::ready;
+use deno_core::AsyncResult;
+use deno_core::Resource;
+use deno_core::ZeroCopyBuf;
+use nix::fcntl::fcntl;
+use nix::fcntl::FcntlArg;
+use nix::fcntl::FdFlag;
+use nix::fcntl::OFlag;
+use std::borrow::Cow;
+use std::fs::File as StdFile;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::io::FromRawFd;
+use std::os::unix::process::CommandExt;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::Context;
+use std::task::Poll;
+use tokio::io::unix::AsyncFd;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::ReadBuf;
+use tokio::io::ReadHalf;
+use tokio::io::WriteHalf;
+
+/// The controlling side of a pseudo-terminal, from which the output of the
+/// processes attached to it is read and to which their input is written.
+pub struct PtyMaster(AsyncFd<StdFile>);
+
+impl AsyncRead for PtyMaster {
+  fn poll_read(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+  ) -> Poll<io::Result<()>> {
+    loop {
+      let mut guard = ready!(self.0.poll_read_ready(cx))?;
+      let result = guard.try_io(|inner| {
+        let mut file: &StdFile = inner.get_ref();
+        file.read(buf.initialize_unfilled())
+      });
+      match result {
+        Ok(Ok(nread)) => {
+          buf.advance(nread);
+          return Poll::Ready(Ok(()));
+        }
+        // Linux fails with EIO once every process attached to the terminal
+        // has exited, which is the end of its output.
+        Ok(Err(err)) if err.raw_os_error() == Some(libc::EIO) => {
+          return Poll::Ready(Ok(()));
+        }
+        Ok(Err(err)) => return Poll::Ready(Err(err)),
+        Err(_would_block) => continue,
+      }
+    }
+  }
+}
+
+impl AsyncWrite for PtyMaster {
+  fn poll_write(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+  ) -> Poll<io::Result<usize>> {
+    loop {
+      let mut guard = ready!(self.0.poll_write_ready(cx))?;
+      let result = guard.try_io(|inner| {
+        let mut file: &StdFile = inner.get_ref();
+        file.write(buf)
+      });
+      match result {
+        Ok(result) => return Poll::Ready(result),
+        Err(_would_block) => continue,
+      }
+    }
+  }
+
+  fn poll_flush(
+    self: Pin<&mut Self>,
+    _cx: &mut Context<'_>,
+  ) -> Poll<io::Result<()>> {
+    Poll::Ready(Ok(()))
+  }
+
+  fn poll_shutdown(
+    self: Pin<&mut Self>,
+    _cx: &mut Context<'_>,
+  ) -> Poll<io::Result<()>> {
+    Poll::Ready(Ok(()))
+  }
+}
+
+pub type PtyOutputResource = ReadOnlyResource<ReadHalf<PtyMaster>>;
+
+impl Resource for PtyOutputResource {
+  fn name(&self) -> Cow<str> {
+    "ptyOutput".into()
+  }
+
+  fn read_return(
+    self: Rc<Self>,
+    buf: ZeroCopyBuf,
+  ) -> AsyncResult<(usize, ZeroCopyBuf)> {
+    Box::pin(self.read(buf))
+  }
+
+  fn close(self: Rc<Self>) {
+    self.cancel_read_ops();
+  }
+}
+
+pub type PtyInputResource = WriteOnlyResource<WriteHalf<PtyMaster>>;
+
+impl Resource for PtyInputResource {
+  fn name(&self) -> Cow<str> {
+    "ptyInput".into()
+  }
+
+  fn write(self: Rc<Self>, buf: ZeroCopyBuf) -> AsyncResult<usize> {
+    Box::pin(self.write(buf))
+  }
+
+  fn shutdown(self: Rc<Self>) -> AsyncResult<()> {
+    Box::pin(self.shutdown())
+  }
+}
+
+/// Holds a duplicate of the master side of a pseudo-terminal to resize it.
+pub struct PtyResource(StdFile);
+
+impl Resource for PtyResource {
+  fn name(&self) -> Cow<str> {
+    "pty".into()
+  }
+}
+
+impl PtyResource {
+  pub fn resize(&self, size: PtySize) -> Result<(), AnyError> {
+    let winsize = to_winsize(size);
+    if unsafe { libc::ioctl(self.0.as_raw_fd(), libc::TIOCSWINSZ, &winsize) }
+      == -1
+    {
+      return Err(io::Error::last_os_error().into());
+    }
+    Ok(())
+  }
+}
+
+fn to_winsize(size: PtySize) -> libc::winsize {
+  libc::winsize {
+    ws_row: size.rows,
+    ws_col: size.columns,
+    ws_xpixel: 0,
+    ws_ypixel: 0,
+  }
+}
+
+/// A newly opened pseudo-terminal.
+pub struct Pty {
+  master: StdFile,
+  slave: StdFile,
+}
+
+impl Pty {
+  pub fn open(size: PtySize) -> Result<Self, AnyError> {
+    let fds = nix::pty::openpty(&to_winsize(size), None)?;
+    let (master, slave) = unsafe {
+      (
+        StdFile::from_raw_fd(fds.master),
+        StdFile::from_raw_fd(fds.slave),
+      )
+    };
+    // other subprocesses must not inherit the terminal
+    for fd in [master.as_raw_fd(), slave.as_raw_fd()] {
+      fcntl(fd, FcntlArg::F_SETFD(FdFlag::FD_CLOEXEC))?;
+    }
+    fcntl(master.as_raw_fd(), FcntlArg::F_SETFL(OFlag::O_NONBLOCK))?;
+    Ok(Self { master, slave })
+  }
+
+  /// Makes the terminal the stdio and the controlling terminal of the
+  /// process spawned by the command.
+  pub fn attach(
+    &self,
+    command: &mut std::process::Command,
+  ) -> Result<(), AnyError> {
+    command.stdin(self.slave.try_clone()?);
+    command.stdout(self.slave.try_clone()?);
+    command.stderr(self.slave.try_clone()?);
+    unsafe {
+      command.pre_exec(|| {
+        // a new session has no controlling terminal yet, so the one on stdin
+        // can be acquired
+        if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1
+        {
+          return Err(io::Error::last_os_error());
+        }
+        Ok(())
+      });
+    }
+    Ok(())
+  }
+
+  /// Splits the master side of the terminal into the resources reading the
+  /// output of the process, writing its input, and resizing the terminal.
+  /// The slave side is closed, so that the output ends with the process.
+  pub fn into_resources(
+    self,
+  ) -> Result<(PtyOutputResource, PtyInputResource, PtyResource), AnyError> {
+    let control = PtyResource(self.master.try_clone()?);
+    let master = PtyMaster(AsyncFd::new(self.master)?);
+    let (output, input) = tokio::io::split(master);
+    Ok((output.into(), input.into(), control))
+  }
+}
@@ -28,8 +28,7 @@ fn op_main_module(state: &mut OpState) -> Result<String, AnyError> {
       .join(main_url.to_string());
     state
       .borrow_mut::<Permissions>()
-      .read
-      .check_blind(&main_path, "main_module")?;
+      .check_read_blind(&main_path, "main_module")?;
   }
   Ok(main)
 }
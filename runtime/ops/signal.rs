@@ -1,35 +1,24 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
-#[cfg(not(unix))]
-use deno_core::error::generic_error;
-#[cfg(not(target_os = "windows"))]
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op;
 
-use deno_core::Extension;
-#[cfg(unix)]
-use deno_core::OpState;
-#[cfg(unix)]
-use std::cell::RefCell;
-#[cfg(unix)]
-use std::rc::Rc;
-
-#[cfg(unix)]
 use deno_core::AsyncRefCell;
-#[cfg(unix)]
 use deno_core::CancelFuture;
-#[cfg(unix)]
 use deno_core::CancelHandle;
-#[cfg(unix)]
+use deno_core::Extension;
+use deno_core::OpState;
 use deno_core::RcRef;
-#[cfg(unix)]
 use deno_core::Resource;
-#[cfg(unix)]
 use deno_core::ResourceId;
-#[cfg(unix)]
 use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+
 #[cfg(unix)]
 use tokio::signal::unix::{signal, Signal, SignalKind};
+#[cfg(windows)]
+use tokio::signal::windows::{ctrl_break, ctrl_c, CtrlBreak, CtrlC};
 
 pub fn init() -> Extension {
   Extension::builder()
@@ -41,15 +30,34 @@ pub fn init() -> Extension {
     .build()
 }
 
-#[cfg(unix)]
+/// The console control events that are handled like the signals of the same
+/// name on Windows, `SetConsoleCtrlHandler` being registered for them.
+#[cfg(windows)]
+enum WindowsSignal {
+  CtrlC(CtrlC),
+  CtrlBreak(CtrlBreak),
+}
+
+#[cfg(windows)]
+impl WindowsSignal {
+  async fn recv(&mut self) -> Option<()> {
+    match self {
+      WindowsSignal::CtrlC(signal) => signal.recv().await,
+      WindowsSignal::CtrlBreak(signal) => signal.recv().await,
+    }
+  }
+}
+
 /// The resource for signal stream.
 /// The second element is the waker of polling future.
 struct SignalStreamResource {
+  #[cfg(unix)]
   signal: AsyncRefCell<Signal>,
+  #[cfg(windows)]
+  signal: AsyncRefCell<WindowsSignal>,
   cancel: CancelHandle,
 }
 
-#[cfg(unix)]
 impl Resource for SignalStreamResource {
   fn name(&self) -> Cow<str> {
     "signal".into()
@@ -247,7 +255,30 @@ fn op_signal_bind(
   Ok(rid)
 }
 
-#[cfg(unix)]
+#[cfg(windows)]
+#[op]
+fn op_signal_bind(
+  state: &mut OpState,
+  sig: String,
+) -> Result<ResourceId, AnyError> {
+  let signal = match sig.as_str() {
+    "SIGINT" => WindowsSignal::CtrlC(ctrl_c()?),
+    "SIGBREAK" => WindowsSignal::CtrlBreak(ctrl_break()?),
+    _ => {
+      return Err(type_error(format!(
+        "Windows only supports ctrl-c (SIGINT) and ctrl-break (SIGBREAK), but got {}",
+        sig
+      )))
+    }
+  };
+  let resource = SignalStreamResource {
+    signal: AsyncRefCell::new(signal),
+    cancel: Default::default(),
+  };
+  let rid = state.resource_table.add(resource);
+  Ok(rid)
+}
+
 #[op]
 async fn op_signal_poll(
   state: Rc<RefCell<OpState>>,
@@ -266,7 +297,6 @@ async fn op_signal_poll(
   }
 }
 
-#[cfg(unix)]
 #[op]
 pub fn op_signal_unbind(
   state: &mut OpState,
@@ -275,21 +305,3 @@ pub fn op_signal_unbind(
   state.resource_table.close(rid)?;
   Ok(())
 }
-
-#[cfg(not(unix))]
-#[op]
-pub fn op_signal_bind() -> Result<(), AnyError> {
-  Err(generic_error("not implemented"))
-}
-
-#[cfg(not(unix))]
-#[op]
-fn op_signal_unbind() -> Result<(), AnyError> {
-  Err(generic_error("not implemented"))
-}
-
-#[cfg(not(unix))]
-#[op]
-async fn op_signal_poll() -> Result<(), AnyError> {
-  Err(generic_error("not implemented"))
-}
@@ -6,6 +6,7 @@ use super::io::ChildStdoutResource;
 use super::process::Stdio;
 use super::process::StdioOrRid;
 use crate::permissions::Permissions;
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::op;
 use deno_core::Extension;
@@ -20,6 +21,10 @@ use std::cell::RefCell;
 use std::process::ExitStatus;
 use std::rc::Rc;
 
+#[cfg(unix)]
+use super::pty::Pty;
+#[cfg(unix)]
+use super::pty::PtyResource;
 #[cfg(unix)]
 use std::os::unix::prelude::ExitStatusExt;
 #[cfg(unix)]
@@ -31,6 +36,7 @@ pub fn init() -> Extension {
       op_spawn_child::decl(),
       op_spawn_wait::decl(),
       op_spawn_sync::decl(),
+      op_spawn_pty_resize::decl(),
     ])
     .build()
 }
@@ -55,11 +61,21 @@ pub struct SpawnArgs {
   gid: Option<u32>,
   #[cfg(unix)]
   uid: Option<u32>,
+  detached: bool,
+  new_process_group: bool,
+  pty: Option<PtySize>,
 
   #[serde(flatten)]
   stdio: ChildStdio,
 }
 
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct PtySize {
+  pub columns: u16,
+  pub rows: u16,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ChildStdio {
@@ -115,7 +131,9 @@ fn create_command(
   args: SpawnArgs,
 ) -> Result<std::process::Command, AnyError> {
   super::check_unstable(state, "Deno.spawn");
-  state.borrow_mut::<Permissions>().run.check(&args.cmd)?;
+  state
+    .borrow_mut::<Permissions>()
+    .check_run(&args.cmd, &args.args)?;
 
   let mut command = std::process::Command::new(args.cmd);
   command.args(args.args);
@@ -147,6 +165,44 @@ fn create_command(
     });
   }
 
+  if args.detached {
+    super::check_unstable(state, "Deno.spawn.detached");
+  }
+  if args.new_process_group {
+    super::check_unstable(state, "Deno.spawn.newProcessGroup");
+  }
+  #[cfg(unix)]
+  if args.detached || args.new_process_group {
+    let detached = args.detached;
+    unsafe {
+      command.pre_exec(move || {
+        // a new session also is a new process group, without a controlling
+        // terminal
+        let result = if detached {
+          libc::setsid()
+        } else {
+          libc::setpgid(0, 0)
+        };
+        if result == -1 {
+          return Err(std::io::Error::last_os_error());
+        }
+        Ok(())
+      });
+    }
+  }
+  #[cfg(windows)]
+  {
+    use std::os::windows::process::CommandExt;
+    use winapi::um::winbase::CREATE_NEW_PROCESS_GROUP;
+    use winapi::um::winbase::DETACHED_PROCESS;
+
+    if args.detached {
+      command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+    } else if args.new_process_group {
+      command.creation_flags(CREATE_NEW_PROCESS_GROUP);
+    }
+  }
+
   command.stdin(args.stdio.stdin.as_stdio());
   command.stdout(match args.stdio.stdout {
     Stdio::Inherit => StdioOrRid::Rid(1).as_stdio(state)?,
@@ -168,6 +224,7 @@ struct Child {
   stdin_rid: Option<ResourceId>,
   stdout_rid: Option<ResourceId>,
   stderr_rid: Option<ResourceId>,
+  pty_rid: Option<ResourceId>,
 }
 
 #[op]
@@ -175,11 +232,13 @@ fn op_spawn_child(
   state: &mut OpState,
   args: SpawnArgs,
 ) -> Result<Child, AnyError> {
+  if args.pty.is_some() {
+    return spawn_pty_child(state, args);
+  }
+  let detached = args.detached;
   let mut command = tokio::process::Command::from(create_command(state, args)?);
-  // TODO(@crowlkats): allow detaching processes.
-  //  currently deno will orphan a process when exiting with an error or Deno.exit()
-  // We want to kill child when it's closed
-  command.kill_on_drop(true);
+  // We want to kill child when it's closed, unless it outlives deno
+  command.kill_on_drop(!detached);
 
   let mut child = command.spawn()?;
   let pid = child.id().expect("Process ID should be set.");
@@ -207,9 +266,77 @@ fn op_spawn_child(
     stdin_rid,
     stdout_rid,
     stderr_rid,
+    pty_rid: None,
+  })
+}
+
+/// Spawns a child whose stdio is a new pseudo-terminal, whose output is read
+/// through the stdout resource and whose input is written through the stdin
+/// one.
+#[cfg(unix)]
+fn spawn_pty_child(
+  state: &mut OpState,
+  args: SpawnArgs,
+) -> Result<Child, AnyError> {
+  super::check_unstable(state, "Deno.spawnChild.pty");
+  if args.detached || args.new_process_group {
+    // the terminal already is the controlling one of a new session
+    return Err(type_error(
+      "The pty option can't be combined with detached or newProcessGroup",
+    ));
+  }
+  let pty = Pty::open(args.pty.unwrap())?;
+  let mut command = create_command(state, args)?;
+  pty.attach(&mut command)?;
+  let mut command = tokio::process::Command::from(command);
+  command.kill_on_drop(true);
+
+  let child = command.spawn()?;
+  let pid = child.id().expect("Process ID should be set.");
+  // the terminal only reports the end of the output once the copies of its
+  // slave side held by the command are closed
+  drop(command);
+  let (output, input, pty) = pty.into_resources()?;
+
+  Ok(Child {
+    rid: state.resource_table.add(ChildResource(child)),
+    pid,
+    stdin_rid: Some(state.resource_table.add(input)),
+    stdout_rid: Some(state.resource_table.add(output)),
+    stderr_rid: None,
+    pty_rid: Some(state.resource_table.add(pty)),
   })
 }
 
+#[cfg(not(unix))]
+fn spawn_pty_child(
+  state: &mut OpState,
+  _args: SpawnArgs,
+) -> Result<Child, AnyError> {
+  super::check_unstable(state, "Deno.spawnChild.pty");
+  Err(type_error(
+    "Pseudo-terminals are not supported on this platform",
+  ))
+}
+
+#[cfg(unix)]
+#[op]
+fn op_spawn_pty_resize(
+  state: &mut OpState,
+  rid: ResourceId,
+  size: PtySize,
+) -> Result<(), AnyError> {
+  state.resource_table.get::<PtyResource>(rid)?.resize(size)
+}
+
+#[cfg(not(unix))]
+#[op]
+fn op_spawn_pty_resize() -> Result<(), AnyError> {
+  Err(type_error(
+    "Pseudo-terminals are not supported on this platform",
+  ))
+}
+
 #[op]
 async fn op_spawn_wait(
   state: Rc<RefCell<OpState>>,
@@ -235,6 +362,11 @@ fn op_spawn_sync(
   state: &mut OpState,
   args: SpawnArgs,
 ) -> Result<SpawnOutput, AnyError> {
+  if args.pty.is_some() {
+    return Err(type_error(
+      "Pseudo-terminals are not supported for this function, use 'Deno.spawnChild()' instead",
+    ));
+  }
   let stdout = matches!(args.stdio.stdout, Stdio::Piped);
   let stderr = matches!(args.stdio.stderr, Stdio::Piped);
   let output = create_command(state, args)?.output()?;
@@ -6,12 +6,25 @@ use deno_core::error::not_supported;
 use deno_core::error::resource_unavailable;
 use deno_core::error::AnyError;
 use deno_core::op;
+use deno_core::AsyncRefCell;
+use deno_core::CancelFuture;
+use deno_core::CancelHandle;
 use deno_core::Extension;
 use deno_core::OpState;
+use deno_core::RcRef;
+use deno_core::Resource;
 use deno_core::ResourceId;
 use serde::Deserialize;
 use serde::Serialize;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::fs::File as StdFile;
 use std::io::Error;
+use std::rc::Rc;
+
+#[cfg(unix)]
+use tokio::signal::unix::{signal, Signal, SignalKind};
 
 #[cfg(unix)]
 use nix::sys::termios;
@@ -49,6 +62,8 @@ pub fn init() -> Extension {
       op_set_raw::decl(),
       op_isatty::decl(),
       op_console_size::decl(),
+      op_console_size_watch_open::decl(),
+      op_console_size_watch_poll::decl(),
     ])
     .build()
 }
@@ -210,12 +225,55 @@ fn op_isatty(state: &mut OpState, rid: ResourceId) -> Result<bool, AnyError> {
   Ok(isatty)
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone, Copy, PartialEq)]
 struct ConsoleSize {
   columns: u32,
   rows: u32,
 }
 
+fn console_size(std_file: &StdFile) -> Result<ConsoleSize, AnyError> {
+  #[cfg(windows)]
+  {
+    use std::os::windows::io::AsRawHandle;
+    let handle = std_file.as_raw_handle();
+
+    unsafe {
+      let mut bufinfo: winapi::um::wincon::CONSOLE_SCREEN_BUFFER_INFO =
+        std::mem::zeroed();
+
+      if winapi::um::wincon::GetConsoleScreenBufferInfo(handle, &mut bufinfo)
+        == 0
+      {
+        return Err(Error::last_os_error().into());
+      }
+
+      Ok(ConsoleSize {
+        columns: bufinfo.dwSize.X as u32,
+        rows: bufinfo.dwSize.Y as u32,
+      })
+    }
+  }
+
+  #[cfg(unix)]
+  {
+    use std::os::unix::io::AsRawFd;
+
+    let fd = std_file.as_raw_fd();
+    unsafe {
+      let mut size: libc::winsize = std::mem::zeroed();
+      if libc::ioctl(fd, libc::TIOCGWINSZ, &mut size as *mut _) != 0 {
+        return Err(Error::last_os_error().into());
+      }
+
+      // TODO (caspervonb) return a tuple instead
+      Ok(ConsoleSize {
+        columns: size.ws_col as u32,
+        rows: size.ws_row as u32,
+      })
+    }
+  }
+}
+
 #[op]
 fn op_console_size(
   state: &mut OpState,
@@ -224,52 +282,88 @@ fn op_console_size(
   super::check_unstable(state, "Deno.consoleSize");
 
   let size = StdFileResource::with(state, rid, move |r| match r {
-    Ok(std_file) => {
-      #[cfg(windows)]
-      {
-        use std::os::windows::io::AsRawHandle;
-        let handle = std_file.as_raw_handle();
-
-        unsafe {
-          let mut bufinfo: winapi::um::wincon::CONSOLE_SCREEN_BUFFER_INFO =
-            std::mem::zeroed();
-
-          if winapi::um::wincon::GetConsoleScreenBufferInfo(
-            handle,
-            &mut bufinfo,
-          ) == 0
-          {
-            return Err(Error::last_os_error().into());
-          }
-
-          Ok(ConsoleSize {
-            columns: bufinfo.dwSize.X as u32,
-            rows: bufinfo.dwSize.Y as u32,
-          })
-        }
-      }
-
-      #[cfg(unix)]
-      {
-        use std::os::unix::io::AsRawFd;
-
-        let fd = std_file.as_raw_fd();
-        unsafe {
-          let mut size: libc::winsize = std::mem::zeroed();
-          if libc::ioctl(fd, libc::TIOCGWINSZ, &mut size as *mut _) != 0 {
-            return Err(Error::last_os_error().into());
-          }
-
-          // TODO (caspervonb) return a tuple instead
-          Ok(ConsoleSize {
-            columns: size.ws_col as u32,
-            rows: size.ws_row as u32,
-          })
-        }
-      }
-    }
+    Ok(std_file) => console_size(std_file),
     Err(_) => Err(bad_resource_id()),
   })?;
 
   Ok(size)
 }
+
+/// How often the size of a Windows console is checked for changes, as
+/// consoles only report resizes as input events, which are consumed by reads
+/// from stdin.
+#[cfg(windows)]
+const CONSOLE_SIZE_POLL_INTERVAL: std::time::Duration =
+  std::time::Duration::from_millis(200);
+
+/// Watches the size of a terminal, independently of the signal listeners of
+/// the program. On Unix it's notified of resizes with `SIGWINCH`.
+struct ConsoleSizeWatcherResource {
+  file: StdFile,
+  size: Cell<ConsoleSize>,
+  #[cfg(unix)]
+  resized: AsyncRefCell<Signal>,
+  #[cfg(windows)]
+  resized: AsyncRefCell<tokio::time::Interval>,
+  cancel: CancelHandle,
+}
+
+impl Resource for ConsoleSizeWatcherResource {
+  fn name(&self) -> Cow<str> {
+    "consoleSizeWatcher".into()
+  }
+
+  fn close(self: Rc<Self>) {
+    self.cancel.cancel();
+  }
+}
+
+#[op]
+fn op_console_size_watch_open(
+  state: &mut OpState,
+  rid: ResourceId,
+) -> Result<ResourceId, AnyError> {
+  super::check_unstable(state, "Deno.watchConsoleSize");
+
+  let file = StdFileResource::clone_file(state, rid)?;
+  let size = console_size(&file)?;
+  #[cfg(unix)]
+  let resized = signal(SignalKind::window_change())?;
+  #[cfg(windows)]
+  let resized = tokio::time::interval(CONSOLE_SIZE_POLL_INTERVAL);
+  let resource = ConsoleSizeWatcherResource {
+    file,
+    size: Cell::new(size),
+    resized: AsyncRefCell::new(resized),
+    cancel: Default::default(),
+  };
+  Ok(state.resource_table.add(resource))
+}
+
+/// Resolves with the new size of the terminal once it changes, or with
+/// `None` once the watcher is closed.
+#[op]
+async fn op_console_size_watch_poll(
+  state: Rc<RefCell<OpState>>,
+  rid: ResourceId,
+) -> Result<Option<ConsoleSize>, AnyError> {
+  let resource = state
+    .borrow_mut()
+    .resource_table
+    .get::<ConsoleSizeWatcherResource>(rid)?;
+  let mut resized = RcRef::map(&resource, |r| &r.resized).borrow_mut().await;
+  loop {
+    let cancel = RcRef::map(&resource, |r| &r.cancel);
+    #[cfg(unix)]
+    let next = resized.recv().or_cancel(cancel).await;
+    #[cfg(windows)]
+    let next = resized.tick().or_cancel(cancel).await.map(Some);
+    if !matches!(next, Ok(Some(_))) {
+      return Ok(None);
+    }
+    let size = console_size(&resource.file)?;
+    if size != resource.size.replace(size) {
+      return Ok(Some(size));
+    }
+  }
+}
@@ -1,5 +1,6 @@
 // Copyright 2018-2022 the Deno authors. All rights reserved. MIT license.
 
+use crate::ops::permissions::ParentPermissions;
 use crate::ops::TestingFeaturesEnabled;
 use crate::permissions::create_child_permissions;
 use crate::permissions::ChildPermissionsArg;
@@ -11,7 +12,9 @@ use crate::web_worker::WebWorkerHandle;
 use crate::web_worker::WebWorkerType;
 use crate::web_worker::WorkerControlEvent;
 use crate::web_worker::WorkerId;
+use crate::web_worker::WorkerLimits;
 use crate::worker::FormatJsErrorFn;
+use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::futures::future::LocalFutureObj;
 use deno_core::op;
@@ -29,6 +32,7 @@ use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::atomic::AtomicI32;
 use std::sync::Arc;
+use std::time::Duration;
 
 pub struct CreateWebWorkerArgs {
   pub name: String,
@@ -39,6 +43,7 @@ pub struct CreateWebWorkerArgs {
   pub use_deno_namespace: bool,
   pub worker_type: WebWorkerType,
   pub maybe_exit_code: Option<Arc<AtomicI32>>,
+  pub limits: WorkerLimits,
 }
 
 pub type CreateWebWorkerCb = dyn Fn(CreateWebWorkerArgs) -> (WebWorker, SendableWebWorkerHandle)
@@ -129,6 +134,7 @@ pub fn init(
 #[serde(rename_all = "camelCase")]
 pub struct CreateWorkerArgs {
   has_source_code: bool,
+  limits: Option<WorkerLimits>,
   name: Option<String>,
   permissions: Option<ChildPermissionsArg>,
   source_code: String,
@@ -169,14 +175,35 @@ fn op_create_worker(
   if args.permissions.is_some() {
     super::check_unstable(state, "Worker.deno.permissions");
   }
+  let limits = args.limits.unwrap_or_default();
+  if args.limits.is_some() {
+    super::check_unstable(state, "Worker.deno.limits");
+    if limits.memory_mb == Some(0) || limits.cpu_ms == Some(0) {
+      return Err(type_error("Worker limits must be positive"));
+    }
+    if let Some(memory_mb) = limits.memory_mb {
+      if memory_mb.checked_mul(1024 * 1024).is_none() {
+        return Err(type_error("Worker memory limit is too large"));
+      }
+    }
+  }
   let parent_permissions = state.borrow_mut::<Permissions>();
-  let worker_permissions = if let Some(child_permissions_arg) = args.permissions
-  {
-    create_child_permissions(parent_permissions, child_permissions_arg)?
-  } else {
-    parent_permissions.clone()
-  };
-  let parent_permissions = parent_permissions.clone();
+  parent_permissions.apply_revocations();
+  let mut worker_permissions =
+    if let Some(child_permissions_arg) = args.permissions {
+      create_child_permissions(parent_permissions, child_permissions_arg)?
+    } else {
+      parent_permissions.clone()
+    };
+  parent_permissions.link_child(&mut worker_permissions);
+  let mut worker_parent_permissions = parent_permissions.clone();
+  parent_permissions.link_child(&mut worker_parent_permissions);
+  let published_permissions = parent_permissions.published();
+  let mut ancestor_permissions = state
+    .try_borrow::<ParentPermissions>()
+    .map(|parent_permissions| parent_permissions.0.clone())
+    .unwrap_or_default();
+  ancestor_permissions.push(published_permissions);
   // `try_borrow` here, because worker might have been started without
   // access to `Deno` namespace.
   // TODO(bartlomieju): can a situation happen when parent doesn't
@@ -209,17 +236,23 @@ fn op_create_worker(
     //  all action done upon it should be noops
     // - newly spawned thread exits
 
-    let (worker, external_handle) =
+    let (mut worker, external_handle) =
       (create_web_worker_cb.0)(CreateWebWorkerArgs {
         name: worker_name,
         worker_id,
-        parent_permissions,
+        parent_permissions: worker_parent_permissions,
         permissions: worker_permissions,
         main_module: module_specifier.clone(),
         use_deno_namespace,
         worker_type,
         maybe_exit_code,
+        limits,
       });
+    worker
+      .js_runtime
+      .op_state()
+      .borrow_mut()
+      .put(ParentPermissions(ancestor_permissions));
 
     // Send thread safe handle from newly created worker to host thread
     handle_sender.send(Ok(external_handle)).unwrap();
@@ -239,10 +272,13 @@ fn op_create_worker(
   })?;
 
   // Receive WebWorkerHandle from newly created worker
-  let worker_handle = handle_receiver.recv().unwrap()?;
+  let worker_handle: WebWorkerHandle = handle_receiver.recv().unwrap()?.into();
+  if let Some(cpu_ms) = limits.cpu_ms {
+    worker_handle.watch_cpu_time(Duration::from_millis(cpu_ms));
+  }
 
   let worker_thread = WorkerThread {
-    worker_handle: worker_handle.into(),
+    worker_handle,
     cancel_handle: CancelHandle::new_rc(),
     ctrl_closed: false,
     message_closed: false,
@@ -5,7 +5,6 @@ use crate::fs_util::resolve_from_cwd;
 use deno_core::error::custom_error;
 use deno_core::error::uri_error;
 use deno_core::error::AnyError;
-#[cfg(test)]
 use deno_core::parking_lot::Mutex;
 use deno_core::serde::de;
 use deno_core::serde::Deserialize;
@@ -17,19 +16,32 @@ use deno_core::ModuleSpecifier;
 use deno_core::OpState;
 use log;
 use once_cell::sync::Lazy;
+use once_cell::sync::OnceCell;
+use std::collections::HashMap;
 use std::collections::HashSet;
+use std::ffi::OsString;
 use std::fmt;
 use std::hash::Hash;
-use std::path::{Path, PathBuf};
+use std::net::IpAddr;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::string::ToString;
 #[cfg(test)]
 use std::sync::atomic::AtomicBool;
-#[cfg(test)]
+use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 
 const PERMISSION_EMOJI: &str = "⚠️";
 
+/// The number of permission prompts that were answered, which tells whether a
+/// check may have changed the permissions.
+static PROMPTS: AtomicUsize = AtomicUsize::new(0);
+
 static DEBUG_LOG_ENABLED: Lazy<bool> =
   Lazy::new(|| log::log_enabled!(log::Level::Debug));
 
@@ -79,20 +91,23 @@ impl PermissionState {
   }
 
   /// Check the permission state. bool is whether a prompt was issued.
+  /// `descriptor` is what gets recorded when the prompt is answered with
+  /// "always allow".
   fn check(
     self,
     name: &str,
     info: Option<&str>,
+    descriptor: Option<&str>,
     prompt: bool,
   ) -> (Result<(), AnyError>, bool) {
-    match self {
+    let (result, prompted) = match self {
       PermissionState::Granted => {
         Self::log_perm_access(name, info);
         (Ok(()), false)
       }
       PermissionState::Prompt if prompt => {
         let msg = Self::fmt_access(name, info);
-        if permission_prompt(&msg, name) {
+        if permission_prompt(&msg, name, descriptor) {
           Self::log_perm_access(name, info);
           (Ok(()), true)
         } else {
@@ -100,10 +115,47 @@ impl PermissionState {
         }
       }
       _ => (Err(Self::error(name, info)), false),
-    }
+    };
+    if let Some(handler) = PERMISSION_AUDIT_HANDLER.get() {
+      handler(&PermissionAuditEntry {
+        timestamp: SystemTime::now()
+          .duration_since(UNIX_EPOCH)
+          .map(|duration| duration.as_millis() as u64)
+          .unwrap_or_default(),
+        name,
+        descriptor: descriptor.or(info),
+        granted: result.is_ok(),
+        prompted,
+      });
+    }
+    (result, prompted)
   }
 }
 
+/// A permission check, as passed to the handler set with
+/// `set_permission_audit_handler()`.
+#[derive(Debug, Serialize)]
+pub struct PermissionAuditEntry<'a> {
+  /// Milliseconds since the UNIX epoch.
+  pub timestamp: u64,
+  pub name: &'a str,
+  pub descriptor: Option<&'a str>,
+  pub granted: bool,
+  pub prompted: bool,
+}
+
+pub type PermissionAuditHandler =
+  Box<dyn Fn(&PermissionAuditEntry) + Send + Sync>;
+
+static PERMISSION_AUDIT_HANDLER: OnceCell<PermissionAuditHandler> =
+  OnceCell::new();
+
+/// Sets the handler every permission check is reported to. Only the first
+/// handler that is set is used.
+pub fn set_permission_audit_handler(handler: PermissionAuditHandler) {
+  let _ = PERMISSION_AUDIT_HANDLER.set(handler);
+}
+
 impl fmt::Display for PermissionState {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match self {
@@ -138,6 +190,7 @@ impl UnitPermission {
       if permission_prompt(
         &format!("access to {}", self.description),
         self.name,
+        None,
       ) {
         self.state = PermissionState::Granted;
       } else {
@@ -155,7 +208,8 @@ impl UnitPermission {
   }
 
   pub fn check(&mut self) -> Result<(), AnyError> {
-    let (result, prompted) = self.state.check(self.name, None, self.prompt);
+    let (result, prompted) =
+      self.state.check(self.name, None, None, self.prompt);
     if prompted {
       if result.is_ok() {
         self.state = PermissionState::Granted;
@@ -208,6 +262,9 @@ pub struct ReadDescriptor(pub PathBuf);
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct WriteDescriptor(pub PathBuf);
 
+/// A host and optional port. Besides a hostname or IP address, the host may
+/// be a wildcard covering all subdomains of a domain, like `*.example.com`,
+/// or an IP network in CIDR notation, like `10.0.0.0/8`.
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct NetDescriptor(pub String, pub Option<u16>);
 
@@ -217,10 +274,105 @@ impl NetDescriptor {
   }
 
   pub fn from_string(host: String) -> Self {
-    let url = url::Url::parse(&format!("http://{}", host)).unwrap();
-    let hostname = url.host_str().unwrap().to_string();
+    Self::parse(&host).unwrap()
+  }
+
+  /// Parses a host with an optional port, returning `None` if it is invalid.
+  pub fn parse(host: &str) -> Option<Self> {
+    if let Some((ip, rest)) = host.split_once('/') {
+      let (prefix_len, port) = match rest.split_once(':') {
+        Some((prefix_len, port)) => (prefix_len, Some(port.parse().ok()?)),
+        None => (rest, None),
+      };
+      let ip = ip
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+        .ok()?;
+      let prefix_len = prefix_len.parse::<u8>().ok()?;
+      let max_prefix_len = if ip.is_ipv4() { 32 } else { 128 };
+      if prefix_len > max_prefix_len {
+        return None;
+      }
+      return Some(NetDescriptor(format!("{}/{}", ip, prefix_len), port));
+    }
+    if let Some(domain) = host.strip_prefix("*.") {
+      let NetDescriptor(hostname, port) = Self::parse(domain)?;
+      if hostname.starts_with('[') || hostname.parse::<IpAddr>().is_ok() {
+        return None;
+      }
+      return Some(NetDescriptor(format!("*.{}", hostname), port));
+    }
+    // a bare IPv6 address can't be told apart from one with a port
+    if let Ok(IpAddr::V6(ip)) = host.parse::<IpAddr>() {
+      return Some(NetDescriptor(format!("[{}]", ip), None));
+    }
+    let url = url::Url::parse(&format!("http://{}/", host)).ok()?;
+    if url.path() != "/" {
+      return None;
+    }
+    Some(NetDescriptor(url.host_str()?.to_string(), url.port()))
+  }
+
+  /// Returns if the host of this descriptor covers `host`, either by being
+  /// equal to it, or by being a wildcard or network including it.
+  fn matches_host(&self, host: &str) -> bool {
+    if self.0 == host {
+      return true;
+    }
+    if let Some(domain) = self.0.strip_prefix("*.") {
+      return host.strip_suffix(domain).map_or(false, |subdomain| {
+        subdomain.len() > 1 && subdomain.ends_with('.')
+      });
+    }
+    if let Some((network, prefix_len)) = self.0.split_once('/') {
+      let (network, prefix_len) =
+        match (network.parse::<IpAddr>(), prefix_len.parse::<u32>()) {
+          (Ok(network), Ok(prefix_len)) => (network, prefix_len),
+          _ => return false,
+        };
+      // A network also covers the smaller networks within it, e.g. when a
+      // worker is granted a part of its parent's network.
+      let (ip, host_prefix_len) = match host.split_once('/') {
+        Some((ip, len)) => match len.parse::<u32>() {
+          Ok(len) => (ip, len),
+          Err(_) => return false,
+        },
+        None => (host, prefix_len),
+      };
+      let ip = ip.trim_start_matches('[').trim_end_matches(']');
+      return host_prefix_len >= prefix_len
+        && ip
+          .parse()
+          .map_or(false, |ip| ip_in_network(ip, network, prefix_len));
+    }
+    false
+  }
+
+  /// Returns if this descriptor of the granted list covers the host and
+  /// port, where a descriptor without a port covers all of them.
+  fn grants<T: AsRef<str>>(&self, host: &(T, Option<u16>)) -> bool {
+    self.matches_host(host.0.as_ref()) && (self.1.is_none() || self.1 == host.1)
+  }
+
+  /// Returns if this descriptor of the denied list covers the host and
+  /// port, where a host without a port is covered by any port.
+  fn denies<T: AsRef<str>>(&self, host: &(T, Option<u16>)) -> bool {
+    self.matches_host(host.0.as_ref()) && (host.1.is_none() || self.1 == host.1)
+  }
+}
 
-    NetDescriptor(hostname, url.port())
+fn ip_in_network(ip: IpAddr, network: IpAddr, prefix_len: u32) -> bool {
+  match (ip, network) {
+    (IpAddr::V4(ip), IpAddr::V4(network)) => {
+      let mask = u32::MAX.checked_shl(32 - prefix_len).unwrap_or(0);
+      u32::from(ip) & mask == u32::from(network) & mask
+    }
+    (IpAddr::V6(ip), IpAddr::V6(network)) => {
+      let mask = u128::MAX.checked_shl(128 - prefix_len).unwrap_or(0);
+      u128::from(ip) & mask == u128::from(network) & mask
+    }
+    _ => false,
   }
 }
 
@@ -240,6 +392,15 @@ impl EnvDescriptor {
   pub fn new(env: impl AsRef<str>) -> Self {
     Self(EnvVarName::new(env))
   }
+
+  /// Returns if this descriptor covers the variable, where a descriptor
+  /// ending with `*` (e.g. `AWS_*`) covers all variables with its prefix.
+  fn grants(&self, env: &EnvVarName) -> bool {
+    match self.as_ref().strip_suffix('*') {
+      Some(prefix) => env.as_ref().starts_with(prefix),
+      None => self.0 == *env,
+    }
+  }
 }
 
 impl AsRef<str> for EnvDescriptor {
@@ -278,6 +439,75 @@ impl ToString for RunDescriptor {
   }
 }
 
+impl RunDescriptor {
+  /// Resolves a command to the binary spawning it runs, by looking up names
+  /// in `PATH` and canonicalizing the path, so that a binary can't be reached
+  /// under another name or through a symlink. Commands that can't be found
+  /// are kept as they are.
+  fn resolve(cmd: &str) -> Self {
+    let descriptor = RunDescriptor::from_str(cmd).unwrap();
+    let path = match &descriptor {
+      RunDescriptor::Path(path) => Some(path.clone()),
+      RunDescriptor::Name(name) => find_in_path(name),
+    };
+    match path.and_then(|path| path.canonicalize().ok()) {
+      Some(path) => RunDescriptor::Path(path),
+      None => descriptor,
+    }
+  }
+}
+
+/// Finds the file a command name refers to in the directories of `PATH`,
+/// trying the extensions of `PATHEXT` on Windows.
+fn find_in_path(name: &str) -> Option<PathBuf> {
+  let paths = std::env::var_os("PATH")?;
+  let mut names = vec![name.to_string()];
+  if cfg!(windows) {
+    let extensions = std::env::var("PATHEXT")
+      .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    names.extend(
+      extensions
+        .split(';')
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| format!("{}{}", name, ext)),
+    );
+  }
+  std::env::split_paths(&paths)
+    .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+    .find(|path| path.is_file())
+}
+
+/// The argument prefixes binaries are allowed to be spawned with. Binaries
+/// without an entry can be spawned with any arguments. The binaries are
+/// resolved like spawning them does, see `RunDescriptor::resolve`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunArgsAllowlist(HashMap<RunDescriptor, Vec<Vec<String>>>);
+
+impl RunArgsAllowlist {
+  pub fn check(&self, cmd: &str, args: &[String]) -> Result<(), AnyError> {
+    let prefixes = match self.0.get(&RunDescriptor::resolve(cmd)) {
+      Some(prefixes) => prefixes,
+      None => return Ok(()),
+    };
+    if prefixes.iter().any(|prefix| args.starts_with(prefix)) {
+      return Ok(());
+    }
+    Err(custom_error(
+      "PermissionDenied",
+      format!(
+        "Requires run access to \"{} {}\", allowed argument prefixes are {}",
+        cmd,
+        args.join(" "),
+        prefixes
+          .iter()
+          .map(|prefix| format!("\"{}\"", prefix.join(" ")))
+          .collect::<Vec<_>>()
+          .join(", ")
+      ),
+    ))
+  }
+}
+
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct FfiDescriptor(pub PathBuf);
 
@@ -300,7 +530,7 @@ impl UnaryPermission<ReadDescriptor> {
         Some(path) => self
           .granted_list
           .iter()
-          .any(|path_| path.starts_with(&path_.0)),
+          .any(|path_| path_grants(&path_.0, path)),
       }
     {
       PermissionState::Granted
@@ -317,6 +547,7 @@ impl UnaryPermission<ReadDescriptor> {
         if permission_prompt(
           &format!("read access to \"{}\"", display_path.display()),
           self.name,
+          Some(&resolved_path.to_string_lossy()),
         ) {
           self.granted_list.insert(ReadDescriptor(resolved_path));
           PermissionState::Granted
@@ -334,7 +565,7 @@ impl UnaryPermission<ReadDescriptor> {
     } else {
       let state = self.query(None);
       if state == PermissionState::Prompt {
-        if permission_prompt("read access", self.name) {
+        if permission_prompt("read access", self.name, None) {
           self.granted_list.clear();
           self.global_state = PermissionState::Granted;
           PermissionState::Granted
@@ -353,7 +584,7 @@ impl UnaryPermission<ReadDescriptor> {
       let path = resolve_from_cwd(path).unwrap();
       self
         .granted_list
-        .retain(|path_| !path.starts_with(&path_.0));
+        .retain(|path_| !path_grants(&path_.0, &path));
     } else {
       self.granted_list.clear();
     }
@@ -365,9 +596,15 @@ impl UnaryPermission<ReadDescriptor> {
 
   pub fn check(&mut self, path: &Path) -> Result<(), AnyError> {
     let (resolved_path, display_path) = resolved_and_display_path(path);
+    let info = path_check_info(
+      self.granted_list.iter().map(|path_| path_.0.as_path()),
+      &resolved_path,
+      &display_path,
+    );
     let (result, prompted) = self.query(Some(&resolved_path)).check(
       self.name,
-      Some(&format!("\"{}\"", display_path.display())),
+      Some(&info),
+      Some(&resolved_path.to_string_lossy()),
       self.prompt,
     );
     if prompted {
@@ -392,6 +629,7 @@ impl UnaryPermission<ReadDescriptor> {
     let (result, prompted) = self.query(Some(&resolved_path)).check(
       self.name,
       Some(&format!("<{}>", display)),
+      None,
       self.prompt,
     );
     if prompted {
@@ -407,7 +645,9 @@ impl UnaryPermission<ReadDescriptor> {
 
   pub fn check_all(&mut self) -> Result<(), AnyError> {
     let (result, prompted) =
-      self.query(None).check(self.name, Some("all"), self.prompt);
+      self
+        .query(None)
+        .check(self.name, Some("all"), None, self.prompt);
     if prompted {
       if result.is_ok() {
         self.global_state = PermissionState::Granted;
@@ -451,7 +691,7 @@ impl UnaryPermission<WriteDescriptor> {
         Some(path) => self
           .granted_list
           .iter()
-          .any(|path_| path.starts_with(&path_.0)),
+          .any(|path_| path_grants(&path_.0, path)),
       }
     {
       PermissionState::Granted
@@ -468,6 +708,7 @@ impl UnaryPermission<WriteDescriptor> {
         if permission_prompt(
           &format!("write access to \"{}\"", display_path.display()),
           self.name,
+          Some(&resolved_path.to_string_lossy()),
         ) {
           self.granted_list.insert(WriteDescriptor(resolved_path));
           PermissionState::Granted
@@ -485,7 +726,7 @@ impl UnaryPermission<WriteDescriptor> {
     } else {
       let state = self.query(None);
       if state == PermissionState::Prompt {
-        if permission_prompt("write access", self.name) {
+        if permission_prompt("write access", self.name, None) {
           self.granted_list.clear();
           self.global_state = PermissionState::Granted;
           PermissionState::Granted
@@ -504,7 +745,7 @@ impl UnaryPermission<WriteDescriptor> {
       let path = resolve_from_cwd(path).unwrap();
       self
         .granted_list
-        .retain(|path_| !path.starts_with(&path_.0));
+        .retain(|path_| !path_grants(&path_.0, &path));
     } else {
       self.granted_list.clear();
     }
@@ -516,9 +757,15 @@ impl UnaryPermission<WriteDescriptor> {
 
   pub fn check(&mut self, path: &Path) -> Result<(), AnyError> {
     let (resolved_path, display_path) = resolved_and_display_path(path);
+    let info = path_check_info(
+      self.granted_list.iter().map(|path_| path_.0.as_path()),
+      &resolved_path,
+      &display_path,
+    );
     let (result, prompted) = self.query(Some(&resolved_path)).check(
       self.name,
-      Some(&format!("\"{}\"", display_path.display())),
+      Some(&info),
+      Some(&resolved_path.to_string_lossy()),
       self.prompt,
     );
     if prompted {
@@ -534,7 +781,9 @@ impl UnaryPermission<WriteDescriptor> {
 
   pub fn check_all(&mut self) -> Result<(), AnyError> {
     let (result, prompted) =
-      self.query(None).check(self.name, Some("all"), self.prompt);
+      self
+        .query(None)
+        .check(self.name, Some("all"), None, self.prompt);
     if prompted {
       if result.is_ok() {
         self.global_state = PermissionState::Granted;
@@ -567,26 +816,14 @@ impl UnaryPermission<NetDescriptor> {
     if self.global_state == PermissionState::Denied
       && match host.as_ref() {
         None => true,
-        Some(host) => match host.1 {
-          None => self
-            .denied_list
-            .iter()
-            .any(|host_| host.0.as_ref() == host_.0),
-          Some(_) => self.denied_list.contains(&NetDescriptor::new(host)),
-        },
+        Some(host) => self.denied_list.iter().any(|d| d.denies(host)),
       }
     {
       PermissionState::Denied
     } else if self.global_state == PermissionState::Granted
       || match host.as_ref() {
         None => false,
-        Some(host) => {
-          self.granted_list.contains(&NetDescriptor::new(&&(
-            host.0.as_ref().to_string(),
-            None,
-          )))
-            || self.granted_list.contains(&NetDescriptor::new(host))
-        }
+        Some(host) => self.granted_list.iter().any(|d| d.grants(host)),
       }
     {
       PermissionState::Granted
@@ -606,6 +843,7 @@ impl UnaryPermission<NetDescriptor> {
         if permission_prompt(
           &format!("network access to \"{}\"", host),
           self.name,
+          Some(&host.to_string()),
         ) {
           self.granted_list.insert(host);
           PermissionState::Granted
@@ -623,7 +861,7 @@ impl UnaryPermission<NetDescriptor> {
     } else {
       let state = self.query::<&str>(None);
       if state == PermissionState::Prompt {
-        if permission_prompt("network access", self.name) {
+        if permission_prompt("network access", self.name, None) {
           self.granted_list.clear();
           self.global_state = PermissionState::Granted;
           PermissionState::Granted
@@ -642,14 +880,8 @@ impl UnaryPermission<NetDescriptor> {
     host: Option<&(T, Option<u16>)>,
   ) -> PermissionState {
     if let Some(host) = host {
-      if host.1.is_some() {
-        self
-          .granted_list
-          .remove(&NetDescriptor(host.0.as_ref().to_string(), host.1));
-      }
-      self
-        .granted_list
-        .remove(&NetDescriptor(host.0.as_ref().to_string(), None));
+      // also remove the wildcards and networks covering the host
+      self.granted_list.retain(|d| !d.grants(host));
     } else {
       self.granted_list.clear();
     }
@@ -667,6 +899,7 @@ impl UnaryPermission<NetDescriptor> {
     let (result, prompted) = self.query(Some(host)).check(
       self.name,
       Some(&format!("\"{}\"", new_host)),
+      Some(&new_host.to_string()),
       self.prompt,
     );
     if prompted {
@@ -693,6 +926,7 @@ impl UnaryPermission<NetDescriptor> {
     let (result, prompted) = self.query(Some(host)).check(
       self.name,
       Some(&format!("\"{}\"", display_host)),
+      Some(&NetDescriptor::new(&host).to_string()),
       self.prompt,
     );
     if prompted {
@@ -710,7 +944,7 @@ impl UnaryPermission<NetDescriptor> {
     let (result, prompted) =
       self
         .query::<&str>(None)
-        .check(self.name, Some("all"), self.prompt);
+        .check(self.name, Some("all"), None, self.prompt);
     if prompted {
       if result.is_ok() {
         self.global_state = PermissionState::Granted;
@@ -741,14 +975,17 @@ impl UnaryPermission<EnvDescriptor> {
     if self.global_state == PermissionState::Denied
       && match env.as_ref() {
         None => true,
-        Some(env) => self.denied_list.contains(&EnvDescriptor::new(env)),
+        Some(env) => {
+          let queried = EnvDescriptor::new(env);
+          self.denied_list.iter().any(|env_| queried.grants(&env_.0))
+        }
       }
     {
       PermissionState::Denied
     } else if self.global_state == PermissionState::Granted
       || match env.as_ref() {
         None => false,
-        Some(env) => self.granted_list.contains(&EnvDescriptor::new(env)),
+        Some(env) => self.granted_list.iter().any(|env_| env_.grants(env)),
       }
     {
       PermissionState::Granted
@@ -761,7 +998,11 @@ impl UnaryPermission<EnvDescriptor> {
     if let Some(env) = env {
       let state = self.query(Some(env));
       if state == PermissionState::Prompt {
-        if permission_prompt(&format!("env access to \"{}\"", env), self.name) {
+        if permission_prompt(
+          &format!("env access to \"{}\"", env),
+          self.name,
+          Some(env),
+        ) {
           self.granted_list.insert(EnvDescriptor::new(env));
           PermissionState::Granted
         } else {
@@ -778,7 +1019,7 @@ impl UnaryPermission<EnvDescriptor> {
     } else {
       let state = self.query(None);
       if state == PermissionState::Prompt {
-        if permission_prompt("env access", self.name) {
+        if permission_prompt("env access", self.name, None) {
           self.granted_list.clear();
           self.global_state = PermissionState::Granted;
           PermissionState::Granted
@@ -794,7 +1035,8 @@ impl UnaryPermission<EnvDescriptor> {
 
   pub fn revoke(&mut self, env: Option<&str>) -> PermissionState {
     if let Some(env) = env {
-      self.granted_list.remove(&EnvDescriptor::new(env));
+      let env = EnvVarName::new(env);
+      self.granted_list.retain(|env_| !env_.grants(&env));
     } else {
       self.granted_list.clear();
     }
@@ -808,6 +1050,7 @@ impl UnaryPermission<EnvDescriptor> {
     let (result, prompted) = self.query(Some(env)).check(
       self.name,
       Some(&format!("\"{}\"", env)),
+      Some(env),
       self.prompt,
     );
     if prompted {
@@ -823,7 +1066,9 @@ impl UnaryPermission<EnvDescriptor> {
 
   pub fn check_all(&mut self) -> Result<(), AnyError> {
     let (result, prompted) =
-      self.query(None).check(self.name, Some("all"), self.prompt);
+      self
+        .query(None)
+        .check(self.name, Some("all"), None, self.prompt);
     if prompted {
       if result.is_ok() {
         self.global_state = PermissionState::Granted;
@@ -877,7 +1122,11 @@ impl UnaryPermission<RunDescriptor> {
     if let Some(cmd) = cmd {
       let state = self.query(Some(cmd));
       if state == PermissionState::Prompt {
-        if permission_prompt(&format!("run access to \"{}\"", cmd), self.name) {
+        if permission_prompt(
+          &format!("run access to \"{}\"", cmd),
+          self.name,
+          Some(cmd),
+        ) {
           self
             .granted_list
             .insert(RunDescriptor::from_str(cmd).unwrap());
@@ -900,7 +1149,7 @@ impl UnaryPermission<RunDescriptor> {
     } else {
       let state = self.query(None);
       if state == PermissionState::Prompt {
-        if permission_prompt("run access", self.name) {
+        if permission_prompt("run access", self.name, None) {
           self.granted_list.clear();
           self.global_state = PermissionState::Granted;
           PermissionState::Granted
@@ -932,6 +1181,7 @@ impl UnaryPermission<RunDescriptor> {
     let (result, prompted) = self.query(Some(cmd)).check(
       self.name,
       Some(&format!("\"{}\"", cmd)),
+      Some(cmd),
       self.prompt,
     );
     if prompted {
@@ -951,7 +1201,9 @@ impl UnaryPermission<RunDescriptor> {
 
   pub fn check_all(&mut self) -> Result<(), AnyError> {
     let (result, prompted) =
-      self.query(None).check(self.name, Some("all"), self.prompt);
+      self
+        .query(None)
+        .check(self.name, Some("all"), None, self.prompt);
     if prompted {
       if result.is_ok() {
         self.global_state = PermissionState::Granted;
@@ -1006,6 +1258,7 @@ impl UnaryPermission<FfiDescriptor> {
         if permission_prompt(
           &format!("ffi access to \"{}\"", display_path.display()),
           self.name,
+          Some(&resolved_path.to_string_lossy()),
         ) {
           self.granted_list.insert(FfiDescriptor(resolved_path));
           PermissionState::Granted
@@ -1023,7 +1276,7 @@ impl UnaryPermission<FfiDescriptor> {
     } else {
       let state = self.query(None);
       if state == PermissionState::Prompt {
-        if permission_prompt("ffi access", self.name) {
+        if permission_prompt("ffi access", self.name, None) {
           self.granted_list.clear();
           self.global_state = PermissionState::Granted;
           PermissionState::Granted
@@ -1056,6 +1309,7 @@ impl UnaryPermission<FfiDescriptor> {
       let (result, prompted) = self.query(Some(&resolved_path)).check(
         self.name,
         Some(&format!("\"{}\"", display_path.display())),
+        Some(&resolved_path.to_string_lossy()),
         self.prompt,
       );
 
@@ -1071,7 +1325,7 @@ impl UnaryPermission<FfiDescriptor> {
       result
     } else {
       let (result, prompted) =
-        self.query(None).check(self.name, None, self.prompt);
+        self.query(None).check(self.name, None, None, self.prompt);
 
       if prompted {
         if result.is_ok() {
@@ -1087,7 +1341,9 @@ impl UnaryPermission<FfiDescriptor> {
 
   pub fn check_all(&mut self) -> Result<(), AnyError> {
     let (result, prompted) =
-      self.query(None).check(self.name, Some("all"), self.prompt);
+      self
+        .query(None)
+        .check(self.name, Some("all"), None, self.prompt);
     if prompted {
       if result.is_ok() {
         self.global_state = PermissionState::Granted;
@@ -1112,15 +1368,63 @@ impl Default for UnaryPermission<FfiDescriptor> {
   }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// A revocation made with the permissions of a thread, which the web workers
+/// it created apply to their own permissions.
+pub type Revocation = Arc<dyn Fn(&mut Permissions) + Send + Sync>;
+
+type RevocationLog = Arc<Mutex<Vec<Revocation>>>;
+
+/// Links the permissions of a web worker to those of its ancestors. The
+/// revocations they make are applied when the worker next checks or queries
+/// a permission, even if the event loop of the worker or of the workers in
+/// between isn't polled in the meantime.
+#[derive(Clone, Default)]
+pub struct Revocations {
+  /// The revocations made with these permissions, shared with the web
+  /// workers created with them.
+  log: RevocationLog,
+  /// The logs of the ancestors, with how many of their revocations were
+  /// applied.
+  ancestors: Vec<(RevocationLog, usize)>,
+  /// Where these permissions are published for the web workers created with
+  /// them.
+  published: PublishedPermissions,
+}
+
+/// The permissions of a thread as it last published them for the web workers
+/// it created, which can't request permissions beyond them. They are
+/// published again whenever they change by a request, a revocation or an
+/// answered prompt.
+#[derive(Clone, Default)]
+pub struct PublishedPermissions(Arc<Mutex<Option<Permissions>>>);
+
+impl PublishedPermissions {
+  /// Gets the published permissions, with the revocations made by their
+  /// ancestors since applied.
+  pub fn get(&self) -> Option<Permissions> {
+    let mut permissions = self.0.lock().clone()?;
+    permissions.apply_revocations();
+    Some(permissions)
+  }
+}
+
+impl fmt::Debug for Revocations {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("Revocations").finish_non_exhaustive()
+  }
+}
+
+#[derive(Clone, Debug)]
 pub struct Permissions {
   pub read: UnaryPermission<ReadDescriptor>,
   pub write: UnaryPermission<WriteDescriptor>,
   pub net: UnaryPermission<NetDescriptor>,
   pub env: UnaryPermission<EnvDescriptor>,
   pub run: UnaryPermission<RunDescriptor>,
+  pub run_args: RunArgsAllowlist,
   pub ffi: UnaryPermission<FfiDescriptor>,
   pub hrtime: UnitPermission,
+  revocations: Revocations,
 }
 
 impl Default for Permissions {
@@ -1131,8 +1435,10 @@ impl Default for Permissions {
       net: Permissions::new_net(&None, false),
       env: Permissions::new_env(&None, false),
       run: Permissions::new_run(&None, false),
+      run_args: Default::default(),
       ffi: Permissions::new_ffi(&None, false),
       hrtime: Permissions::new_hrtime(false),
+      revocations: Default::default(),
     }
   }
 }
@@ -1145,6 +1451,8 @@ pub struct PermissionsOptions {
   pub allow_ffi: Option<Vec<PathBuf>>,
   pub allow_read: Option<Vec<PathBuf>>,
   pub allow_run: Option<Vec<String>>,
+  /// The argument prefixes allowed per binary, see `RunArgsAllowlist`.
+  pub allow_run_args: Option<HashMap<String, Vec<Vec<String>>>>,
   pub allow_write: Option<Vec<PathBuf>>,
   pub prompt: bool,
 }
@@ -1227,6 +1535,18 @@ impl Permissions {
     }
   }
 
+  pub fn new_run_args(
+    state: &Option<HashMap<String, Vec<Vec<String>>>>,
+  ) -> RunArgsAllowlist {
+    RunArgsAllowlist(
+      state
+        .iter()
+        .flatten()
+        .map(|(cmd, prefixes)| (RunDescriptor::resolve(cmd), prefixes.clone()))
+        .collect(),
+    )
+  }
+
   pub fn new_ffi(
     state: &Option<Vec<PathBuf>>,
     prompt: bool,
@@ -1255,8 +1575,10 @@ impl Permissions {
       net: Permissions::new_net(&opts.allow_net, opts.prompt),
       env: Permissions::new_env(&opts.allow_env, opts.prompt),
       run: Permissions::new_run(&opts.allow_run, opts.prompt),
+      run_args: Permissions::new_run_args(&opts.allow_run_args),
       ffi: Permissions::new_ffi(&opts.allow_ffi, opts.prompt),
       hrtime: Permissions::new_hrtime(opts.allow_hrtime),
+      revocations: Default::default(),
     }
   }
 
@@ -1267,20 +1589,71 @@ impl Permissions {
       net: Permissions::new_net(&Some(vec![]), false),
       env: Permissions::new_env(&Some(vec![]), false),
       run: Permissions::new_run(&Some(vec![]), false),
+      run_args: Default::default(),
       ffi: Permissions::new_ffi(&Some(vec![]), false),
       hrtime: Permissions::new_hrtime(true),
+      revocations: Default::default(),
     }
   }
 
+  /// Checks run access to `cmd` and, if argument prefixes are configured for
+  /// it, that `args` start with one of them.
+  pub fn check_run(
+    &mut self,
+    cmd: &str,
+    args: &[String],
+  ) -> Result<(), AnyError> {
+    self.checked(|perms| {
+      perms.run.check(cmd)?;
+      perms.run_args.check(cmd, args)
+    })
+  }
+
+  pub fn check_run_all(&mut self) -> Result<(), AnyError> {
+    self.checked(|perms| perms.run.check_all())
+  }
+
+  pub fn check_read(&mut self, path: &Path) -> Result<(), AnyError> {
+    self.checked(|perms| perms.read.check(path))
+  }
+
+  pub fn check_read_blind(
+    &mut self,
+    path: &Path,
+    display: &str,
+  ) -> Result<(), AnyError> {
+    self.checked(|perms| perms.read.check_blind(path, display))
+  }
+
+  pub fn check_read_all(&mut self) -> Result<(), AnyError> {
+    self.checked(|perms| perms.read.check_all())
+  }
+
+  pub fn check_write(&mut self, path: &Path) -> Result<(), AnyError> {
+    self.checked(|perms| perms.write.check(path))
+  }
+
+  pub fn check_write_all(&mut self) -> Result<(), AnyError> {
+    self.checked(|perms| perms.write.check_all())
+  }
+
+  pub fn check_env(&mut self, env: &str) -> Result<(), AnyError> {
+    self.checked(|perms| perms.env.check(env))
+  }
+
+  pub fn check_env_all(&mut self) -> Result<(), AnyError> {
+    self.checked(|perms| perms.env.check_all())
+  }
+
   /// A helper function that determines if the module specifier is a local or
   /// remote, and performs a read or net check for the specifier.
   pub fn check_specifier(
     &mut self,
     specifier: &ModuleSpecifier,
   ) -> Result<(), AnyError> {
-    match specifier.scheme() {
+    self.checked(|perms| match specifier.scheme() {
       "file" => match specifier.to_file_path() {
-        Ok(path) => self.read.check(&path),
+        Ok(path) => perms.read.check(&path),
         Err(_) => Err(uri_error(format!(
           "Invalid file path.\n  Specifier: {}",
           specifier
@@ -1288,7 +1661,78 @@ impl Permissions {
       },
       "data" => Ok(()),
       "blob" => Ok(()),
-      _ => self.net.check_url(specifier),
+      _ => perms.net.check_url(specifier),
+    })
+  }
+
+  /// Links `child`, the permissions of a web worker created with these
+  /// permissions, to them and to their ancestors, so that it applies the
+  /// revocations made with them from now on.
+  pub fn link_child(&self, child: &mut Permissions) {
+    let mut ancestors = self.revocations.ancestors.clone();
+    let log = self.revocations.log.clone();
+    let applied = log.lock().len();
+    ancestors.push((log, applied));
+    child.revocations = Revocations {
+      log: Default::default(),
+      ancestors,
+      published: Default::default(),
+    };
+  }
+
+  /// Publishes these permissions for a web worker created with them, and
+  /// gets the handle it checks its requests against.
+  pub fn published(&self) -> PublishedPermissions {
+    let published = self.revocations.published.clone();
+    *published.0.lock() = Some(self.to_published());
+    published
+  }
+
+  /// Publishes these permissions again, if a web worker was created with
+  /// them.
+  pub fn publish(&self) {
+    let published = &self.revocations.published;
+    if Arc::strong_count(&published.0) > 1 {
+      *published.0.lock() = Some(self.to_published());
+    }
+  }
+
+  fn to_published(&self) -> Permissions {
+    let mut permissions = self.clone();
+    permissions.revocations.published = Default::default();
+    permissions
+  }
+
+  /// Runs a check of these permissions once the revocations of their
+  /// ancestors are applied, and publishes them again if it prompted.
+  fn checked<T>(&mut self, check: impl FnOnce(&mut Self) -> T) -> T {
+    self.apply_revocations();
+    let prompts = PROMPTS.load(Ordering::SeqCst);
+    let result = check(self);
+    if PROMPTS.load(Ordering::SeqCst) != prompts {
+      self.publish();
+    }
+    result
+  }
+
+  /// Revokes permissions in the web workers created with these permissions,
+  /// directly or not, once they next check or query them. The revocation is
+  /// expected to be applied to these permissions already.
+  pub fn revoke_in_children(&self, revocation: Revocation) {
+    self.revocations.log.lock().push(revocation);
+  }
+
+  /// Applies the revocations made by the ancestors of a web worker since
+  /// these permissions last checked or queried.
+  pub fn apply_revocations(&mut self) {
+    let mut revocations = vec![];
+    for (log, applied) in &mut self.revocations.ancestors {
+      let log = log.lock();
+      revocations.extend_from_slice(&log[*applied..]);
+      *applied = log.len();
+    }
+    for revocation in revocations {
+      revocation(self);
     }
   }
 }
@@ -1298,31 +1742,31 @@ impl deno_net::NetPermissions for Permissions {
     &mut self,
     host: &(T, Option<u16>),
   ) -> Result<(), AnyError> {
-    self.net.check(host)
+    self.checked(|perms| perms.net.check(host))
   }
 
   fn check_read(&mut self, path: &Path) -> Result<(), AnyError> {
-    self.read.check(path)
+    Permissions::check_read(self, path)
   }
 
   fn check_write(&mut self, path: &Path) -> Result<(), AnyError> {
-    self.write.check(path)
+    Permissions::check_write(self, path)
   }
 }
 
 impl deno_fetch::FetchPermissions for Permissions {
   fn check_net_url(&mut self, url: &url::Url) -> Result<(), AnyError> {
-    self.net.check_url(url)
+    self.checked(|perms| perms.net.check_url(url))
   }
 
   fn check_read(&mut self, path: &Path) -> Result<(), AnyError> {
-    self.read.check(path)
+    Permissions::check_read(self, path)
   }
 }
 
 impl deno_web::TimersPermission for Permissions {
   fn allow_hrtime(&mut self) -> bool {
-    self.hrtime.check().is_ok()
+    self.checked(|perms| perms.hrtime.check().is_ok())
   }
 
   fn check_unstable(&self, state: &OpState, api_name: &'static str) {
@@ -1332,13 +1776,13 @@ impl deno_web::TimersPermission for Permissions {
 
 impl deno_websocket::WebSocketPermissions for Permissions {
   fn check_net_url(&mut self, url: &url::Url) -> Result<(), AnyError> {
-    self.net.check_url(url)
+    self.checked(|perms| perms.net.check_url(url))
   }
 }
 
 impl deno_ffi::FfiPermissions for Permissions {
   fn check(&mut self, path: Option<&Path>) -> Result<(), AnyError> {
-    self.ffi.check(path)
+    self.checked(|perms| perms.ffi.check(path))
   }
 }
 
@@ -1373,9 +1817,8 @@ pub fn resolve_read_allowlist(
 ) -> HashSet<ReadDescriptor> {
   if let Some(v) = allow {
     v.iter()
-      .map(|raw_path| {
-        ReadDescriptor(resolve_from_cwd(Path::new(&raw_path)).unwrap())
-      })
+      .flat_map(|raw_path| resolve_allowlist_path(raw_path))
+      .map(ReadDescriptor)
       .collect()
   } else {
     HashSet::new()
@@ -1387,9 +1830,8 @@ pub fn resolve_write_allowlist(
 ) -> HashSet<WriteDescriptor> {
   if let Some(v) = allow {
     v.iter()
-      .map(|raw_path| {
-        WriteDescriptor(resolve_from_cwd(Path::new(&raw_path)).unwrap())
-      })
+      .flat_map(|raw_path| resolve_allowlist_path(raw_path))
+      .map(WriteDescriptor)
       .collect()
   } else {
     HashSet::new()
@@ -1410,6 +1852,167 @@ pub fn resolve_ffi_allowlist(
   }
 }
 
+/// The prefix of a read or write allowlist entry that is only a glob pattern,
+/// e.g. `glob:./data/*.csv`. The granted patterns are stored with it, which
+/// tells them apart from the resolved paths being checked, as those are
+/// always absolute.
+pub const GLOB_PREFIX: &str = "glob:";
+
+/// Resolves an entry of a read or write allowlist from CWD. An entry with
+/// glob characters, e.g. `./data/**/*.csv`, grants the paths it matches as
+/// a pattern and also the path spelled the same, so that a directory like
+/// `./a[1]/` still grants the files within it. An entry prefixed with
+/// `GLOB_PREFIX` is only taken as a pattern.
+fn resolve_allowlist_path(raw_path: &Path) -> Vec<PathBuf> {
+  let (path, literal) = match raw_path.to_str() {
+    Some(raw) => match raw.strip_prefix(GLOB_PREFIX) {
+      Some(pattern) => (resolve_from_cwd(Path::new(pattern)).unwrap(), false),
+      None => (resolve_from_cwd(raw_path).unwrap(), true),
+    },
+    None => (resolve_from_cwd(raw_path).unwrap(), true),
+  };
+  if !is_glob(&path) {
+    return vec![path];
+  }
+  let mut pattern = OsString::from(GLOB_PREFIX);
+  pattern.push(&path);
+  if literal {
+    vec![path, PathBuf::from(pattern)]
+  } else {
+    vec![PathBuf::from(pattern)]
+  }
+}
+
+fn is_glob(path: &Path) -> bool {
+  path.to_string_lossy().contains(&['*', '?', '['][..])
+}
+
+/// The glob pattern of a granted read or write path, if it is one.
+fn glob_pattern(granted: &Path) -> Option<&Path> {
+  granted.to_str()?.strip_prefix(GLOB_PREFIX).map(Path::new)
+}
+
+/// Whether a granted read or write path grants access to the resolved
+/// `path`. A plain path grants itself and everything within it, while a
+/// glob pattern only grants the paths it matches.
+fn path_grants(granted: &Path, path: &Path) -> bool {
+  match glob_pattern(granted) {
+    Some(pattern) => glob_matches(pattern, path),
+    None => path.starts_with(granted),
+  }
+}
+
+/// Whether a granted read or write path grants every path a glob `pattern`
+/// matches, as it does when the pattern is within a plain path without glob
+/// characters.
+fn path_grants_pattern(granted: &Path, pattern: &Path) -> bool {
+  match glob_pattern(granted) {
+    Some(granted_pattern) => granted_pattern == pattern,
+    None => !is_glob(granted) && pattern.starts_with(granted),
+  }
+}
+
+/// Matches `path` against a glob `pattern` component by component. `**`
+/// matches any number of components, while `*`, `?` and `[...]` match within
+/// a single component.
+fn glob_matches(pattern: &Path, path: &Path) -> bool {
+  let pattern = pattern.components().collect::<Vec<_>>();
+  let path = path.components().collect::<Vec<_>>();
+  components_match(&pattern, &path)
+}
+
+fn components_match(pattern: &[Component], path: &[Component]) -> bool {
+  match pattern.split_first() {
+    None => path.is_empty(),
+    Some((Component::Normal(name), rest)) if *name == "**" => {
+      (0..=path.len()).any(|i| components_match(rest, &path[i..]))
+    }
+    Some((Component::Normal(name), rest)) => match path.split_first() {
+      Some((Component::Normal(path_name), path_rest)) => {
+        let name = name.to_string_lossy().chars().collect::<Vec<_>>();
+        let path_name = path_name.to_string_lossy().chars().collect::<Vec<_>>();
+        wildcard_matches(&name, &path_name) && components_match(rest, path_rest)
+      }
+      _ => false,
+    },
+    Some((component, rest)) => match path.split_first() {
+      Some((path_component, path_rest)) => {
+        component == path_component && components_match(rest, path_rest)
+      }
+      None => false,
+    },
+  }
+}
+
+fn wildcard_matches(pattern: &[char], name: &[char]) -> bool {
+  match pattern.split_first() {
+    None => name.is_empty(),
+    Some((&'*', rest)) => {
+      (0..=name.len()).any(|i| wildcard_matches(rest, &name[i..]))
+    }
+    Some((&'?', rest)) => {
+      !name.is_empty() && wildcard_matches(rest, &name[1..])
+    }
+    Some((&'[', rest)) if rest.contains(&']') => {
+      let end = rest.iter().position(|c| *c == ']').unwrap();
+      let (class, rest) = (&rest[..end], &rest[end + 1..]);
+      let (negated, class) = match class.split_first() {
+        Some((&'!', class)) | Some((&'^', class)) => (true, class),
+        _ => (false, class),
+      };
+      match name.split_first() {
+        Some((c, name_rest)) => {
+          class_contains(class, *c) != negated
+            && wildcard_matches(rest, name_rest)
+        }
+        None => false,
+      }
+    }
+    Some((c, rest)) => {
+      name.first() == Some(c) && wildcard_matches(rest, &name[1..])
+    }
+  }
+}
+
+/// Whether a `[...]` character class, e.g. `a-z0-9_`, contains `c`.
+fn class_contains(class: &[char], c: char) -> bool {
+  let mut i = 0;
+  while i < class.len() {
+    if i + 2 < class.len() && class[i + 1] == '-' {
+      if class[i] <= c && c <= class[i + 2] {
+        return true;
+      }
+      i += 3;
+    } else {
+      if class[i] == c {
+        return true;
+      }
+      i += 1;
+    }
+  }
+  false
+}
+
+/// The info of a path check, which names the glob pattern granting the path
+/// if there is one.
+fn path_check_info<'a>(
+  mut granted: impl Iterator<Item = &'a Path>,
+  resolved_path: &Path,
+  display_path: &Path,
+) -> String {
+  let pattern = granted.find_map(|granted| {
+    glob_pattern(granted).filter(|pattern| glob_matches(pattern, resolved_path))
+  });
+  match pattern {
+    Some(pattern) => format!(
+      "\"{}\" (matching \"{}\")",
+      display_path.display(),
+      pattern.display()
+    ),
+    None => format!("\"{}\"", display_path.display()),
+  }
+}
+
 /// Arbitrary helper. Resolves the path from CWD, and also gets a path that
 /// can be displayed without leaking the CWD when not allowed.
 fn resolved_and_display_path(path: &Path) -> (PathBuf, PathBuf) {
@@ -1418,13 +2021,26 @@ fn resolved_and_display_path(path: &Path) -> (PathBuf, PathBuf) {
   (resolved_path, display_path)
 }
 
-fn escalation_error() -> AnyError {
+pub(crate) fn escalation_error() -> AnyError {
   custom_error(
     "PermissionDenied",
     "Can't escalate parent thread permissions",
   )
 }
 
+/// The error for a worker or test requesting access which its parent thread
+/// doesn't have, naming the access that would be escalated.
+fn child_escalation_error(name: &str, descriptor: Option<&str>) -> AnyError {
+  let info = descriptor.map(|descriptor| format!("\"{}\"", descriptor));
+  custom_error(
+    "PermissionDenied",
+    format!(
+      "Can't escalate parent thread permissions: {}",
+      PermissionState::fmt_access(name, info.as_deref())
+    ),
+  )
+}
+
 #[derive(Debug, PartialEq)]
 pub enum ChildUnitPermissionArg {
   Inherit,
@@ -1680,7 +2296,7 @@ pub fn create_child_permissions(
     }
     ChildUnaryPermissionArg::Granted => {
       if main_perms.env.check_all().is_err() {
-        return Err(escalation_error());
+        return Err(child_escalation_error("env", None));
       }
       worker_perms.env.global_state = PermissionState::Granted;
     }
@@ -1688,13 +2304,10 @@ pub fn create_child_permissions(
     ChildUnaryPermissionArg::GrantedList(granted_list) => {
       worker_perms.env.granted_list =
         Permissions::new_env(&Some(granted_list), false).granted_list;
-      if !worker_perms
-        .env
-        .granted_list
-        .iter()
-        .all(|desc| main_perms.env.check(desc.as_ref()).is_ok())
-      {
-        return Err(escalation_error());
+      for desc in &worker_perms.env.granted_list {
+        if main_perms.env.check(desc.as_ref()).is_err() {
+          return Err(child_escalation_error("env", Some(desc.as_ref())));
+        }
       }
     }
   }
@@ -1709,7 +2322,7 @@ pub fn create_child_permissions(
     }
     ChildUnitPermissionArg::Granted => {
       if main_perms.hrtime.check().is_err() {
-        return Err(escalation_error());
+        return Err(child_escalation_error("hrtime", None));
       }
       worker_perms.hrtime.state = PermissionState::Granted;
     }
@@ -1725,7 +2338,7 @@ pub fn create_child_permissions(
     }
     ChildUnaryPermissionArg::Granted => {
       if main_perms.net.check_all().is_err() {
-        return Err(escalation_error());
+        return Err(child_escalation_error("net", None));
       }
       worker_perms.net.global_state = PermissionState::Granted;
     }
@@ -1733,13 +2346,10 @@ pub fn create_child_permissions(
     ChildUnaryPermissionArg::GrantedList(granted_list) => {
       worker_perms.net.granted_list =
         Permissions::new_net(&Some(granted_list), false).granted_list;
-      if !worker_perms
-        .net
-        .granted_list
-        .iter()
-        .all(|desc| main_perms.net.check(&(&desc.0, desc.1)).is_ok())
-      {
-        return Err(escalation_error());
+      for desc in &worker_perms.net.granted_list {
+        if main_perms.net.check(&(&desc.0, desc.1)).is_err() {
+          return Err(child_escalation_error("net", Some(&desc.to_string())));
+        }
       }
     }
   }
@@ -1754,7 +2364,7 @@ pub fn create_child_permissions(
     }
     ChildUnaryPermissionArg::Granted => {
       if main_perms.ffi.check_all().is_err() {
-        return Err(escalation_error());
+        return Err(child_escalation_error("ffi", None));
       }
       worker_perms.ffi.global_state = PermissionState::Granted;
     }
@@ -1765,13 +2375,11 @@ pub fn create_child_permissions(
         false,
       )
       .granted_list;
-      if !worker_perms
-        .ffi
-        .granted_list
-        .iter()
-        .all(|desc| main_perms.ffi.check(Some(&desc.0)).is_ok())
-      {
-        return Err(escalation_error());
+      for desc in &worker_perms.ffi.granted_list {
+        if main_perms.ffi.check(Some(&desc.0)).is_err() {
+          let path = desc.0.to_string_lossy();
+          return Err(child_escalation_error("ffi", Some(&path)));
+        }
       }
     }
   }
@@ -1786,7 +2394,7 @@ pub fn create_child_permissions(
     }
     ChildUnaryPermissionArg::Granted => {
       if main_perms.read.check_all().is_err() {
-        return Err(escalation_error());
+        return Err(child_escalation_error("read", None));
       }
       worker_perms.read.global_state = PermissionState::Granted;
     }
@@ -1797,13 +2405,22 @@ pub fn create_child_permissions(
         false,
       )
       .granted_list;
-      if !worker_perms
-        .read
-        .granted_list
-        .iter()
-        .all(|desc| main_perms.read.check(&desc.0).is_ok())
-      {
-        return Err(escalation_error());
+      for desc in &worker_perms.read.granted_list {
+        let granted = match glob_pattern(&desc.0) {
+          Some(pattern) => {
+            main_perms.read.global_state == PermissionState::Granted
+              || main_perms
+                .read
+                .granted_list
+                .iter()
+                .any(|path| path_grants_pattern(&path.0, pattern))
+          }
+          None => main_perms.read.check(&desc.0).is_ok(),
+        };
+        if !granted {
+          let path = desc.0.to_string_lossy();
+          return Err(child_escalation_error("read", Some(&path)));
+        }
       }
     }
   }
@@ -1818,7 +2435,7 @@ pub fn create_child_permissions(
     }
     ChildUnaryPermissionArg::Granted => {
       if main_perms.run.check_all().is_err() {
-        return Err(escalation_error());
+        return Err(child_escalation_error("run", None));
       }
       worker_perms.run.global_state = PermissionState::Granted;
     }
@@ -1826,13 +2443,10 @@ pub fn create_child_permissions(
     ChildUnaryPermissionArg::GrantedList(granted_list) => {
       worker_perms.run.granted_list =
         Permissions::new_run(&Some(granted_list), false).granted_list;
-      if !worker_perms
-        .run
-        .granted_list
-        .iter()
-        .all(|desc| main_perms.run.check(&desc.to_string()).is_ok())
-      {
-        return Err(escalation_error());
+      for desc in &worker_perms.run.granted_list {
+        if main_perms.run.check(&desc.to_string()).is_err() {
+          return Err(child_escalation_error("run", Some(&desc.to_string())));
+        }
       }
     }
   }
@@ -1841,13 +2455,14 @@ pub fn create_child_permissions(
     worker_perms.run.global_state = PermissionState::Denied;
   }
   worker_perms.run.prompt = main_perms.run.prompt;
+  worker_perms.run_args = main_perms.run_args.clone();
   match child_permissions_arg.write {
     ChildUnaryPermissionArg::Inherit => {
       worker_perms.write = main_perms.write.clone();
     }
     ChildUnaryPermissionArg::Granted => {
       if main_perms.write.check_all().is_err() {
-        return Err(escalation_error());
+        return Err(child_escalation_error("write", None));
       }
       worker_perms.write.global_state = PermissionState::Granted;
     }
@@ -1858,13 +2473,22 @@ pub fn create_child_permissions(
         false,
       )
       .granted_list;
-      if !worker_perms
-        .write
-        .granted_list
-        .iter()
-        .all(|desc| main_perms.write.check(&desc.0).is_ok())
-      {
-        return Err(escalation_error());
+      for desc in &worker_perms.write.granted_list {
+        let granted = match glob_pattern(&desc.0) {
+          Some(pattern) => {
+            main_perms.write.global_state == PermissionState::Granted
+              || main_perms
+                .write
+                .granted_list
+                .iter()
+                .any(|path| path_grants_pattern(&path.0, pattern))
+          }
+          None => main_perms.write.check(&desc.0).is_ok(),
+        };
+        if !granted {
+          let path = desc.0.to_string_lossy();
+          return Err(child_escalation_error("write", Some(&path)));
+        }
       }
     }
   }
@@ -1876,13 +2500,31 @@ pub fn create_child_permissions(
   Ok(worker_perms)
 }
 
+/// Records a permission granted with the "always allow" prompt answer. It is
+/// called with the permission name and the descriptor, e.g. `"read"` and
+/// `"/home/user/project/data.txt"`.
+pub type AlwaysAllowHandler = Box<dyn Fn(&str, &str) + Send + Sync>;
+
+static ALWAYS_ALLOW_HANDLER: OnceCell<AlwaysAllowHandler> = OnceCell::new();
+
+/// Enables the "always allow" answer of permission prompts. Only the first
+/// handler that is set is used.
+pub fn set_always_allow_handler(handler: AlwaysAllowHandler) {
+  let _ = ALWAYS_ALLOW_HANDLER.set(handler);
+}
+
 /// Shows the permission prompt and returns the answer according to the user input.
 /// This loops until the user gives the proper input.
 #[cfg(not(test))]
-fn permission_prompt(message: &str, name: &str) -> bool {
+fn permission_prompt(
+  message: &str,
+  name: &str,
+  descriptor: Option<&str>,
+) -> bool {
   if !atty::is(atty::Stream::Stdin) || !atty::is(atty::Stream::Stderr) {
     return false;
   };
+  PROMPTS.fetch_add(1, Ordering::SeqCst);
 
   #[cfg(unix)]
   fn clear_stdin() -> Result<(), AnyError> {
@@ -1999,7 +2641,15 @@ fn permission_prompt(message: &str, name: &str) -> bool {
     return false; // don't grant permission if this fails
   }
 
-  let opts = "[y/n (y = yes allow, n = no deny)] ";
+  let always_allow = match (ALWAYS_ALLOW_HANDLER.get(), descriptor) {
+    (Some(handler), Some(descriptor)) => Some((handler, descriptor)),
+    _ => None,
+  };
+  let opts = if always_allow.is_some() {
+    "[y/n/A (y = yes allow, n = no deny, A = allow always)] "
+  } else {
+    "[y/n (y = yes allow, n = no deny)] "
+  };
   let msg = format!(
     "{}  ️Deno requests {}. Run again with --allow-{} to bypass this prompt.\n   Allow? {} ",
     PERMISSION_EMOJI, message, name, opts
@@ -2017,9 +2667,13 @@ fn permission_prompt(message: &str, name: &str) -> bool {
       None => return false,
       Some(v) => v,
     };
-    match ch.to_ascii_lowercase() {
-      'y' => return true,
-      'n' => return false,
+    match (ch, always_allow) {
+      ('A', Some((handler, descriptor))) => {
+        handler(name, descriptor);
+        return true;
+      }
+      ('y' | 'Y', _) => return true,
+      ('n' | 'N', _) => return false,
       _ => {
         // If we don't get a recognized option try again.
         let msg_again = format!("Unrecognized option '{}' {}", ch, opts);
@@ -2032,7 +2686,12 @@ fn permission_prompt(message: &str, name: &str) -> bool {
 // When testing, permission prompt returns the value of STUB_PROMPT_VALUE
 // which we set from the test functions.
 #[cfg(test)]
-fn permission_prompt(_message: &str, _flag: &str) -> bool {
+fn permission_prompt(
+  _message: &str,
+  _flag: &str,
+  _descriptor: Option<&str>,
+) -> bool {
+  PROMPTS.fetch_add(1, Ordering::SeqCst);
   STUB_PROMPT_VALUE.load(Ordering::SeqCst)
 }
 
@@ -2065,6 +2724,32 @@ mod tests {
       ($($x:expr),*) => (vec![$($x.to_string()),*]);
   }
 
+  // The accesses granted by permissions, which are compared instead of the
+  // permissions themselves.
+  fn grants(
+    perms: &Permissions,
+  ) -> (
+    &UnaryPermission<ReadDescriptor>,
+    &UnaryPermission<WriteDescriptor>,
+    &UnaryPermission<NetDescriptor>,
+    &UnaryPermission<EnvDescriptor>,
+    &UnaryPermission<RunDescriptor>,
+    &RunArgsAllowlist,
+    &UnaryPermission<FfiDescriptor>,
+    &UnitPermission,
+  ) {
+    (
+      &perms.read,
+      &perms.write,
+      &perms.net,
+      &perms.env,
+      &perms.run,
+      &perms.run_args,
+      &perms.ffi,
+      &perms.hrtime,
+    )
+  }
+
   #[test]
   fn check_paths() {
     let allowlist = vec![
@@ -2131,6 +2816,79 @@ mod tests {
     assert!(perms.write.check(Path::new("/a/b")).is_err());
   }
 
+  #[test]
+  fn check_glob_paths() {
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_read: Some(vec![
+        PathBuf::from("/data/**/*.csv"),
+        PathBuf::from("/logs/app-[0-9].log"),
+      ]),
+      allow_write: Some(vec![PathBuf::from("glob:/tmp/out-?.txt")]),
+      ..Default::default()
+    });
+
+    assert!(perms.read.check(Path::new("/data/a.csv")).is_ok());
+    assert!(perms.read.check(Path::new("/data/x/y/b.csv")).is_ok());
+    assert!(perms.read.check(Path::new("/data/x/../c.csv")).is_ok());
+    assert!(perms.read.check(Path::new("/data/a.json")).is_err());
+    assert!(perms.read.check(Path::new("/data/a.csv/inner")).is_err());
+    assert!(perms.read.check(Path::new("/other/a.csv")).is_err());
+    assert!(perms.read.check(Path::new("/logs/app-1.log")).is_ok());
+    assert!(perms.read.check(Path::new("/logs/app-a.log")).is_err());
+    assert!(perms.write.check(Path::new("/tmp/out-1.txt")).is_ok());
+    assert!(perms.write.check(Path::new("/tmp/out-12.txt")).is_err());
+
+    assert_eq!(
+      perms.read.query(Some(Path::new("/data/x/a.csv"))),
+      PermissionState::Granted
+    );
+    assert_eq!(
+      perms.read.revoke(Some(Path::new("/data/x/a.csv"))),
+      PermissionState::Prompt
+    );
+    assert!(perms.read.check(Path::new("/data/b.csv")).is_err());
+
+    assert_eq!(
+      path_check_info(
+        [Path::new("/a"), Path::new("glob:/data/**/*.csv")].into_iter(),
+        Path::new("/data/a.csv"),
+        Path::new("a.csv"),
+      ),
+      "\"a.csv\" (matching \"/data/**/*.csv\")"
+    );
+  }
+
+  #[test]
+  fn check_literal_glob_characters() {
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_read: Some(vec![PathBuf::from("/a[1]/"), PathBuf::from("/b*")]),
+      ..Default::default()
+    });
+
+    assert!(perms.read.check(Path::new("/a[1]/file")).is_ok());
+    assert!(perms.read.check(Path::new("/a1/file")).is_err());
+    assert!(perms.read.check(Path::new("/b*/file")).is_ok());
+    assert!(perms.read.check(Path::new("/bc")).is_ok());
+    assert!(perms.read.check(Path::new("/bc/file")).is_err());
+  }
+
+  #[test]
+  fn check_glob_prefixed_paths_literally() {
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_read: Some(vec![PathBuf::from("/allowed")]),
+      allow_write: Some(vec![PathBuf::from("/allowed")]),
+      ..Default::default()
+    });
+
+    assert!(perms.read.check(Path::new("/allowed/x")).is_ok());
+    assert!(perms.read.check(Path::new("glob:/allowed/x")).is_err());
+    assert!(perms.write.check(Path::new("glob:/allowed/x")).is_err());
+    assert_eq!(
+      perms.read.query(Some(Path::new("glob:/allowed/x"))),
+      PermissionState::Prompt
+    );
+  }
+
   #[test]
   fn test_check_net_with_values() {
     let mut perms = Permissions::from_options(&PermissionsOptions {
@@ -2173,6 +2931,142 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_check_net_with_wildcards_and_networks() {
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_net: Some(svec![
+        "*.example.com",
+        "*.deno.land:443",
+        "10.0.0.0/8",
+        "192.168.1.0/24:8000",
+        "[fd00::]/8"
+      ]),
+      ..Default::default()
+    });
+
+    let domain_tests = vec![
+      ("api.example.com", 80, true),
+      ("a.b.example.com", 443, true),
+      ("example.com", 80, false),
+      ("badexample.com", 80, false),
+      ("api.deno.land", 443, true),
+      ("api.deno.land", 80, false),
+      ("deno.land", 443, false),
+      ("10.0.0.1", 80, true),
+      ("10.255.255.255", 3000, true),
+      ("11.0.0.1", 80, false),
+      ("192.168.1.42", 8000, true),
+      ("192.168.1.42", 8001, false),
+      ("192.168.2.1", 8000, false),
+      ("[fd12::1]", 80, true),
+      ("[fe80::1]", 80, false),
+    ];
+
+    for (host, port, is_ok) in domain_tests {
+      assert_eq!(
+        is_ok,
+        perms.net.check(&(host, Some(port))).is_ok(),
+        "{}:{}",
+        host,
+        port
+      );
+    }
+  }
+
+  #[test]
+  fn test_revoke_net_with_wildcards_and_networks() {
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_net: Some(svec!["*.example.com", "10.0.0.0/8", "deno.land:443"]),
+      ..Default::default()
+    });
+    #[rustfmt::skip]
+    {
+      assert_eq!(perms.net.revoke(Some(&("api.example.com", Some(443)))), PermissionState::Prompt);
+      assert_eq!(perms.net.query(Some(&("web.example.com", Some(443)))), PermissionState::Prompt);
+      assert_eq!(perms.net.revoke(Some(&("10.1.2.3", None))), PermissionState::Prompt);
+      assert_eq!(perms.net.query(Some(&("10.0.0.1", Some(80)))), PermissionState::Prompt);
+      assert_eq!(perms.net.query(Some(&("deno.land", Some(443)))), PermissionState::Granted);
+    };
+  }
+
+  #[test]
+  fn test_revocations_apply_at_check_time() {
+    let mut parent = Permissions::from_options(&PermissionsOptions {
+      allow_read: Some(vec![PathBuf::from("/foo")]),
+      ..Default::default()
+    });
+    let mut child = parent.clone();
+    parent.link_child(&mut child);
+    let mut grandchild = child.clone();
+    child.link_child(&mut grandchild);
+    assert!(grandchild.check_read(Path::new("/foo/bar")).is_ok());
+
+    let revocation: Revocation = Arc::new(|permissions| {
+      permissions.read.revoke(Some(Path::new("/foo")));
+    });
+    revocation(&mut parent);
+    parent.revoke_in_children(revocation);
+    // the grandchild doesn't wait for the child to apply the revocation
+    assert!(grandchild.check_read(Path::new("/foo/bar")).is_err());
+    assert!(child.check_read(Path::new("/foo/bar")).is_err());
+    assert!(parent.check_read(Path::new("/foo/bar")).is_err());
+  }
+
+  #[test]
+  fn test_published_permissions_follow_the_parent() {
+    let prompt_value = PERMISSION_PROMPT_STUB_VALUE_SETTER.lock();
+    prompt_value.set(true);
+    let mut parent = Permissions::from_options(&PermissionsOptions {
+      prompt: true,
+      ..Default::default()
+    });
+    let published = parent.published();
+    assert_eq!(
+      published.get().unwrap().env.query(Some("HOME")),
+      PermissionState::Prompt
+    );
+
+    // granted by a prompt after the worker was created
+    assert!(parent.check_env("HOME").is_ok());
+    assert_eq!(
+      published.get().unwrap().env.query(Some("HOME")),
+      PermissionState::Granted
+    );
+
+    parent.env.revoke(Some("HOME"));
+    parent.publish();
+    assert_eq!(
+      published.get().unwrap().env.query(Some("HOME")),
+      PermissionState::Prompt
+    );
+  }
+
+  #[test]
+  fn test_parse_net_descriptor() {
+    let fixtures = vec![
+      ("deno.land", Some(("deno.land", None))),
+      ("deno.land:80", Some(("deno.land", Some(80)))),
+      ("*.Example.com", Some(("*.example.com", None))),
+      ("*.example.com:8080", Some(("*.example.com", Some(8080)))),
+      ("10.0.0.0/8", Some(("10.0.0.0/8", None))),
+      ("10.0.0.0/8:443", Some(("10.0.0.0/8", Some(443)))),
+      ("[fd00::]/8", Some(("fd00::/8", None))),
+      ("::1", Some(("[::1]", None))),
+      ("10.0.0.0/33", None),
+      ("deno.land/8", None),
+      ("*.127.0.0.1", None),
+      ("deno.land/path", None),
+    ];
+    for (host, expected) in fixtures {
+      assert_eq!(
+        NetDescriptor::parse(host),
+        expected.map(|(host, port)| NetDescriptor(host.to_string(), port)),
+        "{}",
+        host
+      );
+    }
+  }
+
   #[test]
   fn test_check_net_only_flag() {
     let mut perms = Permissions::from_options(&PermissionsOptions {
@@ -2387,6 +3281,7 @@ mod tests {
         global_state: PermissionState::Prompt,
         ..Permissions::new_run(&Some(svec!["deno"]), false)
       },
+      run_args: Default::default(),
       ffi: UnaryPermission {
         global_state: PermissionState::Prompt,
         ..Permissions::new_ffi(&Some(vec![PathBuf::from("deno")]), false)
@@ -2395,6 +3290,7 @@ mod tests {
         state: PermissionState::Prompt,
         ..Permissions::new_hrtime(false)
       },
+      revocations: Default::default(),
     };
     #[rustfmt::skip]
     {
@@ -2503,6 +3399,7 @@ mod tests {
         global_state: PermissionState::Prompt,
         ..Permissions::new_run(&Some(svec!["deno"]), false)
       },
+      run_args: Default::default(),
       ffi: UnaryPermission {
         global_state: PermissionState::Prompt,
         ..Permissions::new_ffi(&Some(vec![PathBuf::from("deno")]), false)
@@ -2511,6 +3408,7 @@ mod tests {
         state: PermissionState::Denied,
         ..Permissions::new_hrtime(false)
       },
+      revocations: Default::default(),
     };
     #[rustfmt::skip]
     {
@@ -2538,8 +3436,10 @@ mod tests {
       net: Permissions::new_net(&None, true),
       env: Permissions::new_env(&None, true),
       run: Permissions::new_run(&None, true),
+      run_args: Default::default(),
       ffi: Permissions::new_ffi(&None, true),
       hrtime: Permissions::new_hrtime(false),
+      revocations: Default::default(),
     };
 
     let prompt_value = PERMISSION_PROMPT_STUB_VALUE_SETTER.lock();
@@ -2588,8 +3488,10 @@ mod tests {
       net: Permissions::new_net(&None, true),
       env: Permissions::new_env(&None, true),
       run: Permissions::new_run(&None, true),
+      run_args: Default::default(),
       ffi: Permissions::new_ffi(&None, true),
       hrtime: Permissions::new_hrtime(false),
+      revocations: Default::default(),
     };
 
     let prompt_value = PERMISSION_PROMPT_STUB_VALUE_SETTER.lock();
@@ -2661,6 +3563,76 @@ mod tests {
     assert_eq!(perms.env.revoke(Some("HomE")), PermissionState::Prompt);
   }
 
+  #[test]
+  fn test_env_wildcard() {
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_env: Some(svec!["AWS_*", "HOME"]),
+      ..Default::default()
+    });
+
+    assert!(perms.env.check("AWS_REGION").is_ok());
+    assert!(perms.env.check("AWS_").is_ok());
+    assert!(perms.env.check("HOME").is_ok());
+    assert!(perms.env.check("HOMEPATH").is_err());
+    assert!(perms.env.check("AWS").is_err());
+    assert_eq!(perms.env.query(Some("AWS_KEY")), PermissionState::Granted);
+    assert_eq!(perms.env.query(Some("AWS_*")), PermissionState::Granted);
+    assert_eq!(perms.env.query(Some("DENO_*")), PermissionState::Prompt);
+    assert_eq!(perms.env.query(Some("*")), PermissionState::Prompt);
+
+    assert_eq!(perms.env.revoke(Some("AWS_KEY")), PermissionState::Prompt);
+    assert!(perms.env.check("AWS_REGION").is_err());
+    assert!(perms.env.check("HOME").is_ok());
+  }
+
+  #[test]
+  fn test_check_run_args() {
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_run: Some(svec!["git", "deno"]),
+      allow_run_args: Some(HashMap::from([(
+        "git".to_string(),
+        vec![svec!["status"], svec!["log", "--oneline"]],
+      )])),
+      ..Default::default()
+    });
+
+    assert!(perms.check_run("git", &svec!["status"]).is_ok());
+    assert!(perms.check_run("git", &svec!["status", "-s"]).is_ok());
+    assert!(perms.check_run("git", &svec!["log", "--oneline"]).is_ok());
+    assert!(perms.check_run("git", &svec!["log"]).is_err());
+    assert!(perms.check_run("git", &svec!["push", "--force"]).is_err());
+    assert!(perms.check_run("git", &[]).is_err());
+    assert!(perms.check_run("deno", &svec!["eval", "1"]).is_ok());
+    assert!(perms.check_run("curl", &[]).is_err());
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_check_run_args_resolves_binary() {
+    let temp_dir = test_util::TempDir::new();
+    let binary = temp_dir.path().join("tool");
+    std::fs::write(&binary, "").unwrap();
+    let link = temp_dir.path().join("link");
+    std::os::unix::fs::symlink(&binary, &link).unwrap();
+    let binary = binary.to_string_lossy().to_string();
+    let link = link.to_string_lossy().to_string();
+    let perms = Permissions::from_options(&PermissionsOptions {
+      allow_run_args: Some(HashMap::from([(
+        binary.clone(),
+        vec![svec!["status"]],
+      )])),
+      ..Default::default()
+    });
+
+    assert!(perms.run_args.check(&binary, &svec!["status"]).is_ok());
+    // the same binary reached through a symlink or another path
+    assert!(perms.run_args.check(&link, &svec!["push"]).is_err());
+    std::fs::create_dir(temp_dir.path().join("dir")).unwrap();
+    let other_path = temp_dir.path().join("./dir/../tool");
+    let other_path = other_path.to_string_lossy().to_string();
+    assert!(perms.run_args.check(&other_path, &svec!["push"]).is_err());
+  }
+
   #[test]
   fn test_deserialize_child_permissions_arg() {
     assert_eq!(
@@ -2813,22 +3785,24 @@ mod tests {
       ..Default::default()
     };
     assert_eq!(
-      create_child_permissions(
-        &mut main_perms.clone(),
-        ChildPermissionsArg {
-          env: ChildUnaryPermissionArg::Inherit,
-          hrtime: ChildUnitPermissionArg::NotGranted,
-          net: ChildUnaryPermissionArg::GrantedList(svec!["foo"]),
-          ffi: ChildUnaryPermissionArg::NotGranted,
-          ..ChildPermissionsArg::none()
-        }
-      )
-      .unwrap(),
-      Permissions {
+      grants(
+        &create_child_permissions(
+          &mut main_perms.clone(),
+          ChildPermissionsArg {
+            env: ChildUnaryPermissionArg::Inherit,
+            hrtime: ChildUnitPermissionArg::NotGranted,
+            net: ChildUnaryPermissionArg::GrantedList(svec!["foo"]),
+            ffi: ChildUnaryPermissionArg::NotGranted,
+            ..ChildPermissionsArg::none()
+          }
+        )
+        .unwrap()
+      ),
+      grants(&Permissions {
         env: Permissions::new_env(&Some(vec![]), false),
         net: Permissions::new_net(&Some(svec!["foo"]), false),
         ..Default::default()
-      }
+      })
     );
     assert!(create_child_permissions(
       &mut main_perms.clone(),
@@ -2856,6 +3830,83 @@ mod tests {
     .is_err());
   }
 
+  #[test]
+  fn test_create_child_permissions_narrowed() {
+    let mut main_perms = Permissions::from_options(&PermissionsOptions {
+      allow_net: Some(svec!["*.example.com", "10.0.0.0/8"]),
+      allow_read: Some(vec![
+        PathBuf::from("/data"),
+        PathBuf::from("glob:/a/*.csv"),
+        PathBuf::from("/b*"),
+      ]),
+      ..Default::default()
+    });
+    let worker_perms = create_child_permissions(
+      &mut main_perms,
+      ChildPermissionsArg {
+        net: ChildUnaryPermissionArg::GrantedList(svec![
+          "api.example.com:443",
+          "10.1.0.0/16"
+        ]),
+        read: ChildUnaryPermissionArg::GrantedList(svec![
+          "/data/public",
+          "/data/**/*.json",
+          "/a/*.csv",
+          "glob:/b*"
+        ]),
+        ..ChildPermissionsArg::none()
+      },
+    )
+    .unwrap();
+    assert_eq!(
+      worker_perms.net.query(Some(&("10.1.2.3", Some(80)))),
+      PermissionState::Granted
+    );
+    assert_eq!(
+      worker_perms.net.query(Some(&("10.2.0.1", Some(80)))),
+      PermissionState::Prompt
+    );
+    assert_eq!(
+      worker_perms.read.query(Some(Path::new("/data/private"))),
+      PermissionState::Prompt
+    );
+    assert_eq!(
+      worker_perms.read.query(Some(Path::new("/data/x/y.json"))),
+      PermissionState::Granted
+    );
+
+    let err = create_child_permissions(
+      &mut main_perms,
+      ChildPermissionsArg {
+        net: ChildUnaryPermissionArg::GrantedList(svec!["deno.land"]),
+        ..ChildPermissionsArg::none()
+      },
+    )
+    .unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Can't escalate parent thread permissions: net access to \"deno.land\""
+    );
+    for read in ["/a/**/*.csv", "glob:/b*/x", "/c"] {
+      assert!(create_child_permissions(
+        &mut main_perms,
+        ChildPermissionsArg {
+          read: ChildUnaryPermissionArg::GrantedList(svec![read]),
+          ..ChildPermissionsArg::none()
+        },
+      )
+      .is_err());
+    }
+    assert!(create_child_permissions(
+      &mut main_perms,
+      ChildPermissionsArg {
+        net: ChildUnaryPermissionArg::GrantedList(svec!["0.0.0.0/0"]),
+        ..ChildPermissionsArg::none()
+      },
+    )
+    .is_err());
+  }
+
   #[test]
   fn test_create_child_permissions_with_prompt() {
     let prompt_value = PERMISSION_PROMPT_STUB_VALUE_SETTER.lock();
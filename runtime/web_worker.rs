@@ -9,6 +9,7 @@ use crate::tokio_util::run_basic;
 use crate::worker::FormatJsErrorFn;
 use crate::BootstrapOptions;
 use deno_broadcast_channel::InMemoryBroadcastChannel;
+use deno_core::error::custom_error;
 use deno_core::error::AnyError;
 use deno_core::error::JsError;
 use deno_core::futures::channel::mpsc;
@@ -16,6 +17,7 @@ use deno_core::futures::future::poll_fn;
 use deno_core::futures::stream::StreamExt;
 use deno_core::futures::task::AtomicWaker;
 use deno_core::located_script_name;
+use deno_core::parking_lot::Mutex;
 use deno_core::serde::Deserialize;
 use deno_core::serde::Serialize;
 use deno_core::serde_json::json;
@@ -24,10 +26,12 @@ use deno_core::CancelHandle;
 use deno_core::CompiledWasmModuleStore;
 use deno_core::Extension;
 use deno_core::GetErrorClassFn;
+use deno_core::HeapLimits;
 use deno_core::JsRuntime;
 use deno_core::ModuleId;
 use deno_core::ModuleLoader;
 use deno_core::ModuleSpecifier;
+use deno_core::NearHeapLimitAction;
 use deno_core::RuntimeOptions;
 use deno_core::SharedArrayBufferStore;
 use deno_core::SourceMapGetter;
@@ -43,8 +47,10 @@ use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicI32;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::sync::Weak;
 use std::task::Context;
 use std::task::Poll;
+use std::time::Duration;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -68,6 +74,189 @@ impl WorkerId {
   }
 }
 
+/// Resource limits of a worker, set with the `deno.limits` option of the
+/// `Worker` constructor.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerLimits {
+  /// Maximum size of the JavaScript heap, in megabytes.
+  pub memory_mb: Option<usize>,
+  /// Maximum CPU time the worker thread may use, in milliseconds. The time
+  /// it spends waiting for events, like timers or I/O, doesn't count.
+  pub cpu_ms: Option<u64>,
+}
+
+/// The CPU time clock of a worker thread and the limit the worker exceeded,
+/// if any. Shared between the worker thread and the watchdog thread its host
+/// uses to enforce the CPU time limit.
+struct WorkerUsage {
+  cpu_clock: Option<ThreadCpuClock>,
+  exceeded_limit: Option<String>,
+}
+
+/// The CPU time clock of a thread, which can be read from other threads.
+/// Unlike wall-clock time, it doesn't advance while the thread waits or
+/// isn't scheduled.
+struct ThreadCpuClock {
+  #[cfg(all(unix, not(target_os = "macos")))]
+  clock_id: libc::clockid_t,
+  #[cfg(target_os = "macos")]
+  thread: libc::mach_port_t,
+  #[cfg(windows)]
+  thread: winapi::um::winnt::HANDLE,
+  start: Duration,
+}
+
+// SAFETY: the duplicated handle of the thread can be used from any thread.
+#[cfg(windows)]
+unsafe impl Send for ThreadCpuClock {}
+
+impl ThreadCpuClock {
+  /// Gets the clock of the calling thread, starting at the CPU time it used
+  /// so far.
+  fn current() -> Option<Self> {
+    let mut clock = Self::of_current_thread()?;
+    clock.start = clock.cpu_time()?;
+    Some(clock)
+  }
+
+  /// The CPU time the thread used since the clock was created.
+  fn elapsed(&self) -> Option<Duration> {
+    Some(self.cpu_time()?.saturating_sub(self.start))
+  }
+
+  #[cfg(all(unix, not(target_os = "macos")))]
+  fn of_current_thread() -> Option<Self> {
+    let mut clock_id: libc::clockid_t = 0;
+    // SAFETY: `pthread_self()` is the calling thread.
+    let result = unsafe {
+      libc::pthread_getcpuclockid(libc::pthread_self(), &mut clock_id)
+    };
+    (result == 0).then(|| Self {
+      clock_id,
+      start: Duration::ZERO,
+    })
+  }
+
+  #[cfg(all(unix, not(target_os = "macos")))]
+  fn cpu_time(&self) -> Option<Duration> {
+    let mut time = libc::timespec {
+      tv_sec: 0,
+      tv_nsec: 0,
+    };
+    // SAFETY: the clock was obtained from `pthread_getcpuclockid()`.
+    let result = unsafe { libc::clock_gettime(self.clock_id, &mut time) };
+    (result == 0)
+      .then(|| Duration::new(time.tv_sec as u64, time.tv_nsec as u32))
+  }
+
+  #[cfg(target_os = "macos")]
+  fn of_current_thread() -> Option<Self> {
+    // SAFETY: `pthread_self()` is the calling thread, whose port stays valid
+    // while it runs.
+    let thread = unsafe { libc::pthread_mach_thread_np(libc::pthread_self()) };
+    Some(Self {
+      thread,
+      start: Duration::ZERO,
+    })
+  }
+
+  #[cfg(target_os = "macos")]
+  fn cpu_time(&self) -> Option<Duration> {
+    let mut info = std::mem::MaybeUninit::<libc::thread_basic_info>::zeroed();
+    let mut count = libc::THREAD_BASIC_INFO_COUNT;
+    // SAFETY: `info` holds `count` integers, as `thread_info()` expects.
+    let result = unsafe {
+      libc::thread_info(
+        self.thread,
+        libc::THREAD_BASIC_INFO as libc::thread_flavor_t,
+        info.as_mut_ptr() as libc::thread_info_t,
+        &mut count,
+      )
+    };
+    if result != libc::KERN_SUCCESS {
+      return None;
+    }
+    // SAFETY: `thread_info()` succeeded, so it filled in `info`.
+    let info = unsafe { info.assume_init() };
+    let time = |time: libc::time_value_t| {
+      Duration::new(time.seconds as u64, time.microseconds as u32 * 1000)
+    };
+    Some(time(info.user_time) + time(info.system_time))
+  }
+
+  #[cfg(windows)]
+  fn of_current_thread() -> Option<Self> {
+    use winapi::shared::minwindef::FALSE;
+    use winapi::um::handleapi::DuplicateHandle;
+    use winapi::um::processthreadsapi::GetCurrentProcess;
+    use winapi::um::processthreadsapi::GetCurrentThread;
+    use winapi::um::winnt::DUPLICATE_SAME_ACCESS;
+
+    let mut thread = std::ptr::null_mut();
+    // SAFETY: `GetCurrentThread()` is a pseudo handle of the calling thread,
+    // which is duplicated into a handle the other threads can use.
+    let result = unsafe {
+      DuplicateHandle(
+        GetCurrentProcess(),
+        GetCurrentThread(),
+        GetCurrentProcess(),
+        &mut thread,
+        0,
+        FALSE,
+        DUPLICATE_SAME_ACCESS,
+      )
+    };
+    (result != FALSE).then(|| Self {
+      thread,
+      start: Duration::ZERO,
+    })
+  }
+
+  #[cfg(windows)]
+  fn cpu_time(&self) -> Option<Duration> {
+    use winapi::shared::minwindef::FALSE;
+    use winapi::shared::minwindef::FILETIME;
+    use winapi::um::processthreadsapi::GetThreadTimes;
+
+    let mut creation = FILETIME {
+      dwLowDateTime: 0,
+      dwHighDateTime: 0,
+    };
+    let mut exit = creation;
+    let mut kernel = creation;
+    let mut user = creation;
+    // SAFETY: the handle was duplicated in `of_current_thread()`.
+    let result = unsafe {
+      GetThreadTimes(
+        self.thread,
+        &mut creation,
+        &mut exit,
+        &mut kernel,
+        &mut user,
+      )
+    };
+    if result == FALSE {
+      return None;
+    }
+    // the times are counted in intervals of 100 nanoseconds
+    let ticks = |time: FILETIME| {
+      (time.dwHighDateTime as u64) << 32 | time.dwLowDateTime as u64
+    };
+    Some(Duration::from_nanos((ticks(kernel) + ticks(user)) * 100))
+  }
+}
+
+#[cfg(windows)]
+impl Drop for ThreadCpuClock {
+  fn drop(&mut self) {
+    // SAFETY: the handle was duplicated in `of_current_thread()`.
+    unsafe {
+      winapi::um::handleapi::CloseHandle(self.thread);
+    }
+  }
+}
+
 /// Events that are sent to host from child
 /// worker.
 pub enum WorkerControlEvent {
@@ -127,6 +316,7 @@ pub struct WebWorkerInternalHandle {
   has_terminated: Arc<AtomicBool>,
   terminate_waker: Arc<AtomicWaker>,
   isolate_handle: v8::IsolateHandle,
+  usage: Arc<Mutex<WorkerUsage>>,
   pub name: String,
   pub worker_type: WebWorkerType,
 }
@@ -184,6 +374,14 @@ impl WebWorkerInternalHandle {
     // Wake parent by closing the channel
     self.sender.close_channel();
   }
+
+  /// Returns the error describing the resource limit the worker exceeded, if
+  /// its execution was terminated because of one.
+  pub fn exceeded_limit_error(&self) -> Option<AnyError> {
+    let usage = self.usage.lock();
+    let message = usage.exceeded_limit.as_ref()?;
+    Some(custom_error("Error", message.clone()))
+  }
 }
 
 pub struct SendableWebWorkerHandle {
@@ -193,6 +391,7 @@ pub struct SendableWebWorkerHandle {
   has_terminated: Arc<AtomicBool>,
   terminate_waker: Arc<AtomicWaker>,
   isolate_handle: v8::IsolateHandle,
+  usage: Weak<Mutex<WorkerUsage>>,
 }
 
 impl From<SendableWebWorkerHandle> for WebWorkerHandle {
@@ -204,6 +403,7 @@ impl From<SendableWebWorkerHandle> for WebWorkerHandle {
       has_terminated: handle.has_terminated,
       terminate_waker: handle.terminate_waker,
       isolate_handle: handle.isolate_handle,
+      usage: handle.usage,
     }
   }
 }
@@ -223,6 +423,7 @@ pub struct WebWorkerHandle {
   has_terminated: Arc<AtomicBool>,
   terminate_waker: Arc<AtomicWaker>,
   isolate_handle: v8::IsolateHandle,
+  usage: Weak<Mutex<WorkerUsage>>,
 }
 
 impl WebWorkerHandle {
@@ -236,6 +437,36 @@ impl WebWorkerHandle {
     Ok(receiver.next().await)
   }
 
+  /// Spawn a watchdog thread which terminates the worker's execution once
+  /// its thread has used more than `limit` of CPU time. The watchdog stops
+  /// when the worker shuts down.
+  pub fn watch_cpu_time(&self, limit: Duration) {
+    use std::thread::{sleep, spawn};
+
+    let usage = self.usage.clone();
+    let has_terminated = self.has_terminated.clone();
+    let isolate_handle = self.isolate_handle.clone();
+    let interval =
+      (limit / 10).clamp(Duration::from_millis(1), Duration::from_millis(50));
+    spawn(move || loop {
+      sleep(interval);
+      let usage = match usage.upgrade() {
+        Some(usage) if !has_terminated.load(Ordering::SeqCst) => usage,
+        _ => return,
+      };
+      let mut usage = usage.lock();
+      let cpu_time = usage.cpu_clock.as_ref().and_then(ThreadCpuClock::elapsed);
+      if matches!(cpu_time, Some(cpu_time) if cpu_time > limit) {
+        usage.exceeded_limit = Some(format!(
+          "Worker exceeded its CPU time limit of {}ms",
+          limit.as_millis()
+        ));
+        isolate_handle.terminate_execution();
+        return;
+      }
+    });
+  }
+
   /// Terminate the worker
   /// This function will set the termination signal, close the message channel,
   /// and schedule to terminate the isolate after two seconds.
@@ -275,6 +506,7 @@ fn create_handles(
   isolate_handle: v8::IsolateHandle,
   name: String,
   worker_type: WebWorkerType,
+  usage: Arc<Mutex<WorkerUsage>>,
 ) -> (WebWorkerInternalHandle, SendableWebWorkerHandle) {
   let (parent_port, worker_port) = create_entangled_message_port();
   let (ctrl_tx, ctrl_rx) = mpsc::channel::<WorkerControlEvent>(1);
@@ -291,6 +523,7 @@ fn create_handles(
     cancel: CancelHandle::new_rc(),
     sender: ctrl_tx,
     worker_type,
+    usage: usage.clone(),
   };
   let external_handle = SendableWebWorkerHandle {
     receiver: ctrl_rx,
@@ -299,6 +532,7 @@ fn create_handles(
     has_terminated,
     terminate_waker,
     isolate_handle,
+    usage: Arc::downgrade(&usage),
   };
   (internal_handle, external_handle)
 }
@@ -340,6 +574,7 @@ pub struct WebWorkerOptions {
   pub compiled_wasm_module_store: Option<CompiledWasmModuleStore>,
   pub maybe_exit_code: Option<Arc<AtomicI32>>,
   pub stdio: Stdio,
+  pub limits: WorkerLimits,
 }
 
 impl WebWorker {
@@ -445,6 +680,26 @@ impl WebWorker {
     // Append exts
     extensions.extend(std::mem::take(&mut options.extensions));
 
+    // this runs on the worker thread, whose CPU time is limited
+    let usage = Arc::new(Mutex::new(WorkerUsage {
+      cpu_clock: ThreadCpuClock::current(),
+      exceeded_limit: None,
+    }));
+    let heap_limits = options.limits.memory_mb.map(|memory_mb| {
+      let usage = usage.clone();
+      HeapLimits {
+        initial: 0,
+        // the limit was checked not to overflow when creating the worker
+        max: memory_mb.saturating_mul(1024 * 1024),
+        near_heap_limit_callback: Some(Box::new(move |_, _| {
+          usage.lock().exceeded_limit = Some(format!(
+            "Worker exceeded its memory limit of {}MB",
+            memory_mb
+          ));
+          NearHeapLimitAction::Terminate
+        })),
+      }
+    });
     let mut js_runtime = JsRuntime::new(RuntimeOptions {
       module_loader: Some(options.module_loader.clone()),
       startup_snapshot: Some(js::deno_isolate_init()),
@@ -452,6 +707,7 @@ impl WebWorker {
       get_error_class_fn: options.get_error_class_fn,
       shared_array_buffer_store: options.shared_array_buffer_store.clone(),
       compiled_wasm_module_store: options.compiled_wasm_module_store.clone(),
+      heap_limits,
       extensions,
       ..Default::default()
     });
@@ -467,7 +723,7 @@ impl WebWorker {
     let (internal_handle, external_handle) = {
       let handle = js_runtime.v8_isolate().thread_safe_handle();
       let (internal_handle, external_handle) =
-        create_handles(handle, name.clone(), options.worker_type);
+        create_handles(handle, name.clone(), options.worker_type, usage);
       let op_state = js_runtime.op_state();
       let mut op_state = op_state.borrow_mut();
       op_state.put(internal_handle.clone());
@@ -730,6 +986,9 @@ pub fn run_web_worker(
     };
 
     if let Err(e) = result {
+      // Report the limit that got the worker's execution terminated instead
+      // of the termination itself.
+      let e = internal_handle.exceeded_limit_error().unwrap_or(e);
       print_worker_error(&e, &name, format_js_error_fn.as_deref());
       internal_handle
         .post_event(WorkerControlEvent::TerminalError(e))
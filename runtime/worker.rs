@@ -14,10 +14,12 @@ use deno_core::located_script_name;
 use deno_core::CompiledWasmModuleStore;
 use deno_core::Extension;
 use deno_core::GetErrorClassFn;
+use deno_core::HeapLimits;
 use deno_core::JsRuntime;
 use deno_core::LocalInspectorSession;
 use deno_core::ModuleId;
 use deno_core::ModuleLoader;
+use deno_core::ModuleMapData;
 use deno_core::ModuleSpecifier;
 use deno_core::RuntimeOptions;
 use deno_core::SharedArrayBufferStore;
@@ -45,6 +47,7 @@ pub type FormatJsErrorFn = dyn Fn(&JsError) -> String + Sync + Send;
 pub struct MainWorker {
   pub js_runtime: JsRuntime,
   should_break_on_first_statement: bool,
+  should_wait_for_inspector_session: bool,
 }
 
 pub struct WorkerOptions {
@@ -62,6 +65,9 @@ pub struct WorkerOptions {
   pub source_map_getter: Option<Box<dyn SourceMapGetter>>,
   pub maybe_inspector_server: Option<Arc<InspectorServer>>,
   pub should_break_on_first_statement: bool,
+  /// Like `should_break_on_first_statement`, but only waits for a debugger to
+  /// attach and resume instead of pausing on the first statement.
+  pub should_wait_for_inspector_session: bool,
   pub get_error_class_fn: Option<GetErrorClassFn>,
   pub origin_storage_dir: Option<std::path::PathBuf>,
   pub blob_store: BlobStore,
@@ -69,6 +75,14 @@ pub struct WorkerOptions {
   pub shared_array_buffer_store: Option<SharedArrayBufferStore>,
   pub compiled_wasm_module_store: Option<CompiledWasmModuleStore>,
   pub stdio: Stdio,
+  /// Path to which op metrics are written as JSON when the worker's
+  /// runtime shuts down.
+  pub dump_op_metrics: Option<std::path::PathBuf>,
+  /// Maximum V8 heap size in bytes. Execution is terminated with an error
+  /// instead of aborting the process when the limit is approached.
+  pub max_heap_size: Option<usize>,
+  /// Module graph of a previous run, see `JsRuntime::serialize_module_map`.
+  pub module_map_data: Option<ModuleMapData>,
 }
 
 impl MainWorker {
@@ -167,6 +181,13 @@ impl MainWorker {
       get_error_class_fn: options.get_error_class_fn,
       shared_array_buffer_store: options.shared_array_buffer_store.clone(),
       compiled_wasm_module_store: options.compiled_wasm_module_store.clone(),
+      dump_op_metrics: options.dump_op_metrics.clone(),
+      heap_limits: options.max_heap_size.map(|max| HeapLimits {
+        initial: 0,
+        max,
+        near_heap_limit_callback: None,
+      }),
+      module_map_data: options.module_map_data.take(),
       extensions,
       ..Default::default()
     });
@@ -175,13 +196,16 @@ impl MainWorker {
       server.register_inspector(
         main_module.to_string(),
         &mut js_runtime,
-        options.should_break_on_first_statement,
+        options.should_break_on_first_statement
+          || options.should_wait_for_inspector_session,
       );
     }
 
     Self {
       js_runtime,
       should_break_on_first_statement: options.should_break_on_first_statement,
+      should_wait_for_inspector_session: options
+        .should_wait_for_inspector_session,
     }
   }
 
@@ -270,6 +294,8 @@ impl MainWorker {
         .js_runtime
         .inspector()
         .wait_for_session_and_break_on_next_statement()
+    } else if self.should_wait_for_inspector_session {
+      self.js_runtime.inspector().wait_for_session()
     }
   }
 
@@ -387,6 +413,7 @@ mod tests {
       create_web_worker_cb: Arc::new(|_| unreachable!()),
       maybe_inspector_server: None,
       should_break_on_first_statement: false,
+      should_wait_for_inspector_session: false,
       module_loader: Rc::new(deno_core::FsModuleLoader),
       get_error_class_fn: None,
       origin_storage_dir: None,
@@ -395,6 +422,9 @@ mod tests {
       shared_array_buffer_store: None,
       compiled_wasm_module_store: None,
       stdio: Default::default(),
+      dump_op_metrics: None,
+      max_heap_size: None,
+      module_map_data: None,
     };
 
     MainWorker::bootstrap_from_options(main_module, permissions, options)